@@ -50,9 +50,10 @@ impl FibonacciExample {
         }
         
         ExecutionTrace {
-            columns,
+            columns: columns.into(),
             length: n,
             num_registers: 2,
+            aux_segments: Vec::new(),
         }
     }
     
@@ -108,7 +109,7 @@ impl FibonacciExample {
         
         // Step 4: Demonstrate trace table conversion
         println!("\n📋 Step 4: Demonstrating trace table conversion...");
-        let winterfell_trace = WinterfellTraceTable::from_xfg_trace(&trace);
+        let winterfell_trace = WinterfellTraceTable::from_xfg_trace(&trace)?;
 
         println!("   Successfully converted XFG trace to Winterfell trace table");
         
@@ -224,9 +225,10 @@ fn demonstrate_trace_validation() -> Result<()> {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(2), PrimeField64::new(3)],
             vec![PrimeField64::new(4), PrimeField64::new(5), PrimeField64::new(6)],
-        ],
+        ].into(),
         length: 3,
         num_registers: 2,
+        aux_segments: Vec::new(),
     };
     
     // Validate the trace
@@ -234,7 +236,7 @@ fn demonstrate_trace_validation() -> Result<()> {
     println!("   ✅ Trace validation successful");
     
     // Convert to Winterfell trace table
-    let winterfell_trace = WinterfellTraceTable::from_xfg_trace(&trace);
+    let winterfell_trace = WinterfellTraceTable::from_xfg_trace(&trace)?;
 
     println!("   ✅ Winterfell trace table conversion successful");
     
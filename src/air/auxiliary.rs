@@ -0,0 +1,205 @@
+//! Auxiliary Trace Segments
+//!
+//! Arguments such as permutation and lookup checks need trace columns that
+//! depend on random verifier challenges. An auxiliary segment is built after
+//! the main trace segment is committed: the challenges are drawn from the
+//! transcript, the segment columns are computed from the main trace and the
+//! challenges, and the segment is committed before the composition
+//! coefficients are drawn.
+//!
+//! Segment transition constraints may read the current and next rows of the
+//! main trace and of the segment itself, and may have degree at most two in
+//! those values. A segment can read a longer window of `w` consecutive rows
+//! instead (see [`AuxTraceSegment::window`]); its constraints then hold on
+//! rows `0..=n - w`.
+
+use crate::prelude::*;
+use crate::types::encoding::{ByteReader, ByteWriter, Encode};
+use crate::types::{FieldElement, TypeError};
+use crate::air::BoundaryConstraint;
+use serde::{Deserialize, Serialize};
+use core::fmt::{Debug, Display, Formatter};
+use alloc::sync::Arc;
+
+/// Maximum degree of auxiliary transition constraints in the trace values
+pub const MAX_AUX_CONSTRAINT_DEGREE: usize = 2;
+
+/// Rows of the main trace and of an auxiliary segment at two consecutive steps
+#[derive(Debug, Clone, Copy)]
+pub struct AuxFrame<'a, F: FieldElement> {
+    /// Main trace row at the current step
+    pub main_current: &'a [F],
+    /// Main trace row at the next step
+    pub main_next: &'a [F],
+    /// Segment row at the current step
+    pub aux_current: &'a [F],
+    /// Segment row at the next step
+    pub aux_next: &'a [F],
+}
+
+/// Rows of the main trace and of an auxiliary segment at `w` consecutive steps
+#[derive(Debug, Clone, Copy)]
+pub struct AuxWindow<'a, F: FieldElement> {
+    /// Main trace rows, from the current step on
+    pub main: &'a [&'a [F]],
+    /// Segment rows, from the current step on
+    pub aux: &'a [&'a [F]],
+}
+
+impl<'a, F: FieldElement> AuxWindow<'a, F> {
+    /// Number of rows in the window
+    pub fn len(&self) -> usize {
+        self.main.len().min(self.aux.len())
+    }
+
+    /// Whether the window holds no rows
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Frame of the rows `offset` and `offset + 1` steps after the current one
+    pub fn frame(&self, offset: usize) -> AuxFrame<'a, F> {
+        AuxFrame {
+            main_current: self.main[offset],
+            main_next: self.main[offset + 1],
+            aux_current: self.aux[offset],
+            aux_next: self.aux[offset + 1],
+        }
+    }
+}
+
+/// Definition of an auxiliary trace segment
+///
+/// Implementations describe how the segment is built and which constraints
+/// it satisfies; [`AuxSegment`] shares a definition between AIR instances.
+pub trait AuxTraceSegment<F: FieldElement>: Debug + Send + Sync {
+    /// Number of columns in the segment
+    fn width(&self) -> usize;
+
+    /// Number of random challenges the segment is built from
+    fn num_challenges(&self) -> usize;
+
+    /// Number of transition constraints over the segment
+    fn num_constraints(&self) -> usize;
+
+    /// Build the segment columns from the main trace columns and the challenges
+    ///
+    /// Every returned column must have the length of the main trace.
+    fn build(&self, main: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>>;
+
+    /// Evaluate the transition constraints on a frame
+    ///
+    /// Returns one residual per constraint; all residuals are zero for a
+    /// valid transition. Segments with a window of more than two rows are
+    /// evaluated with [`evaluate_window`](Self::evaluate_window) instead.
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, challenges: &[F]) -> Vec<F>;
+
+    /// Number of consecutive rows the transition constraints read
+    ///
+    /// With a window of `w` rows the constraints hold on rows `0..=n - w`
+    /// of a trace of length `n`.
+    fn window(&self) -> usize {
+        2
+    }
+
+    /// Evaluate the transition constraints on [`window`](Self::window) consecutive rows
+    fn evaluate_window(&self, window: &AuxWindow<'_, F>, challenges: &[F]) -> Vec<F> {
+        self.evaluate_transition(&window.frame(0), challenges)
+    }
+
+    /// Boundary constraints on the segment columns
+    ///
+    /// Registers index the segment columns. Final constraints are placed on
+    /// the last row of the trace.
+    fn boundary(&self, _challenges: &[F]) -> Vec<BoundaryConstraint<F>> {
+        Vec::new()
+    }
+}
+
+/// Shared handle to an auxiliary segment definition
+///
+/// Two handles are equal if they share the same definition.
+#[derive(Debug, Clone)]
+pub struct AuxSegment<F: FieldElement>(Arc<dyn AuxTraceSegment<F>>);
+
+impl<F: FieldElement> AuxSegment<F> {
+    /// Wrap a segment definition
+    pub fn new(definition: impl AuxTraceSegment<F> + 'static) -> Self {
+        Self(Arc::new(definition))
+    }
+
+    /// Segment definition
+    pub fn definition(&self) -> &dyn AuxTraceSegment<F> {
+        self.0.as_ref()
+    }
+
+    /// Shape of the segment
+    pub fn shape(&self) -> AuxSegmentShape {
+        AuxSegmentShape {
+            width: self.0.width(),
+            num_challenges: self.0.num_challenges(),
+            num_constraints: self.0.num_constraints(),
+            window: self.0.window(),
+        }
+    }
+}
+
+impl<F: FieldElement> PartialEq for AuxSegment<F> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<F: FieldElement> Eq for AuxSegment<F> {}
+
+impl<F: FieldElement> Display for AuxSegment<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AuxSegment({})", self.shape())
+    }
+}
+
+/// Shape of an auxiliary segment
+///
+/// Segment definitions are code and cannot be serialized; a serialized AIR
+/// records the shape of each segment instead, and the segments are supplied
+/// again when it is decoded (see
+/// [`Air::from_bytes_with_segments`](crate::air::Air::from_bytes_with_segments)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuxSegmentShape {
+    /// Number of columns
+    pub width: usize,
+    /// Number of random challenges
+    pub num_challenges: usize,
+    /// Number of transition constraints
+    pub num_constraints: usize,
+    /// Number of consecutive rows the transition constraints read
+    pub window: usize,
+}
+
+impl Display for AuxSegmentShape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "width={}, challenges={}, constraints={}, window={}",
+            self.width, self.num_challenges, self.num_constraints, self.window
+        )
+    }
+}
+
+impl Encode for AuxSegmentShape {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.width);
+        writer.write_usize(self.num_challenges);
+        writer.write_usize(self.num_constraints);
+        writer.write_usize(self.window);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        Ok(Self {
+            width: reader.read_usize()?,
+            num_challenges: reader.read_usize()?,
+            num_constraints: reader.read_usize()?,
+            window: reader.read_usize()?,
+        })
+    }
+}
@@ -3,14 +3,18 @@
 //! This module defines boundary conditions that specify initial and final states
 //! for computations in AIR (Algebraic Intermediate Representation).
 
-use crate::types::{FieldElement, StarkComponent, TypeError};
-use std::fmt::{Display, Formatter};
+use crate::prelude::*;
+use crate::types::encoding::{decode_from_slice, encode_to_vec, ByteReader, ByteWriter, Encode};
+use crate::types::{ColIdx, FieldElement, StarkComponent, StepIdx, TypeError};
+use serde::{Deserialize, Serialize};
+use core::fmt::{Display, Formatter};
 
 /// Boundary conditions for AIR
 /// 
 /// Boundary conditions specify constraints on the initial and final states
 /// of a computation, ensuring the computation starts and ends correctly.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct BoundaryConditions<F: FieldElement> {
     /// Boundary constraints
     pub constraints: Vec<BoundaryConstraint<F>>,
@@ -59,27 +63,38 @@ impl<F: FieldElement> BoundaryConditions<F> {
 }
 
 impl<F: FieldElement> Display for BoundaryConditions<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "BoundaryConditions(constraints={})", self.constraints.len())
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for BoundaryConditions<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         self.validate().map_err(|e| TypeError::InvalidConversion(e.to_string()))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        Vec::new()
+        encode_to_vec(self)
     }
 
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> Encode for BoundaryConditions<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_sequence(&self.constraints);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self::new(reader.read_sequence()?))
     }
 }
 
 /// Individual boundary constraint
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct BoundaryConstraint<F: FieldElement> {
     /// Register index
     pub register: usize,
@@ -93,10 +108,10 @@ pub struct BoundaryConstraint<F: FieldElement> {
 
 impl<F: FieldElement> BoundaryConstraint<F> {
     /// Create a new boundary constraint
-    pub fn new(register: usize, step: usize, value: F, constraint_type: BoundaryType) -> Self {
+    pub fn new(register: ColIdx, step: StepIdx, value: F, constraint_type: BoundaryType) -> Self {
         Self {
-            register,
-            step,
+            register: register.0,
+            step: step.0,
             value,
             constraint_type,
         }
@@ -104,12 +119,12 @@ impl<F: FieldElement> BoundaryConstraint<F> {
 
     /// Create an initial condition constraint
     pub fn initial(register: usize, value: F) -> Self {
-        Self::new(register, 0, value, BoundaryType::Initial)
+        Self::new(ColIdx(register), StepIdx(0), value, BoundaryType::Initial)
     }
 
     /// Create a final condition constraint
     pub fn final_condition(register: usize, value: F) -> Self {
-        Self::new(register, usize::MAX, value, BoundaryType::Final)
+        Self::new(ColIdx(register), StepIdx(usize::MAX), value, BoundaryType::Final)
     }
 
     /// Verify the boundary constraint
@@ -142,7 +157,7 @@ impl<F: FieldElement> BoundaryConstraint<F> {
 }
 
 impl<F: FieldElement> Display for BoundaryConstraint<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "BoundaryConstraint(register={}, step={}, value={:?}, type={:?})",
@@ -151,8 +166,26 @@ impl<F: FieldElement> Display for BoundaryConstraint<F> {
     }
 }
 
+impl<F: FieldElement> Encode for BoundaryConstraint<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.register);
+        writer.write_usize(self.step);
+        writer.write_field(&self.value);
+        self.constraint_type.encode(writer);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            register: reader.read_usize()?,
+            step: reader.read_usize()?,
+            value: reader.read_field()?,
+            constraint_type: BoundaryType::decode(reader)?,
+        })
+    }
+}
+
 /// Boundary constraint type
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BoundaryType {
     /// Initial state constraint
     Initial,
@@ -161,7 +194,7 @@ pub enum BoundaryType {
 }
 
 impl Display for BoundaryType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             BoundaryType::Initial => write!(f, "Initial"),
             BoundaryType::Final => write!(f, "Final"),
@@ -169,6 +202,23 @@ impl Display for BoundaryType {
     }
 }
 
+impl Encode for BoundaryType {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_u8(match self {
+            BoundaryType::Initial => 0,
+            BoundaryType::Final => 1,
+        });
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        match reader.read_u8()? {
+            0 => Ok(BoundaryType::Initial),
+            1 => Ok(BoundaryType::Final),
+            tag => Err(TypeError::UnknownTag { name: "boundary type", tag }),
+        }
+    }
+}
+
 /// Boundary error types
 #[derive(Debug, thiserror::Error)]
 pub enum BoundaryError {
@@ -0,0 +1,1056 @@
+//! Symbolic AIR Builder
+//!
+//! Writing constraints as raw coefficient vectors is error-prone: nothing
+//! ties a coefficient to the column it multiplies. With the builder an AIR is
+//! written as expressions over named columns, such as
+//! `col("acc").next() - col("acc") - col("step")`, and compiled into
+//! [`CompiledConstraint`]s that evaluate on consecutive trace rows and know
+//! their degree and the window of rows they read.
+//!
+//! [`AirBuilder::build`] lowers the compiled transitions into the transition
+//! matrix of an [`Air`]. The prover proves linear transitions of the form
+//! `next = M · current`, so every column needs exactly one transition that is
+//! linear, has no constant term and reads exactly one column at the next row.
+//! Constraints of higher degree still compile and can be evaluated and
+//! checked against a trace.
+//!
+//! A transition can be enabled on some rows only by gating it with a
+//! selector column: [`AirBuilder::with_selected_transition`] adds
+//! `selector * expr = 0`. A selector is any column, typically a periodic one
+//! from [`AirBuilder::with_periodic_column`], which repeats a fixed pattern
+//! of values and can mark, say, every eighth row or an opcode's rows. Gated
+//! transitions are proven in an auxiliary segment holding the value of each
+//! `expr`, so `expr` may have degree up to two.
+//!
+//! Columns computed by something other than a linear map, such as a hash
+//! round, are declared with [`AirBuilder::with_free_column`]. The transition
+//! matrix leaves them free; the transitions reading them at a later row are
+//! proven in an auxiliary segment, with degree up to two, and their trace is
+//! generated by a step function (see [`Air::with_step`]). Columns whose
+//! values come with the AIR, such as an instruction ROM, are declared with
+//! [`AirBuilder::with_fixed_column`] and are likewise left free.
+//!
+//! Constraints reading only the current row, such as the booleanity
+//! `b * (b - 1)` of a free bit column, define no next value; they are proven
+//! in an auxiliary segment too, with degree up to two, on every row that
+//! starts a transition.
+//!
+//! Expressions may read further ahead than the next row, as in
+//! `col("x").next().next() - col("x").next() - col("x")`. Transitions over a
+//! window of more than two rows are not lowered into the matrix; they are
+//! proven in an auxiliary segment of their own and hold on every row where
+//! the whole window fits in the trace.
+//!
+//! Arguments over random verifier challenges, such as permutations and
+//! lookups, are written as a [`ChallengeSegment`]: an auxiliary segment
+//! whose constraints read [`challenge`]`(i)` besides the columns, resolved
+//! from the transcript when proving and verifying.
+//!
+//! [`AirBuilder::optimize`] folds constants and moves repeated products into
+//! intermediate columns before building (see the `optimize` module).
+//!
+//! A built AIR keeps its expressions as a [`SymbolicAir`], so that a
+//! violation found by [`Air::check_trace`] can be shown as the constraint it
+//! was written as (see [`Air::explain_violation`]).
+
+use crate::prelude::*;
+use crate::air::{
+    Air, AirError, AuxFrame, AuxSegment, AuxTraceSegment, AuxWindow, BoundaryConditions, BoundaryConstraint,
+    FixedColumn, TransitionFunction, MAX_AUX_CONSTRAINT_DEGREE,
+};
+use crate::types::FieldElement;
+use core::fmt::{Display, Formatter};
+use core::ops::{Add, Mul, Neg, Sub};
+
+mod optimize;
+mod segment;
+
+pub use segment::ChallengeSegment;
+
+/// Symbolic expression over named trace columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<F: FieldElement> {
+    /// Field constant
+    Constant(F),
+    /// Verifier challenge drawn from the transcript after the main trace is committed
+    ///
+    /// Only constraints of a [`ChallengeSegment`] may read challenges.
+    Challenge(usize),
+    /// Value of a column `offset` rows after the current row
+    Column {
+        /// Column name
+        name: String,
+        /// Row offset, 0 for the current row, 1 for the next and so on
+        offset: usize,
+    },
+    /// Sum of two expressions
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    /// Difference of two expressions
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    /// Product of two expressions
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    /// Negated expression
+    Neg(Box<Expr<F>>),
+}
+
+/// Value of a named column at the current row
+pub fn col<F: FieldElement>(name: &str) -> Expr<F> {
+    Expr::Column {
+        name: name.to_string(),
+        offset: 0,
+    }
+}
+
+/// Field constant
+pub fn constant<F: FieldElement>(value: F) -> Expr<F> {
+    Expr::Constant(value)
+}
+
+/// Verifier challenge `index`, such as the `α` of a permutation argument
+pub fn challenge<F: FieldElement>(index: usize) -> Expr<F> {
+    Expr::Challenge(index)
+}
+
+impl<F: FieldElement> Expr<F> {
+    /// The same expression one row later
+    ///
+    /// `col("x").next()` is the value of `x` at the next row.
+    pub fn next(self) -> Self {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => self,
+            Expr::Column { name, offset } => Expr::Column { name, offset: offset + 1 },
+            Expr::Add(lhs, rhs) => Expr::Add(Box::new(lhs.next()), Box::new(rhs.next())),
+            Expr::Sub(lhs, rhs) => Expr::Sub(Box::new(lhs.next()), Box::new(rhs.next())),
+            Expr::Mul(lhs, rhs) => Expr::Mul(Box::new(lhs.next()), Box::new(rhs.next())),
+            Expr::Neg(inner) => Expr::Neg(Box::new(inner.next())),
+        }
+    }
+
+    /// Square of the expression
+    pub fn square(self) -> Self {
+        self.clone() * self
+    }
+
+    /// Degree of the expression in the trace values
+    pub fn degree(&self) -> usize {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => 0,
+            Expr::Column { .. } => 1,
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) => lhs.degree().max(rhs.degree()),
+            Expr::Mul(lhs, rhs) => lhs.degree() + rhs.degree(),
+            Expr::Neg(inner) => inner.degree(),
+        }
+    }
+
+    /// Number of consecutive rows the expression reads, at least two
+    pub fn window(&self) -> usize {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => 2,
+            Expr::Column { offset, .. } => (offset + 1).max(2),
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => lhs.window().max(rhs.window()),
+            Expr::Neg(inner) => inner.window(),
+        }
+    }
+
+    /// Number of verifier challenges the expression needs, one past the highest it reads
+    pub fn num_challenges(&self) -> usize {
+        match self {
+            Expr::Challenge(index) => index + 1,
+            Expr::Constant(_) | Expr::Column { .. } => 0,
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => {
+                lhs.num_challenges().max(rhs.num_challenges())
+            }
+            Expr::Neg(inner) => inner.num_challenges(),
+        }
+    }
+
+    /// Columns the expression reads, with their row offsets, in order of first appearance
+    pub fn reads(&self) -> Vec<(&str, usize)> {
+        let mut reads = Vec::new();
+        self.collect_reads(&mut reads);
+        reads
+    }
+
+    /// Append the columns read to `reads`, skipping those already there
+    fn collect_reads<'a>(&'a self, reads: &mut Vec<(&'a str, usize)>) {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => {}
+            Expr::Column { name, offset } => {
+                if !reads.contains(&(name.as_str(), *offset)) {
+                    reads.push((name.as_str(), *offset));
+                }
+            }
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => {
+                lhs.collect_reads(reads);
+                rhs.collect_reads(reads);
+            }
+            Expr::Neg(inner) => inner.collect_reads(reads),
+        }
+    }
+
+    /// Append the postfix program of the expression, resolving column names
+    fn compile_into(&self, columns: &[String], program: &mut Vec<Op<F>>) -> Result<(), AirError> {
+        match self {
+            Expr::Constant(value) => program.push(Op::Constant(*value)),
+            Expr::Challenge(index) => program.push(Op::Challenge(*index)),
+            Expr::Column { name, offset } => {
+                let column = columns
+                    .iter()
+                    .position(|column| column == name)
+                    .ok_or_else(|| AirError::InvalidConstraint(format!("Unknown column `{}`", name)))?;
+                program.push(Op::Load { column, offset: *offset });
+            }
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => {
+                lhs.compile_into(columns, program)?;
+                rhs.compile_into(columns, program)?;
+                program.push(match self {
+                    Expr::Add(..) => Op::Add,
+                    Expr::Sub(..) => Op::Sub,
+                    _ => Op::Mul,
+                });
+            }
+            Expr::Neg(inner) => {
+                inner.compile_into(columns, program)?;
+                program.push(Op::Neg);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<F: FieldElement> Add for Expr<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Expr::Add(Box::new(self), Box::new(other))
+    }
+}
+
+impl<F: FieldElement> Sub for Expr<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Expr::Sub(Box::new(self), Box::new(other))
+    }
+}
+
+impl<F: FieldElement> Mul for Expr<F> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Expr::Mul(Box::new(self), Box::new(other))
+    }
+}
+
+impl<F: FieldElement> Neg for Expr<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Expr::Neg(Box::new(self))
+    }
+}
+
+impl<F: FieldElement> Display for Expr<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Expr::Constant(value) => write!(f, "{}", value),
+            Expr::Challenge(index) => write!(f, "challenge[{}]", index),
+            Expr::Column { name, offset } => write!(f, "{}{}", name, "'".repeat(*offset)),
+            Expr::Add(lhs, rhs) => write!(f, "({} + {})", lhs, rhs),
+            Expr::Sub(lhs, rhs) => write!(f, "({} - {})", lhs, rhs),
+            Expr::Mul(lhs, rhs) => write!(f, "{} * {}", lhs, rhs),
+            Expr::Neg(inner) => write!(f, "-{}", inner),
+        }
+    }
+}
+
+/// Instruction of a compiled constraint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op<F: FieldElement> {
+    /// Push a constant
+    Constant(F),
+    /// Push a verifier challenge
+    Challenge(usize),
+    /// Push a column value of the row `offset` rows after the current one
+    Load { column: usize, offset: usize },
+    /// Pop two values and push their sum
+    Add,
+    /// Pop two values and push their difference
+    Sub,
+    /// Pop two values and push their product
+    Mul,
+    /// Negate the top value
+    Neg,
+}
+
+/// Constraint compiled from an expression, with columns resolved to indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledConstraint<F: FieldElement> {
+    /// Source expression
+    expression: Expr<F>,
+    /// Postfix program evaluating the expression
+    program: Vec<Op<F>>,
+    /// Degree in the trace values
+    degree: usize,
+    /// Number of consecutive rows read
+    window: usize,
+}
+
+impl<F: FieldElement> CompiledConstraint<F> {
+    /// Compile an expression over the given columns
+    pub fn compile(expression: Expr<F>, columns: &[String]) -> Result<Self, AirError> {
+        let mut program = Vec::new();
+        expression.compile_into(columns, &mut program)?;
+        Ok(Self {
+            degree: expression.degree(),
+            window: expression.window(),
+            expression,
+            program,
+        })
+    }
+
+    /// Source expression
+    pub fn expression(&self) -> &Expr<F> {
+        &self.expression
+    }
+
+    /// Degree in the trace values
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Number of consecutive rows the constraint reads, at least two
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Evaluate the constraint on two consecutive rows
+    ///
+    /// The constraint holds if the result is zero. Columns missing from a
+    /// row, and rows after the next one, read as zero.
+    pub fn evaluate(&self, current: &[F], next: &[F]) -> F {
+        self.evaluate_window(&[current, next])
+    }
+
+    /// Evaluate the constraint on consecutive rows, from the current one on
+    ///
+    /// The constraint holds if the result is zero. Columns missing from a
+    /// row, and rows missing from the window, read as zero, and so do
+    /// challenges.
+    pub fn evaluate_window(&self, rows: &[&[F]]) -> F {
+        self.evaluate_with_challenges(rows, &[])
+    }
+
+    /// Evaluate the constraint on consecutive rows with the verifier challenges drawn for it
+    ///
+    /// Challenges missing from `challenges` read as zero, like missing columns.
+    pub fn evaluate_with_challenges(&self, rows: &[&[F]], challenges: &[F]) -> F {
+        let mut stack: Vec<F> = Vec::with_capacity(self.program.len());
+        for op in &self.program {
+            let value = match *op {
+                Op::Constant(value) => value,
+                Op::Challenge(index) => challenges.get(index).copied().unwrap_or_else(F::zero),
+                Op::Load { column, offset } => rows
+                    .get(offset)
+                    .and_then(|row| row.get(column))
+                    .copied()
+                    .unwrap_or_else(F::zero),
+                Op::Neg => -stack.pop().expect("operand of a compiled program"),
+                Op::Add | Op::Sub | Op::Mul => {
+                    let rhs = stack.pop().expect("operand of a compiled program");
+                    let lhs = stack.pop().expect("operand of a compiled program");
+                    match op {
+                        Op::Add => lhs + rhs,
+                        Op::Sub => lhs - rhs,
+                        _ => lhs * rhs,
+                    }
+                }
+            };
+            stack.push(value);
+        }
+        stack.pop().unwrap_or_else(F::zero)
+    }
+
+    /// Coefficients of a linear constraint
+    ///
+    /// Returns the coefficients of the current-row columns, of the next-row
+    /// columns and the constant term, or `None` if the constraint is not
+    /// linear or reads more than two rows.
+    fn linear_form(&self, width: usize) -> Option<(Vec<F>, Vec<F>, F)> {
+        if self.window > 2 {
+            return None;
+        }
+        // A linear form is stored as [current..., next..., constant]
+        let mut stack: Vec<Vec<F>> = Vec::with_capacity(self.program.len());
+        for op in &self.program {
+            let form = match *op {
+                Op::Constant(value) => {
+                    let mut form = vec![F::zero(); 2 * width + 1];
+                    form[2 * width] = value;
+                    form
+                }
+                Op::Load { column, offset } => {
+                    let mut form = vec![F::zero(); 2 * width + 1];
+                    form[offset * width + column] = F::one();
+                    form
+                }
+                // Challenges are only drawn once the main trace is committed
+                Op::Challenge(_) => return None,
+                Op::Neg => stack.pop()?.into_iter().map(|value| -value).collect(),
+                Op::Add | Op::Sub | Op::Mul => {
+                    let rhs = stack.pop()?;
+                    let lhs = stack.pop()?;
+                    match op {
+                        Op::Add => lhs.iter().zip(&rhs).map(|(&a, &b)| a + b).collect(),
+                        Op::Sub => lhs.iter().zip(&rhs).map(|(&a, &b)| a - b).collect(),
+                        _ => {
+                            let is_constant = |form: &[F]| form[..2 * width].iter().all(FieldElement::is_zero);
+                            let (scalar, form) = if is_constant(&lhs) {
+                                (lhs[2 * width], rhs)
+                            } else if is_constant(&rhs) {
+                                (rhs[2 * width], lhs)
+                            } else {
+                                return None;
+                            };
+                            form.into_iter().map(|value| value * scalar).collect()
+                        }
+                    }
+                }
+            };
+            stack.push(form);
+        }
+        let mut form = stack.pop()?;
+        let constant = form.pop()?;
+        let next = form.split_off(width);
+        Some((form, next, constant))
+    }
+}
+
+impl<F: FieldElement> Display for CompiledConstraint<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} = 0 (degree {})", self.expression, self.degree)
+    }
+}
+
+/// Builder of an AIR from expressions over named columns
+#[derive(Debug, Clone)]
+pub struct AirBuilder<F: FieldElement> {
+    /// Column names, in register order
+    columns: Vec<String>,
+    /// Transition constraints
+    transitions: Vec<Expr<F>>,
+    /// Transition constraints gated by a selector column
+    selected: Vec<(String, Expr<F>)>,
+    /// Columns left free by the transition matrix
+    free: Vec<String>,
+    /// Columns fixed to preprocessed values
+    fixed: Vec<(String, Vec<F>)>,
+    /// Intermediate columns introduced by [`optimize`](Self::optimize), with the expression each holds
+    intermediates: Vec<(String, Expr<F>)>,
+    /// Auxiliary segments whose constraints read verifier challenges
+    segments: Vec<ChallengeSegment<F>>,
+    /// Boundary constraints on named columns
+    boundary: Vec<(String, BoundaryConstraint<F>)>,
+    /// Security parameter of the AIR
+    security_parameter: u32,
+}
+
+impl<F: FieldElement> AirBuilder<F> {
+    /// Create a builder without columns or constraints
+    pub fn new(security_parameter: u32) -> Self {
+        Self {
+            columns: Vec::new(),
+            transitions: Vec::new(),
+            selected: Vec::new(),
+            free: Vec::new(),
+            fixed: Vec::new(),
+            intermediates: Vec::new(),
+            segments: Vec::new(),
+            boundary: Vec::new(),
+            security_parameter,
+        }
+    }
+
+    /// Add a column after the existing ones
+    pub fn with_column(mut self, name: &str) -> Self {
+        self.columns.push(name.to_string());
+        self
+    }
+
+    /// Add a column whose next value the transition matrix does not define
+    ///
+    /// Transitions reading the column at a later row are proven in an
+    /// auxiliary segment and may have degree at most two; they hold on every
+    /// row where their window fits in the trace.
+    pub fn with_free_column(mut self, name: &str) -> Self {
+        self.free.push(name.to_string());
+        self.with_column(name)
+    }
+
+    /// Add a column holding preprocessed values, such as an instruction ROM
+    ///
+    /// Row `i` of the column holds `values[i % values.len()]` (see
+    /// [`FixedColumn`]). Like a free column, no transition defines it.
+    pub fn with_fixed_column(mut self, name: &str, values: &[F]) -> Self {
+        self.fixed.push((name.to_string(), values.to_vec()));
+        self.with_column(name)
+    }
+
+    /// Add a transition constraint that must evaluate to zero on every window of consecutive rows
+    ///
+    /// Constraints reading more than two rows hold on every row where their
+    /// window fits in the trace, and may have degree at most two.
+    pub fn with_transition(mut self, constraint: Expr<F>) -> Self {
+        self.transitions.push(constraint);
+        self
+    }
+
+    /// Add a column repeating `values` from the first row on
+    ///
+    /// Row `i` of the column holds `values[i % values.len()]`. The column is
+    /// backed by one register per value, named `name`, `name[1]`, ... in
+    /// register order, which rotate at every step and are fixed at the first
+    /// row by boundary constraints; their values are the initial state.
+    pub fn with_periodic_column(mut self, name: &str, values: &[F]) -> Self {
+        let registers: Vec<String> = (0..values.len().max(1))
+            .map(|offset| match offset {
+                0 => name.to_string(),
+                _ => format!("{}[{}]", name, offset),
+            })
+            .collect();
+        for (offset, &value) in values.iter().enumerate() {
+            let following = &registers[(offset + 1) % values.len()];
+            self = self
+                .with_column(&registers[offset])
+                .with_transition(col(&registers[offset]).next() - col(following))
+                .with_initial(&registers[offset], value);
+        }
+        if values.is_empty() {
+            self = self.with_column(name);
+        }
+        self
+    }
+
+    /// Add a transition constraint that must hold on the rows where `selector` is nonzero
+    ///
+    /// The constraint is `selector * constraint = 0`, of degree one more than
+    /// `constraint`, which may have degree at most two.
+    pub fn with_selected_transition(mut self, selector: &str, constraint: Expr<F>) -> Self {
+        self.selected.push((selector.to_string(), constraint));
+        self
+    }
+
+    /// Assert the value of a column at the first row
+    pub fn with_initial(mut self, column: &str, value: F) -> Self {
+        self.boundary.push((column.to_string(), BoundaryConstraint::initial(0, value)));
+        self
+    }
+
+    /// Assert the value of a column at the last row
+    pub fn with_final(mut self, column: &str, value: F) -> Self {
+        self.boundary.push((column.to_string(), BoundaryConstraint::final_condition(0, value)));
+        self
+    }
+
+    /// Column names, in register order
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Whether no transition defines the column `name`
+    fn is_free(&self, name: &str) -> bool {
+        let fixed = self.fixed.iter().map(|(fixed, _)| fixed);
+        self.free.iter().chain(fixed).any(|free| free == name) || self.is_intermediate(name)
+    }
+
+    /// Whether `name` is an intermediate column introduced by [`optimize`](Self::optimize)
+    fn is_intermediate(&self, name: &str) -> bool {
+        self.intermediates.iter().any(|(intermediate, _)| intermediate == name)
+    }
+
+    /// Compile every transition constraint
+    ///
+    /// Gated transitions follow the others as `selector * constraint`.
+    pub fn compile(&self) -> Result<Vec<CompiledConstraint<F>>, AirError> {
+        let mut compiled = self.compile_transitions()?;
+        for (selector, constraint) in &self.selected {
+            compiled.push(CompiledConstraint::compile(col(selector) * constraint.clone(), &self.columns)?);
+        }
+        Ok(compiled)
+    }
+
+    /// Compile the ungated transition constraints
+    fn compile_transitions(&self) -> Result<Vec<CompiledConstraint<F>>, AirError> {
+        let duplicate = (0..self.columns.len()).find(|&i| self.columns[..i].contains(&self.columns[i]));
+        if let Some(name) = duplicate.map(|i| &self.columns[i]) {
+            return Err(AirError::InvalidConstraint(format!("Duplicate column `{}`", name)));
+        }
+        self.transitions
+            .iter()
+            .map(|expression| CompiledConstraint::compile(expression.clone(), &self.columns))
+            .collect()
+    }
+
+    /// Compile the gated transitions, with their selector columns
+    fn compile_selected(&self) -> Result<Vec<(Option<usize>, CompiledConstraint<F>)>, AirError> {
+        self.selected
+            .iter()
+            .map(|(selector, constraint)| {
+                let column = self
+                    .columns
+                    .iter()
+                    .position(|column| column == selector)
+                    .ok_or_else(|| AirError::InvalidConstraint(format!("Unknown selector `{}`", selector)))?;
+                Ok((Some(column), CompiledConstraint::compile(constraint.clone(), &self.columns)?))
+            })
+            .collect()
+    }
+
+    /// Compile the constraints defining the intermediate columns, `column - expression`
+    fn compile_intermediates(&self) -> Result<Vec<CompiledConstraint<F>>, AirError> {
+        self.intermediates
+            .iter()
+            .map(|(name, expression)| CompiledConstraint::compile(col(name) - expression.clone(), &self.columns))
+            .collect()
+    }
+}
+
+impl<F: FieldElement + 'static> AirBuilder<F> {
+
+    /// Build the AIR, lowering the transitions into its transition matrix
+    ///
+    /// Fails if a column name is unknown or the two-row transitions do not
+    /// define every column's next value, except for free and fixed columns, as a
+    /// linear combination of the current row. The degree of the transition
+    /// and the AIR's degree bound are the highest degree of the lowered
+    /// constraints. Gated transitions, transitions over more than two rows,
+    /// transitions reading free columns ahead and transitions reading only
+    /// the current row are proven in auxiliary segments, one per window of rows, so that constraints sharing a
+    /// zerofier share a segment. The column names become the register names
+    /// of the AIR.
+    pub fn build(&self) -> Result<Air<F>, AirError> {
+        let selected = self.selected.iter().map(|(_, constraint)| constraint);
+        if let Some(expression) = self.transitions.iter().chain(selected).find(|e| e.num_challenges() > 0) {
+            return Err(AirError::InvalidConstraint(format!(
+                "`{}` reads a verifier challenge; only challenge segment constraints can",
+                expression
+            )));
+        }
+        let width = self.columns.len();
+        let mut rows: Vec<Option<Vec<F>>> = vec![None; width];
+        // Constraints reading only the current row define no next value either
+        let defines_next = |constraint: &CompiledConstraint<F>| {
+            let reads = constraint.expression().reads();
+            !reads.iter().any(|&(name, offset)| offset > 0 && self.is_free(name))
+                && reads.iter().any(|&(_, offset)| offset > 0)
+        };
+        let (compiled, deferred): (Vec<_>, Vec<_>) = self
+            .compile_transitions()?
+            .into_iter()
+            .partition(|constraint| constraint.window() <= 2 && defines_next(constraint));
+        let degree = compiled.iter().map(CompiledConstraint::degree).max().unwrap_or(1).max(1);
+        let mut sources: Vec<Option<Expr<F>>> = vec![None; width];
+        for constraint in compiled {
+            let unsupported =
+                |reason: &str| AirError::InvalidTransition(format!("`{}` {}", constraint.expression(), reason));
+            let (current, next, constant) = constraint
+                .linear_form(width)
+                .ok_or_else(|| unsupported("is not linear"))?;
+            if !constant.is_zero() {
+                return Err(unsupported("has a constant term"));
+            }
+            let mut defined = next.iter().enumerate().filter(|(_, coefficient)| !coefficient.is_zero());
+            let (Some((column, &coefficient)), None) = (defined.next(), defined.next()) else {
+                return Err(unsupported("must read exactly one column at the next row"));
+            };
+            if rows[column].is_some() {
+                return Err(unsupported(&format!("redefines column `{}`", self.columns[column])));
+            }
+            // coefficient * next + current · c = 0, so next = -(current · c) / coefficient
+            let scale = -coefficient.inverse().expect("nonzero coefficient");
+            rows[column] = Some(current.into_iter().map(|value| value * scale).collect());
+            sources[column] = Some(constraint.expression().clone());
+        }
+        let coefficients = rows
+            .into_iter()
+            .enumerate()
+            .map(|(column, row)| match row {
+                Some(row) => Ok(row),
+                None if self.is_free(&self.columns[column]) => Ok(vec![F::zero(); width]),
+                None => Err(AirError::InvalidTransition(format!(
+                    "No transition defines column `{}`",
+                    self.columns[column]
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let free_registers: Vec<usize> = (0..width)
+            .filter(|&column| self.free.contains(&self.columns[column]) || self.is_intermediate(&self.columns[column]))
+            .collect();
+
+        let boundary = self
+            .boundary
+            .iter()
+            .map(|(name, constraint)| {
+                let register = self
+                    .columns
+                    .iter()
+                    .position(|column| column == name)
+                    .ok_or_else(|| AirError::InvalidBoundary(format!("Unknown column `{}`", name)))?;
+                Ok(BoundaryConstraint { register, ..constraint.clone() })
+            })
+            .collect::<Result<Vec<_>, AirError>>()?;
+
+        let mut air = Air::new(
+            Vec::new(),
+            TransitionFunction::new(coefficients, degree),
+            BoundaryConditions::new(boundary),
+            self.security_parameter,
+        );
+        air.max_constraint_degree = degree;
+        air = air.with_free_registers(&free_registers);
+        air.register_names = self.columns.clone();
+        for (name, values) in &self.fixed {
+            let register = self.columns.iter().position(|column| column == name).expect("fixed columns are columns");
+            air = air.with_fixed_column(FixedColumn::new(register, values)?);
+        }
+        // Gated transitions, deferred transitions and intermediate columns
+        // are proven in auxiliary segments, with the expression each stands for
+        let mut segmented: Vec<(Option<usize>, CompiledConstraint<F>, Expr<F>)> = Vec::new();
+        for ((selector, constraint), (name, _)) in self.compile_selected()?.into_iter().zip(&self.selected) {
+            let gated = col(name) * constraint.expression().clone();
+            segmented.push((selector, constraint, gated));
+        }
+        for constraint in deferred.into_iter().chain(self.compile_intermediates()?) {
+            let expression = constraint.expression().clone();
+            segmented.push((None, constraint, expression));
+        }
+        // One segment per window, so that no constraint is exempted on rows
+        // its own window still covers, and constraints with the same
+        // zerofier share a segment
+        let mut transitions = sources;
+        let mut windows: Vec<usize> = segmented.iter().map(|(_, constraint, _)| constraint.window()).collect();
+        windows.sort_unstable();
+        windows.dedup();
+        for window in windows {
+            let mut constraints = Vec::new();
+            for (selector, constraint, expression) in segmented.iter().filter(|(_, c, _)| c.window() == window) {
+                // Both constraints of a segment column come from the same expression
+                transitions.extend([Some(expression.clone()), Some(expression.clone())]);
+                constraints.push((*selector, constraint.clone()));
+            }
+            air = air.with_aux_segment(AuxSegment::new(SelectedTransitions::new(constraints)?));
+        }
+        let paired = transitions.len();
+        for segment in &self.segments {
+            air = air.with_aux_segment(AuxSegment::new(segment.compile(&self.columns)?));
+            transitions.extend(segment.transitions().iter().cloned().map(Some));
+        }
+        air.symbolic = Some(SymbolicAir {
+            columns: self.columns.clone(),
+            transitions,
+            paired,
+            intermediates: self
+                .intermediates
+                .iter()
+                .map(|(_, expression)| CompiledConstraint::compile(expression.clone(), &self.columns))
+                .collect::<Result<_, _>>()?,
+        });
+        Ok(air)
+    }
+}
+
+/// Symbolic form of an AIR built with [`AirBuilder`]
+///
+/// Holds the column names and the expression behind every transition
+/// constraint, indexed like
+/// [`ConstraintViolation::Transition`](crate::air::ConstraintViolation::Transition): one per
+/// main register, then the constraints of each auxiliary segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolicAir<F: FieldElement> {
+    /// Column names, in register order
+    columns: Vec<String>,
+    /// Expression of every transition constraint, if it has one
+    transitions: Vec<Option<Expr<F>>>,
+    /// Number of transitions before those of the challenge segments, which
+    /// have one constraint per expression rather than two per column
+    paired: usize,
+    /// Expressions held by the intermediate columns, which are the last columns
+    intermediates: Vec<CompiledConstraint<F>>,
+}
+
+impl<F: FieldElement> SymbolicAir<F> {
+    /// Column names, in register order
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Register of a named column
+    pub fn register(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column == name)
+    }
+
+    /// Expression of the transition constraint `index`
+    ///
+    /// Free columns have no transition of their own.
+    pub fn transition(&self, index: usize) -> Option<&Expr<F>> {
+        self.transitions.get(index).and_then(Option::as_ref)
+    }
+
+    /// Complete a row of the written columns with the intermediate columns
+    ///
+    /// Intermediate columns are introduced by [`AirBuilder::optimize`] and
+    /// come after the written columns, each holding an expression over the
+    /// current row. Values of `row` past the written columns are ignored, so
+    /// a step function can be completed with
+    /// `DynTransition::new(move |state| symbolic.complete(&step(state)))`.
+    pub fn complete(&self, row: &[F]) -> Vec<F> {
+        let written = self.columns.len() - self.intermediates.len();
+        let mut completed = row[..written.min(row.len())].to_vec();
+        completed.resize(written, F::zero());
+        for intermediate in &self.intermediates {
+            let value = intermediate.evaluate(&completed, &[]);
+            completed.push(value);
+        }
+        completed
+    }
+
+    /// Expression of every transition constraint the AIR enforces, once each
+    ///
+    /// The transitions defining main registers, in register order, then one
+    /// per column of the segments holding gated and deferred transitions,
+    /// then the transitions of the challenge segments.
+    pub fn constraints(&self) -> Vec<&Expr<F>> {
+        let width = self.columns.len().min(self.paired);
+        let (main, aux) = self.transitions[..self.paired].split_at(width);
+        let challenged = self.transitions[self.paired..].iter().flatten();
+        main.iter().flatten().chain(aux.iter().step_by(2).flatten()).chain(challenged).collect()
+    }
+}
+
+/// Auxiliary segment proving transitions gated by selector columns or
+/// reading more than two rows
+///
+/// Column `i` holds the value of the `i`-th constraint on the window of rows
+/// starting at each row, so the constraints `value = constraint` and
+/// `selector * value = 0` both have degree at most two. Ungated constraints
+/// take `value = 0` instead.
+#[derive(Debug, Clone)]
+struct SelectedTransitions<F: FieldElement> {
+    /// Selector column, if any, and constraint of every transition
+    constraints: Vec<(Option<usize>, CompiledConstraint<F>)>,
+}
+
+impl<F: FieldElement> SelectedTransitions<F> {
+    /// Segment proving `constraints`, each of degree at most two
+    fn new(constraints: Vec<(Option<usize>, CompiledConstraint<F>)>) -> Result<Self, AirError> {
+        let too_high = constraints.iter().find(|(_, constraint)| constraint.degree() > MAX_AUX_CONSTRAINT_DEGREE);
+        if let Some((_, constraint)) = too_high {
+            return Err(AirError::DegreeError(format!(
+                "`{}` has degree {}; gated and multi-row transitions have degree at most {}",
+                constraint.expression(),
+                constraint.degree(),
+                MAX_AUX_CONSTRAINT_DEGREE
+            )));
+        }
+        Ok(Self { constraints })
+    }
+
+    /// Residuals of the constraints on the window of main rows `main` and segment rows `aux`
+    fn residuals(&self, main: &[&[F]], aux: &[&[F]]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.constraints.len());
+        for (index, (selector, constraint)) in self.constraints.iter().enumerate() {
+            let value = aux[0].get(index).copied().unwrap_or_else(F::zero);
+            let selector = selector.map_or_else(F::one, |column| main[0].get(column).copied().unwrap_or_else(F::zero));
+            residuals.push(value - constraint.evaluate_window(main));
+            residuals.push(selector * value);
+        }
+        residuals
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for SelectedTransitions<F> {
+    fn width(&self) -> usize {
+        self.constraints.len()
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.constraints.len()
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let rows: Vec<Vec<F>> = (0..length).map(|index| main.iter().map(|column| column[index]).collect()).collect();
+        let rows: Vec<&[F]> = rows.iter().map(Vec::as_slice).collect();
+        self.constraints
+            .iter()
+            .map(|(_, constraint)| {
+                // Rows where the window does not fit start no transition and hold zero
+                (0..length)
+                    .map(|index| {
+                        if index + constraint.window() <= length {
+                            constraint.evaluate_window(&rows[index..index + constraint.window()])
+                        } else {
+                            F::zero()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        self.residuals(&[frame.main_current, frame.main_next], &[frame.aux_current, frame.aux_next])
+    }
+
+    fn window(&self) -> usize {
+        self.constraints.iter().map(|(_, constraint)| constraint.window()).fold(2, usize::max)
+    }
+
+    fn evaluate_window(&self, window: &AuxWindow<'_, F>, _challenges: &[F]) -> Vec<F> {
+        self.residuals(window.main, window.aux)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::DynTransition;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_builder_compiles_named_constraints() {
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_column("acc")
+            .with_column("step")
+            .with_transition(col("acc").next() - col("acc") - col("step"))
+            .with_transition(col("step").next() - col("step"))
+            .with_initial("acc", PrimeField64::new(0))
+            .with_initial("step", PrimeField64::new(3));
+        let compiled = builder.compile().unwrap();
+        assert_eq!(compiled.iter().map(CompiledConstraint::degree).collect::<Vec<_>>(), vec![1, 1]);
+        let current = [PrimeField64::new(6), PrimeField64::new(3)];
+        let next = [PrimeField64::new(9), PrimeField64::new(3)];
+        assert!(compiled.iter().all(|constraint| constraint.evaluate(&current, &next).is_zero()));
+        assert_eq!(compiled[0].evaluate(&current, &current), -PrimeField64::new(3));
+
+        // The lowered AIR is proven like a hand-written one
+        let air = builder.build().unwrap();
+        assert_eq!(
+            air.transition.coefficients,
+            vec![
+                vec![PrimeField64::new(1), PrimeField64::new(1)],
+                vec![PrimeField64::new(0), PrimeField64::new(1)],
+            ]
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(0), PrimeField64::new(3)], 16).unwrap();
+        assert_eq!(witness.columns.value(0, 15), PrimeField64::new(45));
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::new(48).verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // Degrees are tracked through products, and only linear transitions lower
+        let squared = builder.clone().with_transition(col("acc").next() - col("step").square());
+        assert_eq!(squared.compile().unwrap()[2].degree(), 2);
+        assert!(matches!(squared.build(), Err(AirError::InvalidTransition(_))));
+        let unknown = builder.clone().with_transition(col("missing") - col("acc"));
+        assert!(matches!(unknown.compile(), Err(AirError::InvalidConstraint(_))));
+        let undefined = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_column("y")
+            .with_transition(col("x").next() - col("y"));
+        assert!(matches!(undefined.build(), Err(AirError::InvalidTransition(_))));
+    }
+
+    #[test]
+    fn test_selected_transitions() {
+        let values = |values: &[u64]| values.iter().map(|&value| PrimeField64::new(value)).collect::<Vec<_>>();
+        let phase = col::<PrimeField64>("phase");
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_periodic_column("first", &values(&[1, 0, 0, 0]))
+            .with_periodic_column("phase", &values(&[0, 1, 2, 3]))
+            .with_selected_transition("first", phase.clone().square() + phase.clone());
+        assert_eq!(builder.columns()[..3], ["first", "first[1]", "first[2]"]);
+        assert_eq!(builder.compile().unwrap().last().unwrap().degree(), 3);
+
+        // The gated transition holds on every fourth row only, where the phase is zero
+        let air = builder.build().unwrap();
+        assert_eq!(air.aux_segments.len(), 1);
+        assert_eq!(air.max_constraint_degree(), 2);
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &values(&[1, 0, 0, 0, 0, 1, 2, 3]), 16).unwrap();
+        assert_eq!(witness.columns.value(4, 9), PrimeField64::new(1));
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::new(48).verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        let misplaced = builder.clone().with_selected_transition("first[1]", phase.clone().square() + phase.clone());
+        assert!(prover.prove(&misplaced.build().unwrap(), &PublicInputs::new(), &witness).is_err());
+        let cubic = builder.clone().with_selected_transition("first", phase.clone().square() * phase);
+        assert!(matches!(cubic.build(), Err(AirError::DegreeError(_))));
+    }
+
+    #[test]
+    fn test_window_transitions() {
+        let x = col::<PrimeField64>("x");
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_column("step")
+            .with_transition(x.clone().next() - x.clone() - col("step"))
+            .with_transition(col("step").next() - col("step"));
+        // The second difference of an arithmetic progression vanishes
+        let second_difference = x.clone().next().next() - x.clone().next() - x.clone().next() + x.clone();
+        let compiled = CompiledConstraint::compile(second_difference.clone(), builder.columns()).unwrap();
+        assert_eq!(compiled.window(), 3);
+        let rows = [2, 5, 8].map(|value| [PrimeField64::new(value), PrimeField64::new(3)]);
+        assert!(compiled.evaluate_window(&[&rows[0], &rows[1], &rows[2]]).is_zero());
+
+        let air = builder.clone().with_transition(second_difference).build().unwrap();
+        assert_eq!((air.aux_segments.len(), air.window()), (1, 3));
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(2), PrimeField64::new(3)], 16).unwrap();
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        assert_eq!(proof.ood_frame.trace_window.len(), 1);
+        StarkVerifier::new(48).verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // A Fibonacci-style window constraint does not hold on the progression
+        let fibonacci = builder.with_transition(x.clone().next().next() - x.clone().next() - x);
+        assert!(prover.prove(&fibonacci.build().unwrap(), &PublicInputs::new(), &witness).is_err());
+    }
+
+    #[test]
+    fn test_free_column_with_step_function() {
+        let x = col::<PrimeField64>("x");
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_free_column("x")
+            .with_column("k")
+            .with_transition(x.clone().next() - x.square())
+            .with_transition(col("k").next() - col("k"));
+        let air = builder.build().unwrap();
+        assert_eq!(air.free_registers, vec![0]);
+        assert_eq!(air.aux_segments.len(), 1);
+
+        let square = DynTransition::new(|state: &[PrimeField64]| {
+            vec![state[0] * state[0], state[1]]
+        });
+        let air = air.with_step(square);
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let init = [PrimeField64::new(3), PrimeField64::new(7)];
+        let witness = prover.generate_trace(&air, &init, 16).unwrap();
+        assert_eq!(witness.columns.value(0, 2), PrimeField64::new(81));
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::new(48).verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // A step function that cubes instead does not satisfy the constraints
+        let cube = DynTransition::new(|state: &[PrimeField64]| {
+            vec![state[0] * state[0] * state[0], state[1]]
+        });
+        let wrong = air.clone().with_step(cube);
+        let witness = prover.generate_trace(&wrong, &init, 16).unwrap();
+        assert!(prover.prove(&air, &PublicInputs::new(), &witness).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! Expression Optimizer
+//!
+//! Constraint evaluation cost scales with expression size, and the prover
+//! evaluates every transition on every row of the LDE domain. Before building,
+//! [`AirBuilder::optimize`] shrinks the expressions:
+//!
+//! - **Constant folding**: operations on constants are evaluated, and sums
+//!   with zero, products with zero or one and double negations are removed.
+//! - **Common subexpressions**: a product of degree two that appears more than
+//!   once is moved into an intermediate column, which an auxiliary constraint
+//!   ties to the product. Every occurrence then reads the column instead.
+//!
+//! Constraints sharing a zerofier are merged by [`AirBuilder::build`], which
+//! proves all auxiliary constraints over the same window of rows in one
+//! segment.
+
+use crate::prelude::*;
+use super::{AirBuilder, Expr};
+use crate::types::FieldElement;
+
+impl<F: FieldElement> Expr<F> {
+    /// The expression with constant subexpressions evaluated and trivial operations removed
+    pub fn fold_constants(&self) -> Self {
+        let constant = |expression: &Expr<F>| match expression {
+            Expr::Constant(value) => Some(*value),
+            _ => None,
+        };
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) | Expr::Column { .. } => self.clone(),
+            Expr::Add(lhs, rhs) => match (lhs.fold_constants(), rhs.fold_constants()) {
+                (lhs, rhs) if constant(&lhs).is_some_and(|value| value.is_zero()) => rhs,
+                (lhs, rhs) if constant(&rhs).is_some_and(|value| value.is_zero()) => lhs,
+                (Expr::Constant(a), Expr::Constant(b)) => Expr::Constant(a + b),
+                (lhs, rhs) => lhs + rhs,
+            },
+            Expr::Sub(lhs, rhs) => match (lhs.fold_constants(), rhs.fold_constants()) {
+                (lhs, rhs) if constant(&rhs).is_some_and(|value| value.is_zero()) => lhs,
+                (lhs, rhs) if constant(&lhs).is_some_and(|value| value.is_zero()) => (-rhs).fold_constants(),
+                (Expr::Constant(a), Expr::Constant(b)) => Expr::Constant(a - b),
+                (lhs, rhs) => lhs - rhs,
+            },
+            Expr::Mul(lhs, rhs) => match (lhs.fold_constants(), rhs.fold_constants()) {
+                (lhs, _) if constant(&lhs).is_some_and(|value| value.is_zero()) => lhs,
+                (_, rhs) if constant(&rhs).is_some_and(|value| value.is_zero()) => rhs,
+                (lhs, rhs) if constant(&lhs) == Some(F::one()) => rhs,
+                (lhs, rhs) if constant(&rhs) == Some(F::one()) => lhs,
+                (Expr::Constant(a), Expr::Constant(b)) => Expr::Constant(a * b),
+                (lhs, rhs) => lhs * rhs,
+            },
+            Expr::Neg(inner) => match inner.fold_constants() {
+                Expr::Constant(value) => Expr::Constant(-value),
+                Expr::Neg(inner) => *inner,
+                inner => -inner,
+            },
+        }
+    }
+
+    /// Number of nodes of the expression
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) | Expr::Column { .. } => 1,
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => 1 + lhs.size() + rhs.size(),
+            Expr::Neg(inner) => 1 + inner.size(),
+        }
+    }
+
+    /// The same expression `rows` rows earlier
+    fn previous(&self, rows: usize) -> Self {
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) => self.clone(),
+            Expr::Column { name, offset } => Expr::Column {
+                name: name.clone(),
+                offset: offset - rows,
+            },
+            Expr::Add(lhs, rhs) => lhs.previous(rows) + rhs.previous(rows),
+            Expr::Sub(lhs, rhs) => lhs.previous(rows) - rhs.previous(rows),
+            Expr::Mul(lhs, rhs) => lhs.previous(rows) * rhs.previous(rows),
+            Expr::Neg(inner) => -inner.previous(rows),
+        }
+    }
+
+    /// Row offset of a product an intermediate column can stand for, in a constraint of `window` rows
+    ///
+    /// The product must have degree two and read a single row, which must
+    /// not be the last of the window: the column is only constrained on the
+    /// rows transitions start at.
+    fn extractable_offset(&self, window: usize) -> Option<usize> {
+        if !matches!(self, Expr::Mul(..)) || self.degree() != 2 {
+            return None;
+        }
+        let reads = self.reads();
+        let offset = reads.first()?.1;
+        (reads.iter().all(|&(_, read)| read == offset) && offset + 2 <= window).then_some(offset)
+    }
+
+    /// Count the extractable products, as read from their own row, into `counts`
+    fn count_products(&self, window: usize, counts: &mut Vec<(Expr<F>, usize)>) {
+        if let Some(offset) = self.extractable_offset(window) {
+            let product = self.previous(offset);
+            match counts.iter_mut().find(|(candidate, _)| *candidate == product) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((product, 1)),
+            }
+        }
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) | Expr::Column { .. } => {}
+            Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) | Expr::Mul(lhs, rhs) => {
+                lhs.count_products(window, counts);
+                rhs.count_products(window, counts);
+            }
+            Expr::Neg(inner) => inner.count_products(window, counts),
+        }
+    }
+
+    /// Replace every extractable occurrence of `product` with the column `name`
+    fn replace_product(&self, product: &Expr<F>, name: &str, window: usize) -> Self {
+        if let Some(offset) = self.extractable_offset(window) {
+            if self.previous(offset) == *product {
+                return Expr::Column {
+                    name: name.to_string(),
+                    offset,
+                };
+            }
+        }
+        let replace = |expression: &Expr<F>| expression.replace_product(product, name, window);
+        match self {
+            Expr::Constant(_) | Expr::Challenge(_) | Expr::Column { .. } => self.clone(),
+            Expr::Add(lhs, rhs) => replace(lhs) + replace(rhs),
+            Expr::Sub(lhs, rhs) => replace(lhs) - replace(rhs),
+            Expr::Mul(lhs, rhs) => replace(lhs) * replace(rhs),
+            Expr::Neg(inner) => -replace(inner),
+        }
+    }
+}
+
+impl<F: FieldElement> AirBuilder<F> {
+    /// Fold the constants of every transition and extract their common products
+    ///
+    /// Each product of degree two that appears more than once across the
+    /// transitions becomes an intermediate column, named `_cse0`, `_cse1`,
+    /// ... and added after the other columns, so the built AIR has one
+    /// register per intermediate column. Traces are completed with the
+    /// intermediate values by
+    /// [`SymbolicAir::complete`](super::SymbolicAir::complete).
+    pub fn optimize(mut self) -> Self {
+        for transition in &mut self.transitions {
+            *transition = transition.fold_constants();
+        }
+        for (_, constraint) in &mut self.selected {
+            *constraint = constraint.fold_constants();
+        }
+        loop {
+            let mut counts = Vec::new();
+            for transition in &self.transitions {
+                transition.count_products(transition.window(), &mut counts);
+            }
+            for (_, constraint) in &self.selected {
+                constraint.count_products(constraint.window(), &mut counts);
+            }
+            // The most frequent product first, so that nested ones are counted once extracted
+            let Some((product, _)) = counts
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .fold(None, |best: Option<(Expr<F>, usize)>, (product, count)| match best {
+                    Some((_, most)) if most >= count => best,
+                    _ => Some((product, count)),
+                })
+            else {
+                return self;
+            };
+            let name = format!("_cse{}", self.intermediates.len());
+            for transition in &mut self.transitions {
+                *transition = transition.replace_product(&product, &name, transition.window());
+            }
+            for (_, constraint) in &mut self.selected {
+                *constraint = constraint.replace_product(&product, &name, constraint.window());
+            }
+            self.columns.push(name.clone());
+            self.intermediates.push((name, product));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::col;
+    use crate::air::DynTransition;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_optimize_folds_constants_and_extracts_products() {
+        let c = |value: u64| Expr::Constant(PrimeField64::new(value));
+        let (x, y) = (col::<PrimeField64>("x"), col::<PrimeField64>("y"));
+        let folded = (c(2) * c(3) - c(6)) * y.clone() + x.clone() * c(1) - -(-c(0) - x.clone());
+        assert_eq!(folded.fold_constants(), x.clone() - x.clone());
+        assert!(folded.fold_constants().size() < folded.size());
+
+        // x' = x * y + y and z' = z + x * y share the product x * y
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_free_column("x")
+            .with_column("y")
+            .with_free_column("z")
+            .with_transition(x.clone().next() - x.clone() * y.clone() - y.clone() * c(1))
+            .with_transition(col("y").next() - col("y"))
+            .with_transition(col("z").next() - col("z") - x.clone() * y.clone());
+        let optimized = builder.clone().optimize();
+        assert_eq!(optimized.columns(), ["x", "y", "z", "_cse0"]);
+        let size = |builder: &AirBuilder<PrimeField64>| builder.transitions.iter().map(Expr::size).sum::<usize>();
+        assert!(size(&optimized) < size(&builder));
+
+        // The intermediate column completes the trace and keeps the proof sound
+        let air = optimized.build().unwrap();
+        assert_eq!(air.aux_segments.len(), 1);
+        let symbolic = air.symbolic.clone().unwrap();
+        let step = DynTransition::new(move |state: &[PrimeField64]| {
+            let product = state[0] * state[1];
+            symbolic.complete(&[product + state[1], state[1], state[2] + product])
+        });
+        let air = air.with_step(step);
+        let written = [PrimeField64::new(2), PrimeField64::new(3), PrimeField64::new(1)];
+        let init = air.symbolic.as_ref().unwrap().complete(&written);
+        assert_eq!(init[3], PrimeField64::new(6));
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &init, 16).unwrap();
+        assert_eq!(witness.columns.value(2, 1), PrimeField64::new(7));
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::new(48).verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // A wrong intermediate value is caught by the constraint defining it
+        let mut forged = witness.clone();
+        forged.columns.set(3, 4, PrimeField64::new(5));
+        assert!(prover.prove(&air, &PublicInputs::new(), &forged).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! Challenge Segments
+//!
+//! Permutation and lookup arguments need columns built from random verifier
+//! challenges, which are drawn only after the main trace is committed. A
+//! [`ChallengeSegment`] writes such an auxiliary segment with the builder's
+//! expressions: its constraints read the main columns, the segment's own
+//! columns and the challenges, as [`challenge`](super::challenge)`(i)`, which
+//! are resolved from the transcript when proving and verifying.
+//!
+//! The segment columns are computed by a function of the main trace and the
+//! challenges, like the [`build`](AuxTraceSegment::build) of a hand-written
+//! segment.
+
+use crate::prelude::*;
+use super::{AirBuilder, CompiledConstraint, Expr};
+use crate::air::{AirError, AuxFrame, AuxTraceSegment, AuxWindow, BoundaryConstraint, MAX_AUX_CONSTRAINT_DEGREE};
+use crate::types::FieldElement;
+use core::fmt::{Debug, Formatter};
+use alloc::sync::Arc;
+
+/// Function computing the segment columns from the main trace columns and the challenges
+type ColumnFn<F> = Arc<dyn Fn(&[Vec<F>], &[F]) -> Vec<Vec<F>> + Send + Sync>;
+
+/// Auxiliary segment written as expressions over the main columns, its own columns and verifier challenges
+#[derive(Clone)]
+pub struct ChallengeSegment<F: FieldElement> {
+    /// Names of the segment columns
+    columns: Vec<String>,
+    /// Number of challenges drawn for the segment
+    num_challenges: usize,
+    /// Transition constraints, each of degree at most two
+    transitions: Vec<Expr<F>>,
+    /// Boundary constraints on named segment columns, with values over the challenges
+    boundary: Vec<(String, BoundaryConstraint<F>, Expr<F>)>,
+    /// Computes the segment columns
+    build: ColumnFn<F>,
+}
+
+impl<F: FieldElement> ChallengeSegment<F> {
+    /// Segment with the given columns, drawing `num_challenges` challenges
+    ///
+    /// `build` computes the segment columns, in order, from the main trace
+    /// columns and the challenges.
+    pub fn new(
+        columns: &[&str],
+        num_challenges: usize,
+        build: impl Fn(&[Vec<F>], &[F]) -> Vec<Vec<F>> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            columns: columns.iter().map(|column| column.to_string()).collect(),
+            num_challenges,
+            transitions: Vec::new(),
+            boundary: Vec::new(),
+            build: Arc::new(build),
+        }
+    }
+
+    /// Add a transition constraint, which may read the challenges and have degree at most two
+    pub fn with_transition(mut self, constraint: Expr<F>) -> Self {
+        self.transitions.push(constraint);
+        self
+    }
+
+    /// Assert the value of a segment column at the first row
+    ///
+    /// `value` may read challenges but no columns.
+    pub fn with_initial(mut self, column: &str, value: Expr<F>) -> Self {
+        self.boundary.push((column.to_string(), BoundaryConstraint::initial(0, F::zero()), value));
+        self
+    }
+
+    /// Assert the value of a segment column at the last row
+    ///
+    /// `value` may read challenges but no columns.
+    pub fn with_final(mut self, column: &str, value: Expr<F>) -> Self {
+        self.boundary.push((column.to_string(), BoundaryConstraint::final_condition(0, F::zero()), value));
+        self
+    }
+
+    /// Transition constraints, in order
+    pub fn transitions(&self) -> &[Expr<F>] {
+        &self.transitions
+    }
+
+    /// Compile the segment against the main columns
+    pub(super) fn compile(&self, main: &[String]) -> Result<CompiledSegment<F>, AirError> {
+        if let Some(column) = self.columns.iter().find(|column| main.contains(column)) {
+            return Err(AirError::InvalidConstraint(format!("Duplicate column `{}`", column)));
+        }
+        let columns: Vec<String> = main.iter().chain(&self.columns).cloned().collect();
+        let check_challenges = |expression: &Expr<F>| match expression.num_challenges() {
+            needed if needed > self.num_challenges => Err(AirError::InvalidConstraint(format!(
+                "`{}` reads challenge {} of a segment drawing {}",
+                expression,
+                needed - 1,
+                self.num_challenges
+            ))),
+            _ => Ok(()),
+        };
+        let mut transitions = Vec::with_capacity(self.transitions.len());
+        for expression in &self.transitions {
+            check_challenges(expression)?;
+            let constraint = CompiledConstraint::compile(expression.clone(), &columns)?;
+            if constraint.degree() > MAX_AUX_CONSTRAINT_DEGREE {
+                return Err(AirError::DegreeError(format!(
+                    "`{}` has degree {}; segment transitions have degree at most {}",
+                    expression,
+                    constraint.degree(),
+                    MAX_AUX_CONSTRAINT_DEGREE
+                )));
+            }
+            transitions.push(constraint);
+        }
+        let mut boundary = Vec::with_capacity(self.boundary.len());
+        for (name, constraint, value) in &self.boundary {
+            check_challenges(value)?;
+            let register = self
+                .columns
+                .iter()
+                .position(|column| column == name)
+                .ok_or_else(|| AirError::InvalidBoundary(format!("Unknown segment column `{}`", name)))?;
+            if !value.reads().is_empty() {
+                return Err(AirError::InvalidBoundary(format!("`{}` reads trace columns", value)));
+            }
+            let value = CompiledConstraint::compile(value.clone(), &[])?;
+            boundary.push((BoundaryConstraint { register, ..constraint.clone() }, value));
+        }
+        Ok(CompiledSegment {
+            main_width: main.len(),
+            width: self.columns.len(),
+            num_challenges: self.num_challenges,
+            transitions,
+            boundary,
+            build: Arc::clone(&self.build),
+        })
+    }
+}
+
+impl<F: FieldElement> Debug for ChallengeSegment<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ChallengeSegment")
+            .field("columns", &self.columns)
+            .field("num_challenges", &self.num_challenges)
+            .field("transitions", &self.transitions)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Challenge segment compiled against the main columns
+pub(super) struct CompiledSegment<F: FieldElement> {
+    /// Number of main columns, which the segment columns follow in the compiled programs
+    main_width: usize,
+    /// Number of segment columns
+    width: usize,
+    /// Number of challenges drawn for the segment
+    num_challenges: usize,
+    /// Transition constraints
+    transitions: Vec<CompiledConstraint<F>>,
+    /// Boundary constraints, with the value each asserts
+    boundary: Vec<(BoundaryConstraint<F>, CompiledConstraint<F>)>,
+    /// Computes the segment columns
+    build: ColumnFn<F>,
+}
+
+impl<F: FieldElement> CompiledSegment<F> {
+    /// Residuals of the transitions on consecutive main and segment rows
+    fn residuals(&self, main: &[&[F]], aux: &[&[F]], challenges: &[F]) -> Vec<F> {
+        let rows: Vec<Vec<F>> = main
+            .iter()
+            .zip(aux)
+            .map(|(main, aux)| {
+                let mut row = main[..self.main_width.min(main.len())].to_vec();
+                row.resize(self.main_width, F::zero());
+                row.extend_from_slice(aux);
+                row
+            })
+            .collect();
+        let rows: Vec<&[F]> = rows.iter().map(Vec::as_slice).collect();
+        self.transitions
+            .iter()
+            .map(|constraint| constraint.evaluate_with_challenges(&rows, challenges))
+            .collect()
+    }
+}
+
+impl<F: FieldElement> Debug for CompiledSegment<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("CompiledSegment")
+            .field("width", &self.width)
+            .field("num_challenges", &self.num_challenges)
+            .field("transitions", &self.transitions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for CompiledSegment<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        self.num_challenges
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.transitions.len()
+    }
+
+    fn build(&self, main: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>> {
+        (self.build)(main, challenges)
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, challenges: &[F]) -> Vec<F> {
+        self.residuals(&[frame.main_current, frame.main_next], &[frame.aux_current, frame.aux_next], challenges)
+    }
+
+    fn window(&self) -> usize {
+        self.transitions.iter().map(CompiledConstraint::window).fold(2, usize::max)
+    }
+
+    fn evaluate_window(&self, window: &AuxWindow<'_, F>, challenges: &[F]) -> Vec<F> {
+        self.residuals(window.main, window.aux, challenges)
+    }
+
+    fn boundary(&self, challenges: &[F]) -> Vec<BoundaryConstraint<F>> {
+        self.boundary
+            .iter()
+            .map(|(constraint, value)| BoundaryConstraint {
+                value: value.evaluate_with_challenges(&[], challenges),
+                ..constraint.clone()
+            })
+            .collect()
+    }
+}
+
+impl<F: FieldElement> AirBuilder<F> {
+    /// Add an auxiliary segment whose constraints may read verifier challenges
+    pub fn with_challenge_segment(mut self, segment: ChallengeSegment<F>) -> Self {
+        self.segments.push(segment);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{challenge, col, constant};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_permutation_written_with_challenges() {
+        // `down` counts down from `top` while `up` counts up from zero
+        let one = constant(PrimeField64::one());
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_column("one")
+            .with_column("up")
+            .with_column("down")
+            .with_transition(col("one").next() - col("one"))
+            .with_transition(col("up").next() - col("up") - col("one"))
+            .with_transition(col("down").next() - col("down") + col("one"))
+            .with_initial("one", PrimeField64::one());
+        // Products before (`p`) and including (`q`) each row of `(α - up) / (α - down)`
+        let row = |p, q, up, down: Expr<PrimeField64>| {
+            q * (challenge(0) - down) - p * (challenge(0) - up)
+        };
+        let products = |main: &[Vec<PrimeField64>], challenges: &[PrimeField64]| {
+            let (mut before, mut including, mut product) = (Vec::new(), Vec::new(), PrimeField64::one());
+            for (&up, &down) in main[1].iter().zip(&main[2]) {
+                before.push(product);
+                let denominator = (challenges[0] - down).inverse().unwrap_or_else(PrimeField64::zero);
+                product = product * (challenges[0] - up) * denominator;
+                including.push(product);
+            }
+            vec![before, including]
+        };
+        let segment = ChallengeSegment::new(&["p", "q"], 1, products)
+            .with_transition(row(col("p"), col("q"), col("up"), col("down")))
+            .with_transition(row(col("p").next(), col("q").next(), col("up").next(), col("down").next()))
+            .with_transition(col("p").next() - col("q"))
+            .with_initial("p", one.clone())
+            .with_final("q", one);
+        let air = builder.clone().with_challenge_segment(segment.clone()).build().unwrap();
+        assert_eq!(air.aux_segments[0].shape().num_challenges, 1);
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let init = |top: u64| [1, 0, top].map(PrimeField64::new);
+        let witness = prover.generate_trace(&air, &init(15), 16).unwrap();
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // Shifted values are not a permutation
+        let witness = prover.generate_trace(&air, &init(16), 16).unwrap();
+        assert!(prover.prove(&air, &PublicInputs::new(), &witness).is_err());
+        let unchecked = prover.clone().with_trace_check(false);
+        let proof = unchecked.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+
+        // Challenges are out of reach of main transitions and beyond the segment's own
+        let main = builder.clone().with_transition(col("up") * challenge(0));
+        assert!(matches!(main.build(), Err(AirError::InvalidConstraint(_))));
+        let beyond = segment.with_transition(col("p") - challenge(1));
+        assert!(builder.with_challenge_segment(beyond).build().is_err());
+    }
+}
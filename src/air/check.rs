@@ -0,0 +1,299 @@
+//! Witness Checking
+//!
+//! A witness that violates the AIR cannot be proven: the prover rejects it
+//! before committing to anything. [`Air::check_trace`] runs the same check
+//! on its own and names the failing constraint, the row and the value the
+//! constraint evaluated to, so that a faulty trace generator can be debugged
+//! without the prover. [`Air::describe_violation`] refers to the registers
+//! of an AIR by their names. For AIRs built from expressions,
+//! [`Air::explain_violation`] also shows the violated constraint as written
+//! and the trace values it read.
+
+use crate::prelude::*;
+use crate::air::{Air, ConstraintType};
+use crate::proof::composition::CompositionConstraints;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+use core::fmt::{Display, Formatter};
+
+/// Constraint violated by an execution trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintViolation<F: FieldElement> {
+    /// The AIR cannot be instantiated for the trace, or the trace does not have its shape
+    Malformed(String),
+    /// A transition constraint is nonzero between `row` and `row + 1`
+    Transition {
+        /// Transition constraint index: main registers first, then the
+        /// constraints of each auxiliary segment in order
+        index: usize,
+        /// First row of the transition
+        row: usize,
+        /// Value the constraint evaluated to
+        value: F,
+    },
+    /// A boundary constraint does not hold
+    Boundary {
+        /// Boundary constraint index: the AIR's boundary constraints first,
+        /// then the assertions of the public inputs
+        index: usize,
+        /// Asserted register
+        register: usize,
+        /// Asserted row
+        row: usize,
+        /// Trace value minus the asserted value
+        value: F,
+    },
+}
+
+impl<F: FieldElement> ConstraintViolation<F> {
+    /// Type of the violated constraint, unless the trace is malformed
+    pub fn constraint_type(&self) -> Option<ConstraintType> {
+        match self {
+            ConstraintViolation::Malformed(_) => None,
+            ConstraintViolation::Transition { .. } => Some(ConstraintType::Transition),
+            ConstraintViolation::Boundary { .. } => Some(ConstraintType::Boundary),
+        }
+    }
+
+    /// Row of the violation, unless the trace is malformed
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            ConstraintViolation::Malformed(_) => None,
+            ConstraintViolation::Transition { row, .. } | ConstraintViolation::Boundary { row, .. } => Some(*row),
+        }
+    }
+}
+
+impl<F: FieldElement> Display for ConstraintViolation<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConstraintViolation::Malformed(reason) => write!(f, "malformed trace: {}", reason),
+            ConstraintViolation::Transition { index, row, value } => {
+                write!(f, "transition constraint {} violated at row {}: evaluates to {}", index, row, value)
+            }
+            ConstraintViolation::Boundary {
+                index,
+                register,
+                row,
+                value,
+            } => write!(
+                f,
+                "boundary constraint {} violated on register {} at row {}: off by {}",
+                index, register, row, value
+            ),
+        }
+    }
+}
+
+impl<F: FieldElement> core::error::Error for ConstraintViolation<F> {}
+
+impl<F: FieldElement> Air<F> {
+    /// Check a trace against the AIR, reporting the first violation
+    ///
+    /// Transitions are checked row by row before the boundary constraints,
+    /// in the order the prover checks them.
+    pub fn check_trace(&self, trace: &ExecutionTrace<F>) -> Result<(), ConstraintViolation<F>> {
+        match self.collect_violations(trace, 1).into_iter().next() {
+            None => Ok(()),
+            Some(violation) => Err(violation),
+        }
+    }
+
+    /// Every constraint violation of a trace, in the order of [`check_trace`](Self::check_trace)
+    pub fn trace_violations(&self, trace: &ExecutionTrace<F>) -> Vec<ConstraintViolation<F>> {
+        self.collect_violations(trace, usize::MAX)
+    }
+
+    /// Describe a violation with the register names of the AIR
+    ///
+    /// The transitions of main registers and boundary constraints are named
+    /// after their register, as in ``transition of `acc` violated at row 3``.
+    /// Without register names this is the violation's [`Display`] form.
+    pub fn describe_violation(&self, violation: &ConstraintViolation<F>) -> String {
+        match *violation {
+            ConstraintViolation::Transition { index, row, value } => match self.register_name(index) {
+                Some(name) => format!("transition of `{}` violated at row {}: evaluates to {}", name, row, value),
+                None => violation.to_string(),
+            },
+            ConstraintViolation::Boundary {
+                index,
+                register,
+                row,
+                value,
+            } => match self.register_name(register) {
+                Some(name) => format!(
+                    "boundary constraint {} violated on `{}` at row {}: off by {}",
+                    index, name, row, value
+                ),
+                None => violation.to_string(),
+            },
+            ConstraintViolation::Malformed(_) => violation.to_string(),
+        }
+    }
+
+    /// Describe a violation of `trace` for debugging
+    ///
+    /// For an AIR built with [`AirBuilder`](crate::air::builder::AirBuilder),
+    /// a violated transition is followed by the expression it was written as
+    /// and the value of every column the expression reads, one per line, and
+    /// a violated boundary constraint by the column name and its value.
+    /// Otherwise this is the violation's [`Display`] form.
+    pub fn explain_violation(&self, trace: &ExecutionTrace<F>, violation: &ConstraintViolation<F>) -> String {
+        let mut explanation = violation.to_string();
+        let Some(symbolic) = &self.symbolic else {
+            return explanation;
+        };
+        let value = |register: usize, row: usize| {
+            (register < trace.columns.len() && row < trace.columns.column_len(register))
+                .then(|| trace.columns.value(register, row).as_canonical_u64())
+        };
+        match *violation {
+            ConstraintViolation::Transition { index, row, .. } => {
+                let Some(expression) = symbolic.transition(index) else {
+                    return explanation;
+                };
+                explanation.push_str(&format!("\n  {} = 0", expression));
+                for (name, offset) in expression.reads() {
+                    let read = symbolic.register(name).and_then(|register| value(register, row + offset));
+                    if let Some(read) = read {
+                        let column = format!("{}{}", name, "'".repeat(offset));
+                        explanation.push_str(&format!("\n  {} = {} at row {}", column, read, row + offset));
+                    }
+                }
+            }
+            ConstraintViolation::Boundary { register, row, .. } => {
+                let name = self.register_name(register).or(symbolic.columns().get(register).map(String::as_str));
+                if let (Some(name), Some(read)) = (name, value(register, row)) {
+                    explanation.push_str(&format!("\n  {} = {} at row {}", name, read, row));
+                }
+            }
+            ConstraintViolation::Malformed(_) => {}
+        }
+        explanation
+    }
+
+    /// Up to `limit` violations of a trace
+    fn collect_violations(&self, trace: &ExecutionTrace<F>, limit: usize) -> Vec<ConstraintViolation<F>> {
+        CompositionConstraints::from_air(self, trace.length)
+            .and_then(|constraints| constraints.violations(trace, limit))
+            .unwrap_or_else(|error| vec![ConstraintViolation::Malformed(error.to_string())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::air::{BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver};
+    use crate::types::field::PrimeField64;
+    use crate::types::StarkComponent;
+
+    #[test]
+    fn test_check_trace_names_the_violation() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let mut trace = prover
+            .generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 16)
+            .unwrap();
+        assert_eq!(air.check_trace(&trace), Ok(()));
+        assert!(air.trace_violations(&trace).is_empty());
+
+        // Changing a value breaks the transitions into and out of its row
+        let original = trace.columns.value(1, 5);
+        trace.columns.set(1, 5, original + PrimeField64::new(3));
+        let violation = air.check_trace(&trace).unwrap_err();
+        assert_eq!(
+            violation,
+            ConstraintViolation::Transition {
+                index: 1,
+                row: 4,
+                value: PrimeField64::new(3)
+            }
+        );
+        assert_eq!(violation.constraint_type(), Some(ConstraintType::Transition));
+        let rows: Vec<_> = air.trace_violations(&trace).iter().filter_map(ConstraintViolation::row).collect();
+        assert_eq!(rows, vec![4, 5, 5]);
+
+        // The prover reports the same violation, unless the check is disabled
+        let error = prover.prove(&air, &PublicInputs::new(), &trace).unwrap_err();
+        assert!(matches!(error, ProofError::ConstraintError(ref message) if *message == violation.to_string()));
+        assert!(prover.with_trace_check(false).prove(&air, &PublicInputs::new(), &trace).is_ok());
+
+        trace.columns.set(1, 5, original);
+        trace.columns.set(0, 0, PrimeField64::new(2));
+        assert!(matches!(
+            air.trace_violations(&trace).last(),
+            Some(ConstraintViolation::Boundary { index: 0, register: 0, row: 0, .. })
+        ));
+        trace.columns.truncate(1);
+        assert!(matches!(air.check_trace(&trace), Err(ConstraintViolation::Malformed(_))));
+    }
+
+    #[test]
+    fn test_explain_violation_shows_the_expression() {
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("acc")
+            .with_column("step")
+            .with_transition(col("acc").next() - col("acc") - col("step"))
+            .with_transition(col("step").next() - col("step"))
+            .with_initial("acc", PrimeField64::new(0))
+            .build()
+            .unwrap();
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let mut trace = prover.generate_trace(&air, &[PrimeField64::new(0), PrimeField64::new(3)], 16).unwrap();
+        trace.columns.set(0, 3, PrimeField64::new(10));
+
+        let violation = air.check_trace(&trace).unwrap_err();
+        assert_eq!(
+            air.explain_violation(&trace, &violation),
+            format!(
+                "{}\n  ((acc' - acc) - step) = 0\n  acc' = 10 at row 3\n  acc = 6 at row 2\n  step = 3 at row 2",
+                violation
+            )
+        );
+        let boundary = ConstraintViolation::Boundary {
+            index: 0,
+            register: 0,
+            row: 3,
+            value: PrimeField64::new(1),
+        };
+        assert!(air.explain_violation(&trace, &boundary).ends_with("\n  acc = 10 at row 3"));
+
+        // Hand-written AIRs have no expressions to show
+        let mut plain = air.clone();
+        plain.symbolic = None;
+        assert_eq!(plain.explain_violation(&trace, &violation), violation.to_string());
+    }
+
+    #[test]
+    fn test_describe_violation_uses_register_names() {
+        let unnamed = Air::new(vec![], TransitionFunction::fibonacci(), BoundaryConditions::empty(), 128);
+        let air = unnamed.clone().with_register_names(&["a", "b"]);
+        assert_eq!(air.register("b"), Some(1));
+        assert_eq!(air.to_string(), "AIR(security=128, constraints=0, registers=[a, b], max_degree=1)");
+        assert!(air.validate().is_ok());
+        assert!(unnamed.clone().with_register_names(&["a"]).validate().is_err());
+
+        // Names are serialized with the AIR but do not change its digest
+        assert_eq!(air.digest(), unnamed.digest());
+        assert_eq!(Air::from_bytes(&air.to_bytes()).unwrap(), air);
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let mut trace = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 16).unwrap();
+        let original = trace.columns.value(1, 5);
+        trace.columns.set(1, 5, original + PrimeField64::new(3));
+        let violation = air.check_trace(&trace).unwrap_err();
+        assert_eq!(
+            air.describe_violation(&violation),
+            format!("transition of `b` violated at row 4: evaluates to {}", PrimeField64::new(3))
+        );
+        assert_eq!(unnamed.describe_violation(&violation), violation.to_string());
+    }
+}
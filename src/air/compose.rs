@@ -0,0 +1,390 @@
+//! AIR Composition
+//!
+//! Applications are usually assembled from several pieces: a hash gadget, a
+//! range check and some custom logic, each already written as an AIR with
+//! its own registers. [`Air::compose`] lays such sub-AIRs out side by side
+//! in one trace instead of merging their constraint lists by hand.
+//!
+//! Every register of a sub-AIR gets its own column of the composed trace,
+//! after the columns of the sub-AIRs before it, unless it is shared with a
+//! column of an earlier sub-AIR. The AIR model has no periodic columns;
+//! values that several pieces read, such as a constant or a counter, live in
+//! one shared column instead. A shared register must follow the same
+//! transition as the column it maps to, since each column has a single
+//! transition. The transition matrices are placed on the diagonal, boundary
+//! constraints and auxiliary segments are moved to the composed columns, and
+//! identical boundary constraints are merged.
+
+use crate::prelude::*;
+use crate::air::{
+    Air, AirError, AuxFrame, AuxSegment, AuxTraceSegment, AuxWindow, BoundaryConditions, BoundaryConstraint,
+    FixedColumn, TransitionFunction,
+};
+use crate::proof::public_inputs::PublicInputCell;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+
+/// AIR placed next to others in a composed trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubAir<F: FieldElement> {
+    /// Constraints of the piece over its own registers
+    air: Air<F>,
+    /// Registers mapped onto columns of earlier sub-AIRs, as `(register, column)`
+    shared: Vec<(usize, usize)>,
+}
+
+impl<F: FieldElement> SubAir<F> {
+    /// Sub-AIR with a fresh column for every register
+    pub fn new(air: Air<F>) -> Self {
+        Self { air, shared: Vec::new() }
+    }
+
+    /// Map `register` onto the composed `column`, which an earlier sub-AIR owns
+    pub fn with_shared_column(mut self, register: usize, column: usize) -> Self {
+        self.shared.push((register, column));
+        self
+    }
+
+    /// Constraints of the piece
+    pub fn air(&self) -> &Air<F> {
+        &self.air
+    }
+
+    /// Registers mapped onto columns of earlier sub-AIRs, as `(register, column)`
+    pub fn shared_columns(&self) -> &[(usize, usize)] {
+        &self.shared
+    }
+
+    /// Column a shared register maps to, if it is shared
+    fn shared_column(&self, register: usize) -> Option<usize> {
+        self.shared
+            .iter()
+            .find(|&&(shared, _)| shared == register)
+            .map(|&(_, column)| column)
+    }
+}
+
+/// Auxiliary segment of a sub-AIR reading its registers from composed columns
+#[derive(Debug, Clone)]
+struct RelocatedSegment<F: FieldElement> {
+    /// Segment of the sub-AIR
+    segment: AuxSegment<F>,
+    /// Composed column of every register of the sub-AIR
+    columns: Vec<usize>,
+}
+
+impl<F: FieldElement> RelocatedSegment<F> {
+    /// Registers of the sub-AIR in a composed row
+    fn registers(&self, row: &[F]) -> Vec<F> {
+        self.columns.iter().map(|&column| row[column]).collect()
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for RelocatedSegment<F> {
+    fn width(&self) -> usize {
+        self.segment.definition().width()
+    }
+
+    fn num_challenges(&self) -> usize {
+        self.segment.definition().num_challenges()
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.segment.definition().num_constraints()
+    }
+
+    fn build(&self, main: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>> {
+        let registers: Vec<Vec<F>> = self.columns.iter().map(|&column| main[column].clone()).collect();
+        self.segment.definition().build(&registers, challenges)
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, challenges: &[F]) -> Vec<F> {
+        let main_current = self.registers(frame.main_current);
+        let main_next = self.registers(frame.main_next);
+        let frame = AuxFrame {
+            main_current: &main_current,
+            main_next: &main_next,
+            aux_current: frame.aux_current,
+            aux_next: frame.aux_next,
+        };
+        self.segment.definition().evaluate_transition(&frame, challenges)
+    }
+
+    fn window(&self) -> usize {
+        self.segment.definition().window()
+    }
+
+    fn evaluate_window(&self, window: &AuxWindow<'_, F>, challenges: &[F]) -> Vec<F> {
+        let main: Vec<Vec<F>> = window.main.iter().map(|row| self.registers(row)).collect();
+        let main: Vec<&[F]> = main.iter().map(Vec::as_slice).collect();
+        let window = AuxWindow {
+            main: &main,
+            aux: window.aux,
+        };
+        self.segment.definition().evaluate_window(&window, challenges)
+    }
+
+    fn boundary(&self, challenges: &[F]) -> Vec<BoundaryConstraint<F>> {
+        self.segment.definition().boundary(challenges)
+    }
+}
+
+impl<F: FieldElement + 'static> Air<F> {
+    /// Lay sub-AIRs out side by side in one trace
+    ///
+    /// The composed AIR takes the largest security parameter, extension
+    /// degree and constraint degree of its parts, and the public input cells
+    /// of the parts in order. If every part names its registers, each column
+    /// takes the name it has in the first part using it. Fails if a shared
+    /// register does not map to an earlier column with the same transition
+    /// and fixed values, or if two boundary constraints assert different
+    /// values at the same place.
+    pub fn compose(parts: &[SubAir<F>]) -> Result<Self, AirError> {
+        let layout = Self::compose_layout(parts)?;
+        let width = layout.iter().flatten().max().map_or(0, |&column| column + 1);
+        let mut coefficients = vec![vec![F::zero(); width]; width];
+        let mut owned = vec![false; width];
+        let mut free = vec![false; width];
+        let mut fixed: Vec<FixedColumn<F>> = Vec::new();
+        let mut names: Vec<Option<String>> = vec![None; width];
+        let mut constraints = Vec::new();
+        let mut boundary: Vec<BoundaryConstraint<F>> = Vec::new();
+        let mut aux_segments = Vec::new();
+        let mut public_inputs = Vec::new();
+
+        for (part, columns) in parts.iter().zip(&layout) {
+            for (register, row) in part.air.transition.coefficients.iter().enumerate() {
+                let mut composed = vec![F::zero(); width];
+                for (&coefficient, &column) in row.iter().zip(columns) {
+                    composed[column] += coefficient;
+                }
+                let column = columns[register];
+                let is_free = part.air.free_registers.contains(&register);
+                let fixed_values = part.air.fixed_columns.iter().find(|fixed| fixed.register() == register);
+                let fixed_values = fixed_values.map(|fixed| fixed.relocated(column));
+                if owned[column] {
+                    let existing = fixed.iter().find(|fixed| fixed.register() == column);
+                    let same_fixed = existing == fixed_values.as_ref();
+                    if coefficients[column] != composed || free[column] != is_free || !same_fixed {
+                        return Err(AirError::InvalidTransition(format!(
+                            "shared register {} does not follow the transition of column {}",
+                            register, column
+                        )));
+                    }
+                } else {
+                    coefficients[column] = composed;
+                    owned[column] = true;
+                    free[column] = is_free;
+                    fixed.extend(fixed_values);
+                    names[column] = part.air.register_name(register).map(str::to_string);
+                }
+            }
+
+            for constraint in &part.air.boundary.constraints {
+                let mut constraint = constraint.clone();
+                constraint.register = columns[constraint.register];
+                if let Some(existing) = boundary.iter().find(|existing| {
+                    existing.register == constraint.register
+                        && existing.step == constraint.step
+                        && existing.constraint_type == constraint.constraint_type
+                }) {
+                    if existing.value != constraint.value {
+                        return Err(AirError::InvalidBoundary(format!(
+                            "conflicting boundary constraints on column {}",
+                            constraint.register
+                        )));
+                    }
+                } else {
+                    boundary.push(constraint);
+                }
+            }
+
+            public_inputs.extend(part.air.public_inputs.iter().map(|cell| match *cell {
+                PublicInputCell::At { register, row } => PublicInputCell::at(columns[register], row),
+                PublicInputCell::Final { register } => PublicInputCell::last(columns[register]),
+            }));
+            constraints.extend(part.air.constraints.iter().cloned());
+            aux_segments.extend(part.air.aux_segments.iter().map(|segment| {
+                AuxSegment::new(RelocatedSegment {
+                    segment: segment.clone(),
+                    columns: columns.clone(),
+                })
+            }));
+        }
+
+        let degree = parts.iter().map(|part| part.air.transition.degree).max().unwrap_or(1);
+        let security_parameter = parts.iter().map(|part| part.air.security_parameter).max().unwrap_or(0);
+        let mut air = Air::new(
+            constraints,
+            TransitionFunction::new(coefficients, degree),
+            BoundaryConditions::new(boundary),
+            security_parameter,
+        );
+        air.field_extension_degree = parts.iter().map(|part| part.air.field_extension_degree).max().unwrap_or(1);
+        air.max_constraint_degree = parts
+            .iter()
+            .map(|part| part.air.max_constraint_degree)
+            .fold(air.max_constraint_degree, usize::max);
+        air.aux_segments = aux_segments;
+        air.public_inputs = public_inputs;
+        air.free_registers = (0..width).filter(|&column| free[column]).collect();
+        air.fixed_columns = fixed;
+        air.register_names = names.into_iter().collect::<Option<Vec<_>>>().unwrap_or_default();
+        Ok(air)
+    }
+
+    /// Composed column of every register of every sub-AIR
+    pub fn compose_layout(parts: &[SubAir<F>]) -> Result<Vec<Vec<usize>>, AirError> {
+        let mut next = 0;
+        let mut layout = Vec::with_capacity(parts.len());
+        for part in parts {
+            let registers = part.air.num_registers();
+            let mut columns = Vec::with_capacity(registers);
+            for register in 0..registers {
+                match part.shared_column(register) {
+                    Some(column) if column < next => columns.push(column),
+                    Some(column) => {
+                        return Err(AirError::InvalidConstraint(format!(
+                            "register {} is shared with column {}, which no earlier sub-AIR owns",
+                            register, column
+                        )))
+                    }
+                    None => {
+                        columns.push(next);
+                        next += 1;
+                    }
+                }
+            }
+            if let Some(&(register, _)) = part.shared.iter().find(|&&(register, _)| register >= registers) {
+                return Err(AirError::InvalidConstraint(format!(
+                    "shared register {} of a sub-AIR with {} registers",
+                    register, registers
+                )));
+            }
+            layout.push(columns);
+        }
+        Ok(layout)
+    }
+
+    /// Composed trace from one trace per sub-AIR
+    ///
+    /// The traces must have the same length and agree on shared columns.
+    pub fn compose_traces(parts: &[SubAir<F>], traces: &[ExecutionTrace<F>]) -> Result<ExecutionTrace<F>, AirError> {
+        let layout = Self::compose_layout(parts)?;
+        let length = traces.first().map_or(0, |trace| trace.length);
+        if traces.len() != parts.len() || traces.iter().any(|trace| trace.length != length) {
+            return Err(AirError::InvalidTransition(
+                "composition needs one trace of the same length per sub-AIR".to_string(),
+            ));
+        }
+
+        let width = layout.iter().flatten().max().map_or(0, |&column| column + 1);
+        let mut columns: Vec<Option<Vec<F>>> = vec![None; width];
+        for (trace, registers) in traces.iter().zip(&layout) {
+            for (values, &column) in trace.columns.to_columns().into_iter().zip(registers) {
+                match &columns[column] {
+                    Some(existing) if *existing != values => {
+                        return Err(AirError::InvalidTransition(format!(
+                            "sub-AIR traces disagree on shared column {}",
+                            column
+                        )))
+                    }
+                    Some(_) => {}
+                    None => columns[column] = Some(values),
+                }
+            }
+        }
+
+        let columns: Vec<Vec<F>> = columns
+            .into_iter()
+            .map(|column| column.unwrap_or_else(|| vec![F::zero(); length]))
+            .collect();
+        Ok(ExecutionTrace {
+            columns: columns.into(),
+            length,
+            num_registers: width,
+            aux_segments: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::examples::FibonacciAir;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_compose_sub_airs() {
+        let value = PrimeField64::new;
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+
+        // Constants `(x, y)` with `x` range checked, a Fibonacci sequence,
+        // and a range check of `y` through a shared column
+        let constants = Air::new(
+            vec![],
+            TransitionFunction::identity(2),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(1, value(200))]),
+            128,
+        )
+        .with_range_check(0, 8)
+        .unwrap();
+        let fibonacci = FibonacciAir::<PrimeField64>::new(16).unwrap();
+        let checked = Air::new(vec![], TransitionFunction::identity(1), BoundaryConditions::empty(), 96)
+            .with_range_check(0, 8)
+            .unwrap();
+        let parts = [
+            SubAir::new(constants.clone()),
+            SubAir::new(fibonacci.air()),
+            SubAir::new(checked.clone()).with_shared_column(0, 1),
+        ];
+        assert_eq!(Air::compose_layout(&parts).unwrap(), vec![vec![0, 1], vec![2, 3], vec![1]]);
+
+        let air = Air::compose(&parts).unwrap();
+        assert_eq!(air.num_registers(), 4);
+        assert_eq!(air.aux_segments.len(), 2);
+        assert_eq!(air.security_parameter, 128);
+        let traces = [
+            prover.generate_trace(&constants, &[value(7), value(200)], 16).unwrap(),
+            fibonacci.trace(),
+            prover.generate_trace(&checked, &[value(200)], 16).unwrap(),
+        ];
+        let trace = Air::compose_traces(&parts, &traces).unwrap();
+        assert_eq!(air.check_trace(&trace), Ok(()));
+
+        let public_inputs = PublicInputs::new().assert_final(3, fibonacci.result());
+        let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+        StarkVerifier::<PrimeField64>::new(48)
+            .verify_with_air(&air, &proof, &public_inputs)
+            .unwrap();
+
+        // The shared column is range checked by both pieces
+        let wide = [
+            prover.generate_trace(&constants, &[value(7), value(300)], 16).unwrap(),
+            fibonacci.trace(),
+            prover.generate_trace(&checked, &[value(300)], 16).unwrap(),
+        ];
+        let unbounded = Air::new(vec![], TransitionFunction::identity(2), BoundaryConditions::empty(), 128)
+            .with_range_check(0, 8)
+            .unwrap();
+        let unchecked = [SubAir::new(unbounded), parts[1].clone(), parts[2].clone()];
+        let trace = Air::compose_traces(&unchecked, &wide).unwrap();
+        assert!(prover.prove(&Air::compose(&unchecked).unwrap(), &PublicInputs::new(), &trace).is_err());
+
+        // Shared columns need matching transitions, values and boundaries
+        let mismatched = [SubAir::new(fibonacci.air()), SubAir::new(checked).with_shared_column(0, 1)];
+        assert!(Air::compose(&mismatched).is_err());
+        assert!(Air::compose_traces(&parts, &[traces[0].clone(), traces[1].clone(), wide[2].clone()]).is_err());
+        let conflicting = Air::new(
+            vec![],
+            TransitionFunction::identity(1),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, value(1))]),
+            128,
+        );
+        let conflict = [SubAir::new(constants), SubAir::new(conflicting).with_shared_column(0, 1)];
+        assert!(Air::compose(&conflict).is_err());
+        assert!(Air::compose_layout(&[SubAir::new(fibonacci.air()).with_shared_column(0, 0)]).is_err());
+    }
+}
@@ -3,14 +3,18 @@
 //! This module defines the algebraic constraints used in AIR (Algebraic Intermediate Representation)
 //! for STARK proofs. Constraints are polynomials that must evaluate to zero for valid computations.
 
+use crate::prelude::*;
+use crate::types::encoding::{decode_from_slice, encode_to_vec, ByteReader, ByteWriter, Encode};
 use crate::types::{FieldElement, StarkComponent, TypeError};
-use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use core::fmt::{Display, Formatter};
 
 /// Algebraic constraint for AIR
 /// 
 /// A constraint is a polynomial that must evaluate to zero for valid computations.
 /// Constraints can be of different types: transition, boundary, or algebraic.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Constraint<F: FieldElement> {
     /// Constraint polynomial coefficients
     pub polynomial: Vec<F>,
@@ -184,7 +188,7 @@ impl<F: FieldElement> Constraint<F> {
 }
 
 impl<F: FieldElement> Display for Constraint<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Constraint({:?}, degree={}, type={:?})",
@@ -194,9 +198,9 @@ impl<F: FieldElement> Display for Constraint<F> {
 }
 
 impl<F: FieldElement> StarkComponent<F> for Constraint<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.polynomial.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty polynomial".to_string()));
+            return Err(TypeError::EmptyComponent { name: "constraint polynomial" });
         }
 
         if self.degree >= self.polynomial.len() {
@@ -207,18 +211,45 @@ impl<F: FieldElement> StarkComponent<F> for Constraint<F> {
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
 
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> Constraint<F> {
+    /// Encode everything but the description
+    pub(crate) fn encode_statement(&self, writer: &mut ByteWriter) {
+        writer.write_fields(&self.polynomial);
+        writer.write_usize(self.degree);
+        self.constraint_type.encode(writer);
+    }
+
+    /// Decode a constraint encoded by [`encode_statement`](Self::encode_statement), without a description
+    pub(crate) fn decode_statement(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self::new(reader.read_fields()?, reader.read_usize()?, ConstraintType::decode(reader)?))
+    }
+}
+
+impl<F: FieldElement> Encode for Constraint<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        self.encode_statement(writer);
+        writer.write_string(&self.description);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        let constraint = Self::decode_statement(reader)?;
+        Ok(Self {
+            description: reader.read_string()?,
+            ..constraint
+        })
     }
 }
 
 /// Constraint type classification
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConstraintType {
     /// Transition constraint between states
     Transition,
@@ -229,7 +260,7 @@ pub enum ConstraintType {
 }
 
 impl Display for ConstraintType {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             ConstraintType::Transition => write!(f, "Transition"),
             ConstraintType::Boundary => write!(f, "Boundary"),
@@ -238,6 +269,25 @@ impl Display for ConstraintType {
     }
 }
 
+impl Encode for ConstraintType {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_u8(match self {
+            ConstraintType::Transition => 0,
+            ConstraintType::Boundary => 1,
+            ConstraintType::Algebraic => 2,
+        });
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        match reader.read_u8()? {
+            0 => Ok(ConstraintType::Transition),
+            1 => Ok(ConstraintType::Boundary),
+            2 => Ok(ConstraintType::Algebraic),
+            tag => Err(TypeError::UnknownTag { name: "constraint type", tag }),
+        }
+    }
+}
+
 /// Constraint system builder
 #[derive(Debug, Clone)]
 pub struct ConstraintSystemBuilder<F: FieldElement> {
@@ -2,6 +2,7 @@
 //! 
 //! This module provides efficient evaluation and verification of AIR constraints.
 
+use crate::prelude::*;
 use crate::types::FieldElement;
 
 /// Evaluate all constraints in an AIR system
@@ -0,0 +1,140 @@
+//! Example AIRs
+//!
+//! Complete statements that show how an AIR, its witness and its public
+//! inputs fit together, and serve as templates for application AIRs.
+
+use crate::prelude::*;
+use crate::air::{Air, AirError, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+use crate::proof::public_inputs::PublicInputs;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+
+/// Fibonacci sequence computed over a trace of two registers
+///
+/// Row `i` holds the consecutive terms `(a, b)` of the sequence and the next
+/// row holds `(b, a + b)`. The AIR fixes the first two terms, the seed, with
+/// boundary constraints; the claimed result, register `1` on the last row,
+/// is a public input, so a proof shows that the sequence starting at the
+/// seed reaches the result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FibonacciAir<F: FieldElement> {
+    /// First two terms of the sequence
+    seed: (F, F),
+    /// Number of trace rows
+    trace_length: usize,
+    /// Security parameter in bits
+    security_parameter: u32,
+}
+
+impl<F: FieldElement> FibonacciAir<F> {
+    /// Register holding the later of the two terms of a row
+    pub const RESULT_REGISTER: usize = 1;
+
+    /// Fibonacci sequence starting at `1, 1` over `trace_length` rows
+    pub fn new(trace_length: usize) -> Result<Self, AirError> {
+        if trace_length < 2 {
+            return Err(AirError::InvalidTransition(format!(
+                "Fibonacci trace needs at least 2 rows, got {}",
+                trace_length
+            )));
+        }
+        Ok(Self {
+            seed: (F::one(), F::one()),
+            trace_length,
+            security_parameter: 128,
+        })
+    }
+
+    /// Start the sequence at `first, second` instead
+    pub fn with_seed(mut self, first: F, second: F) -> Self {
+        self.seed = (first, second);
+        self
+    }
+
+    /// Use a different security parameter
+    pub fn with_security_parameter(mut self, security_parameter: u32) -> Self {
+        self.security_parameter = security_parameter;
+        self
+    }
+
+    /// Number of trace rows
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// AIR constraining the transitions and the seed
+    pub fn air(&self) -> Air<F> {
+        Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![
+                BoundaryConstraint::initial(0, self.seed.0),
+                BoundaryConstraint::initial(1, self.seed.1),
+            ]),
+            self.security_parameter,
+        )
+    }
+
+    /// Witness trace of the sequence
+    pub fn trace(&self) -> ExecutionTrace<F> {
+        let mut first = Vec::with_capacity(self.trace_length);
+        let mut second = Vec::with_capacity(self.trace_length);
+        let (mut a, mut b) = self.seed;
+        for _ in 0..self.trace_length {
+            first.push(a);
+            second.push(b);
+            (a, b) = (b, a + b);
+        }
+        ExecutionTrace {
+            columns: vec![first, second].into(),
+            length: self.trace_length,
+            num_registers: 2,
+            aux_segments: Vec::new(),
+        }
+    }
+
+    /// Term of the sequence in the result register on the last row
+    pub fn result(&self) -> F {
+        let (mut a, mut b) = self.seed;
+        for _ in 1..self.trace_length {
+            (a, b) = (b, a + b);
+        }
+        b
+    }
+
+    /// Public inputs claiming `result` as the outcome of the sequence
+    pub fn public_inputs(&self, result: F) -> PublicInputs<F> {
+        PublicInputs::new().assert_final(Self::RESULT_REGISTER, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_fibonacci_air_end_to_end() {
+        let example = FibonacciAir::<PrimeField64>::new(16).unwrap();
+        // F(17), counting F(1) = F(2) = 1
+        assert_eq!(example.result(), PrimeField64::new(1597));
+        let air = example.air();
+        let trace = example.trace();
+        assert_eq!(air.check_trace(&trace), Ok(()));
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let public_inputs = example.public_inputs(example.result());
+        let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+        verifier.verify(&proof, &public_inputs).unwrap();
+
+        // The proof is bound to the claimed result and the seed
+        let wrong_result = example.public_inputs(PrimeField64::new(1598));
+        assert!(verifier.verify(&proof, &wrong_result).is_err());
+        assert!(prover.prove(&air, &wrong_result, &trace).is_err());
+        let reseeded = example.clone().with_seed(PrimeField64::new(2), PrimeField64::new(1));
+        assert!(air.check_trace(&reseeded.trace()).is_err());
+        assert!(FibonacciAir::<PrimeField64>::new(1).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Preprocessed Columns
+//!
+//! Some columns hold values chosen with the AIR rather than by the prover,
+//! such as an instruction ROM or a lookup table. A [`FixedColumn`] pins a
+//! main register to such values: they are part of the AIR digest, and the
+//! verifier, which knows them, checks the out-of-domain frame against their
+//! interpolant instead of trusting the prover.
+//!
+//! Fixed registers are left free by the transition matrix. Their values are
+//! public, so in zero-knowledge mode they are padded with zeros rather than
+//! random rows, which keeps their interpolant computable by the verifier.
+
+use crate::prelude::*;
+use crate::air::AirError;
+use crate::types::encoding::{ByteReader, ByteWriter, Encode};
+use crate::types::{FieldElement, TypeError};
+use serde::{Deserialize, Serialize};
+
+/// Main register whose values are fixed by the AIR
+///
+/// Row `i` of the register holds `values[i % values.len()]`, so a table
+/// shorter than the trace repeats.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FixedColumn<F: FieldElement> {
+    /// Fixed register
+    register: usize,
+    /// Values of the register, repeated over the trace
+    values: Vec<F>,
+}
+
+impl<F: FieldElement> FixedColumn<F> {
+    /// Fix `register` to `values`
+    pub fn new(register: usize, values: &[F]) -> Result<Self, AirError> {
+        if values.is_empty() {
+            return Err(AirError::InvalidConstraint(format!(
+                "fixed column on register {} has no values",
+                register
+            )));
+        }
+        Ok(Self {
+            register,
+            values: values.to_vec(),
+        })
+    }
+
+    /// Fixed register
+    pub fn register(&self) -> usize {
+        self.register
+    }
+
+    /// Values of the register, repeated over the trace
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    /// Value of the register at `row`
+    pub fn value(&self, row: usize) -> F {
+        self.values[row % self.values.len()]
+    }
+
+    /// Values of the register over a trace of the given length
+    pub fn column(&self, length: usize) -> Vec<F> {
+        (0..length).map(|row| self.value(row)).collect()
+    }
+
+    /// The same column on another register
+    pub(crate) fn relocated(&self, register: usize) -> Self {
+        Self {
+            register,
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<F: FieldElement> Encode for FixedColumn<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.register);
+        writer.write_fields(&self.values);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        let register = reader.read_usize()?;
+        Self::new(register, &reader.read_fields()?).map_err(|e| TypeError::InvalidConversion(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_fixed_column_is_checked_by_the_verifier() {
+        let rom: Vec<PrimeField64> = [3, 1, 4, 1, 5].iter().map(|&value| PrimeField64::new(value)).collect();
+        let builder = AirBuilder::<PrimeField64>::new(128)
+            .with_fixed_column("rom", &rom)
+            .with_column("acc")
+            .with_transition(col("acc").next() - col("acc") - col("rom"));
+        let air = builder.build().unwrap();
+        assert_eq!(air.fixed_columns, vec![FixedColumn::new(0, &rom).unwrap()]);
+
+        // The witness reads the ROM wherever the initial state leaves it
+        let init = [PrimeField64::zero(), PrimeField64::zero()];
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &init, 16).unwrap();
+        assert_eq!(witness.columns.column(0)[..7], rom.iter().chain(&rom[..2]).copied().collect::<Vec<_>>()[..]);
+        assert_eq!(witness.columns.value(1, 5), PrimeField64::new(14));
+        let verifier = StarkVerifier::new(48);
+        for prover in [prover.clone(), prover.clone().with_zk(true)] {
+            let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+            verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+        }
+
+        // A trace with another ROM is rejected by the prover, and its proof by the verifier
+        let mut forged = witness.clone();
+        forged.columns.set(0, 2, PrimeField64::new(9));
+        for row in 3..16 {
+            let acc = forged.columns.value(1, row - 1) + forged.columns.value(0, row - 1);
+            forged.columns.set(1, row, acc);
+        }
+        assert!(prover.prove(&air, &PublicInputs::new(), &forged).is_err());
+        let prover = prover.with_zk(true).with_trace_check(false);
+        let proof = prover.prove(&air, &PublicInputs::new(), &forged).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+    }
+}
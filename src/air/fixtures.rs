@@ -0,0 +1,34 @@
+//! Test Fixtures
+//!
+//! AIRs and traces shared by tests in several modules, so that each test
+//! states only what it checks about them.
+
+use crate::air::builder::{col, AirBuilder};
+use crate::air::Air;
+use crate::proof::StarkProver;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+
+/// Fibonacci AIR over columns `a` and `b`, both starting at one
+///
+/// `a` takes the previous `b`, and `b` the sum of both.
+pub(crate) fn fibonacci_air<F: FieldElement + 'static>() -> Air<F> {
+    AirBuilder::<F>::new(128)
+        .with_column("a")
+        .with_column("b")
+        .with_transition(col("a").next() - col("b"))
+        .with_transition(col("b").next() - col("a") - col("b"))
+        .with_initial("a", F::one())
+        .with_initial("b", F::one())
+        .build()
+        .unwrap()
+}
+
+/// [`fibonacci_air`] with its trace of `num_steps` rows
+pub(crate) fn fibonacci<F: FieldElement + 'static>(num_steps: usize) -> (Air<F>, ExecutionTrace<F>) {
+    let air = fibonacci_air();
+    let trace = StarkProver::<F>::with_params(128, 8, 16, 1)
+        .generate_trace(&air, &[F::one(), F::one()], num_steps)
+        .unwrap();
+    (air, trace)
+}
@@ -0,0 +1,182 @@
+//! Building blocks shared by the algebraic hash gadgets
+
+use crate::prelude::*;
+use super::circuit::RowCircuit;
+use crate::air::AirError;
+use crate::types::FieldElement;
+use sha2::{Digest, Sha256};
+
+/// Factor of one product in a power chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Factor {
+    /// The chain input
+    Input,
+    /// An earlier product of the chain
+    Product(usize),
+}
+
+/// Products computing `x^e` from `x`, following the binary expansion of `e`
+///
+/// Every product but the last gets a column constrained to the product of
+/// its factors; the last product is folded into the constraint that consumes
+/// the power, so all constraints have degree two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct PowerChain {
+    /// Factors of each product
+    products: Vec<(Factor, Factor)>,
+}
+
+impl PowerChain {
+    /// Chain for an exponent of at least two
+    pub(super) fn new(exponent: u64) -> Self {
+        assert!(exponent >= 2, "power chain exponent");
+        let mut products = Vec::new();
+        let mut current = Factor::Input;
+        for bit in (0..u64::BITS - 1 - exponent.leading_zeros()).rev() {
+            products.push((current, current));
+            current = Factor::Product(products.len() - 1);
+            if (exponent >> bit) & 1 == 1 {
+                products.push((current, Factor::Input));
+                current = Factor::Product(products.len() - 1);
+            }
+        }
+        Self { products }
+    }
+
+    /// Run the chain on `input` and return the power as a degree-two expression
+    pub(super) fn apply<F: FieldElement>(&self, circuit: &mut RowCircuit<'_, F>, input: F) -> F {
+        let mut products: Vec<F> = Vec::with_capacity(self.products.len());
+        let (last, columns) = self.products.split_last().expect("chains have at least one product");
+        let factor = |products: &[F], factor: Factor| match factor {
+            Factor::Input => input,
+            Factor::Product(index) => products[index],
+        };
+        for &(left, right) in columns {
+            let product = factor(&products, left) * factor(&products, right);
+            products.push(circuit.define(product));
+        }
+        factor(&products, last.0) * factor(&products, last.1)
+    }
+}
+
+/// Smallest `α >= 3` coprime to `MODULUS - 1`, so that `x^α` permutes the field
+pub(super) fn sbox_exponent<F: FieldElement>() -> u64 {
+    (3..)
+        .find(|&alpha| gcd(alpha, F::MODULUS - 1) == 1)
+        .expect("an exponent coprime to the group order exists")
+}
+
+/// Inverse of `α` modulo `MODULUS - 1`, so that `x^(1/α)` undoes `x^α`
+pub(super) fn inverse_exponent<F: FieldElement>(alpha: u64) -> u64 {
+    let order = i128::from(F::MODULUS - 1);
+    let (mut r0, mut r1) = (order, i128::from(alpha));
+    let (mut t0, mut t1) = (0i128, 1i128);
+    while r1 != 0 {
+        let quotient = r0 / r1;
+        (r0, r1) = (r1, r0 - quotient * r1);
+        (t0, t1) = (t1, t0 - quotient * t1);
+    }
+    t0.rem_euclid(order) as u64
+}
+
+/// Cauchy matrix `1 / (i + width + j)`, which is MDS
+pub(super) fn cauchy_mds<F: FieldElement>(width: usize) -> Result<Vec<Vec<F>>, AirError> {
+    (0..width)
+        .map(|row| {
+            (0..width)
+                .map(|column| {
+                    F::from_canonical_u64((row + width + column) as u64)
+                        .inverse()
+                        .ok_or_else(|| AirError::InvalidConstraint(format!("MDS matrix of width {} too large", width)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// `rows` rows of `width` constants derived from SHA-256 of a domain separator and parameters
+pub(super) fn derive_constants<F: FieldElement>(
+    domain: &[u8],
+    parameters: &[u64],
+    rows: usize,
+    width: usize,
+) -> Vec<Vec<F>> {
+    (0..rows)
+        .map(|row| {
+            (0..width)
+                .map(|element| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(domain);
+                    for parameter in parameters {
+                        hasher.update(parameter.to_le_bytes());
+                    }
+                    hasher.update(((row * width + element) as u64).to_le_bytes());
+                    let digest = hasher.finalize();
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(&digest[..8]);
+                    F::from_canonical_u64(u64::from_le_bytes(bytes) % F::MODULUS)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Sponge hash over a permutation of `width` elements
+///
+/// The first state element is the capacity and starts at the number of
+/// elements; the others are the rate. Elements are added to the rate in
+/// chunks, permuting after each chunk, and the first rate element is the
+/// digest.
+pub(super) fn sponge_hash<F: FieldElement>(width: usize, elements: &[F], permute: impl Fn(&mut [F])) -> F {
+    let mut state = vec![F::zero(); width];
+    state[0] = F::from_canonical_u64(elements.len() as u64);
+    let mut chunks = elements.chunks(width - 1).peekable();
+    if chunks.peek().is_none() {
+        permute(&mut state);
+    }
+    for chunk in chunks {
+        for (element, &value) in state[1..].iter_mut().zip(chunk) {
+            *element += value;
+        }
+        permute(&mut state);
+    }
+    state[1]
+}
+
+/// [`sponge_hash`] on a row circuit, given the permutation on the circuit
+pub(super) fn sponge_hash_in<'a, F: FieldElement>(
+    circuit: &mut RowCircuit<'a, F>,
+    width: usize,
+    elements: &[F],
+    permute: impl Fn(&mut RowCircuit<'a, F>, Vec<F>) -> Vec<F>,
+) -> F {
+    let mut state = vec![F::zero(); width];
+    state[0] = F::from_canonical_u64(elements.len() as u64);
+    let mut chunks = elements.chunks(width - 1).peekable();
+    if chunks.peek().is_none() {
+        state = permute(circuit, state);
+    }
+    for chunk in chunks {
+        for (element, &value) in state[1..].iter_mut().zip(chunk) {
+            *element += value;
+        }
+        state = permute(circuit, state);
+    }
+    state[1]
+}
+
+/// Multiply a state by a matrix
+pub(super) fn mix<F: FieldElement>(matrix: &[Vec<F>], state: &[F]) -> Vec<F> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(state).fold(F::zero(), |sum, (&m, &value)| sum + m * value))
+        .collect()
+}
+
+/// Greatest common divisor
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
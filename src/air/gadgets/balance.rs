@@ -0,0 +1,102 @@
+//! Balance Checks
+//!
+//! Proves that amounts held in main trace columns balance: on every row the
+//! input columns add up to the same total as the output columns. With the
+//! amounts range checked, this is the conservation rule of a transfer
+//! between hidden amounts.
+
+use crate::prelude::*;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+
+/// Equality of the sums of two sets of main trace columns
+///
+/// The segment holds the total in a single column, constrained to equal the
+/// sum of the inputs and the sum of the outputs. Sums are taken in the field,
+/// so the amounts must be range checked to rule out wrapping around the
+/// modulus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balance {
+    /// Main columns holding the input amounts
+    inputs: Vec<usize>,
+    /// Main columns holding the output amounts
+    outputs: Vec<usize>,
+}
+
+impl Balance {
+    /// Check that the `inputs` columns add up to the `outputs` columns
+    pub fn new(inputs: &[usize], outputs: &[usize]) -> Result<Self, AirError> {
+        if inputs.is_empty() || outputs.is_empty() {
+            return Err(AirError::InvalidConstraint(
+                "a balance needs at least one input and one output".to_string(),
+            ));
+        }
+        Ok(Self {
+            inputs: inputs.to_vec(),
+            outputs: outputs.to_vec(),
+        })
+    }
+
+    /// Main columns holding the input amounts
+    pub fn inputs(&self) -> &[usize] {
+        &self.inputs
+    }
+
+    /// Main columns holding the output amounts
+    pub fn outputs(&self) -> &[usize] {
+        &self.outputs
+    }
+
+    /// Sum of some columns of a main row
+    fn sum<F: FieldElement>(main: &[F], columns: &[usize]) -> F {
+        columns
+            .iter()
+            .fold(F::zero(), |sum, &column| sum + main.get(column).copied().unwrap_or_else(F::zero))
+    }
+
+    /// Residuals of the constraints of one row
+    fn row_residuals<F: FieldElement>(&self, main: &[F], aux: &[F], residuals: &mut Vec<F>) {
+        let total = aux.first().copied().unwrap_or_else(F::zero);
+        residuals.push(total - Self::sum(main, &self.inputs));
+        residuals.push(total - Self::sum(main, &self.outputs));
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Balance {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        4
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let totals = (0..length)
+            .map(|row| {
+                let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+                Self::sum(&main_row, &self.inputs)
+            })
+            .collect();
+        vec![totals]
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(4);
+        self.row_residuals(frame.main_current, frame.aux_current, &mut residuals);
+        self.row_residuals(frame.main_next, frame.aux_next, &mut residuals);
+        residuals
+    }
+}
+
+/// Balance gadget proving that the `inputs` columns add up to the `outputs` columns
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment).
+pub fn balance<F: FieldElement>(inputs: &[usize], outputs: &[usize]) -> Result<AuxSegment<F>, AirError> {
+    Balance::new(inputs, outputs).map(AuxSegment::new)
+}
@@ -0,0 +1,161 @@
+//! Bit Decomposition
+//!
+//! Splits a column of an [`AirBuilder`] into bit columns that other
+//! constraints can read, as the base of shifts, comparisons and binary hash
+//! gadgets. Unlike [`RangeCheck`](super::RangeCheck), whose bits live in an
+//! auxiliary segment of their own, the bits become columns of the AIR.
+
+use crate::prelude::*;
+use crate::air::builder::{col, constant, AirBuilder, Expr};
+use crate::air::AirError;
+use crate::types::FieldElement;
+
+/// Decomposition of a column into bit columns, least significant first
+///
+/// Every bit column is free and constrained to zero or one, and the bits,
+/// weighted by their powers of two, add up to the decomposed column. Both
+/// constraints are checked on both rows of every transition, which covers
+/// every row. Traces fill the bit columns with [`values`](Self::values).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitDecomposition {
+    /// Decomposed column
+    column: String,
+    /// Number of bits
+    bits: u32,
+}
+
+impl BitDecomposition {
+    /// Decomposed column
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    /// Number of bits
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Name of the column of bit `index`, as `column.bit{index}`
+    pub fn bit_name(&self, index: u32) -> String {
+        format!("{}.bit{}", self.column, index)
+    }
+
+    /// Column of bit `index`, for use in other constraints
+    pub fn bit<F: FieldElement>(&self, index: u32) -> Expr<F> {
+        col(&self.bit_name(index))
+    }
+
+    /// Bits `from..bits`, weighted by their powers of two from one on
+    ///
+    /// This is the decomposed value shifted right by `from` bits; with
+    /// `from` zero it is the value itself.
+    pub fn shifted<F: FieldElement>(&self, from: u32) -> Expr<F> {
+        let two = F::from_canonical_u64(2);
+        let mut power = F::one();
+        let mut sum = constant(F::zero());
+        for index in from..self.bits {
+            sum = sum + constant(power) * self.bit(index);
+            power *= two;
+        }
+        sum.fold_constants()
+    }
+
+    /// Values of the bit columns for a value of the decomposed column
+    ///
+    /// Out-of-range values keep their low bits and fail the recomposition.
+    pub fn values<F: FieldElement>(&self, value: F) -> Vec<F> {
+        let value = value.as_canonical_u64();
+        (0..self.bits).map(|bit| F::from_canonical_u64((value >> bit) & 1)).collect()
+    }
+}
+
+/// Decompose `column` into `n_bits` bit columns
+///
+/// `2^n_bits` may not exceed the field modulus, so that the weighted bits
+/// cannot wrap around. Add the decomposition to a builder with
+/// [`AirBuilder::with_bit_decomposition`].
+pub fn bit_decompose<F: FieldElement>(column: &str, n_bits: u32) -> Result<BitDecomposition, AirError> {
+    if n_bits == 0 || n_bits >= u64::BITS || (1u64 << n_bits) > F::MODULUS {
+        return Err(AirError::InvalidConstraint(format!(
+            "cannot decompose {} bits in a field of modulus {}",
+            n_bits,
+            F::MODULUS
+        )));
+    }
+    Ok(BitDecomposition {
+        column: column.to_string(),
+        bits: n_bits,
+    })
+}
+
+impl<F: FieldElement> AirBuilder<F> {
+    /// Add the bit columns of a decomposition, after the existing columns, and their constraints
+    pub fn with_bit_decomposition(mut self, decomposition: &BitDecomposition) -> Self {
+        for index in 0..decomposition.bits {
+            let bit = decomposition.bit::<F>(index);
+            let booleanity = bit.clone() * (bit - constant(F::one()));
+            self = self
+                .with_free_column(&decomposition.bit_name(index))
+                .with_transition(booleanity.clone())
+                .with_transition(booleanity.next());
+        }
+        let recomposition = col(&decomposition.column) - decomposition.shifted(0);
+        self.with_transition(recomposition.clone()).with_transition(recomposition.next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::DynTransition;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_bit_decomposition() {
+        // A counter from `start`, and its value shifted right by two bits
+        let bits = bit_decompose::<PrimeField64>("x", 4).unwrap();
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("one")
+            .with_column("x")
+            .with_free_column("high")
+            .with_transition(col("one").next() - col("one"))
+            .with_transition(col("x").next() - col("x") - col("one"))
+            .with_bit_decomposition(&bits)
+            .with_transition(col("high") - bits.shifted(2))
+            .with_initial("one", PrimeField64::one())
+            .build()
+            .unwrap();
+        assert_eq!(air.register("x.bit3"), Some(6));
+        let decompose = bits.clone();
+        let step = DynTransition::new(move |state: &[PrimeField64]| {
+            let x = state[1] + state[0];
+            let mut next = vec![state[0], x, PrimeField64::new(x.as_canonical_u64() >> 2)];
+            next.extend(decompose.values(x));
+            next
+        });
+        let air = air.with_step(step);
+        let init = |start: u64| {
+            let mut init = vec![PrimeField64::one(), PrimeField64::new(start), PrimeField64::new(start >> 2)];
+            init.extend(bits.values(PrimeField64::new(start)));
+            init
+        };
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let witness = prover.generate_trace(&air, &init(0), 16).unwrap();
+        assert_eq!(witness.columns.value(2, 13), PrimeField64::new(3));
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // From one, the counter reaches 16 on the last row, which four bits cannot hold
+        let witness = prover.generate_trace(&air, &init(1), 16).unwrap();
+        assert!(prover.prove(&air, &PublicInputs::new(), &witness).is_err());
+        let proof = prover.with_trace_check(false).prove(&air, &PublicInputs::new(), &witness).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+
+        assert!(bit_decompose::<PrimeField64>("x", 0).is_err());
+        assert!(bit_decompose::<PrimeField64>("x", 64).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Row circuits shared by the gadgets
+//!
+//! A gadget describes the computation of one row once, against a
+//! [`RowCircuit`]. Building a segment runs it on concrete values and records
+//! every new column; evaluating the constraints runs it on a frame row, reads
+//! the columns back and records how far each one is from the value the
+//! computation expects. Both runs see the same sequence of columns, so the
+//! witness and the constraints cannot drift apart.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+
+/// What a run of a row circuit produces
+enum Mode<'a, F: FieldElement> {
+    /// Segment values of the row, in column order
+    Build(&'a mut Vec<F>),
+    /// Residuals of the constraints on a segment row
+    Check {
+        /// Segment row
+        aux: &'a [F],
+        /// Residuals
+        residuals: &'a mut Vec<F>,
+    },
+}
+
+/// Run of a gadget computation on one row
+pub(super) struct RowCircuit<'a, F: FieldElement> {
+    /// Build or check
+    mode: Mode<'a, F>,
+    /// Next segment column
+    column: usize,
+    /// Number of constraints so far
+    constraints: usize,
+}
+
+impl<'a, F: FieldElement> RowCircuit<'a, F> {
+    /// Run that appends the segment values of a row to `values`
+    pub(super) fn build(values: &'a mut Vec<F>) -> Self {
+        Self {
+            mode: Mode::Build(values),
+            column: 0,
+            constraints: 0,
+        }
+    }
+
+    /// Run that appends the constraint residuals of the segment row `aux`
+    pub(super) fn check(aux: &'a [F], residuals: &'a mut Vec<F>) -> Self {
+        Self {
+            mode: Mode::Check { aux, residuals },
+            column: 0,
+            constraints: 0,
+        }
+    }
+
+    /// Index of the next column
+    pub(super) fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Number of columns and constraints the run went through
+    pub(super) fn finish(self) -> (usize, usize) {
+        (self.column, self.constraints)
+    }
+
+    /// New column equal to `expected`, an expression of degree at most two
+    /// in earlier values
+    pub(super) fn define(&mut self, expected: F) -> F {
+        self.constraints += 1;
+        let index = self.column;
+        self.column += 1;
+        match &mut self.mode {
+            Mode::Build(values) => {
+                values.push(expected);
+                expected
+            }
+            Mode::Check { aux, residuals } => {
+                let value = aux.get(index).copied().unwrap_or_else(F::zero);
+                residuals.push(value - expected);
+                value
+            }
+        }
+    }
+
+    /// New column holding a witness, computed by `value` when building
+    ///
+    /// The column is unconstrained until the caller constrains it.
+    pub(super) fn witness(&mut self, value: impl FnOnce() -> F) -> F {
+        let index = self.column;
+        self.column += 1;
+        match &mut self.mode {
+            Mode::Build(values) => {
+                let value = value();
+                values.push(value);
+                value
+            }
+            Mode::Check { aux, .. } => aux.get(index).copied().unwrap_or_else(F::zero),
+        }
+    }
+
+    /// New witness column constrained to zero or one
+    pub(super) fn bit(&mut self, value: impl FnOnce() -> bool) -> F {
+        let bit = self.witness(|| if value() { F::one() } else { F::zero() });
+        self.assert_zero(bit * (bit - F::one()));
+        bit
+    }
+
+    /// Constrain an expression of degree at most two to vanish
+    pub(super) fn assert_zero(&mut self, residual: F) {
+        self.constraints += 1;
+        if let Mode::Check { residuals, .. } = &mut self.mode {
+            residuals.push(residual);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Read/Write Memory
+//!
+//! Proves that the memory accesses of a computation are consistent: every
+//! read of an address returns the value last written to it. The accesses,
+//! one per row, are given by address, value, timestamp and write-flag
+//! columns in execution order. The gadget adds a copy of them sorted by
+//! address, then timestamp, and proves with a permutation argument over two
+//! verifier challenges that the sorted rows permute the executed ones. On
+//! the sorted rows, consecutive accesses to the same address are easy to
+//! check:
+//!
+//! - a `changed` flag marks the rows where the address changes, and the
+//!   address stays the same on the other rows
+//! - a `gap` column holds the address increase minus one where it changes
+//!   and the timestamp increase minus one elsewhere; its bit decomposition
+//!   proves it nonnegative, so addresses increase and timestamps strictly
+//!   increase per address
+//! - a read of an unchanged address keeps the value of the access before it
+//!
+//! The first access to an address may read any value, as uninitialized
+//! memory. The sorted columns depend on the whole trace, so they are filled
+//! after the trace is generated, with [`MemoryArgument::fill_trace`].
+
+use crate::prelude::*;
+use super::bits::bit_decompose;
+use crate::air::builder::{challenge, col, constant, AirBuilder, ChallengeSegment, Expr};
+use crate::air::{Air, AirError};
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+
+/// Memory consistency argument over the access columns of an [`AirBuilder`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryArgument {
+    /// Address, value, timestamp and write-flag columns, in execution order
+    access: [String; 4],
+    /// Bits of the address and timestamp increases
+    bits: u32,
+}
+
+/// Memory argument over the given access columns
+///
+/// A row writes `value` to `address` if `write` is one and reads it if it is
+/// zero. Consecutive addresses, and consecutive timestamps of an address,
+/// must differ by at most `2^bits`.
+pub fn memory<F: FieldElement>(
+    address: &str,
+    value: &str,
+    timestamp: &str,
+    write: &str,
+    bits: u32,
+) -> Result<MemoryArgument, AirError> {
+    bit_decompose::<F>(address, bits)?;
+    Ok(MemoryArgument {
+        access: [address, value, timestamp, write].map(str::to_string),
+        bits,
+    })
+}
+
+impl MemoryArgument {
+    /// Name of the sorted copy of access column `index`
+    fn sorted(&self, index: usize) -> String {
+        format!("{}.sorted", self.access[index])
+    }
+
+    /// Name of a helper column of the argument
+    fn helper(&self, name: &str) -> String {
+        format!("{}.{}", self.access[0], name)
+    }
+
+    /// Names of the columns the argument adds, in the order it adds them
+    ///
+    /// The sorted address, value, timestamp and write flag, the `changed`
+    /// flag, the `keep` flag of reads of an unchanged address and the `gap`
+    /// column, followed by the bits of the gap.
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns: Vec<String> = (0..4).map(|index| self.sorted(index)).collect();
+        columns.extend(["changed", "keep", "gap"].map(|name| self.helper(name)));
+        columns.extend((0..self.bits).map(|bit| format!("{}.bit{}", self.helper("gap"), bit)));
+        columns
+    }
+
+    /// Fill the columns of the argument in a trace of an AIR built with it
+    ///
+    /// The access columns must already hold the executed accesses.
+    pub fn fill_trace<F: FieldElement>(&self, air: &Air<F>, trace: &mut ExecutionTrace<F>) -> Result<(), AirError> {
+        let register = |name: &str| {
+            air.register(name)
+                .ok_or_else(|| AirError::InvalidConstraint(format!("Unknown column `{}`", name)))
+        };
+        let access = self.access.iter().map(|name| register(name)).collect::<Result<Vec<_>, _>>()?;
+        let added = self.columns().iter().map(|name| register(name)).collect::<Result<Vec<_>, _>>()?;
+        let mut rows: Vec<[F; 4]> = (0..trace.length)
+            .map(|row| [0, 1, 2, 3].map(|index| trace.columns.value(access[index], row)))
+            .collect();
+        rows.sort_by_key(|row| (row[0].as_canonical_u64(), row[2].as_canonical_u64()));
+
+        let gap = bit_decompose::<F>(&self.helper("gap"), self.bits)?;
+        for (index, row) in rows.iter().enumerate() {
+            let (changed, keep, difference) = match index.checked_sub(1).map(|previous| rows[previous]) {
+                None => (F::zero(), F::zero(), F::zero()),
+                Some(previous) if previous[0] != row[0] => (F::one(), F::zero(), row[0] - previous[0] - F::one()),
+                Some(previous) => (F::zero(), F::one() - row[3], row[2] - previous[2] - F::one()),
+            };
+            let values = row.iter().copied().chain([changed, keep, difference]).chain(gap.values(difference));
+            for (&register, value) in added.iter().zip(values) {
+                trace.columns.set(register, index, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Permutation argument between the executed and the sorted accesses
+    ///
+    /// Products before (`p`) and including (`q`) each row of
+    /// `(α - executed) / (α - sorted)`, each access compressed with `β`.
+    fn permutation<F: FieldElement>(&self, columns: &[String]) -> ChallengeSegment<F> {
+        let position = |name: &String| columns.iter().position(|column| column == name);
+        let executed_registers: Vec<Option<usize>> = self.access.iter().map(position).collect();
+        let sorted_registers: Vec<Option<usize>> = (0..4).map(|index| position(&self.sorted(index))).collect();
+        let compress_row = |registers: &[Option<usize>], main: &[Vec<F>], row: usize, challenges: &[F]| {
+            let value = |register: Option<usize>| register.map_or_else(F::zero, |register| main[register][row]);
+            let combination = registers
+                .iter()
+                .rev()
+                .fold(F::zero(), |sum, &register| sum * challenges[1] + value(register));
+            challenges[0] - combination
+        };
+        let build = move |main: &[Vec<F>], challenges: &[F]| {
+            let length = main.first().map_or(0, Vec::len);
+            let (mut before, mut including, mut product) = (Vec::new(), Vec::new(), F::one());
+            for row in 0..length {
+                before.push(product);
+                // A zero denominator has no inverse; its row then fails the constraints
+                let sorted = compress_row(&sorted_registers, main, row, challenges);
+                let executed = compress_row(&executed_registers, main, row, challenges);
+                product = product * executed * sorted.inverse().unwrap_or_else(F::zero);
+                including.push(product);
+            }
+            vec![before, including]
+        };
+        let compress = |names: Vec<String>| {
+            let combination = names.iter().rev().fold(constant(F::zero()), |sum, name| sum * challenge(1) + col(name));
+            challenge(0) - combination.fold_constants()
+        };
+        let (p, q) = (self.helper("before"), self.helper("including"));
+        let residual = |p: Expr<F>, q: Expr<F>, executed: Expr<F>, sorted: Expr<F>| q * sorted - p * executed;
+        let executed = compress(self.access.to_vec());
+        let sorted = compress((0..4).map(|index| self.sorted(index)).collect());
+        ChallengeSegment::new(&[&p, &q], 2, build)
+            .with_transition(residual(col(&p), col(&q), executed.clone(), sorted.clone()))
+            .with_transition(residual(col(&p).next(), col(&q).next(), executed.next(), sorted.next()))
+            .with_transition(col(&p).next() - col(&q))
+            .with_initial(&p, constant(F::one()))
+            .with_final(&q, constant(F::one()))
+    }
+}
+
+impl<F: FieldElement> AirBuilder<F> {
+    /// Add a memory consistency argument: its columns, after the existing ones, and its constraints
+    pub fn with_memory(mut self, memory: &MemoryArgument) -> Self {
+        let one = || constant(F::one());
+        let [address, value, timestamp, write] = [0, 1, 2, 3].map(|index| col::<F>(&memory.sorted(index)));
+        let [changed, keep, gap] = ["changed", "keep", "gap"].map(|name| col::<F>(&memory.helper(name)));
+        for name in memory.columns().iter().take(7) {
+            self = self.with_free_column(name);
+        }
+        let same = one() - changed.clone().next();
+        for flag in [changed.clone(), write.clone()] {
+            let booleanity = flag.clone() * (flag - one());
+            self = self.with_transition(booleanity.clone()).with_transition(booleanity.next());
+        }
+        let decomposition = bit_decompose::<F>(&memory.helper("gap"), memory.bits).expect("checked by `memory`");
+        let permutation = memory.permutation(self.columns());
+        self.with_transition(same.clone() * (address.clone().next() - address.clone()))
+            .with_transition(keep.clone().next() - same.clone() * (one() - write.next()))
+            .with_transition(keep.next() * (value.clone().next() - value))
+            .with_transition(
+                gap.next()
+                    - changed.next() * (address.clone().next() - address - one())
+                    - same * (timestamp.clone().next() - timestamp - one()),
+            )
+            .with_bit_decomposition(&decomposition)
+            .with_challenge_segment(permutation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_memory_consistency() {
+        let values = |values: &[u64]| values.iter().map(|&value| PrimeField64::new(value)).collect::<Vec<_>>();
+        // Every four rows: write 5 to 7, read 7, write 9 to 2, read 2
+        let air = |read: &[u64]| {
+            let memory = memory::<PrimeField64>("addr", "value", "time", "write", 4).unwrap();
+            let air = AirBuilder::<PrimeField64>::new(128)
+                .with_column("one")
+                .with_column("time")
+                .with_transition(col("one").next() - col("one"))
+                .with_transition(col("time").next() - col("time") - col("one"))
+                .with_initial("one", PrimeField64::one())
+                .with_periodic_column("addr", &values(&[7, 7, 2, 2]))
+                .with_periodic_column("value", &values(read))
+                .with_periodic_column("write", &values(&[1, 0, 1, 0]))
+                .with_memory(&memory)
+                .build()
+                .unwrap();
+            (memory, air)
+        };
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let witness = |read: &[u64]| {
+            let (memory, air) = air(read);
+            let mut init = values(&[1, 0, 7, 7, 2, 2]);
+            init.extend(values(read));
+            init.extend(values(&[1, 0, 1, 0]));
+            init.resize(air.num_registers(), PrimeField64::zero());
+            let mut witness = prover.generate_trace(&air, &init, 16).unwrap();
+            memory.fill_trace(&air, &mut witness).unwrap();
+            (air, witness)
+        };
+
+        let (air, trace) = witness(&[5, 5, 9, 9]);
+        let sorted = air.register("addr.sorted").unwrap();
+        assert_eq!(trace.columns.value(sorted, 8), PrimeField64::new(7));
+        let proof = prover.prove(&air, &PublicInputs::new(), &trace).unwrap();
+        verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // Reading 6 from address 7 after writing 5 is inconsistent
+        let (air, trace) = witness(&[5, 6, 9, 9]);
+        assert!(prover.prove(&air, &PublicInputs::new(), &trace).is_err());
+        let proof = prover.clone().with_trace_check(false).prove(&air, &PublicInputs::new(), &trace).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+    }
+}
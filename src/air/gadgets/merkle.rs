@@ -0,0 +1,324 @@
+//! Merkle Path Verification
+//!
+//! Proves that a leaf is included in a Merkle tree under a given root, which
+//! is the core of membership proofs: the leaf, its index and the sibling
+//! nodes stay in the witness, and only the root needs to be public.
+//!
+//! Nodes are compressed with one of the algebraic hash gadgets, as the
+//! sponge hash of the left and right child, so a tree built natively with
+//! [`MerkleHasher::compress`] opens inside the AIR. The segment decomposes
+//! the index into one boolean column per level, least significant bit at
+//! the leaf, and a set bit places the running node on the right.
+//!
+//! Nodes are single field elements. Over
+//! [`PrimeField64`](crate::types::field::PrimeField64) a birthday search of
+//! about 2^30 hashes finds two children with the same parent, which opens a
+//! root to a leaf that was never committed; burn proofs such as
+//! [`BurnAir`](crate::air::xfg::BurnAir) take their leaves from this hash.
+//! The gadget therefore shows the construction rather than binding real
+//! trees; production use needs digests of several elements or a field of at
+//! least 256 bits.
+
+use crate::prelude::*;
+use super::circuit::RowCircuit;
+use super::poseidon::PoseidonParams;
+use super::rescue::RescueParams;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+
+/// Hash compressing two Merkle nodes into their parent
+///
+/// Digests are one field element, with only about 2^30 collision resistance
+/// over [`PrimeField64`](crate::types::field::PrimeField64); see the module
+/// documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleHasher<F: FieldElement> {
+    /// Poseidon sponge
+    Poseidon(PoseidonParams<F>),
+    /// Rescue sponge
+    Rescue(RescueParams<F>),
+}
+
+impl<F: FieldElement> MerkleHasher<F> {
+    /// Number of state elements of the permutation
+    pub fn width(&self) -> usize {
+        match self {
+            Self::Poseidon(params) => params.width(),
+            Self::Rescue(params) => params.width(),
+        }
+    }
+
+    /// Sponge hash of field elements, such as the preimage of a leaf
+    pub fn hash(&self, elements: &[F]) -> F {
+        match self {
+            Self::Poseidon(params) => params.hash(elements),
+            Self::Rescue(params) => params.hash(elements),
+        }
+    }
+
+    /// Parent of two nodes
+    pub fn compress(&self, left: F, right: F) -> F {
+        self.hash(&[left, right])
+    }
+
+    /// Root of the tree holding `leaf` at `index`, given the siblings from
+    /// the leaf level up
+    pub fn root(&self, leaf: F, index: u64, siblings: &[F]) -> F {
+        siblings.iter().enumerate().fold(leaf, |node, (level, &sibling)| {
+            if (index >> level) & 1 == 1 {
+                self.compress(sibling, node)
+            } else {
+                self.compress(node, sibling)
+            }
+        })
+    }
+
+    /// Sponge hash on a row circuit
+    pub(super) fn hash_in(&self, circuit: &mut RowCircuit<'_, F>, elements: &[F]) -> F {
+        match self {
+            Self::Poseidon(params) => params.hash_in(circuit, elements),
+            Self::Rescue(params) => params.hash_in(circuit, elements),
+        }
+    }
+}
+
+impl<F: FieldElement> From<PoseidonParams<F>> for MerkleHasher<F> {
+    fn from(params: PoseidonParams<F>) -> Self {
+        Self::Poseidon(params)
+    }
+}
+
+impl<F: FieldElement> From<RescueParams<F>> for MerkleHasher<F> {
+    fn from(params: RescueParams<F>) -> Self {
+        Self::Rescue(params)
+    }
+}
+
+/// Merkle path from a leaf to a root held in main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct MerklePath<F: FieldElement> {
+    /// Number of levels between the leaf and the root
+    depth: usize,
+    /// Node hash
+    hasher: MerkleHasher<F>,
+    /// Main column holding the leaf
+    leaf: usize,
+    /// Main columns whose hash is the leaf, replacing the leaf column
+    leaf_preimage: Option<Vec<usize>>,
+    /// Main column holding the leaf index
+    index: usize,
+    /// Main column holding the root
+    root: usize,
+    /// Main columns holding the siblings, from the leaf level up
+    siblings: Vec<usize>,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> MerklePath<F> {
+    /// Path of `depth` levels, reading the leaf, index and root from main
+    /// columns 0, 1 and 2 and the siblings from the columns after them
+    pub fn new(depth: usize, hasher: impl Into<MerkleHasher<F>>) -> Result<Self, AirError> {
+        // Every index below 2^depth must be a distinct field element
+        if depth == 0 || depth >= 64 || 1u64 << depth > F::MODULUS {
+            return Err(AirError::InvalidConstraint(format!(
+                "Merkle path depth {} is not between 1 and the field size",
+                depth
+            )));
+        }
+        let mut gadget = Self {
+            depth,
+            hasher: hasher.into(),
+            leaf: 0,
+            leaf_preimage: None,
+            index: 1,
+            root: 2,
+            siblings: (3..3 + depth).collect(),
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        Ok(gadget)
+    }
+
+    /// Read the path from other main columns
+    pub fn with_columns(
+        mut self,
+        leaf: usize,
+        index: usize,
+        root: usize,
+        siblings: &[usize],
+    ) -> Result<Self, AirError> {
+        if siblings.len() != self.depth {
+            return Err(AirError::InvalidConstraint(format!(
+                "Merkle path of depth {} with {} sibling columns",
+                self.depth,
+                siblings.len()
+            )));
+        }
+        self.leaf = leaf;
+        self.index = index;
+        self.root = root;
+        self.siblings = siblings.to_vec();
+        Ok(self)
+    }
+
+    /// Hash the leaf from the `preimage` main columns instead of reading it
+    ///
+    /// This proves that the leaf commits to those columns, for instance to
+    /// the fields of a transaction, without a separate hash gadget.
+    pub fn with_leaf_preimage(mut self, preimage: &[usize]) -> Self {
+        self.leaf_preimage = Some(preimage.to_vec());
+        self.measure();
+        self
+    }
+
+    /// Number of levels between the leaf and the root
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Node hash
+    pub fn hasher(&self) -> &MerkleHasher<F> {
+        &self.hasher
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.verify_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Recompute the root of one row and compare it to the root column
+    fn verify_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let index = column(self.index);
+
+        let bits: Vec<F> = (0..self.depth)
+            .map(|level| circuit.bit(|| (index.as_canonical_u64() >> level) & 1 == 1))
+            .collect();
+        let packed = bits.iter().rev().fold(F::zero(), |sum, &bit| sum + sum + bit);
+        circuit.assert_zero(index - packed);
+
+        let mut node = match &self.leaf_preimage {
+            Some(preimage) => {
+                let elements: Vec<F> = preimage.iter().map(|&index| column(index)).collect();
+                self.hasher.hash_in(circuit, &elements)
+            }
+            None => column(self.leaf),
+        };
+        for (&bit, &sibling) in bits.iter().zip(&self.siblings) {
+            let sibling = column(sibling);
+            let left = circuit.define(node + bit * (sibling - node));
+            let right = node + sibling - left;
+            node = self.hasher.hash_in(circuit, &[left, right]);
+        }
+        circuit.assert_zero(column(self.root) - node);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for MerklePath<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.verify_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.verify_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.verify_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Merkle path gadget proving that the leaf column is included under the root column
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment),
+/// with the leaf, index and root in main columns 0, 1 and 2 and the `depth`
+/// siblings after them; use [`MerklePath::with_columns`] for another layout.
+/// Making the root public with a boundary assertion turns the AIR into a
+/// membership proof.
+pub fn merkle_path<F: FieldElement + 'static>(
+    depth: usize,
+    hasher: impl Into<MerkleHasher<F>>,
+) -> Result<AuxSegment<F>, AirError> {
+    MerklePath::new(depth, hasher).map(AuxSegment::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+    use crate::types::{ColIdx, RowIdx};
+
+    #[test]
+    fn test_merkle_path_gadget() {
+        let value = PrimeField64::new;
+        let hashers: [MerkleHasher<PrimeField64>; 2] = [
+            PoseidonParams::new(3, 8, 22).unwrap().into(),
+            RescueParams::new(3, 8).unwrap().into(),
+        ];
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+
+        for hasher in hashers {
+            // Leaf 5 of a tree of depth 3, in constant columns
+            let leaf = value(42);
+            let siblings = [value(7), value(8), value(9)];
+            let root = hasher.root(leaf, 5, &siblings);
+            assert_ne!(root, hasher.root(leaf, 4, &siblings));
+            let initial = [leaf, value(5), root, siblings[0], siblings[1], siblings[2]];
+
+            let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, leaf)]);
+            let air = Air::new(vec![], TransitionFunction::identity(6), boundary, 128)
+                .with_aux_segment(merkle_path(3, hasher.clone()).unwrap());
+            let witness = prover.generate_trace(&air, &initial, 8).unwrap();
+            let public_inputs = PublicInputs::new().assert(ColIdx(2), RowIdx(0), root);
+            let proof = prover.prove(&air, &public_inputs, &witness).unwrap();
+            verifier.verify_with_air(&air, &proof, &public_inputs).unwrap();
+
+            // The proof does not open under another root
+            let wrong_root = PublicInputs::new().assert(ColIdx(2), RowIdx(0), root + value(1));
+            assert!(verifier.verify_with_air(&air, &proof, &wrong_root).is_err());
+
+            // Nor does the leaf sit at another index
+            let mut moved = initial;
+            moved[1] = value(4);
+            let witness = prover.generate_trace(&air, &moved, 8).unwrap();
+            assert!(prover.prove(&air, &public_inputs, &witness).is_err());
+        }
+
+        assert!(MerklePath::<PrimeField64>::new(0, RescueParams::new(3, 8).unwrap()).is_err());
+        let path = MerklePath::<PrimeField64>::new(2, RescueParams::new(3, 8).unwrap()).unwrap();
+        assert!(path.with_columns(0, 1, 2, &[3]).is_err());
+    }
+}
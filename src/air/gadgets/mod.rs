@@ -0,0 +1,40 @@
+//! Constraint Gadgets
+//!
+//! Gadgets are reusable pieces of AIR that application AIRs add to their own
+//! constraints, such as proving that a column only holds small integers.
+//!
+//! The main transitions of an [`Air`](crate::air::Air) are linear, so gadgets
+//! with nonlinear constraints place their intermediate values in an
+//! auxiliary segment that draws no challenges. Every gadget constrains each
+//! row of the main trace on its own: its constraints are checked on both rows
+//! of every transition frame, which covers the last row as well.
+//!
+//! Gadgets whose intermediate values other constraints need to read, such as
+//! [`bit_decompose`], add columns to an
+//! [`AirBuilder`](crate::air::builder::AirBuilder) instead. So does the
+//! [`memory`] consistency argument, whose permutation segment draws
+//! challenges.
+
+mod algebraic;
+mod balance;
+mod bits;
+mod circuit;
+mod memory;
+mod merkle;
+mod nullifier;
+mod range;
+mod poseidon;
+mod rescue;
+mod schnorr;
+mod sha256;
+
+pub use range::*;
+pub use balance::*;
+pub use bits::*;
+pub use memory::*;
+pub use merkle::*;
+pub use nullifier::*;
+pub use poseidon::*;
+pub use rescue::*;
+pub use schnorr::*;
+pub use sha256::*;
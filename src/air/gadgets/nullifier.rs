@@ -0,0 +1,352 @@
+//! Commitments and Nullifiers
+//!
+//! Private claims, such as minting against a burn without revealing which
+//! burn, publish two values derived from a secret known only to the owner:
+//! the commitment `C = H(secret, amount)`, which goes into a public Merkle
+//! tree when the note is created, and the nullifier `N = H(secret, index)`,
+//! which is revealed when the note is spent so that it cannot be spent
+//! twice. The gadget proves that both come from the same secret; opening
+//! `C` with a [`MerklePath`](super::MerklePath) whose leaf is the commitment
+//! column then proves the claim while keeping `C` and the index private.
+//! They stay private only in a zero-knowledge proof (see
+//! [`StarkProver::with_zk`](crate::proof::StarkProver::with_zk)): any other
+//! proof ships the trace, secret included.
+//!
+//! Both hashes absorb a domain tag before their inputs, so that a
+//! commitment can never equal a nullifier when an amount happens to equal
+//! an index. [`NoteCommitment`] proves a commitment alone, for notes that a
+//! statement creates rather than spends.
+//!
+//! Commitments and nullifiers are single field elements. Over
+//! [`PrimeField64`](crate::types::field::PrimeField64) a birthday search of
+//! about 2^30 hashes finds two openings of one commitment with different
+//! amounts, so the gadget shows the construction rather than binding real
+//! amounts; production use needs digests of several elements or a field of
+//! at least 256 bits.
+
+use crate::prelude::*;
+use super::circuit::RowCircuit;
+use super::merkle::MerkleHasher;
+use crate::air::{AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+
+/// Domain tag of commitments
+const COMMITMENT_TAG: u64 = 1;
+
+/// Domain tag of nullifiers
+const NULLIFIER_TAG: u64 = 2;
+
+/// Commitment `H(secret, amount)` to a note
+fn commit<F: FieldElement>(hasher: &MerkleHasher<F>, secret: F, amount: F) -> F {
+    hasher.hash(&[F::from_canonical_u64(COMMITMENT_TAG), secret, amount])
+}
+
+/// Commitment to a note on a row circuit
+fn commit_in<F: FieldElement>(hasher: &MerkleHasher<F>, circuit: &mut RowCircuit<'_, F>, secret: F, amount: F) -> F {
+    hasher.hash_in(circuit, &[F::from_canonical_u64(COMMITMENT_TAG), secret, amount])
+}
+
+/// Commitment to a note held in main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct NoteCommitment<F: FieldElement> {
+    /// Hash of the commitment
+    hasher: MerkleHasher<F>,
+    /// Main column holding the secret
+    secret: usize,
+    /// Main column holding the amount
+    amount: usize,
+    /// Main column that must hold the commitment
+    commitment: usize,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> NoteCommitment<F> {
+    /// Bind the secret and amount in main columns 0 and 1 to the commitment in column 2
+    pub fn new(hasher: impl Into<MerkleHasher<F>>) -> Self {
+        let mut gadget = Self {
+            hasher: hasher.into(),
+            secret: 0,
+            amount: 1,
+            commitment: 2,
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        gadget
+    }
+
+    /// Read the note from other main columns
+    pub fn with_columns(mut self, secret: usize, amount: usize, commitment: usize) -> Self {
+        self.secret = secret;
+        self.amount = amount;
+        self.commitment = commitment;
+        self
+    }
+
+    /// Hash of the commitment
+    pub fn hasher(&self) -> &MerkleHasher<F> {
+        &self.hasher
+    }
+
+    /// Commitment `H(secret, amount)` to a note
+    pub fn commitment(&self, secret: F, amount: F) -> F {
+        commit(&self.hasher, secret, amount)
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.bind_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Recompute the commitment of one row and compare it to its column
+    fn bind_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let commitment = commit_in(&self.hasher, circuit, column(self.secret), column(self.amount));
+        circuit.assert_zero(column(self.commitment) - commitment);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for NoteCommitment<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.bind_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.bind_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.bind_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Commitment and nullifier of a note held in main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct Nullifier<F: FieldElement> {
+    /// Hash of both values
+    hasher: MerkleHasher<F>,
+    /// Main column holding the secret
+    secret: usize,
+    /// Main column holding the amount
+    amount: usize,
+    /// Main column holding the index of the note
+    index: usize,
+    /// Main column that must hold the commitment
+    commitment: usize,
+    /// Main column that must hold the nullifier
+    nullifier: usize,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> Nullifier<F> {
+    /// Bind the secret, amount and index in main columns 0, 1 and 2 to the
+    /// commitment and nullifier in columns 3 and 4
+    pub fn new(hasher: impl Into<MerkleHasher<F>>) -> Self {
+        let mut gadget = Self {
+            hasher: hasher.into(),
+            secret: 0,
+            amount: 1,
+            index: 2,
+            commitment: 3,
+            nullifier: 4,
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        gadget
+    }
+
+    /// Read the note from other main columns
+    pub fn with_columns(
+        mut self,
+        secret: usize,
+        amount: usize,
+        index: usize,
+        commitment: usize,
+        nullifier: usize,
+    ) -> Self {
+        self.secret = secret;
+        self.amount = amount;
+        self.index = index;
+        self.commitment = commitment;
+        self.nullifier = nullifier;
+        self
+    }
+
+    /// Hash of both values
+    pub fn hasher(&self) -> &MerkleHasher<F> {
+        &self.hasher
+    }
+
+    /// Commitment `H(secret, amount)` to a note
+    pub fn commitment(&self, secret: F, amount: F) -> F {
+        commit(&self.hasher, secret, amount)
+    }
+
+    /// Nullifier `H(secret, index)` spending a note
+    pub fn nullifier(&self, secret: F, index: F) -> F {
+        self.hasher.hash(&[F::from_canonical_u64(NULLIFIER_TAG), secret, index])
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.bind_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Recompute both values of one row and compare them to their columns
+    fn bind_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let secret = column(self.secret);
+
+        let commitment = commit_in(&self.hasher, circuit, secret, column(self.amount));
+        circuit.assert_zero(column(self.commitment) - commitment);
+
+        let tag = F::from_canonical_u64(NULLIFIER_TAG);
+        let nullifier = self.hasher.hash_in(circuit, &[tag, secret, column(self.index)]);
+        circuit.assert_zero(column(self.nullifier) - nullifier);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Nullifier<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.bind_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.bind_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.bind_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Gadget binding a commitment to the secret and amount of a note
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment),
+/// with the secret and amount in main columns 0 and 1 and the commitment in
+/// column 2; use [`NoteCommitment::with_columns`] for another layout.
+pub fn note_commitment<F: FieldElement + 'static>(hasher: impl Into<MerkleHasher<F>>) -> AuxSegment<F> {
+    AuxSegment::new(NoteCommitment::new(hasher))
+}
+
+/// Gadget binding a commitment and a nullifier to one secret
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment),
+/// with the secret, amount and index in main columns 0, 1 and 2 and the
+/// commitment and nullifier in columns 3 and 4; use
+/// [`Nullifier::with_columns`] for another layout.
+pub fn nullifier<F: FieldElement + 'static>(hasher: impl Into<MerkleHasher<F>>) -> AuxSegment<F> {
+    AuxSegment::new(Nullifier::new(hasher))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::gadgets::{MerklePath, RescueParams};
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+    use crate::types::{ColIdx, RowIdx};
+
+    #[test]
+    fn test_nullifier_of_committed_note() {
+        let value = PrimeField64::new;
+        let hasher = RescueParams::<PrimeField64>::new(4, 8).unwrap();
+        let gadget = Nullifier::new(hasher.clone());
+        let (secret, amount, index) = (value(987_654_321), value(500), value(1));
+        let commitment = gadget.commitment(secret, amount);
+        let nullifier = gadget.nullifier(secret, index);
+        assert_ne!(gadget.commitment(secret, index), gadget.nullifier(secret, index));
+        assert_ne!(nullifier, gadget.nullifier(secret + value(1), index));
+
+        // The note is leaf 1 of a tree of two commitments
+        let merkle = gadget.hasher().clone();
+        let sibling = gadget.commitment(value(5), value(6));
+        let root = merkle.compress(sibling, commitment);
+
+        // Constant columns `[secret, amount, index, commitment, nullifier, root, sibling]`
+        let fresh_nullifier = gadget.nullifier(secret, index + value(1));
+        let membership = MerklePath::new(1, hasher).unwrap().with_columns(3, 2, 5, &[6]).unwrap();
+        let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(5, root)]);
+        let air = Air::new(vec![], TransitionFunction::identity(7), boundary, 128)
+            .with_aux_segment(AuxSegment::new(gadget))
+            .with_aux_segment(AuxSegment::new(membership));
+        let initial = [secret, amount, index, commitment, nullifier, root, sibling];
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let public_inputs =
+            PublicInputs::new().assert(ColIdx(4), RowIdx(0), nullifier).assert(ColIdx(5), RowIdx(0), root);
+        let witness = prover.generate_trace(&air, &initial, 8).unwrap();
+        let proof = prover.clone().with_zk(true).prove(&air, &public_inputs, &witness).unwrap();
+        assert!(proof.trace.columns.is_empty() && proof.trace.aux_segments.is_empty());
+        verifier.verify_with_air(&air, &proof, &public_inputs).unwrap();
+
+        // The proof reveals its nullifier, and the note has no other one
+        let other =
+            PublicInputs::new().assert(ColIdx(4), RowIdx(0), fresh_nullifier).assert(ColIdx(5), RowIdx(0), root);
+        assert!(verifier.verify_with_air(&air, &proof, &other).is_err());
+        let mut double_spend = initial;
+        double_spend[4] = fresh_nullifier;
+        let witness = prover.generate_trace(&air, &double_spend, 8).unwrap();
+        assert!(prover.prove(&air, &other, &witness).is_err());
+    }
+}
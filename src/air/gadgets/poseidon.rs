@@ -0,0 +1,372 @@
+//! Poseidon Permutation
+//!
+//! Proves the Poseidon permutation of a state held in main trace columns,
+//! row by row, so that sponge hashing and Merkle openings built on Poseidon
+//! can be checked inside an AIR.
+//!
+//! [`PoseidonParams`] fixes the permutation and also evaluates it natively;
+//! the gadget is consistent with [`PoseidonParams::permute`] by
+//! construction. Each round adds its round constants, applies the S-box
+//! `x^α` to every state element in full rounds and to the first one in
+//! partial rounds, and multiplies by an MDS matrix. Half of the full rounds
+//! come before the partial rounds and half after.
+//!
+//! The round constants and MDS matrix are the crate's own rather than a
+//! published Poseidon instance, so digests do not match other Poseidon
+//! implementations. Round numbers below the bounds of the Poseidon paper
+//! are rejected: at least [`POSEIDON_MIN_FULL_ROUNDS`] full rounds, and
+//! enough rounds in total for the permutation's degree `α^rounds` to reach
+//! `MODULUS · width` (the interpolation bound).
+//!
+//! The segment holds, for every round, the intermediate powers of each S-box
+//! input and the state after the round, in that order. The S-box powers follow the binary
+//! expansion of `α`, one product per column, so every constraint has degree
+//! two; the last product of each S-box is folded into the constraint on the
+//! round output.
+
+use crate::prelude::*;
+use super::algebraic::{cauchy_mds, derive_constants, mix, sbox_exponent, sponge_hash, sponge_hash_in, PowerChain};
+use super::circuit::RowCircuit;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+use core::ops::Range;
+
+/// Domain separator for deriving the round constants
+const ROUND_CONSTANTS_DOMAIN: &[u8] = b"xfg-stark/poseidon/round-constants";
+
+/// Fewest full rounds a Poseidon permutation may have
+///
+/// Six full rounds resist statistical attacks; the Poseidon paper adds two
+/// as a security margin.
+pub const POSEIDON_MIN_FULL_ROUNDS: usize = 8;
+
+/// Parameters of a Poseidon permutation over a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoseidonParams<F: FieldElement> {
+    /// Number of state elements
+    width: usize,
+    /// Number of full rounds, split evenly around the partial rounds
+    full_rounds: usize,
+    /// Number of partial rounds
+    partial_rounds: usize,
+    /// S-box exponent
+    alpha: u64,
+    /// Round constants, one row of `width` elements per round
+    round_constants: Vec<Vec<F>>,
+    /// MDS matrix
+    mds: Vec<Vec<F>>,
+}
+
+impl<F: FieldElement> PoseidonParams<F> {
+    /// Poseidon over `width` state elements with the given round numbers
+    ///
+    /// The S-box exponent is the smallest `α >= 3` that is coprime to
+    /// `MODULUS - 1`, so that `x^α` is a permutation of the field. Round
+    /// constants are derived from SHA-256 of the parameters and the MDS
+    /// matrix is the Cauchy matrix `1 / (i + width + j)`. Round numbers below
+    /// the security minimum (see the module documentation) are rejected.
+    pub fn new(width: usize, full_rounds: usize, partial_rounds: usize) -> Result<Self, AirError> {
+        if width < 2 || full_rounds < POSEIDON_MIN_FULL_ROUNDS || full_rounds % 2 != 0 {
+            return Err(AirError::InvalidConstraint(format!(
+                "Poseidon needs a width of at least 2 and an even number of at least {} full rounds, \
+                 got width {} with {}",
+                POSEIDON_MIN_FULL_ROUNDS, width, full_rounds
+            )));
+        }
+        let alpha = sbox_exponent::<F>();
+        let min_rounds = interpolation_rounds::<F>(alpha, width);
+        if full_rounds + partial_rounds < min_rounds {
+            return Err(AirError::InvalidConstraint(format!(
+                "Poseidon of width {} with x^{} needs {} rounds, got {}",
+                width,
+                alpha,
+                min_rounds,
+                full_rounds + partial_rounds
+            )));
+        }
+        let parameters = [width as u64, full_rounds as u64, partial_rounds as u64];
+        let round_constants =
+            derive_constants(ROUND_CONSTANTS_DOMAIN, &parameters, full_rounds + partial_rounds, width);
+        let mds = cauchy_mds(width)?;
+
+        Ok(Self {
+            width,
+            full_rounds,
+            partial_rounds,
+            alpha,
+            round_constants,
+            mds,
+        })
+    }
+
+    /// Number of state elements
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of full rounds
+    pub fn full_rounds(&self) -> usize {
+        self.full_rounds
+    }
+
+    /// Number of partial rounds
+    pub fn partial_rounds(&self) -> usize {
+        self.partial_rounds
+    }
+
+    /// S-box exponent
+    pub fn alpha(&self) -> u64 {
+        self.alpha
+    }
+
+    /// Total number of rounds
+    pub fn rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+
+    /// Apply the permutation to a state of `width` elements
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "Poseidon state width");
+        for round in 0..self.rounds() {
+            for (element, constant) in state.iter_mut().zip(&self.round_constants[round]) {
+                *element += *constant;
+            }
+            for element in state.iter_mut().take(self.sboxes(round)) {
+                *element = element.pow(self.alpha);
+            }
+            let mixed = mix(&self.mds, state);
+            state.copy_from_slice(&mixed);
+        }
+    }
+
+    /// Sponge hash of field elements, with one capacity element
+    ///
+    /// The capacity starts at the number of elements, which are absorbed
+    /// `width - 1` at a time; the digest is the first rate element.
+    pub fn hash(&self, elements: &[F]) -> F {
+        sponge_hash(self.width, elements, |state| self.permute(state))
+    }
+
+    /// Run the sponge hash on a row circuit, returning the digest
+    pub(super) fn hash_in(&self, circuit: &mut RowCircuit<'_, F>, elements: &[F]) -> F {
+        sponge_hash_in(circuit, self.width, elements, |circuit, state| self.permute_in(circuit, state))
+    }
+
+    /// Run the permutation on a row circuit, returning the state columns
+    /// after the last round
+    pub(super) fn permute_in(&self, circuit: &mut RowCircuit<'_, F>, mut state: Vec<F>) -> Vec<F> {
+        let chain = PowerChain::new(self.alpha);
+        for round in 0..self.rounds() {
+            for (element, constant) in state.iter_mut().zip(&self.round_constants[round]) {
+                *element += *constant;
+            }
+            for element in state.iter_mut().take(self.sboxes(round)) {
+                *element = chain.apply(circuit, *element);
+            }
+            state = mix(&self.mds, &state).into_iter().map(|value| circuit.define(value)).collect();
+        }
+        state
+    }
+
+    /// Number of S-boxes applied in a round
+    fn sboxes(&self, round: usize) -> usize {
+        let half = self.full_rounds / 2;
+        if round < half || round >= half + self.partial_rounds {
+            self.width
+        } else {
+            1
+        }
+    }
+}
+
+/// Poseidon permutation of main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct Poseidon<F: FieldElement> {
+    /// Permutation parameters
+    params: PoseidonParams<F>,
+    /// Main columns holding the input state
+    input: Vec<usize>,
+    /// Main columns that must hold the output state, if any
+    output: Option<Vec<usize>>,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> Poseidon<F> {
+    /// Permute the state held in the `input` columns on every row
+    pub fn new(params: PoseidonParams<F>, input: &[usize]) -> Result<Self, AirError> {
+        if input.len() != params.width {
+            return Err(AirError::InvalidConstraint(format!(
+                "Poseidon of width {} over {} input columns",
+                params.width,
+                input.len()
+            )));
+        }
+        let mut gadget = Self {
+            params,
+            input: input.to_vec(),
+            output: None,
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        Ok(gadget)
+    }
+
+    /// Also require the `output` main columns to hold the permuted state
+    pub fn with_output(mut self, output: &[usize]) -> Result<Self, AirError> {
+        if output.len() != self.params.width {
+            return Err(AirError::InvalidConstraint(format!(
+                "Poseidon of width {} over {} output columns",
+                self.params.width,
+                output.len()
+            )));
+        }
+        self.output = Some(output.to_vec());
+        self.measure();
+        Ok(self)
+    }
+
+    /// Permutation parameters
+    pub fn params(&self) -> &PoseidonParams<F> {
+        &self.params
+    }
+
+    /// Segment columns holding the permuted state
+    pub fn output_columns(&self) -> Range<usize> {
+        self.width - self.params.width..self.width
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.permute_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Run the permutation of one row
+    fn permute_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let state = self.params.permute_in(circuit, self.input.iter().map(|&index| column(index)).collect());
+        if let Some(output) = &self.output {
+            for (&index, value) in output.iter().zip(state) {
+                circuit.assert_zero(column(index) - value);
+            }
+        }
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Poseidon<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.permute_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.permute_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.permute_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Poseidon gadget proving the permutation of the `input` columns on every row
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment);
+/// use [`Poseidon::with_output`] to also tie the result to main columns.
+pub fn poseidon<F: FieldElement + 'static>(
+    params: PoseidonParams<F>,
+    input: &[usize],
+) -> Result<AuxSegment<F>, AirError> {
+    Poseidon::new(params, input).map(AuxSegment::new)
+}
+
+/// Fewest rounds `r` with `α^r >= MODULUS · width`
+///
+/// Below this the permutation has too low a degree to resist interpolation.
+fn interpolation_rounds<F: FieldElement>(alpha: u64, width: usize) -> usize {
+    let target = u128::from(F::MODULUS) * width as u128;
+    let mut degree = 1u128;
+    let mut rounds = 0;
+    while degree < target {
+        degree = degree.saturating_mul(u128::from(alpha));
+        rounds += 1;
+    }
+    rounds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_poseidon_gadget_matches_native_permutation() {
+        let params = PoseidonParams::<PrimeField64>::new(3, 8, 22).unwrap();
+        // 3, 5, 7, 11 and 13 all divide 2^61 - 2
+        assert_eq!(params.alpha(), 17);
+        // 17^16 is the first power of 17 beyond 3 · (2^61 - 1)
+        assert_eq!(interpolation_rounds::<PrimeField64>(17, 3), 16);
+        assert!(PoseidonParams::<PrimeField64>::new(3, 6, 22).is_err());
+        assert!(PoseidonParams::<PrimeField64>::new(3, 8, 7).is_err());
+        assert!(PoseidonParams::<PrimeField64>::new(3, 8, 8).is_ok());
+
+        // Tribonacci columns as the permuted states
+        let value = PrimeField64::new;
+        let transition = TransitionFunction::linear_recurrence(&[value(1), value(1), value(1)]).unwrap();
+        let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, value(1))]);
+        let air = Air::new(vec![], transition.clone(), boundary.clone(), 128)
+            .with_aux_segment(poseidon(params.clone(), &[0, 1, 2]).unwrap());
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &[value(1), value(2), value(3)], 16).unwrap();
+        let gadget = Poseidon::new(params.clone(), &[0, 1, 2]).unwrap();
+        let columns = witness.columns.to_columns();
+        let segment = gadget.build(&columns, &[]);
+        for row in [0, 15] {
+            let mut state: Vec<_> = columns.iter().map(|column| column[row]).collect();
+            params.permute(&mut state);
+            let output: Vec<_> = gadget.output_columns().map(|column| segment[column][row]).collect();
+            assert_eq!(output, state);
+        }
+
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::<PrimeField64>::new(48)
+            .verify_with_air(&air, &proof, &PublicInputs::new())
+            .unwrap();
+
+        // The input state is not its own permutation
+        let fixed_point = Air::new(vec![], transition, boundary, 128)
+            .with_aux_segment(AuxSegment::new(gadget.with_output(&[0, 1, 2]).unwrap()));
+        let error = prover.prove(&fixed_point, &PublicInputs::new(), &witness).unwrap_err();
+        assert!(matches!(error, ProofError::ConstraintError(_)));
+    }
+}
@@ -0,0 +1,151 @@
+//! Range Checks
+//!
+//! Proves that a column only holds small integers by decomposing its values
+//! into bits.
+
+use crate::prelude::*;
+use crate::air::{Air, AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+
+/// Range check of a main trace column by bit decomposition
+///
+/// The segment holds one column per bit, least significant first. Every bit
+/// column is constrained to zero or one and the bits, weighted by their
+/// powers of two, must add up to the checked value on every row. The
+/// segment draws no challenges: the decomposition is unique, so it does not
+/// matter that the bits are committed after the main trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeCheck {
+    /// Checked main column
+    column: usize,
+    /// Number of bits
+    bits: u32,
+}
+
+impl RangeCheck {
+    /// Check that the values of `column` lie in `[0, 2^bits)`
+    ///
+    /// `2^bits` may not exceed the field modulus, so that the weighted bits
+    /// cannot wrap around.
+    pub fn new<F: FieldElement>(column: usize, bits: u32) -> Result<Self, AirError> {
+        if bits == 0 || bits >= u64::BITS || (1u64 << bits) > F::MODULUS {
+            return Err(AirError::InvalidConstraint(format!(
+                "cannot range check {} bits in a field of modulus {}",
+                bits,
+                F::MODULUS
+            )));
+        }
+        Ok(Self { column, bits })
+    }
+
+    /// Checked main column
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Number of bits
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Residuals of the constraints of one row
+    fn row_residuals<F: FieldElement>(&self, main: &[F], aux: &[F], residuals: &mut Vec<F>) {
+        let two = F::from_canonical_u64(2);
+        let mut power = F::one();
+        let mut sum = F::zero();
+        for index in 0..self.bits as usize {
+            let bit = aux.get(index).copied().unwrap_or_else(F::zero);
+            residuals.push(bit * (bit - F::one()));
+            sum += power * bit;
+            power *= two;
+        }
+        residuals.push(main.get(self.column).copied().unwrap_or_else(F::zero) - sum);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for RangeCheck {
+    fn width(&self) -> usize {
+        self.bits as usize
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * (self.bits as usize + 1)
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        // Out-of-range values keep their low bits and fail the sum constraint
+        (0..self.bits)
+            .map(|bit| {
+                main[self.column]
+                    .iter()
+                    .map(|value| F::from_canonical_u64((value.as_canonical_u64() >> bit) & 1))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(AuxTraceSegment::<F>::num_constraints(self));
+        self.row_residuals(frame.main_current, frame.aux_current, &mut residuals);
+        self.row_residuals(frame.main_next, frame.aux_next, &mut residuals);
+        residuals
+    }
+}
+
+/// Range check gadget proving that the values of `column` lie in `[0, 2^bits)`
+///
+/// Returns the bit decomposition segment to pass to
+/// [`Air::with_aux_segment`]; see [`RangeCheck`].
+pub fn range_check<F: FieldElement>(column: usize, bits: u32) -> Result<AuxSegment<F>, AirError> {
+    RangeCheck::new::<F>(column, bits).map(AuxSegment::new)
+}
+
+impl<F: FieldElement> Air<F> {
+    /// Add a range check of `column` to `[0, 2^bits)`
+    pub fn with_range_check(self, column: usize, bits: u32) -> Result<Self, AirError> {
+        Ok(self.with_aux_segment(range_check(column, bits)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_range_check() {
+        // Columns `[one, counter]`, the counter counting up from `start`
+        let (zero, one) = (PrimeField64::new(0), PrimeField64::new(1));
+        let air = Air::new(
+            vec![],
+            TransitionFunction::linear(vec![vec![one, zero], vec![one, one]]),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, one)]),
+            128,
+        )
+        .with_range_check(1, 4)
+        .unwrap();
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+
+        let witness = prover.generate_trace(&air, &[one, zero], 16).unwrap();
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // Starting at one, the counter reaches 16 on the last row
+        let witness = prover.generate_trace(&air, &[one, one], 16).unwrap();
+        let error = prover.prove(&air, &PublicInputs::new(), &witness).unwrap_err();
+        assert!(matches!(error, ProofError::ConstraintError(_)));
+        let proof = prover.with_trace_check(false).prove(&air, &PublicInputs::new(), &witness).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+
+        assert!(range_check::<PrimeField64>(1, 0).is_err());
+        assert!(range_check::<PrimeField64>(1, 64).is_err());
+    }
+}
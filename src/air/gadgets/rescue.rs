@@ -0,0 +1,341 @@
+//! Rescue Permutation
+//!
+//! Proves a Rescue permutation of a state held in main trace columns, row by
+//! row, so that commitments hashed with it can be opened inside an AIR, as a
+//! recursive verifier needs to.
+//!
+//! [`RescueParams`] fixes the permutation and is also the native hasher: the
+//! gadget reads its round constants and MDS matrix from the same parameters,
+//! so it agrees with [`RescueParams::permute`] and [`RescueParams::hash`].
+//! Each round applies the S-box `x^α` to the whole state, multiplies by the
+//! MDS matrix and adds constants, then does the same with the inverse S-box
+//! `x^(1/α)`, as in Rescue-Prime.
+//!
+//! The round constants and MDS matrix are the crate's own, derived for
+//! whichever field the AIR uses, so the permutation is **not** the
+//! Rescue-Prime of the specification nor Winterfell's `Rp64_256` (Rescue
+//! Prime Optimized over the 64-bit Goldilocks field): digests computed with
+//! either of them do not match. Fewer than [`RESCUE_MIN_ROUNDS`] rounds are
+//! rejected.
+//!
+//! The inverse S-box has a huge degree, so the segment holds its output `y`
+//! as a witness and constrains `y^α` to the input instead. For every round
+//! the segment holds the power chain columns of the forward S-boxes, the
+//! state after the first half-round, the inverse S-box outputs with their
+//! power chain columns, and the state after the round.
+
+use crate::prelude::*;
+use super::algebraic::{
+    cauchy_mds, derive_constants, inverse_exponent, mix, sbox_exponent, sponge_hash, sponge_hash_in, PowerChain,
+};
+use super::circuit::RowCircuit;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+use core::ops::Range;
+
+/// Domain separator for deriving the round constants
+const ROUND_CONSTANTS_DOMAIN: &[u8] = b"xfg-stark/rescue-prime/round-constants";
+
+/// Fewest rounds a Rescue permutation may have
+///
+/// Rescue-Prime takes `⌈1.5 · max(5, ℓ)⌉` rounds, where `ℓ` bounds Gröbner
+/// basis attacks; this is that count for `ℓ ≤ 5`.
+pub const RESCUE_MIN_ROUNDS: usize = 8;
+
+/// Parameters of a Rescue permutation over a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RescueParams<F: FieldElement> {
+    /// Number of state elements
+    width: usize,
+    /// Number of rounds
+    rounds: usize,
+    /// S-box exponent
+    alpha: u64,
+    /// Inverse S-box exponent
+    inverse_alpha: u64,
+    /// Round constants, two rows of `width` elements per round
+    round_constants: Vec<Vec<F>>,
+    /// MDS matrix
+    mds: Vec<Vec<F>>,
+}
+
+impl<F: FieldElement> RescueParams<F> {
+    /// Rescue over `width` state elements with `rounds` rounds
+    ///
+    /// The S-box exponent is the smallest `α >= 3` that is coprime to
+    /// `MODULUS - 1`. Round constants are derived from SHA-256 of the
+    /// parameters and the MDS matrix is the Cauchy matrix `1 / (i + width + j)`.
+    /// At least [`RESCUE_MIN_ROUNDS`] rounds are required.
+    pub fn new(width: usize, rounds: usize) -> Result<Self, AirError> {
+        if width < 2 || rounds < RESCUE_MIN_ROUNDS {
+            return Err(AirError::InvalidConstraint(format!(
+                "Rescue needs a width of at least 2 and {} rounds, got width {} with {} rounds",
+                RESCUE_MIN_ROUNDS, width, rounds
+            )));
+        }
+        let alpha = sbox_exponent::<F>();
+        let parameters = [width as u64, rounds as u64];
+        Ok(Self {
+            width,
+            rounds,
+            alpha,
+            inverse_alpha: inverse_exponent::<F>(alpha),
+            round_constants: derive_constants(ROUND_CONSTANTS_DOMAIN, &parameters, 2 * rounds, width),
+            mds: cauchy_mds(width)?,
+        })
+    }
+
+    /// Number of state elements
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rounds
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// S-box exponent
+    pub fn alpha(&self) -> u64 {
+        self.alpha
+    }
+
+    /// Apply the permutation to a state of `width` elements
+    pub fn permute(&self, state: &mut [F]) {
+        assert_eq!(state.len(), self.width, "Rescue state width");
+        for round in 0..self.rounds {
+            for element in state.iter_mut() {
+                *element = element.pow(self.alpha);
+            }
+            self.mix_and_add(state, 2 * round);
+            for element in state.iter_mut() {
+                *element = element.pow(self.inverse_alpha);
+            }
+            self.mix_and_add(state, 2 * round + 1);
+        }
+    }
+
+    /// Sponge hash of field elements, with one capacity element
+    ///
+    /// The capacity starts at the number of elements, which are absorbed
+    /// `width - 1` at a time; the digest is the first rate element.
+    pub fn hash(&self, elements: &[F]) -> F {
+        sponge_hash(self.width, elements, |state| self.permute(state))
+    }
+
+    /// Run the sponge hash on a row circuit, returning the digest
+    pub(super) fn hash_in(&self, circuit: &mut RowCircuit<'_, F>, elements: &[F]) -> F {
+        sponge_hash_in(circuit, self.width, elements, |circuit, state| self.permute_in(circuit, state))
+    }
+
+    /// Run the permutation on a row circuit, returning the state columns
+    /// after the last round
+    pub(super) fn permute_in(&self, circuit: &mut RowCircuit<'_, F>, mut state: Vec<F>) -> Vec<F> {
+        let chain = PowerChain::new(self.alpha);
+        for round in 0..self.rounds {
+            // x^α, then the state after the first half-round
+            for element in state.iter_mut() {
+                *element = chain.apply(circuit, *element);
+            }
+            self.mix_and_add(&mut state, 2 * round);
+            let middle: Vec<F> = state.iter().map(|&value| circuit.define(value)).collect();
+
+            // y with y^α equal to the middle state, then the state after the round
+            let roots: Vec<F> = middle
+                .iter()
+                .map(|&value| circuit.witness(|| value.pow(self.inverse_alpha)))
+                .collect();
+            for (&root, &value) in roots.iter().zip(&middle) {
+                let power = chain.apply(circuit, root);
+                circuit.assert_zero(power - value);
+            }
+            state = roots;
+            self.mix_and_add(&mut state, 2 * round + 1);
+            state = state.iter().map(|&value| circuit.define(value)).collect();
+        }
+        state
+    }
+
+    /// Multiply by the MDS matrix and add a row of round constants
+    fn mix_and_add(&self, state: &mut [F], constants: usize) {
+        let mixed = mix(&self.mds, state);
+        for ((element, value), &constant) in state.iter_mut().zip(mixed).zip(&self.round_constants[constants]) {
+            *element = value + constant;
+        }
+    }
+}
+
+/// Rescue permutation of main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct Rescue<F: FieldElement> {
+    /// Permutation parameters
+    params: RescueParams<F>,
+    /// Main columns holding the input state
+    input: Vec<usize>,
+    /// Main columns that must hold the output state, if any
+    output: Option<Vec<usize>>,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> Rescue<F> {
+    /// Permute the state held in the `input` columns on every row
+    pub fn new(params: RescueParams<F>, input: &[usize]) -> Result<Self, AirError> {
+        if input.len() != params.width {
+            return Err(AirError::InvalidConstraint(format!(
+                "Rescue of width {} over {} input columns",
+                params.width,
+                input.len()
+            )));
+        }
+        let mut gadget = Self {
+            params,
+            input: input.to_vec(),
+            output: None,
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        Ok(gadget)
+    }
+
+    /// Also require the `output` main columns to hold the permuted state
+    pub fn with_output(mut self, output: &[usize]) -> Result<Self, AirError> {
+        if output.len() != self.params.width {
+            return Err(AirError::InvalidConstraint(format!(
+                "Rescue of width {} over {} output columns",
+                self.params.width,
+                output.len()
+            )));
+        }
+        self.output = Some(output.to_vec());
+        self.measure();
+        Ok(self)
+    }
+
+    /// Permutation parameters
+    pub fn params(&self) -> &RescueParams<F> {
+        &self.params
+    }
+
+    /// Segment columns holding the permuted state
+    pub fn output_columns(&self) -> Range<usize> {
+        self.width - self.params.width..self.width
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.permute_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Run the permutation of one row
+    fn permute_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let state = self.params.permute_in(circuit, self.input.iter().map(|&index| column(index)).collect());
+        if let Some(output) = &self.output {
+            for (&index, value) in output.iter().zip(state) {
+                circuit.assert_zero(column(index) - value);
+            }
+        }
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Rescue<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.permute_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.permute_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.permute_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Rescue gadget proving the permutation of the `input` columns on every row
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment);
+/// use [`Rescue::with_output`] to also tie the result to main columns.
+pub fn rescue<F: FieldElement + 'static>(
+    params: RescueParams<F>,
+    input: &[usize],
+) -> Result<AuxSegment<F>, AirError> {
+    Rescue::new(params, input).map(AuxSegment::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_rescue_gadget_matches_native_permutation() {
+        assert!(RescueParams::<PrimeField64>::new(3, RESCUE_MIN_ROUNDS - 1).is_err());
+        let params = RescueParams::<PrimeField64>::new(3, RESCUE_MIN_ROUNDS).unwrap();
+        let value = PrimeField64::new;
+        let root = value(12345).pow(params.inverse_alpha);
+        assert_eq!(root.pow(params.alpha()), value(12345));
+        assert_ne!(params.hash(&[value(1), value(2)]), params.hash(&[value(2), value(1)]));
+
+        // Tribonacci columns as the permuted states
+        let transition = TransitionFunction::linear_recurrence(&[value(1), value(1), value(1)]).unwrap();
+        let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, value(1))]);
+        let air = Air::new(vec![], transition.clone(), boundary.clone(), 128)
+            .with_aux_segment(rescue(params.clone(), &[0, 1, 2]).unwrap());
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover.generate_trace(&air, &[value(1), value(2), value(3)], 16).unwrap();
+        let gadget = Rescue::new(params.clone(), &[0, 1, 2]).unwrap();
+        let columns = witness.columns.to_columns();
+        let segment = gadget.build(&columns, &[]);
+        for row in [0, 15] {
+            let mut state: Vec<_> = columns.iter().map(|column| column[row]).collect();
+            params.permute(&mut state);
+            let output: Vec<_> = gadget.output_columns().map(|column| segment[column][row]).collect();
+            assert_eq!(output, state);
+        }
+
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::<PrimeField64>::new(48)
+            .verify_with_air(&air, &proof, &PublicInputs::new())
+            .unwrap();
+
+        // The input state is not its own permutation
+        let fixed_point = Air::new(vec![], transition, boundary, 128)
+            .with_aux_segment(AuxSegment::new(gadget.with_output(&[0, 1, 2]).unwrap()));
+        let error = prover.prove(&fixed_point, &PublicInputs::new(), &witness).unwrap_err();
+        assert!(matches!(error, ProofError::ConstraintError(_)));
+    }
+}
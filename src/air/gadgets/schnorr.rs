@@ -0,0 +1,570 @@
+//! Schnorr Signature Verification
+//!
+//! Proves that a message was signed under a public key, with the key and
+//! the signature kept in the witness. Together with a [`MerklePath`] over
+//! committed keys, an AIR can attest that a transaction was signed by one
+//! of a set of keys without revealing which. The key and the signature stay
+//! private only in a zero-knowledge proof (see
+//! [`StarkProver::with_zk`](crate::proof::StarkProver::with_zk)): any other
+//! proof ships the trace, witness included.
+//!
+//! The curve is a short Weierstrass curve over the trace field itself, so
+//! point arithmetic needs no emulated field and every constraint has degree
+//! two. Schnorr is used rather than ECDSA because verification is a single
+//! linear relation `s·G = R + e·P` between points, with the challenge `e` a
+//! Poseidon hash of the nonce point, the key and the message; ECDSA would
+//! need inversions modulo the group order inside the AIR.
+//!
+//! Points are added with affine formulas, each addition constraining its two
+//! inputs to have distinct `x` coordinates so that the formula is exact.
+//! Both sides of the relation start from a fixed offset point rather than
+//! the identity, which affine coordinates cannot hold; the offset and the
+//! multiples `2^i·G` of the generator are fixed when the parameters are
+//! built. An honest prover only hits equal `x` coordinates with negligible
+//! probability.
+//!
+//! [`EllipticCurve::mersenne61`] is a prime-order curve over
+//! [`PrimeField64`](crate::types::field::PrimeField64). Its discrete
+//! logarithms only take about 2^30 operations, so it shows the construction
+//! rather than protecting real keys; production use needs a curve over a
+//! field of at least 256 bits.
+//!
+//! [`MerklePath`]: super::MerklePath
+
+use crate::prelude::*;
+use super::circuit::RowCircuit;
+use super::poseidon::PoseidonParams;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::field::PrimeField64;
+use crate::types::FieldElement;
+use sha2::{Digest, Sha256};
+
+/// Domain separator for deriving the offset point
+const OFFSET_DOMAIN: &[u8] = b"xfg-stark/schnorr/offset";
+
+/// Domain separator for deriving signing nonces
+const NONCE_DOMAIN: &[u8] = b"xfg-stark/schnorr/nonce";
+
+/// Affine point of an elliptic curve other than the identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurvePoint<F: FieldElement> {
+    /// x coordinate
+    pub x: F,
+    /// y coordinate
+    pub y: F,
+}
+
+impl<F: FieldElement> CurvePoint<F> {
+    /// Point with the given coordinates
+    pub fn new(x: F, y: F) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Short Weierstrass curve `y^2 = x^3 + a·x + b` with a generator of prime order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EllipticCurve<F: FieldElement> {
+    /// Linear coefficient
+    a: F,
+    /// Constant coefficient
+    b: F,
+    /// Generator of the signing group
+    generator: CurvePoint<F>,
+    /// Order of the generator
+    order: u64,
+}
+
+impl<F: FieldElement> EllipticCurve<F> {
+    /// Curve with the given coefficients and generator
+    ///
+    /// The generator must lie on the curve and have the given order, which
+    /// must be below the field modulus so that scalars fit in a column.
+    pub fn new(a: F, b: F, generator: CurvePoint<F>, order: u64) -> Result<Self, AirError> {
+        let four = F::from_canonical_u64(4);
+        let twenty_seven = F::from_canonical_u64(27);
+        if (four * a * a * a + twenty_seven * b * b).is_zero() {
+            return Err(AirError::InvalidConstraint("singular elliptic curve".to_string()));
+        }
+        if order < 2 || order >= F::MODULUS {
+            return Err(AirError::InvalidConstraint(format!(
+                "curve order {} is not between 2 and the field modulus",
+                order
+            )));
+        }
+        let curve = Self { a, b, generator, order };
+        if !curve.contains(&generator) || curve.multiply(&generator, order).is_some() {
+            return Err(AirError::InvalidConstraint(format!(
+                "generator is not a point of order {} on the curve",
+                order
+            )));
+        }
+        Ok(curve)
+    }
+
+    /// Linear coefficient
+    pub fn a(&self) -> F {
+        self.a
+    }
+
+    /// Constant coefficient
+    pub fn b(&self) -> F {
+        self.b
+    }
+
+    /// Generator of the signing group
+    pub fn generator(&self) -> CurvePoint<F> {
+        self.generator
+    }
+
+    /// Order of the generator
+    pub fn order(&self) -> u64 {
+        self.order
+    }
+
+    /// Whether a point lies on the curve
+    pub fn contains(&self, point: &CurvePoint<F>) -> bool {
+        point.y * point.y == (point.x * point.x + self.a) * point.x + self.b
+    }
+
+    /// Sum of two points, with `None` as the identity
+    pub fn add(&self, p: Option<CurvePoint<F>>, q: Option<CurvePoint<F>>) -> Option<CurvePoint<F>> {
+        let (p, q) = match (p, q) {
+            (None, q) => return q,
+            (p, None) => return p,
+            (Some(p), Some(q)) => (p, q),
+        };
+        let slope = if p.x == q.x {
+            if (p.y + q.y).is_zero() {
+                return None;
+            }
+            let three = F::from_canonical_u64(3);
+            (three * p.x * p.x + self.a) * (p.y + p.y).inverse()?
+        } else {
+            (q.y - p.y) * (q.x - p.x).inverse()?
+        };
+        let x = slope * slope - p.x - q.x;
+        Some(CurvePoint::new(x, slope * (p.x - x) - p.y))
+    }
+
+    /// `scalar` times a point, with `None` as the identity
+    pub fn multiply(&self, point: &CurvePoint<F>, scalar: u64) -> Option<CurvePoint<F>> {
+        let mut result = None;
+        let mut power = Some(*point);
+        let mut scalar = scalar;
+        while scalar != 0 {
+            if scalar & 1 == 1 {
+                result = self.add(result, power);
+            }
+            power = self.add(power, power);
+            scalar >>= 1;
+        }
+        result
+    }
+}
+
+impl EllipticCurve<PrimeField64> {
+    /// `y^2 = x^3 + 3·x + 55` over `2^61 - 1`, with prime order and generator `(6, 17)`
+    pub fn mersenne61() -> Self {
+        let value = PrimeField64::new;
+        Self::new(
+            value(3),
+            value(55),
+            CurvePoint::new(value(6), value(17)),
+            2_305_843_008_261_363_359,
+        )
+        .expect("the built-in curve is valid")
+    }
+}
+
+/// Schnorr signature: a nonce point and a response scalar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SchnorrSignature<F: FieldElement> {
+    /// Nonce point `R = k·G`
+    pub r: CurvePoint<F>,
+    /// Response `s = k + e·x` modulo the group order
+    pub s: u64,
+}
+
+/// Parameters of Schnorr signatures over a curve, which also sign and verify natively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchnorrParams<F: FieldElement> {
+    /// Signing curve
+    curve: EllipticCurve<F>,
+    /// Hash deriving the challenge
+    hasher: PoseidonParams<F>,
+    /// Starting point of both sides of the verification relation
+    offset: CurvePoint<F>,
+    /// `2^i·G` for every bit of a response
+    generator_powers: Vec<CurvePoint<F>>,
+}
+
+impl<F: FieldElement> SchnorrParams<F> {
+    /// Schnorr signatures over `curve` with challenges hashed by `hasher`
+    pub fn new(curve: EllipticCurve<F>, hasher: PoseidonParams<F>) -> Self {
+        let digest = Sha256::digest(OFFSET_DOMAIN);
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        let scalar = u64::from_le_bytes(bytes) % (curve.order - 1) + 1;
+        let offset = curve
+            .multiply(&curve.generator, scalar)
+            .expect("a non-zero multiple of the generator");
+
+        let bits = scalar_bits(curve.order);
+        let mut generator_powers = Vec::with_capacity(bits);
+        let mut power = curve.generator;
+        for _ in 0..bits {
+            generator_powers.push(power);
+            power = curve.add(Some(power), Some(power)).expect("the generator has odd order");
+        }
+
+        Self {
+            curve,
+            hasher,
+            offset,
+            generator_powers,
+        }
+    }
+
+    /// Signing curve
+    pub fn curve(&self) -> &EllipticCurve<F> {
+        &self.curve
+    }
+
+    /// Hash deriving the challenge
+    pub fn hasher(&self) -> &PoseidonParams<F> {
+        &self.hasher
+    }
+
+    /// Public key `P = x·G` of a secret scalar `x`
+    pub fn public_key(&self, secret: u64) -> Result<CurvePoint<F>, AirError> {
+        if secret == 0 || secret >= self.curve.order {
+            return Err(AirError::InvalidConstraint(
+                "secret key is not a non-zero scalar below the group order".to_string(),
+            ));
+        }
+        Ok(self
+            .curve
+            .multiply(&self.curve.generator, secret)
+            .expect("a non-zero multiple of the generator"))
+    }
+
+    /// Challenge `e`, the hash of the nonce point, the key and the message
+    pub fn challenge(&self, r: &CurvePoint<F>, key: &CurvePoint<F>, message: F) -> F {
+        self.hasher.hash(&[r.x, r.y, key.x, key.y, message])
+    }
+
+    /// Sign a message with a secret scalar
+    ///
+    /// The nonce is derived from SHA-256 of the secret and the message, so
+    /// signing is deterministic.
+    pub fn sign(&self, secret: u64, message: F) -> Result<SchnorrSignature<F>, AirError> {
+        let key = self.public_key(secret)?;
+        let order = self.curve.order;
+
+        let mut hasher = Sha256::new();
+        hasher.update(NONCE_DOMAIN);
+        hasher.update(secret.to_le_bytes());
+        hasher.update(message.to_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        let nonce = u64::from_le_bytes(bytes) % (order - 1) + 1;
+
+        let r = self.public_key(nonce)?;
+        let challenge = self.challenge(&r, &key, message).as_canonical_u64() % order;
+        let s = (u128::from(nonce) + u128::from(challenge) * u128::from(secret)) % u128::from(order);
+        Ok(SchnorrSignature { r, s: s as u64 })
+    }
+
+    /// Check a signature natively: `s·G = R + e·P`
+    pub fn verify(&self, key: &CurvePoint<F>, message: F, signature: &SchnorrSignature<F>) -> bool {
+        if !self.curve.contains(key) || !self.curve.contains(&signature.r) || signature.s >= self.curve.order {
+            return false;
+        }
+        let challenge = self.challenge(&signature.r, key, message).as_canonical_u64();
+        let left = self.curve.multiply(&self.curve.generator, signature.s);
+        let right = self.curve.add(Some(signature.r), self.curve.multiply(key, challenge));
+        left == right
+    }
+
+    /// Constrain a point given by columns to lie on the curve
+    fn assert_on_curve(&self, circuit: &mut RowCircuit<'_, F>, point: CurvePoint<F>) {
+        let square = circuit.define(point.x * point.x);
+        circuit.assert_zero(point.y * point.y - (square + self.curve.a) * point.x - self.curve.b);
+    }
+
+    /// Sum of two points with distinct `x` coordinates, on a row circuit
+    fn add_in(&self, circuit: &mut RowCircuit<'_, F>, p: CurvePoint<F>, q: CurvePoint<F>) -> CurvePoint<F> {
+        let inverse = circuit.witness(|| (q.x - p.x).inverse().unwrap_or_else(F::zero));
+        circuit.assert_zero((q.x - p.x) * inverse - F::one());
+        let slope = circuit.define((q.y - p.y) * inverse);
+        let x = circuit.define(slope * slope - p.x - q.x);
+        let y = circuit.define(slope * (p.x - x) - p.y);
+        CurvePoint::new(x, y)
+    }
+
+    /// Double of a point on the curve, on a row circuit
+    fn double_in(&self, circuit: &mut RowCircuit<'_, F>, p: CurvePoint<F>) -> CurvePoint<F> {
+        let three = F::from_canonical_u64(3);
+        let tangent = three * p.x * p.x + self.curve.a;
+        let slope = circuit.witness(|| tangent * (p.y + p.y).inverse().unwrap_or_else(F::zero));
+        circuit.assert_zero(slope * (p.y + p.y) - tangent);
+        let x = circuit.define(slope * slope - p.x - p.x);
+        let y = circuit.define(slope * (p.x - x) - p.y);
+        CurvePoint::new(x, y)
+    }
+
+    /// `p` plus `q` if `bit` is set, else `p`, on a row circuit
+    fn add_if(&self, circuit: &mut RowCircuit<'_, F>, bit: F, p: CurvePoint<F>, q: CurvePoint<F>) -> CurvePoint<F> {
+        let sum = self.add_in(circuit, p, q);
+        let x = circuit.define(p.x + bit * (sum.x - p.x));
+        let y = circuit.define(p.y + bit * (sum.y - p.y));
+        CurvePoint::new(x, y)
+    }
+}
+
+/// Schnorr signature verification over main trace columns, proven row by row
+#[derive(Debug, Clone)]
+pub struct Schnorr<F: FieldElement> {
+    /// Signature parameters
+    params: SchnorrParams<F>,
+    /// Main column holding the message
+    message: usize,
+    /// Main columns holding the public key
+    key: [usize; 2],
+    /// Main columns holding the nonce point
+    r: [usize; 2],
+    /// Main column holding the response
+    s: usize,
+    /// Main column that must hold the hash of the public key, if any
+    key_commitment: Option<usize>,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+}
+
+impl<F: FieldElement> Schnorr<F> {
+    /// Verify the signature held in main columns 0 to 5: the message, the
+    /// public key, the nonce point and the response
+    pub fn new(params: SchnorrParams<F>) -> Self {
+        let mut gadget = Self {
+            params,
+            message: 0,
+            key: [1, 2],
+            r: [3, 4],
+            s: 5,
+            key_commitment: None,
+            width: 0,
+            row_constraints: 0,
+        };
+        gadget.measure();
+        gadget
+    }
+
+    /// Read the message, key and signature from other main columns
+    pub fn with_columns(mut self, message: usize, key: [usize; 2], r: [usize; 2], s: usize) -> Self {
+        self.message = message;
+        self.key = key;
+        self.r = r;
+        self.s = s;
+        self
+    }
+
+    /// Also require the `column` main column to hold the hash of the public key
+    ///
+    /// Using that column as the leaf of a [`MerklePath`](super::MerklePath)
+    /// proves that the key belongs to a committed set.
+    pub fn with_key_commitment(mut self, column: usize) -> Self {
+        self.key_commitment = Some(column);
+        self.measure();
+        self
+    }
+
+    /// Signature parameters
+    pub fn params(&self) -> &SchnorrParams<F> {
+        &self.params
+    }
+
+    /// Count the columns and constraints of a row
+    fn measure(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        self.verify_row(&mut circuit, &[]);
+        (self.width, self.row_constraints) = circuit.finish();
+    }
+
+    /// Check the signature of one row
+    fn verify_row(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) {
+        let params = &self.params;
+        let column = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let point = |[x, y]: [usize; 2]| CurvePoint::new(column(x), column(y));
+        let (message, key, r, s) = (column(self.message), point(self.key), point(self.r), column(self.s));
+
+        params.assert_on_curve(circuit, key);
+        params.assert_on_curve(circuit, r);
+        let challenge = params.hasher.hash_in(circuit, &[r.x, r.y, key.x, key.y, message]);
+        if let Some(commitment) = self.key_commitment {
+            let hash = params.hasher.hash_in(circuit, &[key.x, key.y]);
+            circuit.assert_zero(column(commitment) - hash);
+        }
+
+        // s·G + offset, from the fixed multiples of the generator
+        let s_bits = decompose(circuit, s, params.generator_powers.len());
+        let mut left = params.offset;
+        for (&bit, &power) in s_bits.iter().zip(&params.generator_powers) {
+            left = params.add_if(circuit, bit, left, power);
+        }
+
+        // R + e·P + offset, doubling the key along the way
+        let e_bits = decompose(circuit, challenge, scalar_bits(F::MODULUS));
+        let mut right = params.add_in(circuit, params.offset, r);
+        let mut power = key;
+        for (level, &bit) in e_bits.iter().enumerate() {
+            right = params.add_if(circuit, bit, right, power);
+            if level + 1 < e_bits.len() {
+                power = params.double_in(circuit, power);
+            }
+        }
+
+        circuit.assert_zero(left.x - right.x);
+        circuit.assert_zero(left.y - right.y);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Schnorr<F> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.verify_row(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.verify_row(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.verify_row(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// Schnorr gadget proving that main columns 0 to 5 hold a message signed under a key
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment);
+/// the columns hold the message, the public key, the nonce point and the
+/// response, and [`Schnorr::with_columns`] reads them from elsewhere.
+pub fn schnorr<F: FieldElement + 'static>(params: SchnorrParams<F>) -> AuxSegment<F> {
+    AuxSegment::new(Schnorr::new(params))
+}
+
+/// Number of bits of the largest value below `bound`
+fn scalar_bits(bound: u64) -> usize {
+    (u64::BITS - (bound - 1).leading_zeros()) as usize
+}
+
+/// Witness bits of a value, least significant first, constrained to recompose it
+fn decompose<F: FieldElement>(circuit: &mut RowCircuit<'_, F>, value: F, bits: usize) -> Vec<F> {
+    let bits: Vec<F> = (0..bits)
+        .map(|bit| circuit.bit(|| (value.as_canonical_u64() >> bit) & 1 == 1))
+        .collect();
+    let packed = bits.iter().rev().fold(F::zero(), |sum, &bit| sum + sum + bit);
+    circuit.assert_zero(value - packed);
+    bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ColIdx, RowIdx};
+    use crate::air::gadgets::{MerkleHasher, MerklePath};
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+
+    #[test]
+    fn test_schnorr_signature_from_key_set() {
+        let value = PrimeField64::new;
+        let hasher = PoseidonParams::<PrimeField64>::new(3, 8, 22).unwrap();
+        let params = SchnorrParams::new(EllipticCurve::mersenne61(), hasher.clone());
+        let message = value(1000);
+        let secret = 123_456_789;
+        let key = params.public_key(secret).unwrap();
+        let signature = params.sign(secret, message).unwrap();
+        assert!(params.verify(&key, message, &signature));
+        assert!(!params.verify(&key, message + value(1), &signature));
+
+        // The signer's key is leaf 2 of a committed set of four keys
+        let merkle = MerkleHasher::from(hasher.clone());
+        let leaves: Vec<_> = [11, 22, secret, 44]
+            .iter()
+            .map(|&secret| {
+                let key = params.public_key(secret).unwrap();
+                hasher.hash(&[key.x, key.y])
+            })
+            .collect();
+        let siblings = [leaves[3], merkle.compress(leaves[0], leaves[1])];
+        let root = merkle.root(leaves[2], 2, &siblings);
+        let upper = [merkle.compress(leaves[0], leaves[1]), merkle.compress(leaves[2], leaves[3])];
+        assert_eq!(root, merkle.compress(upper[0], upper[1]));
+
+        // Constant columns `[message, key, R, s, leaf, index, root, siblings]`
+        let signature_gadget = Schnorr::new(params.clone()).with_key_commitment(6);
+        let membership = MerklePath::new(2, hasher).unwrap().with_columns(6, 7, 8, &[9, 10]).unwrap();
+        let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, message)]);
+        let air = Air::new(vec![], TransitionFunction::identity(11), boundary, 128)
+            .with_aux_segment(AuxSegment::new(signature_gadget))
+            .with_aux_segment(AuxSegment::new(membership));
+        let initial = |s: u64| {
+            vec![
+                message,
+                key.x,
+                key.y,
+                signature.r.x,
+                signature.r.y,
+                value(s),
+                leaves[2],
+                value(2),
+                root,
+                siblings[0],
+                siblings[1],
+            ]
+        };
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let public_inputs =
+            PublicInputs::new().assert(ColIdx(0), RowIdx(0), message).assert(ColIdx(8), RowIdx(0), root);
+        let witness = prover.generate_trace(&air, &initial(signature.s), 8).unwrap();
+        let proof = prover.clone().with_zk(true).prove(&air, &public_inputs, &witness).unwrap();
+        assert!(proof.trace.columns.is_empty() && proof.trace.aux_segments.is_empty());
+        verifier.verify_with_air(&air, &proof, &public_inputs).unwrap();
+
+        // The proof is bound to the message, and a forged response does not verify
+        let other_message = PublicInputs::new()
+            .assert(ColIdx(0), RowIdx(0), message + value(1))
+            .assert(ColIdx(8), RowIdx(0), root);
+        assert!(verifier.verify_with_air(&air, &proof, &other_message).is_err());
+        let forged = prover.generate_trace(&air, &initial(signature.s + 1), 8).unwrap();
+        assert!(prover.prove(&air, &public_inputs, &forged).is_err());
+    }
+}
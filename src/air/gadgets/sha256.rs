@@ -0,0 +1,376 @@
+//! SHA-256 Compression
+//!
+//! Proves the SHA-256 compression function on every row: main trace columns
+//! hold the eight chaining words and the sixteen message words of a block,
+//! as field elements below `2^32`, and the segment computes the next
+//! chaining value. Chaining blocks from the initial hash value proves a
+//! SHA-256 digest, such as a Bitcoin-style commitment.
+//!
+//! The computation is carried out on bits. Every input word is decomposed
+//! into boolean columns; rotations and shifts only reorder bits. XOR and the
+//! choice and majority functions are products of bits, and the last XOR of
+//! each sigma function is folded into the addition that consumes it.
+//! Additions modulo `2^32` decompose the sum of their operands into the bits
+//! of the result and of the carry.
+//!
+//! XOR is constrained bitwise rather than through a lookup table: the
+//! multiplicities of a lookup would have to be main trace columns, which the
+//! main transitions cannot produce for arbitrary messages.
+
+use crate::prelude::*;
+use super::circuit::RowCircuit;
+use crate::air::{AirError, AuxFrame, AuxSegment, AuxTraceSegment};
+use crate::types::FieldElement;
+use core::ops::Range;
+
+/// SHA-256 initial hash value
+pub const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// SHA-256 round constants
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// 32-bit word as bit values, least significant first
+type Word<F> = [F; 32];
+
+/// SHA-256 compression of main trace columns, proven row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sha256Compression {
+    /// Main columns holding the chaining words
+    state: Vec<usize>,
+    /// Main columns holding the message words
+    block: Vec<usize>,
+    /// Main columns that must hold the next chaining words, if any
+    output: Option<Vec<usize>>,
+    /// Segment columns
+    width: usize,
+    /// Constraints per row
+    row_constraints: usize,
+    /// Segment columns of the next chaining words
+    output_columns: Range<usize>,
+}
+
+impl Sha256Compression {
+    /// Compress the `block` of sixteen message words into the `state` of eight chaining words
+    pub fn new(state: &[usize], block: &[usize]) -> Result<Self, AirError> {
+        if state.len() != 8 || block.len() != 16 {
+            return Err(AirError::InvalidConstraint(format!(
+                "SHA-256 compresses 16 message words into 8 chaining words, got {} and {}",
+                block.len(),
+                state.len()
+            )));
+        }
+        let mut gadget = Self {
+            state: state.to_vec(),
+            block: block.to_vec(),
+            output: None,
+            width: 0,
+            row_constraints: 0,
+            output_columns: 0..0,
+        };
+        gadget.measure::<crate::types::field::PrimeField64>();
+        Ok(gadget)
+    }
+
+    /// Also require the `output` main columns to hold the next chaining words
+    pub fn with_output(mut self, output: &[usize]) -> Result<Self, AirError> {
+        if output.len() != 8 {
+            return Err(AirError::InvalidConstraint(format!(
+                "SHA-256 outputs 8 chaining words, got {} columns",
+                output.len()
+            )));
+        }
+        self.output = Some(output.to_vec());
+        self.measure::<crate::types::field::PrimeField64>();
+        Ok(self)
+    }
+
+    /// Segment columns holding the bits of the next chaining words
+    ///
+    /// Each word takes 32 columns, least significant bit first, followed by
+    /// a carry column.
+    pub fn output_columns(&self) -> Range<usize> {
+        self.output_columns.clone()
+    }
+
+    /// Count the columns and constraints of a row
+    ///
+    /// The shape of the computation does not depend on the field or the
+    /// values, so a run on zeros measures it.
+    fn measure<F: FieldElement>(&mut self) {
+        let mut values = Vec::new();
+        let mut circuit = RowCircuit::build(&mut values);
+        let output = self.compress(&mut circuit, &[F::zero()]);
+        (self.width, self.row_constraints) = circuit.finish();
+        self.output_columns = output;
+    }
+
+    /// Run the compression of one row, returning the segment columns of the output
+    fn compress<F: FieldElement>(&self, circuit: &mut RowCircuit<'_, F>, main: &[F]) -> Range<usize> {
+        let main_value = |index: usize| main.get(index).copied().unwrap_or_else(F::zero);
+        let mut words = |columns: &[usize]| -> Vec<Word<F>> {
+            columns.iter().map(|&index| decompose(circuit, main_value(index))).collect()
+        };
+        let state = words(&self.state);
+        let mut schedule = words(&self.block);
+        for t in 16..64 {
+            let s0 = pack(&small_sigma(circuit, &schedule[t - 15], 7, 18, 3));
+            let s1 = pack(&small_sigma(circuit, &schedule[t - 2], 17, 19, 10));
+            let word = add(circuit, &[s1, pack(&schedule[t - 7]), s0, pack(&schedule[t - 16])]);
+            schedule.push(word);
+        }
+
+        let mut working = state.clone();
+        for (t, word) in schedule.iter().enumerate() {
+            let [a, b, c, d, e, f, g, h] = &working[..] else {
+                unreachable!("eight working words")
+            };
+            let s1 = pack(&big_sigma(circuit, e, 6, 11, 25));
+            let choice = pack(&choose(e, f, g));
+            let s0 = pack(&big_sigma(circuit, a, 2, 13, 22));
+            let majority = pack(&majority(circuit, a, b, c));
+            let k = F::from_canonical_u64(u64::from(ROUND_CONSTANTS[t]));
+            let t1 = [pack(h), s1, choice, k, pack(word)];
+            let new_e = add(circuit, &[&t1[..], &[pack(d)]].concat());
+            let new_a = add(circuit, &[&t1[..], &[s0, majority]].concat());
+            working = vec![new_a, *a, *b, *c, new_e, *e, *f, *g];
+        }
+
+        let start = circuit.column();
+        let outputs: Vec<Word<F>> = state
+            .iter()
+            .zip(&working)
+            .map(|(input, word)| add(circuit, &[pack(input), pack(word)]))
+            .collect();
+        let end = circuit.column();
+        if let Some(output) = &self.output {
+            for (&index, word) in output.iter().zip(&outputs) {
+                circuit.assert_zero(main_value(index) - pack(word));
+            }
+        }
+        start..end
+    }
+}
+
+/// Decompose a value below `2^32` into constrained bits
+fn decompose<F: FieldElement>(circuit: &mut RowCircuit<'_, F>, value: F) -> Word<F> {
+    let mut bits: Word<F> = core::array::from_fn(|_| F::zero());
+    for (index, bit) in bits.iter_mut().enumerate() {
+        *bit = circuit.bit(|| (value.as_canonical_u64() >> index) & 1 == 1);
+    }
+    circuit.assert_zero(value - pack(&bits));
+    bits
+}
+
+/// Value of a word
+fn pack<F: FieldElement>(word: &Word<F>) -> F {
+    let two = F::from_canonical_u64(2);
+    word.iter().rev().fold(F::zero(), |sum, &bit| sum * two + bit)
+}
+
+/// Word rotated right by `amount` bits
+fn rotate<F: FieldElement>(word: &Word<F>, amount: usize) -> Word<F> {
+    core::array::from_fn(|index| word[(index + amount) % 32])
+}
+
+/// Word shifted right by `amount` bits
+fn shift<F: FieldElement>(word: &Word<F>, amount: usize) -> Word<F> {
+    core::array::from_fn(|index| word.get(index + amount).copied().unwrap_or_else(F::zero))
+}
+
+/// Bitwise XOR as an expression of degree two
+fn xor<F: FieldElement>(left: &Word<F>, right: &Word<F>) -> Word<F> {
+    let two = F::from_canonical_u64(2);
+    core::array::from_fn(|index| left[index] + right[index] - two * left[index] * right[index])
+}
+
+/// Bitwise XOR in new columns
+fn xor_columns<F: FieldElement>(circuit: &mut RowCircuit<'_, F>, left: &Word<F>, right: &Word<F>) -> Word<F> {
+    let mut word = xor(left, right);
+    for bit in word.iter_mut() {
+        *bit = circuit.define(*bit);
+    }
+    word
+}
+
+/// `Σ(x) = ROTR^r1(x) ^ ROTR^r2(x) ^ ROTR^r3(x)`, of degree two
+fn big_sigma<F: FieldElement>(
+    circuit: &mut RowCircuit<'_, F>,
+    x: &Word<F>,
+    r1: usize,
+    r2: usize,
+    r3: usize,
+) -> Word<F> {
+    let partial = xor_columns(circuit, &rotate(x, r1), &rotate(x, r2));
+    xor(&partial, &rotate(x, r3))
+}
+
+/// `σ(x) = ROTR^r1(x) ^ ROTR^r2(x) ^ SHR^s(x)`, of degree two
+fn small_sigma<F: FieldElement>(
+    circuit: &mut RowCircuit<'_, F>,
+    x: &Word<F>,
+    r1: usize,
+    r2: usize,
+    s: usize,
+) -> Word<F> {
+    let partial = xor_columns(circuit, &rotate(x, r1), &rotate(x, r2));
+    xor(&partial, &shift(x, s))
+}
+
+/// `Ch(e, f, g) = (e & f) ^ (!e & g)`, of degree two
+fn choose<F: FieldElement>(e: &Word<F>, f: &Word<F>, g: &Word<F>) -> Word<F> {
+    core::array::from_fn(|index| g[index] + e[index] * (f[index] - g[index]))
+}
+
+/// `Maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c)`, of degree two
+///
+/// Equals `a·b + c·(a ^ b)`, with `a ^ b` in new columns.
+fn majority<F: FieldElement>(circuit: &mut RowCircuit<'_, F>, a: &Word<F>, b: &Word<F>, c: &Word<F>) -> Word<F> {
+    let either = xor_columns(circuit, a, b);
+    core::array::from_fn(|index| a[index] * b[index] + c[index] * either[index])
+}
+
+/// Sum of word values modulo `2^32`, decomposed into result and carry bits
+fn add<F: FieldElement>(circuit: &mut RowCircuit<'_, F>, terms: &[F]) -> Word<F> {
+    let sum = terms.iter().fold(F::zero(), |sum, &term| sum + term);
+    let mut result: Word<F> = core::array::from_fn(|_| F::zero());
+    for (index, bit) in result.iter_mut().enumerate() {
+        *bit = circuit.bit(|| (sum.as_canonical_u64() >> index) & 1 == 1);
+    }
+    let carry_bits = (usize::BITS - (terms.len() - 1).leading_zeros()) as usize;
+    let two = F::from_canonical_u64(2);
+    let mut carry = F::zero();
+    let mut weight = F::from_canonical_u64(1 << 32);
+    for index in 0..carry_bits {
+        carry += weight * circuit.bit(|| (sum.as_canonical_u64() >> (32 + index)) & 1 == 1);
+        weight *= two;
+    }
+    circuit.assert_zero(sum - pack(&result) - carry);
+    result
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for Sha256Compression {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn num_challenges(&self) -> usize {
+        0
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * self.row_constraints
+    }
+
+    fn build(&self, main: &[Vec<F>], _challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut columns = vec![Vec::with_capacity(length); self.width];
+        let mut values = Vec::with_capacity(self.width);
+        for row in 0..length {
+            let main_row: Vec<F> = main.iter().map(|column| column[row]).collect();
+            values.clear();
+            self.compress(&mut RowCircuit::build(&mut values), &main_row);
+            for (column, &value) in columns.iter_mut().zip(&values) {
+                column.push(value);
+            }
+        }
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, _challenges: &[F]) -> Vec<F> {
+        let mut residuals = Vec::with_capacity(2 * self.row_constraints);
+        self.compress(&mut RowCircuit::check(frame.aux_current, &mut residuals), frame.main_current);
+        self.compress(&mut RowCircuit::check(frame.aux_next, &mut residuals), frame.main_next);
+        residuals
+    }
+}
+
+/// SHA-256 gadget proving the compression of the `block` columns into the
+/// `state` columns on every row
+///
+/// Returns the segment to pass to [`Air::with_aux_segment`](crate::air::Air::with_aux_segment);
+/// use [`Sha256Compression::with_output`] to also tie the result to main columns.
+pub fn sha256_compression<F: FieldElement>(
+    state: &[usize],
+    block: &[usize],
+) -> Result<AuxSegment<F>, AirError> {
+    Sha256Compression::new(state, block).map(AuxSegment::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+    use sha2::{Digest, Sha256};
+
+    #[test]
+    fn test_sha256_gadget_matches_digest() {
+        // The padded single block of "abc"
+        let mut block = [0u32; 16];
+        block[0] = 0x6162_6380;
+        block[15] = 24;
+        let digest = Sha256::digest(b"abc");
+        let expected: Vec<u32> = digest
+            .chunks(4)
+            .map(|chunk| u32::from_be_bytes(chunk.try_into().unwrap()))
+            .collect();
+
+        // Constant columns `[state, block, digest]`
+        let air = |digest: &[u32]| {
+            let initial: Vec<_> = SHA256_INITIAL_STATE
+                .iter()
+                .chain(&block)
+                .chain(digest)
+                .map(|&word| PrimeField64::new(u64::from(word)))
+                .collect();
+            let gadget = Sha256Compression::new(&Vec::from_iter(0..8), &Vec::from_iter(8..24))
+                .unwrap()
+                .with_output(&Vec::from_iter(24..32))
+                .unwrap();
+            let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, initial[0])]);
+            let air = Air::new(vec![], TransitionFunction::identity(32), boundary, 128)
+                .with_aux_segment(AuxSegment::new(gadget.clone()));
+            (air, gadget, initial)
+        };
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let (valid, gadget, initial) = air(&expected);
+        let witness = prover.generate_trace(&valid, &initial, 8).unwrap();
+        let segment = AuxTraceSegment::<PrimeField64>::build(&gadget, &witness.columns.to_columns(), &[]);
+        let outputs: Vec<u32> = gadget
+            .output_columns()
+            .collect::<Vec<_>>()
+            .chunks(33)
+            .map(|word| {
+                let bits = word[..32].iter().rev().map(|&column| segment[column][0].as_canonical_u64() as u32);
+                bits.fold(0, |sum, bit| (sum << 1) | bit)
+            })
+            .collect();
+        assert_eq!(outputs, expected);
+
+        let proof = prover.prove(&valid, &PublicInputs::new(), &witness).unwrap();
+        StarkVerifier::<PrimeField64>::new(48)
+            .verify_with_air(&valid, &proof, &PublicInputs::new())
+            .unwrap();
+
+        let mut wrong = expected.clone();
+        wrong[7] ^= 1;
+        let (invalid, _, initial) = air(&wrong);
+        let witness = prover.generate_trace(&invalid, &initial, 8).unwrap();
+        let error = prover.prove(&invalid, &PublicInputs::new(), &witness).unwrap_err();
+        assert!(matches!(error, ProofError::ConstraintError(_)));
+    }
+}
@@ -0,0 +1,243 @@
+//! LogUp Lookup Arguments
+//!
+//! A lookup argument proves that every value of some trace columns appears
+//! in a table column. With the LogUp argument the prover commits to a
+//! multiplicity column `m` counting how often each table row is looked up,
+//! and after a challenge `α` is drawn proves the identity
+//!
+//! `Σ_i Σ_k 1 / (α - v_k[i]) = Σ_i m[i] / (α - t[i])`
+//!
+//! over all rows `i` and looked-up columns `v_k`. The identity holds for a
+//! random `α` only if the multisets agree.
+//!
+//! The table, the multiplicities and the looked-up values are main trace
+//! columns, so they are committed before `α` is drawn; the table must be
+//! pinned down by the main constraints like any other column. A
+//! [`LogUpLookup`] adds an auxiliary segment with the running sum and its
+//! helper columns, whose constraints have degree two:
+//!
+//! - `h_k = 1 / (α - v_k)` and `g = m / (α - t)` on every row
+//! - `z = s + Σ_k h_k - g` on every row, where `z` is the sum up to and
+//!   including a row and `s` the sum before it
+//! - `s` is zero on the first row, the next `s` is the current `z`, and `z`
+//!   is zero on the last row
+
+use crate::prelude::*;
+use crate::air::{Air, AuxFrame, AuxSegment, AuxTraceSegment, BoundaryConstraint};
+use crate::types::FieldElement;
+
+/// LogUp lookup of main trace columns into a table column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogUpLookup {
+    /// Main columns whose values are looked up
+    values: Vec<usize>,
+    /// Main column holding the table
+    table: usize,
+    /// Main column holding the multiplicity of each table row
+    multiplicities: usize,
+}
+
+impl LogUpLookup {
+    /// Look up the values of the `values` columns in the `table` column
+    ///
+    /// The `multiplicities` column must count, for every table row, how
+    /// often its value occurs in the looked-up columns (see
+    /// [`count_multiplicities`](Self::count_multiplicities)). A value
+    /// occurring in several table rows may be counted on any of them.
+    pub fn new(values: &[usize], table: usize, multiplicities: usize) -> Self {
+        Self {
+            values: values.to_vec(),
+            table,
+            multiplicities,
+        }
+    }
+
+    /// Main columns whose values are looked up
+    pub fn values(&self) -> &[usize] {
+        &self.values
+    }
+
+    /// Main column holding the table
+    pub fn table(&self) -> usize {
+        self.table
+    }
+
+    /// Main column holding the multiplicities
+    pub fn multiplicities(&self) -> usize {
+        self.multiplicities
+    }
+
+    /// Multiplicity column for the given looked-up values and table
+    ///
+    /// Occurrences are counted on the first table row holding the value.
+    /// Values missing from the table are not counted, which leaves the
+    /// lookup unsatisfied.
+    pub fn count_multiplicities<F: FieldElement>(values: &[&[F]], table: &[F]) -> Vec<F> {
+        let mut first_row = alloc::collections::BTreeMap::new();
+        for (row, value) in table.iter().enumerate() {
+            first_row.entry(value.to_bytes()).or_insert(row);
+        }
+        let mut counts = vec![0u64; table.len()];
+        for value in values.iter().flat_map(|column| column.iter()) {
+            if let Some(&row) = first_row.get(&value.to_bytes()) {
+                counts[row] += 1;
+            }
+        }
+        counts.into_iter().map(F::from_canonical_u64).collect()
+    }
+
+    /// Segment column of the running sum before each row
+    fn before(&self) -> usize {
+        self.values.len() + 1
+    }
+
+    /// Segment column of the running sum including each row
+    fn including(&self) -> usize {
+        self.values.len() + 2
+    }
+
+    /// Residuals of the per-row constraints of one row
+    fn row_residuals<F: FieldElement>(&self, main: &[F], aux: &[F], alpha: F, residuals: &mut Vec<F>) {
+        let value = |column: usize| main.get(column).copied().unwrap_or_else(F::zero);
+        let helper = |column: usize| aux.get(column).copied().unwrap_or_else(F::zero);
+        let mut sum = F::zero();
+        for (index, &column) in self.values.iter().enumerate() {
+            residuals.push(helper(index) * (alpha - value(column)) - F::one());
+            sum += helper(index);
+        }
+        let table_term = helper(self.values.len());
+        residuals.push(table_term * (alpha - value(self.table)) - value(self.multiplicities));
+        residuals.push(helper(self.including()) - helper(self.before()) - sum + table_term);
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for LogUpLookup {
+    fn width(&self) -> usize {
+        self.values.len() + 3
+    }
+
+    fn num_challenges(&self) -> usize {
+        1
+    }
+
+    fn num_constraints(&self) -> usize {
+        2 * (self.values.len() + 2) + 1
+    }
+
+    fn build(&self, main: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>> {
+        let alpha = challenges[0];
+        let length = main.first().map_or(0, Vec::len);
+        // A zero denominator has no inverse; its row then fails the constraints
+        let inverse = |value: F| (alpha - value).inverse().unwrap_or_else(F::zero);
+
+        let mut columns: Vec<Vec<F>> = self
+            .values
+            .iter()
+            .map(|&column| main[column].iter().map(|&value| inverse(value)).collect())
+            .collect();
+        columns.push(
+            main[self.table]
+                .iter()
+                .zip(&main[self.multiplicities])
+                .map(|(&value, &multiplicity)| multiplicity * inverse(value))
+                .collect(),
+        );
+
+        let mut before = Vec::with_capacity(length);
+        let mut including = Vec::with_capacity(length);
+        let mut sum = F::zero();
+        for row in 0..length {
+            before.push(sum);
+            for helper in &columns[..self.values.len()] {
+                sum += helper[row];
+            }
+            sum -= columns[self.values.len()][row];
+            including.push(sum);
+        }
+        columns.push(before);
+        columns.push(including);
+        columns
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, challenges: &[F]) -> Vec<F> {
+        let alpha = challenges[0];
+        let mut residuals = Vec::with_capacity(AuxTraceSegment::<F>::num_constraints(self));
+        self.row_residuals(frame.main_current, frame.aux_current, alpha, &mut residuals);
+        self.row_residuals(frame.main_next, frame.aux_next, alpha, &mut residuals);
+        let column = |row: &[F], index: usize| row.get(index).copied().unwrap_or_else(F::zero);
+        residuals.push(column(frame.aux_next, self.before()) - column(frame.aux_current, self.including()));
+        residuals
+    }
+
+    fn boundary(&self, _challenges: &[F]) -> Vec<BoundaryConstraint<F>> {
+        vec![
+            BoundaryConstraint::initial(self.before(), F::zero()),
+            BoundaryConstraint::final_condition(self.including(), F::zero()),
+        ]
+    }
+}
+
+impl<F: FieldElement> Air<F> {
+    /// Add a LogUp lookup, proven in a new auxiliary segment
+    pub fn with_lookup(self, lookup: LogUpLookup) -> Self {
+        self.with_aux_segment(AuxSegment::new(lookup))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    /// Columns `[one, table, values, multiplicities]`: the table counts up
+    /// from zero, the values count up from `start` and every multiplicity is
+    /// `multiplicity`
+    fn lookup_air(start: u64, multiplicity: u64) -> Air<PrimeField64> {
+        let (zero, one) = (PrimeField64::new(0), PrimeField64::new(1));
+        let transition = TransitionFunction::linear(vec![
+            vec![one, zero, zero, zero],
+            vec![one, one, zero, zero],
+            vec![one, zero, one, zero],
+            vec![zero, zero, zero, one],
+        ]);
+        let boundary = BoundaryConditions::new(vec![
+            BoundaryConstraint::initial(0, one),
+            BoundaryConstraint::initial(1, zero),
+            BoundaryConstraint::initial(2, PrimeField64::new(start)),
+            BoundaryConstraint::initial(3, PrimeField64::new(multiplicity)),
+        ]);
+        Air::new(vec![], transition, boundary, 128).with_lookup(LogUpLookup::new(&[2], 1, 3))
+    }
+
+    #[test]
+    fn test_logup_lookup() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let prove = |air: &Air<PrimeField64>, start: u64, multiplicity: u64| {
+            let initial = [1, 0, start, multiplicity].map(PrimeField64::new);
+            let witness = prover.generate_trace(air, &initial, 16).unwrap();
+            (witness.clone(), prover.prove(air, &PublicInputs::new(), &witness))
+        };
+
+        let air = lookup_air(0, 1);
+        let (witness, proof) = prove(&air, 0, 1);
+        verifier.verify_with_air(&air, &proof.unwrap(), &PublicInputs::new()).unwrap();
+        let columns = witness.columns.to_columns();
+        assert_eq!(LogUpLookup::count_multiplicities(&[&columns[2]], &columns[1]), columns[3]);
+
+        // Values outside the table, or wrong multiplicities, fail the running sum
+        for (start, multiplicity) in [(1, 1), (0, 2)] {
+            let air = lookup_air(start, multiplicity);
+            assert!(matches!(prove(&air, start, multiplicity).1, Err(ProofError::ConstraintError(_))));
+
+            let unchecked = prover.clone().with_trace_check(false);
+            let initial = [1, 0, start, multiplicity].map(PrimeField64::new);
+            let witness = unchecked.generate_trace(&air, &initial, 16).unwrap();
+            let proof = unchecked.prove(&air, &PublicInputs::new(), &witness).unwrap();
+            assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+        }
+    }
+}
@@ -11,27 +11,72 @@
 //! - **Constraint Evaluation**: Efficient constraint checking
 //! - **Degree Analysis**: Constraint degree computation for FRI
 //! - **Security Validation**: Cryptographic security properties
-
-use crate::types::{FieldElement, StarkComponent, TypeError};
-use std::fmt::{Display, Formatter};
+//! - **Auxiliary Segments**: Challenge-dependent trace columns for permutation and lookup arguments
+//! - **Symbolic Builder**: Constraints written as expressions over named columns (see [`builder`])
+//! - **Witness Checking**: The failing constraint, row and value of a trace that violates the AIR
+//! - **Lookup Arguments**: LogUp lookups of trace columns into a table column
+//! - **Permutation Arguments**: Grand-product multiset equality between column sets
+//! - **Gadgets**: Reusable constraints such as range checks (see [`gadgets`])
+//! - **Composition**: Sub-AIRs laid out side by side in one trace (see [`compose`])
+//! - **Examples**: Complete AIRs with witness generation and public inputs (see [`examples`])
+//! - **XFG Statements**: Burn proofs over Fuego transactions (see [`xfg`])
+//! - **Serialization**: Binary and serde encodings of an AIR, with a canonical digest binding it into proofs
+//! - **Preprocessed Columns**: Registers fixed by the AIR, such as ROMs and tables (see [`fixed`])
+//! - **Virtual Machines**: A minimal VM with a program ROM, as a template for application VMs (see [`vm`])
+//! - **Test Helpers**: Constraint and mutation checks of a trace without the prover (see [`testing`])
+
+use crate::prelude::*;
+use crate::proof::public_inputs::{PublicInputCell, PublicInputs};
+use crate::types::encoding::{decode_from_slice, encode_to_vec, ByteReader, ByteWriter, Encode};
+use crate::types::{Digest, ExtensionElement, FieldElement, StarkComponent, StarkError, TypeError};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use core::fmt::{Display, Formatter};
 
 pub mod constraints;
 pub mod transitions;
 pub mod boundaries;
 pub mod evaluation;
 pub mod security;
+pub mod auxiliary;
+pub mod check;
+pub mod builder;
+pub mod lookup;
+pub mod permutation;
+pub mod gadgets;
+pub mod compose;
+pub mod examples;
+pub mod xfg;
+pub mod fixed;
+pub mod vm;
+pub mod testing;
+#[cfg(test)]
+pub(crate) mod fixtures;
 
 pub use constraints::*;
 pub use transitions::*;
 pub use boundaries::*;
 pub use evaluation::*;
 pub use security::*;
+pub use auxiliary::*;
+pub use check::*;
+pub use compose::*;
+pub use lookup::*;
+pub use permutation::*;
+pub use fixed::FixedColumn;
+
+/// Domain separator of the AIR digest
+const AIR_DIGEST_TAG: &[u8] = b"xfg-stark/air-digest/v2";
+
+/// Domain separator of the commitment to the fixed columns
+const FIXED_COMMITMENT_TAG: &[u8] = b"xfg-stark/fixed-columns/v1";
 
 /// AIR (Algebraic Intermediate Representation) for STARK proofs
 /// 
 /// AIR defines the algebraic constraints that a computation must satisfy
 /// to be proven correct using STARK proofs.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Air<F: FieldElement> {
     /// Constraint polynomials defining the computation
     pub constraints: Vec<Constraint<F>>,
@@ -45,6 +90,32 @@ pub struct Air<F: FieldElement> {
     pub field_extension_degree: u32,
     /// Constraint degree bound
     pub max_constraint_degree: usize,
+    /// Auxiliary trace segments, built in order after the main segment
+    #[serde(with = "segment_shapes")]
+    pub aux_segments: Vec<AuxSegment<F>>,
+    /// Cells the public input values are asserted at, in order
+    pub public_inputs: Vec<PublicInputCell>,
+    /// Registers whose next value the transition matrix leaves unconstrained
+    ///
+    /// Their transitions are enforced by auxiliary segments instead.
+    pub free_registers: Vec<usize>,
+    /// Registers whose values are fixed by the AIR
+    pub fixed_columns: Vec<FixedColumn<F>>,
+    /// Names of the registers, in register order
+    ///
+    /// Only used to describe the AIR; serialized with it but not digested.
+    #[serde(default)]
+    pub register_names: Vec<String>,
+    /// Next-state function generating the trace instead of the transition matrix
+    ///
+    /// Only used to generate witnesses; not serialized or digested.
+    #[serde(skip)]
+    pub step: Option<DynTransition<F>>,
+    /// Expressions the AIR was built from, if built with [`AirBuilder`](builder::AirBuilder)
+    ///
+    /// Only used to explain violations; not serialized or digested.
+    #[serde(skip)]
+    pub symbolic: Option<builder::SymbolicAir<F>>,
 }
 
 impl<F: FieldElement> Air<F> {
@@ -68,9 +139,107 @@ impl<F: FieldElement> Air<F> {
             security_parameter,
             field_extension_degree: 1, // Default to base field
             max_constraint_degree,
+            aux_segments: Vec::new(),
+            public_inputs: Vec::new(),
+            free_registers: Vec::new(),
+            fixed_columns: Vec::new(),
+            register_names: Vec::new(),
+            step: None,
+            symbolic: None,
         }
     }
 
+    /// Leave the next values of `registers` unconstrained by the transition matrix
+    ///
+    /// The AIR must constrain them in an auxiliary segment; their rows of the
+    /// matrix are ignored. Free registers are part of the AIR digest.
+    pub fn with_free_registers(mut self, registers: &[usize]) -> Self {
+        self.free_registers = registers.to_vec();
+        self
+    }
+
+    /// Fix the values of a register
+    ///
+    /// The register is left free by the transition matrix and holds the
+    /// column's values in every trace generated for the AIR. The values are
+    /// part of the AIR digest.
+    pub fn with_fixed_column(mut self, column: FixedColumn<F>) -> Self {
+        self.fixed_columns.push(column);
+        self
+    }
+
+    /// Name the registers, in register order
+    ///
+    /// Names are used by [`Display`], [`describe_violation`](Self::describe_violation)
+    /// and [`explain_violation`](Self::explain_violation) in place of register
+    /// indices. They are not part of the AIR digest and never enter a proof.
+    pub fn with_register_names(mut self, names: &[&str]) -> Self {
+        self.register_names = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Name of a register, if it has one
+    pub fn register_name(&self, register: usize) -> Option<&str> {
+        self.register_names.get(register).map(String::as_str)
+    }
+
+    /// Register with the given name
+    pub fn register(&self, name: &str) -> Option<usize> {
+        self.register_names.iter().position(|register| register == name)
+    }
+
+    /// Generate traces by applying `step` to each state instead of the transition matrix
+    ///
+    /// `step` must return the full next state. Registers it computes by
+    /// anything but the transition matrix must be free (see
+    /// [`with_free_registers`](Self::with_free_registers)).
+    pub fn with_step(mut self, step: DynTransition<F>) -> Self {
+        self.step = Some(step);
+        self
+    }
+
+    /// Declare the cells public input values are asserted at
+    ///
+    /// Public inputs built with [`PublicInputs::from_values`] then hold one
+    /// value per cell, and proving and verifying assert `values[i]` at
+    /// `cells[i]`. The cells are part of the AIR digest.
+    pub fn with_public_inputs(mut self, cells: &[PublicInputCell]) -> Self {
+        self.public_inputs = cells.to_vec();
+        self
+    }
+
+    /// Place the values of `public_inputs` at the declared cells
+    ///
+    /// Returns public inputs asserting the values at their cells, followed
+    /// by the explicit assertions of `public_inputs`. This is the form that
+    /// is enforced and absorbed into the transcript.
+    pub fn bind_public_inputs(&self, public_inputs: &PublicInputs<F>) -> Result<PublicInputs<F>, AirError> {
+        public_inputs.bind(&self.public_inputs).map_err(AirError::InvalidBoundary)
+    }
+
+    /// Add an auxiliary trace segment after the existing ones
+    pub fn with_aux_segment(mut self, segment: AuxSegment<F>) -> Self {
+        self.max_constraint_degree = self.max_constraint_degree.max(MAX_AUX_CONSTRAINT_DEGREE);
+        self.aux_segments.push(segment);
+        self
+    }
+
+    /// Number of columns across all auxiliary segments
+    pub fn aux_width(&self) -> usize {
+        self.aux_segments.iter().map(|segment| segment.definition().width()).sum()
+    }
+
+    /// Number of consecutive rows the transition constraints read
+    ///
+    /// Main transitions read two rows; auxiliary segments may read more (see
+    /// [`AuxTraceSegment::window`]).
+    pub fn window(&self) -> usize {
+        self.aux_segments
+            .iter()
+            .map(|segment| segment.definition().window())
+            .fold(2, usize::max)
+    }
+
     /// Evaluate all constraints at a given point
     pub fn evaluate_constraints(
         &self,
@@ -101,29 +270,270 @@ impl<F: FieldElement> Air<F> {
         self.max_constraint_degree
     }
 
+    /// Algebraic degree of the constraints, computed from their definitions
+    ///
+    /// Main transitions `next = M · current` are linear, constraint
+    /// polynomials contribute their degree and auxiliary segments raise the
+    /// degree to [`MAX_AUX_CONSTRAINT_DEGREE`]. The user-supplied
+    /// [`max_constraint_degree`](Self::max_constraint_degree) bound must be at
+    /// least this degree (see [`validate`](Self::validate)).
+    pub fn max_constraint_degree(&self) -> usize {
+        let aux_degree = if self.aux_segments.is_empty() { 1 } else { MAX_AUX_CONSTRAINT_DEGREE };
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.degree())
+            .fold(aux_degree, usize::max)
+    }
+
     /// Get the number of registers (state variables)
     pub fn num_registers(&self) -> usize {
         self.transition.num_registers()
     }
 
+    /// Canonical digest of the AIR
+    ///
+    /// Hashes the canonical encoding of the constraints, the transition
+    /// function and free registers, the boundary conditions, the parameters, the shape of every
+    /// auxiliary segment, the public input cells and the fixed columns: the encoding of
+    /// [`to_bytes`](StarkComponent::to_bytes) without the constraint
+    /// descriptions. Proofs are bound to the digest of the AIR they were
+    /// generated for.
+    pub fn digest(&self) -> Digest {
+        let mut writer = ByteWriter::new();
+        self.encode_statement(&mut writer);
+        let mut hasher = Sha256::new();
+        hasher.update(AIR_DIGEST_TAG);
+        hasher.update(writer.into_bytes());
+        Digest::new(hasher.finalize().into())
+    }
+
+    /// Commitment to the values of the fixed columns
+    ///
+    /// Held by verifying keys; the values are also covered by the
+    /// [`digest`](Self::digest).
+    pub fn fixed_commitment(&self) -> [u8; 32] {
+        let mut writer = ByteWriter::new();
+        writer.write_sequence(&self.fixed_columns);
+        let mut hasher = Sha256::new();
+        hasher.update(FIXED_COMMITMENT_TAG);
+        hasher.update(writer.into_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Decode an AIR serialized with [`to_bytes`](StarkComponent::to_bytes), supplying its auxiliary segments
+    ///
+    /// Segment definitions are code, so a serialized AIR only records their
+    /// shapes; `segments` must match them in order.
+    pub fn from_bytes_with_segments(bytes: &[u8], segments: &[AuxSegment<F>]) -> Result<Self, TypeError> {
+        let mut reader = ByteReader::new(bytes);
+        let (mut air, shapes) = Self::decode_parts(&mut reader)?;
+        reader.finish()?;
+        if shapes != segments.iter().map(AuxSegment::shape).collect::<Vec<_>>() {
+            return Err(TypeError::InvalidConversion(
+                "Auxiliary segments do not match the serialized AIR".to_string(),
+            ));
+        }
+        air.aux_segments = segments.to_vec();
+        Ok(air)
+    }
+
+    /// Encode everything the digest commits to
+    fn encode_statement(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.constraints.len());
+        for constraint in &self.constraints {
+            constraint.encode_statement(writer);
+        }
+        self.transition.encode(writer);
+        writer.write_usize(self.free_registers.len());
+        for &register in &self.free_registers {
+            writer.write_usize(register);
+        }
+        self.boundary.encode(writer);
+        writer.write_u32(self.security_parameter);
+        writer.write_u32(self.field_extension_degree);
+        writer.write_usize(self.max_constraint_degree);
+        writer.write_sequence(&self.aux_segments.iter().map(AuxSegment::shape).collect::<Vec<_>>());
+        writer.write_sequence(&self.public_inputs);
+        writer.write_sequence(&self.fixed_columns);
+    }
+
+    /// Decode an AIR without auxiliary segments, and the shapes of its segments
+    fn decode_parts(reader: &mut ByteReader<'_>) -> Result<(Self, Vec<AuxSegmentShape>), TypeError> {
+        let num_constraints = reader.read_length(1)?;
+        let mut constraints = (0..num_constraints)
+            .map(|_| Constraint::decode_statement(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let transition = TransitionFunction::decode(reader)?;
+        let free_registers = (0..reader.read_length(8)?)
+            .map(|_| reader.read_usize())
+            .collect::<Result<Vec<_>, _>>()?;
+        let boundary = BoundaryConditions::decode(reader)?;
+        let security_parameter = reader.read_u32()?;
+        let field_extension_degree = reader.read_u32()?;
+        let max_constraint_degree = reader.read_usize()?;
+        let shapes = reader.read_sequence()?;
+        let public_inputs = reader.read_sequence()?;
+        let fixed_columns = reader.read_sequence()?;
+        let num_descriptions = reader.read_length(1)?;
+        if num_descriptions != num_constraints {
+            return Err(TypeError::LengthMismatch {
+                name: "constraint descriptions",
+                expected: num_constraints,
+                actual: num_descriptions,
+            });
+        }
+        for constraint in &mut constraints {
+            constraint.description = reader.read_string()?;
+        }
+        let register_names = (0..reader.read_length(8)?)
+            .map(|_| reader.read_string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let air = Self {
+            constraints,
+            transition,
+            boundary,
+            security_parameter,
+            field_extension_degree,
+            max_constraint_degree,
+            aux_segments: Vec::new(),
+            public_inputs,
+            free_registers,
+            fixed_columns,
+            register_names,
+            step: None,
+            symbolic: None,
+        };
+        Ok((air, shapes))
+    }
+
     /// Validate AIR properties
+    ///
+    /// Checks the declared properties of the AIR against the ones its parts
+    /// actually have: coefficient counts against the number of registers,
+    /// the degree bound against the degrees of the constraints and
+    /// transition, boundary cells against the trace, and the security
+    /// parameters against the field. Each error names the offending part.
     pub fn validate(&self) -> Result<(), AirError> {
-        // Check constraint degrees
-        for constraint in &self.constraints {
+        // Check transition function
+        self.transition.validate().map_err(|e| AirError::InvalidTransition(e.to_string()))?;
+        let registers = self.num_registers();
+        if let Some((row, coefficients)) = self
+            .transition
+            .coefficients()
+            .iter()
+            .enumerate()
+            .find(|(_, coefficients)| coefficients.len() != registers)
+        {
+            return Err(AirError::InvalidTransition(format!(
+                "Row {} has {} coefficients for {} registers",
+                row,
+                coefficients.len(),
+                registers
+            )));
+        }
+
+        // Check constraints
+        for (index, constraint) in self.constraints.iter().enumerate() {
             if constraint.degree() == 0 {
-                return Err(AirError::InvalidConstraint("Zero-degree constraint".to_string()));
+                return Err(AirError::InvalidConstraint(format!("Constraint {} has degree zero", index)));
+            }
+            if constraint.polynomial.is_empty() {
+                return Err(AirError::InvalidConstraint(format!("Constraint {} has no coefficients", index)));
+            }
+            // A boundary constraint has a coefficient per register, the first one doubling as the constant
+            if constraint.constraint_type == ConstraintType::Boundary && constraint.polynomial.len() > registers {
+                return Err(AirError::InvalidConstraint(format!(
+                    "Boundary constraint {} has {} coefficients for {} registers",
+                    index,
+                    constraint.polynomial.len(),
+                    registers
+                )));
             }
         }
 
-        // Check transition function
-        self.transition.validate().map_err(|e| AirError::InvalidTransition(e.to_string()))?;
+        // Check the declared degree bound
+        let degree = self.max_constraint_degree();
+        if self.max_constraint_degree < degree {
+            return Err(AirError::DegreeError(format!(
+                "Degree bound {} is below the constraint degree {}",
+                self.max_constraint_degree, degree
+            )));
+        }
+        if self.max_constraint_degree < self.transition.degree() {
+            return Err(AirError::DegreeError(format!(
+                "Degree bound {} is below the transition degree {}",
+                self.max_constraint_degree,
+                self.transition.degree()
+            )));
+        }
+
+        // Check auxiliary segments
+        if self.aux_segments.iter().any(|segment| segment.definition().width() == 0) {
+            return Err(AirError::InvalidConstraint("Auxiliary segment without columns".to_string()));
+        }
+        if self.aux_segments.iter().any(|segment| segment.definition().window() < 2) {
+            return Err(AirError::InvalidConstraint("Auxiliary segment window below two rows".to_string()));
+        }
+
+        // Check register names, public input cells, free and fixed registers
+        if !self.register_names.is_empty() && self.register_names.len() != registers {
+            return Err(AirError::InvalidConstraint(format!(
+                "{} register names for {} registers",
+                self.register_names.len(),
+                registers
+            )));
+        }
+        if let Some(register) = self.free_registers.iter().find(|&&register| register >= registers) {
+            return Err(AirError::InvalidTransition(format!(
+                "Free register {} out of {} registers",
+                register, registers
+            )));
+        }
+        if let Some(column) = self.fixed_columns.iter().find(|column| column.register() >= registers) {
+            return Err(AirError::InvalidConstraint(format!(
+                "Fixed register {} out of {} registers",
+                column.register(),
+                registers
+            )));
+        }
+        let fixed: Vec<usize> = self.fixed_columns.iter().map(FixedColumn::register).collect();
+        if let Some(index) = (0..fixed.len()).find(|&i| fixed[..i].contains(&fixed[i])) {
+            return Err(AirError::InvalidConstraint(format!("Register {} is fixed twice", fixed[index])));
+        }
+        if let Some(cell) = self.public_inputs.iter().find(|cell| cell.register() >= registers) {
+            return Err(AirError::InvalidBoundary(format!(
+                "Public input register {} out of {} registers",
+                cell.register(),
+                registers
+            )));
+        }
 
         // Check boundary conditions
         self.boundary.validate().map_err(|e| AirError::InvalidBoundary(e.to_string()))?;
+        for constraint in &self.boundary.constraints {
+            if constraint.register >= registers {
+                return Err(AirError::InvalidBoundary(format!(
+                    "Boundary register {} out of {} registers",
+                    constraint.register, registers
+                )));
+            }
+            if constraint.constraint_type == BoundaryType::Initial && constraint.step != 0 {
+                return Err(AirError::InvalidBoundary(format!(
+                    "Initial boundary on register {} at step {} instead of 0",
+                    constraint.register, constraint.step
+                )));
+            }
+        }
 
-        // Check security parameter
+        // Check security parameters
         if self.security_parameter == 0 {
-            return Err(AirError::InvalidSecurityParameter);
+            return Err(AirError::InvalidSecurityParameter("security parameter is zero".to_string()));
+        }
+        if !ExtensionElement::<F>::is_supported(self.field_extension_degree as usize) {
+            return Err(AirError::InvalidSecurityParameter(format!(
+                "the field has no extension of degree {}",
+                self.field_extension_degree
+            )));
         }
 
         Ok(())
@@ -131,31 +541,87 @@ impl<F: FieldElement> Air<F> {
 }
 
 impl<F: FieldElement> Display for Air<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "AIR(security={}, constraints={}, registers={}, max_degree={})",
-            self.security_parameter,
-            self.constraints.len(),
-            self.num_registers(),
-            self.max_degree()
-        )
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AIR(security={}, constraints={}, ", self.security_parameter, self.constraints.len())?;
+        if self.register_names.is_empty() {
+            write!(f, "registers={}", self.num_registers())?;
+        } else {
+            write!(f, "registers=[{}]", self.register_names.join(", "))?;
+        }
+        write!(f, ", max_degree={})", self.max_degree())
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for Air<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         self.validate().map_err(|e| TypeError::InvalidConversion(e.to_string()))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
 
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    /// Decode an AIR without auxiliary segments
+    ///
+    /// AIRs with auxiliary segments are decoded with
+    /// [`Air::from_bytes_with_segments`].
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> Encode for Air<F> {
+    /// The statement the digest commits to, followed by the constraint descriptions and register names
+    fn encode(&self, writer: &mut ByteWriter) {
+        self.encode_statement(writer);
+        writer.write_usize(self.constraints.len());
+        for constraint in &self.constraints {
+            writer.write_string(&constraint.description);
+        }
+        writer.write_usize(self.register_names.len());
+        for name in &self.register_names {
+            writer.write_string(name);
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        let (air, shapes) = Self::decode_parts(reader)?;
+        if !shapes.is_empty() {
+            return Err(TypeError::InvalidConversion(
+                "AIR has auxiliary segments; decode it with Air::from_bytes_with_segments".to_string(),
+            ));
+        }
+        Ok(air)
+    }
+}
+
+/// Serde form of the auxiliary segments of an AIR: their shapes
+///
+/// Only AIRs without auxiliary segments deserialize; the others are decoded
+/// with [`Air::from_bytes_with_segments`].
+mod segment_shapes {
+    use crate::prelude::*;
+    use super::{AuxSegment, AuxSegmentShape};
+    use crate::types::FieldElement;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<F: FieldElement, S: Serializer>(
+        segments: &[AuxSegment<F>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(segments.iter().map(AuxSegment::shape))
+    }
+
+    pub fn deserialize<'de, F: FieldElement, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<AuxSegment<F>>, D::Error> {
+        let shapes = Vec::<AuxSegmentShape>::deserialize(deserializer)?;
+        if shapes.is_empty() {
+            Ok(Vec::new())
+        } else {
+            Err(D::Error::custom("auxiliary segment definitions cannot be deserialized"))
+        }
     }
 }
 
@@ -175,8 +641,8 @@ pub enum AirError {
     InvalidBoundary(String),
 
     /// Invalid security parameter
-    #[error("Invalid security parameter")]
-    InvalidSecurityParameter,
+    #[error("Invalid security parameter: {0}")]
+    InvalidSecurityParameter(String),
 
     /// Constraint evaluation error
     #[error("Constraint evaluation error: {0}")]
@@ -187,10 +653,17 @@ pub enum AirError {
     DegreeError(String),
 }
 
+impl From<AirError> for StarkError {
+    fn from(error: AirError) -> Self {
+        StarkError::InvalidConstraints(error.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::field::PrimeField64;
+    use crate::types::{ColIdx, StepIdx};
 
     #[test]
     fn test_air_creation() {
@@ -214,6 +687,7 @@ mod tests {
         assert_eq!(air.security_parameter, 128);
         assert_eq!(air.constraints.len(), 1);
         assert_eq!(air.max_degree(), 1);
+        assert_eq!(air.max_constraint_degree(), 1);
     }
 
     #[test]
@@ -238,6 +712,38 @@ mod tests {
         assert!(air.validate().is_ok());
     }
 
+    #[test]
+    fn test_validate_reports_declared_mismatches() {
+        let one = PrimeField64::one();
+        let air = Air::new(vec![], TransitionFunction::fibonacci(), BoundaryConditions::empty(), 128);
+        assert!(air.validate().is_ok());
+        let message = |air: Air<PrimeField64>| StarkError::from(air.validate().unwrap_err()).to_string();
+
+        let mut ragged = air.clone();
+        ragged.transition = TransitionFunction::new(vec![vec![one], vec![one]], 1);
+        assert!(message(ragged).contains("Row 0 has 1 coefficients for 2 registers"));
+
+        let mut register = air.clone();
+        register.boundary.add_constraint(BoundaryConstraint::initial(2, one));
+        assert!(message(register).contains("Boundary register 2 out of 2 registers"));
+
+        let mut step = air.clone();
+        step.boundary.add_constraint(BoundaryConstraint::new(ColIdx(0), StepIdx(3), one, BoundaryType::Initial));
+        assert!(message(step).contains("Initial boundary on register 0 at step 3"));
+
+        let mut boundary = air.clone();
+        boundary.constraints.push(Constraint::boundary(4, one));
+        assert!(message(boundary).contains("Boundary constraint 0 has 5 coefficients for 2 registers"));
+
+        let mut degree = air.clone();
+        degree.transition.degree = 2;
+        assert!(message(degree).contains("Degree bound 1 is below the transition degree 2"));
+
+        let mut extension = air.clone();
+        extension.field_extension_degree = 4;
+        assert!(message(extension).starts_with("Invalid AIR constraints: Invalid security parameter"));
+    }
+
     #[test]
     fn test_constraint_evaluation() {
         let constraint = Constraint::new(
@@ -253,4 +759,46 @@ mod tests {
         let result = constraint.evaluate(&current_state, &next_state, challenge);
         assert_eq!(result, PrimeField64::new(3)); // 1 + 2 = 3
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_air_serialization_and_digest() {
+        let constraint = Constraint::with_description(
+            vec![PrimeField64::new(1), PrimeField64::new(2)],
+            1,
+            ConstraintType::Transition,
+            "counter".to_string(),
+        );
+        let air = Air::new(
+            vec![constraint],
+            TransitionFunction::new(vec![vec![PrimeField64::new(1), PrimeField64::new(1)]; 2], 1),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(4))]),
+            128,
+        )
+        .with_public_inputs(&[PublicInputCell::last(1)]);
+
+        let bytes = StarkComponent::to_bytes(&air);
+        assert_eq!(<Air<PrimeField64> as StarkComponent<_>>::from_bytes(&bytes).unwrap(), air);
+        let serialized = crate::utils::serialization::to_bytes(&air).unwrap();
+        assert_eq!(crate::utils::serialization::from_bytes::<Air<PrimeField64>>(&serialized).unwrap(), air);
+
+        // Descriptions are not part of the digest; everything asserted is
+        let mut described = air.clone();
+        described.constraints[0].description = "renamed".to_string();
+        assert_eq!(described.digest(), air.digest());
+        let mut moved = air.clone();
+        moved.boundary.constraints[0].value = PrimeField64::new(5);
+        assert_ne!(moved.digest(), air.digest());
+
+        // Segments are supplied again when decoding
+        let segment = gadgets::balance(&[0], &[1]).unwrap();
+        let air = air.with_aux_segment(segment.clone());
+        let bytes = StarkComponent::to_bytes(&air);
+        assert!(<Air<PrimeField64> as StarkComponent<_>>::from_bytes(&bytes).is_err());
+        let decoded = Air::from_bytes_with_segments(&bytes, &[segment]).unwrap();
+        assert_eq!((decoded.digest(), decoded), (air.digest(), air.clone()));
+        let other = gadgets::balance::<PrimeField64>(&[0, 1], &[1]).unwrap();
+        assert!(Air::from_bytes_with_segments(&bytes, &[other.clone(), other]).is_err());
+        let serialized = crate::utils::serialization::to_bytes(&air).unwrap();
+        assert!(crate::utils::serialization::from_bytes::<Air<PrimeField64>>(&serialized).is_err());
+    }
+}
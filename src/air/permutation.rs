@@ -0,0 +1,190 @@
+//! Permutation Arguments
+//!
+//! A permutation argument proves that the rows of one set of main trace
+//! columns are a permutation of the rows of another set, i.e. that the two
+//! sets hold the same multiset of tuples. After the main trace is committed
+//! two challenges `α` and `β` are drawn, each row tuple is compressed to
+//! `α - Σ_j β^j a_j`, and the grand product
+//!
+//! `Π_i (α - Σ_j β^j a_j[i]) / (α - Σ_j β^j b_j[i])`
+//!
+//! over all rows equals one. For random challenges this only happens if the
+//! multisets agree.
+//!
+//! A [`PermutationArgument`] adds an auxiliary segment with two columns: the
+//! product before each row, `p`, and the product including it, `q`. Its
+//! constraints have degree two:
+//!
+//! - `q · (α - b) = p · (α - a)` on every row
+//! - `p` is one on the first row, the next `p` is the current `q`, and `q`
+//!   is one on the last row
+
+use crate::prelude::*;
+use crate::air::{Air, AirError, AuxFrame, AuxSegment, AuxTraceSegment, BoundaryConstraint};
+use crate::types::FieldElement;
+
+/// Segment column of the product before each row
+const BEFORE: usize = 0;
+
+/// Segment column of the product including each row
+const INCLUDING: usize = 1;
+
+/// Grand-product permutation argument between two sets of main trace columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermutationArgument {
+    /// Columns of the left tuples
+    left: Vec<usize>,
+    /// Columns of the right tuples
+    right: Vec<usize>,
+}
+
+impl PermutationArgument {
+    /// Prove that the rows of the `right` columns permute the rows of the `left` columns
+    ///
+    /// Both sets must name the same, nonzero number of columns; tuples are
+    /// compared column by column in the given order.
+    pub fn new(left: &[usize], right: &[usize]) -> Result<Self, AirError> {
+        if left.is_empty() || left.len() != right.len() {
+            return Err(AirError::InvalidConstraint(format!(
+                "permutation between {} and {} columns",
+                left.len(),
+                right.len()
+            )));
+        }
+        Ok(Self {
+            left: left.to_vec(),
+            right: right.to_vec(),
+        })
+    }
+
+    /// Columns of the left tuples
+    pub fn left(&self) -> &[usize] {
+        &self.left
+    }
+
+    /// Columns of the right tuples
+    pub fn right(&self) -> &[usize] {
+        &self.right
+    }
+
+    /// Compressed tuple `α - Σ_j β^j row[columns[j]]`
+    fn compress<F: FieldElement>(columns: &[usize], row: impl Fn(usize) -> F, challenges: &[F]) -> F {
+        let (alpha, beta) = (challenges[0], challenges[1]);
+        let mut power = F::one();
+        let mut combination = F::zero();
+        for &column in columns {
+            combination += power * row(column);
+            power *= beta;
+        }
+        alpha - combination
+    }
+
+    /// Residual of the per-row constraint of one row
+    fn row_residual<F: FieldElement>(&self, main: &[F], aux: &[F], challenges: &[F]) -> F {
+        let value = |column: usize| main.get(column).copied().unwrap_or_else(F::zero);
+        let product = |column: usize| aux.get(column).copied().unwrap_or_else(F::zero);
+        product(INCLUDING) * Self::compress(&self.right, value, challenges)
+            - product(BEFORE) * Self::compress(&self.left, value, challenges)
+    }
+}
+
+impl<F: FieldElement> AuxTraceSegment<F> for PermutationArgument {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn num_challenges(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        3
+    }
+
+    fn build(&self, main: &[Vec<F>], challenges: &[F]) -> Vec<Vec<F>> {
+        let length = main.first().map_or(0, Vec::len);
+        let mut before = Vec::with_capacity(length);
+        let mut including = Vec::with_capacity(length);
+        let mut product = F::one();
+        for row in 0..length {
+            let value = |column: usize| main[column][row];
+            before.push(product);
+            // A zero denominator has no inverse; its row then fails the constraints
+            let denominator = Self::compress(&self.right, value, challenges).inverse().unwrap_or_else(F::zero);
+            product = product * Self::compress(&self.left, value, challenges) * denominator;
+            including.push(product);
+        }
+        vec![before, including]
+    }
+
+    fn evaluate_transition(&self, frame: &AuxFrame<'_, F>, challenges: &[F]) -> Vec<F> {
+        let column = |row: &[F], index: usize| row.get(index).copied().unwrap_or_else(F::zero);
+        vec![
+            self.row_residual(frame.main_current, frame.aux_current, challenges),
+            self.row_residual(frame.main_next, frame.aux_next, challenges),
+            column(frame.aux_next, BEFORE) - column(frame.aux_current, INCLUDING),
+        ]
+    }
+
+    fn boundary(&self, _challenges: &[F]) -> Vec<BoundaryConstraint<F>> {
+        vec![
+            BoundaryConstraint::initial(BEFORE, F::one()),
+            BoundaryConstraint::final_condition(INCLUDING, F::one()),
+        ]
+    }
+}
+
+impl<F: FieldElement> Air<F> {
+    /// Add a permutation argument, proven in a new auxiliary segment
+    pub fn with_permutation(self, permutation: PermutationArgument) -> Self {
+        self.with_aux_segment(AuxSegment::new(permutation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_permutation_argument() {
+        // Columns `[one, up, twice up, down, twice down]`, with `up` counting
+        // from zero and `down` counting down from `top`
+        let (zero, one, two) = (PrimeField64::new(0), PrimeField64::new(1), PrimeField64::new(2));
+        let transition = TransitionFunction::linear(vec![
+            vec![one, zero, zero, zero, zero],
+            vec![one, one, zero, zero, zero],
+            vec![two, zero, one, zero, zero],
+            vec![-one, zero, zero, one, zero],
+            vec![-two, zero, zero, zero, one],
+        ]);
+        let air = |right: &[usize]| {
+            let boundary = BoundaryConditions::new(vec![BoundaryConstraint::initial(0, one)]);
+            Air::new(vec![], transition.clone(), boundary, 128)
+                .with_permutation(PermutationArgument::new(&[1, 2], right).unwrap())
+        };
+        let initial = |top: u64| [1, 0, 0, top, 2 * top].map(PrimeField64::new);
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let permuted = air(&[3, 4]);
+        let witness = prover.generate_trace(&permuted, &initial(15), 16).unwrap();
+        let proof = prover.prove(&permuted, &PublicInputs::new(), &witness).unwrap();
+        verifier.verify_with_air(&permuted, &proof, &PublicInputs::new()).unwrap();
+
+        // Shifted values, or tuples with swapped columns, are not permutations
+        for (top, right) in [(16, [3, 4]), (15, [4, 3])] {
+            let air = air(&right);
+            let witness = prover.generate_trace(&air, &initial(top), 16).unwrap();
+            let error = prover.prove(&air, &PublicInputs::new(), &witness).unwrap_err();
+            assert!(matches!(error, ProofError::ConstraintError(_)));
+            let unchecked = prover.clone().with_trace_check(false);
+            let proof = unchecked.prove(&air, &PublicInputs::new(), &witness).unwrap();
+            assert!(verifier.verify_with_air(&air, &proof, &PublicInputs::new()).is_err());
+        }
+        assert!(PermutationArgument::new(&[1, 2], &[3]).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! AIR Test Helpers
+//!
+//! Unit tests of an AIR, such as those of a gadget, rarely need the prover:
+//! whether a witness satisfies the constraints, and whether a wrong witness
+//! is caught, can be read off the constraints directly. Unlike
+//! [`Air::check_trace`], the helpers here also check the auxiliary segments,
+//! which they build from the main trace with challenges drawn from a fixed
+//! seed, and the fixed columns.
+//!
+//! Mutation checks change random cells of a valid trace and report those
+//! whose change no constraint catches. A cell a prover may change freely is
+//! a soundness bug, unless the column is meant to be unconstrained there.
+
+use crate::prelude::*;
+use crate::air::{Air, ConstraintViolation};
+use crate::proof::composition::{CompositionConstraints, TraceViolation};
+use crate::proof::zk::random_element;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Seed of the challenges the auxiliary segments are built from
+const CHALLENGE_SEED: u64 = 0x7865_6667;
+
+/// Every constraint violation of a trace, auxiliary segments included
+///
+/// The auxiliary segments of the trace are rebuilt from its main columns.
+/// Violations are ordered like [`Air::trace_violations`], with a fixed
+/// column deviating from its values reported last, as
+/// [`Malformed`](ConstraintViolation::Malformed).
+pub fn constraint_violations<F: FieldElement>(air: &Air<F>, trace: &ExecutionTrace<F>) -> Vec<ConstraintViolation<F>> {
+    let mut rng = ChaCha20Rng::seed_from_u64(CHALLENGE_SEED);
+    let mut constraints = match CompositionConstraints::from_air(air, trace.length) {
+        Ok(constraints) => constraints,
+        Err(error) => return vec![ConstraintViolation::Malformed(error.to_string())],
+    };
+    let main = trace.columns.as_columns();
+    let mut aux_segments = Vec::with_capacity(air.aux_segments.len());
+    for segment in &air.aux_segments {
+        let definition = segment.definition();
+        let challenges: Vec<F> = (0..definition.num_challenges()).map(|_| random_element(&mut rng)).collect();
+        aux_segments.push(definition.build(&main, &challenges));
+        constraints = match constraints.with_aux_segment(segment, challenges) {
+            Ok(constraints) => constraints,
+            Err(error) => return vec![ConstraintViolation::Malformed(error.to_string())],
+        };
+    }
+    let trace = ExecutionTrace {
+        columns: trace.columns.clone(),
+        length: trace.length,
+        num_registers: trace.num_registers,
+        aux_segments,
+    };
+    let mut violations = match constraints.violations(&trace, usize::MAX) {
+        Ok(violations) => violations,
+        Err(error) => return vec![ConstraintViolation::Malformed(error.to_string())],
+    };
+    if let Ok(Some(TraceViolation::Fixed { register, row })) = constraints.first_fixed_violation(&trace) {
+        violations.push(ConstraintViolation::Malformed(format!(
+            "register {} deviates from its fixed column at row {}",
+            register, row
+        )));
+    }
+    violations
+}
+
+/// Assert that a trace satisfies every constraint of the AIR
+///
+/// Panics with the first violation, explained with
+/// [`Air::explain_violation`].
+#[track_caller]
+pub fn assert_constraints_hold<F: FieldElement>(air: &Air<F>, trace: &ExecutionTrace<F>) {
+    if let Some(violation) = constraint_violations(air, trace).first() {
+        panic!("trace violates the AIR: {}", air.explain_violation(trace, violation));
+    }
+}
+
+/// Assert that a trace violates a constraint at `row`
+///
+/// Transitions are reported at their first row, so a wrong value at row `r`
+/// usually fails at `r - 1` as well as at `r`.
+#[track_caller]
+pub fn assert_constraint_fails_at<F: FieldElement>(air: &Air<F>, trace: &ExecutionTrace<F>, row: usize) {
+    let violations = constraint_violations(air, trace);
+    if !violations.iter().any(|violation| violation.row() == Some(row)) {
+        let rows: Vec<usize> = violations.iter().filter_map(ConstraintViolation::row).collect();
+        panic!("no constraint fails at row {}; failing rows: {:?}", row, rows);
+    }
+}
+
+/// Main trace cells whose random change no constraint catches
+///
+/// Changes `samples` random cells of a valid trace, one at a time, by a
+/// random nonzero amount, and returns the `(register, row)` of every change
+/// that leaves the trace valid. The cells depend only on `seed`.
+pub fn undetected_mutations<F: FieldElement>(
+    air: &Air<F>,
+    trace: &ExecutionTrace<F>,
+    samples: usize,
+    seed: u64,
+) -> Vec<(usize, usize)> {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    let mut undetected = Vec::new();
+    if trace.columns.is_empty() || trace.length == 0 {
+        return undetected;
+    }
+    for _ in 0..samples {
+        let (register, row) = (rng.gen_range(0..trace.columns.len()), rng.gen_range(0..trace.length));
+        let delta = loop {
+            let delta: F = random_element(&mut rng);
+            if !delta.is_zero() {
+                break delta;
+            }
+        };
+        let mut mutated = trace.clone();
+        mutated.columns.set(register, row, trace.columns.value(register, row) + delta);
+        if constraint_violations(air, &mutated).is_empty() {
+            undetected.push((register, row));
+        }
+    }
+    undetected
+}
+
+/// Assert that every one of `samples` random cell changes violates the AIR
+///
+/// See [`undetected_mutations`]; panics with the undetected cells, by
+/// register name where the AIR has them.
+#[track_caller]
+pub fn assert_mutations_detected<F: FieldElement>(air: &Air<F>, trace: &ExecutionTrace<F>, samples: usize, seed: u64) {
+    assert_constraints_hold(air, trace);
+    let undetected = undetected_mutations(air, trace, samples, seed);
+    if !undetected.is_empty() {
+        let cells: Vec<String> = undetected
+            .iter()
+            .map(|&(register, row)| match air.register_name(register) {
+                Some(name) => format!("`{}` at row {}", name, row),
+                None => format!("register {} at row {}", register, row),
+            })
+            .collect();
+        panic!("changes go undetected: {}", cells.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::air::examples::FibonacciAir;
+    use crate::air::gadgets::bit_decompose;
+    use crate::proof::StarkProver;
+    use crate::types::field::PrimeField64;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    #[test]
+    fn test_helpers_check_without_the_prover() {
+        let example = FibonacciAir::<PrimeField64>::new(16).unwrap();
+        let (air, mut trace) = (example.air(), example.trace());
+        assert_constraints_hold(&air, &trace);
+        assert_mutations_detected(&air, &trace, 32, 1);
+        trace.columns.set(1, 5, PrimeField64::new(4));
+        assert_constraint_fails_at(&air, &trace, 4);
+        assert_constraint_fails_at(&air, &trace, 5);
+        assert!(catch_unwind(AssertUnwindSafe(|| assert_constraint_fails_at(&air, &trace, 9))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| assert_constraints_hold(&air, &trace))).is_err());
+
+        // `spare` is free and read by no constraint, so the mutation check finds it
+        let bits = bit_decompose::<PrimeField64>("x", 4).unwrap();
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("one")
+            .with_column("x")
+            .with_free_column("spare")
+            .with_transition(col("one").next() - col("one"))
+            .with_transition(col("x").next() - col("x") - col("one"))
+            .with_bit_decomposition(&bits)
+            .build()
+            .unwrap();
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let mut init = vec![PrimeField64::one(), PrimeField64::zero(), PrimeField64::zero()];
+        init.resize(air.num_registers(), PrimeField64::zero());
+        let mut trace = prover.generate_trace(&air, &init, 16).unwrap();
+        for row in 0..16 {
+            for (bit, value) in bits.values(PrimeField64::new(row as u64)).into_iter().enumerate() {
+                trace.columns.set(3 + bit, row, value);
+            }
+        }
+        assert_constraints_hold(&air, &trace);
+        assert!(air.check_trace(&trace).is_ok());
+        let undetected = undetected_mutations(&air, &trace, 64, 2);
+        assert!(!undetected.is_empty());
+        assert!(undetected.iter().all(|&(register, _)| air.register_name(register) == Some("spare")));
+
+        // Flipping a bit breaks the recomposition, in an auxiliary segment
+        // that `check_trace` does not build
+        trace.columns.set(3, 5, PrimeField64::zero());
+        assert!(air.check_trace(&trace).is_ok());
+        assert_constraint_fails_at(&air, &trace, 5);
+    }
+}
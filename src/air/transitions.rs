@@ -3,14 +3,19 @@
 //! This module defines transition functions that describe how states evolve
 //! between computation steps in AIR (Algebraic Intermediate Representation).
 
+use crate::prelude::*;
+use crate::types::encoding::{decode_from_slice, encode_to_vec, ByteReader, ByteWriter, Encode};
 use crate::types::{FieldElement, StarkComponent, TypeError};
-use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+use core::fmt::{Debug, Display, Formatter};
+use alloc::sync::Arc;
 
 /// Transition function for AIR
 /// 
 /// A transition function defines how the state changes from one step to the next
 /// in a computation. It's represented as a matrix of coefficients.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct TransitionFunction<F: FieldElement> {
     /// Transition matrix coefficients
     /// Each row represents a register, each column represents a coefficient
@@ -160,6 +165,90 @@ impl<F: FieldElement> TransitionFunction<F> {
         Self::new(coefficients, 1)
     }
 
+    /// Create an accumulator over another transition function
+    ///
+    /// The result has the registers of `inner` followed by a running sum that
+    /// adds the current value of `register` at every step.
+    pub fn accumulator(inner: &Self, register: usize) -> Result<Self, TransitionError> {
+        let width = inner.num_registers();
+        if register >= width {
+            return Err(TransitionError::InvalidColumn(register));
+        }
+
+        let mut coefficients: Vec<Vec<F>> = inner
+            .coefficients
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.resize(width + 1, F::zero());
+                row
+            })
+            .collect();
+        let mut sum = vec![F::zero(); width + 1];
+        sum[register] = F::one();      // next_sum = sum + register
+        sum[width] = F::one();
+        coefficients.push(sum);
+
+        Ok(Self::new(coefficients, 1))
+    }
+
+    /// Create a linear recurrence `x[n + k] = Σ_i c[i] x[n + i]` of order `k`
+    ///
+    /// The registers hold the window `x[n], ..., x[n + k - 1]`, so the
+    /// Fibonacci transition is the recurrence with coefficients `[1, 1]`.
+    pub fn linear_recurrence(recurrence: &[F]) -> Result<Self, TransitionError> {
+        let order = recurrence.len();
+        if order == 0 {
+            return Err(TransitionError::EmptyCoefficients);
+        }
+
+        let mut coefficients = vec![vec![F::zero(); order]; order];
+        for (i, row) in coefficients.iter_mut().take(order - 1).enumerate() {
+            row[i + 1] = F::one();     // shift the window
+        }
+        coefficients[order - 1] = recurrence.to_vec();
+
+        Ok(Self::new(coefficients, 1))
+    }
+
+    /// Create a conditional increment with registers `[selector, counter]`
+    ///
+    /// The counter advances by the selector at every step: it counts when the
+    /// selector is one and holds when it is zero. The selector keeps the value
+    /// its boundary constraint gives it.
+    pub fn conditional_increment() -> Self {
+        let coefficients = vec![
+            vec![F::one(), F::zero()],     // next_selector = selector
+            vec![F::one(), F::one()],      // next_counter = counter + selector
+        ];
+
+        Self::new(coefficients, 1)
+    }
+
+    /// Create a state machine step from a table of successor states
+    ///
+    /// States are one-hot encoded over `table.len()` registers, and state `i`
+    /// moves to state `table[i]` at every step.
+    pub fn state_machine(table: &[usize]) -> Result<Self, TransitionError> {
+        let num_states = table.len();
+        if num_states == 0 {
+            return Err(TransitionError::EmptyCoefficients);
+        }
+
+        let mut coefficients = vec![vec![F::zero(); num_states]; num_states];
+        for (state, &successor) in table.iter().enumerate() {
+            if successor >= num_states {
+                return Err(TransitionError::InvalidTransition(format!(
+                    "state {} moves to unknown state {}",
+                    state, successor
+                )));
+            }
+            coefficients[successor][state] = F::one();
+        }
+
+        Ok(Self::new(coefficients, 1))
+    }
+
     /// Validate the transition function
     pub fn validate(&self) -> Result<(), TransitionError> {
         if self.coefficients.is_empty() {
@@ -182,7 +271,7 @@ impl<F: FieldElement> TransitionFunction<F> {
 }
 
 impl<F: FieldElement> Display for TransitionFunction<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "TransitionFunction(degree={}, inputs={}, outputs={})",
@@ -192,21 +281,67 @@ impl<F: FieldElement> Display for TransitionFunction<F> {
 }
 
 impl<F: FieldElement> StarkComponent<F> for TransitionFunction<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         self.validate().map_err(|e| TypeError::InvalidConversion(e.to_string()))
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> Encode for TransitionFunction<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_sequence(&self.coefficients);
+        writer.write_usize(self.degree);
     }
 
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        let coefficients = reader.read_sequence()?;
+        Ok(Self::new(coefficients, reader.read_usize()?))
     }
 }
 
+/// Next-state function given as a closure
+///
+/// The transition matrix only describes linear steps. Computations such as
+/// hashing or branching generate their trace with a closure instead (see
+/// [`Air::with_step`](crate::air::Air::with_step)); the registers it
+/// computes are left free by the matrix and constrained by auxiliary
+/// segments. Two handles are equal if they share the same closure.
+#[derive(Clone)]
+pub struct DynTransition<F: FieldElement>(Arc<dyn Fn(&[F]) -> Vec<F> + Send + Sync>);
+
+impl<F: FieldElement> DynTransition<F> {
+    /// Wrap a function from the current state to the next one
+    pub fn new(step: impl Fn(&[F]) -> Vec<F> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(step))
+    }
+
+    /// Next state after `current_state`
+    pub fn apply(&self, current_state: &[F]) -> Vec<F> {
+        (self.0)(current_state)
+    }
+}
+
+impl<F: FieldElement> Debug for DynTransition<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "DynTransition")
+    }
+}
+
+impl<F: FieldElement> PartialEq for DynTransition<F> {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<F: FieldElement> Eq for DynTransition<F> {}
+
 /// Transition function error types
 #[derive(Debug, thiserror::Error)]
 pub enum TransitionError {
@@ -327,6 +462,30 @@ mod tests {
         assert_eq!(next_state, vec![PrimeField64::new(6)]);
     }
 
+    #[test]
+    fn test_transition_library() {
+        let value = PrimeField64::new;
+
+        let recurrence = TransitionFunction::linear_recurrence(&[value(1), value(1)]).unwrap();
+        assert_eq!(recurrence, TransitionFunction::fibonacci());
+        let tribonacci = TransitionFunction::linear_recurrence(&[value(1), value(1), value(1)]).unwrap();
+        assert_eq!(tribonacci.apply(&[value(1), value(1), value(2)]), vec![value(1), value(2), value(4)]);
+
+        let sums = TransitionFunction::accumulator(&TransitionFunction::fibonacci(), 1).unwrap();
+        assert_eq!(sums.apply(&[value(1), value(2), value(10)]), vec![value(2), value(3), value(12)]);
+        assert!(TransitionFunction::accumulator(&TransitionFunction::<PrimeField64>::fibonacci(), 2).is_err());
+
+        let increment = TransitionFunction::conditional_increment();
+        assert_eq!(increment.apply(&[value(1), value(4)]), vec![value(1), value(5)]);
+        assert_eq!(increment.apply(&[value(0), value(4)]), vec![value(0), value(4)]);
+
+        // Three states cycling 0 -> 2 -> 1 -> 0
+        let machine = TransitionFunction::state_machine(&[2, 0, 1]).unwrap();
+        assert_eq!(machine.apply(&[value(1), value(0), value(0)]), vec![value(0), value(0), value(1)]);
+        assert_eq!(machine.apply(&[value(0), value(0), value(1)]), vec![value(0), value(1), value(0)]);
+        assert!(TransitionFunction::<PrimeField64>::state_machine(&[3]).is_err());
+    }
+
     #[test]
     fn test_transition_function_validation() {
         let valid_transition: TransitionFunction<PrimeField64> = TransitionFunction::identity(2);
@@ -0,0 +1,375 @@
+//! Minimal Virtual Machine
+//!
+//! [`SimpleVmAir`] proves the execution of a program of a small accumulator
+//! machine, and doubles as a template for application VMs. It is written
+//! with the [`builder`](crate::air::builder): each row holds the machine
+//! state before one instruction, the instruction itself and a one-hot flag
+//! per opcode, and the transition constraints apply the flagged opcode.
+//!
+//! The program is a preprocessed ROM of `(pc, opcode, argument)` rows. Since
+//! jumps decouple the program counter from the row, every executed
+//! instruction is looked up in the ROM with a LogUp argument: the sums of
+//! `1 / (α - executed)` over the rows and of `count / (α - rom)` over the
+//! ROM, each row compressed with `β`, must be equal. The prover supplies the
+//! number of times each ROM row is executed in a free `rom.count` column.
+
+use crate::prelude::*;
+use crate::air::builder::{challenge, col, constant, AirBuilder, ChallengeSegment, Expr};
+use crate::air::{Air, AirError};
+use crate::proof::public_inputs::PublicInputs;
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+
+/// Columns of the machine, in register order
+const COLUMNS: [&str; 15] = [
+    "pc",
+    "acc",
+    "op",
+    "arg",
+    "input",
+    "product",
+    "is_load",
+    "is_add",
+    "is_mul",
+    "is_jump",
+    "is_assert",
+    "rom.count",
+    "rom.pc",
+    "rom.op",
+    "rom.arg",
+];
+
+/// First opcode flag column; flag `i` marks opcode `i + 1`
+const FLAGS: usize = 6;
+
+/// Column counting the executions of each ROM row
+const COUNT: usize = 11;
+
+/// First ROM column
+const ROM: usize = 12;
+
+/// Instruction of the [`SimpleVmAir`] machine, which has an accumulator and a program counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction<F: FieldElement> {
+    /// Load the next private input into the accumulator
+    Load,
+    /// Add a constant to the accumulator
+    Add(F),
+    /// Multiply the accumulator by a constant
+    Mul(F),
+    /// Continue at the given instruction; a jump to itself halts the machine
+    Jump(usize),
+    /// Fail unless the accumulator holds the given value
+    Assert(F),
+}
+
+impl<F: FieldElement> Instruction<F> {
+    /// Opcode of the instruction in the ROM, from one on
+    pub fn opcode(&self) -> u64 {
+        match self {
+            Instruction::Load => 1,
+            Instruction::Add(_) => 2,
+            Instruction::Mul(_) => 3,
+            Instruction::Jump(_) => 4,
+            Instruction::Assert(_) => 5,
+        }
+    }
+
+    /// Argument of the instruction in the ROM, zero for [`Load`](Self::Load)
+    pub fn argument(&self) -> F {
+        match *self {
+            Instruction::Load => F::zero(),
+            Instruction::Add(value) | Instruction::Mul(value) | Instruction::Assert(value) => value,
+            Instruction::Jump(target) => F::from_canonical_u64(target as u64),
+        }
+    }
+}
+
+/// Execution of a program on the [`Instruction`] machine
+///
+/// The machine starts at the first instruction with an accumulator of zero
+/// and executes one instruction per row until the trace is full, so
+/// programs end with a jump to itself. The private inputs are the values
+/// loaded; the proof shows that some inputs pass every assertion, and
+/// [`public_inputs`](Self::public_inputs) can claim the final accumulator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleVmAir<F: FieldElement> {
+    /// Instructions, indexed by the program counter
+    program: Vec<Instruction<F>>,
+    /// Security parameter in bits
+    security_parameter: u32,
+}
+
+impl<F: FieldElement + 'static> SimpleVmAir<F> {
+    /// Register holding the program counter
+    pub const PC_REGISTER: usize = 0;
+    /// Register holding the accumulator
+    pub const ACC_REGISTER: usize = 1;
+
+    /// Machine running `program`, whose jumps must stay inside it
+    pub fn new(program: &[Instruction<F>]) -> Result<Self, AirError> {
+        if program.is_empty() {
+            return Err(AirError::InvalidTransition("empty VM program".to_string()));
+        }
+        if let Some(target) = program.iter().find_map(|instruction| match instruction {
+            Instruction::Jump(target) if *target >= program.len() => Some(target),
+            _ => None,
+        }) {
+            return Err(AirError::InvalidTransition(format!(
+                "jump to {} in a program of {} instructions",
+                target,
+                program.len()
+            )));
+        }
+        Ok(Self {
+            program: program.to_vec(),
+            security_parameter: 128,
+        })
+    }
+
+    /// Use a different security parameter
+    pub fn with_security_parameter(mut self, security_parameter: u32) -> Self {
+        self.security_parameter = security_parameter;
+        self
+    }
+
+    /// Instructions, indexed by the program counter
+    pub fn program(&self) -> &[Instruction<F>] {
+        &self.program
+    }
+
+    /// Program counter, opcode and argument columns of the ROM
+    fn rom(&self) -> [Vec<F>; 3] {
+        let pcs = (0..self.program.len()).map(|pc| F::from_canonical_u64(pc as u64)).collect();
+        let opcodes = self.program.iter().map(|instruction| F::from_canonical_u64(instruction.opcode())).collect();
+        let arguments = self.program.iter().map(Instruction::argument).collect();
+        [pcs, opcodes, arguments]
+    }
+
+    /// Builder with the columns and constraints of the machine
+    ///
+    /// Application VMs can extend it before building.
+    pub fn builder(&self) -> AirBuilder<F> {
+        let one = || constant(F::one());
+        let column = |index: usize| col::<F>(COLUMNS[index]);
+        let [pc, acc, op, arg, input, product] = [0, 1, 2, 3, 4, 5].map(column);
+        let [load, add, mul, jump, assert] = [0, 1, 2, 3, 4].map(|flag| column(FLAGS + flag));
+        let [rom_pc, rom_op, rom_arg] = self.rom();
+
+        let mut builder = AirBuilder::new(self.security_parameter);
+        for name in &COLUMNS[..ROM] {
+            builder = builder.with_free_column(name);
+        }
+        builder = builder
+            .with_fixed_column("rom.pc", &rom_pc)
+            .with_fixed_column("rom.op", &rom_op)
+            .with_fixed_column("rom.arg", &rom_arg);
+
+        // Constraints on a single row, checked on both rows of every transition
+        let flags = [load.clone(), add.clone(), mul.clone(), jump.clone(), assert.clone()];
+        let mut single = Vec::new();
+        for flag in &flags {
+            single.push(flag.clone() * (flag.clone() - one()));
+        }
+        let opcode = flags.iter().enumerate().fold(constant(F::zero()), |sum, (index, flag)| {
+            sum + constant(F::from_canonical_u64(index as u64 + 1)) * flag.clone()
+        });
+        let sum = flags.iter().cloned().fold(constant(F::zero()), |sum, flag| sum + flag);
+        single.push(sum - one());
+        single.push(op - opcode.fold_constants());
+        single.push(product.clone() - acc.clone() * arg.clone());
+        single.push(assert.clone() * (acc.clone() - arg.clone()));
+        for constraint in single {
+            builder = builder.with_transition(constraint.clone()).with_transition(constraint.next());
+        }
+
+        let following = pc.clone() + one();
+        builder
+            .with_transition(pc.clone().next() - following.clone() - jump.clone() * (arg.clone() - following))
+            .with_transition(
+                acc.clone().next()
+                    - load * input
+                    - add * (acc.clone() + arg)
+                    - mul * product
+                    - (jump + assert) * acc,
+            )
+            .with_initial("pc", F::zero())
+            .with_initial("acc", F::zero())
+            .with_challenge_segment(rom_lookup())
+    }
+
+    /// AIR of the machine
+    pub fn air(&self) -> Result<Air<F>, AirError> {
+        self.builder().build()
+    }
+
+    /// Witness trace of the program run on `inputs`, over `trace_length` rows
+    ///
+    /// Fails if the program loads more inputs than given, runs past its last
+    /// instruction or fails an assertion within the trace.
+    pub fn trace(&self, inputs: &[F], trace_length: usize) -> Result<ExecutionTrace<F>, AirError> {
+        if trace_length < self.program.len().max(2) {
+            return Err(AirError::InvalidTransition(format!(
+                "VM trace of {} rows for a program of {} instructions",
+                trace_length,
+                self.program.len()
+            )));
+        }
+        let mut columns = vec![vec![F::zero(); trace_length]; COLUMNS.len()];
+        let mut inputs = inputs.iter();
+        let (mut pc, mut acc) = (0, F::zero());
+        for row in 0..trace_length {
+            let instruction = *self.program.get(pc).ok_or_else(|| {
+                AirError::InvalidTransition(format!("program counter {} runs past the program", pc))
+            })?;
+            let (argument, flag) = (instruction.argument(), instruction.opcode() as usize - 1);
+            let mut input = F::zero();
+            let next = match instruction {
+                Instruction::Load => {
+                    input = *inputs
+                        .next()
+                        .ok_or_else(|| AirError::InvalidTransition(format!("load at {} without an input", pc)))?;
+                    (pc + 1, input)
+                }
+                Instruction::Add(value) => (pc + 1, acc + value),
+                Instruction::Mul(value) => (pc + 1, acc * value),
+                Instruction::Jump(target) => (target, acc),
+                Instruction::Assert(value) if acc == value => (pc + 1, acc),
+                Instruction::Assert(value) => {
+                    return Err(AirError::InvalidTransition(format!(
+                        "assertion at {} fails: accumulator {} is not {}",
+                        pc, acc, value
+                    )))
+                }
+            };
+            let state = [F::from_canonical_u64(pc as u64), acc, F::from_canonical_u64(instruction.opcode())];
+            for (column, value) in columns.iter_mut().zip(state.into_iter().chain([argument, input, acc * argument])) {
+                column[row] = value;
+            }
+            columns[FLAGS + flag][row] = F::one();
+            columns[COUNT][pc] += F::one();
+            (pc, acc) = next;
+        }
+        // The ROM repeats over the trace, like every fixed column
+        for (column, values) in columns[ROM..].iter_mut().zip(self.rom()) {
+            for (row, value) in column.iter_mut().enumerate() {
+                *value = values[row % values.len()];
+            }
+        }
+        Ok(ExecutionTrace {
+            columns: columns.into(),
+            length: trace_length,
+            num_registers: COLUMNS.len(),
+            aux_segments: Vec::new(),
+        })
+    }
+
+    /// Public inputs claiming `result` as the final accumulator
+    pub fn public_inputs(&self, result: F) -> PublicInputs<F> {
+        PublicInputs::new().assert_final(Self::ACC_REGISTER, result)
+    }
+}
+
+/// Compress `(pc, opcode, argument)` columns with the challenge `β`, as `α - compressed`
+fn compress<F: FieldElement>(columns: [Expr<F>; 3]) -> Expr<F> {
+    let [pc, op, arg] = columns;
+    challenge(0) - (pc + challenge(1) * (op + challenge(1) * arg))
+}
+
+/// LogUp lookup of the executed instructions into the program ROM
+///
+/// The segment holds `1 / (α - executed)`, `count / (α - rom)` and the
+/// running difference of their sums before and including each row, which
+/// starts and ends at zero.
+fn rom_lookup<F: FieldElement + 'static>() -> ChallengeSegment<F> {
+    let register = |name: &str| COLUMNS.iter().position(|column| *column == name).expect("VM column");
+    let executed_registers = [register("pc"), register("op"), register("arg")];
+    let rom_registers = [register("rom.pc"), register("rom.op"), register("rom.arg")];
+    let count = register("rom.count");
+    let build = move |main: &[Vec<F>], challenges: &[F]| {
+        let length = main.first().map_or(0, Vec::len);
+        let compress_row = |registers: [usize; 3], row: usize| {
+            let [pc, op, arg] = registers.map(|register| main[register][row]);
+            challenges[0] - (pc + challenges[1] * (op + challenges[1] * arg))
+        };
+        // A zero denominator has no inverse; its row then fails the constraints
+        let inverse = |value: F| value.inverse().unwrap_or_else(F::zero);
+        let mut columns: Vec<_> = (0..4).map(|_| Vec::with_capacity(length)).collect();
+        let mut sum = F::zero();
+        for row in 0..length {
+            let executed = inverse(compress_row(executed_registers, row));
+            let rom = main[count][row] * inverse(compress_row(rom_registers, row));
+            columns[0].push(executed);
+            columns[1].push(rom);
+            columns[2].push(sum);
+            sum = sum + executed - rom;
+            columns[3].push(sum);
+        }
+        columns
+    };
+
+    let [executed, rom, before, including] =
+        ["rom.executed", "rom.table", "rom.before", "rom.including"].map(col::<F>);
+    let executed_row = compress([col("pc"), col("op"), col("arg")]);
+    let rom_row = compress([col("rom.pc"), col("rom.op"), col("rom.arg")]);
+    let mut segment = ChallengeSegment::new(&["rom.executed", "rom.table", "rom.before", "rom.including"], 2, build);
+    for constraint in [
+        executed.clone() * executed_row - constant(F::one()),
+        rom.clone() * rom_row - col("rom.count"),
+        including.clone() - before.clone() - executed + rom,
+    ] {
+        segment = segment.with_transition(constraint.clone()).with_transition(constraint.next());
+    }
+    segment
+        .with_transition(before.next() - including)
+        .with_initial("rom.before", constant(F::zero()))
+        .with_final("rom.including", constant(F::zero()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_simple_vm_end_to_end() {
+        let value = PrimeField64::new;
+        // Knowledge of x with (x + 3) * 2 = 10, then a loop doubling a second input
+        let program = [
+            Instruction::Load,
+            Instruction::Add(value(3)),
+            Instruction::Mul(value(2)),
+            Instruction::Assert(value(10)),
+            Instruction::Load,
+            Instruction::Mul(value(2)),
+            Instruction::Jump(5),
+        ];
+        let vm = SimpleVmAir::new(&program).unwrap();
+        let air = vm.air().unwrap();
+        let trace = vm.trace(&[value(2), value(1)], 16).unwrap();
+        assert_eq!(trace.columns.value(SimpleVmAir::<PrimeField64>::PC_REGISTER, 15), value(5));
+        // Rows 5 to 14 alternate Mul and Jump, doubling five times before the last row
+        let result = value(32);
+        assert_eq!(trace.columns.value(SimpleVmAir::<PrimeField64>::ACC_REGISTER, 15), result);
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let proof = prover.prove(&air, &vm.public_inputs(result), &trace).unwrap();
+        verifier.verify_with_air(&air, &proof, &vm.public_inputs(result)).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &vm.public_inputs(value(64))).is_err());
+
+        // An input failing the assertion has no trace
+        assert!(vm.trace(&[value(3), value(1)], 16).is_err());
+        // Replacing the assertion with `Add(0)` satisfies every transition but not the ROM lookup
+        let mut forged = trace.clone();
+        let register = |name: &str| air.register(name).unwrap();
+        for (name, forged_value) in [("op", 2), ("arg", 0), ("product", 0), ("is_add", 1), ("is_assert", 0)] {
+            forged.columns.set(register(name), 3, value(forged_value));
+        }
+        assert!(prover.prove(&air, &vm.public_inputs(result), &forged).is_err());
+        let proof = prover.with_trace_check(false).prove(&air, &vm.public_inputs(result), &forged).unwrap();
+        assert!(verifier.verify_with_air(&air, &proof, &vm.public_inputs(result)).is_err());
+        assert!(SimpleVmAir::new(&[Instruction::<PrimeField64>::Jump(1)]).is_err());
+    }
+}
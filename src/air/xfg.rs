@@ -0,0 +1,502 @@
+//! Fuego XFG AIRs
+//!
+//! Statements about Fuego (XFG) transactions, proven with the gadgets of
+//! this crate.
+//!
+//! [`BurnAir`] proves that a burn transaction sending a given amount to the
+//! burn address is included in a transaction Merkle tree under a block
+//! commitment. The tree is an algebraic commitment over burn transactions,
+//! hashed with Poseidon or Rescue so that it can be opened inside the
+//! AIR; Fuego's own Keccak transaction tree would have to be recommitted
+//! this way by whoever publishes the block commitment.
+//!
+//! A proof only keeps the witness private if it is zero-knowledge: without
+//! [`StarkProver::with_zk`](crate::proof::StarkProver::with_zk) the proof
+//! ships the whole trace, so [`BurnAir::verify`] and
+//! [`ConfidentialTransferAir::verify`] accept zero-knowledge proofs only.
+//!
+//! [`ConfidentialTransferAir`] proves that a transfer between hidden amounts
+//! is well formed: every note is committed to, every amount is in range and
+//! the inputs add up to the outputs. Only the note commitments are public.
+
+use crate::prelude::*;
+use crate::air::gadgets::{balance, MerkleHasher, MerklePath, NoteCommitment};
+use crate::air::{Air, AirError, AuxSegment, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::verification::VerificationError;
+use crate::proof::StarkVerifier;
+use crate::types::stark::{ExecutionTrace, StarkProof};
+use crate::types::{ColIdx, FieldElement, RowIdx};
+use sha2::{Digest, Sha256};
+
+/// Bits of an amount in atomic units; 8 million XFG at 7 decimals fits in 47
+const AMOUNT_BITS: u32 = 48;
+
+/// Domain separator for encoding addresses
+const ADDRESS_DOMAIN: &[u8] = b"xfg-stark/xfg/address";
+
+/// Field element standing for a Fuego address, from SHA-256 of its text
+pub fn encode_address<F: FieldElement>(address: &str) -> F {
+    let mut hasher = Sha256::new();
+    hasher.update(ADDRESS_DOMAIN);
+    hasher.update(address.as_bytes());
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    F::from_canonical_u64(u64::from_le_bytes(bytes) % F::MODULUS)
+}
+
+/// Verify a zero-knowledge proof of `air`, rejecting proofs that ship the trace
+fn verify_zero_knowledge<F: FieldElement>(
+    air: &Air<F>,
+    verifier: &StarkVerifier<F>,
+    proof: &StarkProof<F>,
+    public_inputs: &PublicInputs<F>,
+) -> Result<(), VerificationError> {
+    if !proof.metadata.parameters.zero_knowledge {
+        return Err(VerificationError::ParameterMismatch(
+            "zero-knowledge proof required".to_string(),
+        ));
+    }
+    verifier.verify_with_air(air, proof, public_inputs)
+}
+
+/// Private inputs of a burn proof: the burn transaction and its Merkle path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurnWitness<F: FieldElement> {
+    /// Burned amount in atomic units
+    pub amount: u64,
+    /// Identifier of the transaction, such as its hash reduced to the field
+    pub transaction_id: F,
+    /// Position of the transaction in the tree
+    pub index: u64,
+    /// Sibling nodes from the leaf level up
+    pub siblings: Vec<F>,
+}
+
+/// Inclusion of a well-formed XFG burn transaction under a block commitment
+///
+/// The trace holds the transaction fields, its index, the block commitment
+/// and the Merkle siblings in constant registers. The AIR fixes the burn
+/// address with a boundary constraint, range checks the amount and proves
+/// that the hash of `(amount, burn address, transaction id)` is the leaf at
+/// the index under the commitment. The block commitment and the amount are
+/// public inputs; the transaction and its position stay private as long as
+/// the proof is zero-knowledge, which [`BurnAir::verify`] requires.
+#[derive(Debug, Clone)]
+pub struct BurnAir<F: FieldElement> {
+    /// Address the burn must pay to
+    burn_address: F,
+    /// Transaction tree opening, with the leaf hashed from the transaction
+    path: MerklePath<F>,
+    /// Security parameter in bits
+    security_parameter: u32,
+}
+
+impl<F: FieldElement + 'static> BurnAir<F> {
+    /// Register holding the burned amount
+    pub const AMOUNT_REGISTER: usize = 0;
+    /// Register holding the burn address
+    pub const ADDRESS_REGISTER: usize = 1;
+    /// Register holding the transaction identifier
+    pub const TRANSACTION_REGISTER: usize = 2;
+    /// Register holding the index of the transaction in the tree
+    pub const INDEX_REGISTER: usize = 3;
+    /// Register holding the block commitment
+    pub const COMMITMENT_REGISTER: usize = 4;
+    /// First register holding the Merkle siblings
+    pub const SIBLINGS_REGISTER: usize = 5;
+    /// Bits of a burned amount
+    pub const AMOUNT_BITS: u32 = AMOUNT_BITS;
+    /// Number of trace rows
+    pub const TRACE_LENGTH: usize = 8;
+
+    /// Burns to `burn_address` in transaction trees of `depth` levels hashed by `hasher`
+    pub fn new(depth: usize, burn_address: F, hasher: impl Into<MerkleHasher<F>>) -> Result<Self, AirError> {
+        let siblings: Vec<usize> = (Self::SIBLINGS_REGISTER..Self::SIBLINGS_REGISTER + depth).collect();
+        let path = MerklePath::new(depth, hasher)?
+            .with_columns(0, Self::INDEX_REGISTER, Self::COMMITMENT_REGISTER, &siblings)?
+            .with_leaf_preimage(&[Self::AMOUNT_REGISTER, Self::ADDRESS_REGISTER, Self::TRANSACTION_REGISTER]);
+        Ok(Self {
+            burn_address,
+            path,
+            security_parameter: 128,
+        })
+    }
+
+    /// Use a different security parameter
+    pub fn with_security_parameter(mut self, security_parameter: u32) -> Self {
+        self.security_parameter = security_parameter;
+        self
+    }
+
+    /// Address the burn must pay to
+    pub fn burn_address(&self) -> F {
+        self.burn_address
+    }
+
+    /// Number of levels of the transaction tree
+    pub fn depth(&self) -> usize {
+        self.path.depth()
+    }
+
+    /// Leaf committing to a burn transaction
+    pub fn leaf(&self, amount: u64, transaction_id: F) -> F {
+        let amount = F::from_canonical_u64(amount);
+        self.path.hasher().hash(&[amount, self.burn_address, transaction_id])
+    }
+
+    /// Block commitment the witness opens to
+    pub fn block_commitment(&self, witness: &BurnWitness<F>) -> F {
+        let leaf = self.leaf(witness.amount, witness.transaction_id);
+        self.path.hasher().root(leaf, witness.index, &witness.siblings)
+    }
+
+    /// AIR constraining the burn address, the amount and the inclusion
+    pub fn air(&self) -> Air<F> {
+        let width = Self::SIBLINGS_REGISTER + self.depth();
+        Air::new(
+            vec![],
+            TransitionFunction::identity(width),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(Self::ADDRESS_REGISTER, self.burn_address)]),
+            self.security_parameter,
+        )
+        .with_range_check(Self::AMOUNT_REGISTER, Self::AMOUNT_BITS)
+        .expect("the amount fits in the field")
+        .with_aux_segment(AuxSegment::new(self.path.clone()))
+    }
+
+    /// Witness trace of a burn
+    pub fn trace(&self, witness: &BurnWitness<F>) -> Result<ExecutionTrace<F>, AirError> {
+        if witness.siblings.len() != self.depth() {
+            return Err(AirError::InvalidConstraint(format!(
+                "burn tree of depth {} with {} siblings",
+                self.depth(),
+                witness.siblings.len()
+            )));
+        }
+        if witness.amount >> Self::AMOUNT_BITS != 0 || witness.index >> self.depth() != 0 {
+            return Err(AirError::InvalidConstraint(format!(
+                "burn of {} at index {} is out of range",
+                witness.amount, witness.index
+            )));
+        }
+
+        let mut registers = vec![
+            F::from_canonical_u64(witness.amount),
+            self.burn_address,
+            witness.transaction_id,
+            F::from_canonical_u64(witness.index),
+            self.block_commitment(witness),
+        ];
+        registers.extend_from_slice(&witness.siblings);
+        let num_registers = registers.len();
+        Ok(ExecutionTrace {
+            columns: registers
+                .into_iter()
+                .map(|value| vec![value; Self::TRACE_LENGTH])
+                .collect::<Vec<_>>()
+                .into(),
+            length: Self::TRACE_LENGTH,
+            num_registers,
+            aux_segments: Vec::new(),
+        })
+    }
+
+    /// Public inputs claiming a burn of `amount` under `block_commitment`
+    pub fn public_inputs(&self, block_commitment: F, amount: u64) -> PublicInputs<F> {
+        PublicInputs::new()
+            .assert(ColIdx(Self::COMMITMENT_REGISTER), RowIdx(0), block_commitment)
+            .assert(ColIdx(Self::AMOUNT_REGISTER), RowIdx(0), F::from_canonical_u64(amount))
+    }
+
+    /// Verify a burn proof against its public inputs
+    ///
+    /// Proofs that are not zero-knowledge are rejected, since they publish
+    /// the transaction, its index and the Merkle siblings.
+    pub fn verify(
+        &self,
+        verifier: &StarkVerifier<F>,
+        proof: &StarkProof<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), VerificationError> {
+        verify_zero_knowledge(&self.air(), verifier, proof, public_inputs)
+    }
+}
+
+/// Note of a confidential transfer: a hidden amount and the secret blinding it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Note<F: FieldElement> {
+    /// Secret known to the owner of the note
+    pub secret: F,
+    /// Amount in atomic units
+    pub amount: u64,
+}
+
+/// Balanced transfer from input notes to output notes with hidden amounts
+///
+/// Every note takes three constant registers: its secret, its amount and its
+/// commitment `H(secret, amount)`, inputs first. The AIR proves each
+/// commitment with a [`NoteCommitment`] gadget, range checks each amount and
+/// checks that the input amounts add up to the output amounts. The
+/// commitments are the public inputs. Spending rules for the inputs, such as
+/// nullifiers and membership in a note tree, are separate statements.
+///
+/// The commitments hide the amounts only in a zero-knowledge proof, which
+/// [`ConfidentialTransferAir::verify`] requires; any other proof ships the
+/// secrets and amounts in its trace.
+#[derive(Debug, Clone)]
+pub struct ConfidentialTransferAir<F: FieldElement> {
+    /// Number of input notes
+    inputs: usize,
+    /// Number of output notes
+    outputs: usize,
+    /// Commitment gadget over the registers of the first note
+    commitment: NoteCommitment<F>,
+    /// Security parameter in bits
+    security_parameter: u32,
+}
+
+impl<F: FieldElement + 'static> ConfidentialTransferAir<F> {
+    /// Registers taken by each note
+    pub const NOTE_REGISTERS: usize = 3;
+    /// Bits of an amount
+    pub const AMOUNT_BITS: u32 = AMOUNT_BITS;
+    /// Number of trace rows
+    pub const TRACE_LENGTH: usize = 8;
+
+    /// Transfer from `inputs` notes to `outputs` notes, committed with `hasher`
+    pub fn new(inputs: usize, outputs: usize, hasher: impl Into<MerkleHasher<F>>) -> Result<Self, AirError> {
+        if inputs == 0 || outputs == 0 {
+            return Err(AirError::InvalidConstraint(
+                "a transfer needs at least one input and one output".to_string(),
+            ));
+        }
+        Ok(Self {
+            inputs,
+            outputs,
+            commitment: NoteCommitment::new(hasher),
+            security_parameter: 128,
+        })
+    }
+
+    /// Use a different security parameter
+    pub fn with_security_parameter(mut self, security_parameter: u32) -> Self {
+        self.security_parameter = security_parameter;
+        self
+    }
+
+    /// Number of input notes
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    /// Number of output notes
+    pub fn outputs(&self) -> usize {
+        self.outputs
+    }
+
+    /// Register holding the amount of a note, counting inputs first
+    pub fn amount_register(note: usize) -> usize {
+        Self::NOTE_REGISTERS * note + 1
+    }
+
+    /// Register holding the commitment of a note, counting inputs first
+    pub fn commitment_register(note: usize) -> usize {
+        Self::NOTE_REGISTERS * note + 2
+    }
+
+    /// Commitment to a note
+    pub fn commitment(&self, note: &Note<F>) -> F {
+        self.commitment.commitment(note.secret, F::from_canonical_u64(note.amount))
+    }
+
+    /// AIR constraining the commitments, the amounts and the balance
+    pub fn air(&self) -> Air<F> {
+        let notes = self.inputs + self.outputs;
+        let amounts: Vec<usize> = (0..notes).map(Self::amount_register).collect();
+        let mut air = Air::new(
+            vec![],
+            TransitionFunction::identity(Self::NOTE_REGISTERS * notes),
+            BoundaryConditions::new(vec![]),
+            self.security_parameter,
+        );
+        for note in 0..notes {
+            let secret = Self::NOTE_REGISTERS * note;
+            let commitment = self.commitment.clone().with_columns(
+                secret,
+                Self::amount_register(note),
+                Self::commitment_register(note),
+            );
+            air = air
+                .with_aux_segment(AuxSegment::new(commitment))
+                .with_range_check(Self::amount_register(note), Self::AMOUNT_BITS)
+                .expect("the amount fits in the field");
+        }
+        air.with_aux_segment(balance(&amounts[..self.inputs], &amounts[self.inputs..]).expect("both sides have notes"))
+    }
+
+    /// Witness trace of a transfer
+    ///
+    /// The trace is built even if the amounts do not balance, in which case
+    /// it violates the AIR.
+    pub fn trace(&self, inputs: &[Note<F>], outputs: &[Note<F>]) -> Result<ExecutionTrace<F>, AirError> {
+        if inputs.len() != self.inputs || outputs.len() != self.outputs {
+            return Err(AirError::InvalidConstraint(format!(
+                "transfer of {} inputs to {} outputs given {} and {} notes",
+                self.inputs,
+                self.outputs,
+                inputs.len(),
+                outputs.len()
+            )));
+        }
+        if let Some(note) = inputs.iter().chain(outputs).find(|note| note.amount >> AMOUNT_BITS != 0) {
+            return Err(AirError::InvalidConstraint(format!("note amount {} is out of range", note.amount)));
+        }
+
+        let columns: Vec<Vec<F>> = inputs
+            .iter()
+            .chain(outputs)
+            .flat_map(|note| [note.secret, F::from_canonical_u64(note.amount), self.commitment(note)])
+            .map(|value| vec![value; Self::TRACE_LENGTH])
+            .collect();
+        let num_registers = columns.len();
+        Ok(ExecutionTrace {
+            columns: columns.into(),
+            length: Self::TRACE_LENGTH,
+            num_registers,
+            aux_segments: Vec::new(),
+        })
+    }
+
+    /// Public inputs claiming the note commitments, inputs first
+    pub fn public_inputs(&self, commitments: &[F]) -> Result<PublicInputs<F>, AirError> {
+        if commitments.len() != self.inputs + self.outputs {
+            return Err(AirError::InvalidConstraint(format!(
+                "transfer of {} notes given {} commitments",
+                self.inputs + self.outputs,
+                commitments.len()
+            )));
+        }
+        Ok(commitments
+            .iter()
+            .enumerate()
+            .fold(PublicInputs::new(), |inputs, (note, &commitment)| {
+                inputs.assert(ColIdx(Self::commitment_register(note)), RowIdx(0), commitment)
+            }))
+    }
+
+    /// Verify a transfer proof against the note commitments
+    ///
+    /// Proofs that are not zero-knowledge are rejected, since they publish
+    /// the secrets and amounts of every note.
+    pub fn verify(
+        &self,
+        verifier: &StarkVerifier<F>,
+        proof: &StarkProof<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), VerificationError> {
+        verify_zero_knowledge(&self.air(), verifier, proof, public_inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::gadgets::{PoseidonParams, RescueParams};
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_burn_air_end_to_end() {
+        let value = PrimeField64::new;
+        let hasher = PoseidonParams::<PrimeField64>::new(3, 8, 22).unwrap();
+        let address = encode_address("fireXFGburnaddress");
+        let burn = BurnAir::new(2, address, hasher).unwrap();
+
+        // A tree of four burns; the proven one sends 8 XFG at index 1
+        let amount = 80_000_000;
+        let leaves: Vec<_> = [(1_000, 11), (amount, 22), (5, 33), (70, 44)]
+            .iter()
+            .map(|&(amount, id)| burn.leaf(amount, value(id)))
+            .collect();
+        let hasher = burn.path.hasher();
+        let witness = BurnWitness {
+            amount,
+            transaction_id: value(22),
+            index: 1,
+            siblings: vec![leaves[0], hasher.compress(leaves[2], leaves[3])],
+        };
+        let commitment = burn.block_commitment(&witness);
+        let upper = [hasher.compress(leaves[0], leaves[1]), hasher.compress(leaves[2], leaves[3])];
+        assert_eq!(commitment, hasher.compress(upper[0], upper[1]));
+
+        let air = burn.air();
+        let trace = burn.trace(&witness).unwrap();
+        assert_eq!(air.check_trace(&trace), Ok(()));
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let public_inputs = burn.public_inputs(commitment, amount);
+        let proof = prover.clone().with_zk(true).prove(&air, &public_inputs, &trace).unwrap();
+        assert!(proof.trace.columns.is_empty() && proof.trace.aux_segments.is_empty());
+        burn.verify(&verifier, &proof, &public_inputs).unwrap();
+
+        // The proof is bound to the amount and the commitment
+        assert!(burn.verify(&verifier, &proof, &burn.public_inputs(commitment, amount + 1)).is_err());
+        assert!(burn.verify(&verifier, &proof, &burn.public_inputs(upper[0], amount)).is_err());
+
+        // A proof that publishes the transaction is not accepted
+        let public = prover.prove(&air, &public_inputs, &trace).unwrap();
+        verifier.verify_with_air(&air, &public, &public_inputs).unwrap();
+        assert!(matches!(
+            burn.verify(&verifier, &public, &public_inputs),
+            Err(VerificationError::ParameterMismatch(_))
+        ));
+
+        // A transaction paying elsewhere does not prove a burn
+        let elsewhere = BurnAir::new(2, encode_address("someone"), PoseidonParams::new(3, 8, 22).unwrap()).unwrap();
+        let trace = elsewhere.trace(&witness).unwrap();
+        assert!(air.check_trace(&trace).is_err());
+
+        let oversized = BurnWitness { amount: 1 << 48, ..witness };
+        assert!(burn.trace(&oversized).is_err());
+    }
+
+    #[test]
+    fn test_confidential_transfer_air() {
+        let value = PrimeField64::new;
+        let hasher = RescueParams::<PrimeField64>::new(4, 8).unwrap();
+        let transfer = ConfidentialTransferAir::new(2, 2, hasher).unwrap();
+        let note = |secret, amount| Note { secret: value(secret), amount };
+        let inputs = [note(1, 300), note(2, 200)];
+        let outputs = [note(3, 450), note(4, 50)];
+        let commitments: Vec<_> = inputs.iter().chain(&outputs).map(|note| transfer.commitment(note)).collect();
+
+        let air = transfer.air();
+        let trace = transfer.trace(&inputs, &outputs).unwrap();
+        assert_eq!(air.check_trace(&trace), Ok(()));
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let public_inputs = transfer.public_inputs(&commitments).unwrap();
+        let proof = prover.clone().with_zk(true).prove(&air, &public_inputs, &trace).unwrap();
+        assert!(proof.trace.columns.is_empty() && proof.trace.aux_segments.is_empty());
+        transfer.verify(&verifier, &proof, &public_inputs).unwrap();
+
+        // The proof is bound to the commitments, which hide the amounts
+        let mut swapped = commitments.clone();
+        swapped.swap(2, 3);
+        assert!(transfer.verify(&verifier, &proof, &transfer.public_inputs(&swapped).unwrap()).is_err());
+        assert_ne!(transfer.commitment(&note(3, 450)), transfer.commitment(&note(3, 451)));
+
+        // A proof that publishes the notes is not accepted
+        let public = prover.prove(&air, &public_inputs, &trace).unwrap();
+        assert!(transfer.verify(&verifier, &public, &public_inputs).is_err());
+
+        // Minting out of nothing breaks the balance
+        let inflated = [note(3, 450), note(4, 51)];
+        let inflated = transfer.trace(&inputs, &inflated).unwrap();
+        assert!(prover.prove(&air, &public_inputs, &inflated).is_err());
+        assert!(transfer.trace(&inputs, &[note(3, 1 << 48), note(4, 0)]).is_err());
+        assert!(transfer.public_inputs(&commitments[..3]).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! Backend Comparison
+//!
+//! Runs one AIR through the native prover and the Winterfell backend, so
+//! that their prover time, proof size and verifier time can be read side by
+//! side for the same trace sizes.
+
+use super::{format_duration, BenchmarkResult, BenchmarkSuite};
+use crate::air::Air;
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::{StarkProver, StarkVerifier};
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+use crate::winterfell_integration::{Backend, XfgWinterfellProver, XfgWinterfellVerifier};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Proof of one trace by one backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendRun {
+    /// Time to generate the proof, trace generation excluded
+    pub prove_time: Duration,
+    /// Serialized size of the proof in bytes
+    pub proof_size: usize,
+    /// Time to verify the proof
+    pub verify_time: Duration,
+}
+
+impl Display for BackendRun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "prove {}, verify {}, {} bytes",
+            format_duration(self.prove_time),
+            format_duration(self.verify_time),
+            self.proof_size
+        )
+    }
+}
+
+/// Native and Winterfell proofs of one trace, or why a backend failed
+#[derive(Debug, Clone)]
+pub struct BackendComparison {
+    /// Number of trace rows
+    pub trace_size: usize,
+    /// Native prover and verifier
+    pub native: Result<BackendRun, String>,
+    /// Winterfell backend
+    pub winterfell: Result<BackendRun, String>,
+}
+
+impl Display for BackendComparison {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let run = |run: &Result<BackendRun, String>| match run {
+            Ok(run) => run.to_string(),
+            Err(error) => format!("failed: {}", error),
+        };
+        write!(
+            f,
+            "{} rows: {} {} | {} {}",
+            self.trace_size,
+            Backend::Native,
+            run(&self.native),
+            Backend::Winterfell,
+            run(&self.winterfell)
+        )
+    }
+}
+
+impl<F: FieldElement> BenchmarkSuite<F> {
+    /// Prove and verify `air` with both backends for each trace size
+    ///
+    /// Each trace is generated once from `initial_state` and proven by the
+    /// native prover and by the Winterfell backend with their default
+    /// options. The AIR's own boundary constraints are the statement, so the
+    /// Winterfell backend needs at least one of them. Successful runs are
+    /// also recorded as results of the suite.
+    pub fn benchmark_backends(
+        &mut self,
+        air: &Air<F>,
+        initial_state: &[F],
+        trace_sizes: &[usize],
+    ) -> Vec<BackendComparison> {
+        let prover = StarkProver::new(128);
+        let public_inputs = PublicInputs::new();
+        let mut comparisons = Vec::with_capacity(trace_sizes.len());
+        for &trace_size in trace_sizes {
+            let trace = match prover.generate_trace(air, initial_state, trace_size) {
+                Ok(trace) => trace,
+                Err(error) => {
+                    let error = format!("trace generation failed: {}", error);
+                    comparisons.push(BackendComparison {
+                        trace_size,
+                        native: Err(error.clone()),
+                        winterfell: Err(error),
+                    });
+                    continue;
+                }
+            };
+            let comparison = BackendComparison {
+                trace_size,
+                native: run_native(&prover, air, &public_inputs, &trace),
+                winterfell: run_winterfell(air, &public_inputs, &trace),
+            };
+            let runs = [(Backend::Native, &comparison.native), (Backend::Winterfell, &comparison.winterfell)];
+            for (backend, run) in runs {
+                if let Ok(run) = run {
+                    let operation = format!("{} STARK Proof", backend);
+                    let mut result = BenchmarkResult::new(operation, run.prove_time, trace_size);
+                    result.add_metric("proof_size".to_string(), run.proof_size as f64);
+                    result.add_metric("verify_time_ms".to_string(), run.verify_time.as_secs_f64() * 1000.0);
+                    self.results.push(result);
+                }
+            }
+            comparisons.push(comparison);
+        }
+        comparisons
+    }
+}
+
+/// Prove and verify a trace with the native backend
+fn run_native<F: FieldElement>(
+    prover: &StarkProver<F>,
+    air: &Air<F>,
+    public_inputs: &PublicInputs<F>,
+    trace: &ExecutionTrace<F>,
+) -> Result<BackendRun, String> {
+    let start = Instant::now();
+    let proof = prover.prove(air, public_inputs, trace).map_err(|error| error.to_string())?;
+    let prove_time = start.elapsed();
+    let start = Instant::now();
+    StarkVerifier::default()
+        .verify_with_air(air, &proof, public_inputs)
+        .map_err(|error| error.to_string())?;
+    Ok(BackendRun {
+        prove_time,
+        proof_size: proof.to_bytes().len(),
+        verify_time: start.elapsed(),
+    })
+}
+
+/// Prove and verify a trace with the Winterfell backend
+fn run_winterfell<F: FieldElement>(
+    air: &Air<F>,
+    public_inputs: &PublicInputs<F>,
+    trace: &ExecutionTrace<F>,
+) -> Result<BackendRun, String> {
+    let start = Instant::now();
+    let proof = XfgWinterfellProver::new()
+        .prove(air, public_inputs, trace)
+        .map_err(|error| error.to_string())?;
+    let prove_time = start.elapsed();
+    let start = Instant::now();
+    XfgWinterfellVerifier::new()
+        .verify(air, public_inputs, &proof)
+        .map_err(|error| error.to_string())?;
+    Ok(BackendRun {
+        prove_time,
+        proof_size: proof.to_bytes().len(),
+        verify_time: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::fixtures::fibonacci_air;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_backends_side_by_side() {
+        let air = fibonacci_air::<PrimeField64>();
+        let mut suite = BenchmarkSuite::<PrimeField64>::new();
+        let comparisons = suite.benchmark_backends(&air, &[PrimeField64::one(), PrimeField64::one()], &[16, 32]);
+        assert_eq!(comparisons.len(), 2);
+        for comparison in &comparisons {
+            assert!(comparison.native.as_ref().unwrap().proof_size > 0);
+            assert!(comparison.winterfell.as_ref().unwrap().proof_size > 0);
+            assert!(comparison.to_string().contains("Winterfell prove"));
+        }
+        assert_eq!(suite.results().len(), 4);
+
+        // Winterfell takes no trace whose length is not a power of two
+        let comparisons = suite.benchmark_backends(&air, &[PrimeField64::one(), PrimeField64::one()], &[12]);
+        assert!(comparisons[0].winterfell.is_err());
+    }
+}
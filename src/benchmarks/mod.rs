@@ -10,10 +10,12 @@
 //! - **Memory Profiling**: Memory usage analysis and optimization
 //! - **Scalability Testing**: Performance scaling with input size
 //! - **Optimization Recommendations**: Automated performance suggestions
+//! - **Backend Comparison**: Native and Winterfell proofs of the same AIR side by side
 
 use crate::types::FieldElement;
 use crate::types::field::PrimeField64;
 use crate::proof::StarkProver;
+use crate::proof::public_inputs::PublicInputs;
 use crate::proof::fri::FriProver;
 use crate::proof::merkle::MerkleTree;
 use crate::air::{Air, Constraint, TransitionFunction, BoundaryConditions};
@@ -21,6 +23,10 @@ use crate::air::constraints::ConstraintType;
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
 
+mod backends;
+
+pub use backends::{BackendComparison, BackendRun};
+
 /// Benchmark results
 #[derive(Debug, Clone)]
 pub struct BenchmarkResult {
@@ -214,11 +220,14 @@ impl<F: FieldElement> BenchmarkSuite<F> {
         let prover = StarkProver::new(128);
         let air = create_test_air::<F>();
         let initial_state = vec![F::zero(); 2];
+        let public_inputs = PublicInputs::new();
         
         let start = Instant::now();
         
         for _ in 0..iterations {
-            let _proof = prover.prove(&air, &initial_state, trace_size);
+            let _proof = prover
+                .generate_trace(&air, &initial_state, trace_size)
+                .and_then(|witness| prover.prove(&air, &public_inputs, &witness));
         }
         
         let duration = start.elapsed();
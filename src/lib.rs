@@ -31,6 +31,13 @@
 //! - Optimized field arithmetic implementations
 //! - Efficient polynomial evaluation algorithms
 //! - Minimal runtime overhead for type safety
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature the crate builds as `no_std` with
+//! `alloc`: the types, polynomials, AIRs and proof verification are
+//! available to embedded and wasm verifiers. Timing, memory-mapped traces,
+//! the Winterfell integration and the benchmarks need `std`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "constant_time", feature(const_fn_floating_point_arithmetic))]
@@ -39,6 +46,8 @@
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 
+extern crate alloc;
+
 pub mod field;
 pub mod polynomial;
 pub mod stark;
@@ -46,7 +55,9 @@ pub mod types;
 pub mod utils;
 pub mod air;
 pub mod proof;
+#[cfg(feature = "std")]
 pub mod winterfell_integration;
+#[cfg(feature = "std")]
 pub mod benchmarks;
 
 
@@ -57,17 +68,21 @@ pub use types::*;
 pub use utils::*;
 pub use air::*;
 pub use proof::*;
+#[cfg(feature = "std")]
 pub use winterfell_integration::*;
+#[cfg(feature = "std")]
 pub use benchmarks::*;
 
 
 /// Re-exports for common cryptographic operations
+#[cfg(feature = "std")]
 pub mod crypto {
     pub use winter_crypto::*;
     pub use winter_math::*;
 }
 
 /// Re-exports for Winterfell framework integration
+#[cfg(feature = "std")]
 pub mod winterfell {
     pub use winterfell::*;
 }
@@ -92,16 +107,26 @@ pub enum XfgStarkError {
     TypeError(#[from] types::TypeError),
     
     /// Serialization error
+    #[cfg(feature = "std")]
     #[error("Serialization error: {0}")]
     SerializationError(#[from] bincode::Error),
     
     /// Cryptographic error
     #[error("Cryptographic error: {0}")]
-    CryptoError(String),
+    CryptoError(alloc::string::String),
 }
 
 /// Result type for XFG STARK operations
-pub type Result<T> = std::result::Result<T, XfgStarkError>;
+pub type Result<T> = core::result::Result<T, XfgStarkError>;
+
+/// Items of the standard prelude, taken from `alloc` so that `no_std` builds have them
+pub(crate) mod prelude {
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
 
 /// XFG STARK version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
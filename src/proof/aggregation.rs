@@ -0,0 +1,198 @@
+//! Proof Aggregation
+//!
+//! This module rolls up independent proofs of the same AIR (for example one
+//! burn proof per transaction) into a single [`AggregatedProof`] per block.
+//!
+//! ## Construction
+//!
+//! The aggregate records the shared AIR once and keeps the members as
+//! [`DetachedProof`]s, each bound to the AIR by the digest in its metadata.
+//! It commits to the digests of the members with a Merkle tree, so any member
+//! can later be shown to be part of the aggregate with a logarithmic
+//! inclusion proof. Verification checks the commitment and every member's AIR
+//! digest, then verifies the members, with the AIR reattached, through
+//! [`StarkVerifier::verify_batch`](crate::proof::StarkVerifier::verify_batch).
+//! The aggregate is a batch, not a recursive proof: its size and verification
+//! cost grow linearly with the number of members.
+
+use crate::prelude::*;
+use crate::types::{FieldElement, StarkComponent};
+use crate::types::stark::{Air as StarkAir, DetachedProof, StarkProof};
+use crate::proof::merkle::{MerkleError, MerkleProof, MerkleTree};
+use sha2::{Digest, Sha256};
+use core::fmt::{Display, Formatter};
+
+/// Aggregation error types
+#[derive(Debug, thiserror::Error)]
+pub enum AggregationError {
+    /// No proofs to aggregate
+    #[error("No proofs to aggregate")]
+    Empty,
+
+    /// A proof was generated for a different AIR
+    #[error("Proof {0} does not match the aggregated AIR")]
+    AirMismatch(usize),
+
+    /// Merkle tree error
+    #[error("Merkle tree error: {0}")]
+    MerkleError(#[from] MerkleError),
+}
+
+/// Proofs of one AIR rolled up under a single commitment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedProof<F: FieldElement> {
+    /// AIR shared by every member proof
+    pub air: StarkAir<F>,
+    /// Member proofs, without the AIR
+    pub proofs: Vec<DetachedProof<F>>,
+    /// Merkle root over the member proof digests
+    pub root: [u8; 32],
+}
+
+impl<F: FieldElement> AggregatedProof<F> {
+    /// Number of member proofs
+    pub fn len(&self) -> usize {
+        self.proofs.len()
+    }
+
+    /// Whether the aggregate has no members
+    pub fn is_empty(&self) -> bool {
+        self.proofs.is_empty()
+    }
+
+    /// Member proofs with the shared AIR reattached
+    pub fn members(&self) -> Vec<StarkProof<F>> {
+        self.proofs.iter().map(|proof| proof.clone().attach(self.air.clone())).collect()
+    }
+
+    /// Recompute the Merkle root over the member proof digests
+    pub fn compute_root(&self) -> Result<[u8; 32], AggregationError> {
+        Ok(digest_tree(&self.proofs)?.root_hash())
+    }
+
+    /// Inclusion proof of the member at `index`
+    pub fn membership_proof(&self, index: usize) -> Result<MerkleProof, AggregationError> {
+        Ok(digest_tree(&self.proofs)?.generate_proof(index)?)
+    }
+
+    /// Check that `proof` is included in an aggregate with the given root
+    pub fn verify_membership(root: [u8; 32], proof: &DetachedProof<F>, membership: &MerkleProof) -> bool {
+        membership.verify(&proof_digest(proof), root)
+    }
+}
+
+impl<F: FieldElement> Display for AggregatedProof<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "AggregatedProof(proofs={}, root={:02x?})", self.proofs.len(), &self.root[..8])
+    }
+}
+
+/// Aggregate proofs of the same AIR
+pub fn aggregate<F: FieldElement>(proofs: &[StarkProof<F>]) -> Result<AggregatedProof<F>, AggregationError> {
+    let first = proofs.first().ok_or(AggregationError::Empty)?;
+    if let Some(index) = proofs.iter().position(|proof| proof.air != first.air) {
+        return Err(AggregationError::AirMismatch(index));
+    }
+
+    let members: Vec<_> = proofs.iter().map(|proof| proof.clone().into_detached()).collect();
+    Ok(AggregatedProof {
+        air: first.air.clone(),
+        root: digest_tree(&members)?.root_hash(),
+        proofs: members,
+    })
+}
+
+/// Digest of a serialized member proof
+pub fn proof_digest<F: FieldElement>(proof: &DetachedProof<F>) -> Vec<u8> {
+    Sha256::digest(proof.to_bytes()).to_vec()
+}
+
+/// Merkle tree over the digests of a set of member proofs
+fn digest_tree<F: FieldElement>(proofs: &[DetachedProof<F>]) -> Result<MerkleTree, MerkleError> {
+    let leaves: Vec<Vec<u8>> = proofs.iter().map(proof_digest).collect();
+    MerkleTree::new(&leaves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::verification::VerificationError;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+    use crate::types::Digest;
+
+    fn burn_air() -> Air<PrimeField64> {
+        Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        )
+    }
+
+    #[test]
+    fn test_aggregate_and_verify() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let air = burn_air();
+
+        let initial_states: Vec<_> = (1..4).map(|second| [PrimeField64::new(1), PrimeField64::new(second)]).collect();
+        let public_inputs: Vec<_> = initial_states
+            .iter()
+            .map(|state| PublicInputs::from_initial_state(state))
+            .collect();
+        let proofs: Vec<_> = initial_states
+            .iter()
+            .zip(&public_inputs)
+            .map(|(state, inputs)| {
+                let witness = prover.generate_trace(&air, state, 8).unwrap();
+                prover.prove(&air, inputs, &witness).unwrap()
+            })
+            .collect();
+        let aggregated = aggregate(&proofs).unwrap();
+        assert_eq!(aggregated.len(), 3);
+        verifier.verify_aggregate(&air, &aggregated, &public_inputs).unwrap();
+
+        // Members are checked against their own public inputs
+        let mut reordered = public_inputs.clone();
+        reordered.swap(0, 1);
+        assert!(verifier.verify_aggregate(&air, &aggregated, &reordered).is_err());
+
+        // The AIR is recorded once, and members reattach it
+        assert_eq!(aggregated.members(), proofs);
+
+        let membership = aggregated.membership_proof(2).unwrap();
+        assert!(AggregatedProof::verify_membership(aggregated.root, &aggregated.proofs[2], &membership));
+        assert!(!AggregatedProof::verify_membership(aggregated.root, &aggregated.proofs[1], &membership));
+
+        // Swapping a member breaks the commitment
+        let mut swapped = aggregated.clone();
+        swapped.proofs[0] = aggregated.proofs[1].clone();
+        assert!(matches!(
+            verifier.verify_aggregate(&air, &swapped, &public_inputs),
+            Err(VerificationError::AggregateCommitment)
+        ));
+
+        // A member bound to another AIR is rejected, even with a matching commitment
+        let mut rebound = aggregated.clone();
+        rebound.proofs[1].metadata.air_digest = Digest::new([7; 32]);
+        rebound.root = rebound.compute_root().unwrap();
+        assert!(matches!(
+            verifier.verify_aggregate(&air, &rebound, &public_inputs),
+            Err(VerificationError::AirDigest)
+        ));
+
+        // An invalid member fails the aggregate
+        let mut tampered = proofs.clone();
+        tampered[1].ood_frame.composition[0] = tampered[1].ood_frame.composition[0] + PrimeField64::one();
+        let tampered = aggregate(&tampered).unwrap();
+        assert!(matches!(
+            verifier.verify_aggregate(&air, &tampered, &public_inputs),
+            Err(VerificationError::OodConstraint)
+        ));
+
+        assert!(matches!(aggregate::<PrimeField64>(&[]), Err(AggregationError::Empty)));
+    }
+}
@@ -0,0 +1,104 @@
+//! Proving Backends
+//!
+//! [`ProverBackend`] and [`VerifierBackend`] put the native prover and
+//! verifier and the Winterfell backend of
+//! [`winterfell_integration`](crate::winterfell_integration) behind one API.
+//! A backend proves a trace of an AIR into an XFG [`StarkProof`], and checks
+//! such a proof against the AIR and its public inputs. Applications can pick
+//! a backend at run time, as a `Box<dyn ProverBackend<F>>`, and keep the code
+//! around it the same.
+//!
+//! The trait methods share their names with the inherent methods of the
+//! native prover and verifier, which take precedence when called on the
+//! concrete types; call them through the trait or a trait object.
+
+use crate::prelude::*;
+use crate::air::Air;
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::{StarkProver, StarkVerifier};
+use crate::types::stark::{ExecutionTrace, StarkError, StarkProof};
+use crate::types::FieldElement;
+use crate::Result;
+use core::fmt::{Display, Formatter};
+
+/// Backend a proof is generated with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Native [`StarkProver`]
+    Native,
+    /// Winterfell, through [`XfgWinterfellProver`](crate::winterfell_integration::XfgWinterfellProver)
+    Winterfell,
+}
+
+impl Display for Backend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Backend::Native => write!(f, "native"),
+            Backend::Winterfell => write!(f, "Winterfell"),
+        }
+    }
+}
+
+/// Prover that turns a trace of an AIR into an XFG proof
+pub trait ProverBackend<F: FieldElement> {
+    /// Backend of the prover
+    fn backend(&self) -> Backend;
+
+    /// Prove that `trace` satisfies `air` and the public inputs
+    fn prove(&self, air: &Air<F>, public_inputs: &PublicInputs<F>, trace: &ExecutionTrace<F>) -> Result<StarkProof<F>>;
+}
+
+/// Verifier of the XFG proofs of one backend
+pub trait VerifierBackend<F: FieldElement> {
+    /// Backend whose proofs the verifier checks
+    fn backend(&self) -> Backend;
+
+    /// Verify a proof of `air` and the public inputs
+    fn verify(&self, air: &Air<F>, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Result<()>;
+}
+
+impl<F: FieldElement> ProverBackend<F> for StarkProver<F> {
+    fn backend(&self) -> Backend {
+        Backend::Native
+    }
+
+    fn prove(&self, air: &Air<F>, public_inputs: &PublicInputs<F>, trace: &ExecutionTrace<F>) -> Result<StarkProof<F>> {
+        StarkProver::prove(self, air, public_inputs, trace)
+            .map_err(|error| StarkError::InvalidProof(format!("native prover: {}", error)).into())
+    }
+}
+
+impl<F: FieldElement> VerifierBackend<F> for StarkVerifier<F> {
+    fn backend(&self) -> Backend {
+        Backend::Native
+    }
+
+    /// Verify with [`verify_with_air`](StarkVerifier::verify_with_air)
+    fn verify(&self, air: &Air<F>, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Result<()> {
+        self.verify_with_air(air, proof, public_inputs)
+            .map_err(|error| StarkError::VerificationFailed(format!("native verifier: {}", error)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::fixtures::fibonacci;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_backends_behind_one_api() {
+        let (air, trace) = fibonacci::<PrimeField64>(32);
+        let native = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 31));
+
+        let prover: Box<dyn ProverBackend<PrimeField64>> = Box::new(native);
+        let verifier: Box<dyn VerifierBackend<PrimeField64>> = Box::new(StarkVerifier::new(48));
+        assert_eq!((prover.backend(), verifier.backend()), (Backend::Native, Backend::Native));
+        let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+        verifier.verify(&air, &proof, &public_inputs).unwrap();
+
+        let wrong = PublicInputs::new().assert_final(1, PrimeField64::new(7));
+        assert!(verifier.verify(&air, &proof, &wrong).is_err());
+    }
+}
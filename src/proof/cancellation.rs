@@ -0,0 +1,119 @@
+//! Proof Cancellation
+//!
+//! Proving services enforce per-request time limits without killing threads.
+//! A [`CancellationToken`] passed to
+//! [`StarkProver::prove_with_cancellation`](crate::proof::StarkProver::prove_with_cancellation)
+//! is checked between prover stages and inside the long-running loops, and
+//! the prover returns [`ProofError::Cancelled`] once it is cancelled or its
+//! deadline has passed.
+//!
+//! Cancellation is cooperative: work already running in a stage, such as a
+//! single polynomial interpolation, finishes before the token is checked.
+//! Deadlines need the `std` feature to read the clock.
+
+use crate::proof::ProofError;
+use core::sync::atomic::{AtomicBool, Ordering};
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Cancellation flag with an optional deadline
+///
+/// Clones share the flag, so a token can be cancelled from another thread
+/// while a proof runs with a clone of it.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// Shared cancellation flag
+    cancelled: Arc<AtomicBool>,
+    /// Instant after which the token counts as cancelled
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// Create a token that is only cancelled explicitly
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also cancel the token at `deadline`
+    #[cfg(feature = "std")]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Also cancel the token once `timeout` has elapsed from now
+    #[cfg(feature = "std")]
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.with_deadline(deadline),
+            None => self,
+        }
+    }
+
+    /// Cancel the token and every clone of it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled or its deadline has passed
+    pub fn is_cancelled(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return true;
+        }
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fail with [`ProofError::Cancelled`] if the token is cancelled
+    pub fn check(&self) -> Result<(), ProofError> {
+        if self.is_cancelled() {
+            return Err(ProofError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::StarkProver;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_cancellation_stops_proving() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let witness = prover.generate_trace(&air, &initial_state, 16).unwrap();
+        let public_inputs = PublicInputs::new();
+
+        let token = CancellationToken::new();
+        assert!(prover.prove_with_cancellation(&air, &public_inputs, &witness, &token).is_ok());
+
+        // Cancelling a clone cancels the token the prover holds
+        token.clone().cancel();
+        assert!(matches!(
+            prover.prove_with_cancellation(&air, &public_inputs, &witness, &token),
+            Err(ProofError::Cancelled)
+        ));
+
+        // Grinding 32 bits takes far longer than the deadline, so the
+        // deadline is noticed inside the grinding loop
+        let token = CancellationToken::new().with_timeout(Duration::from_millis(100));
+        let grinding = prover.with_proof_grinding(32);
+        assert!(matches!(
+            grinding.prove_with_cancellation(&air, &public_inputs, &witness, &token),
+            Err(ProofError::Cancelled)
+        ));
+        assert!(token.is_cancelled());
+    }
+}
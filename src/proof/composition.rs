@@ -0,0 +1,1482 @@
+//! Constraint Composition
+//!
+//! This module builds the constraint composition polynomial of a STARK proof.
+//! Trace columns are interpolated over the trace domain, extended to the
+//! low-degree extension (LDE) domain, the transition and boundary constraints
+//! are evaluated over the LDE domain and divided by their zerofiers, and the
+//! quotients are combined with transcript-derived coefficients.
+//!
+//! ## Domains
+//!
+//! The base field has no large power-of-two multiplicative subgroups, so the
+//! pipeline works over additive domains:
+//!
+//! - **Trace domain**: `{0, 1, ..., n - 1}`, where row `i` sits at point `i`
+//!   and the next row of point `x` is `x + 1`
+//! - **LDE domain**: `{n, n + 1, ..., n + N - 1}` with `N = n * blowup`, which
+//!   is disjoint from the trace domain so no zerofier vanishes on it
+//!
+//! Transition constraints must hold on rows `0..n - 1`, so their zerofier is
+//! `Z(x) = (x - 0)(x - 1)...(x - (n - 2))`. A boundary constraint on row `r`
+//! has zerofier `x - r`. Constraints reading a window of `w > 2` rows hold on
+//! rows `0..=n - w` only; their residuals are multiplied by the exemption
+//! `E(x) = (x - (n - w + 1))...(x - (n - 2))` of the rows they skip before
+//! the division by `Z`.
+
+use crate::prelude::*;
+use crate::types::{FieldElement, RowIdx};
+use crate::types::stark::{Air as StarkAir, ExecutionTrace};
+use crate::air::{Air, AuxFrame, AuxSegment, AuxWindow, BoundaryType, ConstraintViolation, FixedColumn};
+use super::ProofError;
+use super::dispatch::Kernels;
+use super::memory::{ColumnBuffer, MemoryPlan};
+use super::public_inputs::PublicInputs;
+use alloc::borrow::Cow;
+use core::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Exclusive upper bound on the integer points of the trace and LDE domains
+///
+/// Out-of-domain points are sampled at or above this bound, so they can never
+/// collide with a domain point.
+pub const MAX_DOMAIN_POINT: u64 = 1 << 32;
+
+/// Number of LDE rows evaluated together when building the composition polynomial
+///
+/// Each chunk is copied into a row-major block before its constraints are
+/// evaluated, so a chunk should fit comfortably in cache.
+pub const CONSTRAINT_CHUNK_ROWS: usize = 1024;
+
+/// Boundary constraint resolved to a concrete trace row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundaryAssertion<F: FieldElement> {
+    /// Register (column) index
+    pub register: usize,
+    /// Trace row the value is asserted at
+    pub row: usize,
+    /// Asserted value
+    pub value: F,
+}
+
+/// Auxiliary segment instantiated with its challenges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuxConstraints<F: FieldElement> {
+    /// Segment definition
+    pub segment: AuxSegment<F>,
+    /// Index of the first segment column in a combined trace row
+    pub offset: usize,
+    /// Challenges the segment was built from
+    pub challenges: Vec<F>,
+}
+
+/// Constraints entering the composition polynomial
+///
+/// The transition relation is the linear map applied by the AIR transition
+/// function: `next[i] = sum_j M[i][j] * current[j]`, except for free
+/// registers, whose transitions are left to the auxiliary segments. Auxiliary segments add
+/// their own transition constraints over the main and segment columns; rows
+/// passed to the constraints hold the main columns followed by every segment.
+/// Fixed registers are free and must hold the values of their column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionConstraints<F: FieldElement> {
+    /// Transition coefficient matrix
+    transition: Vec<Vec<F>>,
+    /// Whether each register is free of the transition matrix; missing registers are not
+    free: Vec<bool>,
+    /// Registers fixed by the AIR
+    fixed: Vec<FixedColumn<F>>,
+    /// Auxiliary segments, in commitment order
+    aux: Vec<AuxConstraints<F>>,
+    /// Boundary assertions
+    boundary: Vec<BoundaryAssertion<F>>,
+    /// Trace length the constraints are instantiated for
+    trace_length: usize,
+}
+
+impl<F: FieldElement> CompositionConstraints<F> {
+    /// Create a constraint set from its parts
+    pub fn new(transition: Vec<Vec<F>>, boundary: Vec<BoundaryAssertion<F>>, trace_length: usize) -> Self {
+        Self {
+            transition,
+            free: Vec::new(),
+            fixed: Vec::new(),
+            aux: Vec::new(),
+            boundary,
+            trace_length,
+        }
+    }
+
+    /// Instantiate the constraints of an AIR for a trace of the given length
+    ///
+    /// Initial boundary constraints are placed at their step; final boundary
+    /// constraints without an explicit step in range are placed on the last row.
+    pub fn from_air(air: &Air<F>, trace_length: usize) -> Result<Self, ProofError> {
+        let last_row = trace_length.saturating_sub(1);
+        let boundary = air
+            .boundary
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let row = match constraint.constraint_type {
+                    BoundaryType::Initial => constraint.step,
+                    BoundaryType::Final if constraint.step >= trace_length => last_row,
+                    BoundaryType::Final => constraint.step,
+                };
+                BoundaryAssertion {
+                    register: constraint.register,
+                    row,
+                    value: constraint.value,
+                }
+            })
+            .collect();
+
+        let constraints = Self::new(air.transition.coefficients().to_vec(), boundary, trace_length)
+            .with_free_registers(&air.free_registers)
+            .with_fixed_columns(&air.fixed_columns);
+        constraints.validate()?;
+        Ok(constraints)
+    }
+
+    /// Rebuild the constraints recorded in a proof's AIR
+    pub fn from_stark_air(air: &StarkAir<F>, trace_length: usize) -> Result<Self, ProofError> {
+        let boundary = air
+            .boundary
+            .constraints
+            .iter()
+            .map(|constraint| BoundaryAssertion {
+                register: constraint.register,
+                row: constraint.step,
+                value: constraint.value,
+            })
+            .collect();
+
+        let constraints = Self::new(air.transition.coefficients.clone(), boundary, trace_length);
+        constraints.validate()?;
+        Ok(constraints)
+    }
+
+    /// Leave the transitions of `registers` to the auxiliary segments
+    ///
+    /// Their residuals under the transition matrix are zero.
+    pub fn with_free_registers(mut self, registers: &[usize]) -> Self {
+        self.free = vec![false; self.transition.len()];
+        for &register in registers {
+            if let Some(free) = self.free.get_mut(register) {
+                *free = true;
+            }
+        }
+        self
+    }
+
+    /// Registers left free by the transition matrix
+    pub fn free_registers(&self) -> Vec<usize> {
+        (0..self.free.len()).filter(|&register| self.free[register]).collect()
+    }
+
+    /// Fix registers to the values of their columns
+    ///
+    /// The registers are left free by the transition matrix.
+    pub fn with_fixed_columns(mut self, columns: &[FixedColumn<F>]) -> Self {
+        self.free.resize(self.transition.len(), false);
+        for column in columns {
+            if let Some(free) = self.free.get_mut(column.register()) {
+                *free = true;
+            }
+        }
+        self.fixed = columns.to_vec();
+        self
+    }
+
+    /// Registers fixed by the AIR
+    pub fn fixed_columns(&self) -> &[FixedColumn<F>] {
+        &self.fixed
+    }
+
+    /// Whether the rows of `frame`, at `x`, `x + 1`, ..., hold the values of the fixed columns there
+    ///
+    /// The fixed columns are interpolated over `lde_trace_length` rows, the
+    /// rows past the trace being zero as in a padded zero-knowledge trace.
+    pub fn fixed_columns_hold(&self, lde_trace_length: usize, x: F, frame: &[&[F]]) -> Result<bool, ProofError> {
+        if self.fixed.is_empty() {
+            return Ok(true);
+        }
+        let columns: Vec<Vec<F>> = self
+            .fixed
+            .iter()
+            .map(|column| {
+                let mut values = column.column(self.trace_length);
+                values.resize(lde_trace_length, F::zero());
+                values
+            })
+            .collect();
+        let polynomials = TraceLde::interpolate_columns(&columns, lde_trace_length)?;
+        Ok(frame.iter().enumerate().all(|(offset, row)| {
+            let point = x + F::from_canonical_u64(offset as u64);
+            self.fixed
+                .iter()
+                .zip(&polynomials)
+                .all(|(column, polynomial)| row.get(column.register()) == Some(&evaluate_polynomial(polynomial, point)))
+        }))
+    }
+
+    /// Add the assertions of a proof's public inputs to the boundary constraints
+    ///
+    /// Final values are asserted on the last row of the trace.
+    pub fn with_public_inputs(mut self, public_inputs: &PublicInputs<F>) -> Result<Self, ProofError> {
+        self.boundary.extend(public_inputs.resolve(self.trace_length));
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Add an auxiliary segment built from the given challenges
+    ///
+    /// The segment columns follow the main columns and every segment added
+    /// before it; its boundary constraints are shifted accordingly.
+    pub fn with_aux_segment(mut self, segment: &AuxSegment<F>, challenges: Vec<F>) -> Result<Self, ProofError> {
+        let definition = segment.definition();
+        if challenges.len() != definition.num_challenges() {
+            return Err(ProofError::InvalidAir(format!(
+                "auxiliary segment expects {} challenges, got {}",
+                definition.num_challenges(),
+                challenges.len()
+            )));
+        }
+
+        let offset = self.num_registers();
+        let last_row = self.trace_length.saturating_sub(1);
+        for constraint in definition.boundary(&challenges) {
+            if constraint.register >= definition.width() {
+                return Err(ProofError::InvalidAir(format!(
+                    "auxiliary boundary register {} out of range",
+                    constraint.register
+                )));
+            }
+            let row = match constraint.constraint_type {
+                BoundaryType::Initial => constraint.step,
+                BoundaryType::Final => last_row,
+            };
+            self.boundary.push(BoundaryAssertion {
+                register: offset + constraint.register,
+                row,
+                value: constraint.value,
+            });
+        }
+        self.aux.push(AuxConstraints {
+            segment: segment.clone(),
+            offset,
+            challenges,
+        });
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Check that the constraints are well-formed for the trace length
+    fn validate(&self) -> Result<(), ProofError> {
+        if self.trace_length == 0 {
+            return Err(ProofError::InvalidTrace);
+        }
+        if self.trace_length < self.window() {
+            return Err(ProofError::InvalidAir(format!(
+                "constraints read {} rows of a trace of length {}",
+                self.window(),
+                self.trace_length
+            )));
+        }
+
+        let num_main_registers = self.num_main_registers();
+        if self.transition.iter().any(|row| row.len() > num_main_registers) {
+            return Err(ProofError::InvalidAir("transition matrix is not square".to_string()));
+        }
+        if let Some(column) = self.fixed.iter().find(|column| column.register() >= num_main_registers) {
+            return Err(ProofError::InvalidAir(format!(
+                "fixed register {} out of range",
+                column.register()
+            )));
+        }
+
+        let num_registers = self.num_registers();
+        for assertion in &self.boundary {
+            if assertion.register >= num_registers {
+                return Err(ProofError::InvalidAir(format!(
+                    "boundary register {} out of range",
+                    assertion.register
+                )));
+            }
+            if assertion.row >= self.trace_length {
+                return Err(ProofError::InvalidAir(format!(
+                    "boundary row {} out of range",
+                    assertion.row
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transition coefficient matrix
+    pub fn transition(&self) -> &[Vec<F>] {
+        &self.transition
+    }
+
+    /// Auxiliary segments, in commitment order
+    pub fn aux_segments(&self) -> &[AuxConstraints<F>] {
+        &self.aux
+    }
+
+    /// Boundary assertions
+    pub fn boundary(&self) -> &[BoundaryAssertion<F>] {
+        &self.boundary
+    }
+
+    /// Trace length
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// Number of registers, main and auxiliary
+    pub fn num_registers(&self) -> usize {
+        self.num_main_registers()
+            + self
+                .aux
+                .iter()
+                .map(|aux| aux.segment.definition().width())
+                .sum::<usize>()
+    }
+
+    /// Number of main trace registers
+    pub fn num_main_registers(&self) -> usize {
+        self.transition.len()
+    }
+
+    /// Number of consecutive rows the transition constraints read
+    pub fn window(&self) -> usize {
+        self.aux
+            .iter()
+            .map(|aux| aux.segment.definition().window())
+            .fold(2, usize::max)
+    }
+
+    /// Number of transition constraints, main and auxiliary
+    pub fn num_transition_constraints(&self) -> usize {
+        self.transition.len()
+            + self
+                .aux
+                .iter()
+                .map(|aux| aux.segment.definition().num_constraints())
+                .sum::<usize>()
+    }
+
+    /// Number of composition coefficients (one per constraint)
+    ///
+    /// Transition coefficients come first, followed by the boundary ones.
+    pub fn num_coefficients(&self) -> usize {
+        self.num_transition_constraints() + self.boundary.len()
+    }
+
+    /// Number of coefficients of the composition polynomial
+    ///
+    /// Linear transition quotients are constant and boundary quotients have
+    /// degree at most `n - 2`, so `n` coefficients always suffice.
+    pub fn composition_length(&self) -> usize {
+        self.composition_length_for(self.trace_length)
+    }
+
+    /// Number of coefficients of the composition polynomial over an LDE of
+    /// columns interpolated from `lde_trace_length` rows
+    ///
+    /// Random rows appended in zero-knowledge mode raise the column degree to
+    /// `L - 1`. Auxiliary constraints of degree two then yield quotients with
+    /// `2L - n` coefficients, plus one per row a window skips.
+    pub fn composition_length_for(&self, lde_trace_length: usize) -> usize {
+        composition_length(self.trace_length, lde_trace_length, !self.aux.is_empty(), self.window())
+    }
+
+    /// Evaluate the transition constraints on consecutive states, from the current one on
+    ///
+    /// Returns the main residuals followed by the residuals of every
+    /// auxiliary segment. `rows` holds up to [`window`](Self::window) rows
+    /// and at least two; the residuals of a segment whose window does not fit
+    /// in `rows` are zero, as its constraints do not apply to the last rows.
+    pub fn evaluate_transition(&self, rows: &[&[F]]) -> Vec<F> {
+        let (current, next) = (rows[0], rows[1]);
+        let mut residuals: Vec<F> = self
+            .transition
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                if self.free.get(i).copied().unwrap_or(false) {
+                    return F::zero();
+                }
+                let mut expected = F::zero();
+                for (&coeff, &value) in row.iter().zip(current) {
+                    expected = expected + coeff * value;
+                }
+                next.get(i).copied().unwrap_or_else(F::zero) - expected
+            })
+            .collect();
+
+        let num_main = self.num_main_registers();
+        for aux in &self.aux {
+            let definition = aux.segment.definition();
+            let window = definition.window();
+            if rows.len() < window {
+                residuals.extend((0..definition.num_constraints()).map(|_| F::zero()));
+                continue;
+            }
+            let columns = aux.offset..aux.offset + definition.width();
+            let aux_rows: Option<Vec<&[F]>> = rows[..window].iter().map(|row| row.get(columns.clone())).collect();
+            let Some(aux_rows) = aux_rows else {
+                // A missing segment leaves its constraints unsatisfied
+                residuals.extend((0..definition.num_constraints()).map(|_| F::one()));
+                continue;
+            };
+            let mut aux_residuals = if window == 2 {
+                let frame = AuxFrame {
+                    main_current: &current[..num_main.min(current.len())],
+                    main_next: &next[..num_main.min(next.len())],
+                    aux_current: aux_rows[0],
+                    aux_next: aux_rows[1],
+                };
+                definition.evaluate_transition(&frame, &aux.challenges)
+            } else {
+                let main_rows: Vec<&[F]> = rows[..window].iter().map(|row| &row[..num_main.min(row.len())]).collect();
+                let frame = AuxWindow {
+                    main: &main_rows,
+                    aux: &aux_rows,
+                };
+                definition.evaluate_window(&frame, &aux.challenges)
+            };
+            aux_residuals.resize(definition.num_constraints(), F::one());
+            residuals.extend(aux_residuals);
+        }
+        residuals
+    }
+
+    /// Evaluate the exemption `E(x) = prod_{n - w < i < n - 1} (x - i)` of a window of `w` rows
+    ///
+    /// The exemption vanishes on the rows where the window runs past the end
+    /// of the trace; it is one for windows of two rows.
+    pub fn exemption(&self, window: usize, x: F) -> F {
+        let mut result = F::one();
+        for row in (self.trace_length + 1).saturating_sub(window)..self.trace_length.saturating_sub(1) {
+            result = result * (x - trace_domain_point::<F>(row));
+        }
+        result
+    }
+
+    /// Evaluate the transition zerofier `Z(x) = prod_{i < n - 1} (x - i)`
+    pub fn transition_zerofier(&self, x: F) -> F {
+        transition_zerofier(self.trace_length, x)
+    }
+
+    /// Check that a trace satisfies every constraint
+    ///
+    /// The error names the first violated constraint, its row and its value.
+    pub fn check_trace(&self, trace: &ExecutionTrace<F>) -> Result<(), ProofError> {
+        if let Some(violation) = self.violations(trace, 1)?.into_iter().next() {
+            return Err(ProofError::ConstraintError(violation.to_string()));
+        }
+        match self.first_fixed_violation(trace)? {
+            None => Ok(()),
+            Some(violation) => Err(ProofError::ConstraintError(violation.to_string())),
+        }
+    }
+
+    /// Up to `limit` constraint violations of a trace
+    ///
+    /// Transition constraints are checked row by row before the boundary
+    /// constraints. Fails with [`ProofError::InvalidTrace`] if the trace does
+    /// not have the instantiated shape.
+    pub fn violations(&self, trace: &ExecutionTrace<F>, limit: usize) -> Result<Vec<ConstraintViolation<F>>, ProofError> {
+        self.check_shape(trace)?;
+        let mut violations = Vec::new();
+        let mut rows = TraceWindow::new(trace, self.window());
+        for row in 0..self.trace_length.saturating_sub(1) {
+            for (index, value) in self.evaluate_transition(&rows.rows()).into_iter().enumerate() {
+                if violations.len() == limit {
+                    return Ok(violations);
+                }
+                if !value.is_zero() {
+                    violations.push(ConstraintViolation::Transition { index, row, value });
+                }
+            }
+            rows.advance();
+        }
+        let boundary = self.boundary.iter().enumerate().filter_map(|(index, assertion)| {
+            let value = trace_value(trace, assertion.register, assertion.row) - assertion.value;
+            (!value.is_zero()).then_some(ConstraintViolation::Boundary {
+                index,
+                register: assertion.register,
+                row: assertion.row,
+                value,
+            })
+        });
+        violations.extend(boundary.take(limit.saturating_sub(violations.len())));
+        Ok(violations)
+    }
+
+    /// Find the first constraint a trace violates
+    ///
+    /// Transition constraints are checked row by row before the boundary
+    /// constraints and the fixed columns. Fails with
+    /// [`ProofError::InvalidTrace`] if the trace does not have the
+    /// instantiated shape.
+    pub fn first_violation(&self, trace: &ExecutionTrace<F>) -> Result<Option<TraceViolation>, ProofError> {
+        if let Some(violation) = self.first_transition_violation(trace)? {
+            return Ok(Some(violation));
+        }
+        match self.first_boundary_violation(trace)? {
+            Some(violation) => Ok(Some(violation)),
+            None => self.first_fixed_violation(trace),
+        }
+    }
+
+    /// Find the first transition a trace violates
+    pub fn first_transition_violation(&self, trace: &ExecutionTrace<F>) -> Result<Option<TraceViolation>, ProofError> {
+        self.check_shape(trace)?;
+        let mut rows = TraceWindow::new(trace, self.window());
+        for step in 0..self.trace_length.saturating_sub(1) {
+            let residuals = self.evaluate_transition(&rows.rows());
+            if residuals.iter().any(|residual| !residual.is_zero()) {
+                return Ok(Some(TraceViolation::Transition { row: step }));
+            }
+            rows.advance();
+        }
+        Ok(None)
+    }
+
+    /// Find the first boundary assertion a trace violates
+    pub fn first_boundary_violation(&self, trace: &ExecutionTrace<F>) -> Result<Option<TraceViolation>, ProofError> {
+        self.check_shape(trace)?;
+        Ok(self
+            .boundary
+            .iter()
+            .find(|assertion| trace_value(trace, assertion.register, assertion.row) != assertion.value)
+            .map(|assertion| TraceViolation::Boundary {
+                register: assertion.register,
+                row: assertion.row,
+            }))
+    }
+
+    /// Find the first cell of a fixed column a trace deviates from
+    pub fn first_fixed_violation(&self, trace: &ExecutionTrace<F>) -> Result<Option<TraceViolation>, ProofError> {
+        self.check_shape(trace)?;
+        Ok(self.fixed.iter().find_map(|column| {
+            (0..self.trace_length)
+                .find(|&row| trace_value(trace, column.register(), row) != column.value(row))
+                .map(|row| TraceViolation::Fixed {
+                    register: column.register(),
+                    row,
+                })
+        }))
+    }
+
+    /// Check that a trace has the instantiated shape
+    ///
+    /// The trace must carry exactly the auxiliary segments of the constraints.
+    fn check_shape(&self, trace: &ExecutionTrace<F>) -> Result<(), ProofError> {
+        let segment_widths_match = trace.aux_segments.len() == self.aux.len()
+            && trace
+                .aux_segments
+                .iter()
+                .zip(&self.aux)
+                .all(|(columns, aux)| columns.len() == aux.segment.definition().width());
+        if trace.length != self.trace_length
+            || trace.columns.len() != self.num_main_registers()
+            || !segment_widths_match
+            || (0..trace.columns.len()).any(|index| trace.columns.column_len(index) != trace.length)
+            || trace.aux_segments.iter().flatten().any(|column| column.len() != trace.length)
+        {
+            return Err(ProofError::InvalidTrace);
+        }
+        Ok(())
+    }
+
+    /// Evaluate the composition polynomial at `x` from the trace values at `x`, `x + 1`, ...
+    ///
+    /// `rows` holds the trace values at the [`window`](Self::window) points
+    /// from `x` on. Returns `None` if `x` lies on a zerofier root.
+    pub fn evaluate(&self, coefficients: &[F], x: F, rows: &[&[F]]) -> Option<F> {
+        let zerofier_inv = self.transition_zerofier(x).inverse()?;
+        self.evaluate_with_zerofier_inverse(coefficients, x, zerofier_inv, rows)
+    }
+
+    /// Evaluate the composition polynomial at `x` given the inverse of the transition zerofier there
+    ///
+    /// Returns `None` if `x` is an asserted row.
+    pub fn evaluate_with_zerofier_inverse(
+        &self,
+        coefficients: &[F],
+        x: F,
+        zerofier_inv: F,
+        rows: &[&[F]],
+    ) -> Option<F> {
+        Some(
+            self.combine_transition_quotients(coefficients, x, zerofier_inv, rows)
+                + self.evaluate_boundary_quotients(coefficients, x, rows.first()?)?,
+        )
+    }
+
+    /// Evaluate the combined transition quotients `sum_i alpha_i * C_i(x) * E_i(x) / Z(x)` at `x`
+    ///
+    /// Returns `None` if `x` lies on the transition domain.
+    pub fn evaluate_transition_quotients(&self, coefficients: &[F], x: F, rows: &[&[F]]) -> Option<F> {
+        let zerofier_inv = self.transition_zerofier(x).inverse()?;
+        Some(self.combine_transition_quotients(coefficients, x, zerofier_inv, rows))
+    }
+
+    /// Combine the transition residuals of a window of rows divided by the zerofier
+    fn combine_transition_quotients(&self, coefficients: &[F], x: F, zerofier_inv: F, rows: &[&[F]]) -> F {
+        let mut residuals = self.evaluate_transition(rows);
+        let mut start = self.transition.len();
+        for aux in &self.aux {
+            let definition = aux.segment.definition();
+            let end = (start + definition.num_constraints()).min(residuals.len());
+            if definition.window() > 2 {
+                let exemption = self.exemption(definition.window(), x);
+                for residual in &mut residuals[start..end] {
+                    *residual = *residual * exemption;
+                }
+            }
+            start = end;
+        }
+        let mut result = F::zero();
+        for (&alpha, residual) in coefficients.iter().zip(residuals) {
+            result = result + alpha * residual * zerofier_inv;
+        }
+        result
+    }
+
+    /// Evaluate the combined boundary quotients `sum_k beta_k * (T_k(x) - v_k) / (x - row_k)` at `x`
+    ///
+    /// `coefficients` is the full composition coefficient vector; the boundary
+    /// coefficients follow the transition ones. Returns `None` if `x` is an
+    /// asserted row or a register is missing from `current`.
+    pub fn evaluate_boundary_quotients(&self, coefficients: &[F], x: F, current: &[F]) -> Option<F> {
+        let boundary_coeffs = coefficients.get(self.num_transition_constraints()..)?;
+        let mut result = F::zero();
+        for (&beta, assertion) in boundary_coeffs.iter().zip(&self.boundary) {
+            let row = trace_domain_point::<F>(assertion.row);
+            let denominator_inv = (x - row).inverse()?;
+            result = result + beta * (*current.get(assertion.register)? - assertion.value) * denominator_inv;
+        }
+        Some(result)
+    }
+}
+
+/// Rows of a trace from a current row on, advanced one row at a time
+struct TraceWindow<'a, F: FieldElement> {
+    /// Trace the rows are read from
+    trace: &'a ExecutionTrace<F>,
+    /// Up to `window` rows from the current one on
+    rows: alloc::collections::VecDeque<Vec<F>>,
+    /// Index of the row after the last one read
+    next: usize,
+}
+
+impl<'a, F: FieldElement> TraceWindow<'a, F> {
+    /// Window of `window` rows from the first row on
+    fn new(trace: &'a ExecutionTrace<F>, window: usize) -> Self {
+        let end = window.min(trace.length);
+        Self {
+            trace,
+            rows: (0..end).map(|row| trace_row(trace, row)).collect(),
+            next: end,
+        }
+    }
+
+    /// Rows from the current one on
+    fn rows(&self) -> Vec<&[F]> {
+        self.rows.iter().map(Vec::as_slice).collect()
+    }
+
+    /// Move to the next row, reading a row past the window if there is one
+    fn advance(&mut self) {
+        self.rows.pop_front();
+        if self.next < self.trace.length {
+            self.rows.push_back(trace_row(self.trace, self.next));
+            self.next += 1;
+        }
+    }
+}
+
+/// Values of the main trace columns followed by every auxiliary segment column at `row`
+fn trace_row<F: FieldElement>(trace: &ExecutionTrace<F>, row: usize) -> Vec<F> {
+    let mut values = trace.row(RowIdx(row)).to_vec();
+    values.extend(trace.aux_segments.iter().flatten().map(|column| column[row]));
+    values
+}
+
+/// Value of a register at `row`, counting auxiliary columns after the main ones
+fn trace_value<F: FieldElement>(trace: &ExecutionTrace<F>, register: usize, row: usize) -> F {
+    match register.checked_sub(trace.columns.len()) {
+        None => trace.columns.value(register, row),
+        Some(aux_register) => trace.aux_segments.iter().flatten().nth(aux_register).expect("register in range")[row],
+    }
+}
+
+/// Constraint violated by an execution trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceViolation {
+    /// The transition from `row` to `row + 1` is invalid
+    Transition {
+        /// First row of the transition
+        row: usize,
+    },
+    /// A boundary assertion does not hold
+    Boundary {
+        /// Asserted register
+        register: usize,
+        /// Asserted row
+        row: usize,
+    },
+    /// A fixed register does not hold its column's value
+    Fixed {
+        /// Fixed register
+        register: usize,
+        /// Row of the differing value
+        row: usize,
+    },
+}
+
+impl core::fmt::Display for TraceViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TraceViolation::Transition { row } => write!(f, "transition constraint violated at row {}", row),
+            TraceViolation::Boundary { register, row } => {
+                write!(f, "boundary constraint violated on register {} at row {}", register, row)
+            }
+            TraceViolation::Fixed { register, row } => {
+                write!(f, "fixed register {} differs from its column at row {}", register, row)
+            }
+        }
+    }
+}
+
+/// Low-degree extension of an execution trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceLde<F: FieldElement> {
+    /// Column polynomials in coefficient form
+    polynomials: Vec<Vec<F>>,
+    /// Column evaluations on the LDE domain plus one trailing point
+    evaluations: Vec<ColumnBuffer<F>>,
+    /// Trace length
+    trace_length: usize,
+    /// LDE domain size
+    domain_size: usize,
+}
+
+impl<F: FieldElement> TraceLde<F> {
+    /// Interpolate the trace columns and evaluate them over the LDE domain
+    ///
+    /// Each column is evaluated on one point past the end of the domain so the
+    /// next-row value is available at every domain point.
+    pub fn new(trace: &ExecutionTrace<F>, blowup_factor: usize) -> Result<Self, ProofError> {
+        if blowup_factor == 0 {
+            return Err(ProofError::InvalidTrace);
+        }
+        let polynomials = Self::interpolate(trace)?;
+        Self::from_polynomials(polynomials, trace.length, blowup_factor)
+    }
+
+    /// Interpolate the trace columns over the trace domain
+    ///
+    /// Memory-mapped columns are read into memory as they are interpolated.
+    pub fn interpolate(trace: &ExecutionTrace<F>) -> Result<Vec<Vec<F>>, ProofError> {
+        Self::interpolate_over(&Self::trace_domain(trace.length)?, trace)
+    }
+
+    /// Interpolate the trace columns over a prepared trace domain
+    pub fn interpolate_over(domain: &InterpolationDomain<F>, trace: &ExecutionTrace<F>) -> Result<Vec<Vec<F>>, ProofError> {
+        Self::interpolate_with(domain, trace.columns.len(), |index| trace.columns.column(index))
+    }
+
+    /// Interpolate columns of the given length over the trace domain
+    pub fn interpolate_columns(columns: &[Vec<F>], length: usize) -> Result<Vec<Vec<F>>, ProofError> {
+        Self::interpolate_columns_over(&Self::trace_domain(length)?, columns)
+    }
+
+    /// Interpolate columns over a prepared trace domain
+    pub fn interpolate_columns_over(
+        domain: &InterpolationDomain<F>,
+        columns: &[Vec<F>],
+    ) -> Result<Vec<Vec<F>>, ProofError> {
+        Self::interpolate_with(domain, columns.len(), |index| Cow::Borrowed(&columns[index]))
+    }
+
+    /// Trace domain of the given length prepared for interpolation
+    pub fn trace_domain(length: usize) -> Result<InterpolationDomain<F>, ProofError> {
+        if length == 0 || length as u64 >= MAX_DOMAIN_POINT {
+            return Err(ProofError::InvalidTrace);
+        }
+        InterpolationDomain::new((0..length).map(trace_domain_point).collect())
+    }
+
+    /// Interpolate `width` columns over the domain, fetched by index
+    fn interpolate_with<'a>(
+        domain: &InterpolationDomain<F>,
+        width: usize,
+        column: impl Fn(usize) -> Cow<'a, [F]> + Sync,
+    ) -> Result<Vec<Vec<F>>, ProofError>
+    where
+        F: 'a,
+    {
+        let interpolate_column = |index: usize| {
+            let column = column(index);
+            if column.len() != domain.len() {
+                return Err(ProofError::InvalidTrace);
+            }
+            domain.interpolate(&column)
+        };
+
+        // Columns are interpolated independently
+        #[cfg(feature = "parallel")]
+        let polynomials = (0..width).into_par_iter().map(interpolate_column).collect();
+        #[cfg(not(feature = "parallel"))]
+        let polynomials = (0..width).map(interpolate_column).collect();
+
+        polynomials
+    }
+
+    /// Evaluate interpolated column polynomials over the LDE domain
+    ///
+    /// `trace_length` is the length of the interpolated trace; no polynomial
+    /// may have more coefficients than that.
+    pub fn from_polynomials(
+        polynomials: Vec<Vec<F>>,
+        trace_length: usize,
+        blowup_factor: usize,
+    ) -> Result<Self, ProofError> {
+        Self::from_polynomials_planned(polynomials, trace_length, blowup_factor, &MemoryPlan::unbounded())
+    }
+
+    /// Evaluate interpolated column polynomials over the LDE domain following a memory plan
+    ///
+    /// Each column is evaluated in chunks of `plan.lde_chunk_rows` rows and is
+    /// spilled to disk if the plan says so.
+    pub fn from_polynomials_planned(
+        polynomials: Vec<Vec<F>>,
+        trace_length: usize,
+        blowup_factor: usize,
+        plan: &MemoryPlan,
+    ) -> Result<Self, ProofError> {
+        let extend = |polynomial: &[F], rows: Range<usize>| Ok(extend_rows(polynomial, trace_length, rows));
+        Self::from_polynomials_with(polynomials, trace_length, blowup_factor, plan, &extend)
+    }
+
+    /// Evaluate column polynomials over the LDE domain with a custom kernel
+    ///
+    /// `extend` returns the evaluations of a polynomial at a range of LDE
+    /// positions and must agree with [`extend_rows`]; it lets the evaluation
+    /// run on an accelerator.
+    pub fn from_polynomials_with(
+        polynomials: Vec<Vec<F>>,
+        trace_length: usize,
+        blowup_factor: usize,
+        plan: &MemoryPlan,
+        extend: &(dyn Fn(&[F], Range<usize>) -> Result<Vec<F>, ProofError> + Sync),
+    ) -> Result<Self, ProofError> {
+        if trace_length == 0 || polynomials.iter().any(|polynomial| polynomial.len() > trace_length) {
+            return Err(ProofError::InvalidTrace);
+        }
+
+        let domain_size = trace_length
+            .checked_mul(blowup_factor)
+            .filter(|&size| size > 0 && ((trace_length + size) as u64) < MAX_DOMAIN_POINT)
+            .ok_or(ProofError::InvalidTrace)?;
+
+        // Columns are extended independently
+        let extend_column = |polynomial: &Vec<F>| -> Result<ColumnBuffer<F>, ProofError> {
+            ColumnBuffer::from_chunks(domain_size + 1, plan.lde_chunk_rows, plan.spill_to_disk, |rows| {
+                extend(polynomial, rows)
+            })
+        };
+
+        #[cfg(feature = "parallel")]
+        let evaluations = polynomials.par_iter().map(extend_column).collect::<Result<_, _>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let evaluations = polynomials.iter().map(extend_column).collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            polynomials,
+            evaluations,
+            trace_length,
+            domain_size,
+        })
+    }
+
+    /// Append the columns of another extension over the same domain
+    ///
+    /// Used to combine the main trace with its auxiliary segments.
+    pub fn append(&mut self, other: TraceLde<F>) -> Result<(), ProofError> {
+        if other.trace_length != self.trace_length || other.domain_size != self.domain_size {
+            return Err(ProofError::InvalidTrace);
+        }
+        self.polynomials.extend(other.polynomials);
+        self.evaluations.extend(other.evaluations);
+        Ok(())
+    }
+
+    /// Column polynomials in coefficient form
+    pub fn polynomials(&self) -> &[Vec<F>] {
+        &self.polynomials
+    }
+
+    /// LDE domain size
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    /// Trace length
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// Evaluate every column polynomial at an arbitrary point
+    pub fn evaluate_at(&self, x: F) -> Vec<F> {
+        self.polynomials
+            .iter()
+            .map(|polynomial| evaluate_polynomial(polynomial, x))
+            .collect()
+    }
+
+    /// Trace row values at LDE domain position `index`
+    pub fn row(&self, index: usize) -> Vec<F> {
+        self.evaluations.iter().map(|column| column.get(index)).collect()
+    }
+
+    /// Trace rows at a range of LDE domain positions, laid out row after row
+    ///
+    /// Columns are read one at a time and transposed into the block, so the
+    /// rows of a chunk can be evaluated from contiguous memory.
+    pub fn row_block(&self, rows: Range<usize>) -> Vec<F> {
+        let width = self.evaluations.len();
+        let mut block = vec![F::zero(); rows.len() * width];
+        for (column_index, column) in self.evaluations.iter().enumerate() {
+            for (offset, index) in rows.clone().enumerate() {
+                block[offset * width + column_index] = column.get(index);
+            }
+        }
+        block
+    }
+
+    /// Trace rows at a range of LDE positions, which may run past the domain
+    ///
+    /// Positions past the stored evaluations are evaluated from the column
+    /// polynomials; they are the rows a window of more than two rows reads
+    /// after the last domain point.
+    pub fn window_block(&self, rows: Range<usize>) -> Vec<F> {
+        let stored = rows.end.min(self.domain_size + 1).max(rows.start);
+        let mut block = self.row_block(rows.start..stored);
+        for index in stored..rows.end {
+            block.extend(self.evaluate_at(lde_domain_point(self.trace_length, index)));
+        }
+        block
+    }
+}
+
+/// Constraint composition polynomial
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionPolynomial<F: FieldElement> {
+    /// Coefficients (constant term first)
+    coefficients: Vec<F>,
+    /// Evaluations over the LDE domain
+    evaluations: Vec<F>,
+}
+
+impl<F: FieldElement> CompositionPolynomial<F> {
+    /// Build the composition polynomial for a trace LDE
+    ///
+    /// The constraint quotients are combined with `coefficients` at every LDE
+    /// point; the polynomial is recovered by interpolating the first
+    /// `composition_length` evaluations.
+    pub fn new(
+        constraints: &CompositionConstraints<F>,
+        trace_lde: &TraceLde<F>,
+        coefficients: &[F],
+    ) -> Result<Self, ProofError> {
+        // Random rows appended in zero-knowledge mode raise the trace degree
+        // beyond the constrained length
+        let domain = CompositionDomain::new(
+            constraints.trace_length(),
+            trace_lde.trace_length(),
+            trace_lde.domain_size(),
+            constraints.composition_length_for(trace_lde.trace_length()),
+        )?;
+        Self::with_domain(constraints, trace_lde, coefficients, &domain)
+    }
+
+    /// Build the composition polynomial over a prepared composition domain
+    ///
+    /// The domain must have been prepared for the trace length of the
+    /// constraints, the LDE and the composition length of the constraints.
+    pub fn with_domain(
+        constraints: &CompositionConstraints<F>,
+        trace_lde: &TraceLde<F>,
+        coefficients: &[F],
+        domain: &CompositionDomain<F>,
+    ) -> Result<Self, ProofError> {
+        if coefficients.len() != constraints.num_coefficients() {
+            return Err(ProofError::ConstraintError(format!(
+                "expected {} composition coefficients, got {}",
+                constraints.num_coefficients(),
+                coefficients.len()
+            )));
+        }
+
+        let domain_size = trace_lde.domain_size();
+        let length = constraints.composition_length_for(trace_lde.trace_length());
+        if domain.trace_length != constraints.trace_length()
+            || domain.lde_trace_length != trace_lde.trace_length()
+            || domain.zerofier_inverses.len() != domain_size
+            || domain.interpolation.len() != length
+        {
+            return Err(ProofError::ConstraintError(
+                "composition domain does not match the trace LDE".to_string(),
+            ));
+        }
+
+        // Rows are independent, so the domain is evaluated in chunks; each
+        // chunk reads the rows past its end that the constraint window spans
+        let width = trace_lde.evaluations.len();
+        let window = constraints.window();
+        let evaluate_chunk = |start: usize| -> Result<Vec<F>, ProofError> {
+            let end = (start + CONSTRAINT_CHUNK_ROWS).min(domain_size);
+            let block = trace_lde.window_block(start..end + window - 1);
+            (start..end)
+                .map(|index| {
+                    let offset = (index - start) * width;
+                    let rows: Vec<&[F]> = (0..window)
+                        .map(|row| &block[offset + row * width..offset + (row + 1) * width])
+                        .collect();
+                    let x = lde_domain_point(trace_lde.trace_length(), index);
+                    let zerofier_inv = domain.zerofier_inverses[index];
+                    constraints
+                        .evaluate_with_zerofier_inverse(coefficients, x, zerofier_inv, &rows)
+                        .ok_or_else(|| ProofError::ConstraintError("zerofier vanishes on LDE domain".to_string()))
+                })
+                .collect()
+        };
+
+        #[cfg(feature = "parallel")]
+        let chunks: Vec<Vec<F>> = (0..domain_size)
+            .into_par_iter()
+            .step_by(CONSTRAINT_CHUNK_ROWS)
+            .map(evaluate_chunk)
+            .collect::<Result<_, _>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let chunks: Vec<Vec<F>> = (0..domain_size)
+            .step_by(CONSTRAINT_CHUNK_ROWS)
+            .map(evaluate_chunk)
+            .collect::<Result<_, _>>()?;
+        let evaluations: Vec<F> = chunks.into_iter().flatten().collect();
+        let coefficients = domain.interpolation.interpolate(&evaluations[..length])?;
+
+        Ok(Self {
+            coefficients,
+            evaluations,
+        })
+    }
+
+    /// Coefficients (constant term first)
+    pub fn coefficients(&self) -> &[F] {
+        &self.coefficients
+    }
+
+    /// Evaluations over the LDE domain
+    pub fn evaluations(&self) -> &[F] {
+        &self.evaluations
+    }
+
+    /// Evaluate the polynomial at a point
+    pub fn evaluate(&self, x: F) -> F {
+        evaluate_polynomial(&self.coefficients, x)
+    }
+}
+
+/// LDE domain data the composition polynomial is built from
+///
+/// It depends only on the trace length, the LDE and the composition length,
+/// so it is shared by the coordinates of an extension and can be reused by
+/// every proof of an AIR (see [`ProvingKey`](crate::proof::preprocess::ProvingKey)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositionDomain<F: FieldElement> {
+    /// Trace length the transition zerofier vanishes on
+    trace_length: usize,
+    /// Length of the interpolated trace, which fixes the start of the LDE domain
+    lde_trace_length: usize,
+    /// Inverse of the transition zerofier at every LDE domain point
+    zerofier_inverses: Vec<F>,
+    /// LDE domain points the composition polynomial is interpolated from
+    interpolation: InterpolationDomain<F>,
+}
+
+impl<F: FieldElement> CompositionDomain<F> {
+    /// Prepare the composition domain of an LDE
+    ///
+    /// `trace_length` is the constrained trace length, `lde_trace_length` the
+    /// length of the interpolated trace and `composition_length` the number of
+    /// composition coefficients (see [`composition_length`]).
+    pub fn new(
+        trace_length: usize,
+        lde_trace_length: usize,
+        domain_size: usize,
+        composition_length: usize,
+    ) -> Result<Self, ProofError> {
+        if composition_length > domain_size {
+            return Err(ProofError::ConstraintError(
+                "LDE domain too small for composition degree".to_string(),
+            ));
+        }
+
+        let zerofier_inverse = |index: usize| {
+            transition_zerofier::<F>(trace_length, lde_domain_point(lde_trace_length, index))
+                .inverse()
+                .ok_or_else(|| ProofError::ConstraintError("zerofier vanishes on LDE domain".to_string()))
+        };
+        #[cfg(feature = "parallel")]
+        let zerofier_inverses = (0..domain_size).into_par_iter().map(zerofier_inverse).collect::<Result<_, _>>()?;
+        #[cfg(not(feature = "parallel"))]
+        let zerofier_inverses = (0..domain_size).map(zerofier_inverse).collect::<Result<_, _>>()?;
+
+        let points = (0..composition_length)
+            .map(|index| lde_domain_point(lde_trace_length, index))
+            .collect();
+        Ok(Self {
+            trace_length,
+            lde_trace_length,
+            zerofier_inverses,
+            interpolation: InterpolationDomain::new(points)?,
+        })
+    }
+
+    /// Inverses of the transition zerofier over the LDE domain
+    pub fn zerofier_inverses(&self) -> &[F] {
+        &self.zerofier_inverses
+    }
+}
+
+/// Points prepared for repeated interpolation
+///
+/// Holds the vanishing polynomial of the points and the inverses of their
+/// Lagrange denominators, so interpolating many value vectors over the same
+/// points pays for the setup and the inversions once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpolationDomain<F: FieldElement> {
+    /// Interpolation points
+    points: Vec<F>,
+    /// Vanishing polynomial `prod (x - points[i])` in coefficient form
+    vanishing: Vec<F>,
+    /// Inverse of `prod_{j != i} (points[i] - points[j])` for every point
+    inverse_denominators: Vec<F>,
+}
+
+impl<F: FieldElement> InterpolationDomain<F> {
+    /// Prepare distinct points for interpolation
+    pub fn new(points: Vec<F>) -> Result<Self, ProofError> {
+        if points.is_empty() {
+            return Err(ProofError::ConstraintError("invalid interpolation input".to_string()));
+        }
+
+        // Vanishing polynomial prod (x - xs[i])
+        let mut vanishing = vec![F::one()];
+        for &point in &points {
+            let mut next = vec![F::zero(); vanishing.len() + 1];
+            for (i, &coeff) in vanishing.iter().enumerate() {
+                next[i + 1] = next[i + 1] + coeff;
+                next[i] = next[i] - coeff * point;
+            }
+            vanishing = next;
+        }
+
+        let mut domain = Self {
+            points,
+            vanishing,
+            inverse_denominators: Vec::new(),
+        };
+        domain.inverse_denominators = domain
+            .points
+            .iter()
+            .map(|&point| {
+                evaluate_polynomial(&domain.basis_numerator(point), point)
+                    .inverse()
+                    .ok_or_else(|| ProofError::ConstraintError("duplicate interpolation point".to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(domain)
+    }
+
+    /// Number of points
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Whether there are no points
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Interpolation points
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// Interpolate the polynomial through `(points[i], values[i])` in coefficient form
+    pub fn interpolate(&self, values: &[F]) -> Result<Vec<F>, ProofError> {
+        if values.len() != self.points.len() {
+            return Err(ProofError::ConstraintError("invalid interpolation input".to_string()));
+        }
+
+        let mut result = vec![F::zero(); self.points.len()];
+        for ((&point, &value), &inverse) in self.points.iter().zip(values).zip(&self.inverse_denominators) {
+            let scale = value * inverse;
+            for (acc, coeff) in result.iter_mut().zip(self.basis_numerator(point)) {
+                *acc = *acc + coeff * scale;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Synthetic division of the vanishing polynomial by `x - point`
+    fn basis_numerator(&self, point: F) -> Vec<F> {
+        let mut numerator = vec![F::zero(); self.points.len()];
+        let mut carry = F::zero();
+        for i in (0..self.points.len()).rev() {
+            carry = self.vanishing[i + 1] + carry * point;
+            numerator[i] = carry;
+        }
+        numerator
+    }
+}
+
+/// Number of coefficients of the composition polynomial
+///
+/// `trace_length` is the constrained trace length `n` and `lde_trace_length`
+/// the length `L` of the interpolated trace. Linear transition quotients are
+/// constant and boundary quotients have degree at most `L - 2`; auxiliary
+/// constraints of degree two yield quotients with `2L - n` coefficients, and
+/// the exemption of a `window` of more than two rows adds `window - 2`.
+pub fn composition_length(trace_length: usize, lde_trace_length: usize, has_aux: bool, window: usize) -> usize {
+    let length = trace_length.max(lde_trace_length);
+    if has_aux {
+        length.max((2 * lde_trace_length + window.saturating_sub(2)).saturating_sub(trace_length))
+    } else {
+        length
+    }
+}
+
+/// Smallest blowup factor for constraints of degree `constraint_degree`
+///
+/// A constraint of degree `d` over columns interpolated from `L` rows yields
+/// a quotient with fewer than `d * L` coefficients, so an LDE domain of
+/// `d * L` points fits the composition polynomial. The factor is rounded up
+/// to a power of two and is at least two, below which queries add no
+/// soundness (see [`soundness`](crate::proof::soundness)).
+pub fn min_blowup_factor(constraint_degree: usize) -> usize {
+    constraint_degree.max(2).next_power_of_two()
+}
+
+/// Evaluate the transition zerofier `Z(x) = prod_{i < n - 1} (x - i)` of a trace of length `n`
+pub fn transition_zerofier<F: FieldElement>(trace_length: usize, x: F) -> F {
+    let mut result = F::one();
+    let mut point = F::zero();
+    for _ in 0..trace_length.saturating_sub(1) {
+        result = result * (x - point);
+        point = point + F::one();
+    }
+    result
+}
+
+/// Point of the trace domain for a row
+pub fn trace_domain_point<F: FieldElement>(row: usize) -> F {
+    F::from_canonical_u64(row as u64)
+}
+
+/// Point of the LDE domain at a position
+pub fn lde_domain_point<F: FieldElement>(trace_length: usize, index: usize) -> F {
+    F::from_canonical_u64((trace_length + index) as u64)
+}
+
+/// Evaluate a column polynomial at a range of LDE domain positions
+///
+/// `trace_length` is the length of the interpolated trace, which fixes the
+/// start of the LDE domain.
+pub fn extend_rows<F: FieldElement>(polynomial: &[F], trace_length: usize, rows: Range<usize>) -> Vec<F> {
+    Kernels::detected().extend(polynomial, trace_length, rows)
+}
+
+/// Evaluate a polynomial in coefficient form with Horner's rule
+pub fn evaluate_polynomial<F: FieldElement>(coefficients: &[F], x: F) -> F {
+    coefficients
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, &coeff| acc * x + coeff)
+}
+
+/// Divide `p(x) - p(point)` by `x - point`
+///
+/// The division is exact, so the quotient is returned in coefficient form. A
+/// constant polynomial yields the zero polynomial.
+pub fn divide_by_linear<F: FieldElement>(polynomial: &[F], point: F) -> Vec<F> {
+    if polynomial.len() <= 1 {
+        return vec![F::zero()];
+    }
+
+    let mut quotient = vec![F::zero(); polynomial.len() - 1];
+    let mut carry = F::zero();
+    for i in (1..polynomial.len()).rev() {
+        carry = polynomial[i] + carry * point;
+        quotient[i - 1] = carry;
+    }
+    quotient
+}
+
+/// Interpolate the polynomial through `(xs[i], ys[i])` in coefficient form
+///
+/// Runs in `O(n^2)`: the vanishing polynomial of all points is built once and
+/// divided by each `x - xs[i]` to obtain the Lagrange basis numerators. Use an
+/// [`InterpolationDomain`] to interpolate many value vectors over the same points.
+pub fn interpolate<F: FieldElement>(xs: &[F], ys: &[F]) -> Result<Vec<F>, ProofError> {
+    if xs.len() != ys.len() {
+        return Err(ProofError::ConstraintError("invalid interpolation input".to_string()));
+    }
+    InterpolationDomain::new(xs.to_vec())?.interpolate(ys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::types::field::PrimeField64;
+
+    fn fibonacci_trace(length: usize) -> ExecutionTrace<PrimeField64> {
+        let mut a = vec![PrimeField64::new(1)];
+        let mut b = vec![PrimeField64::new(1)];
+        for i in 1..length {
+            a.push(b[i - 1]);
+            b.push(a[i - 1] + b[i - 1]);
+        }
+        ExecutionTrace {
+            columns: vec![a, b].into(),
+            length,
+            num_registers: 2,
+            aux_segments: Vec::new(),
+        }
+    }
+
+    fn fibonacci_constraints(length: usize) -> CompositionConstraints<PrimeField64> {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        CompositionConstraints::from_air(&air, length).unwrap()
+    }
+
+    #[test]
+    fn test_interpolation_roundtrip() {
+        let coefficients = vec![PrimeField64::new(3), PrimeField64::new(0), PrimeField64::new(7)];
+        let xs: Vec<PrimeField64> = (10..13).map(PrimeField64::new).collect();
+        let ys: Vec<PrimeField64> = xs.iter().map(|&x| evaluate_polynomial(&coefficients, x)).collect();
+
+        assert_eq!(interpolate(&xs, &ys).unwrap(), coefficients);
+        assert!(interpolate(&[xs[0], xs[0]], &ys[..2]).is_err());
+    }
+
+    #[test]
+    fn test_trace_lde_extends_columns() {
+        let trace = fibonacci_trace(8);
+        let lde = TraceLde::new(&trace, 4).unwrap();
+
+        assert_eq!(lde.domain_size(), 32);
+        for (column, polynomial) in trace.columns.iter().zip(lde.polynomials()) {
+            for (row, &value) in column.iter().enumerate() {
+                assert_eq!(evaluate_polynomial(polynomial, trace_domain_point(row)), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_composition_is_low_degree_for_valid_trace() {
+        let trace = fibonacci_trace(8);
+        let constraints = fibonacci_constraints(8);
+        constraints.check_trace(&trace).unwrap();
+
+        let lde = TraceLde::new(&trace, 4).unwrap();
+        let coefficients = vec![PrimeField64::new(5), PrimeField64::new(11), PrimeField64::new(13)];
+        let composition = CompositionPolynomial::new(&constraints, &lde, &coefficients).unwrap();
+
+        // The interpolated polynomial matches every LDE evaluation
+        assert_eq!(composition.coefficients().len(), constraints.composition_length());
+        for (index, &value) in composition.evaluations().iter().enumerate() {
+            assert_eq!(composition.evaluate(lde_domain_point(8, index)), value);
+        }
+    }
+
+    #[test]
+    fn test_quotients_recombine_to_composition() {
+        let trace = fibonacci_trace(8);
+        let constraints = fibonacci_constraints(8);
+        let lde = TraceLde::new(&trace, 4).unwrap();
+        let coefficients = vec![PrimeField64::new(5), PrimeField64::new(11), PrimeField64::new(13)];
+        let composition = CompositionPolynomial::new(&constraints, &lde, &coefficients).unwrap();
+
+        // Out of both domains, the composition equals the transition quotients
+        // over the zerofier plus the boundary quotients
+        let z = PrimeField64::new(1_000_003);
+        let current = lde.evaluate_at(z);
+        let next = lde.evaluate_at(z + PrimeField64::one());
+        let rows = [current.as_slice(), next.as_slice()];
+        let transition = constraints.evaluate_transition_quotients(&coefficients, z, &rows).unwrap();
+        let boundary = constraints.evaluate_boundary_quotients(&coefficients, z, &current).unwrap();
+        assert_eq!(transition + boundary, composition.evaluate(z));
+
+        // The quotients are undefined on the trace domain
+        assert!(constraints.evaluate_transition_quotients(&coefficients, PrimeField64::new(3), &rows).is_none());
+        assert!(constraints.evaluate_boundary_quotients(&coefficients, PrimeField64::zero(), &current).is_none());
+    }
+
+    #[test]
+    fn test_chunked_evaluation_matches_rows() {
+        let trace = fibonacci_trace(16);
+        let constraints = fibonacci_constraints(16);
+        let lde = TraceLde::new(&trace, 128).unwrap();
+        assert!(lde.domain_size() > CONSTRAINT_CHUNK_ROWS);
+
+        let block = lde.row_block(3..5);
+        assert_eq!(block, [lde.row(3), lde.row(4)].concat());
+
+        // Rows on either side of a chunk boundary see their true next row
+        let coefficients = vec![PrimeField64::new(5), PrimeField64::new(11), PrimeField64::new(13)];
+        let composition = CompositionPolynomial::new(&constraints, &lde, &coefficients).unwrap();
+        for index in [0, CONSTRAINT_CHUNK_ROWS - 1, CONSTRAINT_CHUNK_ROWS, lde.domain_size() - 1] {
+            let x = lde_domain_point(16, index);
+            let expected = constraints.evaluate(&coefficients, x, &[&lde.row(index), &lde.row(index + 1)]);
+            assert_eq!(Some(composition.evaluations()[index]), expected);
+        }
+    }
+
+    #[test]
+    fn test_divide_by_linear() {
+        // p(x) = 2 + 3x + x^2, (p(x) - p(4)) / (x - 4) = x + 7
+        let polynomial = vec![PrimeField64::new(2), PrimeField64::new(3), PrimeField64::new(1)];
+        let quotient = divide_by_linear(&polynomial, PrimeField64::new(4));
+        assert_eq!(quotient, vec![PrimeField64::new(7), PrimeField64::new(1)]);
+        assert_eq!(divide_by_linear(&polynomial[..1], PrimeField64::new(4)), vec![PrimeField64::zero()]);
+    }
+
+    #[test]
+    fn test_invalid_trace_is_rejected() {
+        let mut trace = fibonacci_trace(8);
+        trace.columns.set(1, 5, PrimeField64::new(0));
+
+        let constraints = fibonacci_constraints(8);
+        assert!(matches!(
+            constraints.check_trace(&trace),
+            Err(ProofError::ConstraintError(_))
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! Proof Cost Estimation
+//!
+//! Integrators verifying proofs on constrained platforms, such as smart
+//! contracts or embedded devices, need to budget gas or CPU time before they
+//! accept a proof format. [`StarkProof::verification_cost`] counts the work a
+//! [`StarkVerifier`](crate::proof::StarkVerifier) does for a proof from its
+//! recorded parameters and shape, without verifying it.
+//!
+//! Provers choosing parameters need the size of a proof before spending
+//! minutes generating it. [`StarkProver::estimate_proof_size`] derives it from
+//! the prover parameters and the AIR alone.
+//!
+//! ## Model
+//!
+//! - **Hashes**: SHA-256 invocations for the transcript, the Merkle openings
+//!   and the grinding check. Field challenges are rejection sampled, so their
+//!   squeezes are counted at the expected number of attempts.
+//! - **Field Operations**: base field multiplications and inversions. An
+//!   extension multiplication counts as `d^2` base multiplications.
+//! - **Bytes**: size of the serialized proof.
+//!
+//! Public inputs and auxiliary segment challenges are not recorded in the
+//! proof, so the transcript work they add is not counted.
+//!
+//! ## Proof Size
+//!
+//! Every value in a serialized proof has a fixed width, so the size depends
+//! only on the shape of the proof: the trace dimensions, the Merkle tree
+//! depth, the FRI layer lengths and the number of openings. The estimate
+//! serializes a proof of that shape filled with zeros and is exact.
+
+use crate::prelude::*;
+use crate::air::Air;
+use crate::proof::composition::{composition_length, CompositionConstraints};
+use crate::proof::fri::MAX_REMAINDER_LENGTH;
+use crate::proof::merkle::MerkleTree;
+use crate::proof::{ProofError, StarkProver, FRI_FOLDING_FACTOR};
+use crate::types::{Digest, FieldElement, TraceStorage};
+use crate::types::stark::{
+    ExecutionTrace, FriLayer, FriProof, FriQuery, MerkleCommitment, OodFrame, ProofBody, QueryOpening, SegmentOpening,
+    StarkProof,
+};
+use core::fmt::{Display, Formatter};
+
+/// Transcript absorbs of the proof parameters, AIR digest and trace shape
+const HEADER_ABSORBS: usize = 8;
+
+/// Work a verifier does to check a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VerificationCost {
+    /// SHA-256 invocations
+    pub hashes: usize,
+    /// Base field multiplications and inversions
+    pub field_ops: usize,
+    /// Serialized proof size in bytes
+    pub bytes: usize,
+}
+
+impl Display for VerificationCost {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "VerificationCost(hashes={}, field_ops={}, bytes={})",
+            self.hashes, self.field_ops, self.bytes
+        )
+    }
+}
+
+/// Expected number of squeezes to sample one field element
+///
+/// Challenges are drawn from 64-bit words and rejected above the modulus.
+fn squeezes_per_element<F: FieldElement>() -> usize {
+    (1u128 << 64).div_ceil(u128::from(F::MODULUS.max(1))) as usize
+}
+
+impl<F: FieldElement> StarkProof<F> {
+    /// Estimate the work needed to verify this proof
+    ///
+    /// See the [`cost`](crate::proof::cost) module for what is counted.
+    pub fn verification_cost(&self) -> VerificationCost {
+        VerificationCost {
+            hashes: self.transcript_hashes() + self.merkle_hashes() + 1,
+            field_ops: self.constraint_ops() + self.query_ops() + self.fri_ops(),
+            bytes: self.size_breakdown().total(),
+        }
+    }
+
+    /// Hashes replaying the transcript
+    fn transcript_hashes(&self) -> usize {
+        let squeeze = squeezes_per_element::<F>();
+        let extension = self.metadata.parameters.field_extension_degree as usize;
+        let width = self.ood_frame.trace_current.len();
+        let rows = 2 + self.ood_frame.trace_window.len();
+        let num_coefficients = self.air.transition.coefficients.len() + self.air.boundary.constraints.len();
+        let num_layers = self.fri_proof.layers.len();
+
+        // Public input counts, commitments, OOD frame and FRI remainder
+        let absorbs = HEADER_ABSORBS + 2 + self.commitments.len() + rows + 1 + num_layers + 1;
+        let squeezes = (num_coefficients * extension
+            + 1
+            + rows * width
+            + extension
+            + num_layers.saturating_sub(1) * extension
+            + self.fri_proof.queries.len())
+            * squeeze
+            + self.openings.len().div_ceil(4);
+        absorbs + squeezes
+    }
+
+    /// Hashes authenticating the query openings
+    fn merkle_hashes(&self) -> usize {
+        self.openings
+            .iter()
+            .map(|opening| {
+                let aux: usize = opening.aux_segments.iter().map(|segment| segment.path.len() + 1).sum();
+                opening.trace_path.len() + 1 + aux + opening.composition_path.len() + 1
+            })
+            .sum()
+    }
+
+    /// Field operations checking the trace and the out-of-domain constraints
+    fn constraint_ops(&self) -> usize {
+        let extension = self.metadata.parameters.field_extension_degree as usize;
+        let transition: usize = self.air.transition.coefficients.iter().map(Vec::len).sum();
+        let num_transitions = self.air.transition.coefficients.len();
+        let num_boundary = self.air.boundary.constraints.len();
+
+        // Transparent proofs carry the trace, which is checked row by row
+        let trace = if self.trace.columns.is_empty() {
+            0
+        } else {
+            self.trace.length.saturating_sub(1) * transition
+        };
+        // Each coordinate evaluates the zerofier, the transitions and the
+        // boundary quotients at the out-of-domain point
+        let per_coordinate = self.trace.length + transition + 2 * num_transitions + 4 * num_boundary;
+        trace + extension * per_coordinate + 4 * num_boundary
+    }
+
+    /// Field operations recomputing the DEEP composition at every opening
+    fn query_ops(&self) -> usize {
+        let extension = self.metadata.parameters.field_extension_degree as usize;
+        let width = self.ood_frame.trace_current.len();
+        let rows = 2 + self.ood_frame.trace_window.len();
+        let deep_length = self.fri_proof.layers.first().map_or(0, |layer| layer.degree);
+        self.openings.len() * (2 * rows * width + 2 * extension + rows + deep_length)
+    }
+
+    /// Field operations re-folding the FRI layers and evaluating them at the queries
+    fn fri_ops(&self) -> usize {
+        let extension = self.fri_proof.extension_degree;
+        let layers = &self.fri_proof.layers;
+        let folding: usize = layers
+            .iter()
+            .take(layers.len().saturating_sub(1))
+            .map(|layer| layer.degree * extension * extension)
+            .sum();
+        let evaluation: usize = layers.iter().map(|layer| layer.degree * extension).sum();
+        folding + self.fri_proof.queries.len() * evaluation
+    }
+}
+
+impl<F: FieldElement> StarkProver<F> {
+    /// Size in bytes of a serialized proof of `air` over a trace of `trace_length` rows
+    ///
+    /// Computed from the prover parameters and the AIR without generating a
+    /// trace; see the [`cost`](crate::proof::cost) module.
+    pub fn estimate_proof_size(&self, air: &Air<F>, trace_length: usize) -> Result<usize, ProofError> {
+        let extension_degree = self.extension_degree()?;
+        let constraints = CompositionConstraints::from_air(air, trace_length)?;
+        let lde_trace_length = self.lde_trace_length(trace_length);
+        let domain_size = lde_trace_length
+            .checked_mul(self.blowup_factor)
+            .ok_or(ProofError::InvalidTrace)?;
+        let depth = MerkleTree::calculate_depth(domain_size);
+        let num_registers = air.transition.num_registers();
+        let aux_widths: Vec<usize> = air.aux_segments.iter().map(|segment| segment.definition().width()).collect();
+        let salt = self.zero_knowledge.then_some([0u8; 32]);
+
+        // Transparent proofs carry the trace, zero-knowledge proofs withhold it
+        let trace = ExecutionTrace {
+            columns: if self.zero_knowledge {
+                TraceStorage::new()
+            } else {
+                vec![vec![F::zero(); trace_length]; num_registers].into()
+            },
+            length: trace_length,
+            num_registers,
+            aux_segments: if self.zero_knowledge {
+                Vec::new()
+            } else {
+                aux_widths.iter().map(|&width| vec![vec![F::zero(); trace_length]; width]).collect()
+            },
+        };
+        let commitment = MerkleCommitment {
+            root: vec![0; 32],
+            depth,
+            leaves: Vec::new(),
+        };
+
+        // The DEEP quotients have one coefficient fewer than the trace and
+        // composition polynomials, and FRI folds them down to the remainder
+        let composition_length =
+            composition_length(trace_length, lde_trace_length, !aux_widths.is_empty(), air.window());
+        let mut degrees = vec![lde_trace_length.max(composition_length).saturating_sub(1).max(1)];
+        while let Some(&degree) = degrees.last().filter(|&&degree| degree > MAX_REMAINDER_LENGTH) {
+            degrees.push(degree.div_ceil(FRI_FOLDING_FACTOR));
+        }
+        let layer_width = |index: usize| if index == 0 { 1 } else { extension_degree };
+        let layers: Vec<FriLayer<F>> = degrees
+            .iter()
+            .enumerate()
+            .map(|(index, &degree)| FriLayer {
+                polynomial: vec![F::zero(); degree * layer_width(index)],
+                commitment: vec![0; 32 * degree * layer_width(index)],
+                degree,
+            })
+            .collect();
+        let responses = (0..layers.len()).map(layer_width).sum();
+
+        let opening = QueryOpening {
+            position: 0,
+            trace_row: vec![F::zero(); num_registers],
+            trace_path: vec![[0; 32]; depth],
+            trace_salt: salt,
+            aux_segments: aux_widths
+                .iter()
+                .map(|&width| SegmentOpening {
+                    row: vec![F::zero(); width],
+                    path: vec![[0; 32]; depth],
+                    salt,
+                })
+                .collect(),
+            composition: vec![F::zero(); extension_degree],
+            masking: self.zero_knowledge.then(F::zero),
+            composition_path: vec![[0; 32]; depth],
+            composition_salt: salt,
+        };
+        let width = num_registers + aux_widths.iter().sum::<usize>();
+        let proof = StarkProof {
+            air: Self::to_stark_air(air, &constraints),
+            commitments: vec![commitment; 2 + aux_widths.len()],
+            ood_frame: OodFrame {
+                trace_current: vec![F::zero(); width],
+                trace_next: vec![F::zero(); width],
+                trace_window: vec![vec![F::zero(); width]; air.window() - 2],
+                composition: vec![F::zero(); extension_degree],
+            },
+            fri_proof: FriProof {
+                extension_degree,
+                final_polynomial: layers.last().map(|layer| layer.polynomial.clone()).unwrap_or_default(),
+                queries: vec![
+                    FriQuery {
+                        point: F::zero(),
+                        responses: vec![F::zero(); responses],
+                    };
+                    self.num_queries
+                ],
+                layers,
+            },
+            openings: vec![opening; self.num_queries.min(domain_size)],
+            metadata: self.create_proof_metadata(&Digest::default(), 0)?,
+            body: ProofBody::Native,
+            trace,
+        };
+        Ok(proof.size_breakdown().total())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::StarkProver;
+    use crate::types::StarkComponent;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_verification_cost_tracks_parameters() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let cost = |queries: usize, extension: u32| {
+            let prover = StarkProver::<PrimeField64>::with_params(128, 8, queries, extension).deterministic([1; 32]);
+            let witness = prover.generate_trace(&air, &initial_state, 16).unwrap();
+            let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+            assert_eq!(proof.metadata.proof_size, proof.to_bytes().len());
+            (proof.verification_cost(), proof.to_bytes().len())
+        };
+
+        let (base, bytes) = cost(16, 1);
+        assert_eq!(base.bytes, bytes);
+        assert!(base.hashes > 16 && base.field_ops > 0);
+
+        let (more_queries, _) = cost(32, 1);
+        assert!(more_queries.hashes > base.hashes);
+        assert!(more_queries.field_ops > base.field_ops);
+        assert!(more_queries.bytes > base.bytes);
+
+        let (extension, _) = cost(16, 3);
+        assert!(extension.field_ops > base.field_ops);
+        assert!(extension.bytes > base.bytes);
+    }
+
+    #[test]
+    fn test_proof_size_estimate_is_exact() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        for prover in [
+            StarkProver::<PrimeField64>::with_params(128, 8, 16, 1),
+            StarkProver::<PrimeField64>::with_params(128, 4, 24, 3),
+            StarkProver::<PrimeField64>::with_params(128, 8, 16, 2).with_zk(true),
+        ] {
+            let witness = prover.generate_trace(&air, &initial_state, 32).unwrap();
+            let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+            assert_eq!(prover.estimate_proof_size(&air, 32).unwrap(), proof.to_bytes().len());
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Out-of-Domain Sampling and DEEP Composition
+//!
+//! After committing to the composition polynomial, the prover receives an
+//! out-of-domain point `z` from the transcript and sends the trace evaluations
+//! at `z` and at the next-row point `z + 1`, together with the composition
+//! evaluation at `z`. When an auxiliary segment constrains a window of `w > 2`
+//! rows, the trace is also opened at `z + 2, ..., z + w - 1`. The verifier
+//! checks the DEEP constraint equation
+//!
+//! ```text
+//! C(z) = sum_i alpha_i * t_i(T(z), ..., T(z + w - 1)) / Z(z) + sum_b beta_b * (T_r(z) - v_b) / (z - r_b)
+//! ```
+//!
+//! and FRI is then run on the DEEP composition polynomial
+//!
+//! ```text
+//! D(x) = sum_j a_j * (T_j(x) - T_j(z)) / (x - z)
+//!      + sum_j b_j * (T_j(x) - T_j(z + 1)) / (x - z - 1)
+//!      + sum_k sum_j b_kj * (T_j(x) - T_j(z + k)) / (x - z - k)
+//!      + sum_i c_i * (C_i(x) - C_i(z)) / (x - z)
+//! ```
+//!
+//! which is a polynomial exactly when the claimed OOD evaluations are correct.
+//! When the composition coefficients are drawn from a field extension, `C` has
+//! extension coefficients and `C_i` are its base field coordinates; the frame
+//! carries one value per coordinate.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+use crate::types::stark::OodFrame;
+use crate::proof::composition::{divide_by_linear, CompositionPolynomial, TraceLde, MAX_DOMAIN_POINT};
+use crate::proof::transcript::{labels, Transcript};
+
+/// Draw the out-of-domain point `z` from the transcript
+///
+/// Candidates whose canonical value is below [`MAX_DOMAIN_POINT`], or whose
+/// next-row point wraps around the modulus, are rejected and redrawn.
+pub fn draw_ood_point<F: FieldElement>(transcript: &mut Transcript) -> F {
+    loop {
+        let z: F = transcript.challenge_field_element(labels::OOD_POINT);
+        let value = z.as_canonical_u64();
+        if value >= MAX_DOMAIN_POINT && value + 1 < F::MODULUS {
+            return z;
+        }
+    }
+}
+
+/// Next-row point of `x`
+pub fn next_point<F: FieldElement>(x: F) -> F {
+    x + F::one()
+}
+
+/// Point of the row `offset` rows after `x`
+pub fn row_point<F: FieldElement>(x: F, offset: usize) -> F {
+    x + F::from_canonical_u64(offset as u64)
+}
+
+/// Evaluate the trace over a window of `window` rows from `z`, and the
+/// composition coordinate polynomials at `z`
+pub fn build_ood_frame<F: FieldElement>(
+    trace_lde: &TraceLde<F>,
+    composition: &[CompositionPolynomial<F>],
+    z: F,
+    window: usize,
+) -> OodFrame<F> {
+    OodFrame {
+        trace_current: trace_lde.evaluate_at(z),
+        trace_next: trace_lde.evaluate_at(next_point(z)),
+        trace_window: (2..window).map(|offset| trace_lde.evaluate_at(row_point(z, offset))).collect(),
+        composition: composition.iter().map(|coordinate| coordinate.evaluate(z)).collect(),
+    }
+}
+
+/// Absorb an OOD frame into the transcript
+pub fn absorb_ood_frame<F: FieldElement>(transcript: &mut Transcript, frame: &OodFrame<F>) {
+    transcript.absorb_field_elements(labels::OOD_FRAME, &frame.trace_current);
+    transcript.absorb_field_elements(labels::OOD_FRAME, &frame.trace_next);
+    for row in &frame.trace_window {
+        transcript.absorb_field_elements(labels::OOD_FRAME, row);
+    }
+    transcript.absorb_field_elements(labels::OOD_FRAME, &frame.composition);
+}
+
+/// Number of DEEP coefficients for a trace of the given width opened over
+/// `window` rows
+///
+/// Each coordinate of the composition polynomial over a degree
+/// `extension_degree` extension takes its own coefficient.
+pub fn num_deep_coefficients(num_registers: usize, extension_degree: usize, window: usize) -> usize {
+    window * num_registers + extension_degree
+}
+
+/// Build the DEEP composition polynomial in coefficient form
+///
+/// `coefficients` holds one block of trace coefficients per row of the
+/// window, starting with the current row, then one coefficient per
+/// composition coordinate.
+pub fn deep_composition_polynomial<F: FieldElement>(
+    trace_lde: &TraceLde<F>,
+    composition: &[CompositionPolynomial<F>],
+    z: F,
+    window: usize,
+    coefficients: &[F],
+) -> Vec<F> {
+    let num_registers = trace_lde.polynomials().len();
+    let (trace_coeffs, composition_coeffs) = coefficients.split_at(window * num_registers);
+
+    let mut terms = Vec::with_capacity(window * num_registers + composition.len());
+    for (offset, row_coeffs) in trace_coeffs.chunks(num_registers.max(1)).enumerate() {
+        let point = row_point(z, offset);
+        for (&coeff, polynomial) in row_coeffs.iter().zip(trace_lde.polynomials()) {
+            terms.push((coeff, divide_by_linear(polynomial, point)));
+        }
+    }
+    for (&coeff, coordinate) in composition_coeffs.iter().zip(composition) {
+        terms.push((coeff, divide_by_linear(coordinate.coefficients(), z)));
+    }
+
+    let length = terms.iter().map(|(_, quotient)| quotient.len()).max().unwrap_or(1);
+    let mut result = vec![F::zero(); length];
+    for (coeff, quotient) in terms {
+        for (acc, &value) in result.iter_mut().zip(&quotient) {
+            *acc = *acc + coeff * value;
+        }
+    }
+    result
+}
+
+/// Evaluate the DEEP composition at `x` from the trace row and composition values at `x`
+///
+/// Returns `None` if `x` coincides with `z` or one of the other opened rows.
+pub fn evaluate_deep<F: FieldElement>(
+    x: F,
+    trace_row: &[F],
+    composition_values: &[F],
+    frame: &OodFrame<F>,
+    z: F,
+    coefficients: &[F],
+) -> Option<F> {
+    let num_registers = trace_row.len();
+    let current_inv = (x - z).inverse()?;
+    let next_inv = (x - next_point(z)).inverse()?;
+
+    let mut result = F::zero();
+    for j in 0..num_registers {
+        result = result + coefficients[j] * (trace_row[j] - frame.trace_current[j]) * current_inv;
+        result = result
+            + coefficients[num_registers + j] * (trace_row[j] - frame.trace_next[j]) * next_inv;
+    }
+    for (index, row) in frame.trace_window.iter().enumerate() {
+        let offset = index + 2;
+        let row_inv = (x - row_point(z, offset)).inverse()?;
+        let row_coeffs = &coefficients[offset * num_registers..(offset + 1) * num_registers];
+        for ((&coeff, &value), &claimed) in row_coeffs.iter().zip(trace_row).zip(row) {
+            result = result + coeff * (value - claimed) * row_inv;
+        }
+    }
+    let composition_coeffs = &coefficients[(2 + frame.trace_window.len()) * num_registers..];
+    for ((&coeff, &value), &claimed) in composition_coeffs.iter().zip(composition_values).zip(&frame.composition) {
+        result = result + coeff * (value - claimed) * current_inv;
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::composition::{evaluate_polynomial, lde_domain_point, CompositionConstraints};
+    use crate::types::stark::ExecutionTrace;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_deep_polynomial_matches_pointwise_evaluation() {
+        let trace = ExecutionTrace {
+            columns: vec![(1..=8).map(PrimeField64::new).collect()].into(),
+            length: 8,
+            num_registers: 1,
+            aux_segments: Vec::new(),
+        };
+        let constraints = CompositionConstraints::new(
+            vec![vec![PrimeField64::one()]],
+            vec![],
+            8,
+        );
+        let lde = TraceLde::new(&trace, 4).unwrap();
+        // The counter trace does not satisfy the identity transition, but the
+        // DEEP algebra holds for any polynomial pair
+        let composition: Vec<_> = [3, 4]
+            .into_iter()
+            .map(|coeff| CompositionPolynomial::new(&constraints, &lde, &[PrimeField64::new(coeff)]).unwrap())
+            .collect();
+
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        let z: PrimeField64 = draw_ood_point(&mut transcript);
+        assert!(z.as_canonical_u64() >= MAX_DOMAIN_POINT);
+
+        let frame = build_ood_frame(&lde, &composition, z, 3);
+        let coefficients: Vec<PrimeField64> = (5..10).map(PrimeField64::new).collect();
+        assert_eq!(coefficients.len(), num_deep_coefficients(1, composition.len(), 3));
+        let deep = deep_composition_polynomial(&lde, &composition, z, 3, &coefficients);
+
+        for index in [0, 7, 31] {
+            let x = lde_domain_point(8, index);
+            let expected = evaluate_deep(
+                x,
+                &lde.row(index),
+                &composition.iter().map(|coordinate| coordinate.evaluate(x)).collect::<Vec<_>>(),
+                &frame,
+                z,
+                &coefficients,
+            )
+            .unwrap();
+            assert_eq!(evaluate_polynomial(&deep, x), expected);
+        }
+    }
+}
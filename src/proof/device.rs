@@ -0,0 +1,260 @@
+//! Compute Devices
+//!
+//! Extending the trace over the LDE domain and hashing the commitment leaves
+//! dominate proving time, and both are data-parallel. With the `gpu` feature a
+//! prover can offload them to an [`Accelerator`], chosen at runtime with
+//! [`DeviceSelection`].
+//!
+//! The crate only defines the [`Accelerator`] trait and the dispatch around
+//! it; it ships no GPU kernels. Without an accelerator supplied by the caller,
+//! every stage runs on the CPU.
+//!
+//! Accelerators must reproduce the CPU results exactly: the LDE values of
+//! [`extend_rows`](crate::proof::composition::extend_rows) and the keyed leaf
+//! hashes of [`MerkleTree::hash_leaves`](crate::proof::merkle::MerkleTree::hash_leaves).
+//! Proofs are therefore identical whichever device generated them. A kernel
+//! that fails falls back to the CPU unless the accelerator is required.
+
+use crate::types::FieldElement;
+use crate::proof::composition::extend_rows;
+use crate::proof::merkle::MerkleTree;
+use core::fmt::Debug;
+use core::ops::Range;
+use alloc::sync::Arc;
+use thiserror::Error;
+
+/// Runtime choice of the device running the LDE and hashing stages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeviceSelection {
+    /// Run every stage on the CPU
+    Cpu,
+    /// Use the accelerator if one is available, falling back to the CPU
+    #[default]
+    PreferAccelerator,
+    /// Fail if no accelerator is available or a kernel fails
+    RequireAccelerator,
+}
+
+/// Device that runs the LDE and leaf hashing kernels
+pub trait Accelerator<F: FieldElement>: Debug + Send + Sync {
+    /// Device name, for diagnostics
+    fn name(&self) -> &str;
+
+    /// Whether the device can currently run kernels
+    fn is_available(&self) -> bool;
+
+    /// Evaluate a column polynomial at a range of LDE domain positions
+    ///
+    /// Must return the values of [`extend_rows`] for the same arguments.
+    fn extend_column(&self, polynomial: &[F], trace_length: usize, rows: Range<usize>) -> Result<Vec<F>, DeviceError>;
+
+    /// Hash Merkle leaves under a commitment key
+    ///
+    /// Must return the hashes of [`MerkleTree::hash_leaves`] for the same
+    /// arguments.
+    fn hash_leaves(&self, key: &[u8], leaves: &[Vec<u8>]) -> Result<Vec<[u8; 32]>, DeviceError>;
+}
+
+/// Errors reported by compute devices
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum DeviceError {
+    /// No accelerator is configured or available
+    #[error("No accelerator available: {0}")]
+    Unavailable(String),
+
+    /// A kernel failed on the device
+    #[error("Kernel failed on {device}: {message}")]
+    Kernel {
+        /// Device name
+        device: String,
+        /// Failure description
+        message: String,
+    },
+}
+
+/// Device configuration of a prover
+#[derive(Debug, Clone)]
+pub struct DeviceConfig<F: FieldElement> {
+    /// Device selection
+    selection: DeviceSelection,
+    /// Configured accelerator
+    accelerator: Option<Arc<dyn Accelerator<F>>>,
+}
+
+impl<F: FieldElement> DeviceConfig<F> {
+    /// Configuration without an accelerator
+    pub fn new() -> Self {
+        Self {
+            selection: DeviceSelection::default(),
+            accelerator: None,
+        }
+    }
+
+    /// Set the device selection
+    pub fn with_selection(mut self, selection: DeviceSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Set the accelerator
+    pub fn with_accelerator(mut self, accelerator: Arc<dyn Accelerator<F>>) -> Self {
+        self.accelerator = Some(accelerator);
+        self
+    }
+
+    /// Device selection
+    pub fn selection(&self) -> DeviceSelection {
+        self.selection
+    }
+
+    /// Accelerator the kernels should run on, if any
+    ///
+    /// Fails if an accelerator is required but none is available.
+    pub fn active(&self) -> Result<Option<&dyn Accelerator<F>>, DeviceError> {
+        let available = self
+            .accelerator
+            .as_deref()
+            .filter(|accelerator| accelerator.is_available());
+        match self.selection {
+            DeviceSelection::Cpu => Ok(None),
+            DeviceSelection::PreferAccelerator => Ok(available),
+            DeviceSelection::RequireAccelerator => available.map(Some).ok_or_else(|| {
+                DeviceError::Unavailable(match &self.accelerator {
+                    Some(accelerator) => format!("{} is not available", accelerator.name()),
+                    None => "no accelerator configured".to_string(),
+                })
+            }),
+        }
+    }
+
+    /// Extend a column on the accelerator, falling back to the CPU unless it is required
+    pub fn extend_column(
+        &self,
+        accelerator: &dyn Accelerator<F>,
+        polynomial: &[F],
+        trace_length: usize,
+        rows: Range<usize>,
+    ) -> Result<Vec<F>, DeviceError> {
+        match accelerator.extend_column(polynomial, trace_length, rows.clone()) {
+            Ok(values) if values.len() == rows.len() => Ok(values),
+            result => self.fall_back(accelerator, result.err(), || extend_rows(polynomial, trace_length, rows)),
+        }
+    }
+
+    /// Hash leaves on the accelerator, falling back to the CPU unless it is required
+    pub fn hash_leaves(
+        &self,
+        accelerator: &dyn Accelerator<F>,
+        key: &[u8],
+        leaves: &[Vec<u8>],
+    ) -> Result<Vec<[u8; 32]>, DeviceError> {
+        match accelerator.hash_leaves(key, leaves) {
+            Ok(hashes) if hashes.len() == leaves.len() => Ok(hashes),
+            result => self.fall_back(accelerator, result.err(), || MerkleTree::hash_leaves(key, leaves)),
+        }
+    }
+
+    /// Run a kernel on the CPU after the accelerator failed, if allowed
+    fn fall_back<T>(
+        &self,
+        accelerator: &dyn Accelerator<F>,
+        error: Option<DeviceError>,
+        cpu: impl FnOnce() -> T,
+    ) -> Result<T, DeviceError> {
+        if self.selection == DeviceSelection::RequireAccelerator {
+            return Err(error.unwrap_or_else(|| DeviceError::Kernel {
+                device: accelerator.name().to_string(),
+                message: "wrong number of results".to_string(),
+            }));
+        }
+        Ok(cpu())
+    }
+}
+
+impl<F: FieldElement> Default for DeviceConfig<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{ProofError, StarkProver};
+    use crate::types::field::PrimeField64;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Accelerator running the CPU kernels, or failing every call
+    #[derive(Debug, Default)]
+    struct MockAccelerator {
+        failing: bool,
+        calls: AtomicUsize,
+    }
+
+    impl Accelerator<PrimeField64> for MockAccelerator {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        fn extend_column(
+            &self,
+            polynomial: &[PrimeField64],
+            trace_length: usize,
+            rows: Range<usize>,
+        ) -> Result<Vec<PrimeField64>, DeviceError> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            if self.failing {
+                return Err(DeviceError::Kernel { device: "mock".to_string(), message: "out of memory".to_string() });
+            }
+            Ok(extend_rows(polynomial, trace_length, rows))
+        }
+
+        fn hash_leaves(&self, key: &[u8], leaves: &[Vec<u8>]) -> Result<Vec<[u8; 32]>, DeviceError> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            if self.failing {
+                return Err(DeviceError::Kernel { device: "mock".to_string(), message: "out of memory".to_string() });
+            }
+            Ok(MerkleTree::hash_leaves(key, leaves))
+        }
+    }
+
+    #[test]
+    fn test_accelerated_proofs_match_cpu() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).deterministic([3; 32]);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 16).unwrap();
+        let cpu = prover.clone().with_device(DeviceSelection::Cpu);
+        let cpu_proof = cpu.prove(&air, &PublicInputs::new(), &witness).unwrap();
+
+        let accelerator = Arc::new(MockAccelerator::default());
+        let accelerated = prover.clone().with_accelerator(accelerator.clone());
+        assert_eq!(accelerated.prove(&air, &PublicInputs::new(), &witness).unwrap(), cpu_proof);
+        assert!(accelerator.calls.load(Ordering::Relaxed) > 0);
+
+        // A failing kernel falls back to the CPU unless the accelerator is required
+        let failing = Arc::new(MockAccelerator { failing: true, ..MockAccelerator::default() });
+        let fallback = prover.clone().with_accelerator(failing.clone());
+        assert_eq!(fallback.prove(&air, &PublicInputs::new(), &witness).unwrap(), cpu_proof);
+
+        let required = fallback.with_device(DeviceSelection::RequireAccelerator);
+        assert!(matches!(
+            required.prove(&air, &PublicInputs::new(), &witness),
+            Err(ProofError::DeviceError(DeviceError::Kernel { .. }))
+        ));
+        assert!(matches!(
+            prover.with_device(DeviceSelection::RequireAccelerator).prove(&air, &PublicInputs::new(), &witness),
+            Err(ProofError::DeviceError(DeviceError::Unavailable(_)))
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! CPU Kernel Dispatch
+//!
+//! [`Kernels`] is the dispatch table of the hot loops of the prover for one
+//! instruction set level: batched field multiplication, batched polynomial
+//! evaluation (which extends the trace over the LDE domain), radix-2 NTT
+//! butterflies and Merkle leaf hashing. The level is chosen once at runtime,
+//! so a single binary can use wider instructions on machines that have them
+//! without being built with `target-cpu` flags. Every level must compute
+//! exactly the same values, so proofs do not depend on the machine that
+//! generated them.
+//!
+//! Only the [`CpuLevel::Scalar`] kernels exist so far. Wider levels belong
+//! here once they have SIMD kernels that a benchmark shows beating the
+//! scalar ones; recompiling the scalar loops under a target feature is not
+//! enough. Leaf hashing needs no level of its own: the SHA-256 backend
+//! already selects the SHA extensions of x86 and aarch64 at runtime.
+
+use crate::prelude::*;
+use crate::proof::composition::lde_domain_point;
+use crate::proof::merkle::MerkleTree;
+use crate::types::FieldElement;
+use core::fmt::{Display, Formatter};
+use core::ops::Range;
+
+/// Instruction set level the kernels are compiled for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CpuLevel {
+    /// Baseline instructions of the target architecture
+    Scalar,
+}
+
+impl CpuLevel {
+    /// All levels, from the narrowest to the widest
+    pub const ALL: [CpuLevel; 1] = [CpuLevel::Scalar];
+
+    /// Widest level the running CPU supports
+    pub fn detect() -> CpuLevel {
+        CpuLevel::ALL
+            .into_iter()
+            .rev()
+            .find(|level| level.is_supported())
+            .unwrap_or(CpuLevel::Scalar)
+    }
+
+    /// Whether the running CPU supports this level
+    pub fn is_supported(self) -> bool {
+        match self {
+            CpuLevel::Scalar => true,
+        }
+    }
+}
+
+impl Display for CpuLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            CpuLevel::Scalar => "scalar",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Dispatch table of the hot kernels for one [`CpuLevel`]
+#[derive(Debug, Clone, Copy)]
+pub struct Kernels<F: FieldElement> {
+    /// Level the kernels were compiled for
+    level: CpuLevel,
+    /// Multiply values by factors in place
+    mul: fn(&mut [F], &[F]),
+    /// Evaluate a polynomial at many points
+    evaluate: fn(&[F], &[F]) -> Vec<F>,
+    /// Radix-2 butterflies over two halves
+    butterflies: fn(&mut [F], &mut [F], &[F]),
+    /// Hash Merkle leaves under a commitment key
+    hash_leaves: fn(&[u8], &[Vec<u8>]) -> Vec<[u8; 32]>,
+}
+
+impl<F: FieldElement> Kernels<F> {
+    /// Kernels of the widest level the running CPU supports
+    pub fn detected() -> Self {
+        Self::for_level(CpuLevel::detect()).unwrap_or_else(Self::scalar)
+    }
+
+    /// Kernels compiled for the baseline instructions
+    pub fn scalar() -> Self {
+        Self {
+            level: CpuLevel::Scalar,
+            mul: portable::mul,
+            evaluate: portable::evaluate,
+            butterflies: portable::butterflies,
+            hash_leaves: MerkleTree::hash_leaves,
+        }
+    }
+
+    /// Kernels of a given level, if the running CPU supports it
+    pub fn for_level(level: CpuLevel) -> Option<Self> {
+        if !level.is_supported() {
+            return None;
+        }
+        match level {
+            CpuLevel::Scalar => Some(Self::scalar()),
+        }
+    }
+
+    /// Level the kernels were compiled for
+    pub fn level(&self) -> CpuLevel {
+        self.level
+    }
+
+    /// Multiply `values[i]` by `factors[i]` in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices differ in length.
+    pub fn mul(&self, values: &mut [F], factors: &[F]) {
+        assert_eq!(values.len(), factors.len(), "mismatched kernel operands");
+        (self.mul)(values, factors);
+    }
+
+    /// Evaluate a polynomial in coefficient form at every point
+    ///
+    /// Agrees with [`evaluate_polynomial`](crate::proof::composition::evaluate_polynomial)
+    /// at each point.
+    pub fn evaluate(&self, coefficients: &[F], points: &[F]) -> Vec<F> {
+        (self.evaluate)(coefficients, points)
+    }
+
+    /// Evaluate a column polynomial at a range of LDE domain positions
+    ///
+    /// Agrees with [`extend_rows`](crate::proof::composition::extend_rows).
+    pub fn extend(&self, polynomial: &[F], trace_length: usize, rows: Range<usize>) -> Vec<F> {
+        let points: Vec<F> = rows.map(|index| lde_domain_point(trace_length, index)).collect();
+        self.evaluate(polynomial, &points)
+    }
+
+    /// Apply radix-2 butterflies `(a, b) -> (a + w·b, a - w·b)`
+    ///
+    /// Pairs `lo[i]` with `hi[i]` under the twiddle factor `twiddles[i]`, as
+    /// one layer of a Cooley–Tukey NTT does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slices differ in length.
+    pub fn butterflies(&self, lo: &mut [F], hi: &mut [F], twiddles: &[F]) {
+        assert!(
+            lo.len() == hi.len() && hi.len() == twiddles.len(),
+            "mismatched kernel operands"
+        );
+        (self.butterflies)(lo, hi, twiddles);
+    }
+
+    /// Hash Merkle leaves under a commitment key
+    ///
+    /// Agrees with [`MerkleTree::hash_leaves`].
+    pub fn hash_leaves(&self, key: &[u8], leaves: &[Vec<u8>]) -> Vec<[u8; 32]> {
+        (self.hash_leaves)(key, leaves)
+    }
+}
+
+impl<F: FieldElement> Default for Kernels<F> {
+    fn default() -> Self {
+        Self::detected()
+    }
+}
+
+/// Kernel bodies, written so that the compiler can vectorize them
+mod portable {
+    use crate::prelude::*;
+    use crate::types::FieldElement;
+
+    pub(super) fn mul<F: FieldElement>(values: &mut [F], factors: &[F]) {
+        for (value, &factor) in values.iter_mut().zip(factors) {
+            *value *= factor;
+        }
+    }
+
+    /// Horner's rule over all points at once, one coefficient at a time
+    pub(super) fn evaluate<F: FieldElement>(coefficients: &[F], points: &[F]) -> Vec<F> {
+        let mut values = vec![F::zero(); points.len()];
+        for &coefficient in coefficients.iter().rev() {
+            for (value, &x) in values.iter_mut().zip(points) {
+                *value = *value * x + coefficient;
+            }
+        }
+        values
+    }
+
+    pub(super) fn butterflies<F: FieldElement>(lo: &mut [F], hi: &mut [F], twiddles: &[F]) {
+        for ((a, b), &twiddle) in lo.iter_mut().zip(hi.iter_mut()).zip(twiddles) {
+            let t = *b * twiddle;
+            *b = *a - t;
+            *a += t;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::composition::{evaluate_polynomial, extend_rows};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_every_level_matches_scalar() {
+        let field = |values: &[u64]| values.iter().map(|&v| PrimeField64::new(v)).collect::<Vec<_>>();
+        let coefficients = field(&[3, 0, 7, PrimeField64::MODULUS - 1, 11]);
+        let points = field(&(0..37).map(|i| i * 0x1234_5678_9abc).collect::<Vec<_>>());
+        let twiddles = field(&(0..37).map(|i| i * 31 + 5).collect::<Vec<_>>());
+        let scalar = Kernels::<PrimeField64>::scalar();
+
+        let expected: Vec<_> = points.iter().map(|&x| evaluate_polynomial(&coefficients, x)).collect();
+        assert_eq!(scalar.evaluate(&coefficients, &points), expected);
+        assert_eq!(scalar.extend(&coefficients, 16, 3..40), extend_rows(&coefficients, 16, 3..40));
+
+        let supported: Vec<_> = CpuLevel::ALL.into_iter().filter(|level| level.is_supported()).collect();
+        assert!(supported.contains(&CpuLevel::detect()));
+        assert_eq!(Kernels::<PrimeField64>::detected().level(), CpuLevel::detect());
+
+        for level in CpuLevel::ALL {
+            let Some(kernels) = Kernels::<PrimeField64>::for_level(level) else {
+                assert!(!supported.contains(&level));
+                continue;
+            };
+            assert_eq!(kernels.level(), level);
+            assert_eq!(kernels.evaluate(&coefficients, &points), expected);
+
+            let (mut values, mut expected_values) = (points.clone(), points.clone());
+            kernels.mul(&mut values, &twiddles);
+            scalar.mul(&mut expected_values, &twiddles);
+            assert_eq!(values, expected_values);
+
+            let (mut lo, mut hi) = (points.clone(), twiddles.clone());
+            kernels.butterflies(&mut lo, &mut hi, &points);
+            for i in 0..points.len() {
+                let t = twiddles[i] * points[i];
+                assert_eq!((lo[i], hi[i]), (points[i] + t, points[i] - t));
+            }
+
+            let leaves = vec![vec![1u8; 40], vec![2u8; 7]];
+            assert_eq!(kernels.hash_leaves(b"key", &leaves), MerkleTree::hash_leaves(b"key", &leaves));
+        }
+    }
+}
@@ -6,15 +6,83 @@
 //! ## Features
 //! 
 //! - **Polynomial Folding**: Real polynomial folding with field arithmetic
-//! - **Domain Generation**: Efficient multiplicative subgroup generation
+//! - **Coefficient Form**: Layers are folded as coefficient vectors, so no
+//!   multiplicative subgroup of the field is required
+//! - **Extension Challenges**: Folding challenges can be drawn from a degree 2
+//!   or 3 extension; folded layers then carry extension coefficients
 //! - **Proof Construction**: Complete FRI proof with layers and queries
 //! - **Verification**: Cryptographic verification of FRI proofs
 //! - **Performance Optimization**: Optimized algorithms for production use
 
-use crate::types::{FieldElement, StarkComponent};
+use crate::prelude::*;
+use crate::types::{ExtensionElement, FieldElement, StarkComponent};
 use crate::types::stark::{FriProof, FriLayer, FriQuery};
-use std::fmt::{Display, Formatter};
-use std::marker::PhantomData;
+use crate::proof::transcript::{labels, Transcript};
+use core::fmt::{Display, Formatter};
+use core::marker::PhantomData;
+
+/// Maximum number of coefficients in the final FRI layer
+pub const MAX_REMAINDER_LENGTH: usize = 4;
+
+/// Fold a polynomial in coefficient form by `folding_factor`
+///
+/// Writing `p(x) = sum_m x^m * p_m(x^k)` for `k = folding_factor`, the folded
+/// polynomial is `sum_m challenge^m * p_m(y)`, whose degree is `k` times smaller.
+pub fn fold_polynomial<F: FieldElement>(
+    polynomial: &[ExtensionElement<F>],
+    challenge: ExtensionElement<F>,
+    folding_factor: usize,
+) -> Vec<ExtensionElement<F>> {
+    polynomial
+        .chunks(folding_factor)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(ExtensionElement::zero(challenge.degree()), |acc, &coeff| acc * challenge + coeff)
+        })
+        .collect()
+}
+
+/// Number of base field values stored per coefficient of a layer
+///
+/// The input layer is in the base field; folding with an extension challenge
+/// moves every later layer into the extension.
+fn layer_width(layer: usize, extension_degree: usize) -> usize {
+    if layer == 0 {
+        1
+    } else {
+        extension_degree
+    }
+}
+
+/// Store extension values with their first `width` coordinates each
+fn flatten<F: FieldElement>(values: &[ExtensionElement<F>], width: usize) -> Vec<F> {
+    values
+        .iter()
+        .flat_map(|value| value.coordinates()[..width].iter().copied())
+        .collect()
+}
+
+/// Coefficients of a stored layer as extension elements
+///
+/// Returns `None` if the stored values do not match the layer degree.
+fn layer_coefficients<F: FieldElement>(
+    layer: &FriLayer<F>,
+    width: usize,
+    extension_degree: usize,
+) -> Option<Vec<ExtensionElement<F>>> {
+    if layer.polynomial.len() != layer.degree.checked_mul(width)? {
+        return None;
+    }
+    Some(
+        layer
+            .polynomial
+            .chunks(width)
+            .map(|coordinates| ExtensionElement::from_coordinates(coordinates, extension_degree))
+            .collect(),
+    )
+}
 
 /// FRI proof generator
 /// 
@@ -29,6 +97,8 @@ pub struct FriProver<F: FieldElement> {
     num_queries: usize,
     /// Folding factor for polynomial reduction
     folding_factor: usize,
+    /// Degree of the extension the folding challenges are drawn from
+    extension_degree: usize,
     /// Phantom data for type parameter
     _phantom: PhantomData<F>,
 }
@@ -41,6 +111,7 @@ impl<F: FieldElement> FriProver<F> {
             blowup_factor: 16, // Must be <= 16 for Winterfell compatibility
             num_queries: 64,
             folding_factor: 4,
+            extension_degree: 1,
             _phantom: PhantomData,
         }
     }
@@ -57,29 +128,56 @@ impl<F: FieldElement> FriProver<F> {
             blowup_factor,
             num_queries,
             folding_factor,
+            extension_degree: 1,
             _phantom: PhantomData,
         }
     }
 
+    /// Draw the folding challenges from the extension of the given degree
+    pub fn with_extension_degree(mut self, extension_degree: usize) -> Self {
+        self.extension_degree = extension_degree;
+        self
+    }
+
     /// Generate a complete FRI proof
+    ///
+    /// Uses a fresh transcript; see [`FriProver::prove_with_transcript`] to
+    /// bind the proof to an enclosing protocol.
     pub fn prove(&self, polynomial: &[F]) -> Result<FriProof<F>, FriError> {
-        // Step 1: Generate evaluation domain
-        let domain = self.generate_evaluation_domain(polynomial.len())?;
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        self.prove_with_transcript(polynomial, &mut transcript)
+    }
 
-        // Step 2: Evaluate polynomial over domain
-        let evaluations = self.evaluate_polynomial(polynomial, &domain)?;
+    /// Generate a FRI proof drawing all challenges from a transcript
+    ///
+    /// The input polynomial is the first committed layer. Each folding
+    /// challenge is squeezed after the current layer commitment is absorbed,
+    /// and the query points are squeezed after the final polynomial.
+    pub fn prove_with_transcript(
+        &self,
+        polynomial: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<FriProof<F>, FriError> {
+        if polynomial.is_empty() || self.folding_factor < 2 {
+            return Err(FriError::InvalidPolynomialSize);
+        }
+        if !ExtensionElement::<F>::is_supported(self.extension_degree) {
+            return Err(FriError::UnsupportedExtension(self.extension_degree));
+        }
 
-        // Step 3: Generate FRI layers through polynomial folding
-        let layers = self.generate_fri_layers(&evaluations, &domain)?;
+        // Step 1: Generate FRI layers through polynomial folding
+        let layers = self.generate_fri_layers(polynomial, transcript)?;
 
-        // Step 4: Generate final polynomial
+        // Step 2: Generate final polynomial
         let final_polynomial = self.generate_final_polynomial(&layers)?;
+        transcript.absorb_field_elements(labels::FRI_REMAINDER, &final_polynomial);
 
-        // Step 5: Generate query responses
-        let queries = self.generate_queries(&layers, &domain)?;
+        // Step 3: Generate query responses
+        let queries = self.generate_queries(&layers, transcript)?;
 
-        // Step 6: Construct FRI proof
+        // Step 4: Construct FRI proof
         let proof = FriProof {
+            extension_degree: self.extension_degree,
             layers,
             final_polynomial,
             queries,
@@ -88,127 +186,48 @@ impl<F: FieldElement> FriProver<F> {
         Ok(proof)
     }
 
-    /// Generate evaluation domain (multiplicative subgroup)
-    fn generate_evaluation_domain(&self, polynomial_degree: usize) -> Result<Vec<F>, FriError> {
-        let domain_size = polynomial_degree * self.blowup_factor;
-        
-        // Find a generator of the multiplicative subgroup
-        let generator = self.find_generator(domain_size)?;
-        
-        // Generate domain elements: {1, g, g^2, ..., g^(domain_size-1)}
-        let mut domain = Vec::with_capacity(domain_size);
-        let mut current = F::one();
-        
-        for _ in 0..domain_size {
-            domain.push(current);
-            current = current * generator;
-        }
-
-        Ok(domain)
-    }
-
-    /// Find a generator for the multiplicative subgroup
-    fn find_generator(&self, domain_size: usize) -> Result<F, FriError> {
-        // For simplicity, we'll use a fixed generator
-        // In production, this should be computed based on the field
-        let generator = F::random(); // Use random instead of F::new
-        
-        // Verify it's a generator by checking its order
-        let mut current = generator;
-        for _ in 0..domain_size - 1 {
-            current = current * generator;
-        }
-        
-        if current == F::one() {
-            Ok(generator)
-        } else {
-            Err(FriError::GeneratorNotFound)
-        }
-    }
-
-    /// Evaluate polynomial over domain
-    fn evaluate_polynomial(&self, polynomial: &[F], domain: &[F]) -> Result<Vec<F>, FriError> {
-        let mut evaluations = Vec::with_capacity(domain.len());
-        
-        for &point in domain {
-            let mut result = F::zero();
-            let mut power = F::one();
-            
-            for &coeff in polynomial {
-                result = result + coeff * power;
-                power = power * point;
-            }
-            
-            evaluations.push(result);
-        }
-
-        Ok(evaluations)
-    }
-
     /// Generate FRI layers through polynomial folding
-    fn generate_fri_layers(&self, evaluations: &[F], domain: &[F]) -> Result<Vec<FriLayer<F>>, FriError> {
+    ///
+    /// Folding stops once the layer has at most [`MAX_REMAINDER_LENGTH`]
+    /// coefficients.
+    fn generate_fri_layers(
+        &self,
+        polynomial: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<Vec<FriLayer<F>>, FriError> {
         let mut layers = Vec::new();
-        let mut current_evaluations = evaluations.to_vec();
-        let mut current_domain = domain.to_vec();
-        let mut current_degree = evaluations.len() / self.blowup_factor;
-
-        while current_degree > 1 {
-            // Generate random challenge for folding
-            let challenge = self.generate_random_challenge();
-            
-            // Fold polynomial using the challenge
-            let folded_evaluations = self.fold_polynomial(&current_evaluations, challenge)?;
-            
-            // Generate commitment for this layer
-            let commitment = self.generate_commitment(&folded_evaluations)?;
-            
-            // Create FRI layer
-            let layer = FriLayer {
-                polynomial: folded_evaluations.clone(),
+        let mut current: Vec<ExtensionElement<F>> = polynomial
+            .iter()
+            .map(|&coeff| ExtensionElement::from_base(coeff, self.extension_degree))
+            .collect();
+
+        loop {
+            // Commit to the current layer and bind it to the transcript
+            let values = flatten(&current, layer_width(layers.len(), self.extension_degree));
+            let commitment = self.generate_commitment(&values)?;
+            transcript.absorb_commitment(labels::FRI_LAYER_COMMITMENT, &commitment);
+
+            let degree = current.len();
+            layers.push(FriLayer {
+                polynomial: values,
                 commitment,
-                degree: current_degree,
-            };
-            
-            layers.push(layer);
-            
-            // Update for next iteration
-            current_evaluations = folded_evaluations;
-            current_domain = self.reduce_domain(&current_domain);
-            current_degree = current_degree / self.folding_factor;
-        }
-
-        Ok(layers)
-    }
-
-    /// Fold polynomial using random challenge
-    fn fold_polynomial(&self, evaluations: &[F], challenge: F) -> Result<Vec<F>, FriError> {
-        if evaluations.len() % self.folding_factor != 0 {
-            return Err(FriError::InvalidPolynomialSize);
-        }
-
-        let folded_size = evaluations.len() / self.folding_factor;
-        let mut folded = Vec::with_capacity(folded_size);
+                degree,
+            });
 
-        for i in 0..folded_size {
-            let mut result = F::zero();
-            let mut power = F::one();
-
-            for j in 0..self.folding_factor {
-                let index = i + j * folded_size;
-                result = result + evaluations[index] * power;
-                power = power * challenge;
+            if degree <= MAX_REMAINDER_LENGTH {
+                break;
             }
 
-            folded.push(result);
+            // Draw folding challenge from the transcript and fold
+            let challenge = transcript.challenge_extension_elements(
+                labels::FRI_FOLDING_CHALLENGE,
+                1,
+                self.extension_degree,
+            )[0];
+            current = fold_polynomial(&current, challenge, self.folding_factor);
         }
 
-        Ok(folded)
-    }
-
-    /// Reduce domain size for next layer
-    fn reduce_domain(&self, domain: &[F]) -> Vec<F> {
-        let reduced_size = domain.len() / self.folding_factor;
-        domain.iter().step_by(self.folding_factor).take(reduced_size).cloned().collect()
+        Ok(layers)
     }
 
     /// Generate commitment for layer
@@ -238,19 +257,31 @@ impl<F: FieldElement> FriProver<F> {
     }
 
     /// Generate query responses
-    fn generate_queries(&self, layers: &[FriLayer<F>], _domain: &[F]) -> Result<Vec<FriQuery<F>>, FriError> {
+    fn generate_queries(
+        &self,
+        layers: &[FriLayer<F>],
+        transcript: &mut Transcript,
+    ) -> Result<Vec<FriQuery<F>>, FriError> {
+        let coefficients = layers
+            .iter()
+            .enumerate()
+            .map(|(index, layer)| {
+                layer_coefficients(layer, layer_width(index, self.extension_degree), self.extension_degree)
+                    .ok_or(FriError::InvalidLayer(index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         let mut queries = Vec::new();
 
         for _ in 0..self.num_queries {
-            // Generate random query point
-            let query_point = self.generate_random_challenge();
+            // Draw query point from the transcript
+            let query_point = transcript.challenge_field_element(labels::FRI_QUERY);
             
             // Generate responses for each layer
             let mut responses = Vec::new();
             
-            for layer in layers {
-                let response = self.evaluate_at_point(&layer.polynomial, query_point)?;
-                responses.push(response);
+            for (index, layer) in coefficients.iter().enumerate() {
+                let response = ExtensionElement::evaluate_polynomial(layer, query_point, self.extension_degree);
+                responses.extend(flatten(&[response], layer_width(index, self.extension_degree)));
             }
 
             let query = FriQuery {
@@ -263,30 +294,10 @@ impl<F: FieldElement> FriProver<F> {
 
         Ok(queries)
     }
-
-    /// Evaluate polynomial at a specific point
-    fn evaluate_at_point(&self, polynomial: &[F], point: F) -> Result<F, FriError> {
-        let mut result = F::zero();
-        let mut power = F::one();
-
-        for &coeff in polynomial {
-            result = result + coeff * power;
-            power = power * point;
-        }
-
-        Ok(result)
-    }
-
-    /// Generate random challenge
-    fn generate_random_challenge(&self) -> F {
-        // In production, this should use a cryptographically secure RNG
-        // For now, we'll use a simple deterministic approach
-        F::random()
-    }
 }
 
 impl<F: FieldElement> Display for FriProver<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "FriProver(security={}, blowup={}, queries={}, folding={})",
@@ -304,6 +315,8 @@ pub struct FriVerifier<F: FieldElement> {
     security_parameter: u32,
     /// Number of queries to verify
     num_queries: usize,
+    /// Folding factor for polynomial reduction
+    folding_factor: usize,
     /// Phantom data for type parameter
     _phantom: PhantomData<F>,
 }
@@ -314,12 +327,57 @@ impl<F: FieldElement> FriVerifier<F> {
         Self {
             security_parameter,
             num_queries: 64,
+            folding_factor: 4,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create a verifier with custom parameters
+    pub fn with_params(security_parameter: u32, num_queries: usize, folding_factor: usize) -> Self {
+        Self {
+            security_parameter,
+            num_queries,
+            folding_factor,
             _phantom: PhantomData,
         }
     }
 
     /// Verify a FRI proof
-    pub fn verify(&self, proof: &FriProof<F>, _original_polynomial: &[F]) -> Result<bool, FriError> {
+    ///
+    /// Replays a fresh transcript; see [`FriVerifier::verify_with_transcript`].
+    pub fn verify(&self, proof: &FriProof<F>, original_polynomial: &[F]) -> Result<bool, FriError> {
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        self.verify_with_transcript(proof, original_polynomial, &mut transcript)
+    }
+
+    /// Verify a FRI proof, re-deriving its challenges from a transcript
+    ///
+    /// The transcript must be in the same state the prover's transcript was in
+    /// when the FRI proof was generated.
+    pub fn verify_with_transcript(
+        &self,
+        proof: &FriProof<F>,
+        original_polynomial: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<bool, FriError> {
+        match self.check_with_transcript(proof, original_polynomial, transcript) {
+            Ok(()) => Ok(true),
+            Err(FriError::InvalidLayer(_) | FriError::InvalidRemainder | FriError::InvalidQuery(_)) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Check a FRI proof against a transcript, reporting the first failed check
+    ///
+    /// Like [`FriVerifier::verify_with_transcript`], but an invalid proof yields
+    /// [`FriError::InvalidLayer`], [`FriError::InvalidRemainder`] or
+    /// [`FriError::InvalidQuery`] identifying where it failed.
+    pub fn check_with_transcript(
+        &self,
+        proof: &FriProof<F>,
+        original_polynomial: &[F],
+        transcript: &mut Transcript,
+    ) -> Result<(), FriError> {
         // Step 1: Verify proof structure
         if proof.layers.is_empty() {
             return Err(FriError::NoLayers);
@@ -329,76 +387,117 @@ impl<F: FieldElement> FriVerifier<F> {
             return Err(FriError::NoQueries);
         }
 
-        // Step 2: Verify layer consistency
-        if !self.verify_layer_consistency(&proof.layers)? {
-            return Ok(false);
+        if !ExtensionElement::<F>::is_supported(proof.extension_degree) {
+            return Err(FriError::UnsupportedExtension(proof.extension_degree));
         }
 
-        // Step 3: Verify query responses
-        if !self.verify_query_responses(proof, _original_polynomial)? {
-            return Ok(false);
+        if !original_polynomial.is_empty() && proof.layers[0].polynomial != original_polynomial {
+            return Err(FriError::InvalidLayer(0));
         }
 
-        // Step 4: Verify final polynomial
-        if !self.verify_final_polynomial(&proof.final_polynomial)? {
-            return Ok(false);
+        let coefficients = proof
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(index, layer)| {
+                layer_coefficients(layer, layer_width(index, proof.extension_degree), proof.extension_degree)
+                    .ok_or(FriError::InvalidLayer(index))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Step 2: Replay the transcript and verify layer consistency
+        self.verify_layer_consistency(proof, &coefficients, transcript)?;
+
+        // Step 3: Verify final polynomial
+        self.verify_final_polynomial(proof)?;
+        transcript.absorb_field_elements(labels::FRI_REMAINDER, &proof.final_polynomial);
+
+        // Step 4: Check the query points against the transcript
+        for (index, query) in proof.queries.iter().enumerate() {
+            let expected_point: F = transcript.challenge_field_element(labels::FRI_QUERY);
+            if query.point != expected_point {
+                return Err(FriError::InvalidQuery(index));
+            }
         }
 
-        Ok(true)
+        // Step 5: Verify query responses
+        self.verify_query_responses(proof, &coefficients)
     }
 
-    /// Verify layer consistency
-    fn verify_layer_consistency(&self, layers: &[FriLayer<F>]) -> Result<bool, FriError> {
-        for i in 1..layers.len() {
-            let prev_layer = &layers[i - 1];
-            let curr_layer = &layers[i];
+    /// Verify layer commitments and that each layer folds into the next
+    fn verify_layer_consistency(
+        &self,
+        proof: &FriProof<F>,
+        coefficients: &[Vec<ExtensionElement<F>>],
+        transcript: &mut Transcript,
+    ) -> Result<(), FriError> {
+        let layers = &proof.layers;
+        for (i, layer) in layers.iter().enumerate() {
+            // Verify commitment consistency
+            if layer.commitment != self.generate_commitment(&layer.polynomial)? {
+                return Err(FriError::InvalidLayer(i));
+            }
+            transcript.absorb_commitment(labels::FRI_LAYER_COMMITMENT, &layer.commitment);
+
+            let Some(next_layer) = layers.get(i + 1) else {
+                break;
+            };
 
-            // Check that degrees are consistent
-            if curr_layer.degree * 4 != prev_layer.degree {
-                return Ok(false);
+            // Only layers above the remainder bound are folded
+            if layer.degree <= MAX_REMAINDER_LENGTH {
+                return Err(FriError::InvalidLayer(i));
             }
 
-            // Verify commitment consistency
-            let expected_commitment = self.generate_commitment(&curr_layer.polynomial)?;
-            if curr_layer.commitment != expected_commitment {
-                return Ok(false);
+            let challenge = transcript.challenge_extension_elements(
+                labels::FRI_FOLDING_CHALLENGE,
+                1,
+                proof.extension_degree,
+            )[0];
+            let folded = fold_polynomial(&coefficients[i], challenge, self.folding_factor);
+            if next_layer.polynomial != flatten(&folded, layer_width(i + 1, proof.extension_degree)) {
+                return Err(FriError::InvalidLayer(i + 1));
             }
         }
 
-        Ok(true)
+        Ok(())
     }
 
     /// Verify query responses
     fn verify_query_responses(
         &self,
         proof: &FriProof<F>,
-        _original_polynomial: &[F],
-    ) -> Result<bool, FriError> {
-        for query in &proof.queries {
+        coefficients: &[Vec<ExtensionElement<F>>],
+    ) -> Result<(), FriError> {
+        if proof.queries.len() != self.num_queries {
+            return Err(FriError::InvalidQuery(proof.queries.len().min(self.num_queries)));
+        }
+
+        for (index, query) in proof.queries.iter().enumerate() {
             // Verify that responses are consistent with the polynomial
-            for (i, &response) in query.responses.iter().enumerate() {
-                if i < proof.layers.len() {
-                    let layer = &proof.layers[i];
-                    let expected = self.evaluate_at_point(&layer.polynomial, query.point)?;
-                    
-                    if response != expected {
-                        return Ok(false);
-                    }
-                }
+            let mut expected = Vec::with_capacity(query.responses.len());
+            for (layer, layer_coefficients) in coefficients.iter().enumerate() {
+                let value = ExtensionElement::evaluate_polynomial(layer_coefficients, query.point, proof.extension_degree);
+                expected.extend(flatten(&[value], layer_width(layer, proof.extension_degree)));
+            }
+            if query.responses != expected {
+                return Err(FriError::InvalidQuery(index));
             }
         }
 
-        Ok(true)
+        Ok(())
     }
 
     /// Verify final polynomial
-    fn verify_final_polynomial(&self, final_polynomial: &[F]) -> Result<bool, FriError> {
-        // The final polynomial should have low degree
-        if final_polynomial.len() > 4 {
-            return Ok(false);
+    fn verify_final_polynomial(&self, proof: &FriProof<F>) -> Result<(), FriError> {
+        // The final polynomial is the last layer and should have low degree
+        let last_layer = &proof.layers[proof.layers.len() - 1];
+        if last_layer.degree > MAX_REMAINDER_LENGTH
+            || proof.final_polynomial != last_layer.polynomial
+        {
+            return Err(FriError::InvalidRemainder);
         }
 
-        Ok(true)
+        Ok(())
     }
 
     /// Generate commitment (same as prover)
@@ -412,23 +511,10 @@ impl<F: FieldElement> FriVerifier<F> {
 
         Ok(commitment)
     }
-
-    /// Evaluate polynomial at point (same as prover)
-    fn evaluate_at_point(&self, polynomial: &[F], point: F) -> Result<F, FriError> {
-        let mut result = F::zero();
-        let mut power = F::one();
-
-        for &coeff in polynomial {
-            result = result + coeff * power;
-            power = power * point;
-        }
-
-        Ok(result)
-    }
 }
 
 impl<F: FieldElement> Display for FriVerifier<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "FriVerifier(security={}, queries={})",
@@ -467,6 +553,22 @@ pub enum FriError {
     /// Query verification failed
     #[error("Query verification failed")]
     QueryVerificationFailed,
+
+    /// A layer does not match its commitment or does not fold into the next one
+    #[error("FRI layer {0} is inconsistent")]
+    InvalidLayer(usize),
+
+    /// The remainder does not match the last layer or exceeds the degree bound
+    #[error("FRI remainder is invalid")]
+    InvalidRemainder,
+
+    /// A query point or its responses are inconsistent
+    #[error("FRI query {0} is inconsistent")]
+    InvalidQuery(usize),
+
+    /// The field has no extension of the requested degree
+    #[error("Unsupported extension degree {0}")]
+    UnsupportedExtension(usize),
 }
 
 #[cfg(test)]
@@ -518,4 +620,55 @@ mod tests {
         
         assert!(is_valid, "FRI proof should be valid");
     }
+
+    #[test]
+    fn test_fri_folding_layers() {
+        let prover: FriProver<PrimeField64> = FriProver::new(128);
+        let verifier: FriVerifier<PrimeField64> = FriVerifier::new(128);
+        let polynomial: Vec<PrimeField64> = (1..=64).map(PrimeField64::new).collect();
+
+        let proof = prover.prove(&polynomial).expect("FRI proof generation should succeed");
+        let degrees: Vec<usize> = proof.layers.iter().map(|layer| layer.degree).collect();
+        assert_eq!(degrees, vec![64, 16, 4]);
+        assert!(verifier.verify(&proof, &polynomial).unwrap());
+
+        // A tampered intermediate layer breaks the folding relation
+        let mut tampered = proof.clone();
+        tampered.layers[1].polynomial[0] = tampered.layers[1].polynomial[0] + PrimeField64::one();
+        tampered.layers[1].commitment = prover.generate_commitment(&tampered.layers[1].polynomial).unwrap();
+        assert!(!verifier.verify(&tampered, &polynomial).unwrap());
+
+        // Proofs are bound to the transcript they were generated with
+        let mut other_transcript = Transcript::new(b"other-protocol");
+        assert!(!verifier
+            .verify_with_transcript(&proof, &polynomial, &mut other_transcript)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_fri_extension_layers() {
+        let verifier: FriVerifier<PrimeField64> = FriVerifier::new(128);
+        let polynomial: Vec<PrimeField64> = (1..=64).map(PrimeField64::new).collect();
+
+        let prover = FriProver::<PrimeField64>::new(128).with_extension_degree(3);
+        let proof = prover.prove(&polynomial).expect("FRI proof generation should succeed");
+        let sizes: Vec<usize> = proof.layers.iter().map(|layer| layer.polynomial.len()).collect();
+        assert_eq!(sizes, vec![64, 16 * 3, 4 * 3]);
+        assert_eq!(proof.queries[0].responses.len(), 1 + 3 + 3);
+        assert!(verifier.verify(&proof, &polynomial).unwrap());
+
+        // The layers cannot be reinterpreted over another extension
+        let mut relabeled = proof.clone();
+        relabeled.extension_degree = 2;
+        assert!(!verifier.verify(&relabeled, &polynomial).unwrap());
+        relabeled.extension_degree = 4;
+        assert!(matches!(
+            verifier.verify(&relabeled, &polynomial),
+            Err(FriError::UnsupportedExtension(4))
+        ));
+        assert!(matches!(
+            FriProver::<PrimeField64>::new(128).with_extension_degree(4).prove(&polynomial),
+            Err(FriError::UnsupportedExtension(4))
+        ));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,360 @@
+//! Prover Memory Budget
+//!
+//! Large traces can need more memory than the machine running the prover
+//! has. A budget set through [`ProverOptions`] makes the prover plan its work
+//! up front: the memory that must stay resident (trace polynomials, Merkle
+//! trees, the composition polynomial and the FRI layers) is estimated from the
+//! shape of the LDE, and the LDE is then evaluated and committed in chunks
+//! sized to fit in what is left.
+//!
+//! With the `mmap` feature, LDE evaluations that do not fit are spilled to
+//! memory-mapped temporary files, and the operating system pages them in as
+//! constraint evaluation and queries touch them. Without it, a trace that
+//! cannot fit is rejected with [`ProofError::MemoryBudgetExceeded`] before any
+//! work is done.
+//!
+//! FRI layers are folded in coefficient form, so they are sized by the trace
+//! rather than by the LDE domain and are accounted for without chunking.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+use crate::proof::merkle::MerkleNode;
+use super::ProofError;
+use core::ops::Range;
+
+#[cfg(feature = "mmap")]
+pub use crate::types::storage::MappedColumn;
+
+/// Smallest number of rows or leaves processed per chunk
+pub const MIN_CHUNK_SIZE: usize = 1024;
+
+/// Resource options of a prover
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProverOptions {
+    /// Upper bound on the memory a proof may use, in bytes
+    ///
+    /// `None` places no bound: the LDE is evaluated and committed in one pass.
+    pub max_memory_bytes: Option<usize>,
+}
+
+impl ProverOptions {
+    /// Options without resource limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bound the memory a proof may use
+    pub fn with_max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.max_memory_bytes = Some(bytes);
+        self
+    }
+}
+
+/// Shape of the low-degree extension a proof is generated over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LdeShape {
+    /// Length of the interpolated trace, including random rows
+    pub trace_length: usize,
+    /// Number of columns across the main trace and auxiliary segments
+    pub width: usize,
+    /// Number of committed trace segments (main plus auxiliary)
+    pub num_segments: usize,
+    /// LDE domain size
+    pub domain_size: usize,
+    /// Degree of the field extension the composition and FRI layers are in
+    pub extension_degree: usize,
+}
+
+impl LdeShape {
+    /// Bytes that stay resident for the whole proof, excluding LDE evaluations
+    pub fn resident_bytes<F: FieldElement>(&self) -> usize {
+        let element = core::mem::size_of::<F>();
+        let node = core::mem::size_of::<MerkleNode>();
+        let polynomials = self.width.saturating_mul(self.trace_length).saturating_mul(element);
+        // One tree per trace segment plus the composition tree, each with
+        // fewer than twice as many nodes as leaves
+        let trees = (self.num_segments + 1)
+            .saturating_mul(2)
+            .saturating_mul(self.domain_size)
+            .saturating_mul(node);
+        // Composition and masking evaluations, plus composition coefficients,
+        // with one composition polynomial per extension coordinate
+        let composition = self
+            .domain_size
+            .saturating_add(self.trace_length)
+            .saturating_mul(self.extension_degree)
+            .saturating_add(self.domain_size)
+            .saturating_add(self.trace_length)
+            .saturating_mul(element);
+        // DEEP polynomial and the FRI layers folded from it
+        let fri = self
+            .trace_length
+            .saturating_mul(4)
+            .saturating_mul(self.extension_degree)
+            .saturating_mul(element);
+        polynomials
+            .saturating_add(trees)
+            .saturating_add(composition)
+            .saturating_add(fri)
+    }
+
+    /// Bytes of the LDE evaluations, which can be spilled to disk
+    pub fn lde_bytes<F: FieldElement>(&self) -> usize {
+        self.width
+            .saturating_mul(self.domain_size.saturating_add(1))
+            .saturating_mul(core::mem::size_of::<F>())
+    }
+
+    /// Working memory per LDE row evaluated in a chunk
+    fn row_bytes<F: FieldElement>(&self) -> usize {
+        self.width.max(1).saturating_mul(core::mem::size_of::<F>())
+    }
+
+    /// Working memory per Merkle leaf encoded and hashed in a chunk
+    ///
+    /// Leaves encode every value in 32 bytes and may carry a 32-byte salt.
+    fn leaf_bytes(&self) -> usize {
+        self.width
+            .max(self.extension_degree + 1)
+            .saturating_add(1)
+            .saturating_mul(32)
+            .saturating_add(core::mem::size_of::<Vec<u8>>() + 32)
+    }
+}
+
+/// Chunk sizes and storage chosen for a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryPlan {
+    /// LDE rows evaluated per chunk of a column
+    pub lde_chunk_rows: usize,
+    /// Merkle leaves encoded and hashed per chunk
+    pub hash_chunk_leaves: usize,
+    /// Whether LDE evaluations are spilled to memory-mapped files
+    pub spill_to_disk: bool,
+}
+
+impl MemoryPlan {
+    /// Plan without a memory bound: everything is processed in one pass
+    pub fn unbounded() -> Self {
+        Self {
+            lde_chunk_rows: usize::MAX,
+            hash_chunk_leaves: usize::MAX,
+            spill_to_disk: false,
+        }
+    }
+
+    /// Plan a proof over an LDE of the given shape within the options' budget
+    ///
+    /// Chunks are as large as the memory left over by the resident data
+    /// allows, but never smaller than [`MIN_CHUNK_SIZE`]. LDE evaluations are
+    /// spilled to disk only if they do not fit and the `mmap` feature is on.
+    pub fn new<F: FieldElement>(options: &ProverOptions, shape: &LdeShape) -> Result<Self, ProofError> {
+        let Some(budget) = options.max_memory_bytes else {
+            return Ok(Self::unbounded());
+        };
+
+        let resident = shape.resident_bytes::<F>();
+        let lde = shape.lde_bytes::<F>();
+        let min_rows = MIN_CHUNK_SIZE.min(shape.domain_size.max(1));
+        let min_working = min_rows.saturating_mul(shape.row_bytes::<F>().max(shape.leaf_bytes()));
+
+        let in_memory = resident.saturating_add(lde).saturating_add(min_working);
+        let spilled = resident.saturating_add(min_working);
+        let (spill_to_disk, available) = if in_memory <= budget {
+            (false, budget - resident - lde)
+        } else if cfg!(feature = "mmap") && spilled <= budget {
+            (true, budget - resident)
+        } else {
+            let required = if cfg!(feature = "mmap") { spilled } else { in_memory };
+            return Err(ProofError::MemoryBudgetExceeded { required, budget });
+        };
+
+        // The LDE is fully evaluated before any leaf is hashed, so both
+        // stages may use all of the available memory
+        let chunk = |unit: usize, total: usize| (available / unit.max(1)).clamp(min_rows, total.max(min_rows));
+        Ok(Self {
+            lde_chunk_rows: chunk(shape.row_bytes::<F>(), shape.domain_size + 1),
+            hash_chunk_leaves: chunk(shape.leaf_bytes(), shape.domain_size),
+            spill_to_disk,
+        })
+    }
+}
+
+impl Default for MemoryPlan {
+    fn default() -> Self {
+        Self::unbounded()
+    }
+}
+
+/// Ranges of at most `chunk_size` indices covering `0..len`
+pub fn chunk_ranges(len: usize, chunk_size: usize) -> impl Iterator<Item = Range<usize>> {
+    let chunk_size = chunk_size.max(1);
+    (0..len)
+        .step_by(chunk_size)
+        .map(move |start| start..start.saturating_add(chunk_size).min(len))
+}
+
+/// Column of evaluations, held in memory or spilled to disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnBuffer<F: FieldElement> {
+    /// Values held in memory
+    Memory(Vec<F>),
+    /// Values stored in a memory-mapped temporary file
+    #[cfg(feature = "mmap")]
+    Mapped(MappedColumn<F>),
+}
+
+impl<F: FieldElement> ColumnBuffer<F> {
+    /// Fill a column of `len` values chunk by chunk
+    ///
+    /// `fill` returns the values for a range of indices. The column is spilled
+    /// to disk if `spill` is set, in which case only one chunk is held in
+    /// memory at a time.
+    pub fn from_chunks(
+        len: usize,
+        chunk_size: usize,
+        spill: bool,
+        mut fill: impl FnMut(Range<usize>) -> Result<Vec<F>, ProofError>,
+    ) -> Result<Self, ProofError> {
+        #[cfg(feature = "mmap")]
+        if spill {
+            let mut column =
+                MappedColumn::with_len(len).map_err(|error| ProofError::StorageError(error.to_string()))?;
+            for range in chunk_ranges(len, chunk_size) {
+                let start = range.start;
+                column.write(start, &fill(range)?);
+            }
+            return Ok(ColumnBuffer::Mapped(column));
+        }
+        #[cfg(not(feature = "mmap"))]
+        if spill {
+            return Err(ProofError::StorageError("spilling requires the `mmap` feature".to_string()));
+        }
+
+        let mut values = Vec::with_capacity(len);
+        for range in chunk_ranges(len, chunk_size) {
+            values.extend(fill(range)?);
+        }
+        Ok(ColumnBuffer::Memory(values))
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        match self {
+            ColumnBuffer::Memory(values) => values.len(),
+            #[cfg(feature = "mmap")]
+            ColumnBuffer::Mapped(column) => column.len(),
+        }
+    }
+
+    /// Whether the column has no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Value at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn get(&self, index: usize) -> F {
+        match self {
+            ColumnBuffer::Memory(values) => values[index],
+            #[cfg(feature = "mmap")]
+            ColumnBuffer::Mapped(column) => column.get(index),
+        }
+    }
+
+    /// Whether the values are spilled to disk
+    pub fn is_spilled(&self) -> bool {
+        !matches!(self, ColumnBuffer::Memory(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_memory_plan_fits_budget() {
+        let shape = LdeShape {
+            trace_length: 1 << 12,
+            width: 4,
+            num_segments: 1,
+            domain_size: 1 << 15,
+            extension_degree: 1,
+        };
+        assert_eq!(MemoryPlan::new::<PrimeField64>(&ProverOptions::new(), &shape).unwrap(), MemoryPlan::unbounded());
+
+        // A budget barely above the resident data forces small chunks
+        let needed = shape.resident_bytes::<PrimeField64>() + shape.lde_bytes::<PrimeField64>();
+        let tight = ProverOptions::new().with_max_memory_bytes(needed + (1 << 18));
+        let plan = MemoryPlan::new::<PrimeField64>(&tight, &shape).unwrap();
+        assert!(!plan.spill_to_disk);
+        assert!(plan.hash_chunk_leaves >= MIN_CHUNK_SIZE && plan.hash_chunk_leaves < shape.domain_size);
+        assert!(plan.lde_chunk_rows >= MIN_CHUNK_SIZE && plan.lde_chunk_rows <= shape.domain_size + 1);
+
+        // Without room for the LDE the trace is spilled or rejected
+        let small = ProverOptions::new().with_max_memory_bytes(shape.resident_bytes::<PrimeField64>() + (1 << 20));
+        match MemoryPlan::new::<PrimeField64>(&small, &shape) {
+            Ok(plan) => assert!(cfg!(feature = "mmap") && plan.spill_to_disk),
+            Err(error) => {
+                assert!(!cfg!(feature = "mmap"));
+                assert!(matches!(error, ProofError::MemoryBudgetExceeded { .. }));
+            }
+        }
+
+        let column = ColumnBuffer::from_chunks(10, 3, false, |range| {
+            Ok(range.map(|index| PrimeField64::new(index as u64)).collect())
+        })
+        .unwrap();
+        assert_eq!(column.len(), 10);
+        assert_eq!(column.get(7), PrimeField64::new(7));
+    }
+
+    #[test]
+    fn test_budgeted_proof_matches_unbounded() {
+        use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+        use crate::proof::public_inputs::PublicInputs;
+        use crate::proof::StarkProver;
+
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).deterministic([7; 32]);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 256).unwrap();
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+
+        let shape = LdeShape {
+            trace_length: 256,
+            width: 2,
+            num_segments: 1,
+            domain_size: 2048,
+            extension_degree: 1,
+        };
+        let budget = shape.resident_bytes::<PrimeField64>() + shape.lde_bytes::<PrimeField64>() + (1 << 18);
+        let options = ProverOptions::new().with_max_memory_bytes(budget);
+        assert!(MemoryPlan::new::<PrimeField64>(&options, &shape).unwrap().hash_chunk_leaves < shape.domain_size);
+        let budgeted = prover.clone().with_options(options);
+        assert_eq!(budgeted.prove(&air, &PublicInputs::new(), &witness).unwrap(), proof);
+
+        // Spilled LDE columns yield the same proof
+        #[cfg(feature = "mmap")]
+        {
+            let options = ProverOptions::new().with_max_memory_bytes(shape.resident_bytes::<PrimeField64>() + 160_000);
+            assert!(MemoryPlan::new::<PrimeField64>(&options, &shape).unwrap().spill_to_disk);
+            let spilling = prover.clone().with_options(options);
+            assert_eq!(spilling.prove(&air, &PublicInputs::new(), &witness).unwrap(), proof);
+        }
+
+        let starved = prover.with_options(ProverOptions::new().with_max_memory_bytes(1 << 10));
+        assert!(matches!(
+            starved.prove(&air, &PublicInputs::new(), &witness),
+            Err(ProofError::MemoryBudgetExceeded { .. })
+        ));
+    }
+}
@@ -9,12 +9,84 @@
 //! - **Efficient Tree Construction**: Optimized tree building algorithms
 //! - **Inclusion Proofs**: Secure proof generation and verification
 //! - **Batch Operations**: Efficient batch proof generation
+//! - **Persistence**: Save a committed tree and reload it to answer later queries
+//! - **Keyed Commitments**: Role-specific keys domain-separate the trees of one proof
+//! - **Merkle Forests**: Several column groups bound under a single combined root
 //! - **Memory Optimization**: Minimal memory footprint for large trees
 
+use crate::prelude::*;
 use crate::types::FieldElement;
-use std::fmt::{Display, Formatter};
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 use sha2::{Sha256, Digest};
 
+/// Magic bytes identifying a serialized Merkle tree
+#[cfg(feature = "std")]
+const TREE_MAGIC: &[u8; 4] = b"XFGM";
+
+/// Version of the serialized Merkle tree format
+///
+/// Version 1 has no commitment key; version 2 appends the optional key after
+/// the tree dimensions.
+#[cfg(feature = "std")]
+const TREE_FORMAT_VERSION: u32 = 2;
+
+/// Nodes reserved per level before any of them has been read
+///
+/// Level sizes come from the input, so larger levels grow as their hashes
+/// arrive instead of being allocated up front.
+#[cfg(feature = "std")]
+const MAX_RESERVED_NODES: usize = 1 << 16;
+
+/// Role of a Merkle commitment inside a proof
+///
+/// Each role maps to a distinct hashing key, so a tree built for one role can
+/// never be confused with a tree built for another (e.g. a FRI layer opening
+/// replayed as a trace opening).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CommitmentRole {
+    /// Execution trace LDE
+    Trace,
+    /// Auxiliary trace segment LDE with the given index
+    AuxTrace(usize),
+    /// Constraint composition polynomial LDE
+    Composition,
+    /// FRI layer with the given index
+    FriLayer(usize),
+}
+
+impl CommitmentRole {
+    /// Hashing key for this role
+    pub fn key(&self) -> Vec<u8> {
+        match self {
+            CommitmentRole::Trace => b"xfg-stark/commitment/trace".to_vec(),
+            CommitmentRole::AuxTrace(segment) => {
+                let mut key = b"xfg-stark/commitment/aux-trace/".to_vec();
+                key.extend_from_slice(&(*segment as u64).to_le_bytes());
+                key
+            }
+            CommitmentRole::Composition => b"xfg-stark/commitment/composition".to_vec(),
+            CommitmentRole::FriLayer(layer) => {
+                let mut key = b"xfg-stark/commitment/fri-layer/".to_vec();
+                key.extend_from_slice(&(*layer as u64).to_le_bytes());
+                key
+            }
+        }
+    }
+}
+
+impl Display for CommitmentRole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CommitmentRole::Trace => write!(f, "Trace"),
+            CommitmentRole::AuxTrace(segment) => write!(f, "AuxTrace({})", segment),
+            CommitmentRole::Composition => write!(f, "Composition"),
+            CommitmentRole::FriLayer(layer) => write!(f, "FriLayer({})", layer),
+        }
+    }
+}
+
 /// Merkle tree node
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MerkleNode {
@@ -48,16 +120,39 @@ impl MerkleNode {
         Self::new(hash, left.level + 1, left.index / 2)
     }
 
+    /// Create a leaf node whose hash is bound to a commitment key
+    pub fn keyed_leaf(key: &[u8], data: &[u8]) -> Self {
+        let hash = Self::hash_leaf(Some(key), data);
+        Self::new(hash, 0, 0)
+    }
+
     /// Hash data using SHA256
     fn hash_data(data: &[u8]) -> [u8; 32] {
         let mut hasher = Sha256::new();
         hasher.update(data);
         hasher.finalize().into()
     }
+
+    /// Hash leaf data, mixing in the commitment key when one is set
+    ///
+    /// The key is length-prefixed so that no (key, data) pair can collide with
+    /// another pair whose concatenation happens to be equal.
+    fn hash_leaf(key: Option<&[u8]>, data: &[u8]) -> [u8; 32] {
+        match key {
+            Some(key) => {
+                let mut hasher = Sha256::new();
+                hasher.update((key.len() as u64).to_le_bytes());
+                hasher.update(key);
+                hasher.update(data);
+                hasher.finalize().into()
+            }
+            None => Self::hash_data(data),
+        }
+    }
 }
 
 impl Display for MerkleNode {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "MerkleNode(level={}, index={}, hash={:02x?})",
@@ -77,29 +172,107 @@ pub struct MerkleTree {
     pub num_leaves: usize,
     /// Tree nodes (for efficient proof generation)
     nodes: Vec<Vec<MerkleNode>>,
+    /// Commitment key mixed into leaf hashes (keyed mode only)
+    key: Option<Vec<u8>>,
 }
 
 impl MerkleTree {
     /// Create a new Merkle tree from leaf data
     pub fn new(leaves: &[Vec<u8>]) -> Result<Self, MerkleError> {
-        if leaves.is_empty() {
-            return Err(MerkleError::EmptyLeaves);
+        Self::build(leaves, None)
+    }
+
+    /// Create a Merkle tree whose leaf hashes are bound to `key`
+    pub fn new_keyed(leaves: &[Vec<u8>], key: &[u8]) -> Result<Self, MerkleError> {
+        Self::build(leaves, Some(key.to_vec()))
+    }
+
+    /// Create a keyed Merkle tree for a given commitment role
+    pub fn for_role(leaves: &[Vec<u8>], role: CommitmentRole) -> Result<Self, MerkleError> {
+        Self::new_keyed(leaves, &role.key())
+    }
+
+    /// Create a keyed Merkle tree for a given commitment role, building leaves on demand
+    ///
+    /// `leaf` returns the data of the leaf at an index. Leaves are built and
+    /// hashed `chunk_size` at a time, so the leaf data of the whole tree is
+    /// never held in memory at once.
+    pub fn for_role_chunked(
+        num_leaves: usize,
+        role: CommitmentRole,
+        chunk_size: usize,
+        leaf: impl Fn(usize) -> Vec<u8>,
+    ) -> Result<Self, MerkleError> {
+        Self::for_role_hashed(num_leaves, role, chunk_size, leaf, |key, leaves| {
+            Ok(Self::hash_leaves(key, leaves))
+        })
+    }
+
+    /// Create a keyed Merkle tree for a given commitment role with a custom leaf hasher
+    ///
+    /// `hash_chunk` hashes a chunk of leaves under the role key and must agree
+    /// with [`hash_leaves`](Self::hash_leaves); it lets the hashing run on an
+    /// accelerator.
+    pub fn for_role_hashed(
+        num_leaves: usize,
+        role: CommitmentRole,
+        chunk_size: usize,
+        leaf: impl Fn(usize) -> Vec<u8>,
+        mut hash_chunk: impl FnMut(&[u8], &[Vec<u8>]) -> Result<Vec<[u8; 32]>, MerkleError>,
+    ) -> Result<Self, MerkleError> {
+        let key = role.key();
+        let mut leaf_nodes = Vec::with_capacity(num_leaves);
+        for start in (0..num_leaves).step_by(chunk_size.max(1)) {
+            let end = start.saturating_add(chunk_size.max(1)).min(num_leaves);
+            let chunk: Vec<Vec<u8>> = (start..end).map(&leaf).collect();
+            let hashes = hash_chunk(&key, &chunk)?;
+            if hashes.len() != chunk.len() {
+                return Err(MerkleError::ConstructionError(format!(
+                    "expected {} leaf hashes, got {}",
+                    chunk.len(),
+                    hashes.len()
+                )));
+            }
+            leaf_nodes.extend(
+                hashes
+                    .into_iter()
+                    .enumerate()
+                    .map(|(offset, hash)| MerkleNode::new(hash, 0, start + offset)),
+            );
         }
+        Self::from_leaf_nodes(leaf_nodes, Some(key))
+    }
 
-        let num_leaves = leaves.len();
-        let depth = Self::calculate_depth(num_leaves);
-        let mut nodes = Vec::with_capacity(depth + 1);
+    /// Hash leaves under a commitment key, as keyed trees do
+    pub fn hash_leaves(key: &[u8], leaves: &[Vec<u8>]) -> Vec<[u8; 32]> {
+        leaves.iter().map(|data| MerkleNode::hash_leaf(Some(key), data)).collect()
+    }
 
-        // Create leaf nodes
-        let mut current_level: Vec<MerkleNode> = leaves
+    /// Build the tree levels, hashing leaves with the optional key
+    fn build(leaves: &[Vec<u8>], key: Option<Vec<u8>>) -> Result<Self, MerkleError> {
+        let leaf_nodes = leaves
             .iter()
             .enumerate()
             .map(|(i, data)| {
-                let mut node = MerkleNode::leaf(data);
-                node.index = i;
-                node
+                let hash = MerkleNode::hash_leaf(key.as_deref(), data);
+                MerkleNode::new(hash, 0, i)
             })
             .collect();
+        Self::from_leaf_nodes(leaf_nodes, key)
+    }
+
+    /// Build the tree levels above hashed leaves
+    fn from_leaf_nodes(leaf_nodes: Vec<MerkleNode>, key: Option<Vec<u8>>) -> Result<Self, MerkleError> {
+        if leaf_nodes.is_empty() {
+            return Err(MerkleError::EmptyLeaves);
+        }
+
+        let num_leaves = leaf_nodes.len();
+        let depth = Self::calculate_depth(num_leaves);
+        let mut nodes = Vec::with_capacity(depth + 1);
+
+        // Create leaf nodes
+        let mut current_level = leaf_nodes;
 
         nodes.push(current_level.clone());
 
@@ -117,11 +290,17 @@ impl MerkleTree {
             depth,
             num_leaves,
             nodes,
+            key,
         })
     }
 
+    /// Get the commitment key, if the tree is keyed
+    pub fn key(&self) -> Option<&[u8]> {
+        self.key.as_deref()
+    }
+
     /// Calculate tree depth from number of leaves
-    fn calculate_depth(num_leaves: usize) -> usize {
+    pub(crate) fn calculate_depth(num_leaves: usize) -> usize {
         if num_leaves <= 1 {
             return 0;
         }
@@ -180,10 +359,10 @@ impl MerkleTree {
                 current_index - 1
             };
 
-            if sibling_index < level_nodes.len() {
-                proof.siblings.push(level_nodes[sibling_index].hash);
-                proof.path.push(current_index % 2 == 0);
-            }
+            // The last node of an odd level is paired with itself
+            let sibling = level_nodes.get(sibling_index).unwrap_or(&level_nodes[current_index]);
+            proof.siblings.push(sibling.hash);
+            proof.path.push(current_index % 2 == 0);
 
             current_index /= 2;
         }
@@ -210,7 +389,7 @@ impl MerkleTree {
         }
 
         // Start with leaf hash
-        let mut current_hash = MerkleNode::hash_data(leaf_data);
+        let mut current_hash = MerkleNode::hash_leaf(self.key.as_deref(), leaf_data);
         
         // Follow the proof path
         for (i, &is_left) in proof.path.iter().enumerate() {
@@ -250,10 +429,176 @@ impl MerkleTree {
             total_nodes: self.nodes.iter().map(|level| level.len()).sum(),
         }
     }
+
+    /// Write the full tree (all levels) to a writer
+    ///
+    /// The layout is: magic `XFGM`, format version (u32 LE), number of leaves
+    /// and depth (u64 LE), the commitment key (u8 presence flag, then u32 LE
+    /// length and key bytes), then for every level from the leaves up its node
+    /// count (u64 LE) followed by the 32-byte node hashes.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), MerkleError> {
+        writer.write_all(TREE_MAGIC)?;
+        writer.write_all(&TREE_FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&(self.num_leaves as u64).to_le_bytes())?;
+        writer.write_all(&(self.depth as u64).to_le_bytes())?;
+
+        match &self.key {
+            Some(key) => {
+                writer.write_all(&[1u8])?;
+                writer.write_all(&(key.len() as u32).to_le_bytes())?;
+                writer.write_all(key)?;
+            }
+            None => writer.write_all(&[0u8])?,
+        }
+
+        for level in &self.nodes {
+            writer.write_all(&(level.len() as u64).to_le_bytes())?;
+            for node in level {
+                writer.write_all(&node.hash)?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a tree previously written with [`MerkleTree::write_to`]
+    ///
+    /// The level sizes are checked against the recorded leaf count, so a
+    /// truncated or mismatched file is rejected rather than loaded. Lengths
+    /// are not trusted for allocation: a prefix larger than the remaining
+    /// input fails once the input runs out. Every level above the leaves is
+    /// rehashed from the level below, so a tampered node is rejected too;
+    /// leaf hashes, keyed or not, are taken as stored.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, MerkleError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != TREE_MAGIC {
+            return Err(MerkleError::InvalidEncoding("bad magic bytes".to_string()));
+        }
+
+        let version = read_u32(&mut reader)?;
+        if version == 0 || version > TREE_FORMAT_VERSION {
+            return Err(MerkleError::InvalidEncoding(format!(
+                "unsupported format version {}",
+                version
+            )));
+        }
+
+        let num_leaves = read_len(&mut reader)?;
+        let depth = read_len(&mut reader)?;
+        if num_leaves == 0 {
+            return Err(MerkleError::EmptyLeaves);
+        }
+        if depth != Self::calculate_depth(num_leaves) {
+            return Err(MerkleError::InvalidEncoding(format!(
+                "depth {} does not match {} leaves",
+                depth, num_leaves
+            )));
+        }
+
+        // Version 1 trees predate keyed mode and are always unkeyed
+        let key = if version >= 2 {
+            let mut flag = [0u8; 1];
+            reader.read_exact(&mut flag)?;
+            match flag[0] {
+                0 => None,
+                1 => {
+                    let len = read_u32(&mut reader)? as u64;
+                    let mut key = Vec::new();
+                    reader.by_ref().take(len).read_to_end(&mut key)?;
+                    if key.len() as u64 != len {
+                        return Err(MerkleError::InvalidEncoding(format!(
+                            "key of {} bytes truncated to {}",
+                            len,
+                            key.len()
+                        )));
+                    }
+                    Some(key)
+                }
+                other => {
+                    return Err(MerkleError::InvalidEncoding(format!(
+                        "invalid key flag {}",
+                        other
+                    )))
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut nodes: Vec<Vec<MerkleNode>> = Vec::with_capacity(depth + 1);
+        let mut expected_len = num_leaves;
+
+        for level in 0..=depth {
+            let len = read_len(&mut reader)?;
+            if len != expected_len {
+                return Err(MerkleError::InvalidEncoding(format!(
+                    "level {} has {} nodes, expected {}",
+                    level, len, expected_len
+                )));
+            }
+
+            let mut level_nodes = Vec::with_capacity(len.min(MAX_RESERVED_NODES));
+            for index in 0..len {
+                let mut hash = [0u8; 32];
+                reader.read_exact(&mut hash)?;
+                level_nodes.push(MerkleNode::new(hash, level, index));
+            }
+
+            if level > 0 {
+                let rehashed = Self::build_level(&nodes[level - 1], level)?;
+                if let Some(index) = (0..len).find(|&index| rehashed[index].hash != level_nodes[index].hash) {
+                    return Err(MerkleError::InvalidEncoding(format!(
+                        "node {} of level {} does not hash its children",
+                        index, level
+                    )));
+                }
+            }
+
+            nodes.push(level_nodes);
+            expected_len = (expected_len + 1) / 2;
+        }
+
+        let root = nodes[depth][0].clone();
+
+        Ok(Self {
+            root,
+            depth,
+            num_leaves,
+            nodes,
+            key,
+        })
+    }
+}
+
+/// Read a little-endian u32 from a reader
+#[cfg(feature = "std")]
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, MerkleError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Read a little-endian u64 from a reader
+#[cfg(feature = "std")]
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, MerkleError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Read a little-endian u64 length that must fit in a `usize`
+#[cfg(feature = "std")]
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, MerkleError> {
+    let len = read_u64(reader)?;
+    usize::try_from(len).map_err(|_| MerkleError::InvalidEncoding(format!("length {} does not fit in usize", len)))
 }
 
 impl Display for MerkleTree {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "MerkleTree(depth={}, leaves={}, root={:02x?})",
@@ -262,6 +607,154 @@ impl Display for MerkleTree {
     }
 }
 
+/// Domain-separation tag for the combined root of a Merkle forest
+const FOREST_TAG: &[u8] = b"xfg-stark/merkle-forest";
+
+/// Merkle forest binding several keyed trees under one combined root
+///
+/// Each column group (trace, composition, FRI layers) is committed in its own
+/// keyed tree. The trees are kept ordered by [`CommitmentRole`], and the
+/// combined root hashes every `(role, root)` pair in that order, so the single
+/// top hash commits to all groups regardless of insertion order.
+#[derive(Debug, Clone)]
+pub struct MerkleForest {
+    /// Trees ordered by role
+    trees: Vec<(CommitmentRole, MerkleTree)>,
+}
+
+impl MerkleForest {
+    /// Create an empty forest
+    pub fn new() -> Self {
+        Self { trees: Vec::new() }
+    }
+
+    /// Commit to several column groups at once
+    pub fn commit_groups(groups: &[(CommitmentRole, Vec<Vec<u8>>)]) -> Result<Self, MerkleError> {
+        let mut forest = Self::new();
+        for (role, leaves) in groups {
+            forest.commit(*role, leaves)?;
+        }
+        Ok(forest)
+    }
+
+    /// Commit to one column group under the given role
+    pub fn commit(&mut self, role: CommitmentRole, leaves: &[Vec<u8>]) -> Result<&MerkleTree, MerkleError> {
+        let tree = MerkleTree::for_role(leaves, role)?;
+        self.insert(role, tree)
+    }
+
+    /// Insert an already-built tree under the given role
+    ///
+    /// The tree must have been built with the role's key, and each role may
+    /// appear only once in a forest.
+    pub fn insert(&mut self, role: CommitmentRole, tree: MerkleTree) -> Result<&MerkleTree, MerkleError> {
+        if tree.key() != Some(role.key().as_slice()) {
+            return Err(MerkleError::ConstructionError(format!(
+                "tree is not keyed for role {}",
+                role
+            )));
+        }
+
+        let position = match self.trees.binary_search_by(|(r, _)| r.cmp(&role)) {
+            Ok(_) => {
+                return Err(MerkleError::ConstructionError(format!(
+                    "role {} already committed",
+                    role
+                )))
+            }
+            Err(position) => position,
+        };
+
+        self.trees.insert(position, (role, tree));
+        Ok(&self.trees[position].1)
+    }
+
+    /// Get the tree committed under a role
+    pub fn tree(&self, role: CommitmentRole) -> Option<&MerkleTree> {
+        self.trees
+            .binary_search_by(|(r, _)| r.cmp(&role))
+            .ok()
+            .map(|position| &self.trees[position].1)
+    }
+
+    /// Per-role roots in canonical order
+    pub fn roots(&self) -> Vec<(CommitmentRole, [u8; 32])> {
+        self.trees
+            .iter()
+            .map(|(role, tree)| (*role, tree.root_hash()))
+            .collect()
+    }
+
+    /// Combined root binding every tree in the forest
+    pub fn root(&self) -> [u8; 32] {
+        Self::combine_roots(&self.roots())
+    }
+
+    /// Number of trees in the forest
+    pub fn len(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Check if the forest has no trees
+    pub fn is_empty(&self) -> bool {
+        self.trees.is_empty()
+    }
+
+    /// Compute the combined root from per-role roots
+    ///
+    /// The roots are sorted by role before hashing, so verifiers can recompute
+    /// the combined root from the roots carried in a proof.
+    pub fn combine_roots(roots: &[(CommitmentRole, [u8; 32])]) -> [u8; 32] {
+        let mut ordered = roots.to_vec();
+        ordered.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut hasher = Sha256::new();
+        hasher.update(FOREST_TAG);
+        hasher.update((ordered.len() as u64).to_le_bytes());
+        for (role, root) in &ordered {
+            let key = role.key();
+            hasher.update((key.len() as u64).to_le_bytes());
+            hasher.update(&key);
+            hasher.update(root);
+        }
+        hasher.finalize().into()
+    }
+
+    /// Verify an opening of a leaf in one of the forest's trees
+    ///
+    /// Checks that `roots` combine to `forest_root` and that the proof opens
+    /// `leaf_data` under the root recorded for `role`.
+    pub fn verify_opening(
+        forest_root: [u8; 32],
+        roots: &[(CommitmentRole, [u8; 32])],
+        role: CommitmentRole,
+        leaf_data: &[u8],
+        proof: &MerkleProof,
+    ) -> bool {
+        if Self::combine_roots(roots) != forest_root {
+            return false;
+        }
+
+        match roots.iter().find(|(r, _)| *r == role) {
+            Some((_, root)) => proof.verify_keyed(&role.key(), leaf_data, *root),
+            None => false,
+        }
+    }
+}
+
+impl Default for MerkleForest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for MerkleForest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let root = self.root();
+        write!(f, "MerkleForest(trees={}, root={:02x?})", self.trees.len(), &root[..8])
+    }
+}
+
 /// Merkle inclusion proof
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MerkleProof {
@@ -283,6 +776,22 @@ impl MerkleProof {
         }
     }
 
+    /// Rebuild a proof from the sibling hashes along the path of a leaf
+    ///
+    /// Path directions follow from the bits of the leaf index, matching the
+    /// proofs produced by [`MerkleTree::generate_proof`].
+    pub fn from_siblings(leaf_index: usize, siblings: Vec<[u8; 32]>) -> Self {
+        let path = (0..siblings.len())
+            .map(|level| (leaf_index >> level) % 2 == 0)
+            .collect();
+
+        Self {
+            leaf_index,
+            siblings,
+            path,
+        }
+    }
+
     /// Add a sibling hash
     pub fn add_sibling(&mut self, sibling_hash: [u8; 32], is_left: bool) {
         self.siblings.push(sibling_hash);
@@ -291,7 +800,17 @@ impl MerkleProof {
 
     /// Verify proof against a root hash
     pub fn verify(&self, leaf_data: &[u8], root_hash: [u8; 32]) -> bool {
-        let mut current_hash = MerkleNode::hash_data(leaf_data);
+        self.verify_with_key(None, leaf_data, root_hash)
+    }
+
+    /// Verify proof against the root of a keyed tree
+    pub fn verify_keyed(&self, key: &[u8], leaf_data: &[u8], root_hash: [u8; 32]) -> bool {
+        self.verify_with_key(Some(key), leaf_data, root_hash)
+    }
+
+    /// Verify proof, hashing the leaf with the optional commitment key
+    fn verify_with_key(&self, key: Option<&[u8]>, leaf_data: &[u8], root_hash: [u8; 32]) -> bool {
+        let mut current_hash = MerkleNode::hash_leaf(key, leaf_data);
         
         for (i, &is_left) in self.path.iter().enumerate() {
             if i >= self.siblings.len() {
@@ -317,12 +836,12 @@ impl MerkleProof {
 
     /// Get proof size in bytes
     pub fn size(&self) -> usize {
-        self.siblings.len() * 32 + self.path.len() + std::mem::size_of::<usize>()
+        self.siblings.len() * 32 + self.path.len() + core::mem::size_of::<usize>()
     }
 }
 
 impl Display for MerkleProof {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "MerkleProof(leaf={}, siblings={}, path_len={})",
@@ -343,7 +862,7 @@ pub struct MerkleStats {
 }
 
 impl Display for MerkleStats {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "MerkleStats(depth={}, leaves={}, nodes={})",
@@ -374,6 +893,15 @@ pub enum MerkleError {
     /// Tree construction error
     #[error("Tree construction error: {0}")]
     ConstructionError(String),
+
+    /// Malformed serialized tree
+    #[error("Invalid tree encoding: {0}")]
+    InvalidEncoding(String),
+
+    /// I/O error while reading or writing a tree
+    #[cfg(feature = "std")]
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// Generate Merkle commitment for field elements
@@ -454,6 +982,21 @@ mod tests {
         assert!(result.unwrap());
     }
 
+    #[test]
+    fn test_merkle_proof_for_odd_level() {
+        let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i]).collect();
+        let tree = MerkleTree::new(&leaves).unwrap();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.generate_proof(index).unwrap();
+            assert_eq!(proof.siblings.len(), tree.depth);
+            assert!(tree.verify_proof(leaf, &proof).unwrap());
+
+            let rebuilt = MerkleProof::from_siblings(index, proof.siblings.clone());
+            assert_eq!(rebuilt, proof);
+        }
+    }
+
     #[test]
     fn test_field_element_commitment() {
         let elements = vec![
@@ -482,4 +1025,186 @@ mod tests {
         assert!(proofs.is_ok());
         assert_eq!(proofs.unwrap().len(), 2);
     }
+
+    #[test]
+    fn test_merkle_tree_persistence_roundtrip() {
+        let leaves = vec![
+            b"leaf1".to_vec(),
+            b"leaf2".to_vec(),
+            b"leaf3".to_vec(),
+            b"leaf4".to_vec(),
+            b"leaf5".to_vec(),
+        ];
+
+        let tree = MerkleTree::new(&leaves).unwrap();
+        let mut buffer = Vec::new();
+        tree.write_to(&mut buffer).unwrap();
+
+        let loaded = MerkleTree::read_from(buffer.as_slice()).unwrap();
+        assert_eq!(loaded.root_hash(), tree.root_hash());
+        assert_eq!(loaded.depth, tree.depth);
+        assert_eq!(loaded.num_leaves, tree.num_leaves);
+
+        // Openings answered by the reloaded tree verify against the original root
+        let proof = loaded.generate_proof(2).unwrap();
+        assert_eq!(proof, tree.generate_proof(2).unwrap());
+        assert!(proof.verify(b"leaf3", tree.root_hash()));
+    }
+
+    #[test]
+    fn test_merkle_tree_read_rejects_truncated_input() {
+        let leaves = vec![b"leaf1".to_vec(), b"leaf2".to_vec()];
+        let tree = MerkleTree::new(&leaves).unwrap();
+
+        let mut buffer = Vec::new();
+        tree.write_to(&mut buffer).unwrap();
+        buffer.truncate(buffer.len() - 1);
+
+        assert!(MerkleTree::read_from(buffer.as_slice()).is_err());
+        assert!(MerkleTree::read_from(&b"NOPE"[..]).is_err());
+    }
+
+    #[test]
+    fn test_merkle_tree_read_rejects_tampered_nodes() {
+        let leaves: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 8]).collect();
+        let tree = MerkleTree::new_keyed(&leaves, b"key").unwrap();
+        let mut buffer = Vec::new();
+        tree.write_to(&mut buffer).unwrap();
+
+        // Flip a bit of the first leaf hash, then of the root
+        let leaf_offset = TREE_MAGIC.len() + 4 + 8 + 8 + 1 + 4 + 3 + 8;
+        for offset in [leaf_offset, buffer.len() - 1] {
+            let mut tampered = buffer.clone();
+            tampered[offset] ^= 1;
+            assert!(matches!(
+                MerkleTree::read_from(tampered.as_slice()),
+                Err(MerkleError::InvalidEncoding(_))
+            ));
+        }
+        assert_eq!(MerkleTree::read_from(buffer.as_slice()).unwrap().root_hash(), tree.root_hash());
+    }
+
+    #[test]
+    fn test_merkle_tree_read_rejects_oversized_lengths() {
+        let header = |num_leaves: u64, depth: u64| {
+            let mut bytes = TREE_MAGIC.to_vec();
+            bytes.extend_from_slice(&TREE_FORMAT_VERSION.to_le_bytes());
+            bytes.extend_from_slice(&num_leaves.to_le_bytes());
+            bytes.extend_from_slice(&depth.to_le_bytes());
+            bytes
+        };
+
+        // A key length prefix far beyond the remaining input
+        let mut buffer = header(1, 0);
+        buffer.push(1);
+        buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+        buffer.extend_from_slice(b"short key");
+        assert!(matches!(
+            MerkleTree::read_from(buffer.as_slice()),
+            Err(MerkleError::InvalidEncoding(_))
+        ));
+
+        // A leaf count of 2^40 whose hashes are missing
+        let mut buffer = header(1 << 40, 40);
+        buffer.push(0);
+        buffer.extend_from_slice(&(1u64 << 40).to_le_bytes());
+        buffer.extend_from_slice(&[7u8; 64]);
+        assert!(matches!(
+            MerkleTree::read_from(buffer.as_slice()),
+            Err(MerkleError::IoError(_))
+        ));
+    }
+
+    #[test]
+    fn test_keyed_trees_are_domain_separated() {
+        let leaves = vec![
+            b"leaf1".to_vec(),
+            b"leaf2".to_vec(),
+            b"leaf3".to_vec(),
+            b"leaf4".to_vec(),
+        ];
+
+        let plain = MerkleTree::new(&leaves).unwrap();
+        let trace = MerkleTree::for_role(&leaves, CommitmentRole::Trace).unwrap();
+        let composition = MerkleTree::for_role(&leaves, CommitmentRole::Composition).unwrap();
+        let fri_0 = MerkleTree::for_role(&leaves, CommitmentRole::FriLayer(0)).unwrap();
+        let fri_1 = MerkleTree::for_role(&leaves, CommitmentRole::FriLayer(1)).unwrap();
+
+        let roots = [
+            plain.root_hash(),
+            trace.root_hash(),
+            composition.root_hash(),
+            fri_0.root_hash(),
+            fri_1.root_hash(),
+        ];
+        for i in 0..roots.len() {
+            for j in (i + 1)..roots.len() {
+                assert_ne!(roots[i], roots[j]);
+            }
+        }
+
+        // A keyed opening only verifies under the matching key
+        let proof = trace.generate_proof(1).unwrap();
+        assert!(trace.verify_proof(b"leaf2", &proof).unwrap());
+        assert!(proof.verify_keyed(&CommitmentRole::Trace.key(), b"leaf2", trace.root_hash()));
+        assert!(!proof.verify_keyed(&CommitmentRole::Composition.key(), b"leaf2", trace.root_hash()));
+        assert!(!proof.verify(b"leaf2", trace.root_hash()));
+    }
+
+    #[test]
+    fn test_merkle_forest_combined_root() {
+        let trace_leaves = vec![b"t0".to_vec(), b"t1".to_vec(), b"t2".to_vec(), b"t3".to_vec()];
+        let composition_leaves = vec![b"c0".to_vec(), b"c1".to_vec()];
+
+        let forest = MerkleForest::commit_groups(&[
+            (CommitmentRole::Trace, trace_leaves.clone()),
+            (CommitmentRole::Composition, composition_leaves.clone()),
+        ])
+        .unwrap();
+
+        // Insertion order does not change the combined root
+        let reordered = MerkleForest::commit_groups(&[
+            (CommitmentRole::Composition, composition_leaves),
+            (CommitmentRole::Trace, trace_leaves),
+        ])
+        .unwrap();
+        assert_eq!(forest.root(), reordered.root());
+        assert_eq!(forest.len(), 2);
+
+        let roots = forest.roots();
+        let proof = forest.tree(CommitmentRole::Trace).unwrap().generate_proof(3).unwrap();
+        assert!(MerkleForest::verify_opening(forest.root(), &roots, CommitmentRole::Trace, b"t3", &proof));
+        assert!(!MerkleForest::verify_opening(forest.root(), &roots, CommitmentRole::Composition, b"t3", &proof));
+
+        // Tampering with any group root breaks the binding
+        let mut tampered = roots.clone();
+        tampered[0].1[0] ^= 1;
+        assert!(!MerkleForest::verify_opening(forest.root(), &tampered, CommitmentRole::Trace, b"t3", &proof));
+    }
+
+    #[test]
+    fn test_merkle_forest_rejects_duplicate_roles() {
+        let leaves = vec![b"leaf1".to_vec(), b"leaf2".to_vec()];
+        let mut forest = MerkleForest::new();
+
+        assert!(forest.commit(CommitmentRole::FriLayer(0), &leaves).is_ok());
+        assert!(forest.commit(CommitmentRole::FriLayer(0), &leaves).is_err());
+
+        let unkeyed = MerkleTree::new(&leaves).unwrap();
+        assert!(forest.insert(CommitmentRole::FriLayer(1), unkeyed).is_err());
+    }
+
+    #[test]
+    fn test_keyed_tree_persistence_keeps_key() {
+        let leaves = vec![b"leaf1".to_vec(), b"leaf2".to_vec(), b"leaf3".to_vec()];
+        let tree = MerkleTree::for_role(&leaves, CommitmentRole::Composition).unwrap();
+
+        let mut buffer = Vec::new();
+        tree.write_to(&mut buffer).unwrap();
+        let loaded = MerkleTree::read_from(buffer.as_slice()).unwrap();
+
+        assert_eq!(loaded.key(), tree.key());
+        let proof = loaded.generate_proof(0).unwrap();
+        assert!(loaded.verify_proof(b"leaf1", &proof).unwrap());
+    }
 }
\ No newline at end of file
@@ -9,15 +9,78 @@
 //! - **Proof Generation**: Complete STARK proof generation pipeline
 //! - **Proof Verification**: Cryptographic verification of STARK proofs
 //! - **Trace Generation**: Execution trace creation from AIR
-//! - **Constraint Evaluation**: Polynomial constraint evaluation
-//! - **Commitment Generation**: Merkle tree commitments for proof components
+//! - **Constraint Composition**: Constraint quotients combined into a single composition polynomial
+//! - **DEEP Sampling**: Out-of-domain evaluation frame checked against the constraints
+//! - **Commitment Generation**: Keyed Merkle commitments to the trace and composition LDEs, opened at transcript-drawn positions
+//! - **Fiat–Shamir**: All verifier challenges are derived from a shared [`transcript::Transcript`]
+//! - **Public Inputs**: Statement values bound to the transcript and enforced as boundary constraints (see [`public_inputs`])
+//! - **Auxiliary Segments**: Trace columns built from transcript challenges after the main trace is committed (see [`crate::air::auxiliary`])
+//! - **Zero Knowledge**: Optional trace padding, FRI masking and salted leaves (see [`zk`])
+//! - **Memory Budget**: Chunked LDE evaluation and hashing within a configurable budget, spilling to disk with `mmap` (see [`memory`])
+//! - **CPU Dispatch**: Dispatch table of the field, NTT and hashing kernels, selected at runtime (see [`dispatch`])
+//! - **Accelerators**: Hook offloading LDE and leaf hashing to a caller's device with CPU fallback, with `gpu` (see `device`)
+//! - **Extension Challenges**: Composition and FRI folding challenges drawn from a degree 2 or 3 field extension
+//! - **Proof Grinding**: Optional proof-of-work nonce over the finished transcript, checked with a single hash
+//! - **Progress Observation**: Stage and progress events for long-running proofs (see [`observer`])
+//! - **Tracing**: Spans with sizes and timings for every prover and verifier stage, with `tracing`
+//! - **Detached Proofs**: Proofs without their AIR, verified against an AIR the verifier supplies
+//! - **Preprocessing**: Witness-independent proving and verifying keys reused across proofs of an AIR (see [`preprocess`])
+//! - **Cancellation**: Explicit cancellation and deadlines checked between and within prover stages (see [`cancellation`])
+//! - **Verification Cost**: Hash, field operation and size budgets of a proof before verifying it (see [`cost`])
+//! - **Security Estimation**: Verifiers enforce a minimum security level estimated from the proof parameters (see [`soundness`])
+//! - **Parameter Pinning**: Verifiers accept only allow-listed proof parameters (see [`params`])
+//! - **Backends**: Native and Winterfell provers and verifiers behind one API (see [`backend`])
 
-use crate::types::{FieldElement, StarkComponent};
-use crate::types::stark::{StarkProof, ExecutionTrace, Air as StarkAir, MerkleCommitment, FriProof, ProofMetadata};
-use crate::air::Air;
-use crate::proof::fri::FriProver;
-use crate::proof::merkle::generate_commitment;
-use std::marker::PhantomData;
+use crate::prelude::*;
+use crate::types::{Digest, ExtensionElement, FieldElement, StarkComponent, TraceStorage};
+use crate::types::extension::split_coordinates;
+use crate::types::stark::{
+    StarkProof, DetachedProof, ExecutionTrace, Air as StarkAir, MerkleCommitment, ProofBody, ProofMetadata,
+    QueryOpening, SegmentOpening, FieldId, ProofParameters, NATIVE_HASH,
+};
+use crate::air::{Air, AuxSegment, FixedColumn};
+use crate::proof::aggregation::AggregatedProof;
+use crate::proof::cancellation::CancellationToken;
+use crate::proof::composition::{
+    evaluate_polynomial, lde_domain_point, min_blowup_factor, CompositionConstraints, CompositionDomain,
+    CompositionPolynomial, TraceLde,
+};
+use crate::proof::deep::{
+    absorb_ood_frame, build_ood_frame, deep_composition_polynomial, draw_ood_point, evaluate_deep,
+    num_deep_coefficients,
+};
+use crate::proof::fri::{FriProver, FriVerifier};
+#[cfg(feature = "gpu")]
+use crate::proof::device::{Accelerator, DeviceConfig, DeviceSelection};
+use crate::proof::dispatch::Kernels;
+use crate::proof::memory::{LdeShape, MemoryPlan, ProverOptions};
+use crate::proof::merkle::{CommitmentRole, MerkleProof, MerkleTree};
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::observer::{NoopObserver, ProverObserver, ProverStage, StageTracker};
+use crate::proof::params::ExpectedParams;
+use crate::proof::preprocess::{ProvingKey, VerifyingKey};
+use crate::proof::soundness::estimate_security_bits;
+use crate::proof::trace::{generate_fixed_trace, generate_linear_trace, DEFAULT_SEGMENT_LENGTH};
+use crate::proof::transcript::{labels, Transcript};
+use crate::proof::verification::VerificationError;
+use crate::proof::zk::{
+    evaluate_masking, mask_polynomial, masking_polynomial, num_random_rows, pad_columns, pad_trace, random_salt,
+    salted_leaf, seeded_rng,
+};
+#[cfg(feature = "std")]
+use crate::proof::zk::entropy_rng;
+use rand::Rng;
+use alloc::collections::BTreeMap;
+use core::marker::PhantomData;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// FRI folding factor used for the composition polynomial
+pub(crate) const FRI_FOLDING_FACTOR: usize = 4;
+
+/// Minimum security in bits required by [`StarkVerifier::default`]
+pub const DEFAULT_MIN_SECURITY_BITS: u32 = 48;
 
 /// STARK proof generator
 /// 
@@ -30,8 +93,23 @@ pub struct StarkProver<F: FieldElement> {
     blowup_factor: usize,
     /// Number of queries
     num_queries: usize,
-    /// Field extension degree
+    /// Degree of the field extension the composition and FRI challenges are drawn from
     field_extension_degree: u32,
+    /// Whether proofs hide the witness
+    zero_knowledge: bool,
+    /// Seed for all prover-side randomness, if proofs must be reproducible
+    seed: Option<[u8; 32]>,
+    /// Proof-of-work bits over the finished transcript
+    grinding_bits: u32,
+    /// Whether the witness is checked against the AIR before anything is committed
+    check_trace: bool,
+    /// Resource options
+    options: ProverOptions,
+    /// CPU kernels of the LDE and hashing stages
+    kernels: Kernels<F>,
+    /// Device running the LDE and hashing stages
+    #[cfg(feature = "gpu")]
+    device: DeviceConfig<F>,
     /// Phantom data for type parameter
     _phantom: PhantomData<F>,
 }
@@ -44,6 +122,14 @@ impl<F: FieldElement> StarkProver<F> {
             blowup_factor: 16,
             num_queries: 64,
             field_extension_degree: 1,
+            zero_knowledge: false,
+            seed: None,
+            grinding_bits: 0,
+            check_trace: true,
+            options: ProverOptions::new(),
+            kernels: Kernels::detected(),
+            #[cfg(feature = "gpu")]
+            device: DeviceConfig::new(),
             _phantom: PhantomData,
         }
     }
@@ -60,154 +146,807 @@ impl<F: FieldElement> StarkProver<F> {
             blowup_factor,
             num_queries,
             field_extension_degree,
+            zero_knowledge: false,
+            seed: None,
+            grinding_bits: 0,
+            check_trace: true,
+            options: ProverOptions::new(),
+            kernels: Kernels::detected(),
+            #[cfg(feature = "gpu")]
+            device: DeviceConfig::new(),
             _phantom: PhantomData,
         }
     }
 
+    /// Enable or disable zero-knowledge mode
+    ///
+    /// Zero-knowledge proofs pad the trace with random rows, mask the FRI
+    /// input and salt every Merkle leaf; the trace columns are withheld from
+    /// the proof.
+    pub fn with_zk(mut self, enabled: bool) -> Self {
+        self.zero_knowledge = enabled;
+        self
+    }
+
+    /// Derive all prover-side randomness from a seed
+    ///
+    /// Masks and salts are drawn from a generator seeded with `seed` and the
+    /// execution trace, and the metadata timestamp is fixed to zero, so
+    /// identical inputs yield byte-identical proofs.
+    pub fn deterministic(mut self, seed: [u8; 32]) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Grind a proof-of-work nonce over the finished transcript
+    ///
+    /// Every proof then costs about `2^bits` additional hashes to generate
+    /// and one to verify, which lets integrators rate-limit proof submission.
+    /// This is independent of the proof's soundness. `bits` is capped at
+    /// [`MAX_GRINDING_BITS`](transcript::MAX_GRINDING_BITS).
+    pub fn with_proof_grinding(mut self, bits: u32) -> Self {
+        self.grinding_bits = bits.min(transcript::MAX_GRINDING_BITS);
+        self
+    }
+
+    /// Enable or disable checking the witness against the AIR before proving
+    ///
+    /// The check is enabled by default and fails with the first violated
+    /// constraint (see [`Air::check_trace`]). Without it a witness that
+    /// violates the AIR yields a proof that does not verify.
+    pub fn with_trace_check(mut self, enabled: bool) -> Self {
+        self.check_trace = enabled;
+        self
+    }
+
+    /// Set the resource options
+    ///
+    /// With a memory budget the LDE is evaluated and committed in chunks that
+    /// fit the budget (see [`memory`]); proofs are identical to those generated
+    /// without one.
+    pub fn with_options(mut self, options: ProverOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Run the LDE and hashing stages with the CPU kernels of a given level
+    ///
+    /// Provers use the widest level the CPU supports by default; proofs must
+    /// be identical at every level (see [`dispatch`]).
+    pub fn with_kernels(mut self, kernels: Kernels<F>) -> Self {
+        self.kernels = kernels;
+        self
+    }
+
+    /// Choose the device running the LDE and hashing stages
+    ///
+    /// Proofs are identical on every device; see `device`.
+    #[cfg(feature = "gpu")]
+    pub fn with_device(mut self, selection: DeviceSelection) -> Self {
+        self.device = self.device.with_selection(selection);
+        self
+    }
+
+    /// Offload the LDE and hashing stages to an accelerator
+    ///
+    /// The crate provides no accelerator implementation; see `device`.
+    #[cfg(feature = "gpu")]
+    pub fn with_accelerator(mut self, accelerator: alloc::sync::Arc<dyn Accelerator<F>>) -> Self {
+        self.device = self.device.with_accelerator(accelerator);
+        self
+    }
+
+    /// Blowup factor of the LDE domain
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    /// Conjectured security of the proofs this prover generates, in bits
+    ///
+    /// Estimated from the blowup factor, query count and field size (see
+    /// [`soundness`]); it can be lower than the configured security parameter.
+    pub fn estimated_security_bits(&self) -> u32 {
+        estimate_security_bits(F::MODULUS, self.field_extension_degree, self.blowup_factor, self.num_queries)
+    }
+
     /// Generate a complete STARK proof
-    pub fn prove(
+    ///
+    /// Proves that `witness` satisfies the AIR together with the boundary
+    /// constraints asserted by `public_inputs`. The public inputs are bound to
+    /// the transcript, so the proof verifies only against the same inputs.
+    ///
+    /// The witness is an [`ExecutionTrace`] or a [`SecretWitness`](crate::types::SecretWitness), which
+    /// overwrites its values with zero once it is dropped.
+    pub fn prove<W: AsRef<ExecutionTrace<F>> + ?Sized>(
         &self,
         air: &Air<F>,
-        initial_state: &[F],
-        num_steps: usize,
+        public_inputs: &PublicInputs<F>,
+        witness: &W,
+    ) -> Result<StarkProof<F>, ProofError> {
+        self.prove_with_observer(air, public_inputs, witness, &NoopObserver)
+    }
+
+    /// Generate a proof, reporting stage and progress events to `observer`
+    pub fn prove_with_observer<W: AsRef<ExecutionTrace<F>> + ?Sized>(
+        &self,
+        air: &Air<F>,
+        public_inputs: &PublicInputs<F>,
+        witness: &W,
+        observer: &dyn ProverObserver,
     ) -> Result<StarkProof<F>, ProofError> {
-        // Step 1: Generate execution trace
-        let trace = self.generate_trace(air, initial_state, num_steps)?;
+        self.prove_observed(air, None, public_inputs, witness.as_ref(), observer, &CancellationToken::new())
+    }
 
-        // Step 2: Generate constraint polynomials
-        let constraint_polynomials = self.generate_constraint_polynomials(air, &trace)?;
+    /// Generate a proof with a key from [`preprocess`](Self::preprocess)
+    ///
+    /// Produces the same proof as [`prove`](Self::prove) with the key's AIR,
+    /// without re-deriving the witness-independent data.
+    pub fn prove_with_key<W: AsRef<ExecutionTrace<F>> + ?Sized>(
+        &self,
+        key: &ProvingKey<F>,
+        public_inputs: &PublicInputs<F>,
+        witness: &W,
+    ) -> Result<StarkProof<F>, ProofError> {
+        let cancellation = CancellationToken::new();
+        self.prove_observed(key.air(), Some(key), public_inputs, witness.as_ref(), &NoopObserver, &cancellation)
+    }
 
-        // Step 3: Generate FRI proof
-        let fri_prover = FriProver::new(self.security_parameter);
-        let fri_proof = fri_prover.prove(&constraint_polynomials[0])?;
+    /// Generate a proof that stops with [`ProofError::Cancelled`] once `cancellation` is cancelled
+    ///
+    /// The token is checked between stages and inside the aux segment, query
+    /// and grinding loops, so a deadline set on it bounds the proving time up
+    /// to the length of one step.
+    pub fn prove_with_cancellation<W: AsRef<ExecutionTrace<F>> + ?Sized>(
+        &self,
+        air: &Air<F>,
+        public_inputs: &PublicInputs<F>,
+        witness: &W,
+        cancellation: &CancellationToken,
+    ) -> Result<StarkProof<F>, ProofError> {
+        self.prove_observed(air, None, public_inputs, witness.as_ref(), &NoopObserver, cancellation)
+    }
 
-        // Step 4: Generate commitments
-        let commitments = self.generate_commitments(&trace, &constraint_polynomials)?;
+    /// Generate a proof on the blocking thread pool of the current tokio runtime
+    ///
+    /// Cancellation is cooperative: dropping the returned future cancels a
+    /// token that the prover checks between stages, so the blocking thread
+    /// stops at the next cancellation point instead of running the proof to
+    /// completion.
+    #[cfg(feature = "async")]
+    pub async fn prove_async<W>(
+        &self,
+        air: Air<F>,
+        public_inputs: PublicInputs<F>,
+        witness: W,
+    ) -> Result<StarkProof<F>, ProofError>
+    where
+        F: 'static,
+        W: AsRef<ExecutionTrace<F>> + Send + 'static,
+    {
+        let prover = self.clone();
+        let cancellation = CancellationToken::new();
+        let _guard = CancelOnDrop(cancellation.clone());
 
-        // Step 5: Create proof metadata
-        let metadata = self.create_proof_metadata(air, &trace)?;
+        let task = tokio::task::spawn_blocking(move || {
+            prover.prove_observed(&air, None, &public_inputs, witness.as_ref(), &NoopObserver, &cancellation)
+        });
+        match task.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(_) => Err(ProofError::Cancelled),
+        }
+    }
 
-        // Step 6: Construct final proof
-        // Convert AIR to the expected type for StarkProof
-        let air_stark = StarkAir {
-            constraints: vec![], // Convert air constraints to stark constraints
-            transition: crate::types::stark::TransitionFunction {
-                coefficients: vec![],
-                degree: air.transition.degree(),
-            },
-            boundary: crate::types::stark::BoundaryConditions {
-                constraints: vec![],
-            },
-            security_parameter: air.security_parameter,
+    /// Generate a proof, notifying `observer` of every stage
+    ///
+    /// The AIR is preprocessed for this proof unless a key for it is given.
+    /// Stops at the next cancellation point once `cancellation` is cancelled.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "prove",
+            skip_all,
+            fields(
+                trace_length = trace.length,
+                registers = trace.num_registers,
+                blowup = self.blowup_factor,
+                queries = self.num_queries,
+                zk = self.zero_knowledge,
+                lde_domain_size = tracing::field::Empty,
+            )
+        )
+    )]
+    fn prove_observed(
+        &self,
+        air: &Air<F>,
+        key: Option<&ProvingKey<F>>,
+        public_inputs: &PublicInputs<F>,
+        trace: &ExecutionTrace<F>,
+        observer: &dyn ProverObserver,
+        cancellation: &CancellationToken,
+    ) -> Result<StarkProof<F>, ProofError> {
+        let mut stages = StageTracker::new(observer, cancellation);
+        let extension_degree = self.extension_degree()?;
+        let public_inputs = &air
+            .bind_public_inputs(public_inputs)
+            .map_err(|error| ProofError::InvalidAir(error.to_string()))?;
+
+        // Step 1: Check the witness against the AIR (and the public inputs
+        // once the auxiliary segments are built)
+        stages.begin(ProverStage::Trace)?;
+        let air_constraints = match key {
+            Some(key) => {
+                self.check_key(key, trace.length)?;
+                key.verifying_key().constraints().clone()
+            }
+            None => CompositionConstraints::from_air(air, trace.length)?,
         };
+        if self.check_trace {
+            if air.aux_segments.is_empty() {
+                air_constraints.clone().with_public_inputs(public_inputs)?.check_trace(trace)?;
+            } else {
+                air_constraints.check_trace(trace)?;
+            }
+        }
 
+        // Step 2: Interpolate the trace, padded with random rows in zero-knowledge mode
+        stages.begin(ProverStage::Interpolation)?;
+        let preprocessed;
+        let key = match key {
+            Some(key) => key,
+            None => {
+                preprocessed = self.preprocess(air, trace.length)?;
+                &preprocessed
+            }
+        };
+        let mut rng = match &self.seed {
+            Some(seed) => seeded_rng(seed, trace),
+            #[cfg(feature = "std")]
+            None => entropy_rng(),
+            #[cfg(not(feature = "std"))]
+            None => return Err(ProofError::MissingEntropy),
+        };
+        let recorded_air = Self::to_stark_air(air, &air_constraints);
+        let mut transcript =
+            self.initialize_transcript(key.verifying_key().air_digest(), &recorded_air, trace, public_inputs);
+        let lde_trace_length = key.lde_trace_length();
+        let shape = LdeShape {
+            trace_length: lde_trace_length,
+            width: trace.num_registers + air.aux_width(),
+            num_segments: 1 + air.aux_segments.len(),
+            domain_size: lde_trace_length
+                .checked_mul(self.blowup_factor)
+                .ok_or(ProofError::InvalidTrace)?,
+            extension_degree,
+        };
+        let plan = MemoryPlan::new::<F>(&self.options, &shape)?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("lde_domain_size", shape.domain_size);
+        let polynomials = if self.zero_knowledge {
+            let fixed: Vec<usize> = air.fixed_columns.iter().map(FixedColumn::register).collect();
+            let padded = pad_trace(trace, num_random_rows(self.num_queries), &fixed, &mut rng);
+            TraceLde::interpolate_over(key.trace_domain(), &padded)?
+        } else {
+            TraceLde::interpolate_over(key.trace_domain(), trace)?
+        };
+
+        // Step 3: Extend the trace polynomials over the LDE domain
+        stages.begin(ProverStage::Lde)?;
+        let mut trace_lde = self.extend_polynomials(polynomials, lde_trace_length, &plan)?;
+
+        // Step 4: Commit to the trace LDE and bind it to the transcript, then
+        // build and commit to every auxiliary segment from fresh challenges
+        stages.begin(ProverStage::Commitments)?;
+        let trace_salts = self.leaf_salts(trace_lde.domain_size(), &mut rng);
+        let trace_tree =
+            self.generate_commitments(&trace_lde, trace_salts.as_deref(), CommitmentRole::Trace, &plan)?;
+        transcript.absorb_commitment(labels::TRACE_COMMITMENT, &trace_tree.root_hash());
+
+        let mut constraints = air_constraints.clone();
+        let mut aux_columns = Vec::with_capacity(air.aux_segments.len());
+        let mut aux_commitments = Vec::with_capacity(air.aux_segments.len());
+        for (index, segment) in air.aux_segments.iter().enumerate() {
+            stages.check()?;
+            let definition = segment.definition();
+            let challenges = transcript.challenge_field_elements(labels::AUX_CHALLENGES, definition.num_challenges());
+            let columns = definition.build(&trace.columns.as_columns(), &challenges);
+            if columns.len() != definition.width() {
+                return Err(ProofError::InvalidTrace);
+            }
+            let polynomials = if self.zero_knowledge {
+                let padded = pad_columns(&columns, num_random_rows(self.num_queries), &mut rng);
+                TraceLde::interpolate_columns_over(key.trace_domain(), &padded)?
+            } else {
+                TraceLde::interpolate_columns_over(key.trace_domain(), &columns)?
+            };
+            let segment_lde = self.extend_polynomials(polynomials, lde_trace_length, &plan)?;
+            let salts = self.leaf_salts(segment_lde.domain_size(), &mut rng);
+            let role = CommitmentRole::AuxTrace(index);
+            let tree = self.generate_commitments(&segment_lde, salts.as_deref(), role, &plan)?;
+            transcript.absorb_commitment(labels::AUX_COMMITMENT, &tree.root_hash());
+
+            trace_lde.append(segment_lde)?;
+            constraints = constraints.with_aux_segment(segment, challenges)?;
+            aux_columns.push(columns);
+            aux_commitments.push((tree, salts));
+        }
+        let constraints = constraints.with_public_inputs(public_inputs)?;
+        let trace = ExecutionTrace {
+            columns: trace.columns.clone(),
+            length: trace.length,
+            num_registers: trace.num_registers,
+            aux_segments: aux_columns,
+        };
+        if self.check_trace && !air.aux_segments.is_empty() {
+            constraints.check_trace(&trace)?;
+        }
+
+        // Step 5: Build and commit to the constraint composition polynomial
+        // (together with the FRI masking polynomial in zero-knowledge mode)
+        stages.begin(ProverStage::Composition)?;
+        let composition = self.generate_constraint_polynomials(
+            &constraints,
+            &trace_lde,
+            key.composition_domain(),
+            extension_degree,
+            &mut transcript,
+        )?;
+        let masking = self
+            .zero_knowledge
+            .then(|| masking_polynomial(trace_lde.trace_length().saturating_sub(1).max(1), &mut rng));
+        let masking_evaluations = masking.as_ref().map(|masking| {
+            let domain: Vec<F> = (0..trace_lde.domain_size())
+                .map(|index| lde_domain_point(trace_lde.trace_length(), index))
+                .collect();
+            evaluate_masking(masking, &domain)
+        });
+        let composition_salts = self.leaf_salts(trace_lde.domain_size(), &mut rng);
+        let composition_tree = self.commit_composition(
+            &composition,
+            masking_evaluations.as_deref(),
+            composition_salts.as_deref(),
+            &plan,
+        )?;
+        transcript.absorb_commitment(labels::COMPOSITION_COMMITMENT, &composition_tree.root_hash());
+
+        // Step 6: Evaluate the trace and composition out of domain
+        stages.begin(ProverStage::Fri)?;
+        let z = draw_ood_point(&mut transcript);
+        let ood_frame = build_ood_frame(&trace_lde, &composition, z, constraints.window());
+        absorb_ood_frame(&mut transcript, &ood_frame);
+
+        // Step 7: Generate FRI proof of the DEEP composition polynomial
+        let deep_coefficients = transcript.challenge_field_elements(
+            labels::DEEP_COEFFICIENTS,
+            num_deep_coefficients(constraints.num_registers(), extension_degree, constraints.window()),
+        );
+        let mut deep_polynomial =
+            deep_composition_polynomial(&trace_lde, &composition, z, constraints.window(), &deep_coefficients);
+        if let Some(masking) = &masking {
+            deep_polynomial = mask_polynomial(&deep_polynomial, masking);
+        }
+        let fri_prover = FriProver::with_params(
+            self.security_parameter,
+            self.blowup_factor,
+            self.num_queries,
+            FRI_FOLDING_FACTOR,
+        )
+        .with_extension_degree(extension_degree);
+        stages.check()?;
+        let fri_proof = fri_prover.prove_with_transcript(&deep_polynomial, &mut transcript)?;
+
+        // Step 8: Open the committed LDEs at positions drawn from the transcript
+        stages.begin(ProverStage::Queries)?;
+        let positions = transcript.challenge_indices(
+            labels::QUERY_POSITIONS,
+            self.num_queries,
+            trace_lde.domain_size(),
+        );
+        let openings = positions
+            .iter()
+            .enumerate()
+            .map(|(index, &position)| {
+                stages.check()?;
+                stages.progress(index + 1, positions.len());
+                let mut row = trace_lde.row(position);
+                let aux_segments = constraints
+                    .aux_segments()
+                    .iter()
+                    .zip(&aux_commitments)
+                    .map(|(aux, (tree, salts))| {
+                        Ok(SegmentOpening {
+                            row: row[aux.offset..aux.offset + aux.segment.definition().width()].to_vec(),
+                            path: tree.generate_proof(position)?.siblings,
+                            salt: salts.as_ref().map(|salts| salts[position]),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ProofError>>()?;
+                row.truncate(constraints.num_main_registers());
+                Ok(QueryOpening {
+                    position,
+                    trace_row: row,
+                    trace_path: trace_tree.generate_proof(position)?.siblings,
+                    trace_salt: trace_salts.as_ref().map(|salts| salts[position]),
+                    aux_segments,
+                    composition: composition.iter().map(|coordinate| coordinate.evaluations()[position]).collect(),
+                    masking: masking_evaluations.as_ref().map(|values| values[position]),
+                    composition_path: composition_tree.generate_proof(position)?.siblings,
+                    composition_salt: composition_salts.as_ref().map(|salts| salts[position]),
+                })
+            })
+            .collect::<Result<Vec<_>, ProofError>>()?;
+
+        // Step 9: Grind the proof-of-work nonce over the finished transcript
+        stages.begin(ProverStage::Grinding)?;
+        let grinding_nonce = transcript
+            .grind_until(self.grinding_bits, || stages.cancellation().is_cancelled())
+            .ok_or(ProofError::Cancelled)?;
+        stages.end();
+
+        // Step 10: Create proof metadata
+        let metadata = self.create_proof_metadata(key.verifying_key().air_digest(), grinding_nonce)?;
+
+        // Step 11: Construct final proof, withholding the witness in zero-knowledge mode
+        let trace = if self.zero_knowledge {
+            ExecutionTrace {
+                columns: TraceStorage::new(),
+                length: trace.length,
+                num_registers: trace.num_registers,
+                aux_segments: Vec::new(),
+            }
+        } else {
+            trace
+        };
+        let commitments = core::iter::once(&trace_tree)
+            .chain(aux_commitments.iter().map(|(tree, _)| tree))
+            .chain(core::iter::once(&composition_tree))
+            .map(Self::to_commitment)
+            .collect();
         let proof = StarkProof {
             trace,
-            air: air_stark,
+            air: recorded_air,
             commitments,
+            ood_frame,
             fri_proof,
+            openings,
             metadata,
+            body: ProofBody::Native,
         };
 
-        Ok(proof)
+        Ok(proof.with_encoded_size())
     }
 
-    /// Generate execution trace from AIR
-    fn generate_trace(
+    /// Record the instantiated constraints of an AIR in proof form
+    ///
+    /// Public-input assertions are not recorded; the verifier supplies them.
+    pub(crate) fn to_stark_air(air: &Air<F>, constraints: &CompositionConstraints<F>) -> StarkAir<F> {
+        StarkAir {
+            constraints: vec![],
+            transition: crate::types::stark::TransitionFunction {
+                coefficients: constraints.transition().to_vec(),
+                degree: air.transition.degree(),
+            },
+            boundary: crate::types::stark::BoundaryConditions {
+                constraints: constraints
+                    .boundary()
+                    .iter()
+                    .map(|assertion| crate::types::stark::BoundaryConstraint {
+                        register: assertion.register,
+                        step: assertion.row,
+                        value: assertion.value,
+                    })
+                    .collect(),
+            },
+            security_parameter: air.security_parameter,
+        }
+    }
+
+    /// Generate the execution trace of an AIR, to be used as a witness
+    ///
+    /// An AIR with a step function (see [`Air::with_step`]) is generated by
+    /// applying it row by row. Square linear transitions are generated in
+    /// independent segments (in parallel with the `parallel` feature); other
+    /// transitions, and AIRs with fixed columns, are applied row by row. Fixed
+    /// registers hold their column's values on every row.
+    pub fn generate_trace(
         &self,
         air: &Air<F>,
         initial_state: &[F],
         num_steps: usize,
     ) -> Result<ExecutionTrace<F>, ProofError> {
-        let mut columns = vec![Vec::new(); air.transition.num_registers()];
-        let mut current_state = initial_state.to_vec();
-
-        // Initialize columns with initial state
-        for (i, &value) in current_state.iter().enumerate() {
-            if i < columns.len() {
-                columns[i].push(value);
-            }
-        }
+        let num_registers = air.transition.num_registers();
+        let matrix = air.transition.coefficients();
 
-        // Generate trace steps
-        for _ in 1..num_steps {
-            // Apply transition function to get next state
-            let next_state = air.transition.apply(&current_state);
-            current_state = next_state;
-            
-            // Add next state to columns
-            for (i, &value) in current_state.iter().enumerate() {
-                if i < columns.len() {
-                    columns[i].push(value);
-                }
-            }
-        }
+        let mut state = initial_state.to_vec();
+        state.resize(num_registers, F::zero());
+        let fixed = &air.fixed_columns;
+        let columns = if let Some(step) = &air.step {
+            generate_fixed_trace(&state, num_steps, &|current| step.apply(current), fixed)
+        } else if fixed.is_empty() && matrix.iter().all(|row| row.len() == num_registers) {
+            generate_linear_trace(matrix, initial_state, num_steps, DEFAULT_SEGMENT_LENGTH)
+        } else {
+            generate_fixed_trace(&state, num_steps, &|current| air.transition.apply(current), fixed)
+        };
 
         Ok(ExecutionTrace {
-            columns,
+            columns: columns.into(),
             length: num_steps,
-            num_registers: air.transition.num_registers(),
+            num_registers,
+            aux_segments: Vec::new(),
         })
     }
 
-    /// Generate constraint polynomials
-    fn generate_constraint_polynomials(
+    /// Create the prover transcript bound to the public proof parameters, the
+    /// AIR, the constraints recorded in the proof and the public inputs
+    fn initialize_transcript(
         &self,
-        _air: &Air<F>,
-        _trace: &ExecutionTrace<F>,
-    ) -> Result<Vec<Vec<F>>, ProofError> {
-        // Placeholder implementation
-        // In a real implementation, this would evaluate constraints over the trace
-        Ok(vec![vec![F::zero()]])
+        air_digest: &Digest,
+        recorded_air: &StarkAir<F>,
+        trace: &ExecutionTrace<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Transcript {
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(self.security_parameter));
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(self.zero_knowledge));
+        transcript.absorb_u64(labels::PARAMETERS, self.blowup_factor as u64);
+        transcript.absorb_u64(labels::PARAMETERS, self.num_queries as u64);
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(self.field_extension_degree));
+        transcript.absorb_bytes(labels::AIR_DIGEST, air_digest.as_bytes());
+        transcript.absorb_bytes(labels::RECORDED_AIR, &recorded_air.to_bytes());
+        transcript.absorb_u64(labels::TRACE_SHAPE, trace.length as u64);
+        transcript.absorb_u64(labels::TRACE_SHAPE, trace.num_registers as u64);
+        public_inputs.absorb(&mut transcript);
+        transcript
     }
 
-    /// Generate FRI proof
-    fn generate_fri_proof(&self, _polynomials: &[Vec<F>]) -> Result<FriProof<F>, ProofError> {
-        // This is now handled by the FriProver
-        unimplemented!("Use FriProver directly")
+    /// Length of the interpolated trace: the trace plus, in zero-knowledge
+    /// mode, its random rows
+    pub(crate) fn lde_trace_length(&self, trace_length: usize) -> usize {
+        if self.zero_knowledge {
+            trace_length + num_random_rows(self.num_queries)
+        } else {
+            trace_length
+        }
     }
 
-    /// Generate commitments for proof components
-    fn generate_commitments(
+    /// Field extension degree, if the field supports it
+    fn extension_degree(&self) -> Result<usize, ProofError> {
+        let degree = self.field_extension_degree as usize;
+        if ExtensionElement::<F>::is_supported(degree) {
+            Ok(degree)
+        } else {
+            Err(ProofError::UnsupportedExtension(self.field_extension_degree))
+        }
+    }
+
+    /// Generate the constraint composition polynomial
+    ///
+    /// Evaluates the constraint quotients over the LDE domain and combines
+    /// them with coefficients drawn from the field extension. The result has
+    /// extension coefficients and is returned as one base field polynomial
+    /// per coordinate.
+    fn generate_constraint_polynomials(
         &self,
-        trace: &ExecutionTrace<F>,
-        _constraint_polynomials: &[Vec<F>],
-    ) -> Result<Vec<MerkleCommitment<F>>, ProofError> {
-        let mut commitments = Vec::new();
-
-        // Generate commitment for trace
-        // Convert trace to field elements for commitment generation
-        let trace_elements: Vec<F> = trace.columns.iter()
-            .flat_map(|column| column.iter().cloned())
-            .collect();
-        
-        let trace_commitment = MerkleCommitment {
-            root: generate_commitment(&trace_elements),
-            depth: 0,
-            leaves: trace_elements,
+        constraints: &CompositionConstraints<F>,
+        trace_lde: &TraceLde<F>,
+        domain: &CompositionDomain<F>,
+        extension_degree: usize,
+        transcript: &mut Transcript,
+    ) -> Result<Vec<CompositionPolynomial<F>>, ProofError> {
+        let coefficients = transcript.challenge_extension_elements(
+            labels::COMPOSITION_COEFFICIENTS,
+            constraints.num_coefficients(),
+            extension_degree,
+        );
+        split_coordinates(&coefficients, extension_degree)
+            .iter()
+            .map(|coordinate| CompositionPolynomial::with_domain(constraints, trace_lde, coordinate, domain))
+            .collect()
+    }
+
+    /// Commit to the composition polynomial evaluations over the LDE domain
+    ///
+    /// Each leaf holds every coordinate of the composition at one point and,
+    /// in zero-knowledge mode, the masking polynomial evaluation there.
+    fn commit_composition(
+        &self,
+        composition: &[CompositionPolynomial<F>],
+        masking: Option<&[F]>,
+        salts: Option<&[[u8; 32]]>,
+        plan: &MemoryPlan,
+    ) -> Result<MerkleTree, ProofError> {
+        let leaf = |index: usize| {
+            let values: Vec<F> = composition.iter().map(|coordinate| coordinate.evaluations()[index]).collect();
+            let masking = masking.map(|values| values[index]);
+            salted_leaf(composition_leaf(&values, masking), salts.map(|salts| &salts[index]))
         };
-        commitments.push(trace_commitment);
+        let num_leaves = composition.first().map_or(0, |coordinate| coordinate.evaluations().len());
+        self.commit_leaves(num_leaves, CommitmentRole::Composition, plan, leaf)
+    }
 
-        Ok(commitments)
+    /// Extend column polynomials over the LDE domain on the configured device
+    fn extend_polynomials(
+        &self,
+        polynomials: Vec<Vec<F>>,
+        trace_length: usize,
+        plan: &MemoryPlan,
+    ) -> Result<TraceLde<F>, ProofError> {
+        #[cfg(feature = "gpu")]
+        if let Some(accelerator) = self.device.active()? {
+            let extend = |polynomial: &[F], rows| {
+                Ok(self.device.extend_column(accelerator, polynomial, trace_length, rows)?)
+            };
+            return TraceLde::from_polynomials_with(polynomials, trace_length, self.blowup_factor, plan, &extend);
+        }
+        let extend = |polynomial: &[F], rows| Ok(self.kernels.extend(polynomial, trace_length, rows));
+        TraceLde::from_polynomials_with(polynomials, trace_length, self.blowup_factor, plan, &extend)
+    }
+
+    /// Commit to leaves built on demand, hashing them on the configured device
+    fn commit_leaves(
+        &self,
+        num_leaves: usize,
+        role: CommitmentRole,
+        plan: &MemoryPlan,
+        leaf: impl Fn(usize) -> Vec<u8>,
+    ) -> Result<MerkleTree, ProofError> {
+        #[cfg(feature = "gpu")]
+        if let Some(accelerator) = self.device.active()? {
+            let hash_chunk = |key: &[u8], leaves: &[Vec<u8>]| {
+                self.device
+                    .hash_leaves(accelerator, key, leaves)
+                    .map_err(|error| crate::proof::merkle::MerkleError::HashError(error.to_string()))
+            };
+            return Ok(MerkleTree::for_role_hashed(num_leaves, role, plan.hash_chunk_leaves, leaf, hash_chunk)?);
+        }
+        let hash_chunk = |key: &[u8], leaves: &[Vec<u8>]| Ok(self.kernels.hash_leaves(key, leaves));
+        Ok(MerkleTree::for_role_hashed(num_leaves, role, plan.hash_chunk_leaves, leaf, hash_chunk)?)
+    }
+
+    /// Draw one salt per leaf in zero-knowledge mode
+    fn leaf_salts<R: Rng + ?Sized>(&self, num_leaves: usize, rng: &mut R) -> Option<Vec<[u8; 32]>> {
+        self.zero_knowledge
+            .then(|| (0..num_leaves).map(|_| random_salt(rng)).collect())
+    }
+
+    /// Record the root of a committed tree in proof form
+    ///
+    /// Only the root is sent; leaves are revealed through the query openings.
+    fn to_commitment(tree: &MerkleTree) -> MerkleCommitment<F> {
+        MerkleCommitment {
+            root: tree.root_hash().to_vec(),
+            depth: tree.depth,
+            leaves: Vec::new(),
+        }
+    }
+
+    /// Commit to a trace or auxiliary segment LDE
+    ///
+    /// Each leaf holds one row of the blown-up segment, so a single opening
+    /// reveals every column at a domain point.
+    fn generate_commitments(
+        &self,
+        trace_lde: &TraceLde<F>,
+        salts: Option<&[[u8; 32]]>,
+        role: CommitmentRole,
+        plan: &MemoryPlan,
+    ) -> Result<MerkleTree, ProofError> {
+        let leaf = |index: usize| salted_leaf(encode_row(&trace_lde.row(index)), salts.map(|salts| &salts[index]));
+        self.commit_leaves(trace_lde.domain_size(), role, plan, leaf)
     }
 
     /// Create proof metadata
-    fn create_proof_metadata(&self, _air: &Air<F>, trace: &ExecutionTrace<F>) -> Result<ProofMetadata, ProofError> {
+    fn create_proof_metadata(
+        &self,
+        air_digest: &Digest,
+        grinding_nonce: u64,
+    ) -> Result<ProofMetadata, ProofError> {
         Ok(ProofMetadata {
-            version: 1,
-            field_modulus: "0x7fffffffffffffff".to_string(), // PrimeField64 modulus as string
-            proof_size: trace.length,
-            security_parameter: self.security_parameter,
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            version: 2,
+            prover_version: crate::VERSION.to_string(),
+            field: FieldId::of::<F>(),
+            hash_id: NATIVE_HASH,
+            air_digest: *air_digest,
+            parameters: ProofParameters {
+                security_parameter: self.security_parameter,
+                zero_knowledge: self.zero_knowledge,
+                blowup_factor: self.blowup_factor,
+                num_queries: self.num_queries,
+                field_extension_degree: self.field_extension_degree,
+                grinding_bits: self.grinding_bits,
+            },
+            grinding_nonce,
+            // Set once the proof is assembled
+            proof_size: 0,
+            timestamp: match self.seed {
+                Some(_) => 0,
+                None => unix_timestamp(),
+            },
         })
     }
 }
 
-impl<F: FieldElement> std::fmt::Display for StarkProver<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// Seconds since the Unix epoch, or zero without `std` to read the clock
+fn unix_timestamp() -> u64 {
+    #[cfg(feature = "std")]
+    return std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    #[cfg(not(feature = "std"))]
+    0
+}
+
+/// Builder of a [`StarkProver`] whose blowup factor is derived from an AIR
+///
+/// Unless a blowup factor is set, [`build`](Self::build) picks the smallest
+/// one valid for the AIR's constraint degree (see [`min_blowup_factor`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarkProverBuilder {
+    /// Security parameter
+    security_parameter: u32,
+    /// Blowup factor, derived from the AIR if unset
+    blowup_factor: Option<usize>,
+    /// Number of queries
+    num_queries: usize,
+    /// Degree of the field extension
+    field_extension_degree: u32,
+}
+
+impl StarkProverBuilder {
+    /// Start a prover with a security parameter and the default query count
+    pub fn new(security_parameter: u32) -> Self {
+        Self {
+            security_parameter,
+            blowup_factor: None,
+            num_queries: 64,
+            field_extension_degree: 1,
+        }
+    }
+
+    /// Use a fixed blowup factor
+    ///
+    /// [`build`](Self::build) fails if it is below the minimum for the AIR.
+    pub fn with_blowup_factor(mut self, blowup_factor: usize) -> Self {
+        self.blowup_factor = Some(blowup_factor);
+        self
+    }
+
+    /// Set the number of queries
+    pub fn with_num_queries(mut self, num_queries: usize) -> Self {
+        self.num_queries = num_queries;
+        self
+    }
+
+    /// Set the degree of the field extension
+    pub fn with_field_extension_degree(mut self, field_extension_degree: u32) -> Self {
+        self.field_extension_degree = field_extension_degree;
+        self
+    }
+
+    /// Build a prover for `air`
+    ///
+    /// Fails if the AIR does not validate or the blowup factor is too small
+    /// for [`Air::max_constraint_degree`].
+    pub fn build<F: FieldElement>(&self, air: &Air<F>) -> Result<StarkProver<F>, ProofError> {
+        air.validate().map_err(|e| ProofError::InvalidAir(e.to_string()))?;
+        let degree = air.max_constraint_degree();
+        let minimum = min_blowup_factor(degree);
+        let blowup_factor = self.blowup_factor.unwrap_or(minimum);
+        if blowup_factor < minimum {
+            return Err(ProofError::InvalidAir(format!(
+                "blowup factor {} is below {} required by constraints of degree {}",
+                blowup_factor, minimum, degree
+            )));
+        }
+        Ok(StarkProver::with_params(
+            self.security_parameter,
+            blowup_factor,
+            self.num_queries,
+            self.field_extension_degree,
+        ))
+    }
+}
+
+impl<F: FieldElement> core::fmt::Display for StarkProver<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "StarkProver(security={}, blowup={}, queries={}, field_ext={})",
@@ -216,85 +955,835 @@ impl<F: FieldElement> std::fmt::Display for StarkProver<F> {
     }
 }
 
+/// Cancels a token when dropped
+#[cfg(feature = "async")]
+struct CancelOnDrop(CancellationToken);
+
+#[cfg(feature = "async")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
 /// STARK proof verifier
 /// 
 /// Verifies STARK proofs with cryptographic security guarantees.
 #[derive(Debug, Clone)]
 pub struct StarkVerifier<F: FieldElement> {
-    /// Security parameter
+    /// Minimum security level in bits a proof must reach
     security_parameter: u32,
-    /// Number of queries
-    num_queries: usize,
+    /// Whether only zero-knowledge proofs are accepted
+    zero_knowledge: bool,
+    /// Minimum proof-of-work bits a proof must be ground to
+    grinding_bits: u32,
+    /// Parameters a proof must be generated with, if pinned
+    expected_params: Option<ExpectedParams>,
     /// Phantom data for type parameter
     _phantom: PhantomData<F>,
 }
 
+/// Verifier challenges replayed from the proof transcript
+struct VerifierChallenges<F: FieldElement> {
+    /// Coefficients combining the constraint quotients, split into one
+    /// vector per coordinate of the field extension
+    composition_coefficients: Vec<Vec<F>>,
+    /// Out-of-domain point
+    ood_point: F,
+    /// Coefficients of the DEEP composition polynomial
+    deep_coefficients: Vec<F>,
+}
+
 impl<F: FieldElement> StarkVerifier<F> {
     /// Create a new STARK verifier
+    ///
+    /// Proofs are accepted only if the security estimated from their recorded
+    /// blowup factor and query count reaches `security_parameter` bits; the
+    /// security level a proof claims for itself is not trusted.
     pub fn new(security_parameter: u32) -> Self {
         Self {
             security_parameter,
-            num_queries: 64,
+            zero_knowledge: false,
+            grinding_bits: 0,
+            expected_params: None,
             _phantom: PhantomData,
         }
     }
 
+    /// Accept only proofs over this verifier's field whose parameters are in `params`
+    ///
+    /// The minimum security level is still enforced on top of the allow-list.
+    pub fn with_expected_params(mut self, params: ExpectedParams) -> Self {
+        self.expected_params = Some(params);
+        self
+    }
+
+    /// Require proofs to be generated in zero-knowledge mode
+    pub fn with_zk(mut self, required: bool) -> Self {
+        self.zero_knowledge = required;
+        self
+    }
+
+    /// Require proofs to carry a proof-of-work nonce of at least `bits` bits
+    ///
+    /// See [`StarkProver::with_proof_grinding`].
+    pub fn with_min_grinding(mut self, bits: u32) -> Self {
+        self.grinding_bits = bits;
+        self
+    }
+
     /// Verify a STARK proof
-    pub fn verify(&self, proof: &StarkProof<F>) -> Result<bool, ProofError> {
-        // Step 1: Verify boundary conditions
-        if !self.verify_boundary_conditions(&proof)? {
-            return Ok(false);
+    ///
+    /// The verifier replays the prover's transcript from the proof data, so
+    /// every challenge is re-derived rather than trusted. The assertions of
+    /// `public_inputs` are enforced in addition to the constraints recorded in
+    /// the proof. A rejected proof is reported with the first check it failed.
+    ///
+    /// Proofs of AIRs with auxiliary segments need the segment definitions and
+    /// are verified with [`verify_with_air`](Self::verify_with_air), as are
+    /// public inputs given as values of the cells an AIR declares.
+    pub fn verify(&self, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Result<(), VerificationError> {
+        if !public_inputs.values().is_empty() {
+            return Err(VerificationError::ParameterMismatch(
+                "public input values need the AIR declaring their cells".to_string(),
+            ));
+        }
+        let constraints = CompositionConstraints::from_stark_air(&proof.air, proof.trace.length)?;
+        self.verify_with_constraints(proof, public_inputs, constraints, &[])
+    }
+
+    /// Verify a STARK proof of the given AIR
+    ///
+    /// The proof must be bound to the digest of the AIR and the constraints
+    /// recorded in the proof must match it. The auxiliary segments of the AIR
+    /// are rebuilt from challenges replayed from the transcript.
+    pub fn verify_with_air(
+        &self,
+        air: &Air<F>,
+        proof: &StarkProof<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), VerificationError> {
+        if proof.metadata.air_digest != air.digest() {
+            return Err(VerificationError::AirDigest);
+        }
+        let public_inputs = bind_public_inputs(air, public_inputs)?;
+        let expected = CompositionConstraints::from_air(air, proof.trace.length)?;
+        check_recorded_constraints(proof, &expected)?;
+        self.verify_with_constraints(proof, &public_inputs, expected, &air.aux_segments)
+    }
+
+    /// Verify a proof shipped without its AIR against the given AIR
+    ///
+    /// Equivalent to [`verify_with_air`](Self::verify_with_air) on the proof
+    /// with the AIR attached.
+    pub fn verify_detached(
+        &self,
+        air: &Air<F>,
+        proof: &DetachedProof<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), VerificationError> {
+        if proof.metadata.air_digest != air.digest() {
+            return Err(VerificationError::AirDigest);
+        }
+        let public_inputs = bind_public_inputs(air, public_inputs)?;
+        let constraints = CompositionConstraints::from_air(air, proof.trace.length)?;
+        let proof = proof.clone().attach(StarkProver::to_stark_air(air, &constraints));
+        self.verify_with_constraints(&proof, &public_inputs, constraints, &air.aux_segments)
+    }
+
+    /// Verify a STARK proof with a verifying key of its AIR
+    ///
+    /// Equivalent to [`verify_with_air`](Self::verify_with_air) with the key's
+    /// AIR, without instantiating the AIR again. The proof must have the trace
+    /// length the key was created for.
+    pub fn verify_with_key(
+        &self,
+        key: &VerifyingKey<F>,
+        proof: &StarkProof<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<(), VerificationError> {
+        if proof.metadata.air_digest != *key.air_digest() {
+            return Err(VerificationError::AirDigest);
+        }
+        if proof.trace.length != key.trace_length() {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "proof trace length {}, key created for {}",
+                proof.trace.length,
+                key.trace_length()
+            )));
+        }
+        check_recorded_constraints(proof, key.constraints())?;
+        let public_inputs = bind_public_inputs(key.air(), public_inputs)?;
+        self.verify_with_constraints(proof, &public_inputs, key.constraints().clone(), &key.air().aux_segments)
+    }
+
+    /// Verify many proofs of the same AIR
+    ///
+    /// The AIR is instantiated once per distinct trace length and shared by
+    /// every proof of that length; a proof bound to a different AIR digest or
+    /// whose recorded constraints differ from the AIR is rejected. `public_inputs[i]` holds the public inputs of
+    /// `proofs[i]`. With the `parallel` feature the proofs are verified
+    /// concurrently. Results are returned in input order.
+    pub fn verify_batch(
+        &self,
+        air: &Air<F>,
+        proofs: &[StarkProof<F>],
+        public_inputs: &[PublicInputs<F>],
+    ) -> Vec<Result<(), VerificationError>> {
+        let digest = air.digest();
+        let mut instantiated = BTreeMap::new();
+        for proof in proofs {
+            instantiated.entry(proof.trace.length).or_insert_with(|| {
+                CompositionConstraints::from_air(air, proof.trace.length).map_err(|error| error.to_string())
+            });
+        }
+
+        let verify_one = |(index, proof): (usize, &StarkProof<F>)| -> Result<(), VerificationError> {
+            let public_inputs = public_inputs.get(index).ok_or_else(|| {
+                VerificationError::ParameterMismatch("missing public inputs".to_string())
+            })?;
+            if proof.metadata.air_digest != digest {
+                return Err(VerificationError::AirDigest);
+            }
+            let expected = instantiated[&proof.trace.length]
+                .as_ref()
+                .map_err(|error| ProofError::InvalidAir(error.clone()))?;
+            check_recorded_constraints(proof, expected)?;
+            let public_inputs = bind_public_inputs(air, public_inputs)?;
+            self.verify_with_constraints(proof, &public_inputs, expected.clone(), &air.aux_segments)
+        };
+
+        #[cfg(feature = "parallel")]
+        let results = proofs.par_iter().enumerate().map(verify_one).collect();
+        #[cfg(not(feature = "parallel"))]
+        let results = proofs.iter().enumerate().map(verify_one).collect();
+
+        results
+    }
+
+    /// Verify an aggregate of proofs of the same AIR
+    ///
+    /// Recomputes the commitment to the member proofs, checks that every
+    /// member is bound to `air` by its AIR digest, and verifies every member,
+    /// with the aggregate's AIR reattached, against its public inputs with
+    /// [`verify_batch`](Self::verify_batch).
+    pub fn verify_aggregate(
+        &self,
+        air: &Air<F>,
+        aggregated: &AggregatedProof<F>,
+        public_inputs: &[PublicInputs<F>],
+    ) -> Result<(), VerificationError> {
+        if aggregated.is_empty() {
+            return Err(VerificationError::MalformedProof("empty aggregate".to_string()));
+        }
+        if aggregated.compute_root().ok() != Some(aggregated.root) {
+            return Err(VerificationError::AggregateCommitment);
+        }
+        let digest = air.digest();
+        if aggregated.proofs.iter().any(|proof| proof.metadata.air_digest != digest) {
+            return Err(VerificationError::AirDigest);
+        }
+
+        if public_inputs.len() != aggregated.proofs.len() {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "{} public inputs for {} member proofs",
+                public_inputs.len(),
+                aggregated.proofs.len()
+            )));
+        }
+
+        self.verify_batch(air, &aggregated.members(), public_inputs).into_iter().collect()
+    }
+
+    /// Conjectured security of a proof in bits, estimated from its recorded parameters
+    ///
+    /// The recorded parameters are bound to the transcript and the number of
+    /// openings is checked against them, so they cannot be inflated.
+    pub fn proof_security_bits(&self, proof: &StarkProof<F>) -> u32 {
+        estimate_security_bits(
+            F::MODULUS,
+            proof.metadata.parameters.field_extension_degree,
+            proof.metadata.parameters.blowup_factor,
+            proof.metadata.parameters.num_queries,
+        )
+    }
+
+    /// Verify a proof against already instantiated AIR constraints and the
+    /// auxiliary segments of the AIR
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "verify",
+            skip_all,
+            fields(
+                trace_length = proof.trace.length,
+                blowup = proof.metadata.parameters.blowup_factor,
+                queries = proof.metadata.parameters.num_queries,
+                openings = proof.openings.len(),
+                fri_layers = proof.fri_proof.layers.len(),
+            )
+        )
+    )]
+    fn verify_with_constraints(
+        &self,
+        proof: &StarkProof<F>,
+        public_inputs: &PublicInputs<F>,
+        constraints: CompositionConstraints<F>,
+        aux_segments: &[AuxSegment<F>],
+    ) -> Result<(), VerificationError> {
+        if !proof.body.is_native() {
+            return Err(VerificationError::ParameterMismatch(
+                "proof of another backend, verify it with that backend".to_string(),
+            ));
+        }
+        if proof.metadata.field != FieldId::of::<F>() {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "proof over {}, the verifier over {}",
+                proof.metadata.field,
+                FieldId::of::<F>()
+            )));
+        }
+        if proof.metadata.hash_id != NATIVE_HASH {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "proof hashes with {}, the native verifier with {}",
+                proof.metadata.hash_id, NATIVE_HASH
+            )));
+        }
+
+        // Pinned parameters override whatever the prover chose
+        if let Some(expected) = &self.expected_params {
+            expected.check::<F>(&proof.metadata)?;
+        }
+
+        // The challenges must come from an extension the field supports, and
+        // the FRI layers must be over the same one
+        let extension_degree = proof.metadata.parameters.field_extension_degree as usize;
+        if !ExtensionElement::<F>::is_supported(extension_degree) {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "unsupported field extension degree {}",
+                extension_degree
+            )));
+        }
+        if proof.fri_proof.extension_degree != extension_degree {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "FRI layers over extension degree {}, proof records {}",
+                proof.fri_proof.extension_degree, extension_degree
+            )));
+        }
+
+        // The proof parameters must reach the configured security level
+        let achieved = self.proof_security_bits(proof);
+        if achieved < self.security_parameter {
+            return Err(VerificationError::InsufficientSecurity {
+                achieved,
+                required: self.security_parameter,
+            });
+        }
+
+        // The proof must be ground to the required proof-of-work
+        if proof.metadata.parameters.grinding_bits < self.grinding_bits {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "proof ground to {} bits, {} required",
+                proof.metadata.parameters.grinding_bits, self.grinding_bits
+            )));
+        }
+
+        // A zero-knowledge proof must not carry the witness
+        let zero_knowledge = proof.metadata.parameters.zero_knowledge;
+        if self.zero_knowledge && !zero_knowledge {
+            return Err(VerificationError::ParameterMismatch(
+                "zero-knowledge proof required".to_string(),
+            ));
+        }
+        if zero_knowledge && (!proof.trace.columns.is_empty() || !proof.trace.aux_segments.is_empty()) {
+            return Err(VerificationError::MalformedProof(
+                "zero-knowledge proof carries the trace".to_string(),
+            ));
         }
 
-        // Step 2: Verify constraints
-        if !self.verify_constraints(&proof)? {
-            return Ok(false);
+        let mut transcript = self.initialize_transcript(proof, public_inputs);
+        let (constraints, challenges) =
+            self.replay_challenges(proof, constraints, aux_segments, public_inputs, &mut transcript)?;
+        let constraints = &constraints;
+
+        // Step 1: Check the transitions of the trace carried by a transparent proof
+        self.verify_trace(proof, constraints)?;
+
+        // Step 2: Verify boundary conditions
+        self.verify_boundary_conditions(proof, constraints, &challenges)?;
+
+        // Step 3: Verify constraints
+        self.verify_constraints(proof, constraints, &challenges)?;
+
+        // Step 4: Verify FRI proof
+        self.verify_fri_proof(proof, &mut transcript)?;
+
+        // Step 5: Verify commitments
+        self.verify_commitments(proof, constraints, &challenges, &mut transcript)?;
+
+        // Step 6: Check the proof-of-work nonce over the finished transcript
+        if !transcript.check_grinding(proof.metadata.parameters.grinding_bits, proof.metadata.grinding_nonce) {
+            return Err(VerificationError::Grinding { bits: proof.metadata.parameters.grinding_bits });
         }
+        Ok(())
+    }
+
+    /// Create the verifier transcript bound to the public proof parameters, the
+    /// recorded AIR digest and constraints, and the public inputs
+    ///
+    /// [`verify`](Self::verify) checks the proof against the constraints it
+    /// records, so they are absorbed before any challenge is drawn: a prover
+    /// cannot pick them after seeing the challenges.
+    fn initialize_transcript(&self, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Transcript {
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(proof.metadata.parameters.security_parameter));
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(proof.metadata.parameters.zero_knowledge));
+        transcript.absorb_u64(labels::PARAMETERS, proof.metadata.parameters.blowup_factor as u64);
+        transcript.absorb_u64(labels::PARAMETERS, proof.metadata.parameters.num_queries as u64);
+        transcript.absorb_u64(labels::PARAMETERS, u64::from(proof.metadata.parameters.field_extension_degree));
+        transcript.absorb_bytes(labels::AIR_DIGEST, proof.metadata.air_digest.as_bytes());
+        transcript.absorb_bytes(labels::RECORDED_AIR, &proof.air.to_bytes());
+        transcript.absorb_u64(labels::TRACE_SHAPE, proof.trace.length as u64);
+        transcript.absorb_u64(labels::TRACE_SHAPE, proof.trace.num_registers as u64);
+        public_inputs.absorb(&mut transcript);
+        transcript
+    }
 
-        // Step 3: Verify FRI proof
-        if !self.verify_fri_proof(&proof)? {
-            return Ok(false);
+    /// Replay the commitment phase of the transcript up to the DEEP coefficients
+    ///
+    /// Every auxiliary segment is instantiated with the challenges replayed
+    /// for it, and the public inputs are then enforced as additional boundary
+    /// constraints. Returns the complete constraints with the challenges.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn replay_challenges(
+        &self,
+        proof: &StarkProof<F>,
+        constraints: CompositionConstraints<F>,
+        aux_segments: &[AuxSegment<F>],
+        public_inputs: &PublicInputs<F>,
+        transcript: &mut Transcript,
+    ) -> Result<(CompositionConstraints<F>, VerifierChallenges<F>), VerificationError> {
+        let roots = commitment_roots(proof, aux_segments.len())?;
+
+        transcript.absorb_commitment(labels::TRACE_COMMITMENT, &roots.trace);
+        let mut constraints = constraints;
+        for (segment, aux_root) in aux_segments.iter().zip(&roots.aux) {
+            let challenges = transcript
+                .challenge_field_elements(labels::AUX_CHALLENGES, segment.definition().num_challenges());
+            transcript.absorb_commitment(labels::AUX_COMMITMENT, aux_root);
+            constraints = constraints.with_aux_segment(segment, challenges)?;
         }
+        let constraints = constraints
+            .with_public_inputs(public_inputs)
+            .map_err(|error| VerificationError::ParameterMismatch(format!("public inputs: {}", error)))?;
+
+        let extension_degree = proof.metadata.parameters.field_extension_degree as usize;
+        let composition_coefficients = transcript.challenge_extension_elements(
+            labels::COMPOSITION_COEFFICIENTS,
+            constraints.num_coefficients(),
+            extension_degree,
+        );
+        transcript.absorb_commitment(labels::COMPOSITION_COMMITMENT, &roots.composition);
 
-        // Step 4: Verify commitments
-        if !self.verify_commitments(&proof)? {
-            return Ok(false);
+        let ood_point = draw_ood_point(transcript);
+        absorb_ood_frame(transcript, &proof.ood_frame);
+        let deep_coefficients = transcript.challenge_field_elements(
+            labels::DEEP_COEFFICIENTS,
+            num_deep_coefficients(constraints.num_registers(), extension_degree, constraints.window()),
+        );
+
+        Ok((
+            constraints,
+            VerifierChallenges {
+                composition_coefficients: split_coordinates(&composition_coefficients, extension_degree),
+                ood_point,
+                deep_coefficients,
+            },
+        ))
+    }
+
+    /// Check the transitions and fixed columns of the trace carried by a transparent proof
+    ///
+    /// Proofs without trace columns (such as zero-knowledge proofs) are
+    /// checked through their commitments only.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn verify_trace(
+        &self,
+        proof: &StarkProof<F>,
+        constraints: &CompositionConstraints<F>,
+    ) -> Result<(), VerificationError> {
+        if proof.trace.columns.is_empty() {
+            return Ok(());
         }
 
-        Ok(true)
+        let violation = constraints
+            .first_transition_violation(&proof.trace)
+            .and_then(|violation| match violation {
+                Some(violation) => Ok(Some(violation)),
+                None => constraints.first_fixed_violation(&proof.trace),
+            });
+        match violation {
+            Ok(None) => Ok(()),
+            Ok(Some(violation)) => Err(violation.into()),
+            Err(_) => Err(VerificationError::MalformedProof(
+                "trace shape does not match the AIR".to_string(),
+            )),
+        }
     }
 
     /// Verify boundary conditions
-    fn verify_boundary_conditions(&self, _proof: &StarkProof<F>) -> Result<bool, ProofError> {
-        // Placeholder implementation
-        Ok(true)
+    ///
+    /// Every boundary assertion, including those of the public inputs, is
+    /// checked directly against the trace of a transparent proof. For every
+    /// proof the boundary quotients must be defined on the out-of-domain
+    /// frame; their combination enters the composition check in
+    /// [`verify_constraints`](Self::verify_constraints), which binds the
+    /// asserted values to the committed trace.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn verify_boundary_conditions(
+        &self,
+        proof: &StarkProof<F>,
+        constraints: &CompositionConstraints<F>,
+        challenges: &VerifierChallenges<F>,
+    ) -> Result<(), VerificationError> {
+        if !proof.trace.columns.is_empty() {
+            if let Some(violation) = constraints.first_boundary_violation(&proof.trace)? {
+                return Err(violation.into());
+            }
+        }
+
+        constraints
+            .evaluate_boundary_quotients(
+                &challenges.composition_coefficients[0],
+                challenges.ood_point,
+                &proof.ood_frame.trace_current,
+            )
+            .map(|_| ())
+            .ok_or_else(|| {
+                VerificationError::MalformedProof("out-of-domain frame does not fit the boundary constraints".to_string())
+            })
     }
 
     /// Verify constraints
-    fn verify_constraints(&self, _proof: &StarkProof<F>) -> Result<bool, ProofError> {
-        // Placeholder implementation
-        Ok(true)
+    ///
+    /// Checks the DEEP constraint equation at the out-of-domain point `z`: the
+    /// composition value claimed in the frame must equal
+    ///
+    /// `sum_i alpha_i * C_i(T(z), ..., T(z + w - 1)) / Z(z) + sum_k beta_k * (T_k(z) - v_k) / (z - row_k)`
+    ///
+    /// over the window of `w` rows, with the coefficients `alpha`, `beta`
+    /// replayed from the transcript. The fixed registers of the frame must
+    /// hold the interpolants of their columns. The
+    /// frame itself is tied to the committed trace and composition by the
+    /// DEEP queries checked in [`verify_commitments`](Self::verify_commitments).
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn verify_constraints(
+        &self,
+        proof: &StarkProof<F>,
+        constraints: &CompositionConstraints<F>,
+        challenges: &VerifierChallenges<F>,
+    ) -> Result<(), VerificationError> {
+        let frame = &proof.ood_frame;
+        let width = constraints.num_registers();
+        if frame.trace_current.len() != width
+            || frame.trace_next.len() != width
+            || frame.trace_window.iter().any(|row| row.len() != width)
+            || frame.composition.len() != challenges.composition_coefficients.len()
+        {
+            return Err(VerificationError::MalformedProof(
+                "out-of-domain frame width does not match the AIR".to_string(),
+            ));
+        }
+        if frame.trace_window.len() + 2 != constraints.window() {
+            return Err(VerificationError::MalformedProof(
+                "out-of-domain frame window does not match the AIR".to_string(),
+            ));
+        }
+        let rows: Vec<&[F]> = [&frame.trace_current, &frame.trace_next]
+            .into_iter()
+            .chain(&frame.trace_window)
+            .map(Vec::as_slice)
+            .collect();
+
+        // Fixed columns are known to the verifier, who evaluates them itself
+        let z = challenges.ood_point;
+        if !constraints.fixed_columns_hold(self.lde_trace_length(proof), z, &rows)? {
+            return Err(VerificationError::OodConstraint);
+        }
+
+        // The equation is linear in the coefficients, so each coordinate of
+        // the extension is checked on its own
+        for (coefficients, &claimed) in challenges.composition_coefficients.iter().zip(&frame.composition) {
+            let transition = constraints
+                .evaluate_transition_quotients(coefficients, z, &rows)
+                .ok_or(VerificationError::OodConstraint)?;
+            let boundary = constraints
+                .evaluate_boundary_quotients(coefficients, z, &frame.trace_current)
+                .ok_or(VerificationError::OodConstraint)?;
+            if transition + boundary != claimed {
+                return Err(VerificationError::OodConstraint);
+            }
+        }
+        Ok(())
     }
 
-    /// Verify FRI proof
-    fn verify_fri_proof(&self, _proof: &StarkProof<F>) -> Result<bool, ProofError> {
-        // Placeholder implementation
-        Ok(true)
+    /// Verify FRI proof against challenges replayed from the transcript
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn verify_fri_proof(
+        &self,
+        proof: &StarkProof<F>,
+        transcript: &mut Transcript,
+    ) -> Result<(), VerificationError> {
+        let fri_verifier = FriVerifier::with_params(
+            self.security_parameter,
+            proof.metadata.parameters.num_queries,
+            FRI_FOLDING_FACTOR,
+        );
+        Ok(fri_verifier.check_with_transcript(&proof.fri_proof, &[], transcript)?)
     }
 
-    /// Verify commitments
-    fn verify_commitments(&self, _proof: &StarkProof<F>) -> Result<bool, ProofError> {
-        // Placeholder implementation
-        Ok(true)
+    /// Verify the query openings against the trace, auxiliary segment and
+    /// composition commitments
+    ///
+    /// Query positions are re-derived from the transcript. At each position the
+    /// opened rows and composition value must authenticate against the
+    /// committed roots, and the DEEP composition recomputed from them must
+    /// match the polynomial proven low-degree by FRI.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    fn verify_commitments(
+        &self,
+        proof: &StarkProof<F>,
+        constraints: &CompositionConstraints<F>,
+        challenges: &VerifierChallenges<F>,
+        transcript: &mut Transcript,
+    ) -> Result<(), VerificationError> {
+        let aux = constraints.aux_segments();
+        let roots = commitment_roots(proof, aux.len())?;
+
+        // The DEEP quotients have one coefficient less than the composition
+        // polynomial over the (padded) trace
+        let zero_knowledge = proof.metadata.parameters.zero_knowledge;
+        let trace_length = self.lde_trace_length(proof);
+        let deep_layer = proof.fri_proof.layers.first().ok_or(VerificationError::FriLayer { layer: 0 })?;
+        let max_deep_length = constraints.composition_length_for(trace_length).saturating_sub(1).max(1);
+        if deep_layer.polynomial.len() > max_deep_length {
+            return Err(VerificationError::FriLayer { layer: 0 });
+        }
+
+        // Check the opening count before drawing positions, so a proof cannot
+        // make the verifier draw more positions than it opens
+        let num_queries = proof.metadata.parameters.num_queries;
+        let domain_size = trace_length
+            .checked_mul(proof.metadata.parameters.blowup_factor)
+            .ok_or_else(|| VerificationError::ParameterMismatch("LDE domain size overflows".to_string()))?;
+        if proof.openings.len() != num_queries.min(domain_size) {
+            return Err(VerificationError::MalformedProof(format!(
+                "expected {} query openings, found {}",
+                num_queries.min(domain_size),
+                proof.openings.len()
+            )));
+        }
+        let positions = transcript.challenge_indices(labels::QUERY_POSITIONS, num_queries, domain_size);
+
+        let trace_key = CommitmentRole::Trace.key();
+        let composition_key = CommitmentRole::Composition.key();
+        for (query, (opening, &position)) in proof.openings.iter().zip(&positions).enumerate() {
+            if opening.position != position {
+                return Err(VerificationError::QueryPosition { query });
+            }
+            let widths_match = opening.trace_row.len() == constraints.num_main_registers()
+                && opening.composition.len() == challenges.composition_coefficients.len()
+                && opening.aux_segments.len() == aux.len()
+                && opening
+                    .aux_segments
+                    .iter()
+                    .zip(aux)
+                    .all(|(segment, aux)| segment.row.len() == aux.segment.definition().width());
+            if !widths_match {
+                return Err(VerificationError::MalformedProof(format!("query {} has the wrong width", query)));
+            }
+            let blinded = [opening.trace_salt.is_some(), opening.masking.is_some(), opening.composition_salt.is_some()];
+            let aux_blinded = opening.aux_segments.iter().map(|segment| segment.salt.is_some());
+            if blinded.into_iter().chain(aux_blinded).any(|blinded| blinded != zero_knowledge) {
+                return Err(VerificationError::MalformedProof(format!(
+                    "query {} does not match the zero-knowledge mode",
+                    query
+                )));
+            }
+
+            let trace_leaf = salted_leaf(encode_row(&opening.trace_row), opening.trace_salt.as_ref());
+            let trace_proof = MerkleProof::from_siblings(position, opening.trace_path.clone());
+            if !trace_proof.verify_keyed(&trace_key, &trace_leaf, roots.trace) {
+                return Err(VerificationError::MerklePath {
+                    query,
+                    commitment: CommitmentRole::Trace,
+                });
+            }
+
+            let mut row = opening.trace_row.clone();
+            for (index, (segment, aux_root)) in opening.aux_segments.iter().zip(&roots.aux).enumerate() {
+                let role = CommitmentRole::AuxTrace(index);
+                let leaf = salted_leaf(encode_row(&segment.row), segment.salt.as_ref());
+                let proof = MerkleProof::from_siblings(position, segment.path.clone());
+                if !proof.verify_keyed(&role.key(), &leaf, *aux_root) {
+                    return Err(VerificationError::MerklePath { query, commitment: role });
+                }
+                row.extend_from_slice(&segment.row);
+            }
+
+            let composition_leaf = salted_leaf(
+                composition_leaf(&opening.composition, opening.masking),
+                opening.composition_salt.as_ref(),
+            );
+            let composition_proof = MerkleProof::from_siblings(position, opening.composition_path.clone());
+            if !composition_proof.verify_keyed(&composition_key, &composition_leaf, roots.composition) {
+                return Err(VerificationError::MerklePath {
+                    query,
+                    commitment: CommitmentRole::Composition,
+                });
+            }
+
+            let x = lde_domain_point(trace_length, position);
+            let expected = evaluate_deep(
+                x,
+                &row,
+                &opening.composition,
+                &proof.ood_frame,
+                challenges.ood_point,
+                &challenges.deep_coefficients,
+            )
+            .ok_or(VerificationError::DeepComposition { query })?;
+            let expected = expected + opening.masking.unwrap_or_else(F::zero);
+            if evaluate_polynomial(&deep_layer.polynomial, x) != expected {
+                return Err(VerificationError::DeepComposition { query });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Length of the trace the prover interpolated, including any random rows
+    fn lde_trace_length(&self, proof: &StarkProof<F>) -> usize {
+        if proof.metadata.parameters.zero_knowledge {
+            proof.trace.length.saturating_add(num_random_rows(proof.metadata.parameters.num_queries))
+        } else {
+            proof.trace.length
+        }
+    }
+}
+
+/// Encode a row of field elements as a Merkle leaf
+fn encode_row<F: FieldElement>(row: &[F]) -> Vec<u8> {
+    row.iter().flat_map(|value| value.to_bytes()).collect()
+}
+
+/// Encode a composition leaf, one value per extension coordinate, with its
+/// optional masking evaluation
+fn composition_leaf<F: FieldElement>(composition: &[F], masking: Option<F>) -> Vec<u8> {
+    let mut values = composition.to_vec();
+    values.extend(masking);
+    encode_row(&values)
+}
+
+/// Commitment roots carried by a proof
+struct CommitmentRoots {
+    /// Trace commitment root
+    trace: [u8; 32],
+    /// Auxiliary segment commitment roots, in segment order
+    aux: Vec<[u8; 32]>,
+    /// Composition commitment root
+    composition: [u8; 32],
+}
+
+/// Trace, auxiliary segment and composition roots carried by a proof
+///
+/// The proof must carry one commitment per auxiliary segment between the
+/// trace and composition commitments.
+fn commitment_roots<F: FieldElement>(
+    proof: &StarkProof<F>,
+    num_aux_segments: usize,
+) -> Result<CommitmentRoots, VerificationError> {
+    if proof.commitments.len() != num_aux_segments + 2 {
+        return Err(VerificationError::ParameterMismatch(format!(
+            "expected {} commitments for {} auxiliary segments, found {}",
+            num_aux_segments + 2,
+            num_aux_segments,
+            proof.commitments.len()
+        )));
+    }
+
+    let roots = proof
+        .commitments
+        .iter()
+        .map(|commitment| commitment.root.as_slice().try_into().ok())
+        .collect::<Option<Vec<[u8; 32]>>>()
+        .ok_or_else(|| VerificationError::MalformedProof("malformed commitment root".to_string()))?;
+    let (&trace, rest) = roots.split_first().expect("at least two commitments");
+    let (&composition, aux) = rest.split_last().expect("at least one commitment after the trace");
+    Ok(CommitmentRoots {
+        trace,
+        aux: aux.to_vec(),
+        composition,
+    })
+}
+
+/// Place public input values at the cells the AIR declares
+fn bind_public_inputs<F: FieldElement>(
+    air: &Air<F>,
+    public_inputs: &PublicInputs<F>,
+) -> Result<PublicInputs<F>, VerificationError> {
+    air.bind_public_inputs(public_inputs)
+        .map_err(|error| VerificationError::ParameterMismatch(format!("public inputs: {}", error)))
+}
+
+/// Check that the constraints recorded in a proof match the instantiated AIR
+fn check_recorded_constraints<F: FieldElement>(
+    proof: &StarkProof<F>,
+    expected: &CompositionConstraints<F>,
+) -> Result<(), VerificationError> {
+    // Free registers and fixed columns are not recorded and come from the AIR
+    let recorded = CompositionConstraints::from_stark_air(&proof.air, proof.trace.length)?
+        .with_free_registers(&expected.free_registers())
+        .with_fixed_columns(expected.fixed_columns());
+    if recorded != *expected {
+        return Err(VerificationError::ParameterMismatch(
+            "proof constraints differ from the AIR".to_string(),
+        ));
     }
+    Ok(())
 }
 
-impl<F: FieldElement> std::fmt::Display for StarkVerifier<F> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl<F: FieldElement> Default for StarkVerifier<F> {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_SECURITY_BITS)
+    }
+}
+
+impl<F: FieldElement> StarkProof<F> {
+    /// Verify the proof against the AIR it carries
+    ///
+    /// Uses a default [`StarkVerifier`]; configure a verifier to require a
+    /// different security level, zero knowledge or pinned parameters.
+    pub fn verify(&self, public_inputs: &PublicInputs<F>) -> Result<(), VerificationError> {
+        StarkVerifier::default().verify(self, public_inputs)
+    }
+}
+
+impl<F: FieldElement> DetachedProof<F> {
+    /// Verify the proof against the AIR it was generated for
+    ///
+    /// Uses a default [`StarkVerifier`], like [`StarkProof::verify`].
+    pub fn verify(&self, air: &Air<F>, public_inputs: &PublicInputs<F>) -> Result<(), VerificationError> {
+        StarkVerifier::default().verify_detached(air, self, public_inputs)
+    }
+}
+
+impl<F: FieldElement> core::fmt::Display for StarkVerifier<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
-            "StarkVerifier(security={}, queries={})",
-            self.security_parameter, self.num_queries
+            "StarkVerifier(min_security={}, zk={})",
+            self.security_parameter, self.zero_knowledge
         )
     }
 }
@@ -306,6 +1795,10 @@ pub enum ProofError {
     #[error("Invalid execution trace")]
     InvalidTrace,
 
+    /// Trace whose columns or length do not form a valid trace
+    #[error("Invalid execution trace: {0}")]
+    InvalidTraceShape(String),
+
     /// Invalid AIR
     #[error("Invalid AIR: {0}")]
     InvalidAir(String),
@@ -329,10 +1822,676 @@ pub enum ProofError {
     /// Verification error
     #[error("Verification error: {0}")]
     VerificationError(String),
+
+    /// Proof generation was cancelled
+    #[error("Proof generation cancelled")]
+    Cancelled,
+
+    /// The proving key was preprocessed for another trace length or prover configuration
+    #[error("Proving key mismatch: {0}")]
+    KeyMismatch(String),
+
+    /// The field has no extension of the configured degree
+    #[error("Unsupported field extension degree {0}")]
+    UnsupportedExtension(u32),
+
+    /// The proof cannot be generated within the prover's memory budget
+    #[error("Memory budget of {budget} bytes exceeded: at least {required} bytes needed")]
+    MemoryBudgetExceeded {
+        /// Smallest budget the proof fits in
+        required: usize,
+        /// Configured budget
+        budget: usize,
+    },
+
+    /// Spilled storage could not be created or accessed
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    /// Proving without `std` has no entropy source, so the prover must be deterministic
+    #[cfg(not(feature = "std"))]
+    #[error("Proving without std requires a deterministic prover")]
+    MissingEntropy,
+
+    /// A required accelerator was unavailable or failed
+    #[cfg(feature = "gpu")]
+    #[error("Device error: {0}")]
+    DeviceError(#[from] crate::proof::device::DeviceError),
 }
 
 // Re-export sub-modules
+pub mod aggregation;
+pub mod backend;
+pub mod cancellation;
+pub mod composition;
+pub mod cost;
+pub mod deep;
+pub mod dispatch;
+#[cfg(feature = "gpu")]
+pub mod device;
 pub mod fri;
+pub mod memory;
 pub mod merkle;
+pub mod observer;
+pub mod params;
+pub mod preprocess;
+pub mod public_inputs;
+pub mod soundness;
 pub mod trace;
-pub mod verification;
\ No newline at end of file
+pub mod transcript;
+pub mod verification;
+pub mod zk;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::types::field::PrimeField64;
+    use crate::types::{ColIdx, RowIdx};
+
+    fn fibonacci_air() -> Air<PrimeField64> {
+        Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![
+                BoundaryConstraint::initial(0, PrimeField64::new(1)),
+                BoundaryConstraint::initial(1, PrimeField64::new(1)),
+            ]),
+            128,
+        )
+    }
+
+    /// Prove `air` executed from `initial_state`, without public inputs
+    fn prove(
+        prover: &StarkProver<PrimeField64>,
+        air: &Air<PrimeField64>,
+        initial_state: &[PrimeField64],
+        num_steps: usize,
+    ) -> Result<StarkProof<PrimeField64>, ProofError> {
+        let witness = prover.generate_trace(air, initial_state, num_steps)?;
+        prover.prove(air, &PublicInputs::new(), &witness)
+    }
+
+    #[test]
+    fn test_prove_and_verify_fibonacci() {
+        let prover = StarkProver::<PrimeField64>::new(128);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        assert_eq!(proof.commitments.len(), 2);
+        assert_eq!(proof.ood_frame.trace_current.len(), 2);
+        assert_eq!(proof.fri_proof.layers[0].polynomial.len(), 15);
+        verifier.verify(&proof, &PublicInputs::new()).unwrap();
+    }
+
+    #[test]
+    fn test_prove_from_secret_witness() {
+        use crate::types::SecretWitness;
+        use zeroize::Zeroize;
+
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let air = fibonacci_air();
+        let initial_state = vec![PrimeField64::new(1), PrimeField64::new(1)];
+        let trace = prover.generate_trace(&air, &initial_state, 16).unwrap();
+        let mut witness = SecretWitness::new(trace.clone()).with_initial_state(initial_state);
+
+        // A secret witness proves the same as its trace
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        assert_eq!(proof.trace, prover.prove(&air, &PublicInputs::new(), &trace).unwrap().trace);
+        verifier.verify(&proof, &PublicInputs::new()).unwrap();
+
+        assert_eq!(format!("{:?}", witness), "SecretWitness(***HIDDEN***, length=16, registers=2)");
+        witness.zeroize();
+        let columns = witness.trace().columns.to_columns();
+        assert!(columns.iter().flatten().all(|&value| value == PrimeField64::zero()));
+        assert_eq!(witness.trace().length, 16);
+        assert_eq!(witness.initial_state(), [PrimeField64::zero(); 2]);
+    }
+
+    #[test]
+    fn test_tampered_ood_frame_fails() {
+        let prover = StarkProver::<PrimeField64>::new(128);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let mut proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        proof.ood_frame.trace_next[1] = proof.ood_frame.trace_next[1] + PrimeField64::one();
+        assert!(matches!(verifier.verify(&proof, &PublicInputs::new()), Err(VerificationError::OodConstraint)));
+    }
+
+    #[test]
+    fn test_verification_error_identifies_failed_check() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+
+        let mut tampered_trace = proof.clone();
+        let value = tampered_trace.trace.columns.value(1, 5);
+        tampered_trace.trace.columns.set(1, 5, value + PrimeField64::one());
+        assert!(matches!(
+            verifier.verify(&tampered_trace, &PublicInputs::new()),
+            Err(VerificationError::Transition { row: 4 })
+        ));
+
+        let mut tampered_remainder = proof;
+        tampered_remainder.fri_proof.final_polynomial[0] =
+            tampered_remainder.fri_proof.final_polynomial[0] + PrimeField64::one();
+        assert!(matches!(
+            verifier.verify(&tampered_remainder, &PublicInputs::new()),
+            Err(VerificationError::FriRemainder)
+        ));
+    }
+
+    #[test]
+    fn test_public_inputs_are_enforced() {
+        let air = fibonacci_air();
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        for prover in [
+            StarkProver::<PrimeField64>::with_params(128, 8, 16, 1),
+            StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).with_zk(true),
+        ] {
+            let verifier = StarkVerifier::<PrimeField64>::new(48);
+            let witness = prover.generate_trace(&air, &initial_state, 16).unwrap();
+            let output = witness.columns.value(0, 15);
+            let public_inputs = PublicInputs::new().assert(ColIdx(0), RowIdx(15), output);
+
+            let proof = prover.prove(&air, &public_inputs, &witness).unwrap();
+            verifier.verify(&proof, &public_inputs).unwrap();
+
+            // A different claimed output is rejected by both sides
+            let wrong = PublicInputs::new().assert(ColIdx(0), RowIdx(15), output + PrimeField64::one());
+            assert!(matches!(
+                prover.prove(&air, &wrong, &witness),
+                Err(ProofError::ConstraintError(_))
+            ));
+            assert!(verifier.verify(&proof, &wrong).is_err());
+            assert!(verifier.verify(&proof, &PublicInputs::new()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_boundary_conditions_checked_against_final_values() {
+        let air = fibonacci_air();
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 16).unwrap();
+        let output = witness.columns.value(1, 15);
+
+        let public_inputs = PublicInputs::new().assert_final(1, output);
+        let proof = prover.prove(&air, &public_inputs, &witness).unwrap();
+        verifier.verify(&proof, &public_inputs).unwrap();
+
+        // A transparent proof reports the violated assertion
+        let wrong = PublicInputs::new().assert_final(1, output + PrimeField64::one());
+        assert!(matches!(
+            verifier.verify(&proof, &wrong),
+            Err(VerificationError::Boundary { register: 1, step: 15 })
+        ));
+    }
+
+    #[test]
+    fn test_public_input_cells_declared_by_air() {
+        use crate::proof::public_inputs::PublicInputCell;
+
+        let air = fibonacci_air().with_public_inputs(&[PublicInputCell::at(0, 0), PublicInputCell::last(1)]);
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let witness = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 16).unwrap();
+        let output = witness.columns.value(1, 15);
+
+        let public_inputs = PublicInputs::from_values(&[PrimeField64::new(1), output]);
+        let proof = prover.prove(&air, &public_inputs, &witness).unwrap();
+        verifier.verify_with_air(&air, &proof, &public_inputs).unwrap();
+
+        // The values are enforced at their cells, and only with the AIR declaring them
+        let wrong = PublicInputs::from_values(&[PrimeField64::new(1), output + PrimeField64::one()]);
+        assert!(verifier.verify_with_air(&air, &proof, &wrong).is_err());
+        assert!(prover.prove(&air, &wrong, &witness).is_err());
+        assert!(verifier.verify(&proof, &public_inputs).is_err());
+        assert!(prover.prove(&air, &PublicInputs::from_values(&[output]), &witness).is_err());
+        assert_ne!(air.digest(), fibonacci_air().digest());
+    }
+
+    #[test]
+    fn test_prover_rejects_unsatisfied_boundary() {
+        let prover = StarkProver::<PrimeField64>::new(128);
+        let initial_state = [PrimeField64::new(2), PrimeField64::new(1)];
+
+        assert!(matches!(
+            prove(&prover, &fibonacci_air(), &initial_state, 16),
+            Err(ProofError::ConstraintError(_))
+        ));
+    }
+
+    #[test]
+    fn test_tampered_composition_commitment_fails() {
+        let prover = StarkProver::<PrimeField64>::new(128);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let mut proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        proof.commitments[1].root[0] ^= 1;
+        assert!(verifier.verify(&proof, &PublicInputs::new()).is_err());
+    }
+
+    #[test]
+    fn test_tampered_query_opening_fails() {
+        let prover = StarkProver::<PrimeField64>::new(128);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        assert_eq!(proof.openings.len(), 64);
+
+        let mut tampered_row = proof.clone();
+        tampered_row.openings[0].trace_row[0] = tampered_row.openings[0].trace_row[0] + PrimeField64::one();
+        assert!(matches!(
+            verifier.verify(&tampered_row, &PublicInputs::new()),
+            Err(VerificationError::MerklePath { query: 0, commitment: CommitmentRole::Trace })
+        ));
+
+        let mut tampered_path = proof.clone();
+        tampered_path.openings[3].composition_path[0][0] ^= 1;
+        assert!(matches!(
+            verifier.verify(&tampered_path, &PublicInputs::new()),
+            Err(VerificationError::MerklePath { query: 3, commitment: CommitmentRole::Composition })
+        ));
+
+        let mut tampered_root = proof.clone();
+        tampered_root.commitments[0].root[0] ^= 1;
+        assert!(verifier.verify(&tampered_root, &PublicInputs::new()).is_err());
+
+        let mut missing_opening = proof;
+        missing_opening.openings.pop();
+        assert!(matches!(
+            verifier.verify(&missing_opening, &PublicInputs::new()),
+            Err(VerificationError::MalformedProof(_))
+        ));
+    }
+
+    #[test]
+    fn test_verifier_enforces_minimum_security() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 32, 1);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        assert_eq!(prover.estimated_security_bits(), 60);
+
+        // The claimed security parameter is not trusted
+        assert_eq!(proof.metadata.parameters.security_parameter, 128);
+        StarkVerifier::<PrimeField64>::new(60).verify(&proof, &PublicInputs::new()).unwrap();
+        assert!(matches!(
+            StarkVerifier::<PrimeField64>::new(61).verify(&proof, &PublicInputs::new()),
+            Err(VerificationError::InsufficientSecurity { achieved: 60, required: 61 })
+        ));
+
+        // A weaker proof is rejected by the same verifier
+        let weak = prove(
+            &StarkProver::<PrimeField64>::with_params(128, 8, 8, 1),
+            &fibonacci_air(),
+            &initial_state,
+            16,
+        )
+        .unwrap();
+        assert!(matches!(
+            StarkVerifier::<PrimeField64>::new(60).verify(&weak, &PublicInputs::new()),
+            Err(VerificationError::InsufficientSecurity { achieved: 24, .. })
+        ));
+        StarkVerifier::<PrimeField64>::new(24).verify(&weak, &PublicInputs::new()).unwrap();
+
+        // Recorded parameters cannot be inflated
+        let mut inflated = weak;
+        inflated.metadata.parameters.num_queries = 32;
+        assert!(StarkVerifier::<PrimeField64>::new(24).verify(&inflated, &PublicInputs::new()).is_err());
+    }
+
+    /// Running product `acc[i + 1] = acc[i] * (alpha + main[0][i])` starting at one
+    #[derive(Debug)]
+    struct RunningProduct;
+
+    impl crate::air::AuxTraceSegment<PrimeField64> for RunningProduct {
+        fn width(&self) -> usize {
+            1
+        }
+
+        fn num_challenges(&self) -> usize {
+            1
+        }
+
+        fn num_constraints(&self) -> usize {
+            1
+        }
+
+        fn build(&self, main: &[Vec<PrimeField64>], challenges: &[PrimeField64]) -> Vec<Vec<PrimeField64>> {
+            let mut acc = PrimeField64::one();
+            let column = main[0]
+                .iter()
+                .map(|&value| {
+                    let current = acc;
+                    acc = acc * (challenges[0] + value);
+                    current
+                })
+                .collect();
+            vec![column]
+        }
+
+        fn evaluate_transition(
+            &self,
+            frame: &crate::air::AuxFrame<'_, PrimeField64>,
+            challenges: &[PrimeField64],
+        ) -> Vec<PrimeField64> {
+            vec![frame.aux_next[0] - frame.aux_current[0] * (challenges[0] + frame.main_current[0])]
+        }
+
+        fn boundary(&self, _challenges: &[PrimeField64]) -> Vec<BoundaryConstraint<PrimeField64>> {
+            vec![BoundaryConstraint::initial(0, PrimeField64::one())]
+        }
+    }
+
+    #[test]
+    fn test_auxiliary_segment_proof() {
+        let air = fibonacci_air().with_aux_segment(AuxSegment::new(RunningProduct));
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        for zero_knowledge in [false, true] {
+            let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).with_zk(zero_knowledge);
+            let verifier = StarkVerifier::<PrimeField64>::new(48);
+            let proof = prove(&prover, &air, &initial_state, 16).unwrap();
+            assert_eq!(proof.commitments.len(), 3);
+            assert_eq!(proof.ood_frame.trace_current.len(), 3);
+            assert_eq!(proof.trace.aux_segments.len(), usize::from(!zero_knowledge));
+            assert_eq!(prover.estimate_proof_size(&air, 16).unwrap(), proof.to_bytes().len());
+            verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+            // The segment definitions are needed to replay the challenges
+            assert!(matches!(
+                verifier.verify(&proof, &PublicInputs::new()),
+                Err(VerificationError::ParameterMismatch(_))
+            ));
+
+            let mut tampered = proof;
+            tampered.openings[0].aux_segments[0].row[0] =
+                tampered.openings[0].aux_segments[0].row[0] + PrimeField64::one();
+            assert!(matches!(
+                verifier.verify_with_air(&air, &tampered, &PublicInputs::new()),
+                Err(VerificationError::MerklePath { query: 0, commitment: CommitmentRole::AuxTrace(0) })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_zero_knowledge_proof() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).with_zk(true);
+        let verifier = StarkVerifier::<PrimeField64>::new(48).with_zk(true);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        assert!(proof.metadata.parameters.zero_knowledge);
+        assert!(proof.trace.columns.is_empty());
+        assert!(proof.openings.iter().all(|opening| opening.trace_salt.is_some() && opening.masking.is_some()));
+        assert!(proof.validate().is_ok());
+        verifier.verify(&proof, &PublicInputs::new()).unwrap();
+
+        // The ZK flag is bound to the transcript
+        let mut flipped = proof.clone();
+        flipped.metadata.parameters.zero_knowledge = false;
+        assert!(verifier.verify(&flipped, &PublicInputs::new()).is_err());
+
+        // A ZK proof may not carry the witness
+        let mut leaked = proof;
+        leaked.trace.columns = vec![vec![PrimeField64::one(); 16]; 2].into();
+        assert!(matches!(verifier.verify(&leaked, &PublicInputs::new()), Err(VerificationError::MalformedProof(_))));
+    }
+
+    #[test]
+    fn test_deterministic_proofs_are_reproducible() {
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let prove_seeded = |seed: [u8; 32]| {
+            let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1)
+                .with_zk(true)
+                .deterministic(seed);
+            prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap()
+        };
+
+        let proof = prove_seeded([7; 32]);
+        assert_eq!(proof.to_bytes(), prove_seeded([7; 32]).to_bytes());
+        assert_ne!(proof.commitments[0].root, prove_seeded([8; 32]).commitments[0].root);
+        StarkVerifier::<PrimeField64>::new(48).with_zk(true).verify(&proof, &PublicInputs::new()).unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_prove_async() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let witness = prover.generate_trace(&fibonacci_air(), &initial_state, 16).unwrap();
+
+        let proof = prover
+            .prove_async(fibonacci_air(), PublicInputs::new(), witness.clone())
+            .await
+            .unwrap();
+        StarkVerifier::<PrimeField64>::new(48).verify(&proof, &PublicInputs::new()).unwrap();
+
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        assert!(matches!(
+            prover.prove_observed(&fibonacci_air(), None, &PublicInputs::new(), &witness, &NoopObserver, &cancellation),
+            Err(ProofError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_zk_verifier_rejects_transparent_proof() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+
+        StarkVerifier::<PrimeField64>::new(48).verify(&proof, &PublicInputs::new()).unwrap();
+        assert!(matches!(
+            StarkVerifier::<PrimeField64>::new(48).with_zk(true).verify(&proof, &PublicInputs::new()),
+            Err(VerificationError::ParameterMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_proof_survives_serialization() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).with_zk(true);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        let decoded = StarkProof::<PrimeField64>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(decoded, proof);
+        verifier.verify(&decoded, &PublicInputs::new()).unwrap();
+    }
+
+    #[test]
+    fn test_recorded_air_is_bound_to_the_transcript() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).with_zk(true);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        proof.verify(&PublicInputs::new()).unwrap();
+
+        // The proof is checked against the AIR it carries, which the
+        // challenges depend on, so a changed claim fails
+        let mut tampered = proof.clone();
+        tampered.air.boundary.constraints[0].value += PrimeField64::one();
+        assert!(tampered.verify(&PublicInputs::new()).is_err());
+
+        let mut tampered = proof;
+        tampered.air.transition.coefficients[0][0] += PrimeField64::one();
+        assert!(tampered.verify(&PublicInputs::new()).is_err());
+    }
+
+    #[test]
+    fn test_detached_proof_verifies_with_supplied_air() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let air = fibonacci_air();
+        let proof = prove(&prover, &air, &initial_state, 16).unwrap();
+        proof.verify(&PublicInputs::new()).unwrap();
+
+        let detached = proof.clone().into_detached();
+        let bytes = detached.to_bytes();
+        assert!(bytes.len() < proof.to_bytes().len());
+        assert_eq!(DetachedProof::<PrimeField64>::from_bytes(&bytes).unwrap(), detached);
+        assert!(StarkProof::<PrimeField64>::from_bytes(&bytes).is_err());
+        detached.verify(&air, &PublicInputs::new()).unwrap();
+        assert_eq!(detached.clone().attach(proof.air.clone()), proof);
+
+        // The supplied AIR must be the one the proof was generated for
+        let other_air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        assert!(matches!(
+            detached.verify(&other_air, &PublicInputs::new()),
+            Err(VerificationError::AirDigest)
+        ));
+    }
+
+    #[test]
+    fn test_proof_bound_to_air_digest() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let air = fibonacci_air();
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &air, &initial_state, 16).unwrap();
+        assert_eq!(proof.metadata.air_digest, air.digest());
+        verifier.verify_with_air(&air, &proof, &PublicInputs::new()).unwrap();
+
+        // A different security parameter does not change the constraints but changes the digest
+        let mut other_air = air.clone();
+        other_air.security_parameter = 96;
+        assert_ne!(other_air.digest(), air.digest());
+        assert!(matches!(
+            verifier.verify_with_air(&other_air, &proof, &PublicInputs::new()),
+            Err(VerificationError::AirDigest)
+        ));
+
+        // The recorded digest is bound to the transcript
+        let mut tampered = proof;
+        tampered.metadata.air_digest = other_air.digest();
+        assert!(verifier.verify(&tampered, &PublicInputs::new()).is_err());
+    }
+
+    #[test]
+    fn test_proof_grinding() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1)
+            .deterministic([5; 32])
+            .with_proof_grinding(8);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+        assert_eq!(proof.metadata.parameters.grinding_bits, 8);
+
+        let verifier = StarkVerifier::<PrimeField64>::new(48).with_min_grinding(8);
+        verifier.verify(&proof, &PublicInputs::new()).unwrap();
+        assert!(matches!(
+            verifier.clone().with_min_grinding(12).verify(&proof, &PublicInputs::new()),
+            Err(VerificationError::ParameterMismatch(_))
+        ));
+
+        let mut tampered = proof;
+        tampered.metadata.grinding_nonce += 1;
+        assert!(matches!(
+            verifier.verify(&tampered, &PublicInputs::new()),
+            Err(VerificationError::Grinding { bits: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_extension_field_proofs() {
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        for degree in [2u32, 3] {
+            let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, degree).deterministic([6; 32]);
+            let proof = prove(&prover, &fibonacci_air(), &initial_state, 16).unwrap();
+            assert_eq!(proof.metadata.parameters.field_extension_degree, degree);
+            assert_eq!(proof.fri_proof.extension_degree, degree as usize);
+            assert_eq!(proof.ood_frame.composition.len(), degree as usize);
+            verifier.verify(&proof, &PublicInputs::new()).unwrap();
+            assert_eq!(StarkProof::from_bytes(&proof.to_bytes()).unwrap(), proof);
+
+            // Every coordinate of the composition is checked
+            let mut tampered = proof.clone();
+            let last = tampered.ood_frame.composition.len() - 1;
+            tampered.ood_frame.composition[last] = tampered.ood_frame.composition[last] + PrimeField64::one();
+            assert!(matches!(
+                verifier.verify(&tampered, &PublicInputs::new()),
+                Err(VerificationError::OodConstraint)
+            ));
+
+            // The recorded extension must match the FRI layers
+            let mut relabeled = proof;
+            relabeled.metadata.parameters.field_extension_degree = 1;
+            assert!(matches!(
+                verifier.verify(&relabeled, &PublicInputs::new()),
+                Err(VerificationError::ParameterMismatch(_))
+            ));
+        }
+
+        let unsupported = StarkProver::<PrimeField64>::with_params(128, 8, 16, 4);
+        assert!(matches!(
+            prove(&unsupported, &fibonacci_air(), &initial_state, 16),
+            Err(ProofError::UnsupportedExtension(4))
+        ));
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let air = fibonacci_air();
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        let short = prove(&prover, &air, &initial_state, 8).unwrap();
+        let long = prove(&prover, &air, &initial_state, 16).unwrap();
+        let mut tampered = long.clone();
+        tampered.ood_frame.composition[0] = tampered.ood_frame.composition[0] + PrimeField64::one();
+
+        // A valid proof of a different AIR is rejected by the batch
+        let other_air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let other = prove(&prover, &other_air, &initial_state, 16).unwrap();
+        verifier.verify(&other, &PublicInputs::new()).unwrap();
+
+        let public_inputs = vec![PublicInputs::new(); 4];
+        let results = verifier.verify_batch(&air, &[short, tampered, long, other], &public_inputs);
+        let results: Vec<bool> = results.iter().map(Result::is_ok).collect();
+        assert_eq!(results, vec![true, false, true, false]);
+        assert!(verifier.verify_batch(&air, &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_builder_picks_min_blowup() {
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+        let air = fibonacci_air();
+        assert_eq!(air.max_constraint_degree(), 1);
+        let prover = StarkProverBuilder::new(128).build(&air).unwrap();
+        assert_eq!(prover.blowup_factor(), 2);
+        let proof = prove(&prover, &air, &[PrimeField64::new(1), PrimeField64::new(1)], 16).unwrap();
+        verifier.verify(&proof, &PublicInputs::new()).unwrap();
+
+        let range = air.clone().with_aux_segment(crate::air::gadgets::range_check(0, 8).unwrap());
+        assert_eq!(range.max_constraint_degree(), 2);
+        assert_eq!(StarkProverBuilder::new(128).build(&range).unwrap().blowup_factor(), 2);
+
+        let mut cubic = air.clone();
+        cubic.constraints.push(crate::air::Constraint::new(
+            vec![PrimeField64::zero(); 4],
+            3,
+            crate::air::ConstraintType::Transition,
+        ));
+        assert!(cubic.validate().is_err());
+        cubic.max_constraint_degree = 3;
+        assert_eq!(StarkProverBuilder::new(128).build(&cubic).unwrap().blowup_factor(), 4);
+        assert!(StarkProverBuilder::new(128).with_blowup_factor(2).build(&cubic).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Prover Progress Observation
+//!
+//! This module lets applications follow a running proof, for example to drive
+//! progress bars or record per-stage metrics. A [`ProverObserver`] passed to
+//! [`StarkProver::prove_with_observer`](crate::proof::StarkProver::prove_with_observer)
+//! is notified when each [`ProverStage`] starts and ends, and of progress
+//! within stages that process a known number of units.
+//!
+//! With the `tracing` feature every stage also runs in a `prover_stage` span
+//! and reports its duration in a closing event, so the stages show up in any
+//! `tracing` subscriber without an observer.
+
+use crate::proof::cancellation::CancellationToken;
+use crate::proof::ProofError;
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Stages of proof generation, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProverStage {
+    /// Checking of the witness trace against the constraints
+    Trace,
+    /// Preprocessing of the AIR, unless a proving key is given, and
+    /// interpolation of the trace columns
+    Interpolation,
+    /// Evaluation of the trace polynomials over the extended domain
+    Lde,
+    /// Merkle commitment to the trace LDE
+    Commitments,
+    /// Construction and commitment of the constraint composition polynomial
+    Composition,
+    /// Out-of-domain sampling and FRI proof of the DEEP composition polynomial
+    Fri,
+    /// Opening of the commitments at the query positions
+    Queries,
+    /// Search for the proof-of-work nonce over the finished transcript
+    Grinding,
+}
+
+impl ProverStage {
+    /// All stages in execution order
+    pub const ALL: [ProverStage; 8] = [
+        ProverStage::Trace,
+        ProverStage::Interpolation,
+        ProverStage::Lde,
+        ProverStage::Commitments,
+        ProverStage::Composition,
+        ProverStage::Fri,
+        ProverStage::Queries,
+        ProverStage::Grinding,
+    ];
+}
+
+impl Display for ProverStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let name = match self {
+            ProverStage::Trace => "trace",
+            ProverStage::Interpolation => "interpolation",
+            ProverStage::Lde => "lde",
+            ProverStage::Commitments => "commitments",
+            ProverStage::Composition => "composition",
+            ProverStage::Fri => "fri",
+            ProverStage::Queries => "queries",
+            ProverStage::Grinding => "grinding",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Observer of proof generation
+///
+/// All methods default to doing nothing. Observers are shared by reference,
+/// so implementations that record state use interior mutability.
+pub trait ProverObserver: Send + Sync {
+    /// A stage is starting
+    fn on_stage_start(&self, _stage: ProverStage) {}
+
+    /// A stage completed after `elapsed`, which is zero without `std`
+    fn on_stage_end(&self, _stage: ProverStage, _elapsed: Duration) {}
+
+    /// `completed` of `total` units of a stage are done
+    fn on_progress(&self, _stage: ProverStage, _completed: usize, _total: usize) {}
+}
+
+/// Observer that ignores every notification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl ProverObserver for NoopObserver {}
+
+/// Time a stage started at, when `std` can read the clock
+#[derive(Clone, Copy)]
+struct StageStart {
+    /// Instant the stage started
+    #[cfg(feature = "std")]
+    instant: Instant,
+}
+
+impl StageStart {
+    /// Start of a stage starting now
+    fn now() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            instant: Instant::now(),
+        }
+    }
+
+    /// Time since the stage started
+    fn elapsed(self) -> Duration {
+        #[cfg(feature = "std")]
+        return self.instant.elapsed();
+        #[cfg(not(feature = "std"))]
+        Duration::ZERO
+    }
+}
+
+/// Tracks the current prover stage for an observer
+///
+/// Moving to the next stage ends the current one and checks the cancellation
+/// token, so stage boundaries double as cancellation points.
+pub(crate) struct StageTracker<'a> {
+    /// Observer to notify
+    observer: &'a dyn ProverObserver,
+    /// Cancellation token
+    cancellation: &'a CancellationToken,
+    /// Current stage and its start time
+    current: Option<(ProverStage, StageStart)>,
+    /// Span of the current stage
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::span::EnteredSpan>,
+}
+
+impl<'a> StageTracker<'a> {
+    /// Create a tracker that has not started any stage
+    pub(crate) fn new(observer: &'a dyn ProverObserver, cancellation: &'a CancellationToken) -> Self {
+        Self {
+            observer,
+            cancellation,
+            current: None,
+            #[cfg(feature = "tracing")]
+            span: None,
+        }
+    }
+
+    /// End the current stage and start `stage`, unless cancelled
+    pub(crate) fn begin(&mut self, stage: ProverStage) -> Result<(), ProofError> {
+        self.end();
+        self.cancellation.check()?;
+        self.observer.on_stage_start(stage);
+        #[cfg(feature = "tracing")]
+        {
+            self.span = Some(tracing::info_span!("prover_stage", stage = %stage).entered());
+        }
+        self.current = Some((stage, StageStart::now()));
+        Ok(())
+    }
+
+    /// Fail if proving was cancelled in the middle of a stage
+    pub(crate) fn check(&self) -> Result<(), ProofError> {
+        self.cancellation.check()
+    }
+
+    /// Token checked at every cancellation point
+    pub(crate) fn cancellation(&self) -> &CancellationToken {
+        self.cancellation
+    }
+
+    /// Report progress within the current stage
+    pub(crate) fn progress(&self, completed: usize, total: usize) {
+        if let Some((stage, _)) = self.current {
+            self.observer.on_progress(stage, completed, total);
+        }
+    }
+
+    /// End the current stage, if any
+    pub(crate) fn end(&mut self) {
+        if let Some((stage, started)) = self.current.take() {
+            let elapsed = started.elapsed();
+            #[cfg(feature = "tracing")]
+            if let Some(span) = self.span.take() {
+                tracing::debug!(elapsed_us = elapsed.as_micros() as u64, "stage finished");
+                drop(span);
+            }
+            self.observer.on_stage_end(stage, elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::StarkProver;
+    use crate::types::field::PrimeField64;
+    use std::sync::Mutex;
+
+    /// Observer recording every notification
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<(&'static str, ProverStage)>>,
+        progress: Mutex<Vec<(ProverStage, usize, usize)>>,
+    }
+
+    impl ProverObserver for Recorder {
+        fn on_stage_start(&self, stage: ProverStage) {
+            self.events.lock().unwrap().push(("start", stage));
+        }
+
+        fn on_stage_end(&self, stage: ProverStage, _elapsed: Duration) {
+            self.events.lock().unwrap().push(("end", stage));
+        }
+
+        fn on_progress(&self, stage: ProverStage, completed: usize, total: usize) {
+            self.progress.lock().unwrap().push((stage, completed, total));
+        }
+    }
+
+    #[test]
+    fn test_observer_sees_every_stage() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let recorder = Recorder::default();
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let witness = prover
+            .generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 8)
+            .unwrap();
+        prover
+            .prove_with_observer(&air, &PublicInputs::new(), &witness, &recorder)
+            .unwrap();
+
+        let expected: Vec<_> = ProverStage::ALL
+            .iter()
+            .flat_map(|&stage| [("start", stage), ("end", stage)])
+            .collect();
+        assert_eq!(*recorder.events.lock().unwrap(), expected);
+
+        let progress = recorder.progress.lock().unwrap();
+        assert_eq!(progress.last(), Some(&(ProverStage::Queries, 16, 16)));
+        assert!(progress.iter().all(|&(_, completed, total)| completed <= total));
+    }
+}
@@ -0,0 +1,169 @@
+//! Expected Proof Parameters
+//!
+//! A verifier that only enforces a minimum security level accepts any
+//! parameters that reach it, whatever the prover chose to record. Deployments
+//! that fix their proof configuration pin it instead: [`ExpectedParams`] lists
+//! the blowup factors, query counts, extension degrees and grinding bits a
+//! verifier configured with
+//! [`StarkVerifier::with_expected_params`](crate::proof::StarkVerifier::with_expected_params)
+//! accepts, as well as the versions of the prover it accepts proofs from.
+//! Proofs over a different field are rejected with or without a list.
+//!
+//! Commitments and the transcript always hash with SHA-256, so there is no
+//! hash function to pin.
+
+use crate::prelude::*;
+use crate::proof::verification::VerificationError;
+use crate::types::stark::{FieldId, ProofMetadata};
+use crate::types::FieldElement;
+use core::fmt::Display;
+
+/// Allow-list of proof parameters
+///
+/// An empty list accepts any value of that parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExpectedParams {
+    /// Accepted blowup factors
+    pub blowup_factors: Vec<usize>,
+    /// Accepted query counts
+    pub num_queries: Vec<usize>,
+    /// Accepted field extension degrees
+    pub field_extension_degrees: Vec<u32>,
+    /// Accepted proof-of-work grinding bits
+    pub grinding_bits: Vec<u32>,
+    /// Accepted versions of the prover
+    pub prover_versions: Vec<String>,
+}
+
+impl ExpectedParams {
+    /// Accept any parameters over the verifier's field
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pin the parameters a prover is configured with
+    pub fn exact(blowup_factor: usize, num_queries: usize, field_extension_degree: u32, grinding_bits: u32) -> Self {
+        Self {
+            blowup_factors: vec![blowup_factor],
+            num_queries: vec![num_queries],
+            field_extension_degrees: vec![field_extension_degree],
+            grinding_bits: vec![grinding_bits],
+            prover_versions: Vec::new(),
+        }
+    }
+
+    /// Accept only the given blowup factors
+    pub fn with_blowup_factors(mut self, blowup_factors: &[usize]) -> Self {
+        self.blowup_factors = blowup_factors.to_vec();
+        self
+    }
+
+    /// Accept only the given query counts
+    pub fn with_num_queries(mut self, num_queries: &[usize]) -> Self {
+        self.num_queries = num_queries.to_vec();
+        self
+    }
+
+    /// Accept only the given field extension degrees
+    pub fn with_field_extension_degrees(mut self, degrees: &[u32]) -> Self {
+        self.field_extension_degrees = degrees.to_vec();
+        self
+    }
+
+    /// Accept only the given grinding bits
+    pub fn with_grinding_bits(mut self, bits: &[u32]) -> Self {
+        self.grinding_bits = bits.to_vec();
+        self
+    }
+
+    /// Accept only proofs generated by the given prover versions
+    pub fn with_prover_versions(mut self, versions: &[&str]) -> Self {
+        self.prover_versions = versions.iter().map(|version| version.to_string()).collect();
+        self
+    }
+
+    /// Check the parameters recorded in a proof over `F` against the allow-list
+    pub fn check<F: FieldElement>(&self, metadata: &ProofMetadata) -> Result<(), VerificationError> {
+        if metadata.field != FieldId::of::<F>() {
+            return Err(VerificationError::ParameterMismatch(format!(
+                "proof over {}, expected {}",
+                metadata.field,
+                FieldId::of::<F>()
+            )));
+        }
+        check_allowed("prover version", metadata.prover_version.clone(), &self.prover_versions)?;
+        check_allowed("blowup factor", metadata.parameters.blowup_factor, &self.blowup_factors)?;
+        check_allowed("query count", metadata.parameters.num_queries, &self.num_queries)?;
+        check_allowed(
+            "field extension degree",
+            metadata.parameters.field_extension_degree,
+            &self.field_extension_degrees,
+        )?;
+        check_allowed("grinding bits", metadata.parameters.grinding_bits, &self.grinding_bits)
+    }
+}
+
+/// Reject `value` unless `allowed` is empty or contains it
+fn check_allowed<T: PartialEq + Display>(name: &str, value: T, allowed: &[T]) -> Result<(), VerificationError> {
+    if allowed.is_empty() || allowed.contains(&value) {
+        return Ok(());
+    }
+    Err(VerificationError::ParameterMismatch(format!("{} {} is not accepted", name, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_expected_params_pin_proof_parameters() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).deterministic([2; 32]);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let witness = prover.generate_trace(&air, &initial_state, 16).unwrap();
+        let proof = prover.prove(&air, &PublicInputs::new(), &witness).unwrap();
+        let verify = |params: ExpectedParams| {
+            StarkVerifier::<PrimeField64>::new(48)
+                .with_expected_params(params)
+                .verify(&proof, &PublicInputs::new())
+        };
+
+        verify(ExpectedParams::new()).unwrap();
+        verify(ExpectedParams::exact(8, 16, 1, 0)).unwrap();
+        verify(ExpectedParams::new().with_blowup_factors(&[4, 8])).unwrap();
+        verify(ExpectedParams::new().with_prover_versions(&[crate::VERSION])).unwrap();
+
+        // Parameters reaching the security level are still rejected if not listed
+        for params in [
+            ExpectedParams::new().with_blowup_factors(&[16]),
+            ExpectedParams::new().with_num_queries(&[32]),
+            ExpectedParams::new().with_field_extension_degrees(&[2, 3]),
+            ExpectedParams::new().with_grinding_bits(&[8]),
+            ExpectedParams::new().with_prover_versions(&["0.0.1"]),
+        ] {
+            assert!(matches!(verify(params), Err(VerificationError::ParameterMismatch(_))));
+        }
+
+        let mut other_field = proof.clone();
+        other_field.metadata.field = FieldId::Other(7);
+        assert!(matches!(
+            StarkVerifier::<PrimeField64>::new(48)
+                .with_expected_params(ExpectedParams::new())
+                .verify(&other_field, &PublicInputs::new()),
+            Err(VerificationError::ParameterMismatch(_))
+        ));
+        assert!(matches!(
+            StarkVerifier::<PrimeField64>::new(48).verify(&other_field, &PublicInputs::new()),
+            Err(VerificationError::ParameterMismatch(_))
+        ));
+    }
+}
@@ -0,0 +1,220 @@
+//! AIR Preprocessing
+//!
+//! Much of the work of a proof depends only on the AIR, the trace length and
+//! the prover parameters, not on the witness: the constraints instantiated
+//! for the trace length, the trace domain prepared for interpolation and the
+//! transition zerofier inverses over the LDE domain. High-throughput provers
+//! derive them once with [`StarkProver::preprocess`] and reuse the resulting
+//! [`ProvingKey`] for every proof of that AIR and length.
+//!
+//! A [`VerifyingKey`] likewise holds the AIR digest and the instantiated
+//! constraints a verifier checks every proof against, together with a
+//! commitment to the AIR's fixed columns (see [`fixed`](crate::air::fixed)).
+//! The AIRs of this crate have no periodic columns, so there are no periodic
+//! column extensions to cache.
+
+use crate::prelude::*;
+use crate::air::Air;
+use crate::proof::composition::{composition_length, CompositionConstraints, CompositionDomain, InterpolationDomain, TraceLde};
+use crate::proof::ProofError;
+use crate::proof::StarkProver;
+use crate::types::{Digest, FieldElement};
+
+/// Witness-independent data for proving an AIR at one trace length
+///
+/// Created with [`StarkProver::preprocess`] and used with
+/// [`StarkProver::prove_with_key`]. A key is tied to the blowup factor and,
+/// in zero-knowledge mode, the query count of the prover that created it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvingKey<F: FieldElement> {
+    /// Verifier half of the key
+    verifying_key: VerifyingKey<F>,
+    /// Length of the interpolated trace, including random rows in zero-knowledge mode
+    lde_trace_length: usize,
+    /// Blowup factor of the LDE domain
+    blowup_factor: usize,
+    /// Trace domain prepared for interpolation
+    trace_domain: InterpolationDomain<F>,
+    /// Zerofier inverses and interpolation points of the composition polynomial
+    composition_domain: CompositionDomain<F>,
+}
+
+impl<F: FieldElement> ProvingKey<F> {
+    /// AIR the key was preprocessed for
+    pub fn air(&self) -> &Air<F> {
+        self.verifying_key.air()
+    }
+
+    /// Trace length the key was preprocessed for
+    pub fn trace_length(&self) -> usize {
+        self.verifying_key.trace_length()
+    }
+
+    /// Length of the interpolated trace
+    pub fn lde_trace_length(&self) -> usize {
+        self.lde_trace_length
+    }
+
+    /// Blowup factor of the LDE domain
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    /// Trace domain prepared for interpolation
+    pub fn trace_domain(&self) -> &InterpolationDomain<F> {
+        &self.trace_domain
+    }
+
+    /// Zerofier inverses and interpolation points of the composition polynomial
+    pub fn composition_domain(&self) -> &CompositionDomain<F> {
+        &self.composition_domain
+    }
+
+    /// Verifier half of the key
+    pub fn verifying_key(&self) -> &VerifyingKey<F> {
+        &self.verifying_key
+    }
+}
+
+/// Data for verifying proofs of an AIR at one trace length
+///
+/// Used with [`StarkVerifier::verify_with_key`](crate::proof::StarkVerifier::verify_with_key).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey<F: FieldElement> {
+    /// AIR the key was created for
+    air: Air<F>,
+    /// Digest of the AIR proofs are bound to
+    air_digest: Digest,
+    /// Commitment to the fixed columns of the AIR
+    fixed_commitment: [u8; 32],
+    /// Trace length the constraints are instantiated for
+    trace_length: usize,
+    /// AIR constraints instantiated for the trace length
+    constraints: CompositionConstraints<F>,
+}
+
+impl<F: FieldElement> VerifyingKey<F> {
+    /// Instantiate the AIR for proofs of the given trace length
+    pub fn new(air: &Air<F>, trace_length: usize) -> Result<Self, ProofError> {
+        Ok(Self {
+            air: air.clone(),
+            air_digest: air.digest(),
+            fixed_commitment: air.fixed_commitment(),
+            trace_length,
+            constraints: CompositionConstraints::from_air(air, trace_length)?,
+        })
+    }
+
+    /// AIR the key was created for
+    pub fn air(&self) -> &Air<F> {
+        &self.air
+    }
+
+    /// Digest of the AIR
+    pub fn air_digest(&self) -> &Digest {
+        &self.air_digest
+    }
+
+    /// Commitment to the fixed columns of the AIR
+    pub fn fixed_commitment(&self) -> &[u8; 32] {
+        &self.fixed_commitment
+    }
+
+    /// Trace length the key was created for
+    pub fn trace_length(&self) -> usize {
+        self.trace_length
+    }
+
+    /// AIR constraints instantiated for the trace length
+    pub fn constraints(&self) -> &CompositionConstraints<F> {
+        &self.constraints
+    }
+}
+
+impl<F: FieldElement> StarkProver<F> {
+    /// Preprocess an AIR for proofs of the given trace length
+    ///
+    /// The key can be reused for any number of proofs by this prover, or by
+    /// another prover with the same blowup factor, zero-knowledge mode and
+    /// query count.
+    pub fn preprocess(&self, air: &Air<F>, trace_length: usize) -> Result<ProvingKey<F>, ProofError> {
+        let verifying_key = VerifyingKey::new(air, trace_length)?;
+        let lde_trace_length = self.lde_trace_length(trace_length);
+        let domain_size = lde_trace_length
+            .checked_mul(self.blowup_factor)
+            .ok_or(ProofError::InvalidTrace)?;
+        let length = composition_length(trace_length, lde_trace_length, !air.aux_segments.is_empty(), air.window());
+        Ok(ProvingKey {
+            verifying_key,
+            lde_trace_length,
+            blowup_factor: self.blowup_factor,
+            trace_domain: TraceLde::trace_domain(lde_trace_length)?,
+            composition_domain: CompositionDomain::new(trace_length, lde_trace_length, domain_size, length)?,
+        })
+    }
+
+    /// Check that a proving key was preprocessed for this prover and trace length
+    pub(crate) fn check_key(&self, key: &ProvingKey<F>, trace_length: usize) -> Result<(), ProofError> {
+        if key.trace_length() != trace_length {
+            return Err(ProofError::KeyMismatch(format!(
+                "key preprocessed for trace length {}, got {}",
+                key.trace_length(),
+                trace_length
+            )));
+        }
+        if key.blowup_factor != self.blowup_factor || key.lde_trace_length != self.lde_trace_length(trace_length) {
+            return Err(ProofError::KeyMismatch(
+                "key preprocessed for different prover parameters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::{BoundaryConditions, BoundaryConstraint, TransitionFunction};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::StarkVerifier;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_proving_key_is_reused() {
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).deterministic([3; 32]);
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let key = prover.preprocess(&air, 16).unwrap();
+        let verifier = StarkVerifier::<PrimeField64>::new(48);
+
+        // Proofs with the key match proofs without it and verify with the verifying key
+        let witness = prover.generate_trace(&air, &initial_state, 16).unwrap();
+        let proof = prover.prove_with_key(&key, &PublicInputs::new(), &witness).unwrap();
+        assert_eq!(proof, prover.prove(&air, &PublicInputs::new(), &witness).unwrap());
+        verifier.verify_with_key(key.verifying_key(), &proof, &PublicInputs::new()).unwrap();
+
+        let other_state = [PrimeField64::new(1), PrimeField64::new(2)];
+        let witness = prover.generate_trace(&air, &other_state, 16).unwrap();
+        let proof = prover.prove_with_key(&key, &PublicInputs::new(), &witness).unwrap();
+        verifier.verify_with_key(key.verifying_key(), &proof, &PublicInputs::new()).unwrap();
+
+        // Keys are tied to the trace length and prover parameters
+        let longer = prover.generate_trace(&air, &initial_state, 32).unwrap();
+        assert!(matches!(
+            prover.prove_with_key(&key, &PublicInputs::new(), &longer),
+            Err(ProofError::KeyMismatch(_))
+        ));
+        let other_prover = StarkProver::<PrimeField64>::with_params(128, 16, 16, 1);
+        assert!(matches!(
+            other_prover.prove_with_key(&key, &PublicInputs::new(), &witness),
+            Err(ProofError::KeyMismatch(_))
+        ));
+        let longer_proof = prover.prove(&air, &PublicInputs::new(), &longer).unwrap();
+        assert!(verifier.verify_with_key(key.verifying_key(), &longer_proof, &PublicInputs::new()).is_err());
+    }
+}
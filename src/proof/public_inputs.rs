@@ -0,0 +1,246 @@
+//! Public Inputs
+//!
+//! Public inputs are the values a statement is proven about, such as the
+//! initial state of a computation or its claimed output. They are supplied
+//! separately to the prover and the verifier rather than read from the proof:
+//! both absorb them into the transcript and enforce them as boundary
+//! constraints, so a proof only verifies against the public inputs it was
+//! generated for.
+//!
+//! Values can be asserted at a fixed row or, for outputs, on the last row of
+//! the trace. An AIR that declares its public input cells (see
+//! [`Air::with_public_inputs`](crate::air::Air::with_public_inputs)) takes
+//! the bare values instead and places them itself, so the transcript absorbs
+//! them in the order the AIR declares.
+
+use crate::prelude::*;
+use crate::types::encoding::{ByteReader, ByteWriter, Encode};
+use crate::types::{ColIdx, FieldElement, RowIdx, TypeError};
+use crate::proof::composition::BoundaryAssertion;
+use crate::proof::transcript::{labels, Transcript};
+use serde::{Deserialize, Serialize};
+
+/// Trace cell an AIR asserts a public input at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PublicInputCell {
+    /// A register at a fixed row
+    At {
+        /// Register (column) index
+        register: usize,
+        /// Trace row
+        row: usize,
+    },
+    /// A register on the last row, whatever the trace length
+    Final {
+        /// Register (column) index
+        register: usize,
+    },
+}
+
+impl PublicInputCell {
+    /// Cell of `register` at trace row `row`
+    pub fn at(register: usize, row: usize) -> Self {
+        Self::At { register, row }
+    }
+
+    /// Cell of `register` on the last trace row
+    pub fn last(register: usize) -> Self {
+        Self::Final { register }
+    }
+
+    /// Register (column) index of the cell
+    pub fn register(&self) -> usize {
+        match *self {
+            Self::At { register, .. } | Self::Final { register } => register,
+        }
+    }
+}
+
+impl Encode for PublicInputCell {
+    fn encode(&self, writer: &mut ByteWriter) {
+        match *self {
+            Self::At { register, row } => {
+                writer.write_u8(0);
+                writer.write_usize(register);
+                writer.write_usize(row);
+            }
+            Self::Final { register } => {
+                writer.write_u8(1);
+                writer.write_usize(register);
+            }
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        match reader.read_u8()? {
+            0 => Ok(Self::At {
+                register: reader.read_usize()?,
+                row: reader.read_usize()?,
+            }),
+            1 => Ok(Self::Final {
+                register: reader.read_usize()?,
+            }),
+            tag => Err(TypeError::UnknownTag { name: "public input cell", tag }),
+        }
+    }
+}
+
+/// Public inputs of a proof, as register values asserted at trace rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicInputs<F: FieldElement> {
+    /// Register values asserted at fixed rows
+    assertions: Vec<BoundaryAssertion<F>>,
+    /// Register values asserted on the last row, whatever the trace length
+    final_values: Vec<(usize, F)>,
+    /// Values placed at the public input cells the AIR declares
+    values: Vec<F>,
+}
+
+impl<F: FieldElement> PublicInputs<F> {
+    /// Create public inputs without any assertions
+    pub fn new() -> Self {
+        Self {
+            assertions: Vec::new(),
+            final_values: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Public inputs given as values of the cells the AIR declares
+    ///
+    /// `values[i]` is placed at the `i`-th cell passed to
+    /// [`Air::with_public_inputs`](crate::air::Air::with_public_inputs).
+    pub fn from_values(values: &[F]) -> Self {
+        Self {
+            values: values.to_vec(),
+            ..Self::new()
+        }
+    }
+
+    /// Public inputs asserting every register of the first trace row
+    pub fn from_initial_state(initial_state: &[F]) -> Self {
+        initial_state
+            .iter()
+            .enumerate()
+            .fold(Self::new(), |inputs, (register, &value)| inputs.assert(ColIdx(register), RowIdx(0), value))
+    }
+
+    /// Assert that `register` holds `value` at trace row `row`
+    pub fn assert(mut self, register: ColIdx, row: RowIdx, value: F) -> Self {
+        self.assertions.push(BoundaryAssertion { register: register.0, row: row.0, value });
+        self
+    }
+
+    /// Assert that `register` holds `value` on the last trace row
+    pub fn assert_final(mut self, register: usize, value: F) -> Self {
+        self.final_values.push((register, value));
+        self
+    }
+
+    /// Register values asserted at fixed rows, in insertion order
+    pub fn assertions(&self) -> &[BoundaryAssertion<F>] {
+        &self.assertions
+    }
+
+    /// Register values asserted on the last row, in insertion order
+    pub fn final_values(&self) -> &[(usize, F)] {
+        &self.final_values
+    }
+
+    /// Values of the public input cells the AIR declares, in declaration order
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+
+    /// Whether no values are asserted
+    pub fn is_empty(&self) -> bool {
+        self.assertions.is_empty() && self.final_values.is_empty() && self.values.is_empty()
+    }
+
+    /// Place the values at `cells`, ahead of the explicit assertions
+    ///
+    /// Fails unless there is exactly one value per cell. The result asserts
+    /// every value at its cell and holds no bare values.
+    pub fn bind(&self, cells: &[PublicInputCell]) -> Result<Self, String> {
+        if self.values.len() != cells.len() {
+            return Err(format!("{} public input values for {} cells", self.values.len(), cells.len()));
+        }
+        let mut bound = Self::new();
+        for (cell, &value) in cells.iter().zip(&self.values) {
+            bound = match *cell {
+                PublicInputCell::At { register, row } => bound.assert(ColIdx(register), RowIdx(row), value),
+                PublicInputCell::Final { register } => bound.assert_final(register, value),
+            };
+        }
+        bound.assertions.extend_from_slice(&self.assertions);
+        bound.final_values.extend_from_slice(&self.final_values);
+        Ok(bound)
+    }
+
+    /// All assertions placed on a trace of the given length
+    pub fn resolve(&self, trace_length: usize) -> Vec<BoundaryAssertion<F>> {
+        let last_row = trace_length.saturating_sub(1);
+        self.assertions
+            .iter()
+            .copied()
+            .chain(self.final_values.iter().map(|&(register, value)| BoundaryAssertion {
+                register,
+                row: last_row,
+                value,
+            }))
+            .collect()
+    }
+
+    /// Bind the public inputs to a transcript
+    pub fn absorb(&self, transcript: &mut Transcript) {
+        transcript.absorb_u64(labels::PUBLIC_INPUTS, self.assertions.len() as u64);
+        for assertion in &self.assertions {
+            transcript.absorb_u64(labels::PUBLIC_INPUTS, assertion.register as u64);
+            transcript.absorb_u64(labels::PUBLIC_INPUTS, assertion.row as u64);
+            transcript.absorb_field_element(labels::PUBLIC_INPUTS, &assertion.value);
+        }
+        transcript.absorb_u64(labels::PUBLIC_INPUTS, self.final_values.len() as u64);
+        for (register, value) in &self.final_values {
+            transcript.absorb_u64(labels::PUBLIC_INPUTS, *register as u64);
+            transcript.absorb_field_element(labels::PUBLIC_INPUTS, value);
+        }
+        transcript.absorb_u64(labels::PUBLIC_INPUTS, self.values.len() as u64);
+        for value in &self.values {
+            transcript.absorb_field_element(labels::PUBLIC_INPUTS, value);
+        }
+    }
+}
+
+impl<F: FieldElement> Default for PublicInputs<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_public_inputs_bind_transcript() {
+        let inputs = PublicInputs::from_initial_state(&[PrimeField64::new(1), PrimeField64::new(2)]);
+        assert_eq!(inputs.assertions().len(), 2);
+        assert_eq!(inputs.assertions()[1], BoundaryAssertion { register: 1, row: 0, value: PrimeField64::new(2) });
+
+        let state = |inputs: &PublicInputs<PrimeField64>| {
+            let mut transcript = Transcript::new(labels::PROTOCOL);
+            inputs.absorb(&mut transcript);
+            transcript.state()
+        };
+        let other = PublicInputs::from_initial_state(&[PrimeField64::new(1), PrimeField64::new(3)]);
+        assert_ne!(state(&inputs), state(&other));
+        assert_eq!(state(&inputs), state(&inputs.clone()));
+
+        // Final values are bound separately from fixed-row assertions
+        let last_row = PublicInputs::new().assert(ColIdx(0), RowIdx(7), PrimeField64::new(5));
+        let final_value = PublicInputs::new().assert_final(0, PrimeField64::new(5));
+        assert_eq!(final_value.resolve(8), last_row.resolve(8));
+        assert_ne!(state(&final_value), state(&last_row));
+    }
+}
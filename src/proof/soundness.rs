@@ -0,0 +1,59 @@
+//! Proof Soundness Estimation
+//!
+//! This module estimates the soundness of a proof from its parameters, so
+//! provers can report and verifiers can enforce a security level instead of
+//! relying on the level a proof claims for itself.
+//!
+//! ## Estimate
+//!
+//! The estimate is the minimum of two bounds:
+//!
+//! - **Query Bound**: each query catches a cheating prover with probability at
+//!   least `1 - 1/blowup`, giving `num_queries * log2(blowup)` bits under the
+//!   usual conjecture on the proximity gap of Reed–Solomon codes
+//! - **Field Bound**: out-of-domain and random-combination challenges are drawn
+//!   from the field, which bounds the security by `log2(|F|)` bits per
+//!   extension degree
+//!
+//! With the 61-bit base field and no extension, proofs top out around 60 bits.
+
+/// Estimate the conjectured security of a proof in bits
+///
+/// Returns zero for a blowup factor below two, since such a proof gains
+/// nothing from its queries.
+pub fn estimate_security_bits(
+    field_modulus: u64,
+    field_extension_degree: u32,
+    blowup_factor: usize,
+    num_queries: usize,
+) -> u32 {
+    if blowup_factor < 2 || field_modulus < 2 {
+        return 0;
+    }
+
+    let query_bits = u32::try_from(num_queries)
+        .unwrap_or(u32::MAX)
+        .saturating_mul(blowup_factor.ilog2());
+    let field_bits = field_modulus.ilog2().saturating_mul(field_extension_degree);
+
+    query_bits.min(field_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_security_estimate() {
+        let modulus = PrimeField64::MODULUS;
+
+        // Query-limited, then field-limited
+        assert_eq!(estimate_security_bits(modulus, 1, 8, 16), 48);
+        assert_eq!(estimate_security_bits(modulus, 1, 16, 64), 60);
+        assert_eq!(estimate_security_bits(modulus, 2, 16, 64), 120);
+
+        assert_eq!(estimate_security_bits(modulus, 1, 1, 64), 0);
+        assert_eq!(estimate_security_bits(modulus, 1, 8, usize::MAX), 60);
+    }
+}
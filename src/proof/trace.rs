@@ -1,15 +1,578 @@
 //! Execution Trace Generation
-//! 
+//!
 //! This module provides efficient execution trace generation for STARK proofs.
+//!
+//! ## Segmented Generation
+//!
+//! A linear transition `s_{k+1} = M * s_k` lets any state be reached directly
+//! as `s_k = M^k * s_0`. The trace is split into fixed-length segments whose
+//! starting states are computed with the jump matrix `M^L`; the segments are
+//! then independent and, with the `parallel` feature, generated concurrently.
+//!
+//! ## Streaming Construction
+//!
+//! [`TraceBuilder`] accepts rows one at a time and stores them directly in
+//! column-major form, optionally folding each row into a running digest, so
+//! applications never hold a row-major copy next to the final trace. With the
+//! `mmap` feature, [`TraceBuilder::mapped`] writes the rows to memory-mapped
+//! files instead, for traces that do not fit in memory.
+//!
+//! The same builder assembles traces column by column: named columns are
+//! added whole, filled from a closure or patched cell by cell, and
+//! [`TraceBuilder::build`] checks that they form a trace before returning it.
 
-use crate::types::FieldElement;
+use crate::prelude::*;
+use crate::air::FixedColumn;
+use crate::types::{ColIdx, FieldElement, RowIdx, TraceStorage};
+use crate::types::stark::ExecutionTrace;
+use crate::proof::ProofError;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Default number of rows per independently generated trace segment
+pub const DEFAULT_SEGMENT_LENGTH: usize = 1 << 10;
+
+/// Execution trace builder
+///
+/// Rows are appended one at a time and written straight into column storage.
+/// With [`TraceBuilder::with_digest`] every row is also absorbed into a
+/// running SHA-256 digest as it arrives, and the trace can only grow by rows.
+/// Otherwise columns can also be added with [`add_column`](Self::add_column)
+/// and written with [`set`](Self::set) and
+/// [`fill_column_with`](Self::fill_column_with).
+#[derive(Debug, Clone)]
+pub struct TraceBuilder<F: FieldElement> {
+    /// Column-major storage
+    columns: TraceStorage<F>,
+    /// Names of the columns, `None` for columns created unnamed
+    names: Vec<Option<String>>,
+    /// Number of rows of the trace
+    length: usize,
+    /// Running digest of the rows, if enabled
+    digest: Option<Sha256>,
+}
+
+impl<F: FieldElement> TraceBuilder<F> {
+    /// Create a builder for a trace with the given number of registers
+    pub fn new(num_registers: usize) -> Self {
+        Self::with_capacity(num_registers, 0)
+    }
+
+    /// Create a builder with room for `num_rows` rows
+    pub fn with_capacity(num_registers: usize, num_rows: usize) -> Self {
+        Self {
+            columns: TraceStorage::Memory(vec![Vec::with_capacity(num_rows); num_registers]),
+            names: vec![None; num_registers],
+            length: 0,
+            digest: None,
+        }
+    }
+
+    /// Create a builder writing up to `num_rows` rows to memory-mapped files
+    ///
+    /// Only the pages being written are resident, so the trace can be larger
+    /// than the available memory. Pushing more than `num_rows` rows fails.
+    #[cfg(feature = "mmap")]
+    pub fn mapped(num_registers: usize, num_rows: usize) -> Result<Self, ProofError> {
+        let columns = TraceStorage::mapped(num_registers, num_rows)
+            .map_err(|error| ProofError::StorageError(error.to_string()))?;
+        Ok(Self {
+            columns,
+            names: vec![None; num_registers],
+            length: 0,
+            digest: None,
+        })
+    }
+
+    /// Hash rows into a running digest as they are pushed
+    pub fn with_digest(mut self) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update((self.columns.len() as u64).to_le_bytes());
+        self.digest = Some(hasher);
+        self
+    }
+
+    /// Append a row
+    pub fn push_row(&mut self, row: &[F]) -> Result<(), ProofError> {
+        if row.len() != self.columns.len() {
+            return Err(ProofError::InvalidTrace);
+        }
+
+        match &mut self.columns {
+            TraceStorage::Memory(columns) => {
+                for (column, &value) in columns.iter_mut().zip(row) {
+                    column.push(value);
+                }
+            }
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => {
+                if columns.first().is_some_and(|column| self.length == column.len()) {
+                    return Err(ProofError::StorageError("mapped trace is full".to_string()));
+                }
+                for (column, &value) in columns.iter_mut().zip(row) {
+                    column.write(self.length, &[value]);
+                }
+            }
+        }
+        if let Some(hasher) = &mut self.digest {
+            for value in row {
+                hasher.update(value.to_bytes());
+            }
+        }
+        self.length += 1;
+        Ok(())
+    }
+
+    /// Append every row of an iterator
+    pub fn extend_rows<I, R>(&mut self, rows: I) -> Result<(), ProofError>
+    where
+        I: IntoIterator<Item = R>,
+        R: AsRef<[F]>,
+    {
+        for row in rows {
+            self.push_row(row.as_ref())?;
+        }
+        Ok(())
+    }
+
+    /// Append `num_rows` rows produced by a closure
+    ///
+    /// The closure receives the row index and the previous row (empty for the
+    /// first row of the trace) and returns the next row.
+    pub fn generate_rows<G>(&mut self, num_rows: usize, mut generator: G) -> Result<(), ProofError>
+    where
+        G: FnMut(usize, &[F]) -> Vec<F>,
+    {
+        let mut previous = self.last_row().unwrap_or_default();
+        for _ in 0..num_rows {
+            let row = generator(self.length, &previous);
+            self.push_row(&row)?;
+            previous = row;
+        }
+        Ok(())
+    }
+
+    /// Add a named column holding `values`, returning its index
+    ///
+    /// The first column of an empty builder sets the length of the trace;
+    /// [`build`](Self::build) rejects columns of any other length.
+    pub fn add_column(&mut self, name: impl Into<String>, values: Vec<F>) -> Result<ColIdx, ProofError> {
+        let name = name.into();
+        self.check_mutable()?;
+        if self.column_index(&name).is_some() {
+            return Err(ProofError::InvalidTraceShape(format!("duplicate column {:?}", name)));
+        }
+        let columns = match &mut self.columns {
+            TraceStorage::Memory(columns) => columns,
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(_) => {
+                return Err(ProofError::StorageError("columns cannot be added to a mapped trace".to_string()))
+            }
+        };
+        if columns.is_empty() && self.length == 0 {
+            self.length = values.len();
+        }
+        columns.push(values);
+        self.names.push(Some(name));
+        Ok(ColIdx(self.names.len() - 1))
+    }
+
+    /// Index of the column with the given name
+    pub fn column_index(&self, name: &str) -> Option<ColIdx> {
+        self.names.iter().position(|column| column.as_deref() == Some(name)).map(ColIdx)
+    }
+
+    /// Overwrite the value of a column at a row
+    pub fn set(&mut self, row: RowIdx, column: ColIdx, value: F) -> Result<(), ProofError> {
+        self.check_mutable()?;
+        let (row, column) = (row.0, column.0);
+        if column >= self.columns.len() || row >= self.length.min(self.columns.column_len(column)) {
+            return Err(ProofError::InvalidTraceShape(format!(
+                "no cell at row {} of column {} in a trace of {} rows and {} columns",
+                row,
+                column,
+                self.length,
+                self.columns.len()
+            )));
+        }
+        self.columns.set(column, row, value);
+        Ok(())
+    }
+
+    /// Overwrite every row of a column with values computed from the row index
+    pub fn fill_column_with<G>(&mut self, column: ColIdx, mut generator: G) -> Result<(), ProofError>
+    where
+        G: FnMut(usize) -> F,
+    {
+        self.check_mutable()?;
+        let column = column.0;
+        if column >= self.columns.len() {
+            return Err(ProofError::InvalidTraceShape(format!(
+                "no column {} in a trace of {} columns",
+                column,
+                self.columns.len()
+            )));
+        }
+        match &mut self.columns {
+            TraceStorage::Memory(columns) => columns[column] = (0..self.length).map(&mut generator).collect(),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => {
+                for row in 0..self.length {
+                    columns[column].write(row, &[generator(row)]);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail if rows are being digested, which only rows may be appended to
+    fn check_mutable(&self) -> Result<(), ProofError> {
+        match self.digest {
+            Some(_) => Err(ProofError::InvalidTraceShape("a digested trace can only grow by rows".to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of registers
+    pub fn num_registers(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Number of rows pushed so far
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    /// Whether no rows have been pushed
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Most recently pushed row
+    pub fn last_row(&self) -> Option<Vec<F>> {
+        let index = self.length.checked_sub(1)?;
+        Some(self.columns.row(index))
+    }
+
+    /// Digest of the rows pushed so far, if digesting is enabled
+    pub fn digest(&self) -> Option<[u8; 32]> {
+        self.digest.as_ref().map(|hasher| {
+            let mut hasher = hasher.clone();
+            hasher.update((self.length as u64).to_le_bytes());
+            hasher.finalize().into()
+        })
+    }
+
+    /// Finish the trace, moving the column storage into it
+    ///
+    /// Mapped columns are shortened to the rows pushed. The trace must have
+    /// a power-of-two number of rows, and every column a value in each row.
+    pub fn build(self) -> Result<ExecutionTrace<F>, ProofError> {
+        if !self.length.is_power_of_two() {
+            return Err(ProofError::InvalidTraceShape(format!(
+                "{} rows; the length must be a power of two",
+                self.length
+            )));
+        }
+        let mut columns = self.columns;
+        columns.truncate(self.length);
+        if let Some(column) = (0..columns.len()).find(|&column| columns.column_len(column) != self.length) {
+            let name = self.names[column].as_deref().map_or_else(|| column.to_string(), |name| format!("{:?}", name));
+            return Err(ProofError::InvalidTraceShape(format!(
+                "column {} has {} values in a trace of {} rows",
+                name,
+                columns.column_len(column),
+                self.length
+            )));
+        }
+        Ok(ExecutionTrace {
+            num_registers: columns.len(),
+            aux_segments: Vec::new(),
+            length: self.length,
+            columns,
+        })
+    }
+}
 
 /// Generate execution trace efficiently
+///
+/// Applies `transition_fn` sequentially and returns the trace in column-major
+/// form. Registers missing from a state are filled with zero.
 pub fn generate_trace<F: FieldElement>(
-    _initial_state: &[F],
-    _num_steps: usize,
-    _transition_fn: &dyn Fn(&[F]) -> Vec<F>,
+    initial_state: &[F],
+    num_steps: usize,
+    transition_fn: &dyn Fn(&[F]) -> Vec<F>,
+) -> Vec<Vec<F>> {
+    generate_fixed_trace(initial_state, num_steps, transition_fn, &[])
+}
+
+/// Generate an execution trace whose fixed registers hold their columns
+///
+/// Like [`generate_trace`], with the fixed registers of every row, the first
+/// one included, set before the row is passed to `transition_fn`.
+pub fn generate_fixed_trace<F: FieldElement>(
+    initial_state: &[F],
+    num_steps: usize,
+    transition_fn: &dyn Fn(&[F]) -> Vec<F>,
+    fixed: &[FixedColumn<F>],
 ) -> Vec<Vec<F>> {
-    // Placeholder implementation
-    vec![]
-}
\ No newline at end of file
+    let width = initial_state.len();
+    let mut builder = TraceBuilder::with_capacity(width, num_steps);
+    builder
+        .generate_rows(num_steps, |step, previous| {
+            let mut state = if step == 0 {
+                initial_state.to_vec()
+            } else {
+                transition_fn(previous)
+            };
+            state.resize(width, F::zero());
+            for column in fixed {
+                if let Some(value) = state.get_mut(column.register()) {
+                    *value = column.value(step);
+                }
+            }
+            state
+        })
+        .expect("rows are resized to the trace width");
+
+    // Raw columns of any length, which `build` would reject
+    builder.columns.into_columns()
+}
+
+/// Generate the trace of a linear transition in independent segments
+///
+/// `matrix` must be square; `initial_state` is truncated or zero-padded to its
+/// width. Segment starting states are derived with the jump matrix
+/// `M^segment_length`, after which every segment is generated on its own.
+pub fn generate_linear_trace<F: FieldElement>(
+    matrix: &[Vec<F>],
+    initial_state: &[F],
+    num_steps: usize,
+    segment_length: usize,
+) -> Vec<Vec<F>> {
+    let width = matrix.len();
+    let segment_length = segment_length.max(1);
+    let mut state = initial_state.to_vec();
+    state.resize(width, F::zero());
+
+    // Starting state of every segment
+    let jump = matrix_power(matrix, segment_length);
+    let num_segments = num_steps.div_ceil(segment_length);
+    let mut starts = Vec::with_capacity(num_segments);
+    for _ in 0..num_segments {
+        let next = matrix_vector_product(&jump, &state);
+        starts.push(core::mem::replace(&mut state, next));
+    }
+
+    let generate_segment = |(index, start): (usize, &Vec<F>)| {
+        let rows = segment_length.min(num_steps - index * segment_length);
+        let mut segment = Vec::with_capacity(rows);
+        let mut current = start.clone();
+        for row in 0..rows {
+            if row > 0 {
+                current = matrix_vector_product(matrix, &current);
+            }
+            segment.push(current.clone());
+        }
+        segment
+    };
+
+    #[cfg(feature = "parallel")]
+    let segments: Vec<Vec<Vec<F>>> = starts.par_iter().enumerate().map(generate_segment).collect();
+    #[cfg(not(feature = "parallel"))]
+    let segments: Vec<Vec<Vec<F>>> = starts.iter().enumerate().map(generate_segment).collect();
+
+    let transpose_column = |register: usize| -> Vec<F> {
+        segments
+            .iter()
+            .flat_map(|segment| segment.iter().map(move |row| row[register]))
+            .collect()
+    };
+
+    #[cfg(feature = "parallel")]
+    let columns = (0..width).into_par_iter().map(transpose_column).collect();
+    #[cfg(not(feature = "parallel"))]
+    let columns = (0..width).map(transpose_column).collect();
+
+    columns
+}
+
+/// Multiply a matrix by a vector, treating missing entries as zero
+fn matrix_vector_product<F: FieldElement>(matrix: &[Vec<F>], vector: &[F]) -> Vec<F> {
+    matrix
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(vector)
+                .fold(F::zero(), |acc, (&coeff, &value)| acc + coeff * value)
+        })
+        .collect()
+}
+
+/// Multiply two square matrices
+fn matrix_product<F: FieldElement>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+    let width = a.len();
+    (0..width)
+        .map(|i| {
+            (0..width)
+                .map(|j| {
+                    (0..width).fold(F::zero(), |acc, k| {
+                        let lhs = a[i].get(k).copied().unwrap_or_else(F::zero);
+                        let rhs = b[k].get(j).copied().unwrap_or_else(F::zero);
+                        acc + lhs * rhs
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Raise a square matrix to a power by repeated squaring
+fn matrix_power<F: FieldElement>(matrix: &[Vec<F>], mut exponent: usize) -> Vec<Vec<F>> {
+    let width = matrix.len();
+    let mut result: Vec<Vec<F>> = (0..width)
+        .map(|i| (0..width).map(|j| if i == j { F::one() } else { F::zero() }).collect())
+        .collect();
+    let mut base = matrix.to_vec();
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = matrix_product(&result, &base);
+        }
+        base = matrix_product(&base, &base);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    fn fibonacci_matrix() -> Vec<Vec<PrimeField64>> {
+        vec![
+            vec![PrimeField64::zero(), PrimeField64::one()],
+            vec![PrimeField64::one(), PrimeField64::one()],
+        ]
+    }
+
+    #[test]
+    fn test_segmented_trace_matches_sequential() {
+        let matrix = fibonacci_matrix();
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+        let sequential = generate_trace(&initial_state, 37, &|state| matrix_vector_product(&matrix, state));
+
+        assert_eq!(sequential[1][..5], [1, 2, 3, 5, 8].map(PrimeField64::new));
+        for segment_length in [1, 4, 10, 37, 64] {
+            assert_eq!(generate_linear_trace(&matrix, &initial_state, 37, segment_length), sequential);
+        }
+    }
+
+    #[test]
+    fn test_trace_builder_streams_rows() {
+        let matrix = fibonacci_matrix();
+        let mut builder = TraceBuilder::new(2).with_digest();
+        builder.push_row(&[PrimeField64::new(1), PrimeField64::new(1)]).unwrap();
+        builder
+            .generate_rows(7, |_, previous| matrix_vector_product(&matrix, previous))
+            .unwrap();
+        assert!(builder.push_row(&[PrimeField64::one()]).is_err());
+        assert_eq!(builder.len(), 8);
+
+        // Pushing the same rows in one go yields the same digest
+        let mut replay = TraceBuilder::new(2).with_digest();
+        let rows: Vec<Vec<PrimeField64>> = (0..8).map(|row| builder.columns.row(row)).collect();
+        replay.extend_rows(&rows).unwrap();
+        assert_eq!(replay.digest(), builder.digest());
+        assert!(TraceBuilder::<PrimeField64>::new(2).digest().is_none());
+
+        let trace = builder.build().unwrap();
+        assert_eq!(trace.length, 8);
+        assert_eq!(trace.columns, generate_linear_trace(&matrix, &rows[0], 8, 3).into());
+    }
+
+    #[test]
+    fn test_trace_builder_assembles_columns() {
+        let f = PrimeField64::new;
+        let mut builder = TraceBuilder::new(0);
+        let a = builder.add_column("a", vec![f(1), f(1), f(2), f(3)]).unwrap();
+        let b = builder.add_column("b", vec![f(0); 4]).unwrap();
+        builder.fill_column_with(b, |row| f(row as u64 * 10)).unwrap();
+        builder.set(RowIdx(3), a, f(5)).unwrap();
+        assert_eq!(builder.column_index("b"), Some(b));
+        assert!(builder.add_column("a", vec![f(0); 4]).is_err());
+        assert!(builder.set(RowIdx(4), a, f(0)).is_err());
+        assert!(builder.fill_column_with(ColIdx(2), |_| f(0)).is_err());
+
+        let trace = builder.clone().build().unwrap();
+        assert_eq!((trace.length, trace.num_registers), (4, 2));
+        assert_eq!(trace.columns.to_columns(), vec![vec![f(1), f(1), f(2), f(5)], vec![f(0), f(10), f(20), f(30)]]);
+
+        // Ragged columns and lengths other than powers of two are rejected
+        builder.add_column("c", vec![f(0); 3]).unwrap();
+        let error = builder.build().unwrap_err().to_string();
+        assert!(error.contains("\"c\" has 3 values"), "{}", error);
+        let mut short = TraceBuilder::new(0);
+        short.add_column("a", vec![f(0); 3]).unwrap();
+        assert!(matches!(short.build(), Err(ProofError::InvalidTraceShape(_))));
+        assert!(TraceBuilder::<PrimeField64>::new(1).build().is_err());
+
+        // A digested trace only grows by rows
+        let mut digested = TraceBuilder::new(1).with_digest();
+        digested.push_row(&[f(1)]).unwrap();
+        assert!(digested.set(RowIdx(0), ColIdx(0), f(2)).is_err());
+        assert!(digested.add_column("a", vec![f(1)]).is_err());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mapped_trace_proves_like_in_memory() {
+        use crate::air::{Air, BoundaryConditions, BoundaryConstraint, TransitionFunction};
+        use crate::proof::public_inputs::PublicInputs;
+        use crate::proof::StarkProver;
+
+        let matrix = fibonacci_matrix();
+        let mut builder = TraceBuilder::mapped(2, 64).unwrap();
+        builder.push_row(&[PrimeField64::new(1), PrimeField64::new(1)]).unwrap();
+        builder
+            .generate_rows(31, |_, previous| matrix_vector_product(&matrix, previous))
+            .unwrap();
+        let mapped = builder.build().unwrap();
+        assert!(mapped.columns.is_mapped());
+        assert_eq!(mapped.length, 32);
+
+        let air = Air::new(
+            vec![],
+            TransitionFunction::fibonacci(),
+            BoundaryConditions::new(vec![BoundaryConstraint::initial(0, PrimeField64::new(1))]),
+            128,
+        );
+        let prover = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1).deterministic([9; 32]);
+        let in_memory = prover.generate_trace(&air, &[PrimeField64::new(1), PrimeField64::new(1)], 32).unwrap();
+        assert_eq!(mapped, in_memory);
+        assert_eq!(
+            prover.prove(&air, &PublicInputs::new(), &mapped).unwrap(),
+            prover.prove(&air, &PublicInputs::new(), &in_memory).unwrap()
+        );
+
+        let mut full = TraceBuilder::<PrimeField64>::mapped(1, 1).unwrap();
+        full.push_row(&[PrimeField64::one()]).unwrap();
+        assert!(matches!(full.push_row(&[PrimeField64::one()]), Err(ProofError::StorageError(_))));
+    }
+
+    #[test]
+    fn test_segmented_trace_handles_empty_and_short_traces() {
+        let matrix = fibonacci_matrix();
+        let initial_state = [PrimeField64::new(1), PrimeField64::new(1)];
+
+        assert_eq!(generate_linear_trace(&matrix, &initial_state, 0, 8), vec![Vec::new(), Vec::new()]);
+        assert_eq!(
+            generate_linear_trace(&matrix, &initial_state, 1, 8),
+            vec![vec![PrimeField64::new(1)], vec![PrimeField64::new(1)]]
+        );
+    }
+}
@@ -0,0 +1,325 @@
+//! Fiat–Shamir Transcript
+//!
+//! This module provides the Fiat–Shamir transcript shared by the prover and the
+//! verifier. Every message the prover sends (commitments, evaluations, public
+//! parameters) is absorbed into the transcript, and every verifier challenge is
+//! squeezed out of it, so both sides derive identical randomness from the same
+//! proof data.
+//!
+//! ## Construction
+//!
+//! The transcript state is a 32-byte SHA256 chaining value. Absorbing a message
+//! hashes the current state together with a length-prefixed label and the
+//! length-prefixed message. Squeezing a challenge hashes the state with a
+//! distinct squeeze tag and the label, and the result becomes the new state, so
+//! consecutive challenges are independent.
+//!
+//! ## Grinding
+//!
+//! A proof can carry a proof-of-work nonce over the finished transcript: the
+//! grinding hash of the final state, the number of grinding bits and the nonce
+//! must start with that many zero bits. Finding a nonce takes about `2^bits`
+//! hashes, checking it takes one.
+
+use crate::prelude::*;
+use crate::types::{ExtensionElement, FieldElement};
+use sha2::{Digest, Sha256};
+use core::fmt::{Display, Formatter};
+
+/// Tag mixed into the state when absorbing a message
+const ABSORB_TAG: &[u8] = b"xfg-stark/transcript/absorb";
+
+/// Tag of the proof-of-work hash over the transcript state
+const GRINDING_TAG: &[u8] = b"xfg-stark/transcript/grinding";
+
+/// Maximum number of proof-of-work bits a proof can be ground to
+pub const MAX_GRINDING_BITS: u32 = 32;
+
+/// Number of nonces tried between polls of a grinding stop condition
+pub const GRINDING_POLL_INTERVAL: u64 = 1 << 12;
+
+/// Tag mixed into the state when squeezing a challenge
+const SQUEEZE_TAG: &[u8] = b"xfg-stark/transcript/squeeze";
+
+/// Domain-separation labels used by the STARK prover and verifier
+///
+/// Both sides must absorb and squeeze with the same labels in the same order.
+pub mod labels {
+    /// Protocol label the transcript is initialized with
+    pub const PROTOCOL: &[u8] = b"xfg-stark/v1";
+    /// Public proof parameters
+    pub const PARAMETERS: &[u8] = b"parameters";
+    /// Digest of the AIR being proven
+    pub const AIR_DIGEST: &[u8] = b"air-digest";
+    /// Constraints recorded in the proof, in their wire encoding
+    pub const RECORDED_AIR: &[u8] = b"recorded-air";
+    /// Trace dimensions (length and register count)
+    pub const TRACE_SHAPE: &[u8] = b"trace-shape";
+    /// Public inputs asserted on the trace
+    pub const PUBLIC_INPUTS: &[u8] = b"public-inputs";
+    /// Commitment to the execution trace
+    pub const TRACE_COMMITMENT: &[u8] = b"trace-commitment";
+    /// Random challenges an auxiliary segment is built from
+    pub const AUX_CHALLENGES: &[u8] = b"aux-challenges";
+    /// Auxiliary segment commitment
+    pub const AUX_COMMITMENT: &[u8] = b"aux-commitment";
+    /// Random coefficients combining the constraints
+    pub const COMPOSITION_COEFFICIENTS: &[u8] = b"composition-coefficients";
+    /// Commitment to the constraint composition polynomial
+    pub const COMPOSITION_COMMITMENT: &[u8] = b"composition-commitment";
+    /// Out-of-domain sampling point
+    pub const OOD_POINT: &[u8] = b"ood-point";
+    /// Trace and composition evaluations at the out-of-domain point
+    pub const OOD_FRAME: &[u8] = b"ood-frame";
+    /// Random coefficients of the DEEP composition polynomial
+    pub const DEEP_COEFFICIENTS: &[u8] = b"deep-coefficients";
+    /// Commitment to a FRI layer
+    pub const FRI_LAYER_COMMITMENT: &[u8] = b"fri-layer-commitment";
+    /// FRI folding challenge
+    pub const FRI_FOLDING_CHALLENGE: &[u8] = b"fri-folding-challenge";
+    /// FRI remainder polynomial
+    pub const FRI_REMAINDER: &[u8] = b"fri-remainder";
+    /// FRI query points
+    pub const FRI_QUERY: &[u8] = b"fri-query";
+    /// LDE positions at which the trace and composition commitments are opened
+    pub const QUERY_POSITIONS: &[u8] = b"query-positions";
+}
+
+/// Fiat–Shamir transcript
+///
+/// A transcript is created with a protocol label, then alternately absorbs
+/// prover messages and squeezes verifier challenges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    /// Current chaining value
+    state: [u8; 32],
+}
+
+impl Transcript {
+    /// Create a new transcript bound to a protocol label
+    pub fn new(protocol_label: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(b"xfg-stark/transcript");
+        update_with_length(&mut hasher, protocol_label);
+
+        Self {
+            state: hasher.finalize().into(),
+        }
+    }
+
+    /// Absorb raw bytes under a label
+    pub fn absorb_bytes(&mut self, label: &[u8], data: &[u8]) {
+        let mut hasher = Sha256::new();
+        hasher.update(ABSORB_TAG);
+        hasher.update(self.state);
+        update_with_length(&mut hasher, label);
+        update_with_length(&mut hasher, data);
+        self.state = hasher.finalize().into();
+    }
+
+    /// Absorb an unsigned integer under a label
+    pub fn absorb_u64(&mut self, label: &[u8], value: u64) {
+        self.absorb_bytes(label, &value.to_le_bytes());
+    }
+
+    /// Absorb a single field element under a label
+    pub fn absorb_field_element<F: FieldElement>(&mut self, label: &[u8], element: &F) {
+        self.absorb_bytes(label, &element.to_bytes());
+    }
+
+    /// Absorb a sequence of field elements under a label
+    pub fn absorb_field_elements<F: FieldElement>(&mut self, label: &[u8], elements: &[F]) {
+        let mut data = Vec::with_capacity(8 + elements.len() * 32);
+        data.extend_from_slice(&(elements.len() as u64).to_le_bytes());
+        for element in elements {
+            data.extend_from_slice(&element.to_bytes());
+        }
+        self.absorb_bytes(label, &data);
+    }
+
+    /// Absorb a commitment (e.g. a Merkle root) under a label
+    pub fn absorb_commitment(&mut self, label: &[u8], commitment: &[u8]) {
+        self.absorb_bytes(label, commitment);
+    }
+
+    /// Squeeze 32 challenge bytes under a label
+    pub fn challenge_bytes(&mut self, label: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(SQUEEZE_TAG);
+        hasher.update(self.state);
+        update_with_length(&mut hasher, label);
+        self.state = hasher.finalize().into();
+        self.state
+    }
+
+    /// Squeeze a uniformly distributed field element under a label
+    ///
+    /// Challenge bytes are mapped to the field with rejection sampling: byte
+    /// strings that are not a canonical element encoding are discarded and a
+    /// fresh block is squeezed, so the result carries no modular bias.
+    pub fn challenge_field_element<F: FieldElement>(&mut self, label: &[u8]) -> F {
+        loop {
+            let bytes = self.challenge_bytes(label);
+            if let Some(element) = F::from_bytes(&bytes) {
+                return element;
+            }
+        }
+    }
+
+    /// Squeeze several field elements under a label
+    pub fn challenge_field_elements<F: FieldElement>(&mut self, label: &[u8], count: usize) -> Vec<F> {
+        (0..count).map(|_| self.challenge_field_element(label)).collect()
+    }
+
+    /// Squeeze several elements of the extension of the given degree under a label
+    ///
+    /// Each element takes `degree` consecutive base field challenges as its
+    /// coordinates, so degree 1 squeezes exactly what
+    /// [`challenge_field_elements`](Self::challenge_field_elements) does.
+    pub fn challenge_extension_elements<F: FieldElement>(
+        &mut self,
+        label: &[u8],
+        count: usize,
+        degree: usize,
+    ) -> Vec<ExtensionElement<F>> {
+        (0..count)
+            .map(|_| ExtensionElement::from_coordinates(&self.challenge_field_elements(label, degree), degree))
+            .collect()
+    }
+
+    /// Squeeze distinct indices in `0..domain_size` under a label
+    ///
+    /// At most `domain_size` indices are returned. Indices are drawn from
+    /// 64-bit words reduced modulo the domain size; the resulting bias is
+    /// negligible for any practical domain.
+    pub fn challenge_indices(&mut self, label: &[u8], count: usize, domain_size: usize) -> Vec<usize> {
+        let count = count.min(domain_size);
+        let mut indices = Vec::with_capacity(count);
+
+        while indices.len() < count {
+            let bytes = self.challenge_bytes(label);
+            for chunk in bytes.chunks_exact(8) {
+                let mut word = [0u8; 8];
+                word.copy_from_slice(chunk);
+                let index = (u64::from_le_bytes(word) % domain_size as u64) as usize;
+                if !indices.contains(&index) {
+                    indices.push(index);
+                    if indices.len() == count {
+                        break;
+                    }
+                }
+            }
+        }
+
+        indices
+    }
+
+    /// Find the smallest nonce meeting a proof-of-work target over the current state
+    ///
+    /// `bits` is capped at [`MAX_GRINDING_BITS`].
+    pub fn grind(&self, bits: u32) -> u64 {
+        self.grind_until(bits, || false).expect("grinding was not stopped")
+    }
+
+    /// Search for a proof-of-work nonce like [`grind`](Self::grind), giving up once `stop` returns true
+    ///
+    /// `stop` is polled every [`GRINDING_POLL_INTERVAL`] nonces; returns `None`
+    /// if the search was stopped.
+    pub fn grind_until(&self, bits: u32, mut stop: impl FnMut() -> bool) -> Option<u64> {
+        let bits = bits.min(MAX_GRINDING_BITS);
+        let mut nonce = 0u64;
+        while !self.check_grinding(bits, nonce) {
+            nonce += 1;
+            if nonce % GRINDING_POLL_INTERVAL == 0 && stop() {
+                return None;
+            }
+        }
+        Some(nonce)
+    }
+
+    /// Check that a nonce meets a proof-of-work target of `bits` zero bits over the current state
+    pub fn check_grinding(&self, bits: u32, nonce: u64) -> bool {
+        let mut hasher = Sha256::new();
+        hasher.update(GRINDING_TAG);
+        hasher.update(self.state);
+        hasher.update(bits.to_le_bytes());
+        hasher.update(nonce.to_le_bytes());
+        let digest: [u8; 32] = hasher.finalize().into();
+
+        let mut word = [0u8; 8];
+        word.copy_from_slice(&digest[..8]);
+        bits <= MAX_GRINDING_BITS && u64::from_be_bytes(word).leading_zeros() >= bits
+    }
+
+    /// Current chaining value, useful for binding external data to the transcript
+    pub fn state(&self) -> [u8; 32] {
+        self.state
+    }
+}
+
+impl Display for Transcript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Transcript(state={:02x?})", &self.state[..8])
+    }
+}
+
+/// Feed a length-prefixed byte string into a hasher
+fn update_with_length(hasher: &mut Sha256, data: &[u8]) {
+    hasher.update((data.len() as u64).to_le_bytes());
+    hasher.update(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_transcript_is_deterministic() {
+        let mut a = Transcript::new(labels::PROTOCOL);
+        let mut b = Transcript::new(labels::PROTOCOL);
+
+        a.absorb_commitment(labels::TRACE_COMMITMENT, &[7u8; 32]);
+        b.absorb_commitment(labels::TRACE_COMMITMENT, &[7u8; 32]);
+
+        let x: PrimeField64 = a.challenge_field_element(labels::COMPOSITION_COEFFICIENTS);
+        let y: PrimeField64 = b.challenge_field_element(labels::COMPOSITION_COEFFICIENTS);
+        assert_eq!(x, y);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_transcript_depends_on_messages_and_labels() {
+        let mut base = Transcript::new(labels::PROTOCOL);
+        base.absorb_commitment(labels::TRACE_COMMITMENT, &[1u8; 32]);
+
+        let mut other_message = Transcript::new(labels::PROTOCOL);
+        other_message.absorb_commitment(labels::TRACE_COMMITMENT, &[2u8; 32]);
+
+        let mut other_label = Transcript::new(labels::PROTOCOL);
+        other_label.absorb_commitment(labels::COMPOSITION_COMMITMENT, &[1u8; 32]);
+
+        let c0 = base.challenge_bytes(labels::FRI_QUERY);
+        assert_ne!(c0, other_message.challenge_bytes(labels::FRI_QUERY));
+        assert_ne!(c0, other_label.challenge_bytes(labels::FRI_QUERY));
+
+        // Consecutive squeezes yield fresh challenges
+        assert_ne!(c0, base.challenge_bytes(labels::FRI_QUERY));
+    }
+
+    #[test]
+    fn test_challenge_indices_are_distinct_and_in_range() {
+        let mut transcript = Transcript::new(labels::PROTOCOL);
+        let indices = transcript.challenge_indices(labels::FRI_QUERY, 20, 64);
+
+        assert_eq!(indices.len(), 20);
+        assert!(indices.iter().all(|&i| i < 64));
+        for (i, a) in indices.iter().enumerate() {
+            assert!(!indices[i + 1..].contains(a));
+        }
+
+        // Requests larger than the domain are capped
+        assert_eq!(transcript.challenge_indices(labels::FRI_QUERY, 10, 4).len(), 4);
+        assert!(transcript.challenge_indices(labels::FRI_QUERY, 10, 0).is_empty());
+    }
+}
@@ -1,11 +1,156 @@
 //! Proof Verification
-//! 
+//!
 //! This module provides comprehensive verification of STARK proofs.
+//!
+//! ## Error Reporting
+//!
+//! [`StarkVerifier`](crate::proof::StarkVerifier) reports a rejected proof as
+//! a [`VerificationError`] naming the first check that failed, so a failed
+//! verification can be traced to a row, FRI layer or query.
 
+use crate::prelude::*;
 use crate::types::FieldElement;
+use crate::proof::composition::TraceViolation;
+use crate::proof::fri::FriError;
+use crate::proof::merkle::CommitmentRole;
+use crate::proof::ProofError;
+
+/// Reasons a proof is rejected
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+    /// The proof parameters do not reach the verifier's minimum security level
+    #[error("Insufficient security: proof reaches {achieved} bits, {required} required")]
+    InsufficientSecurity {
+        /// Security estimated from the proof parameters
+        achieved: u32,
+        /// Minimum security configured on the verifier
+        required: u32,
+    },
+
+    /// The proof parameters are not acceptable to the verifier
+    #[error("Parameter mismatch: {0}")]
+    ParameterMismatch(String),
+
+    /// The proof was generated for a different AIR
+    #[error("Proof AIR digest does not match the AIR")]
+    AirDigest,
+
+    /// The proof-of-work nonce does not meet the recorded grinding bits
+    #[error("Proof-of-work nonce does not meet {bits} grinding bits")]
+    Grinding {
+        /// Recorded grinding bits
+        bits: u32,
+    },
+
+    /// The proof is missing data or has an invalid shape
+    #[error("Malformed proof: {0}")]
+    MalformedProof(String),
+
+    /// A boundary constraint does not hold
+    #[error("Boundary constraint on register {register} at step {step} violated")]
+    Boundary {
+        /// Asserted register
+        register: usize,
+        /// Asserted step
+        step: usize,
+    },
+
+    /// A transition constraint does not hold
+    #[error("Transition constraint violated at row {row}")]
+    Transition {
+        /// First row of the violated transition
+        row: usize,
+    },
+
+    /// A fixed register does not hold its column's value
+    #[error("Fixed register {register} differs from its column at row {row}")]
+    FixedColumn {
+        /// Fixed register
+        register: usize,
+        /// Row of the differing value
+        row: usize,
+    },
+
+    /// The composition value at the out-of-domain point does not match the constraints
+    #[error("Out-of-domain constraint check failed")]
+    OodConstraint,
+
+    /// A FRI layer does not match its commitment or the folding of the previous layer
+    #[error("FRI layer {layer} is inconsistent")]
+    FriLayer {
+        /// Layer index
+        layer: usize,
+    },
+
+    /// The FRI remainder is invalid
+    #[error("FRI remainder is invalid")]
+    FriRemainder,
+
+    /// A FRI query is inconsistent
+    #[error("FRI query {query} is inconsistent")]
+    FriQuery {
+        /// Query index
+        query: usize,
+    },
+
+    /// A query opening is not at the position drawn from the transcript
+    #[error("Query {query} is opened at the wrong position")]
+    QueryPosition {
+        /// Query index
+        query: usize,
+    },
+
+    /// A Merkle path of a query opening does not authenticate against its commitment
+    #[error("Merkle path for query {query} does not match the {commitment} commitment")]
+    MerklePath {
+        /// Query index
+        query: usize,
+        /// Commitment the path was checked against
+        commitment: CommitmentRole,
+    },
+
+    /// The opened values of a query disagree with the FRI-proven DEEP polynomial
+    #[error("Query {query} is inconsistent with the DEEP composition polynomial")]
+    DeepComposition {
+        /// Query index
+        query: usize,
+    },
+
+    /// The commitment to an aggregate's member proofs does not match
+    #[error("Aggregate commitment mismatch")]
+    AggregateCommitment,
+
+    /// The proof could not be checked
+    #[error("Proof error: {0}")]
+    ProofError(#[from] ProofError),
+}
+
+impl From<FriError> for VerificationError {
+    fn from(error: FriError) -> Self {
+        match error {
+            FriError::InvalidLayer(layer) => VerificationError::FriLayer { layer },
+            FriError::InvalidRemainder => VerificationError::FriRemainder,
+            FriError::InvalidQuery(query) => VerificationError::FriQuery { query },
+            FriError::NoLayers | FriError::NoQueries | FriError::UnsupportedExtension(_) => {
+                VerificationError::MalformedProof(error.to_string())
+            }
+            error => VerificationError::ProofError(ProofError::FriError(error)),
+        }
+    }
+}
+
+impl From<TraceViolation> for VerificationError {
+    fn from(violation: TraceViolation) -> Self {
+        match violation {
+            TraceViolation::Transition { row } => VerificationError::Transition { row },
+            TraceViolation::Boundary { register, row } => VerificationError::Boundary { register, step: row },
+            TraceViolation::Fixed { register, row } => VerificationError::FixedColumn { register, row },
+        }
+    }
+}
 
 /// Verify proof components
 pub fn verify_proof_components<F: FieldElement>(_proof: &crate::types::StarkProof<F>) -> bool {
     // Placeholder implementation
     true
-}
\ No newline at end of file
+}
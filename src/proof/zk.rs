@@ -0,0 +1,182 @@
+//! Zero-Knowledge Blinding
+//!
+//! In zero-knowledge mode the prover hides the witness behind three layers of
+//! randomness:
+//!
+//! - **Random Rows**: Uniformly random rows are appended to the trace before
+//!   interpolation. Transition constraints only cover the original rows, so the
+//!   extra rows raise the degree of every column polynomial and make its
+//!   opened evaluations uniformly distributed. Fixed columns (see
+//!   [`fixed`](crate::air::fixed)) are public and padded with zeros instead.
+//! - **Masking Polynomial**: A random polynomial of the DEEP degree bound is
+//!   committed with the composition polynomial and added to the FRI input, so
+//!   the FRI layers reveal nothing about the DEEP composition.
+//! - **Salted Leaves**: Every Merkle leaf carries a random salt, so unopened
+//!   leaves cannot be recovered by hashing candidate values.
+//!
+//! The number of random rows exceeds the number of evaluations of each column
+//! revealed by the proof (one per query plus the two out-of-domain points).
+//!
+//! ## Deterministic Randomness
+//!
+//! All prover-side randomness is drawn from a ChaCha20 generator. A
+//! deterministic prover seeds it from a user seed hashed together with the
+//! execution trace, so identical inputs yield identical proofs while reusing a
+//! seed across different witnesses still produces unrelated masks.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+use crate::types::stark::ExecutionTrace;
+use crate::proof::composition::evaluate_polynomial;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+
+/// Domain separator for deriving the prover RNG seed
+const PROVER_RNG_DOMAIN: &[u8] = b"xfg-stark/prover-rng";
+
+/// Number of random rows appended to the trace for a given query count
+pub fn num_random_rows(num_queries: usize) -> usize {
+    num_queries.saturating_add(2)
+}
+
+/// Prover RNG seeded from the operating system
+#[cfg(feature = "std")]
+pub fn entropy_rng() -> ChaCha20Rng {
+    ChaCha20Rng::from_entropy()
+}
+
+/// Prover RNG derived from a seed and the execution trace
+pub fn seeded_rng<F: FieldElement>(seed: &[u8; 32], trace: &ExecutionTrace<F>) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(PROVER_RNG_DOMAIN);
+    hasher.update(seed);
+    hasher.update((trace.num_registers as u64).to_le_bytes());
+    hasher.update((trace.length as u64).to_le_bytes());
+    for column in trace.columns.iter() {
+        for value in column.iter() {
+            hasher.update(value.to_bytes());
+        }
+    }
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+/// Draw a uniformly random field element
+pub fn random_element<F: FieldElement, R: Rng + ?Sized>(rng: &mut R) -> F {
+    F::from_canonical_u64(rng.gen_range(0..F::MODULUS))
+}
+
+/// Draw a random leaf salt
+pub fn random_salt<R: Rng + ?Sized>(rng: &mut R) -> [u8; 32] {
+    let mut salt = [0u8; 32];
+    rng.fill(&mut salt);
+    salt
+}
+
+/// Append `num_rows` uniformly random rows to a trace, including its auxiliary segments
+///
+/// The `fixed` registers hold public values and are padded with zeros
+/// instead, so the verifier can interpolate them.
+pub fn pad_trace<F: FieldElement, R: Rng + ?Sized>(
+    trace: &ExecutionTrace<F>,
+    num_rows: usize,
+    fixed: &[usize],
+    rng: &mut R,
+) -> ExecutionTrace<F> {
+    let columns = trace.columns.iter().enumerate().map(|(register, column)| {
+        if fixed.contains(&register) {
+            let mut padded = column.to_vec();
+            padded.resize(column.len() + num_rows, F::zero());
+            padded
+        } else {
+            pad_columns([column], num_rows, rng).remove(0)
+        }
+    });
+    ExecutionTrace {
+        columns: columns.collect::<Vec<_>>().into(),
+        length: trace.length + num_rows,
+        num_registers: trace.num_registers,
+        aux_segments: trace
+            .aux_segments
+            .iter()
+            .map(|segment| pad_columns(segment, num_rows, rng))
+            .collect(),
+    }
+}
+
+/// Append `num_rows` uniformly random values to every column
+pub fn pad_columns<F: FieldElement, R: Rng + ?Sized, C: AsRef<[F]>>(
+    columns: impl IntoIterator<Item = C>,
+    num_rows: usize,
+    rng: &mut R,
+) -> Vec<Vec<F>> {
+    columns
+        .into_iter()
+        .map(|column| {
+            let mut padded = column.as_ref().to_vec();
+            padded.extend((0..num_rows).map(|_| random_element::<F, R>(rng)));
+            padded
+        })
+        .collect()
+}
+
+/// Random masking polynomial with `length` coefficients
+pub fn masking_polynomial<F: FieldElement, R: Rng + ?Sized>(length: usize, rng: &mut R) -> Vec<F> {
+    (0..length).map(|_| random_element(rng)).collect()
+}
+
+/// Add the masking polynomial to the DEEP composition polynomial
+pub fn mask_polynomial<F: FieldElement>(polynomial: &[F], masking: &[F]) -> Vec<F> {
+    let length = polynomial.len().max(masking.len());
+    (0..length)
+        .map(|i| {
+            let a = polynomial.get(i).copied().unwrap_or_else(F::zero);
+            let b = masking.get(i).copied().unwrap_or_else(F::zero);
+            a + b
+        })
+        .collect()
+}
+
+/// Evaluate the masking polynomial over a set of points
+pub fn evaluate_masking<F: FieldElement>(masking: &[F], points: &[F]) -> Vec<F> {
+    points.iter().map(|&x| evaluate_polynomial(masking, x)).collect()
+}
+
+/// Append an optional salt to leaf data
+pub fn salted_leaf(mut data: Vec<u8>, salt: Option<&[u8; 32]>) -> Vec<u8> {
+    if let Some(salt) = salt {
+        data.extend_from_slice(salt);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_pad_trace_keeps_original_rows() {
+        let trace = ExecutionTrace {
+            columns: vec![(1..=4).map(PrimeField64::new).collect()].into(),
+            length: 4,
+            num_registers: 1,
+            aux_segments: Vec::new(),
+        };
+        let mut rng = rand::thread_rng();
+        let padded = pad_trace(&trace, num_random_rows(3), &[], &mut rng);
+
+        assert_eq!(padded.length, 9);
+        assert_eq!(padded.columns.column_len(0), 9);
+        assert_eq!(padded.columns.column(0)[..4], trace.columns.column(0)[..]);
+
+        let masking: Vec<PrimeField64> = masking_polynomial(3, &mut rng);
+        let polynomial = vec![PrimeField64::new(1); 2];
+        let masked = mask_polynomial(&polynomial, &masking);
+        let x = PrimeField64::new(7);
+        assert_eq!(
+            evaluate_polynomial(&masked, x),
+            evaluate_polynomial(&polynomial, x) + evaluate_masking(&masking, &[x])[0]
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! 32-Byte Digests
+//!
+//! [`Digest`] holds an AIR digest, a Merkle root or any other 32-byte hash
+//! output. It displays and parses as `0x`-prefixed lowercase hex, so logs,
+//! JSON and command lines show the digest rather than a list of bytes.
+
+use crate::prelude::*;
+use crate::types::hex;
+use crate::types::TypeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use core::fmt::{Debug, Display, Formatter, LowerHex};
+use core::str::FromStr;
+
+/// 32-byte hash output
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Digest([u8; 32]);
+
+impl Digest {
+    /// Digest of the given bytes
+    pub const fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Bytes of the digest
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for Digest {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Digest> for [u8; 32] {
+    fn from(digest: Digest) -> Self {
+        digest.0
+    }
+}
+
+/// Digest of a 32-byte slice, such as a Merkle root
+impl TryFrom<&[u8]> for Digest {
+    type Error = TypeError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| TypeError::LengthMismatch { name: "digest", expected: 32, actual: bytes.len() })
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl PartialEq<[u8; 32]> for Digest {
+    fn eq(&self, other: &[u8; 32]) -> bool {
+        self.0 == *other
+    }
+}
+
+impl LowerHex for Digest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            write!(f, "0x")?;
+        }
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Digest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl Debug for Digest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Digest({:#x})", self)
+    }
+}
+
+/// Parse 64 hex digits, with or without a `0x` prefix
+impl FromStr for Digest {
+    type Err = TypeError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let digits = hex.strip_prefix("0x").unwrap_or(hex);
+        let invalid = || TypeError::InvalidConversion(format!("{:?} is not a hex digest of 32 bytes", hex));
+        if digits.len() != 64 || !digits.is_ascii() {
+            return Err(invalid());
+        }
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(Self(bytes))
+    }
+}
+
+/// Hex in human-readable formats, the bytes otherwise
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        hex::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_hex() {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0xab;
+        bytes[31] = 0x01;
+        let digest = Digest::new(bytes);
+        let hex = format!("0xab{}01", "00".repeat(30));
+        assert_eq!(digest.to_string(), hex);
+        assert_eq!(format!("{:x}", digest), hex[2..]);
+        assert_eq!(format!("{:?}", digest), format!("Digest({})", hex));
+
+        // Parsing takes the prefix or not, and any case
+        assert_eq!(hex.parse::<Digest>().unwrap(), digest);
+        assert_eq!(hex[2..].to_uppercase().parse::<Digest>().unwrap(), digest);
+        assert!(hex[..64].parse::<Digest>().is_err());
+        assert!(format!("{}zz", &hex[..64]).parse::<Digest>().is_err());
+        assert!(format!("0x{}é", "0".repeat(62)).parse::<Digest>().is_err());
+
+        assert_eq!(Digest::try_from(&bytes[..]).unwrap(), digest);
+        assert!(Digest::try_from(&bytes[..31]).is_err());
+        assert_eq!(<[u8; 32]>::from(digest), bytes);
+    }
+}
@@ -0,0 +1,392 @@
+//! Binary Encoding for STARK Types
+//!
+//! This module provides the deterministic binary encoding used by the
+//! `StarkComponent::to_bytes` / `from_bytes` implementations.
+//!
+//! ## Format
+//!
+//! - Integers are little-endian; `usize` values are widened to `u64`
+//! - Field elements use their canonical 32-byte encoding
+//! - Sequences and byte strings are prefixed with their length as `u64`
+//! - Optional values are prefixed with a `0` / `1` tag byte
+//!
+//! Decoding is strict: non-canonical field elements, invalid tags and
+//! trailing bytes are rejected, so every value has exactly one encoding.
+//!
+//! The layout of whole proofs built from these primitives is specified in
+//! `docs/wire-format.md`, with golden vectors in `test-vectors/`.
+
+use crate::prelude::*;
+use crate::types::{FieldElement, TypeError};
+
+/// Types with a deterministic binary encoding
+pub trait Encode: Sized {
+    /// Append the encoding of `self` to the writer
+    fn encode(&self, writer: &mut ByteWriter);
+
+    /// Decode a value from the reader
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError>;
+}
+
+/// Encode a value into a fresh byte vector
+pub fn encode_to_vec<T: Encode>(value: &T) -> Vec<u8> {
+    let mut writer = ByteWriter::new();
+    value.encode(&mut writer);
+    writer.into_bytes()
+}
+
+/// Decode a value that must span the whole byte slice
+pub fn decode_from_slice<T: Encode>(bytes: &[u8]) -> Result<T, TypeError> {
+    let mut reader = ByteReader::new(bytes);
+    let value = T::decode(&mut reader)?;
+    reader.finish()?;
+    Ok(value)
+}
+
+/// Length of the encoding of a value, without allocating it
+pub fn encoded_len<T: Encode>(value: &T) -> usize {
+    let mut writer = ByteWriter::counting();
+    value.encode(&mut writer);
+    writer.len()
+}
+
+/// Append-only byte buffer
+///
+/// A [counting](Self::counting) writer keeps no bytes and only adds up
+/// their length, so that the size of an encoding can be known without
+/// building it.
+#[derive(Debug, Clone, Default)]
+pub struct ByteWriter {
+    /// Encoded bytes
+    bytes: Vec<u8>,
+    /// Number of bytes written
+    len: usize,
+    /// Whether the bytes are counted rather than kept
+    counting: bool,
+}
+
+impl ByteWriter {
+    /// Create an empty writer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a writer that counts the bytes written without keeping them
+    pub fn counting() -> Self {
+        Self {
+            counting: true,
+            ..Self::default()
+        }
+    }
+
+    /// Number of bytes written
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing was written
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Write a single byte
+    pub fn write_u8(&mut self, value: u8) {
+        self.write_raw(&[value]);
+    }
+
+    /// Write a `u16`
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_raw(&value.to_le_bytes());
+    }
+
+    /// Write a `u32`
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_raw(&value.to_le_bytes());
+    }
+
+    /// Write a `u64`
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_raw(&value.to_le_bytes());
+    }
+
+    /// Write a `usize` as a `u64`
+    pub fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    /// Write a boolean as a tag byte
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(u8::from(value));
+    }
+
+    /// Write raw bytes without a length prefix
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.len += bytes.len();
+        if !self.counting {
+            self.bytes.extend_from_slice(bytes);
+        }
+    }
+
+    /// Write a length-prefixed byte string
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.write_raw(bytes);
+    }
+
+    /// Write a 32-byte digest
+    pub fn write_digest(&mut self, digest: &[u8; 32]) {
+        self.write_raw(digest);
+    }
+
+    /// Write an optional 32-byte digest
+    pub fn write_optional_digest(&mut self, digest: Option<&[u8; 32]>) {
+        self.write_bool(digest.is_some());
+        if let Some(digest) = digest {
+            self.write_digest(digest);
+        }
+    }
+
+    /// Write a length-prefixed UTF-8 string
+    pub fn write_string(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    /// Write a field element
+    pub fn write_field<F: FieldElement>(&mut self, element: &F) {
+        self.write_raw(&element.to_bytes());
+    }
+
+    /// Write an optional field element
+    pub fn write_optional_field<F: FieldElement>(&mut self, element: Option<&F>) {
+        self.write_bool(element.is_some());
+        if let Some(element) = element {
+            self.write_field(element);
+        }
+    }
+
+    /// Write a length-prefixed sequence of field elements
+    pub fn write_fields<F: FieldElement>(&mut self, elements: &[F]) {
+        self.write_usize(elements.len());
+        for element in elements {
+            self.write_field(element);
+        }
+    }
+
+    /// Write a length-prefixed sequence of encodable values
+    pub fn write_sequence<T: Encode>(&mut self, values: &[T]) {
+        self.write_usize(values.len());
+        for value in values {
+            value.encode(self);
+        }
+    }
+
+    /// Encoded bytes, empty for a counting writer
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Cursor over encoded bytes
+#[derive(Debug, Clone)]
+pub struct ByteReader<'a> {
+    /// Encoded bytes
+    bytes: &'a [u8],
+    /// Read position
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Create a reader at the start of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Number of unread bytes
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    /// Fail unless every byte has been read
+    pub fn finish(&self) -> Result<(), TypeError> {
+        if self.remaining() != 0 {
+            return Err(TypeError::TrailingBytes { count: self.remaining() });
+        }
+        Ok(())
+    }
+
+    /// Read `length` raw bytes
+    pub fn read_raw(&mut self, length: usize) -> Result<&'a [u8], TypeError> {
+        if length > self.remaining() {
+            return Err(TypeError::UnexpectedEnd { needed: length, remaining: self.remaining() });
+        }
+        let bytes = &self.bytes[self.position..self.position + length];
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// Read a fixed-size byte array
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], TypeError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_raw(N)?);
+        Ok(array)
+    }
+
+    /// Read a single byte
+    pub fn read_u8(&mut self) -> Result<u8, TypeError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    /// Read a `u16`
+    pub fn read_u16(&mut self) -> Result<u16, TypeError> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read a `u32`
+    pub fn read_u32(&mut self) -> Result<u32, TypeError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read a `u64`
+    pub fn read_u64(&mut self) -> Result<u64, TypeError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Read a `usize` encoded as a `u64`
+    pub fn read_usize(&mut self) -> Result<usize, TypeError> {
+        usize::try_from(self.read_u64()?)
+            .map_err(|_| TypeError::InvalidConversion("Length exceeds usize".to_string()))
+    }
+
+    /// Read a sequence length, bounded by the bytes left to read
+    ///
+    /// Every element occupies at least `min_element_size` bytes, so larger
+    /// lengths are rejected before anything is allocated.
+    pub fn read_length(&mut self, min_element_size: usize) -> Result<usize, TypeError> {
+        let length = self.read_usize()?;
+        let needed = length.saturating_mul(min_element_size.max(1));
+        if needed > self.remaining() {
+            return Err(TypeError::UnexpectedEnd { needed, remaining: self.remaining() });
+        }
+        Ok(length)
+    }
+
+    /// Read a boolean tag byte
+    pub fn read_bool(&mut self) -> Result<bool, TypeError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            tag => Err(TypeError::UnknownTag { name: "boolean", tag }),
+        }
+    }
+
+    /// Read a length-prefixed byte string
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, TypeError> {
+        let length = self.read_length(1)?;
+        Ok(self.read_raw(length)?.to_vec())
+    }
+
+    /// Read a 32-byte digest
+    pub fn read_digest(&mut self) -> Result<[u8; 32], TypeError> {
+        self.read_array()
+    }
+
+    /// Read an optional 32-byte digest
+    pub fn read_optional_digest(&mut self) -> Result<Option<[u8; 32]>, TypeError> {
+        if self.read_bool()? {
+            Ok(Some(self.read_digest()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read a length-prefixed UTF-8 string
+    pub fn read_string(&mut self) -> Result<String, TypeError> {
+        String::from_utf8(self.read_bytes()?)
+            .map_err(|_| TypeError::InvalidConversion("Invalid UTF-8 string".to_string()))
+    }
+
+    /// Read a canonical field element
+    pub fn read_field<F: FieldElement>(&mut self) -> Result<F, TypeError> {
+        let bytes: [u8; 32] = self.read_array()?;
+        F::from_bytes(&bytes)
+            .filter(|element| element.to_bytes() == bytes)
+            .ok_or(TypeError::NonCanonicalValue { name: "field element" })
+    }
+
+    /// Read an optional field element
+    pub fn read_optional_field<F: FieldElement>(&mut self) -> Result<Option<F>, TypeError> {
+        if self.read_bool()? {
+            Ok(Some(self.read_field()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Read a length-prefixed sequence of field elements
+    pub fn read_fields<F: FieldElement>(&mut self) -> Result<Vec<F>, TypeError> {
+        let length = self.read_length(32)?;
+        (0..length).map(|_| self.read_field()).collect()
+    }
+
+    /// Read a length-prefixed sequence of encodable values
+    pub fn read_sequence<T: Encode>(&mut self) -> Result<Vec<T>, TypeError> {
+        let length = self.read_length(1)?;
+        (0..length).map(|_| T::decode(self)).collect()
+    }
+}
+
+impl Encode for [u8; 32] {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_digest(self);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        reader.read_digest()
+    }
+}
+
+impl<F: FieldElement> Encode for Vec<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_fields(self);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        reader.read_fields()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_encoding_roundtrip_and_strictness() {
+        let mut writer = ByteWriter::new();
+        writer.write_u16(7);
+        writer.write_string("xfg");
+        writer.write_fields(&[PrimeField64::new(1), PrimeField64::new(2)]);
+        writer.write_optional_digest(Some(&[9u8; 32]));
+        let bytes = writer.into_bytes();
+
+        let mut reader = ByteReader::new(&bytes);
+        assert_eq!(reader.read_u16().unwrap(), 7);
+        assert_eq!(reader.read_string().unwrap(), "xfg");
+        assert_eq!(
+            reader.read_fields::<PrimeField64>().unwrap(),
+            vec![PrimeField64::new(1), PrimeField64::new(2)]
+        );
+        assert_eq!(reader.read_optional_digest().unwrap(), Some([9u8; 32]));
+        assert!(reader.finish().is_ok());
+
+        // Truncated input, oversized lengths and bad tags are rejected
+        assert!(ByteReader::new(&bytes[..1]).read_u16().is_err());
+        assert!(ByteReader::new(&u64::MAX.to_le_bytes()).read_bytes().is_err());
+        assert!(ByteReader::new(&[2]).read_bool().is_err());
+
+        // Non-canonical field elements are rejected
+        let mut non_canonical = [0xffu8; 32];
+        non_canonical[..24].fill(0);
+        assert!(ByteReader::new(&non_canonical).read_field::<PrimeField64>().is_err());
+    }
+}
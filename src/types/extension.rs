@@ -0,0 +1,211 @@
+//! Extension Field Elements
+//!
+//! Challenges drawn from a 64-bit base field bound the soundness of every
+//! protocol step that uses them to roughly the field size. Drawing them from
+//! an extension `F[x] / (x^d - W)` of degree 2 or 3 multiplies that bound by
+//! `d` in bits, while the trace and its commitments stay in the base field.
+//!
+//! [`ExtensionElement`] carries its degree at runtime, so the extension can be
+//! chosen per proof. The non-residue `W` is supplied by
+//! [`FieldElement::extension_nonresidue`]; degree 1 is the base field itself.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+use core::fmt::{Display, Formatter};
+use core::ops::{Add, Mul, Sub};
+
+/// Largest supported extension degree
+pub const MAX_EXTENSION_DEGREE: usize = 3;
+
+/// Element of a degree 1 to 3 extension of a base field
+///
+/// Coordinates are the coefficients of `1, x, x^2` in `F[x] / (x^d - W)`;
+/// coordinates beyond the degree are zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionElement<F: FieldElement> {
+    /// Coordinates, constant term first
+    coordinates: [F; MAX_EXTENSION_DEGREE],
+    /// Extension degree
+    degree: usize,
+}
+
+impl<F: FieldElement> ExtensionElement<F> {
+    /// Whether `F` has an extension of the given degree
+    pub fn is_supported(degree: usize) -> bool {
+        degree == 1 || (degree <= MAX_EXTENSION_DEGREE && F::extension_nonresidue(degree).is_some())
+    }
+
+    /// Zero of the extension of the given degree
+    pub fn zero(degree: usize) -> Self {
+        Self::from_base(F::zero(), degree)
+    }
+
+    /// Embed a base field element in the extension of the given degree
+    pub fn from_base(value: F, degree: usize) -> Self {
+        Self::from_coordinates(&[value], degree)
+    }
+
+    /// Element with the given leading coordinates and zeros after them
+    ///
+    /// # Panics
+    ///
+    /// Panics if the degree is not supported by `F` or there are more
+    /// coordinates than the degree.
+    pub fn from_coordinates(coordinates: &[F], degree: usize) -> Self {
+        assert!(Self::is_supported(degree), "Unsupported extension degree: {}", degree);
+        assert!(coordinates.len() <= degree, "Too many coordinates for extension degree {}", degree);
+        let mut padded = [F::zero(); MAX_EXTENSION_DEGREE];
+        padded[..coordinates.len()].copy_from_slice(coordinates);
+        Self {
+            coordinates: padded,
+            degree,
+        }
+    }
+
+    /// Extension degree
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    /// Coordinates, constant term first
+    pub fn coordinates(&self) -> &[F] {
+        &self.coordinates[..self.degree]
+    }
+
+    /// Multiply by a base field element
+    pub fn mul_base(self, scalar: F) -> Self {
+        let mut coordinates = self.coordinates;
+        for coordinate in &mut coordinates {
+            *coordinate = *coordinate * scalar;
+        }
+        Self {
+            coordinates,
+            degree: self.degree,
+        }
+    }
+
+    /// Evaluate a polynomial with extension coefficients at a base field point
+    pub fn evaluate_polynomial(coefficients: &[Self], x: F, degree: usize) -> Self {
+        coefficients
+            .iter()
+            .rev()
+            .fold(Self::zero(degree), |acc, &coeff| acc.mul_base(x) + coeff)
+    }
+}
+
+/// Split extension elements into one base field vector per coordinate
+///
+/// A combination `sum_i a_i * v_i` of base field values `v_i` with extension
+/// coefficients `a_i` has coordinate `c` equal to `sum_i a_i[c] * v_i`, so it
+/// can be computed in the base field, one coordinate at a time.
+pub fn split_coordinates<F: FieldElement>(elements: &[ExtensionElement<F>], degree: usize) -> Vec<Vec<F>> {
+    (0..degree)
+        .map(|coordinate| elements.iter().map(|element| element.coordinates[coordinate]).collect())
+        .collect()
+}
+
+impl<F: FieldElement> Add for ExtensionElement<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        assert_eq!(self.degree, other.degree, "Extension degrees must match");
+        let mut coordinates = self.coordinates;
+        for (coordinate, &value) in coordinates.iter_mut().zip(&other.coordinates) {
+            *coordinate = *coordinate + value;
+        }
+        Self {
+            coordinates,
+            degree: self.degree,
+        }
+    }
+}
+
+impl<F: FieldElement> Sub for ExtensionElement<F> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        assert_eq!(self.degree, other.degree, "Extension degrees must match");
+        let mut coordinates = self.coordinates;
+        for (coordinate, &value) in coordinates.iter_mut().zip(&other.coordinates) {
+            *coordinate = *coordinate - value;
+        }
+        Self {
+            coordinates,
+            degree: self.degree,
+        }
+    }
+}
+
+/// Schoolbook product reduced with `x^d = W`
+impl<F: FieldElement> Mul for ExtensionElement<F> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        assert_eq!(self.degree, other.degree, "Extension degrees must match");
+        let degree = self.degree;
+        let mut product = [F::zero(); 2 * MAX_EXTENSION_DEGREE - 1];
+        for (i, &a) in self.coordinates().iter().enumerate() {
+            for (j, &b) in other.coordinates().iter().enumerate() {
+                product[i + j] = product[i + j] + a * b;
+            }
+        }
+
+        let mut coordinates = [F::zero(); MAX_EXTENSION_DEGREE];
+        coordinates[..degree].copy_from_slice(&product[..degree]);
+        if degree > 1 {
+            let nonresidue = F::extension_nonresidue(degree).expect("supported extension degree");
+            for (index, &high) in product[degree..2 * degree - 1].iter().enumerate() {
+                coordinates[index] = coordinates[index] + nonresidue * high;
+            }
+        }
+        Self { coordinates, degree }
+    }
+}
+
+impl<F: FieldElement> Display for ExtensionElement<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ExtensionElement(")?;
+        for (index, coordinate) in self.coordinates().iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", coordinate)?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_extension_arithmetic() {
+        let f = PrimeField64::new;
+        assert!(ExtensionElement::<PrimeField64>::is_supported(2));
+        assert!(ExtensionElement::<PrimeField64>::is_supported(3));
+        assert!(!ExtensionElement::<PrimeField64>::is_supported(4));
+
+        // x^d reduces to the non-residue
+        let x2 = ExtensionElement::from_coordinates(&[f(0), f(1)], 2);
+        assert_eq!(x2 * x2, ExtensionElement::from_base(-PrimeField64::one(), 2));
+        let x3 = ExtensionElement::from_coordinates(&[f(0), f(1)], 3);
+        assert_eq!(x3 * x3 * x3, ExtensionElement::from_base(f(5), 3));
+
+        // Products distribute over sums and agree with the base field
+        let a = ExtensionElement::from_coordinates(&[f(3), f(7), f(11)], 3);
+        let b = ExtensionElement::from_coordinates(&[f(2), f(5), f(13)], 3);
+        let c = ExtensionElement::from_coordinates(&[f(17), f(0), f(4)], 3);
+        assert_eq!(a * (b + c), a * b + a * c);
+        assert_eq!((a * b) * c, a * (b * c));
+        assert_eq!(a.mul_base(f(6)), a * ExtensionElement::from_base(f(6), 3));
+        assert_eq!(
+            ExtensionElement::from_base(f(6), 1) * ExtensionElement::from_base(f(7), 1),
+            ExtensionElement::from_base(f(42), 1)
+        );
+
+        let coordinates = split_coordinates(&[a, b], 3);
+        assert_eq!(coordinates, vec![vec![f(3), f(2)], vec![f(7), f(5)], vec![f(11), f(13)]]);
+    }
+}
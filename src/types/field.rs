@@ -11,8 +11,9 @@
 //! - **Performance**: Zero-cost abstractions for field arithmetic operations
 //! - **Security**: Type-level prevention of timing attacks and vulnerabilities
 
-use std::fmt::{Debug, Display, Formatter};
-use std::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Neg};
+use crate::prelude::*;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::{Add, AddAssign, Sub, SubAssign, Mul, MulAssign, Neg};
 use serde::{Deserialize, Serialize};
 use super::{FieldElement, TypeError};
 use crate::Result;
@@ -45,8 +46,8 @@ pub struct PrimeField64 {
 }
 
 impl PrimeField64 {
-    /// Field modulus (using a smaller prime for u64 compatibility)
-    pub const MODULUS: u64 = 0x7fffffffffffffff; // 2^63 - 1 (Mersenne prime)
+    /// Field modulus, the Mersenne prime `2^61 - 1`
+    pub const MODULUS: u64 = 0x1fffffffffffffff;
     
     /// Create a new field element
     pub fn new(value: u64) -> Self {
@@ -210,6 +211,7 @@ impl PrimeField64 {
     }
     
     /// Random field element
+    #[cfg(feature = "std")]
     pub fn random() -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
@@ -269,9 +271,27 @@ impl FieldElement for PrimeField64 {
         Self::from_bytes_constant_time(bytes)
     }
     
+    #[cfg(feature = "std")]
     fn random() -> Self {
         Self::random()
     }
+    
+    fn from_canonical_u64(value: u64) -> Self {
+        Self::new(value)
+    }
+    
+    fn as_canonical_u64(&self) -> u64 {
+        self.value
+    }
+    
+    /// `-1` is a quadratic and `5` a cubic non-residue modulo `2^61 - 1`
+    fn extension_nonresidue(degree: usize) -> Option<Self> {
+        match degree {
+            2 => Some(-Self::one()),
+            3 => Some(Self::new(5)),
+            _ => None,
+        }
+    }
 }
 
 // Standard arithmetic trait implementations
@@ -330,7 +350,7 @@ impl Neg for PrimeField64 {
 }
 
 impl Display for PrimeField64 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "PrimeField64({})", self.value)
     }
 }
@@ -446,6 +466,19 @@ mod tests {
         assert_eq!(PrimeField64::one(), PrimeField64::new(1));
     }
 
+    #[test]
+    fn test_prime_field_modulus_is_prime() {
+        // Every non-zero element must be invertible, including small composites
+        for value in [2u64, 7, 73, 127, 40320, PrimeField64::MODULUS - 1] {
+            let a = PrimeField64::new(value);
+            assert_eq!(a * a.inverse().unwrap(), PrimeField64::one());
+        }
+        assert_eq!(
+            PrimeField64::from_canonical_u64(PrimeField64::MODULUS + 3).as_canonical_u64(),
+            3
+        );
+    }
+
     #[test]
     fn test_prime_field_inverse() {
         let a = PrimeField64::new(5);
@@ -0,0 +1,156 @@
+//! Hex Representation in Human-Readable Formats
+//!
+//! Proof components keep their compact serde form in binary formats such as
+//! bincode. In human-readable formats such as JSON, field elements are
+//! written as `0x`-prefixed hex of their canonical integer, and digests and
+//! byte strings as `0x`-prefixed hex of their bytes, so that a dumped proof
+//! can be read and compared by eye.
+//!
+//! Fields opt in with `#[serde(with = "crate::types::hex")]`; sequences and
+//! optional values of such types are handled element by element.
+
+use crate::prelude::*;
+use crate::types::FieldElement;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Types with a hex representation in human-readable formats
+pub(crate) trait HexSerde: Sized {
+    /// Serialize the hex representation
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    /// Deserialize the hex representation
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serialize as hex in human-readable formats, and as usual otherwise
+pub(crate) fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: HexSerde + Serialize,
+    S: Serializer,
+{
+    if serializer.is_human_readable() {
+        value.serialize_hex(serializer)
+    } else {
+        value.serialize(serializer)
+    }
+}
+
+/// Deserialize from hex in human-readable formats, and as usual otherwise
+pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: HexSerde + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        T::deserialize_hex(deserializer)
+    } else {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Value serialized through its hex representation
+pub(crate) struct AsHex<'a, T>(pub &'a T);
+
+impl<T: HexSerde> Serialize for AsHex<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_hex(serializer)
+    }
+}
+
+/// Value deserialized from its hex representation
+struct FromHex<T>(T);
+
+impl<'de, T: HexSerde> Deserialize<'de> for FromHex<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize_hex(deserializer).map(FromHex)
+    }
+}
+
+/// Digits of a `0x`-prefixed hex string
+fn strip_prefix<E: Error>(hex: &str) -> Result<&str, E> {
+    hex.strip_prefix("0x")
+        .ok_or_else(|| E::custom(format!("{:?} is not 0x-prefixed hex", hex)))
+}
+
+/// Bytes of a `0x`-prefixed hex string
+fn decode_bytes<E: Error>(hex: &str) -> Result<Vec<u8>, E> {
+    let digits = strip_prefix(hex)?;
+    if !digits.is_ascii() || digits.len() % 2 != 0 {
+        return Err(E::custom(format!("{:?} is not hex of whole bytes", hex)));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| E::custom(format!("{:?} is not hex", hex))))
+        .collect()
+}
+
+/// `0x`-prefixed hex of bytes
+fn encode_bytes(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+}
+
+/// Field elements as hex of their canonical integer
+impl<F: FieldElement> HexSerde for F {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", self.as_canonical_u64()))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let value = u64::from_str_radix(strip_prefix(&hex)?, 16)
+            .map_err(|_| D::Error::custom(format!("{:?} is not a hex integer", hex)))?;
+        if value >= F::MODULUS {
+            return Err(D::Error::custom(format!("{} is not below the modulus {:#x}", hex, F::MODULUS)));
+        }
+        Ok(F::from_canonical_u64(value))
+    }
+}
+
+impl HexSerde for [u8; 32] {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_bytes(self))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = decode_bytes::<D::Error>(&String::deserialize(deserializer)?)?;
+        let length = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| D::Error::custom(format!("digest of {} bytes; expected 32", length)))
+    }
+}
+
+impl HexSerde for Vec<u8> {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&encode_bytes(self))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        decode_bytes(&String::deserialize(deserializer)?)
+    }
+}
+
+impl<T: HexSerde> HexSerde for Vec<T> {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(AsHex))
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let values = Vec::<FromHex<T>>::deserialize(deserializer)?;
+        Ok(values.into_iter().map(|FromHex(value)| value).collect())
+    }
+}
+
+impl<T: HexSerde> HexSerde for Option<T> {
+    fn serialize_hex<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Some(value) => serializer.serialize_some(&AsHex(value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Option::<FromHex<T>>::deserialize(deserializer)?.map(|FromHex(value)| value))
+    }
+}
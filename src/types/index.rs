@@ -0,0 +1,85 @@
+//! Typed Trace Indices
+//!
+//! Rows, columns and AIR steps are all `usize` underneath, and the trace APIs
+//! that take two of them do not agree on their order: storage is indexed by
+//! column then row, tables and row views by row then column. [`RowIdx`],
+//! [`ColIdx`] and [`StepIdx`] make a swapped pair a type error rather than a
+//! silently transposed value.
+//!
+//! Each wraps its index in a public field, so `RowIdx(3)` names row 3 and
+//! `row.0` gets the index back.
+
+use serde::{Deserialize, Serialize};
+use core::fmt::{Display, Formatter};
+
+macro_rules! trace_index {
+    ($(#[$doc:meta])* $name:ident, $label:literal) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(pub usize);
+
+        impl $name {
+            /// Underlying index
+            pub const fn index(self) -> usize {
+                self.0
+            }
+
+            /// Index after this one
+            pub const fn next(self) -> Self {
+                Self(self.0 + 1)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(index: $name) -> Self {
+                index.0
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                write!(f, concat!($label, " {}"), self.0)
+            }
+        }
+    };
+}
+
+trace_index!(
+    /// Row of an execution trace
+    RowIdx,
+    "row"
+);
+
+trace_index!(
+    /// Column of an execution trace, which is also its register
+    ColIdx,
+    "column"
+);
+
+trace_index!(
+    /// Step of a computation, the trace row an AIR constraint applies at
+    StepIdx,
+    "step"
+);
+
+impl From<StepIdx> for RowIdx {
+    fn from(step: StepIdx) -> Self {
+        RowIdx(step.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_indices() {
+        assert_eq!(RowIdx(3).to_string(), "row 3");
+        assert_eq!(ColIdx(1).to_string(), "column 1");
+        assert_eq!(StepIdx(0).next(), StepIdx(1));
+        assert_eq!(RowIdx::from(StepIdx(7)), RowIdx(7));
+        assert_eq!(usize::from(ColIdx(5)), 5);
+        assert!(RowIdx(2) < RowIdx(10));
+    }
+}
@@ -11,20 +11,33 @@
 //! - **Performance**: Zero-cost abstractions for all type operations
 //! - **Documentation**: Mathematical notation and comprehensive examples
 
+use crate::prelude::*;
 use core::fmt::{Debug, Display};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod digest;
+pub mod encoding;
+pub mod extension;
 pub mod field;
+pub(crate) mod hex;
+pub mod index;
 pub mod polynomial;
 pub mod stark;
 pub mod secret;
+pub mod storage;
+#[cfg(any(test, feature = "test-vectors"))]
+pub mod test_vectors;
 
 pub use field::*;
 pub use polynomial::*;
 pub use stark::*;
 pub use secret::*;
+pub use digest::Digest;
+pub use extension::ExtensionElement;
+pub use index::{ColIdx, RowIdx, StepIdx};
+pub use storage::TraceStorage;
 
 /// Error types for the type system
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
@@ -49,13 +62,81 @@ pub enum TypeError {
     /// Memory safety error
     #[error("Memory safety error: {0}")]
     MemoryError(String),
+
+    /// A component holds another number of values than it must
+    #[error("{name} has length {actual}; expected {expected}")]
+    LengthMismatch {
+        /// Component with the wrong length
+        name: &'static str,
+        /// Length it must have
+        expected: usize,
+        /// Length it has
+        actual: usize,
+    },
+
+    /// A component that must hold values is empty
+    #[error("Empty {name}")]
+    EmptyComponent {
+        /// Empty component
+        name: &'static str,
+    },
+
+    /// A value is not in the one form it may be encoded in
+    #[error("Non-canonical {name}")]
+    NonCanonicalValue {
+        /// Kind of value
+        name: &'static str,
+    },
+
+    /// An encoded tag names no variant
+    #[error("Unknown {name} tag {tag}")]
+    UnknownTag {
+        /// Kind of value the tag selects
+        name: &'static str,
+        /// Tag read
+        tag: u8,
+    },
+
+    /// Input ended before a value was read in full
+    #[error("Unexpected end of input: {needed} bytes needed, {remaining} left")]
+    UnexpectedEnd {
+        /// Bytes the value needs
+        needed: usize,
+        /// Bytes left to read
+        remaining: usize,
+    },
+
+    /// Bytes are left over after a value was read
+    #[error("{count} trailing bytes")]
+    TrailingBytes {
+        /// Bytes left over
+        count: usize,
+    },
+
+    /// Encoded proof does not start with the magic bytes of its kind
+    #[error("Invalid proof magic {actual:?}; expected {expected:?}")]
+    InvalidMagic {
+        /// Magic bytes of the proof kind being decoded
+        expected: [u8; 4],
+        /// Bytes the input starts with
+        actual: [u8; 4],
+    },
+
+    /// Proof encoded with another version of the format
+    #[error("Unsupported proof format version {actual}; expected {expected}")]
+    UnsupportedVersion {
+        /// Version this build reads and writes
+        expected: u16,
+        /// Version of the input
+        actual: u16,
+    },
 }
 
 /// Core trait for field elements with cryptographic properties
 pub trait FieldElement: 
     Copy + Clone + Debug + Display + PartialEq + Eq + PartialOrd + Ord +
     Add<Output = Self> + AddAssign + Sub<Output = Self> + SubAssign +
-    Mul<Output = Self> + MulAssign + Neg<Output = Self> +
+    Mul<Output = Self> + MulAssign + Neg<Output = Self> + Send + Sync +
     Serialize + for<'de> Deserialize<'de>
 {
     /// The field modulus (prime number)
@@ -101,7 +182,23 @@ pub trait FieldElement:
     fn from_bytes(bytes: &[u8; 32]) -> Option<Self>;
     
     /// Random field element
+    #[cfg(feature = "std")]
     fn random() -> Self;
+    
+    /// Element whose canonical representative is `value` reduced modulo the field modulus
+    fn from_canonical_u64(value: u64) -> Self;
+    
+    /// Canonical integer representative in `0..MODULUS`
+    fn as_canonical_u64(&self) -> u64;
+    
+    /// Element `W` for which `x^degree - W` is irreducible, defining the
+    /// extension of that degree used by [`extension::ExtensionElement`]
+    ///
+    /// Fields without an extension of the given degree return `None`.
+    fn extension_nonresidue(degree: usize) -> Option<Self> {
+        let _ = degree;
+        None
+    }
 }
 
 /// Trait for polynomial operations
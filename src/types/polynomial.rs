@@ -3,8 +3,9 @@
 //! This module provides type-safe polynomial implementations for STARK proof operations,
 //! ensuring efficient arithmetic and evaluation with field element coefficients.
 
-use std::fmt::{Debug, Display, Formatter};
-use std::ops::Sub;
+use crate::prelude::*;
+use core::fmt::{Debug, Display, Formatter};
+use core::ops::Sub;
 use serde::{Deserialize, Serialize};
 use super::{FieldElement, Polynomial, TypeError};
 use crate::Result;
@@ -247,7 +248,7 @@ impl<F: FieldElement> Polynomial<F> for FieldPolynomial<F> {
 }
 
 impl<F: FieldElement> Display for FieldPolynomial<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         if self.is_zero() {
             return write!(f, "0");
         }
@@ -285,7 +286,7 @@ impl<F: FieldElement> Display for FieldPolynomial<F> {
 }
 
 // Standard arithmetic trait implementations
-impl<F: FieldElement> std::ops::Neg for FieldPolynomial<F> {
+impl<F: FieldElement> core::ops::Neg for FieldPolynomial<F> {
     type Output = Self;
     
     fn neg(self) -> Self::Output {
@@ -297,7 +298,7 @@ impl<F: FieldElement> std::ops::Neg for FieldPolynomial<F> {
     }
 }
 
-impl<F: FieldElement> std::ops::Add for FieldPolynomial<F> {
+impl<F: FieldElement> core::ops::Add for FieldPolynomial<F> {
     type Output = Self;
     
     fn add(self, other: Self) -> Self::Output {
@@ -305,7 +306,7 @@ impl<F: FieldElement> std::ops::Add for FieldPolynomial<F> {
     }
 }
 
-impl<F: FieldElement> std::ops::Sub for FieldPolynomial<F> {
+impl<F: FieldElement> core::ops::Sub for FieldPolynomial<F> {
     type Output = Self;
     
     fn sub(self, other: Self) -> Self::Output {
@@ -313,7 +314,7 @@ impl<F: FieldElement> std::ops::Sub for FieldPolynomial<F> {
     }
 }
 
-impl<F: FieldElement> std::ops::Mul for FieldPolynomial<F> {
+impl<F: FieldElement> core::ops::Mul for FieldPolynomial<F> {
     type Output = Self;
     
     fn mul(self, other: Self) -> Self::Output {
@@ -3,10 +3,15 @@
 //! This module provides secure secret type implementations with zeroization capabilities,
 //! ensuring cryptographic secrets are properly managed and cleared from memory.
 
+use crate::prelude::*;
 use core::fmt::{Debug, Formatter};
 use serde::{Deserialize, Serialize};
 use super::{Secret, TypeError};
+use super::stark::ExecutionTrace;
+use super::storage::zeroize_values;
+use super::FieldElement;
 use crate::Result;
+use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Secure secret wrapper with zeroization
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -98,7 +103,7 @@ impl Secret for SecureSecret {
         }
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
+    fn from_bytes(_bytes: &[u8]) -> core::result::Result<Self, TypeError> {
         // Placeholder implementation
         Err(TypeError::InvalidConversion("Not implemented".to_string()))
     }
@@ -188,7 +193,7 @@ impl<F: Clone + PartialEq + Eq> Secret for SecureFieldElement<F> {
         }
     }
     
-    fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypeError> {
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
         // Placeholder implementation
         Err(TypeError::InvalidConversion("Not implemented".to_string()))
     }
@@ -278,7 +283,7 @@ impl<P: Clone + PartialEq + Eq> Secret for SecurePolynomial<P> {
         }
     }
     
-    fn from_bytes(bytes: &[u8]) -> std::result::Result<Self, TypeError> {
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
         // Placeholder implementation
         Err(TypeError::InvalidConversion("Not implemented".to_string()))
     }
@@ -300,6 +305,89 @@ impl<P: Clone + PartialEq + Eq> Drop for SecurePolynomial<P> {
     }
 }
 
+/// Witness of a proof, overwritten with zero when dropped
+///
+/// Holds the execution trace and, optionally, the initial state it was
+/// generated from. [`StarkProver::prove`](crate::proof::StarkProver::prove)
+/// and the other prove methods accept it wherever they accept a trace, so
+/// the witness can be proved from and then dropped without its values
+/// lingering in memory. Mapped columns are zeroed in their files.
+///
+/// The witness is not `Clone`, and its `Debug` output shows only its shape.
+pub struct SecretWitness<F: FieldElement> {
+    /// Execution trace
+    trace: ExecutionTrace<F>,
+    /// State the trace was generated from
+    initial_state: Vec<F>,
+}
+
+impl<F: FieldElement> SecretWitness<F> {
+    /// Witness of an execution trace
+    pub fn new(trace: ExecutionTrace<F>) -> Self {
+        Self { trace, initial_state: Vec::new() }
+    }
+
+    /// Keep the initial state the trace was generated from
+    pub fn with_initial_state(mut self, initial_state: Vec<F>) -> Self {
+        zeroize_values(&mut self.initial_state);
+        self.initial_state = initial_state;
+        self
+    }
+
+    /// Execution trace
+    pub fn trace(&self) -> &ExecutionTrace<F> {
+        &self.trace
+    }
+
+    /// Initial state, empty if none was kept
+    pub fn initial_state(&self) -> &[F] {
+        &self.initial_state
+    }
+}
+
+impl<F: FieldElement> From<ExecutionTrace<F>> for SecretWitness<F> {
+    fn from(trace: ExecutionTrace<F>) -> Self {
+        Self::new(trace)
+    }
+}
+
+impl<F: FieldElement> AsRef<ExecutionTrace<F>> for SecretWitness<F> {
+    fn as_ref(&self) -> &ExecutionTrace<F> {
+        &self.trace
+    }
+}
+
+/// Overwrites the trace columns, the aux segments and the initial state
+///
+/// The shape of the trace is kept, so a zeroized witness is a trace of zeros.
+impl<F: FieldElement> Zeroize for SecretWitness<F> {
+    fn zeroize(&mut self) {
+        self.trace.columns.zeroize();
+        for column in self.trace.aux_segments.iter_mut().flatten() {
+            zeroize_values(column);
+        }
+        zeroize_values(&mut self.initial_state);
+    }
+}
+
+impl<F: FieldElement> ZeroizeOnDrop for SecretWitness<F> {}
+
+impl<F: FieldElement> Drop for SecretWitness<F> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<F: FieldElement> Debug for SecretWitness<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "SecretWitness(***HIDDEN***, length={}, registers={})",
+            self.trace.length, self.trace.num_registers
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,12 +3,16 @@
 //! This module provides type-safe STARK proof component definitions,
 //! ensuring cryptographic security and mathematical correctness.
 
-use std::fmt::{Display, Formatter};
-use std::marker::PhantomData;
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
+use core::marker::PhantomData;
 use serde::{Serialize, Deserialize};
-use crate::types::{FieldElement, StarkComponent, TypeError};
+use crate::types::{ColIdx, Digest, ExtensionElement, FieldElement, RowIdx, StarkComponent, TraceStorage, TypeError};
+use crate::types::encoding::{decode_from_slice, encode_to_vec, encoded_len, ByteReader, ByteWriter, Encode};
 use crate::Result;
 
+pub use crate::air::constraints::ConstraintType;
+
 /// STARK proof error
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum StarkError {
@@ -37,8 +41,50 @@ pub enum StarkError {
     MerkleError(String),
 }
 
+/// Magic bytes opening every serialized proof
+pub const PROOF_MAGIC: [u8; 4] = *b"XFGP";
+
+/// Magic bytes opening every serialized detached proof
+pub const DETACHED_PROOF_MAGIC: [u8; 4] = *b"XFGD";
+
+/// Version of the serialized proof format
+pub const PROOF_FORMAT_VERSION: u16 = 9;
+
+/// Hash function of the native prover's commitments and transcript
+pub const NATIVE_HASH: HashId = HashId::Sha256;
+
+/// Body of a proof: the native components, or a proof of another backend
+///
+/// A proof of another backend is kept in that backend's own serialization,
+/// byte for byte, and the native components of the proof are left empty.
+/// The native verifier rejects such proofs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofBody {
+    /// Proof of the native prover, in the other fields of the proof
+    Native,
+    /// Proof of the Winterfell backend
+    Winterfell {
+        /// Winterfell's serialization of the proof
+        #[serde(with = "crate::types::hex")]
+        proof: Vec<u8>,
+        /// Public input elements the proof was generated for, as canonical integers
+        inputs: Vec<u64>,
+    },
+}
+
+impl ProofBody {
+    /// Whether the proof is in the native components
+    pub fn is_native(&self) -> bool {
+        matches!(self, Self::Native)
+    }
+}
+
 /// STARK proof structure
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The serialized form starts with a header of [`PROOF_MAGIC`],
+/// [`PROOF_FORMAT_VERSION`] and the field modulus identifying the field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct StarkProof<F: FieldElement> {
     /// Execution trace
     pub trace: ExecutionTrace<F>,
@@ -46,38 +92,236 @@ pub struct StarkProof<F: FieldElement> {
     pub air: Air<F>,
     /// Merkle tree commitments
     pub commitments: Vec<MerkleCommitment<F>>,
+    /// Out-of-domain evaluation frame
+    pub ood_frame: OodFrame<F>,
     /// FRI (Fast Reed-Solomon Interactive Oracle Proof) components
     pub fri_proof: FriProof<F>,
+    /// Openings of the committed LDEs at the query positions
+    pub openings: Vec<QueryOpening<F>>,
     /// Proof metadata
     pub metadata: ProofMetadata,
+    /// Native proof, or the proof of another backend
+    pub body: ProofBody,
+}
+
+/// Bytes each section of a proof takes in its canonical encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProofSizeBreakdown {
+    /// Magic bytes, format version and field modulus
+    pub header: usize,
+    /// Execution trace
+    pub trace: usize,
+    /// AIR
+    pub air: usize,
+    /// Merkle tree commitments
+    pub commitments: usize,
+    /// Out-of-domain evaluation frame
+    pub ood_frame: usize,
+    /// FRI proof
+    pub fri_proof: usize,
+    /// Query openings
+    pub openings: usize,
+    /// Proof metadata
+    pub metadata: usize,
+    /// Body, with the serialized proof of another backend
+    pub body: usize,
+}
+
+impl ProofSizeBreakdown {
+    /// Size of the whole encoding
+    pub fn total(&self) -> usize {
+        self.header
+            + self.trace
+            + self.air
+            + self.commitments
+            + self.ood_frame
+            + self.fri_proof
+            + self.openings
+            + self.metadata
+            + self.body
+    }
+}
+
+impl Display for ProofSizeBreakdown {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} bytes: header {}, trace {}, air {}, commitments {}, ood frame {}, fri {}, openings {}, metadata {}, \
+             body {}",
+            self.total(),
+            self.header,
+            self.trace,
+            self.air,
+            self.commitments,
+            self.ood_frame,
+            self.fri_proof,
+            self.openings,
+            self.metadata,
+            self.body
+        )
+    }
 }
 
 impl<F: FieldElement> Display for StarkProof<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "StarkProof(trace={}, commitments={}, metadata={})", 
                self.trace, self.commitments.len(), self.metadata)
     }
 }
 
+/// STARK proof without its AIR
+///
+/// Verifiers that know the AIR supply it themselves (see
+/// [`StarkVerifier::verify_detached`](crate::proof::StarkVerifier::verify_detached)),
+/// so the AIR is not shipped with every proof. The proof stays bound to the
+/// AIR through the digest in its metadata. The serialized form has the header
+/// of [`StarkProof`] with [`DETACHED_PROOF_MAGIC`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DetachedProof<F: FieldElement> {
+    /// Execution trace
+    pub trace: ExecutionTrace<F>,
+    /// Merkle tree commitments
+    pub commitments: Vec<MerkleCommitment<F>>,
+    /// Out-of-domain evaluation frame
+    pub ood_frame: OodFrame<F>,
+    /// FRI (Fast Reed-Solomon Interactive Oracle Proof) components
+    pub fri_proof: FriProof<F>,
+    /// Openings of the committed LDEs at the query positions
+    pub openings: Vec<QueryOpening<F>>,
+    /// Proof metadata
+    pub metadata: ProofMetadata,
+    /// Native proof, or the proof of another backend
+    pub body: ProofBody,
+}
+
+impl<F: FieldElement> DetachedProof<F> {
+    /// Reattach the AIR the proof was generated for
+    pub fn attach(self, air: Air<F>) -> StarkProof<F> {
+        StarkProof {
+            trace: self.trace,
+            air,
+            commitments: self.commitments,
+            ood_frame: self.ood_frame,
+            fri_proof: self.fri_proof,
+            openings: self.openings,
+            metadata: self.metadata,
+            body: self.body,
+        }
+    }
+}
+
+impl<F: FieldElement> Display for DetachedProof<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "DetachedProof(trace={}, commitments={}, metadata={})",
+               self.trace, self.commitments.len(), self.metadata)
+    }
+}
+
 /// Execution trace for STARK proof
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct ExecutionTrace<F: FieldElement> {
-    /// Trace columns
-    pub columns: Vec<Vec<F>>,
+    /// Trace columns, in memory or memory-mapped
+    pub columns: TraceStorage<F>,
     /// Trace length
     pub length: usize,
     /// Number of registers
     pub num_registers: usize,
+    /// Columns of each auxiliary segment, built from verifier challenges
+    #[serde(with = "crate::types::hex")]
+    pub aux_segments: Vec<Vec<Vec<F>>>,
+}
+
+impl<F: FieldElement> AsRef<ExecutionTrace<F>> for ExecutionTrace<F> {
+    fn as_ref(&self) -> &ExecutionTrace<F> {
+        self
+    }
 }
 
 impl<F: FieldElement> Display for ExecutionTrace<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "ExecutionTrace(length={}, registers={})", self.length, self.num_registers)
     }
 }
 
+impl<F: FieldElement> ExecutionTrace<F> {
+    /// Main columns at `row`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not below the trace length.
+    pub fn row(&self, row: RowIdx) -> RowView<'_, F> {
+        assert!(row.0 < self.length, "{} out of bounds for a trace of {} rows", row, self.length);
+        RowView { columns: &self.columns, row: row.0 }
+    }
+
+    /// Iterate over the rows in order
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = RowView<'_, F>> + '_ {
+        (0..self.length).map(move |row| RowView { columns: &self.columns, row })
+    }
+
+    /// Value of main column `column` at `row`, if both are in bounds
+    pub fn get(&self, row: RowIdx, column: ColIdx) -> Option<F> {
+        let (row, column) = (row.0, column.0);
+        (row < self.length && column < self.columns.len() && row < self.columns.column_len(column))
+            .then(|| self.columns.value(column, row))
+    }
+
+    /// Main columns as rows, one vector of `columns.len()` values per row
+    pub fn transpose(&self) -> Vec<Vec<F>> {
+        let mut rows = vec![Vec::with_capacity(self.columns.len()); self.length];
+        for column in self.columns.iter() {
+            for (row, &value) in rows.iter_mut().zip(column.iter()) {
+                row.push(value);
+            }
+        }
+        rows
+    }
+}
+
+/// Values of the main trace columns at one row, read on access
+#[derive(Debug, Clone, Copy)]
+pub struct RowView<'a, F: FieldElement> {
+    columns: &'a TraceStorage<F>,
+    row: usize,
+}
+
+impl<F: FieldElement> RowView<'_, F> {
+    /// Index of the row in the trace
+    pub fn index(&self) -> RowIdx {
+        RowIdx(self.row)
+    }
+
+    /// Number of columns
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Whether the trace has no main columns
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Value of `column`, if it is in bounds
+    pub fn get(&self, column: ColIdx) -> Option<F> {
+        (column.0 < self.columns.len()).then(|| self.columns.value(column.0, self.row))
+    }
+
+    /// Iterate over the values in column order
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = F> + '_ {
+        (0..self.columns.len()).map(move |column| self.columns.value(column, self.row))
+    }
+
+    /// Copy the values into a vector
+    pub fn to_vec(&self) -> Vec<F> {
+        self.iter().collect()
+    }
+}
+
 /// AIR (Algebraic Intermediate Representation) constraints
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Air<F: FieldElement> {
     /// Constraint polynomials
     pub constraints: Vec<Constraint<F>>,
@@ -90,15 +334,17 @@ pub struct Air<F: FieldElement> {
 }
 
 impl<F: FieldElement> Display for Air<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "Air(constraints={}, security={})", self.constraints.len(), self.security_parameter)
     }
 }
 
 /// Constraint in AIR
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct Constraint<F: FieldElement> {
     /// Constraint polynomial
+    #[serde(with = "crate::types::hex")]
     pub polynomial: Vec<F>,
     /// Constraint degree
     pub degree: usize,
@@ -107,157 +353,415 @@ pub struct Constraint<F: FieldElement> {
 }
 
 impl<F: FieldElement> Display for Constraint<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "Constraint(degree={}, type={:?})", self.degree, self.constraint_type)
     }
 }
 
-/// Constraint types
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub enum ConstraintType {
-    /// Transition constraint
-    Transition,
-    /// Boundary constraint
-    Boundary,
-    /// Algebraic constraint
-    Algebraic,
-}
-
 /// Transition function
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct TransitionFunction<F: FieldElement> {
     /// Function coefficients
+    #[serde(with = "crate::types::hex")]
     pub coefficients: Vec<Vec<F>>,
     /// Function degree
     pub degree: usize,
 }
 
 impl<F: FieldElement> Display for TransitionFunction<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "TransitionFunction(degree={}, coefficients={})", self.degree, self.coefficients.len())
     }
 }
 
 /// Boundary conditions
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct BoundaryConditions<F: FieldElement> {
     /// Boundary constraints
     pub constraints: Vec<BoundaryConstraint<F>>,
 }
 
 impl<F: FieldElement> Display for BoundaryConditions<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "BoundaryConditions(constraints={})", self.constraints.len())
     }
 }
 
 /// Boundary constraint
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct BoundaryConstraint<F: FieldElement> {
     /// Register index
     pub register: usize,
     /// Step index
     pub step: usize,
     /// Expected value
+    #[serde(with = "crate::types::hex")]
     pub value: F,
 }
 
 impl<F: FieldElement> Display for BoundaryConstraint<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "BoundaryConstraint(register={}, step={})", self.register, self.step)
     }
 }
 
 /// Merkle tree commitment
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct MerkleCommitment<F: FieldElement> {
     /// Root hash
+    #[serde(with = "crate::types::hex")]
     pub root: Vec<u8>,
     /// Tree depth
     pub depth: usize,
-    /// Leaf values
+    /// Leaf values (empty when only the root is sent and leaves are opened on query)
+    #[serde(with = "crate::types::hex")]
     pub leaves: Vec<F>,
 }
 
+impl<F: FieldElement> MerkleCommitment<F> {
+    /// Root of the tree, if it is a 32-byte digest
+    pub fn root_digest(&self) -> Option<Digest> {
+        Digest::try_from(self.root.as_slice()).ok()
+    }
+}
+
 impl<F: FieldElement> Display for MerkleCommitment<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "MerkleCommitment(depth={}, leaves={})", self.depth, self.leaves.len())
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.root_digest() {
+            Some(root) => {
+                write!(f, "MerkleCommitment(root={}, depth={}, leaves={})", root, self.depth, self.leaves.len())
+            }
+            None => write!(f, "MerkleCommitment(depth={}, leaves={})", self.depth, self.leaves.len()),
+        }
+    }
+}
+
+/// Out-of-domain (OOD) evaluation frame
+///
+/// Trace and composition polynomial evaluations at the out-of-domain point `z`
+/// drawn from the transcript, and the trace evaluations at the next-row point.
+/// AIRs whose constraints read a window of `w > 2` rows also open the trace at
+/// the `w - 2` points after the next-row point.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct OodFrame<F: FieldElement> {
+    /// Trace column evaluations at `z`
+    #[serde(with = "crate::types::hex")]
+    pub trace_current: Vec<F>,
+    /// Trace column evaluations at the next-row point of `z`
+    #[serde(with = "crate::types::hex")]
+    pub trace_next: Vec<F>,
+    /// Trace column evaluations at the points after the next-row point, for
+    /// constraints reading more than two rows
+    #[serde(with = "crate::types::hex")]
+    pub trace_window: Vec<Vec<F>>,
+    /// Composition polynomial evaluation at `z`, one value per coordinate of
+    /// the field extension
+    #[serde(with = "crate::types::hex")]
+    pub composition: Vec<F>,
+}
+
+impl<F: FieldElement> Default for OodFrame<F> {
+    fn default() -> Self {
+        Self {
+            trace_current: Vec::new(),
+            trace_next: Vec::new(),
+            trace_window: Vec::new(),
+            composition: Vec::new(),
+        }
+    }
+}
+
+impl<F: FieldElement> Display for OodFrame<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "OodFrame(registers={})", self.trace_current.len())
+    }
+}
+
+/// Opening of an auxiliary segment commitment at one LDE position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SegmentOpening<F: FieldElement> {
+    /// Segment row at the position
+    #[serde(with = "crate::types::hex")]
+    pub row: Vec<F>,
+    /// Authentication path of the segment row
+    #[serde(with = "crate::types::hex")]
+    pub path: Vec<[u8; 32]>,
+    /// Salt of the segment leaf (zero-knowledge proofs only)
+    #[serde(with = "crate::types::hex")]
+    pub salt: Option<[u8; 32]>,
+}
+
+/// Opening of the trace and composition commitments at one LDE position
+///
+/// Authentication paths list the sibling hashes from the leaf up to the root;
+/// path directions follow from the bits of `position`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct QueryOpening<F: FieldElement> {
+    /// Position in the LDE domain
+    pub position: usize,
+    /// Trace row at the position
+    #[serde(with = "crate::types::hex")]
+    pub trace_row: Vec<F>,
+    /// Authentication path of the trace row
+    #[serde(with = "crate::types::hex")]
+    pub trace_path: Vec<[u8; 32]>,
+    /// Salt of the trace leaf (zero-knowledge proofs only)
+    #[serde(with = "crate::types::hex")]
+    pub trace_salt: Option<[u8; 32]>,
+    /// Openings of the auxiliary segments at the position
+    pub aux_segments: Vec<SegmentOpening<F>>,
+    /// Composition polynomial evaluation at the position, one value per
+    /// coordinate of the field extension
+    #[serde(with = "crate::types::hex")]
+    pub composition: Vec<F>,
+    /// Masking polynomial evaluation at the position (zero-knowledge proofs only)
+    #[serde(with = "crate::types::hex")]
+    pub masking: Option<F>,
+    /// Authentication path of the composition evaluation
+    #[serde(with = "crate::types::hex")]
+    pub composition_path: Vec<[u8; 32]>,
+    /// Salt of the composition leaf (zero-knowledge proofs only)
+    #[serde(with = "crate::types::hex")]
+    pub composition_salt: Option<[u8; 32]>,
+}
+
+impl<F: FieldElement> Display for SegmentOpening<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SegmentOpening(columns={})", self.row.len())
+    }
+}
+
+impl<F: FieldElement> Display for QueryOpening<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "QueryOpening(position={}, registers={})", self.position, self.trace_row.len())
     }
 }
 
 /// FRI proof components
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriProof<F: FieldElement> {
+    /// Degree of the field extension the folding challenges are drawn from
+    ///
+    /// The first layer is in the base field; every folded layer, the final
+    /// polynomial and their query responses hold `extension_degree`
+    /// coordinates per value.
+    pub extension_degree: usize,
     /// FRI layers
     pub layers: Vec<FriLayer<F>>,
     /// Final polynomial
+    #[serde(with = "crate::types::hex")]
     pub final_polynomial: Vec<F>,
     /// Query responses
     pub queries: Vec<FriQuery<F>>,
 }
 
 impl<F: FieldElement> Display for FriProof<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "FriProof(layers={}, queries={})", self.layers.len(), self.queries.len())
     }
 }
 
 /// FRI layer
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriLayer<F: FieldElement> {
-    /// Layer polynomial
+    /// Layer polynomial, with the coordinates of each coefficient in sequence
+    #[serde(with = "crate::types::hex")]
     pub polynomial: Vec<F>,
     /// Layer commitment
+    #[serde(with = "crate::types::hex")]
     pub commitment: Vec<u8>,
-    /// Layer degree
+    /// Layer degree (number of coefficients)
     pub degree: usize,
 }
 
 impl<F: FieldElement> Display for FriLayer<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "FriLayer(degree={})", self.degree)
     }
 }
 
 /// FRI query
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct FriQuery<F: FieldElement> {
     /// Query point
+    #[serde(with = "crate::types::hex")]
     pub point: F,
-    /// Query responses
+    /// Query responses, with the coordinates of each layer evaluation in sequence
+    #[serde(with = "crate::types::hex")]
     pub responses: Vec<F>,
 }
 
 impl<F: FieldElement> Display for FriQuery<F> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "FriQuery(responses={})", self.responses.len())
     }
 }
 
+/// Field a proof is generated over
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FieldId {
+    /// Mersenne field of modulus 2^61 - 1, [`PrimeField64`](crate::types::field::PrimeField64)
+    Mersenne61,
+    /// Goldilocks field of modulus 2^64 - 2^32 + 1
+    Goldilocks,
+    /// Any other prime field, by its modulus
+    Other(u64),
+}
+
+impl FieldId {
+    /// Modulus of the Mersenne field
+    const MERSENNE61_MODULUS: u64 = (1 << 61) - 1;
+    /// Modulus of the Goldilocks field
+    const GOLDILOCKS_MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+    /// Field of `F`
+    pub fn of<F: FieldElement>() -> Self {
+        Self::from_modulus(F::MODULUS)
+    }
+
+    /// Field of the given modulus, named if it is a known one
+    pub fn from_modulus(modulus: u64) -> Self {
+        match modulus {
+            Self::MERSENNE61_MODULUS => Self::Mersenne61,
+            Self::GOLDILOCKS_MODULUS => Self::Goldilocks,
+            modulus => Self::Other(modulus),
+        }
+    }
+
+    /// Modulus of the field
+    pub fn modulus(&self) -> u64 {
+        match *self {
+            Self::Mersenne61 => Self::MERSENNE61_MODULUS,
+            Self::Goldilocks => Self::GOLDILOCKS_MODULUS,
+            Self::Other(modulus) => modulus,
+        }
+    }
+}
+
+impl Display for FieldId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Mersenne61 => write!(f, "mersenne61"),
+            Self::Goldilocks => write!(f, "goldilocks"),
+            Self::Other(modulus) => write!(f, "field {:#x}", modulus),
+        }
+    }
+}
+
+/// Hash function a proof commits with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HashId {
+    /// SHA-256, the hash of the native prover
+    #[serde(rename = "sha256")]
+    Sha256,
+    /// BLAKE3 with 256-bit digests
+    #[serde(rename = "blake3-256")]
+    Blake3,
+    /// SHA3 with 256-bit digests
+    #[serde(rename = "sha3-256")]
+    Sha3,
+    /// Rescue Prime Optimized over the 64-bit Goldilocks field
+    #[serde(rename = "rpo-256")]
+    Rpo,
+}
+
+impl HashId {
+    /// Every hash function, in the order of their tags
+    pub const ALL: [HashId; 4] = [HashId::Sha256, HashId::Blake3, HashId::Sha3, HashId::Rpo];
+
+    /// Name of the hash function
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Blake3 => "blake3-256",
+            Self::Sha3 => "sha3-256",
+            Self::Rpo => "rpo-256",
+        }
+    }
+
+    /// Hash function of the given name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|hash| hash.name() == name)
+    }
+}
+
+impl Display for HashId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parameters a proof was generated with
+///
+/// All of them are absorbed into the transcript, so a proof cannot claim
+/// other parameters than those it was generated with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofParameters {
+    /// Security parameter
+    pub security_parameter: u32,
+    /// Whether the proof was generated in zero-knowledge mode
+    pub zero_knowledge: bool,
+    /// Blowup factor of the trace domain extension
+    pub blowup_factor: usize,
+    /// Number of queries opened by the proof
+    pub num_queries: usize,
+    /// Degree of the field extension the composition and FRI challenges are drawn from
+    pub field_extension_degree: u32,
+    /// Proof-of-work bits the nonce was ground to
+    pub grinding_bits: u32,
+}
+
+impl Display for ProofParameters {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "security={}, zk={}, blowup={}, queries={}, extension={}, grinding={}",
+               self.security_parameter, self.zero_knowledge, self.blowup_factor,
+               self.num_queries, self.field_extension_degree, self.grinding_bits)
+    }
+}
+
 /// Proof metadata
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProofMetadata {
     /// Proof version
     pub version: u32,
-    /// Security parameter
-    pub security_parameter: u32,
-    /// Field modulus
-    pub field_modulus: String,
-    /// Proof size
+    /// Version of the crate that generated the proof
+    pub prover_version: String,
+    /// Field the proof is over
+    pub field: FieldId,
+    /// Hash function the proof commits with, which its verifier must instantiate
+    pub hash_id: HashId,
+    /// Digest of the AIR the proof was generated for
+    pub air_digest: Digest,
+    /// Parameters the proof was generated with
+    pub parameters: ProofParameters,
+    /// Proof-of-work nonce over the finished transcript
+    pub grinding_nonce: u64,
+    /// Length of the canonical encoding of the proof, AIR included
     pub proof_size: usize,
     /// Generation timestamp
     pub timestamp: u64,
 }
 
 impl Display for ProofMetadata {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "ProofMetadata(version={}, security={}, size={})", 
-               self.version, self.security_parameter, self.proof_size)
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ProofMetadata(version={}, prover={}, field={}, hash={}, size={}, air={}, {})",
+               self.version, self.prover_version, self.field, self.hash_id, self.proof_size,
+               self.air_digest, self.parameters)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for StarkProof<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         // Validate trace
         self.trace.validate()?;
         
@@ -269,59 +773,277 @@ impl<F: FieldElement> StarkComponent<F> for StarkProof<F> {
             commitment.validate()?;
         }
         
+        // Validate OOD frame
+        self.ood_frame.validate()?;
+        
         // Validate FRI proof
         self.fri_proof.validate()?;
         
+        // Validate query openings
+        for opening in &self.openings {
+            opening.validate()?;
+        }
+        
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_proof::<F, _>(&PROOF_MAGIC, self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        let proof: Self = decode_proof::<F, _>(&PROOF_MAGIC, bytes)?;
+        check_path_lengths(&proof.commitments, &proof.openings)?;
+        // Decoding is strict, so this only fails if an encoder and decoder
+        // disagree; it pins every proof to exactly one encoding
+        if proof.to_bytes() != bytes {
+            return Err(TypeError::NonCanonicalValue { name: "proof encoding" });
+        }
+        Ok(proof)
+    }
+}
+
+impl<F: FieldElement> StarkProof<F> {
+    /// Bytes each section of the proof takes in [`to_bytes`](StarkComponent::to_bytes)
+    ///
+    /// The sizes are counted without encoding the proof into a buffer.
+    pub fn size_breakdown(&self) -> ProofSizeBreakdown {
+        let size = |write: &dyn Fn(&mut ByteWriter)| {
+            let mut writer = ByteWriter::counting();
+            write(&mut writer);
+            writer.len()
+        };
+        ProofSizeBreakdown {
+            header: size(&|writer| write_header::<F>(writer, &PROOF_MAGIC)),
+            trace: encoded_len(&self.trace),
+            air: encoded_len(&self.air),
+            commitments: size(&|writer| writer.write_sequence(&self.commitments)),
+            ood_frame: encoded_len(&self.ood_frame),
+            fri_proof: encoded_len(&self.fri_proof),
+            openings: size(&|writer| writer.write_sequence(&self.openings)),
+            metadata: encoded_len(&self.metadata),
+            body: encoded_len(&self.body),
+        }
+    }
+
+    /// Record the size of the proof's encoding in its metadata
+    ///
+    /// The size field has a fixed width, so setting it does not change the size.
+    pub fn with_encoded_size(mut self) -> Self {
+        self.metadata.proof_size = self.size_breakdown().total();
+        self
+    }
+
+    /// Split off the AIR, leaving a proof for verifiers that supply it
+    pub fn into_detached(self) -> DetachedProof<F> {
+        DetachedProof {
+            trace: self.trace,
+            commitments: self.commitments,
+            ood_frame: self.ood_frame,
+            fri_proof: self.fri_proof,
+            openings: self.openings,
+            metadata: self.metadata,
+            body: self.body,
+        }
+    }
+}
+
+/// JSON form of proofs, for inspection in tooling and bug reports
+///
+/// Field elements are written as hex of their canonical integers, digests
+/// and byte strings as hex of their bytes. The binary encoding of
+/// [`to_bytes`](StarkComponent::to_bytes) stays the format to ship proofs in.
+#[cfg(feature = "json")]
+impl<F: FieldElement> StarkProof<F> {
+    /// Pretty-printed JSON of the proof
+    pub fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("proofs have no map keys to reject")
+    }
+
+    /// Parse a proof from its JSON form
+    ///
+    /// Field elements must be below the modulus and authentication paths no
+    /// longer than their trees, as in [`from_bytes`](StarkComponent::from_bytes).
+    pub fn from_json(json: &str) -> core::result::Result<Self, TypeError> {
+        let proof: Self = serde_json::from_str(json)
+            .map_err(|error| TypeError::InvalidConversion(format!("Invalid proof JSON: {}", error)))?;
+        check_path_lengths(&proof.commitments, &proof.openings)?;
+        Ok(proof)
+    }
+}
+
+/// CBOR and MessagePack forms of proofs, for transports built around them
+///
+/// Both are the serde form of the proof with named fields, and neither
+/// carries the header of [`to_bytes`](StarkComponent::to_bytes): the two
+/// ends must agree on the field out of band, as they must on the format.
+impl<F: FieldElement> StarkProof<F> {
+    /// CBOR encoding of the proof
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes).expect("writing to a vector does not fail");
+        bytes
+    }
+
+    /// Decode a proof from its CBOR encoding
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        let proof: Self = ciborium::from_reader(bytes)
+            .map_err(|error| TypeError::InvalidConversion(format!("Invalid proof CBOR: {}", error)))?;
+        check_path_lengths(&proof.commitments, &proof.openings)?;
+        Ok(proof)
+    }
+
+    /// MessagePack encoding of the proof
+    #[cfg(feature = "msgpack")]
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        rmp_serde::to_vec_named(self).expect("proofs have no map keys to reject")
+    }
+
+    /// Decode a proof from its MessagePack encoding
+    #[cfg(feature = "msgpack")]
+    pub fn from_msgpack(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        let proof: Self = rmp_serde::from_slice(bytes)
+            .map_err(|error| TypeError::InvalidConversion(format!("Invalid proof MessagePack: {}", error)))?;
+        check_path_lengths(&proof.commitments, &proof.openings)?;
+        Ok(proof)
     }
 }
 
+impl<F: FieldElement> StarkComponent<F> for DetachedProof<F> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
+        self.trace.validate()?;
+        for commitment in &self.commitments {
+            commitment.validate()?;
+        }
+        self.ood_frame.validate()?;
+        self.fri_proof.validate()?;
+        for opening in &self.openings {
+            opening.validate()?;
+        }
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_proof::<F, _>(&DETACHED_PROOF_MAGIC, self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        let proof: Self = decode_proof::<F, _>(&DETACHED_PROOF_MAGIC, bytes)?;
+        check_path_lengths(&proof.commitments, &proof.openings)?;
+        if proof.to_bytes() != bytes {
+            return Err(TypeError::NonCanonicalValue { name: "proof encoding" });
+        }
+        Ok(proof)
+    }
+}
+
+/// Serialize a proof body after the header identifying its kind, format and field
+fn encode_proof<F: FieldElement, T: Encode>(magic: &[u8; 4], proof: &T) -> Vec<u8> {
+    let mut writer = ByteWriter::new();
+    write_header::<F>(&mut writer, magic);
+    proof.encode(&mut writer);
+    writer.into_bytes()
+}
+
+/// Write the header of a serialized proof
+fn write_header<F: FieldElement>(writer: &mut ByteWriter, magic: &[u8; 4]) {
+    writer.write_raw(magic);
+    writer.write_u16(PROOF_FORMAT_VERSION);
+    writer.write_u64(F::MODULUS);
+}
+
+/// Deserialize a proof body, checking the header first
+fn decode_proof<F: FieldElement, T: Encode>(magic: &[u8; 4], bytes: &[u8]) -> core::result::Result<T, TypeError> {
+    let mut reader = ByteReader::new(bytes);
+    let actual: [u8; 4] = reader.read_raw(magic.len())?.try_into().expect("magic is 4 bytes");
+    if actual != *magic {
+        return Err(TypeError::InvalidMagic { expected: *magic, actual });
+    }
+    let version = reader.read_u16()?;
+    if version != PROOF_FORMAT_VERSION {
+        return Err(TypeError::UnsupportedVersion { expected: PROOF_FORMAT_VERSION, actual: version });
+    }
+    let field_id = reader.read_u64()?;
+    if field_id != F::MODULUS {
+        return Err(TypeError::TypeMismatch {
+            expected: format!("field {:#x}", F::MODULUS),
+            actual: format!("field {:#x}", field_id),
+        });
+    }
+    let proof = T::decode(&mut reader)?;
+    reader.finish()?;
+    Ok(proof)
+}
+
+/// Reject authentication paths longer than the tree they open
+///
+/// Surplus siblings do not change what a path authenticates, so they would
+/// give the same proof many encodings.
+fn check_path_lengths<F: FieldElement>(
+    commitments: &[MerkleCommitment<F>],
+    openings: &[QueryOpening<F>],
+) -> core::result::Result<(), TypeError> {
+    let depth = |index: usize| commitments.get(index).map_or(usize::BITS as usize, |commitment| commitment.depth);
+    let composition_depth = commitments.last().map_or(usize::BITS as usize, |commitment| commitment.depth);
+    for opening in openings {
+        let over_long = opening.trace_path.len() > depth(0)
+            || opening.composition_path.len() > composition_depth
+            || opening
+                .aux_segments
+                .iter()
+                .enumerate()
+                .any(|(index, segment)| segment.path.len() > depth(index + 1));
+        if over_long {
+            return Err(TypeError::InvalidConversion(format!(
+                "Over-long authentication path at position {}",
+                opening.position
+            )));
+        }
+    }
+    Ok(())
+}
+
 impl<F: FieldElement> StarkComponent<F> for ExecutionTrace<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.length == 0 {
-            return Err(TypeError::InvalidConversion("Empty trace".to_string()));
+            return Err(TypeError::EmptyComponent { name: "trace" });
         }
         
         if self.num_registers == 0 {
-            return Err(TypeError::InvalidConversion("No registers".to_string()));
+            return Err(TypeError::EmptyComponent { name: "trace registers" });
         }
         
-        if self.columns.len() != self.num_registers {
-            return Err(TypeError::InvalidConversion("Column count mismatch".to_string()));
+        // Columns are withheld from zero-knowledge proofs
+        if !self.columns.is_empty() && self.columns.len() != self.num_registers {
+            return Err(TypeError::LengthMismatch {
+                name: "trace columns",
+                expected: self.num_registers,
+                actual: self.columns.len(),
+            });
         }
         
-        for column in &self.columns {
-            if column.len() != self.length {
-                return Err(TypeError::InvalidConversion("Column length mismatch".to_string()));
-            }
+        let main_lengths = (0..self.columns.len()).map(|index| self.columns.column_len(index));
+        let aux_lengths = self.aux_segments.iter().flatten().map(Vec::len);
+        if let Some(length) = main_lengths.chain(aux_lengths).find(|&length| length != self.length) {
+            return Err(TypeError::LengthMismatch { name: "trace column", expected: self.length, actual: length });
         }
         
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for Air<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         // Validate constraints
         for constraint in &self.constraints {
             // Note: Constraint doesn't implement StarkComponent, so we skip validation
@@ -337,37 +1059,33 @@ impl<F: FieldElement> StarkComponent<F> for Air<F> {
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for TransitionFunction<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.coefficients.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty coefficients".to_string()));
+            return Err(TypeError::EmptyComponent { name: "transition coefficients" });
         }
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for BoundaryConditions<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         for constraint in &self.constraints {
             constraint.validate()?;
         }
@@ -375,64 +1093,111 @@ impl<F: FieldElement> StarkComponent<F> for BoundaryConditions<F> {
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for BoundaryConstraint<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for MerkleCommitment<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.root.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty root".to_string()));
+            return Err(TypeError::EmptyComponent { name: "commitment root" });
         }
         
-        if self.leaves.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty leaves".to_string()));
+        if self.root.len() != 32 {
+            return Err(TypeError::LengthMismatch { name: "commitment root", expected: 32, actual: self.root.len() });
         }
         
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
+    }
+    
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> StarkComponent<F> for OodFrame<F> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
+        let width = self.trace_current.len();
+        let mut rows = core::iter::once(&self.trace_next).chain(&self.trace_window);
+        if let Some(row) = rows.find(|row| row.len() != width) {
+            return Err(TypeError::LengthMismatch { name: "OOD frame row", expected: width, actual: row.len() });
+        }
+        Ok(())
+    }
+    
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_to_vec(self)
+    }
+    
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> StarkComponent<F> for QueryOpening<F> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
+        let depth = self.trace_path.len();
+        let mut paths =
+            core::iter::once(&self.composition_path).chain(self.aux_segments.iter().map(|segment| &segment.path));
+        if let Some(path) = paths.find(|path| path.len() != depth) {
+            return Err(TypeError::LengthMismatch { name: "authentication path", expected: depth, actual: path.len() });
+        }
+        let salted: Vec<bool> = [self.trace_salt.is_some(), self.masking.is_some(), self.composition_salt.is_some()]
+            .into_iter()
+            .chain(self.aux_segments.iter().map(|segment| segment.salt.is_some()))
+            .collect();
+        if salted.contains(&true) && salted.contains(&false) {
+            return Err(TypeError::InvalidConversion("Partially blinded opening".to_string()));
+        }
+        Ok(())
+    }
+    
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for FriProof<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.layers.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty layers".to_string()));
+            return Err(TypeError::EmptyComponent { name: "FRI layers" });
+        }
+        
+        if !ExtensionElement::<F>::is_supported(self.extension_degree) {
+            return Err(TypeError::InvalidConversion(format!(
+                "Unsupported extension degree {}",
+                self.extension_degree
+            )));
         }
         
         if self.final_polynomial.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty final polynomial".to_string()));
+            return Err(TypeError::EmptyComponent { name: "FRI final polynomial" });
         }
         
         for layer in &self.layers {
@@ -447,56 +1212,453 @@ impl<F: FieldElement> StarkComponent<F> for FriProof<F> {
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for FriLayer<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.polynomial.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty polynomial".to_string()));
+            return Err(TypeError::EmptyComponent { name: "FRI layer polynomial" });
         }
         
         if self.commitment.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty commitment".to_string()));
+            return Err(TypeError::EmptyComponent { name: "FRI layer commitment" });
         }
         
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
     }
 }
 
 impl<F: FieldElement> StarkComponent<F> for FriQuery<F> {
-    fn validate(&self) -> std::result::Result<(), TypeError> {
+    fn validate(&self) -> core::result::Result<(), TypeError> {
         if self.responses.is_empty() {
-            return Err(TypeError::InvalidConversion("Empty responses".to_string()));
+            return Err(TypeError::EmptyComponent { name: "FRI query responses" });
         }
         Ok(())
     }
     
     fn to_bytes(&self) -> Vec<u8> {
-        // Placeholder implementation
-        Vec::new()
+        encode_to_vec(self)
     }
     
-    fn from_bytes(_bytes: &[u8]) -> std::result::Result<Self, TypeError> {
-        // Placeholder implementation
-        Err(TypeError::InvalidConversion("Not implemented".to_string()))
+    fn from_bytes(bytes: &[u8]) -> core::result::Result<Self, TypeError> {
+        decode_from_slice(bytes)
+    }
+}
+
+impl<F: FieldElement> Encode for StarkProof<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        self.trace.encode(writer);
+        self.air.encode(writer);
+        writer.write_sequence(&self.commitments);
+        self.ood_frame.encode(writer);
+        self.fri_proof.encode(writer);
+        writer.write_sequence(&self.openings);
+        self.metadata.encode(writer);
+        self.body.encode(writer);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            trace: ExecutionTrace::decode(reader)?,
+            air: Air::decode(reader)?,
+            commitments: reader.read_sequence()?,
+            ood_frame: OodFrame::decode(reader)?,
+            fri_proof: FriProof::decode(reader)?,
+            openings: reader.read_sequence()?,
+            metadata: ProofMetadata::decode(reader)?,
+            body: ProofBody::decode(reader)?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for DetachedProof<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        self.trace.encode(writer);
+        writer.write_sequence(&self.commitments);
+        self.ood_frame.encode(writer);
+        self.fri_proof.encode(writer);
+        writer.write_sequence(&self.openings);
+        self.metadata.encode(writer);
+        self.body.encode(writer);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            trace: ExecutionTrace::decode(reader)?,
+            commitments: reader.read_sequence()?,
+            ood_frame: OodFrame::decode(reader)?,
+            fri_proof: FriProof::decode(reader)?,
+            openings: reader.read_sequence()?,
+            metadata: ProofMetadata::decode(reader)?,
+            body: ProofBody::decode(reader)?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for ExecutionTrace<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.length);
+        writer.write_usize(self.num_registers);
+        self.columns.encode(writer);
+        writer.write_usize(self.aux_segments.len());
+        for segment in &self.aux_segments {
+            writer.write_sequence(segment);
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            length: reader.read_usize()?,
+            num_registers: reader.read_usize()?,
+            columns: TraceStorage::decode(reader)?,
+            aux_segments: {
+                let count = reader.read_length(8)?;
+                (0..count)
+                    .map(|_| reader.read_sequence())
+                    .collect::<core::result::Result<_, _>>()?
+            },
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for Air<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_sequence(&self.constraints);
+        self.transition.encode(writer);
+        self.boundary.encode(writer);
+        writer.write_u32(self.security_parameter);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            constraints: reader.read_sequence()?,
+            transition: TransitionFunction::decode(reader)?,
+            boundary: BoundaryConditions::decode(reader)?,
+            security_parameter: reader.read_u32()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for Constraint<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_fields(&self.polynomial);
+        writer.write_usize(self.degree);
+        self.constraint_type.encode(writer);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            polynomial: reader.read_fields()?,
+            degree: reader.read_usize()?,
+            constraint_type: ConstraintType::decode(reader)?,
+        })
+    }
+}
+
+
+impl<F: FieldElement> Encode for TransitionFunction<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_sequence(&self.coefficients);
+        writer.write_usize(self.degree);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            coefficients: reader.read_sequence()?,
+            degree: reader.read_usize()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for BoundaryConditions<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_sequence(&self.constraints);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            constraints: reader.read_sequence()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for BoundaryConstraint<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.register);
+        writer.write_usize(self.step);
+        writer.write_field(&self.value);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            register: reader.read_usize()?,
+            step: reader.read_usize()?,
+            value: reader.read_field()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for MerkleCommitment<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_bytes(&self.root);
+        writer.write_usize(self.depth);
+        writer.write_fields(&self.leaves);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            root: reader.read_bytes()?,
+            depth: reader.read_usize()?,
+            leaves: reader.read_fields()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for OodFrame<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_fields(&self.trace_current);
+        writer.write_fields(&self.trace_next);
+        writer.write_usize(self.trace_window.len());
+        for row in &self.trace_window {
+            writer.write_fields(row);
+        }
+        writer.write_fields(&self.composition);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        let trace_current = reader.read_fields()?;
+        let trace_next = reader.read_fields()?;
+        let window = reader.read_length(8)?;
+        Ok(Self {
+            trace_current,
+            trace_next,
+            trace_window: (0..window).map(|_| reader.read_fields()).collect::<core::result::Result<_, _>>()?,
+            composition: reader.read_fields()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for QueryOpening<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.position);
+        writer.write_fields(&self.trace_row);
+        writer.write_sequence(&self.trace_path);
+        writer.write_optional_digest(self.trace_salt.as_ref());
+        writer.write_sequence(&self.aux_segments);
+        writer.write_fields(&self.composition);
+        writer.write_optional_field(self.masking.as_ref());
+        writer.write_sequence(&self.composition_path);
+        writer.write_optional_digest(self.composition_salt.as_ref());
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            position: reader.read_usize()?,
+            trace_row: reader.read_fields()?,
+            trace_path: reader.read_sequence()?,
+            trace_salt: reader.read_optional_digest()?,
+            aux_segments: reader.read_sequence()?,
+            composition: reader.read_fields()?,
+            masking: reader.read_optional_field()?,
+            composition_path: reader.read_sequence()?,
+            composition_salt: reader.read_optional_digest()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for SegmentOpening<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_fields(&self.row);
+        writer.write_sequence(&self.path);
+        writer.write_optional_digest(self.salt.as_ref());
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            row: reader.read_fields()?,
+            path: reader.read_sequence()?,
+            salt: reader.read_optional_digest()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for FriProof<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.extension_degree);
+        writer.write_sequence(&self.layers);
+        writer.write_fields(&self.final_polynomial);
+        writer.write_sequence(&self.queries);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            extension_degree: reader.read_usize()?,
+            layers: reader.read_sequence()?,
+            final_polynomial: reader.read_fields()?,
+            queries: reader.read_sequence()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for FriLayer<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_fields(&self.polynomial);
+        writer.write_bytes(&self.commitment);
+        writer.write_usize(self.degree);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            polynomial: reader.read_fields()?,
+            commitment: reader.read_bytes()?,
+            degree: reader.read_usize()?,
+        })
+    }
+}
+
+impl<F: FieldElement> Encode for FriQuery<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_field(&self.point);
+        writer.write_fields(&self.responses);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            point: reader.read_field()?,
+            responses: reader.read_fields()?,
+        })
+    }
+}
+
+impl Encode for ProofBody {
+    fn encode(&self, writer: &mut ByteWriter) {
+        match self {
+            Self::Native => writer.write_u8(0),
+            Self::Winterfell { proof, inputs } => {
+                writer.write_u8(1);
+                writer.write_bytes(proof);
+                writer.write_usize(inputs.len());
+                for input in inputs {
+                    writer.write_u64(*input);
+                }
+            }
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        match reader.read_u8()? {
+            0 => Ok(Self::Native),
+            1 => {
+                let proof = reader.read_bytes()?;
+                let length = reader.read_length(8)?;
+                let inputs = (0..length).map(|_| reader.read_u64()).collect::<core::result::Result<_, _>>()?;
+                Ok(Self::Winterfell { proof, inputs })
+            }
+            tag => Err(TypeError::UnknownTag { name: "proof body", tag }),
+        }
+    }
+}
+
+impl Encode for FieldId {
+    fn encode(&self, writer: &mut ByteWriter) {
+        match *self {
+            FieldId::Mersenne61 => writer.write_u8(0),
+            FieldId::Goldilocks => writer.write_u8(1),
+            FieldId::Other(modulus) => {
+                writer.write_u8(2);
+                writer.write_u64(modulus);
+            }
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        match reader.read_u8()? {
+            0 => Ok(FieldId::Mersenne61),
+            1 => Ok(FieldId::Goldilocks),
+            2 => match FieldId::from_modulus(reader.read_u64()?) {
+                FieldId::Other(modulus) => Ok(FieldId::Other(modulus)),
+                _ => Err(TypeError::NonCanonicalValue { name: "field identifier" }),
+            },
+            tag => Err(TypeError::UnknownTag { name: "field", tag }),
+        }
+    }
+}
+
+impl Encode for HashId {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_u8(*self as u8);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        let tag = reader.read_u8()?;
+        HashId::ALL
+            .get(usize::from(tag))
+            .copied()
+            .ok_or(TypeError::UnknownTag { name: "hash function", tag })
+    }
+}
+
+impl Encode for ProofParameters {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_u32(self.security_parameter);
+        writer.write_bool(self.zero_knowledge);
+        writer.write_usize(self.blowup_factor);
+        writer.write_usize(self.num_queries);
+        writer.write_u32(self.field_extension_degree);
+        writer.write_u32(self.grinding_bits);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            security_parameter: reader.read_u32()?,
+            zero_knowledge: reader.read_bool()?,
+            blowup_factor: reader.read_usize()?,
+            num_queries: reader.read_usize()?,
+            field_extension_degree: reader.read_u32()?,
+            grinding_bits: reader.read_u32()?,
+        })
+    }
+}
+
+impl Encode for ProofMetadata {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_u32(self.version);
+        writer.write_string(&self.prover_version);
+        self.field.encode(writer);
+        self.hash_id.encode(writer);
+        writer.write_digest(self.air_digest.as_bytes());
+        self.parameters.encode(writer);
+        writer.write_u64(self.grinding_nonce);
+        writer.write_usize(self.proof_size);
+        writer.write_u64(self.timestamp);
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> core::result::Result<Self, TypeError> {
+        Ok(Self {
+            version: reader.read_u32()?,
+            prover_version: reader.read_string()?,
+            field: FieldId::decode(reader)?,
+            hash_id: HashId::decode(reader)?,
+            air_digest: Digest::new(reader.read_digest()?),
+            parameters: ProofParameters::decode(reader)?,
+            grinding_nonce: reader.read_u64()?,
+            proof_size: reader.read_usize()?,
+            timestamp: reader.read_u64()?,
+        })
     }
 }
 
@@ -508,9 +1670,10 @@ mod tests {
     #[test]
     fn test_stark_proof_validation() {
         let trace = ExecutionTrace {
-            columns: vec![vec![PrimeField64::new(1), PrimeField64::new(2)]],
+            columns: vec![vec![PrimeField64::new(1), PrimeField64::new(2)]].into(),
             length: 2,
             num_registers: 1,
+            aux_segments: vec![],
         };
         
         let air = Air {
@@ -525,8 +1688,19 @@ mod tests {
         
         let metadata = ProofMetadata {
             version: 1,
-            security_parameter: 128,
-            field_modulus: "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47".to_string(),
+            prover_version: crate::VERSION.to_string(),
+            field: FieldId::of::<PrimeField64>(),
+            hash_id: NATIVE_HASH,
+            air_digest: Digest::default(),
+            parameters: ProofParameters {
+                security_parameter: 128,
+                zero_knowledge: false,
+                blowup_factor: 8,
+                num_queries: 16,
+                field_extension_degree: 1,
+                grinding_bits: 0,
+            },
+            grinding_nonce: 0,
             proof_size: 1024,
             timestamp: 1234567890,
         };
@@ -535,12 +1709,16 @@ mod tests {
             trace,
             air,
             commitments: vec![],
+            ood_frame: OodFrame::default(),
             fri_proof: FriProof {
+                extension_degree: 1,
                 layers: vec![],
                 final_polynomial: vec![PrimeField64::new(1)],
                 queries: vec![],
             },
+            openings: vec![],
             metadata,
+            body: ProofBody::Native,
         };
         
         // The validation will fail because FRI proof has empty layers and queries
@@ -548,4 +1726,282 @@ mod tests {
         let validation_result = proof.validate();
         assert!(validation_result.is_err() || validation_result.is_ok());
     }
+
+    #[test]
+    fn test_stark_proof_bytes_roundtrip() {
+        let proof = StarkProof {
+            trace: ExecutionTrace {
+                columns: vec![vec![PrimeField64::new(1), PrimeField64::new(2)]].into(),
+                length: 2,
+                num_registers: 1,
+                aux_segments: vec![vec![vec![PrimeField64::new(3), PrimeField64::new(4)]]],
+            },
+            air: Air {
+                constraints: vec![Constraint {
+                    polynomial: vec![PrimeField64::new(3)],
+                    degree: 1,
+                    constraint_type: ConstraintType::Algebraic,
+                }],
+                transition: TransitionFunction {
+                    coefficients: vec![vec![PrimeField64::new(1)]],
+                    degree: 1,
+                },
+                boundary: BoundaryConditions {
+                    constraints: vec![BoundaryConstraint { register: 0, step: 0, value: PrimeField64::new(1) }],
+                },
+                security_parameter: 128,
+            },
+            commitments: vec![MerkleCommitment { root: vec![7; 32], depth: 3, leaves: vec![] }],
+            ood_frame: OodFrame {
+                trace_current: vec![PrimeField64::new(4)],
+                trace_next: vec![PrimeField64::new(5)],
+                trace_window: vec![vec![PrimeField64::new(9)]],
+                composition: vec![PrimeField64::new(6), PrimeField64::new(16)],
+            },
+            fri_proof: FriProof {
+                extension_degree: 2,
+                layers: vec![FriLayer { polynomial: vec![PrimeField64::new(8)], commitment: vec![1, 2], degree: 1 }],
+                final_polynomial: vec![PrimeField64::new(8)],
+                queries: vec![FriQuery { point: PrimeField64::new(9), responses: vec![PrimeField64::new(10)] }],
+            },
+            openings: vec![QueryOpening {
+                position: 5,
+                trace_row: vec![PrimeField64::new(11)],
+                trace_path: vec![[1; 32], [2; 32]],
+                trace_salt: Some([3; 32]),
+                aux_segments: vec![SegmentOpening { row: vec![PrimeField64::new(14)], path: vec![[7; 32], [8; 32]], salt: Some([9; 32]) }],
+                composition: vec![PrimeField64::new(12), PrimeField64::new(15)],
+                masking: Some(PrimeField64::new(13)),
+                composition_path: vec![[4; 32], [5; 32]],
+                composition_salt: Some([6; 32]),
+            }],
+            metadata: ProofMetadata {
+                version: 1,
+                prover_version: "0.1.0".to_string(),
+                field: FieldId::Other(7),
+                hash_id: HashId::Blake3,
+                air_digest: Digest::new([7; 32]),
+                parameters: ProofParameters {
+                    security_parameter: 128,
+                    zero_knowledge: true,
+                    blowup_factor: 8,
+                    num_queries: 16,
+                    field_extension_degree: 2,
+                    grinding_bits: 4,
+                },
+                grinding_nonce: 21,
+                proof_size: 2,
+                timestamp: 1234567890,
+            },
+            body: ProofBody::Native,
+        };
+
+        let bytes = proof.to_bytes();
+        assert_eq!(&bytes[..4], &PROOF_MAGIC);
+        assert_eq!(bytes, proof.to_bytes());
+        assert_eq!(StarkProof::<PrimeField64>::from_bytes(&bytes).unwrap(), proof);
+
+        // Components round-trip on their own
+        assert_eq!(OodFrame::from_bytes(&proof.ood_frame.to_bytes()).unwrap(), proof.ood_frame);
+        assert_eq!(Air::from_bytes(&proof.air.to_bytes()).unwrap(), proof.air);
+
+        // Serde sees the same components, so the proof goes through bincode as well
+        let encoded = bincode::serialize(&proof).unwrap();
+        assert_eq!(bincode::deserialize::<StarkProof<PrimeField64>>(&encoded).unwrap(), proof);
+        let detached = proof.clone().into_detached();
+        let encoded = bincode::serialize(&detached).unwrap();
+        assert_eq!(bincode::deserialize::<DetachedProof<PrimeField64>>(&encoded).unwrap(), detached);
+
+        // Header, truncation and trailing data are checked
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 1;
+        assert!(StarkProof::<PrimeField64>::from_bytes(&bad_magic).is_err());
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 0xff;
+        assert!(StarkProof::<PrimeField64>::from_bytes(&bad_version).is_err());
+
+        let mut bad_field = bytes.clone();
+        bad_field[6] ^= 1;
+        assert!(matches!(
+            StarkProof::<PrimeField64>::from_bytes(&bad_field),
+            Err(TypeError::TypeMismatch { .. })
+        ));
+
+        // Non-reduced field elements and surplus path siblings are rejected
+        let five = PrimeField64::new(5).to_bytes();
+        let offset = bytes.windows(32).position(|window| window == five).unwrap();
+        let mut unreduced = bytes.clone();
+        unreduced[offset + 24..offset + 32].copy_from_slice(&(PrimeField64::MODULUS + 5).to_le_bytes());
+        assert!(StarkProof::<PrimeField64>::from_bytes(&unreduced).is_err());
+        let mut padded = bytes.clone();
+        padded[offset] = 1;
+        assert!(StarkProof::<PrimeField64>::from_bytes(&padded).is_err());
+
+        let mut over_long = proof.clone();
+        over_long.openings[0].trace_path.extend([[0; 32], [0; 32]]);
+        assert!(StarkProof::<PrimeField64>::from_bytes(&over_long.to_bytes()).is_err());
+
+        assert!(StarkProof::<PrimeField64>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes;
+        trailing.push(0);
+        assert!(StarkProof::<PrimeField64>::from_bytes(&trailing).is_err());
+
+        // A backend proof keeps its bytes exactly, detached or not
+        let mut wrapped = proof;
+        wrapped.body = ProofBody::Winterfell { proof: vec![0, 1, 255, 7], inputs: vec![3, u64::MAX] };
+        let bytes = wrapped.to_bytes();
+        assert_eq!(StarkProof::<PrimeField64>::from_bytes(&bytes).unwrap(), wrapped);
+        let detached = wrapped.clone().into_detached();
+        assert_eq!(DetachedProof::<PrimeField64>::from_bytes(&detached.to_bytes()).unwrap(), detached);
+        assert_eq!(detached.attach(wrapped.air.clone()), wrapped);
+        let mut unknown_body = bytes;
+        let tag = unknown_body.len() - 4 - 8 - 2 * 8 - 8 - 1;
+        assert_eq!(unknown_body[tag], 1);
+        unknown_body[tag] = 2;
+        assert!(StarkProof::<PrimeField64>::from_bytes(&unknown_body).is_err());
+    }
+
+    #[test]
+    fn test_decoding_and_validation_errors() {
+        use crate::types::test_vectors::sample_proof;
+
+        let proof = sample_proof();
+        let bytes = proof.to_bytes();
+        let decode = |bytes: &[u8]| StarkProof::<PrimeField64>::from_bytes(bytes).unwrap_err();
+        assert!(matches!(decode(&bytes[..bytes.len() - 1]), TypeError::UnexpectedEnd { .. }));
+        let mut trailing = bytes.clone();
+        trailing.extend([0, 0]);
+        assert_eq!(decode(&trailing), TypeError::TrailingBytes { count: 2 });
+        assert_eq!(
+            decode(&proof.clone().into_detached().to_bytes()),
+            TypeError::InvalidMagic { expected: PROOF_MAGIC, actual: DETACHED_PROOF_MAGIC }
+        );
+        let mut old = bytes.clone();
+        old[4..6].copy_from_slice(&8u16.to_le_bytes());
+        assert_eq!(decode(&old), TypeError::UnsupportedVersion { expected: PROOF_FORMAT_VERSION, actual: 8 });
+
+        let mut empty = proof.trace.clone();
+        empty.length = 0;
+        assert_eq!(empty.validate(), Err(TypeError::EmptyComponent { name: "trace" }));
+        let mut ragged = proof.trace.clone();
+        ragged.aux_segments[0][0].push(PrimeField64::new(1));
+        assert_eq!(
+            ragged.validate(),
+            Err(TypeError::LengthMismatch { name: "trace column", expected: 2, actual: 3 })
+        );
+        // The sample opens the composition tree one level deeper than the trace tree
+        assert_eq!(
+            proof.openings[0].validate(),
+            Err(TypeError::LengthMismatch { name: "authentication path", expected: 2, actual: 3 })
+        );
+    }
+
+    #[test]
+    fn test_proof_metadata_identifiers() {
+        assert_eq!(FieldId::of::<PrimeField64>(), FieldId::Mersenne61);
+        assert_eq!(FieldId::from_modulus(0xffff_ffff_0000_0001), FieldId::Goldilocks);
+        assert_eq!(FieldId::Mersenne61.modulus(), PrimeField64::MODULUS);
+        for field in [FieldId::Mersenne61, FieldId::Goldilocks, FieldId::Other(7)] {
+            assert_eq!(decode_from_slice::<FieldId>(&encode_to_vec(&field)).unwrap(), field);
+        }
+        // A known field has only its named encoding
+        let mut unnamed = vec![2];
+        unnamed.extend_from_slice(&PrimeField64::MODULUS.to_le_bytes());
+        assert!(decode_from_slice::<FieldId>(&unnamed).is_err());
+
+        for hash in HashId::ALL {
+            assert_eq!(decode_from_slice::<HashId>(&encode_to_vec(&hash)).unwrap(), hash);
+            assert_eq!(HashId::from_name(hash.name()), Some(hash));
+        }
+        assert_eq!(encode_to_vec(&NATIVE_HASH), vec![0]);
+        assert!(decode_from_slice::<HashId>(&[4]).is_err());
+        assert_eq!(HashId::from_name("md5"), None);
+    }
+
+    #[test]
+    fn test_execution_trace_rows() {
+        let f = PrimeField64::new;
+        let trace = ExecutionTrace {
+            columns: vec![vec![f(1), f(2), f(3)], vec![f(4), f(5), f(6)]].into(),
+            length: 3,
+            num_registers: 2,
+            aux_segments: vec![],
+        };
+        let row = trace.row(RowIdx(1));
+        assert_eq!((row.index(), row.len()), (RowIdx(1), 2));
+        assert_eq!(row.to_vec(), vec![f(2), f(5)]);
+        assert_eq!((row.get(ColIdx(1)), row.get(ColIdx(2))), (Some(f(5)), None));
+
+        assert_eq!(trace.get(RowIdx(2), ColIdx(0)), Some(f(3)));
+        assert_eq!(trace.get(RowIdx(3), ColIdx(0)), None);
+        assert_eq!(trace.get(RowIdx(0), ColIdx(2)), None);
+
+        let rows: Vec<Vec<_>> = trace.rows().map(|row| row.to_vec()).collect();
+        assert_eq!(rows, trace.transpose());
+        assert_eq!(rows, vec![vec![f(1), f(4)], vec![f(2), f(5)], vec![f(3), f(6)]]);
+    }
+
+    #[test]
+    fn test_stark_proof_size_breakdown() {
+        use crate::types::test_vectors::sample_winterfell_proof;
+
+        let proof = sample_winterfell_proof();
+        let sizes = proof.size_breakdown();
+        assert_eq!(sizes.total(), proof.to_bytes().len());
+        assert_eq!(sizes.header, 14);
+        assert_eq!(sizes.air, proof.air.to_bytes().len());
+        assert_eq!(sizes.body, 1 + 8 + 4 + 8 + 2 * 8);
+        assert!(sizes.to_string().starts_with(&format!("{} bytes", sizes.total())));
+
+        // Recording the size leaves it unchanged
+        let proof = proof.with_encoded_size();
+        assert_eq!(proof.metadata.proof_size, sizes.total());
+        assert_eq!(proof.to_bytes().len(), sizes.total());
+        assert_eq!(proof.clone().into_detached().to_bytes().len(), sizes.total() - sizes.air);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_stark_proof_json_roundtrip() {
+        use crate::types::test_vectors::sample_winterfell_proof;
+
+        let proof = sample_winterfell_proof();
+        let json = proof.to_json_pretty();
+        assert_eq!(StarkProof::<PrimeField64>::from_json(&json).unwrap(), proof);
+
+        // Field elements, digests and byte strings are hex
+        assert!(json.contains(&format!("\"{:#x}\"", PrimeField64::MODULUS - 1)));
+        assert!(json.contains(&format!("\"0x{}\"", "aa".repeat(32))));
+        assert!(json.contains("\"0x00017fff\""));
+
+        // Values above the modulus and surplus path siblings are rejected
+        let (largest, modulus) = (format!("{:#x}", PrimeField64::MODULUS - 1), format!("{:#x}", PrimeField64::MODULUS));
+        let unreduced = json.replace(&largest, &modulus);
+        assert!(StarkProof::<PrimeField64>::from_json(&unreduced).is_err());
+        let mut over_long = proof;
+        over_long.openings[0].trace_path.extend([[0; 32], [0; 32]]);
+        assert!(StarkProof::<PrimeField64>::from_json(&over_long.to_json_pretty()).is_err());
+    }
+
+    #[cfg(all(feature = "cbor", feature = "msgpack"))]
+    #[test]
+    fn test_stark_proof_cbor_and_msgpack_roundtrip() {
+        use crate::types::test_vectors::sample_winterfell_proof;
+
+        let proof = sample_winterfell_proof();
+        let cbor = proof.to_cbor();
+        let msgpack = proof.to_msgpack();
+        assert_eq!(StarkProof::<PrimeField64>::from_cbor(&cbor).unwrap(), proof);
+        assert_eq!(StarkProof::<PrimeField64>::from_msgpack(&msgpack).unwrap(), proof);
+        assert!(StarkProof::<PrimeField64>::from_cbor(&cbor[..cbor.len() - 1]).is_err());
+        assert!(StarkProof::<PrimeField64>::from_msgpack(&msgpack[..msgpack.len() - 1]).is_err());
+
+        // The formats do not mix, and the decoded proofs are checked
+        assert!(StarkProof::<PrimeField64>::from_cbor(&msgpack).is_err());
+        let mut over_long = proof;
+        over_long.openings[0].trace_path.extend([[0; 32], [0; 32]]);
+        assert!(StarkProof::<PrimeField64>::from_cbor(&over_long.to_cbor()).is_err());
+        assert!(StarkProof::<PrimeField64>::from_msgpack(&over_long.to_msgpack()).is_err());
+    }
 }
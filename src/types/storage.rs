@@ -0,0 +1,385 @@
+//! Execution Trace Storage
+//!
+//! Trace columns are held either in memory or, with the `mmap` feature, in
+//! memory-mapped temporary files. Mapped traces of hundreds of millions of
+//! rows can be generated, committed and queried while the operating system
+//! pages in only the parts being touched.
+//!
+//! [`TraceStorage`] gives both representations the same column and row
+//! accessors. Columns of a mapped trace are read into memory one at a time
+//! when they are needed as slices.
+
+use crate::prelude::*;
+use crate::types::{FieldElement, TypeError};
+use crate::types::encoding::{ByteReader, ByteWriter, Encode};
+use crate::types::hex::{self, AsHex};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use alloc::borrow::Cow;
+use zeroize::Zeroize;
+
+/// Column-major storage of the execution trace columns
+#[derive(Debug, Clone)]
+pub enum TraceStorage<F: FieldElement> {
+    /// Columns held in memory
+    Memory(Vec<Vec<F>>),
+    /// Columns stored in memory-mapped temporary files
+    #[cfg(feature = "mmap")]
+    Mapped(Vec<MappedColumn<F>>),
+}
+
+impl<F: FieldElement> TraceStorage<F> {
+    /// Storage without columns
+    pub fn new() -> Self {
+        TraceStorage::Memory(Vec::new())
+    }
+
+    /// Map `width` zero-filled columns of `len` values to temporary files
+    #[cfg(feature = "mmap")]
+    pub fn mapped(width: usize, len: usize) -> Result<Self, TypeError> {
+        let columns = (0..width).map(|_| MappedColumn::with_len(len)).collect::<Result<_, _>>()?;
+        Ok(TraceStorage::Mapped(columns))
+    }
+
+    /// Number of columns
+    pub fn len(&self) -> usize {
+        match self {
+            TraceStorage::Memory(columns) => columns.len(),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns.len(),
+        }
+    }
+
+    /// Whether there are no columns
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of values in column `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn column_len(&self, index: usize) -> usize {
+        match self {
+            TraceStorage::Memory(columns) => columns[index].len(),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns[index].len(),
+        }
+    }
+
+    /// Column `index`, borrowed from memory or read from its file
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn column(&self, index: usize) -> Cow<'_, [F]> {
+        match self {
+            TraceStorage::Memory(columns) => Cow::Borrowed(&columns[index]),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => Cow::Owned(columns[index].to_vec()),
+        }
+    }
+
+    /// Iterate over the columns in order
+    pub fn iter(&self) -> impl Iterator<Item = Cow<'_, [F]>> + '_ {
+        (0..self.len()).map(move |index| self.column(index))
+    }
+
+    /// Value of `column` at `row`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` or `row` is out of bounds.
+    pub fn value(&self, column: usize, row: usize) -> F {
+        match self {
+            TraceStorage::Memory(columns) => columns[column][row],
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns[column].get(row),
+        }
+    }
+
+    /// Overwrite the value of `column` at `row`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `column` or `row` is out of bounds.
+    pub fn set(&mut self, column: usize, row: usize, value: F) {
+        match self {
+            TraceStorage::Memory(columns) => columns[column][row] = value,
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns[column].write(row, &[value]),
+        }
+    }
+
+    /// Shorten every column to its first `len` values
+    pub fn truncate(&mut self, len: usize) {
+        match self {
+            TraceStorage::Memory(columns) => columns.iter_mut().for_each(|column| column.truncate(len)),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns.iter_mut().for_each(|column| column.truncate(len)),
+        }
+    }
+
+    /// Values of every column at `row`
+    pub fn row(&self, row: usize) -> Vec<F> {
+        (0..self.len()).map(|column| self.value(column, row)).collect()
+    }
+
+    /// Every column as a slice, borrowed from memory or read from the files
+    pub fn as_columns(&self) -> Cow<'_, [Vec<F>]> {
+        match self {
+            TraceStorage::Memory(columns) => Cow::Borrowed(columns),
+            #[cfg(feature = "mmap")]
+            storage => Cow::Owned(storage.to_columns()),
+        }
+    }
+
+    /// Copy every column into memory
+    pub fn to_columns(&self) -> Vec<Vec<F>> {
+        self.iter().map(Cow::into_owned).collect()
+    }
+
+    /// Move the columns into memory, reading mapped columns from their files
+    pub fn into_columns(self) -> Vec<Vec<F>> {
+        match self {
+            TraceStorage::Memory(columns) => columns,
+            #[cfg(feature = "mmap")]
+            storage => storage.to_columns(),
+        }
+    }
+
+    /// Whether the columns are stored in memory-mapped files
+    pub fn is_mapped(&self) -> bool {
+        !matches!(self, TraceStorage::Memory(_))
+    }
+}
+
+/// Overwrites every value with zero, in memory or in the mapped files
+///
+/// The columns keep their lengths.
+impl<F: FieldElement> Zeroize for TraceStorage<F> {
+    fn zeroize(&mut self) {
+        match self {
+            TraceStorage::Memory(columns) => columns.iter_mut().for_each(|column| zeroize_values(column)),
+            #[cfg(feature = "mmap")]
+            TraceStorage::Mapped(columns) => columns.iter_mut().for_each(|column| column.map.zeroize()),
+        }
+    }
+}
+
+/// Overwrite `values` with zero, with writes the compiler cannot elide
+pub(crate) fn zeroize_values<F: FieldElement>(values: &mut [F]) {
+    for value in values.iter_mut() {
+        // SAFETY: `value` is a valid, aligned and exclusive reference, and
+        // field elements are plain values without a destructor
+        #[allow(unsafe_code)]
+        unsafe {
+            core::ptr::write_volatile(value, F::zero());
+        }
+    }
+    core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+}
+
+impl<F: FieldElement> Default for TraceStorage<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FieldElement> From<Vec<Vec<F>>> for TraceStorage<F> {
+    fn from(columns: Vec<Vec<F>>) -> Self {
+        TraceStorage::Memory(columns)
+    }
+}
+
+/// Encoded like in-memory columns and always decoded into memory
+impl<F: FieldElement> Encode for TraceStorage<F> {
+    fn encode(&self, writer: &mut ByteWriter) {
+        writer.write_usize(self.len());
+        for column in self.iter() {
+            writer.write_fields(&column);
+        }
+    }
+
+    fn decode(reader: &mut ByteReader<'_>) -> Result<Self, TypeError> {
+        Ok(TraceStorage::Memory(reader.read_sequence()?))
+    }
+}
+
+/// Storages are equal if they hold the same values, wherever they are stored
+impl<F: FieldElement> PartialEq for TraceStorage<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<F: FieldElement> Eq for TraceStorage<F> {}
+
+/// Storages serialize as their columns, and deserialize into memory
+///
+/// Values are hex in human-readable formats, as in the other proof components.
+impl<F: FieldElement> Serialize for TraceStorage<F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_seq(self.iter().map(HexColumn))
+        } else {
+            serializer.collect_seq(self.iter())
+        }
+    }
+}
+
+/// Column serialized as the hex of its values
+struct HexColumn<'a, F: FieldElement>(Cow<'a, [F]>);
+
+impl<F: FieldElement> Serialize for HexColumn<'_, F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(AsHex))
+    }
+}
+
+impl<'de, F: FieldElement> Deserialize<'de> for TraceStorage<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        hex::deserialize::<Vec<Vec<F>>, D>(deserializer).map(TraceStorage::Memory)
+    }
+}
+
+/// Column of field elements in a memory-mapped temporary file
+///
+/// Values are stored as canonical little-endian `u64`s. The file is unlinked
+/// on creation and removed by the operating system once the map is dropped.
+#[cfg(feature = "mmap")]
+pub struct MappedColumn<F: FieldElement> {
+    /// Mapped file contents
+    map: memmap2::MmapMut,
+    /// Number of values
+    len: usize,
+    /// Phantom data for type parameter
+    _phantom: core::marker::PhantomData<F>,
+}
+
+#[cfg(feature = "mmap")]
+impl<F: FieldElement> MappedColumn<F> {
+    /// Bytes per stored value
+    const VALUE_BYTES: usize = 8;
+
+    /// Map a zero-filled column of `len` values
+    pub fn with_len(len: usize) -> Result<Self, TypeError> {
+        let storage_error = |error: std::io::Error| TypeError::MemoryError(error.to_string());
+        let bytes = len
+            .checked_mul(Self::VALUE_BYTES)
+            .ok_or_else(|| TypeError::MemoryError("column too large".to_string()))?;
+        let file = tempfile::tempfile().map_err(storage_error)?;
+        file.set_len(bytes as u64).map_err(storage_error)?;
+        // SAFETY: the file is an unlinked temporary file owned by this column,
+        // so no other mapping or process can resize or modify it
+        #[allow(unsafe_code)]
+        let map = unsafe { memmap2::MmapMut::map_mut(&file) }.map_err(storage_error)?;
+        Ok(Self {
+            map,
+            len,
+            _phantom: core::marker::PhantomData,
+        })
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the column has no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Value at `index`
+    pub fn get(&self, index: usize) -> F {
+        assert!(index < self.len, "column index {} out of bounds", index);
+        let offset = index * Self::VALUE_BYTES;
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.map[offset..offset + Self::VALUE_BYTES]);
+        F::from_canonical_u64(u64::from_le_bytes(bytes))
+    }
+
+    /// Write `values` starting at index `start`
+    pub fn write(&mut self, start: usize, values: &[F]) {
+        assert!(start + values.len() <= self.len, "column write out of bounds");
+        for (index, value) in values.iter().enumerate() {
+            let offset = (start + index) * Self::VALUE_BYTES;
+            self.map[offset..offset + Self::VALUE_BYTES].copy_from_slice(&value.as_canonical_u64().to_le_bytes());
+        }
+    }
+
+    /// Shorten the column to its first `len` values
+    ///
+    /// The file keeps its size until the column is dropped.
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Copy the values into memory
+    pub fn to_vec(&self) -> Vec<F> {
+        (0..self.len).map(|index| self.get(index)).collect()
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<F: FieldElement> Clone for MappedColumn<F> {
+    fn clone(&self) -> Self {
+        let mut column = Self::with_len(self.len).expect("failed to map a temporary column");
+        let bytes = self.len * Self::VALUE_BYTES;
+        column.map.copy_from_slice(&self.map[..bytes]);
+        column
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<F: FieldElement> PartialEq for MappedColumn<F> {
+    fn eq(&self, other: &Self) -> bool {
+        let bytes = self.len * Self::VALUE_BYTES;
+        self.len == other.len && self.map[..bytes] == other.map[..bytes]
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl<F: FieldElement> Eq for MappedColumn<F> {}
+
+#[cfg(feature = "mmap")]
+impl<F: FieldElement> core::fmt::Debug for MappedColumn<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MappedColumn").field("len", &self.len).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::field::PrimeField64;
+
+    #[test]
+    fn test_trace_storage_accessors() {
+        let columns: Vec<Vec<PrimeField64>> = (0..3)
+            .map(|column| (0..5).map(|row| PrimeField64::new(10 * column + row)).collect())
+            .collect();
+        let mut storage = TraceStorage::from(columns.clone());
+        assert_eq!(storage.len(), 3);
+        assert_eq!(storage.row(4), vec![PrimeField64::new(4), PrimeField64::new(14), PrimeField64::new(24)]);
+        storage.set(1, 2, PrimeField64::new(99));
+        assert_eq!(storage.value(1, 2), PrimeField64::new(99));
+        assert!(!storage.is_mapped());
+
+        #[cfg(feature = "mmap")]
+        {
+            let mut mapped = TraceStorage::mapped(3, 5).unwrap();
+            for (index, column) in columns.iter().enumerate() {
+                for (row, &value) in column.iter().enumerate() {
+                    mapped.set(index, row, value);
+                }
+            }
+            assert!(mapped.is_mapped());
+            assert_eq!(mapped.to_columns(), columns);
+            assert_ne!(mapped, storage);
+            mapped.set(1, 2, PrimeField64::new(99));
+            assert_eq!(mapped, storage);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Golden Test Vectors for the Proof Wire Format
+//!
+//! Fixed sample proofs and their encodings under the current
+//! [`PROOF_FORMAT_VERSION`], for other implementations of the format
+//! specified in `docs/wire-format.md` to check themselves against. The
+//! vectors live in `test-vectors/v<version>/` as hex, one file per sample;
+//! a format change bumps the version and adds a directory rather than
+//! editing the vectors of an earlier one.
+//!
+//! The module is compiled for the crate's own tests and with the
+//! `test-vectors` feature.
+
+use crate::types::field::PrimeField64;
+use crate::types::stark::*;
+use crate::types::Digest;
+
+/// Encoding of a sample proof under one format version
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestVector {
+    /// Name of the vector, that of its file without the extension
+    pub name: &'static str,
+    /// Format version the bytes are encoded with
+    pub format_version: u16,
+    /// Encoded proof
+    pub bytes: Vec<u8>,
+}
+
+/// Golden vectors of the current format version
+pub fn test_vectors() -> Vec<TestVector> {
+    let vector = |name, hex| TestVector {
+        name,
+        format_version: PROOF_FORMAT_VERSION,
+        bytes: decode_hex(hex),
+    };
+    vec![
+        vector("proof", include_str!("../../test-vectors/v9/proof.hex")),
+        vector("detached-proof", include_str!("../../test-vectors/v9/detached-proof.hex")),
+        vector("winterfell-proof", include_str!("../../test-vectors/v9/winterfell-proof.hex")),
+    ]
+}
+
+/// Native proof over [`PrimeField64`] with every section populated
+///
+/// The values are arbitrary and the proof does not verify; it exercises
+/// the layout, not the protocol.
+pub fn sample_proof() -> StarkProof<PrimeField64> {
+    let f = PrimeField64::new;
+    StarkProof {
+        trace: ExecutionTrace {
+            columns: vec![vec![f(1), f(2)], vec![f(3), f(5)]].into(),
+            length: 2,
+            num_registers: 2,
+            aux_segments: vec![vec![vec![f(7), f(11)]]],
+        },
+        air: Air {
+            constraints: vec![Constraint {
+                polynomial: vec![f(3), f(PrimeField64::MODULUS - 1)],
+                degree: 2,
+                constraint_type: ConstraintType::Transition,
+            }],
+            transition: TransitionFunction {
+                coefficients: vec![vec![f(1), f(0)], vec![f(1), f(1)]],
+                degree: 1,
+            },
+            boundary: BoundaryConditions {
+                constraints: vec![BoundaryConstraint { register: 0, step: 0, value: f(1) }],
+            },
+            security_parameter: 128,
+        },
+        commitments: vec![
+            MerkleCommitment { root: vec![0x11; 32], depth: 3, leaves: vec![] },
+            MerkleCommitment { root: vec![0x22; 32], depth: 3, leaves: vec![] },
+        ],
+        ood_frame: OodFrame {
+            trace_current: vec![f(4), f(6)],
+            trace_next: vec![f(5), f(9)],
+            trace_window: vec![],
+            composition: vec![f(13)],
+        },
+        fri_proof: FriProof {
+            extension_degree: 1,
+            layers: vec![FriLayer { polynomial: vec![f(8), f(10)], commitment: vec![0x33; 32], degree: 1 }],
+            final_polynomial: vec![f(12)],
+            queries: vec![FriQuery { point: f(14), responses: vec![f(15), f(16)] }],
+        },
+        openings: vec![QueryOpening {
+            position: 5,
+            trace_row: vec![f(17), f(18)],
+            trace_path: vec![[0x44; 32], [0x55; 32]],
+            trace_salt: Some([0x66; 32]),
+            aux_segments: vec![],
+            composition: vec![f(19)],
+            masking: None,
+            composition_path: vec![[0x77; 32], [0x88; 32], [0x99; 32]],
+            composition_salt: None,
+        }],
+        metadata: ProofMetadata {
+            version: 1,
+            prover_version: "0.1.0".to_string(),
+            field: FieldId::Mersenne61,
+            hash_id: NATIVE_HASH,
+            air_digest: Digest::new([0xaa; 32]),
+            parameters: ProofParameters {
+                security_parameter: 128,
+                zero_knowledge: true,
+                blowup_factor: 8,
+                num_queries: 1,
+                field_extension_degree: 1,
+                grinding_bits: 4,
+            },
+            grinding_nonce: 21,
+            proof_size: 0,
+            timestamp: 1_700_000_000,
+        },
+        body: ProofBody::Native,
+    }
+}
+
+/// [`sample_proof`] detached from its AIR
+pub fn sample_detached_proof() -> DetachedProof<PrimeField64> {
+    sample_proof().into_detached()
+}
+
+/// [`sample_proof`] carrying a Winterfell body
+pub fn sample_winterfell_proof() -> StarkProof<PrimeField64> {
+    StarkProof {
+        body: ProofBody::Winterfell { proof: vec![0, 1, 0x7f, 0xff], inputs: vec![1, PrimeField64::MODULUS - 1] },
+        ..sample_proof()
+    }
+}
+
+/// Hex in the vector files, with any whitespace between the digits
+fn decode_hex(hex: &str) -> Vec<u8> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_digit(16).expect("test vectors are hex") as u8)
+        .collect();
+    assert!(digits.len() % 2 == 0, "test vectors are whole bytes");
+    digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StarkComponent;
+
+    #[test]
+    fn test_golden_vectors() {
+        let vectors = test_vectors();
+        let encodings = [
+            sample_proof().to_bytes(),
+            sample_detached_proof().to_bytes(),
+            sample_winterfell_proof().to_bytes(),
+        ];
+        assert_eq!(vectors.len(), encodings.len());
+        for (vector, encoding) in vectors.iter().zip(&encodings) {
+            assert_eq!(vector.format_version, PROOF_FORMAT_VERSION);
+            assert!(vector.bytes == *encoding, "{} no longer encodes to its golden vector", vector.name);
+        }
+        assert_eq!(StarkProof::from_bytes(&vectors[0].bytes).unwrap(), sample_proof());
+        assert_eq!(DetachedProof::from_bytes(&vectors[1].bytes).unwrap(), sample_detached_proof());
+        assert_eq!(StarkProof::from_bytes(&vectors[2].bytes).unwrap(), sample_winterfell_proof());
+
+        // The header is spelled out in the format document
+        assert_eq!(&vectors[0].bytes[..4], b"XFGP");
+        assert_eq!(&vectors[1].bytes[..4], b"XFGD");
+        assert_eq!(vectors[0].bytes[4..6], PROOF_FORMAT_VERSION.to_le_bytes());
+        assert_eq!(vectors[0].bytes[6..14], PrimeField64::MODULUS.to_le_bytes());
+    }
+}
@@ -4,6 +4,7 @@
 
 /// Cryptographic utilities
 pub mod crypto {
+    use crate::prelude::*;
     use sha2::{Sha256, Digest};
     
     /// Compute SHA-256 hash
@@ -83,8 +84,7 @@ pub mod math {
             return false;
         }
         
-        let sqrt_n = (n as f64).sqrt() as u64;
-        for i in (3..=sqrt_n).step_by(2) {
+        for i in (3..).step_by(2).take_while(|&i| i <= n / i) {
             if n % i == 0 {
                 return false;
             }
@@ -95,6 +95,7 @@ pub mod math {
 }
 
 /// Serialization utilities
+#[cfg(feature = "std")]
 pub mod serialization {
     use serde::{Serialize, Deserialize};
     use bincode;
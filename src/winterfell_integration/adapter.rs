@@ -0,0 +1,553 @@
+//! Winterfell AIR Adapter
+//!
+//! Compiles an [`air::Air`](crate::air::Air) into [`WinterfellAir`], which
+//! implements Winterfell's `Air` trait, so the same AIR definition drives
+//! both the native prover and the Winterfell backend.
+//!
+//! Winterfell builds its AIR from public inputs alone, so the compiled
+//! constraints travel as [`WinterfellAirInputs`], together with the public
+//! input values and the digest of the XFG AIR they were compiled from.
+//!
+//! - **Transitions**: an AIR built with [`AirBuilder`](crate::air::AirBuilder)
+//!   keeps its expressions, which are evaluated as they are, gated and
+//!   free-column transitions included; other AIRs contribute the rows of
+//!   their transition matrix and may not have auxiliary segments. Winterfell
+//!   frames hold two rows, so transitions over longer windows are rejected.
+//! - **Fixed columns**: become periodic columns, and so must repeat a
+//!   power-of-two number of values. Winterfell skips transitions on the last
+//!   row, so its value is asserted instead.
+//! - **Boundary constraints and public inputs**: become assertions placed
+//!   like the native verifier places them, final values on the last row.
+//!   A cell asserted twice is asserted once, and a register asserted on
+//!   every `2^k`-th row becomes one periodic or sequence assertion.
+//! - **Constants**: are carried over exactly when the moduli match. Otherwise
+//!   only constants of magnitude below 2^32, such as `-1` or small
+//!   coefficients, mean the same thing in both fields and are lifted.
+
+use crate::air::builder::Expr;
+use crate::air::{Air, AirError};
+use crate::proof::public_inputs::PublicInputs;
+use crate::types::FieldElement;
+use std::collections::BTreeMap;
+use winterfell::math::{ExtensibleField, FieldElement as WinterfellElement, StarkField, ToElements};
+use winterfell::{AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo, TransitionConstraintDegree};
+
+/// Largest magnitude of a constant lifted into a field with another modulus
+const MAX_LIFTED_CONSTANT: u64 = 1 << 32;
+
+/// Transition constraint over a two-row Winterfell frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term<B> {
+    /// Base field constant
+    Constant(B),
+    /// Register at the current (`false`) or next (`true`) row
+    Register { register: usize, next: bool },
+    /// Value of a periodic column at the current row
+    Periodic(usize),
+    /// Sum of two terms
+    Add(Box<Term<B>>, Box<Term<B>>),
+    /// Difference of two terms
+    Sub(Box<Term<B>>, Box<Term<B>>),
+    /// Product of two terms
+    Mul(Box<Term<B>>, Box<Term<B>>),
+    /// Negated term
+    Neg(Box<Term<B>>),
+}
+
+impl<B: StarkField> Term<B> {
+    /// Value of the term on a frame
+    fn evaluate<E: WinterfellElement<BaseField = B>>(&self, current: &[E], next: &[E], periodic: &[E]) -> E {
+        match self {
+            Term::Constant(value) => E::from(*value),
+            Term::Register { register, next: false } => current[*register],
+            Term::Register { register, next: true } => next[*register],
+            Term::Periodic(column) => periodic[*column],
+            Term::Add(a, b) => a.evaluate(current, next, periodic) + b.evaluate(current, next, periodic),
+            Term::Sub(a, b) => a.evaluate(current, next, periodic) - b.evaluate(current, next, periodic),
+            Term::Mul(a, b) => a.evaluate(current, next, periodic) * b.evaluate(current, next, periodic),
+            Term::Neg(a) => -a.evaluate(current, next, periodic),
+        }
+    }
+}
+
+/// Compiled transition constraint and its degree
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Transition<B> {
+    /// Constraint, zero on every valid frame
+    term: Term<B>,
+    /// Degree of the constraint in the trace registers
+    ///
+    /// Periodic columns only appear in the linear constraints of fixed
+    /// columns, where they do not raise the degree, so none is declared
+    /// with cycles.
+    degree: usize,
+}
+
+/// Value asserted at a trace cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell<B> {
+    /// Register (column) index
+    register: usize,
+    /// Trace row, or `None` for the last row
+    row: Option<usize>,
+    /// Asserted value
+    value: B,
+}
+
+/// Public inputs of a [`WinterfellAir`]: the compiled XFG AIR and the values it asserts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinterfellAirInputs<B> {
+    /// Number of main registers
+    width: usize,
+    /// Transition constraints, in evaluation order
+    transitions: Vec<Transition<B>>,
+    /// Values of the periodic columns backing the fixed columns
+    periodic: Vec<Vec<B>>,
+    /// Lifted values of every fixed column, by register
+    fixed: Vec<(usize, Vec<B>)>,
+    /// Boundary constraints and public inputs
+    assertions: Vec<Cell<B>>,
+    /// Digest of the XFG AIR, absorbed with the public inputs
+    digest: [u8; 32],
+}
+
+impl<B: StarkField + From<u64>> WinterfellAirInputs<B> {
+    /// Compile `air` and the public inputs of a statement about it
+    ///
+    /// Fails if the AIR has no Winterfell counterpart (see the module
+    /// documentation) or the public inputs do not match its cells.
+    pub fn new<F: FieldElement>(air: &Air<F>, public_inputs: &PublicInputs<F>) -> Result<Self, AirError> {
+        air.validate()?;
+        let width = air.num_registers();
+        let mut transitions = match &air.symbolic {
+            Some(symbolic) => symbolic
+                .constraints()
+                .into_iter()
+                .map(|expression| {
+                    let term = compile(expression, &|name: &str| symbolic.register(name))?;
+                    Ok(Transition { term, degree: expression.degree() })
+                })
+                .collect::<Result<Vec<_>, AirError>>()?,
+            None if !air.aux_segments.is_empty() => {
+                return Err(AirError::InvalidTransition(
+                    "Auxiliary segments without expressions have no Winterfell counterpart".to_string(),
+                ))
+            }
+            None => matrix_transitions(air)?,
+        };
+
+        let mut periodic = Vec::new();
+        let mut fixed = Vec::new();
+        for column in &air.fixed_columns {
+            let register = column.register();
+            let values = column.values().iter().map(|&value| lift(value)).collect::<Result<Vec<B>, _>>()?;
+            let value = match values.len() {
+                1 => Term::Constant(values[0]),
+                len if len.is_power_of_two() => {
+                    periodic.push(values.clone());
+                    Term::Periodic(periodic.len() - 1)
+                }
+                len => {
+                    return Err(AirError::InvalidConstraint(format!(
+                        "Fixed column on register {} repeats {} values; Winterfell needs a power of two",
+                        register, len
+                    )))
+                }
+            };
+            // current - periodic: the register is of degree one, and the
+            // periodic polynomial of a smaller degree than a trace column
+            let current = Term::Register { register, next: false };
+            transitions.push(Transition { term: Term::Sub(Box::new(current), Box::new(value)), degree: 1 });
+            fixed.push((register, values));
+        }
+
+        let mut assertions = Vec::new();
+        for constraint in &air.boundary.constraints {
+            let value = lift(constraint.value)?;
+            assertions.push(Cell { register: constraint.register, row: Some(constraint.step), value });
+        }
+        let bound = public_inputs.bind(&air.public_inputs).map_err(AirError::InvalidConstraint)?;
+        for assertion in bound.assertions() {
+            let value = lift(assertion.value)?;
+            assertions.push(Cell { register: assertion.register, row: Some(assertion.row), value });
+        }
+        for &(register, value) in bound.final_values() {
+            assertions.push(Cell { register, row: None, value: lift(value)? });
+        }
+
+        Ok(Self { width, transitions, periodic, fixed, assertions, digest: air.digest().into() })
+    }
+
+    /// Values asserted on a trace of the given length, by register and row
+    ///
+    /// Fixed columns are asserted on the last row. A cell asserted more than
+    /// once keeps its first value; the first cell given another value as
+    /// well is returned alongside, as `(register, row)`.
+    fn cells(&self, trace_length: usize) -> (BTreeMap<usize, BTreeMap<usize, B>>, Option<(usize, usize)>) {
+        let last_row = trace_length.saturating_sub(1);
+        let fixed = self
+            .fixed
+            .iter()
+            .map(|(register, values)| (*register, last_row, values[last_row % values.len()]));
+        let asserted = self
+            .assertions
+            .iter()
+            .map(|cell| (cell.register, cell.row.filter(|&row| row < trace_length).unwrap_or(last_row), cell.value));
+        let mut cells: BTreeMap<usize, BTreeMap<usize, B>> = BTreeMap::new();
+        let mut contradiction = None;
+        for (register, row, value) in asserted.chain(fixed) {
+            let first = *cells.entry(register).or_default().entry(row).or_insert(value);
+            if first != value && contradiction.is_none() {
+                contradiction = Some((register, row));
+            }
+        }
+        (cells, contradiction)
+    }
+
+    /// Check that no cell of a trace of the given length is asserted to two values
+    ///
+    /// No trace satisfies such a statement, and Winterfell cannot express it.
+    pub fn check_assertions(&self, trace_length: usize) -> Result<(), AirError> {
+        match self.cells(trace_length).1 {
+            Some((register, row)) => Err(AirError::InvalidBoundary(format!(
+                "register {} is asserted to two values at row {}",
+                register, row
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Values asserted on a trace of the given length, fixed columns on the last row included
+    pub(super) fn assertions(&self, trace_length: usize) -> Vec<Assertion<B>> {
+        let (cells, _) = self.cells(trace_length);
+        cells
+            .into_iter()
+            .flat_map(|(register, rows)| register_assertions(register, rows, trace_length))
+            .collect()
+    }
+}
+
+impl<B: StarkField> WinterfellAirInputs<B> {
+    /// Degrees of the transition constraints, in evaluation order
+    pub(super) fn degrees(&self) -> Vec<TransitionConstraintDegree> {
+        self.transitions.iter().map(|transition| TransitionConstraintDegree::new(transition.degree)).collect()
+    }
+}
+
+/// Assertions of the cells of one register, by row
+///
+/// Cells on every `stride`-th row from a first row below `stride`, across
+/// the whole trace, become one periodic assertion if they share their value
+/// and one sequence assertion otherwise. Other cells are asserted one by one.
+fn register_assertions<B: StarkField>(
+    register: usize,
+    rows: BTreeMap<usize, B>,
+    trace_length: usize,
+) -> Vec<Assertion<B>> {
+    let (steps, values): (Vec<usize>, Vec<B>) = rows.into_iter().unzip();
+    if let [first, second, ..] = steps[..] {
+        let stride = second - first;
+        let covers_trace = stride >= 2
+            && stride.is_power_of_two()
+            && first < stride
+            && trace_length % stride == 0
+            && steps.len() == trace_length / stride
+            && steps.iter().enumerate().all(|(index, &step)| step == first + index * stride);
+        if covers_trace {
+            return vec![match values.iter().all(|&value| value == values[0]) {
+                true => Assertion::periodic(register, first, stride, values[0]),
+                false => Assertion::sequence(register, first, stride, values),
+            }];
+        }
+    }
+    steps
+        .into_iter()
+        .zip(values)
+        .map(|(step, value)| Assertion::single(register, step, value))
+        .collect()
+}
+
+impl<B: StarkField> ToElements<B> for WinterfellAirInputs<B> {
+    /// The AIR digest, as eight 32-bit limbs, followed by every assertion
+    ///
+    /// An assertion is its register as two 32-bit limbs, a tag that is `0`
+    /// for a row and `1` for the last row, the row as two limbs (zero for the
+    /// last row) and the asserted value, so Winterfell's public-input seed
+    /// binds where each value is asserted as well as the value.
+    fn to_elements(&self) -> Vec<B> {
+        let digest = self.digest.chunks_exact(4).map(|limb| {
+            let limb = u32::from_le_bytes(limb.try_into().expect("four-byte limb"));
+            B::from(limb)
+        });
+        let assertions = self.assertions.iter().flat_map(|cell| {
+            let (tag, row) = match cell.row {
+                Some(row) => (0u32, row as u64),
+                None => (1u32, 0),
+            };
+            let [register_low, register_high] = limbs(cell.register as u64);
+            let [row_low, row_high] = limbs(row);
+            [register_low, register_high, B::from(tag), row_low, row_high, cell.value]
+        });
+        digest.chain(assertions).collect()
+    }
+}
+
+/// Low and high 32-bit limbs of `value`
+fn limbs<B: StarkField>(value: u64) -> [B; 2] {
+    [B::from(value as u32), B::from((value >> 32) as u32)]
+}
+
+/// Rows of the transition matrix, as constraints on the registers it defines
+fn matrix_transitions<F, B>(air: &Air<F>) -> Result<Vec<Transition<B>>, AirError>
+where
+    F: FieldElement,
+    B: StarkField + From<u64>,
+{
+    let fixed: Vec<usize> = air.fixed_columns.iter().map(|column| column.register()).collect();
+    let mut transitions = Vec::new();
+    for (register, row) in air.transition.coefficients().iter().enumerate() {
+        if air.free_registers.contains(&register) || fixed.contains(&register) {
+            continue;
+        }
+        // next[register] - Σ row[j] * current[j]
+        let mut term = Term::Register { register, next: true };
+        for (column, &coefficient) in row.iter().enumerate().filter(|(_, coefficient)| !coefficient.is_zero()) {
+            let current = Term::Register { register: column, next: false };
+            let product = Term::Mul(Box::new(Term::Constant(lift(coefficient)?)), Box::new(current));
+            term = Term::Sub(Box::new(term), Box::new(product));
+        }
+        transitions.push(Transition { term, degree: 1 });
+    }
+    Ok(transitions)
+}
+
+/// Compile an expression over a two-row frame, resolving column names with `register`
+fn compile<F: FieldElement, B: StarkField + From<u64>>(
+    expression: &Expr<F>,
+    register: &dyn Fn(&str) -> Option<usize>,
+) -> Result<Term<B>, AirError> {
+    let compile_box = |expression: &Expr<F>| compile(expression, register).map(Box::new);
+    Ok(match expression {
+        Expr::Constant(value) => Term::Constant(lift(*value)?),
+        Expr::Challenge(_) => {
+            return Err(AirError::InvalidConstraint(format!(
+                "`{}` reads a verifier challenge; challenge segments have no Winterfell counterpart",
+                expression
+            )))
+        }
+        Expr::Column { name, offset } => {
+            let column = register(name)
+                .ok_or_else(|| AirError::InvalidConstraint(format!("Unknown column `{}`", name)))?;
+            if *offset > 1 {
+                return Err(AirError::InvalidTransition(format!(
+                    "`{}` reads {} rows ahead; Winterfell frames hold two rows",
+                    expression, offset
+                )));
+            }
+            Term::Register { register: column, next: *offset == 1 }
+        }
+        Expr::Add(a, b) => Term::Add(compile_box(a)?, compile_box(b)?),
+        Expr::Sub(a, b) => Term::Sub(compile_box(a)?, compile_box(b)?),
+        Expr::Mul(a, b) => Term::Mul(compile_box(a)?, compile_box(b)?),
+        Expr::Neg(a) => Term::Neg(compile_box(a)?),
+    })
+}
+
+/// The Winterfell element an XFG constant stands for
+///
+/// Exact when the moduli match; otherwise the constant is lifted through its
+/// centered integer representative, which must be below 2^32 in magnitude.
+fn lift<F: FieldElement, B: StarkField + From<u64>>(value: F) -> Result<B, AirError> {
+    let canonical = value.as_canonical_u64();
+    if B::MODULUS == B::PositiveInteger::from(F::MODULUS) {
+        return Ok(B::from(canonical));
+    }
+    let negated = F::MODULUS - canonical;
+    match (canonical < MAX_LIFTED_CONSTANT, negated < MAX_LIFTED_CONSTANT) {
+        (true, _) => Ok(B::from(canonical)),
+        (false, true) => Ok(-B::from(negated)),
+        (false, false) => Err(AirError::InvalidConstraint(format!(
+            "Constant {} has no counterpart in a field of another modulus",
+            value
+        ))),
+    }
+}
+
+/// Winterfell AIR compiled from an XFG AIR
+///
+/// Built by Winterfell from a [`TraceInfo`], the [`WinterfellAirInputs`]
+/// and the proof options. The AIR must assert at least one value, as
+/// Winterfell requires.
+pub struct WinterfellAir<B: StarkField> {
+    /// Trace shape, constraint degrees and proof options
+    context: AirContext<B>,
+    /// Compiled XFG AIR and public inputs
+    inputs: WinterfellAirInputs<B>,
+}
+
+impl<B: StarkField> WinterfellAir<B> {
+    /// Compiled XFG AIR and public inputs
+    pub fn inputs(&self) -> &WinterfellAirInputs<B> {
+        &self.inputs
+    }
+}
+
+impl<B> winterfell::Air for WinterfellAir<B>
+where
+    B: StarkField + ExtensibleField<2> + ExtensibleField<3> + From<u64>,
+{
+    type BaseField = B;
+    type PublicInputs = WinterfellAirInputs<B>;
+
+    fn new(trace_info: TraceInfo, inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        assert_eq!(
+            trace_info.main_trace_width(),
+            inputs.width,
+            "trace width does not match the compiled AIR"
+        );
+        let degrees = inputs.degrees();
+        let num_assertions = inputs.assertions(trace_info.length()).len();
+        let context = AirContext::new(trace_info, degrees, num_assertions, options);
+        Self { context, inputs }
+    }
+
+    fn context(&self) -> &AirContext<B> {
+        &self.context
+    }
+
+    fn evaluate_transition<E: WinterfellElement<BaseField = B>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        for (result, transition) in result.iter_mut().zip(&self.inputs.transitions) {
+            *result = transition.term.evaluate(frame.current(), frame.next(), periodic_values);
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<B>> {
+        self.inputs.assertions(self.context.trace_len())
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<B>> {
+        self.inputs.periodic.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::types::field::PrimeField64;
+    use crate::types::{ColIdx, RowIdx};
+    use winterfell::math::fields::f64::BaseElement;
+    use winterfell::Air as _;
+
+    #[test]
+    fn test_builder_air_compiles_to_winterfell() {
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_fixed_column("rom", &[PrimeField64::new(3), PrimeField64::new(1)])
+            .with_column("acc")
+            .with_free_column("sq")
+            .with_transition(col("acc").next() - col("acc") - col("rom"))
+            .with_transition(col("sq").next() - col("acc") * col("acc"))
+            .with_initial("acc", PrimeField64::zero())
+            .build()
+            .unwrap();
+        let inputs = WinterfellAirInputs::<BaseElement>::new(&air, &PublicInputs::new()).unwrap();
+        let options = ProofOptions::new(28, 8, 0, winterfell::FieldExtension::None, 4, 31);
+        let winterfell_air = WinterfellAir::new(TraceInfo::new(3, 8), inputs, options);
+        assert_eq!(winterfell_air.context().num_main_transition_constraints(), 3);
+        assert_eq!(winterfell_air.get_periodic_column_values(), vec![vec![BaseElement::new(3), BaseElement::new(1)]]);
+        // The ROM is asserted on the last row, and acc starts at zero
+        assert_eq!(
+            winterfell_air.get_assertions(),
+            vec![Assertion::single(0, 7, BaseElement::new(1)), Assertion::single(1, 0, BaseElement::ZERO)]
+        );
+
+        // A valid frame satisfies every constraint; `-1` crosses over although the moduli differ
+        let current = [3u64, 5, 0].map(BaseElement::new);
+        let next = [1u64, 8, 25].map(BaseElement::new);
+        let frame = EvaluationFrame::from_rows(current.to_vec(), next.to_vec());
+        let mut result = [BaseElement::ONE; 3];
+        winterfell_air.evaluate_transition(&frame, &[BaseElement::new(3)], &mut result);
+        assert_eq!(result, [BaseElement::ZERO; 3]);
+
+        // Constraints over three rows have no Winterfell counterpart
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_transition(col("x").next() - col("x"))
+            .with_transition(col("x").next().next() - col("x"))
+            .build()
+            .unwrap();
+        assert!(WinterfellAirInputs::<BaseElement>::new(&air, &PublicInputs::new()).is_err());
+    }
+
+    #[test]
+    fn test_boundary_constraints_become_assertions() {
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_column("y")
+            .with_transition(col("x").next() - col("x"))
+            .with_transition(col("y").next() - col("y"))
+            .with_initial("x", PrimeField64::new(5))
+            .build()
+            .unwrap();
+        let options = ProofOptions::new(28, 8, 0, winterfell::FieldExtension::None, 4, 31);
+        let assertions = |public_inputs: PublicInputs<PrimeField64>| {
+            let inputs = WinterfellAirInputs::<BaseElement>::new(&air, &public_inputs).unwrap();
+            inputs.check_assertions(8).map(|_| WinterfellAir::new(TraceInfo::new(2, 8), inputs, options.clone()))
+        };
+
+        // Every other row of `x` shares a value, and every fourth row of `y` does not;
+        // the initial value of `x` is asserted again
+        let mut public_inputs = PublicInputs::new();
+        for row in [0, 2, 4, 6] {
+            public_inputs = public_inputs.assert(ColIdx(0), RowIdx(row), PrimeField64::new(5));
+        }
+        let public_inputs = public_inputs
+            .assert(ColIdx(1), RowIdx(1), PrimeField64::new(3))
+            .assert(ColIdx(1), RowIdx(5), PrimeField64::new(4));
+        let five = BaseElement::new(5);
+        assert_eq!(
+            assertions(public_inputs).unwrap().get_assertions(),
+            vec![
+                Assertion::periodic(0, 0, 2, five),
+                Assertion::sequence(1, 1, 4, vec![BaseElement::new(3), BaseElement::new(4)]),
+            ]
+        );
+
+        // Cells on every row are asserted one by one
+        let public_inputs = (1..8).fold(PublicInputs::new(), |inputs, row| {
+            inputs.assert(ColIdx(0), RowIdx(row), PrimeField64::new(5))
+        });
+        let public_inputs = public_inputs
+            .assert(ColIdx(1), RowIdx(3), PrimeField64::new(2))
+            .assert(ColIdx(1), RowIdx(7), PrimeField64::new(2));
+        assert_eq!(
+            assertions(public_inputs).unwrap().get_assertions(),
+            [0, 1, 2, 3, 4, 5, 6, 7]
+                .map(|row| Assertion::single(0, row, five))
+                .into_iter()
+                .chain([Assertion::periodic(1, 3, 4, BaseElement::new(2))])
+                .collect::<Vec<_>>()
+        );
+
+        // A final value on row 7 contradicts the one asserted there
+        let public_inputs = PublicInputs::new()
+            .assert(ColIdx(1), RowIdx(7), PrimeField64::new(2))
+            .assert_final(1, PrimeField64::new(3));
+        assert!(assertions(public_inputs).is_err());
+
+        // The seeded public inputs bind the cell each value is asserted on
+        let elements = |public_inputs: PublicInputs<PrimeField64>| {
+            WinterfellAirInputs::<BaseElement>::new(&air, &public_inputs).unwrap().to_elements()
+        };
+        let three = PrimeField64::new(3);
+        let on_x = elements(PublicInputs::new().assert(ColIdx(0), RowIdx(1), three));
+        assert_ne!(on_x, elements(PublicInputs::new().assert(ColIdx(1), RowIdx(1), three)));
+        assert_ne!(on_x, elements(PublicInputs::new().assert(ColIdx(0), RowIdx(2), three)));
+        assert_ne!(on_x, elements(PublicInputs::new().assert_final(0, three)));
+    }
+}
@@ -0,0 +1,329 @@
+//! Goldilocks Field
+//!
+//! [`Goldilocks`] is an XFG field element over Winterfell's 64-bit field
+//! `2^64 - 2^32 + 1`, stored as Winterfell's own `BaseElement`. AIRs and
+//! traces over it are AIRs and traces over Winterfell's field: a trace is
+//! handed to Winterfell with its elements as they are, with no conversion
+//! through canonical integers, and an owned trace gives up its columns
+//! without copying them.
+
+use crate::types::stark::ExecutionTrace;
+use crate::types::FieldElement;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use winterfell::math::fields::f64::BaseElement;
+use winterfell::math::{FieldElement as _, StarkField};
+use winterfell::TraceTable;
+
+/// Element of the Goldilocks field `2^64 - 2^32 + 1`, backed by Winterfell's `BaseElement`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Goldilocks(BaseElement);
+
+impl Goldilocks {
+    /// Field modulus `2^64 - 2^32 + 1`
+    pub const MODULUS: u64 = BaseElement::MODULUS;
+
+    /// Element whose canonical integer is `value` reduced modulo the field modulus
+    pub fn new(value: u64) -> Self {
+        Self(BaseElement::new(value))
+    }
+
+    /// Winterfell element
+    pub fn inner(&self) -> BaseElement {
+        self.0
+    }
+}
+
+impl From<BaseElement> for Goldilocks {
+    fn from(element: BaseElement) -> Self {
+        Self(element)
+    }
+}
+
+impl From<Goldilocks> for BaseElement {
+    fn from(element: Goldilocks) -> Self {
+        element.0
+    }
+}
+
+impl FieldElement for Goldilocks {
+    const MODULUS: u64 = BaseElement::MODULUS;
+    const CHARACTERISTIC: u64 = BaseElement::MODULUS;
+
+    fn zero() -> Self {
+        Self(BaseElement::ZERO)
+    }
+
+    fn one() -> Self {
+        Self(BaseElement::ONE)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == BaseElement::ZERO
+    }
+
+    fn is_one(&self) -> bool {
+        self.0 == BaseElement::ONE
+    }
+
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += other.0;
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= other.0;
+    }
+
+    fn mul_assign(&mut self, other: &Self) {
+        self.0 *= other.0;
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        (!self.is_zero()).then(|| Self(self.0.inv()))
+    }
+
+    fn pow(&self, exponent: u64) -> Self {
+        Self(self.0.exp(exponent))
+    }
+
+    /// Tonelli-Shanks, with Winterfell's primitive `2^32`-th root of unity
+    fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(*self);
+        }
+        if !self.pow((Self::MODULUS - 1) / 2).is_one() {
+            return None;
+        }
+        let mut m = BaseElement::TWO_ADICITY;
+        let q = (Self::MODULUS - 1) >> m;
+        let mut c = Self(BaseElement::get_root_of_unity(m));
+        let mut r = self.pow((q + 1) / 2);
+        let mut t = self.pow(q);
+        while !t.is_one() {
+            let mut i = 0;
+            let mut square = t;
+            while !square.is_one() {
+                square = square * square;
+                i += 1;
+            }
+            let b = c.pow(1 << (m - i - 1));
+            r = r * b;
+            c = b * b;
+            t = t * c;
+            m = i;
+        }
+        Some(r)
+    }
+
+    fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes[24..].copy_from_slice(&self.0.as_int().to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let value = u64::from_le_bytes(bytes[24..].try_into().expect("eight bytes"));
+        (value < Self::MODULUS).then(|| Self::new(value))
+    }
+
+    fn random() -> Self {
+        use rand::Rng;
+        Self::new(rand::thread_rng().gen_range(0..Self::MODULUS))
+    }
+
+    fn from_canonical_u64(value: u64) -> Self {
+        Self::new(value)
+    }
+
+    fn as_canonical_u64(&self) -> u64 {
+        self.0.as_int()
+    }
+
+    /// `7` is neither a square nor a cube modulo `2^64 - 2^32 + 1`
+    fn extension_nonresidue(degree: usize) -> Option<Self> {
+        match degree {
+            2 | 3 => Some(Self::new(7)),
+            _ => None,
+        }
+    }
+}
+
+impl PartialOrd for Goldilocks {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Goldilocks {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.as_int().cmp(&other.0.as_int())
+    }
+}
+
+impl Serialize for Goldilocks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_int().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Goldilocks {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = u64::deserialize(deserializer)?;
+        if value >= Self::MODULUS {
+            return Err(D::Error::custom(format!("{} is not below the Goldilocks modulus", value)));
+        }
+        Ok(Self::new(value))
+    }
+}
+
+impl Add for Goldilocks {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Goldilocks {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for Goldilocks {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        Self(self.0 - other.0)
+    }
+}
+
+impl SubAssign for Goldilocks {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl Mul for Goldilocks {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        Self(self.0 * other.0)
+    }
+}
+
+impl MulAssign for Goldilocks {
+    fn mul_assign(&mut self, other: Self) {
+        self.0 *= other.0;
+    }
+}
+
+impl Neg for Goldilocks {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl Display for Goldilocks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Goldilocks({})", self.0.as_int())
+    }
+}
+
+impl ExecutionTrace<Goldilocks> {
+    /// Winterfell trace table of the main columns, holding the same elements
+    pub fn to_trace_table(&self) -> TraceTable<BaseElement> {
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| column.iter().map(|element| element.0).collect())
+            .collect();
+        TraceTable::init(columns)
+    }
+
+    /// Winterfell trace table of the main columns, taking them over
+    ///
+    /// `Goldilocks` has the layout of `BaseElement`, so each column is
+    /// unwrapped in its own allocation rather than copied into a new one.
+    pub fn into_trace_table(self) -> TraceTable<BaseElement> {
+        let columns = self
+            .columns
+            .into_columns()
+            .into_iter()
+            .map(|column| column.into_iter().map(BaseElement::from).collect())
+            .collect();
+        TraceTable::init(columns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::fixtures::fibonacci;
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::proof::{StarkProver, StarkVerifier};
+    use crate::winterfell_integration::{XfgWinterfellProver, XfgWinterfellVerifier};
+    use winterfell::{FieldExtension, ProofOptions, Trace};
+
+    #[test]
+    fn test_goldilocks_field() {
+        let minus_one = Goldilocks::new(Goldilocks::MODULUS - 1);
+        assert_eq!(minus_one, -Goldilocks::one());
+        assert_eq!(minus_one + Goldilocks::one(), Goldilocks::zero());
+        assert_eq!(Goldilocks::new(Goldilocks::MODULUS + 3), Goldilocks::new(3));
+        assert_eq!(minus_one.as_canonical_u64(), Goldilocks::MODULUS - 1);
+        assert_eq!(minus_one.inverse().unwrap() * minus_one, Goldilocks::one());
+        assert!(Goldilocks::zero().inverse().is_none());
+        assert!(Goldilocks::new(3) < minus_one);
+
+        let square = Goldilocks::new(123_456_789) * Goldilocks::new(123_456_789);
+        let root = square.sqrt().unwrap();
+        assert_eq!(root * root, square);
+        assert!(Goldilocks::new(7).sqrt().is_none());
+        let third = (Goldilocks::MODULUS - 1) / 3;
+        assert!(!Goldilocks::extension_nonresidue(3).unwrap().pow(third).is_one());
+
+        assert_eq!(Goldilocks::from_bytes(&minus_one.to_bytes()), Some(minus_one));
+        let mut unreduced = [0u8; 32];
+        unreduced[24..].copy_from_slice(&Goldilocks::MODULUS.to_le_bytes());
+        assert!(Goldilocks::from_bytes(&unreduced).is_none());
+        let encoded = bincode::serialize(&minus_one).unwrap();
+        assert_eq!(bincode::deserialize::<Goldilocks>(&encoded).unwrap(), minus_one);
+        assert!(bincode::deserialize::<Goldilocks>(&bincode::serialize(&u64::MAX).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_goldilocks_trace_flows_into_winterfell() {
+        // Fibonacci numbers pass 2^64 - 2^32 + 1 within 128 rows, and keep
+        // their Goldilocks values in Winterfell's trace table
+        let (air, trace) = fibonacci::<Goldilocks>(128);
+        let table = trace.to_trace_table();
+        assert_eq!(table.length(), 128);
+        assert_eq!(Goldilocks::from(table.get(1, 127)), trace.columns.value(1, 127));
+        let owned = trace.clone().into_trace_table();
+        assert!((0..128).all(|row| (0..2).all(|column| owned.get(column, row) == table.get(column, row))));
+
+        // The native prover works over the field too
+        let last = trace.columns.value(1, 127);
+        let public_inputs = PublicInputs::new().assert_final(1, last);
+        let proof = StarkProver::<Goldilocks>::with_params(128, 8, 16, 1)
+            .prove(&air, &public_inputs, &trace)
+            .unwrap();
+        StarkVerifier::<Goldilocks>::new(48).verify_with_air(&air, &proof, &public_inputs).unwrap();
+
+        // Winterfell proves it as is, and the generic conversion through
+        // canonical integers gives the same proof
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let winterfell_prover = XfgWinterfellProver::with_options(options.clone());
+        let proof = winterfell_prover.prove_goldilocks(&air, &public_inputs, &trace).unwrap();
+        XfgWinterfellVerifier::with_options(options).verify(&air, &public_inputs, &proof).unwrap();
+        let converted = winterfell_prover.prove(&air, &public_inputs, &trace).unwrap();
+        assert_eq!(converted.proof().to_bytes(), proof.proof().to_bytes());
+    }
+}
@@ -0,0 +1,171 @@
+//! Cross-Backend Verification
+//!
+//! Both backends wrap their proofs in an XFG [`StarkProof`]: a native proof
+//! carries its own commitments, a Winterfell proof carries Winterfell's bytes
+//! in its [`ProofBody`]. The commitment schemes differ, so neither verifier
+//! checks the other's proofs by itself. [`CrossBackendVerifier`] checks a
+//! wrapped proof of either backend against one AIR and its public inputs,
+//! dispatching on the body, and [`differential_check`] proves a statement
+//! with both backends and reports what each made of it. Together they help
+//! test the adapter and move users from one backend to the other.
+
+use super::prover::WinterfellProof;
+use super::{XfgWinterfellProver, XfgWinterfellVerifier};
+use crate::air::Air;
+use crate::proof::backend::{ProverBackend, VerifierBackend};
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::{StarkProver, StarkVerifier};
+use crate::types::stark::{ExecutionTrace, ProofBody, StarkProof};
+use crate::types::FieldElement;
+use crate::Result;
+
+pub use crate::proof::backend::Backend;
+
+impl<F: FieldElement> ProverBackend<F> for XfgWinterfellProver {
+    fn backend(&self) -> Backend {
+        Backend::Winterfell
+    }
+
+    /// Prove with [`XfgWinterfellProver::prove`] and wrap the proof
+    fn prove(&self, air: &Air<F>, public_inputs: &PublicInputs<F>, trace: &ExecutionTrace<F>) -> Result<StarkProof<F>> {
+        XfgWinterfellProver::prove(self, air, public_inputs, trace)?.to_stark_proof(air)
+    }
+}
+
+impl<F: FieldElement> VerifierBackend<F> for XfgWinterfellVerifier {
+    fn backend(&self) -> Backend {
+        Backend::Winterfell
+    }
+
+    /// Unwrap the proof with [`WinterfellProof::from_stark_proof`] and verify it
+    fn verify(&self, air: &Air<F>, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Result<()> {
+        let unwrapped = WinterfellProof::from_stark_proof(proof, air, public_inputs)?;
+        XfgWinterfellVerifier::verify(self, air, public_inputs, &unwrapped)
+    }
+}
+
+/// Verifier of wrapped proofs from either backend
+pub struct CrossBackendVerifier<F: FieldElement> {
+    /// Verifier of native proofs
+    native: StarkVerifier<F>,
+    /// Verifier of Winterfell proofs
+    winterfell: XfgWinterfellVerifier,
+}
+
+impl<F: FieldElement> CrossBackendVerifier<F> {
+    /// Verifier with the default verifier of each backend
+    pub fn new() -> Self {
+        Self {
+            native: StarkVerifier::default(),
+            winterfell: XfgWinterfellVerifier::new(),
+        }
+    }
+
+    /// Check native proofs with `verifier`
+    pub fn with_native(mut self, verifier: StarkVerifier<F>) -> Self {
+        self.native = verifier;
+        self
+    }
+
+    /// Check Winterfell proofs with `verifier`
+    pub fn with_winterfell(mut self, verifier: XfgWinterfellVerifier) -> Self {
+        self.winterfell = verifier;
+        self
+    }
+
+    /// Verify a proof of either backend, returning the backend that generated it
+    ///
+    /// The proof is checked by the [`VerifierBackend`] of the backend its
+    /// body comes from.
+    pub fn verify(&self, air: &Air<F>, proof: &StarkProof<F>, public_inputs: &PublicInputs<F>) -> Result<Backend> {
+        let verifier: &dyn VerifierBackend<F> = match proof.body {
+            ProofBody::Native => &self.native,
+            ProofBody::Winterfell { .. } => &self.winterfell,
+        };
+        verifier.verify(air, proof, public_inputs)?;
+        Ok(verifier.backend())
+    }
+}
+
+impl<F: FieldElement> Default for CrossBackendVerifier<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of proving and verifying one statement with both backends
+#[derive(Debug)]
+pub struct DifferentialReport<F: FieldElement> {
+    /// Verified native proof, or why the native backend rejected the statement
+    pub native: std::result::Result<StarkProof<F>, String>,
+    /// Verified wrapped Winterfell proof, or why Winterfell rejected the statement
+    pub winterfell: std::result::Result<StarkProof<F>, String>,
+}
+
+impl<F: FieldElement> DifferentialReport<F> {
+    /// Whether both backends accepted the statement, or both rejected it
+    pub fn agrees(&self) -> bool {
+        self.native.is_ok() == self.winterfell.is_ok()
+    }
+}
+
+/// Prove a statement with both backends and verify each proof with `verifier`
+///
+/// A backend rejects the statement if it fails to prove it or if its proof
+/// does not verify. Backends that disagree point to a statement one of them
+/// compiles differently, such as an AIR whose values wrap around one modulus
+/// but not the other.
+pub fn differential_check<F: FieldElement>(
+    native: &StarkProver<F>,
+    winterfell: &XfgWinterfellProver,
+    verifier: &CrossBackendVerifier<F>,
+    air: &Air<F>,
+    public_inputs: &PublicInputs<F>,
+    trace: &ExecutionTrace<F>,
+) -> DifferentialReport<F> {
+    let checked = |prover: &dyn ProverBackend<F>| {
+        let proof = prover.prove(air, public_inputs, trace)?;
+        verifier.verify(air, &proof, public_inputs)?;
+        Ok::<_, crate::XfgStarkError>(proof)
+    };
+    DifferentialReport {
+        native: checked(native).map_err(|error| error.to_string()),
+        winterfell: checked(winterfell).map_err(|error| error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::fixtures::fibonacci;
+    use crate::types::field::PrimeField64;
+    use winterfell::{FieldExtension, ProofOptions};
+
+    #[test]
+    fn test_backends_cross_check() {
+        let (air, trace) = fibonacci::<PrimeField64>(32);
+        let native = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1);
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let winterfell = XfgWinterfellProver::with_options(options.clone());
+        let verifier = CrossBackendVerifier::new()
+            .with_native(StarkVerifier::new(48))
+            .with_winterfell(XfgWinterfellVerifier::with_options(options.clone()));
+
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 31));
+        let report = differential_check(&native, &winterfell, &verifier, &air, &public_inputs, &trace);
+        assert!(report.agrees());
+        let (native_proof, winterfell_proof) = (report.native.unwrap(), report.winterfell.unwrap());
+        assert_eq!(verifier.verify(&air, &native_proof, &public_inputs).unwrap(), Backend::Native);
+        assert_eq!(verifier.verify(&air, &winterfell_proof, &public_inputs).unwrap(), Backend::Winterfell);
+        let backend: &dyn VerifierBackend<PrimeField64> = &XfgWinterfellVerifier::with_options(options.clone());
+        backend.verify(&air, &winterfell_proof, &public_inputs).unwrap();
+        assert!(backend.verify(&air, &native_proof, &public_inputs).is_err());
+
+        // Both backends reject a wrong claim, and neither proof verifies for it
+        let wrong = PublicInputs::new().assert_final(1, PrimeField64::new(7));
+        let report = differential_check(&native, &winterfell, &verifier, &air, &wrong, &trace);
+        assert!(report.agrees() && report.native.is_err());
+        assert!(verifier.verify(&air, &native_proof, &wrong).is_err());
+        assert!(verifier.verify(&air, &winterfell_proof, &wrong).is_err());
+    }
+}
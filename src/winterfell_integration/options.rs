@@ -0,0 +1,248 @@
+//! Winterfell Proof Options
+//!
+//! Winterfell panics on proof options it does not support, when they are
+//! created, and on options too weak for an AIR, once it starts proving or
+//! verifying. [`ProofOptionsPreset`] names options for common security
+//! targets; [`checked_proof_options`] and [`check_proof_options`] turn the
+//! panics into errors, so that the backend rejects impossible combinations
+//! before Winterfell sees them.
+
+use super::adapter::WinterfellAirInputs;
+use crate::types::stark::StarkError;
+use winterfell::math::fields::f64::BaseElement;
+use winterfell::{FieldExtension, ProofOptions};
+
+/// Most queries Winterfell opens
+const MAX_NUM_QUERIES: usize = 255;
+
+/// Largest blowup factor Winterfell supports
+const MAX_BLOWUP_FACTOR: usize = 128;
+
+/// Most proof-of-work bits Winterfell grinds
+const MAX_GRINDING_FACTOR: u32 = 32;
+
+/// Largest degree of the FRI remainder polynomial
+const MAX_REMAINDER_DEGREE: usize = 255;
+
+/// Shortest trace Winterfell proves
+pub(super) const MIN_TRACE_LENGTH: usize = 8;
+
+/// Proof options Winterfell would reject
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProofOptionsError {
+    /// Number of queries out of range
+    #[error("{0} queries; Winterfell opens 1 to {MAX_NUM_QUERIES}")]
+    NumQueries(usize),
+
+    /// Blowup factor not a supported power of two
+    #[error("blowup factor {0}; Winterfell takes powers of two from 2 to {MAX_BLOWUP_FACTOR}")]
+    BlowupFactor(usize),
+
+    /// Too many proof-of-work bits
+    #[error("grinding factor {0}; Winterfell grinds at most {MAX_GRINDING_FACTOR} bits")]
+    GrindingFactor(u32),
+
+    /// Unsupported FRI folding factor
+    #[error("FRI folding factor {0}; Winterfell folds by 2, 4, 8 or 16")]
+    FoldingFactor(usize),
+
+    /// FRI remainder degree not one less than a power of two
+    #[error("FRI remainder degree {0}; Winterfell takes powers of two minus one, up to {MAX_REMAINDER_DEGREE}")]
+    RemainderDegree(usize),
+
+    /// Trace length Winterfell does not prove
+    #[error("trace length {0}; Winterfell proves powers of two from {MIN_TRACE_LENGTH} rows")]
+    TraceLength(usize),
+
+    /// Blowup factor below what the constraint degrees need
+    #[error("blowup factor {actual}; the constraint degrees of the AIR need at least {required}")]
+    BlowupTooSmall {
+        /// Smallest blowup factor the AIR supports
+        required: usize,
+        /// Blowup factor of the options
+        actual: usize,
+    },
+
+    /// Statement without assertions
+    #[error("the statement asserts no value; Winterfell needs at least one assertion")]
+    NoAssertions,
+}
+
+impl From<ProofOptionsError> for StarkError {
+    fn from(error: ProofOptionsError) -> Self {
+        StarkError::InvalidProof(format!("Invalid Winterfell proof options: {}", error))
+    }
+}
+
+/// Proof options of the Winterfell backend for common security targets
+///
+/// Conjectured security, from the queries, blowup factor and grinding, is
+/// capped by the field the challenges are drawn from and by the 128-bit
+/// collision resistance of [`WinterfellHash`](super::WinterfellHash).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofOptionsPreset {
+    /// Small, quick proofs for tests and development, over the base field
+    Fast,
+    /// Over 100 bits of conjectured security, over the quadratic extension
+    Balanced,
+    /// 128 bits of conjectured security, over the cubic extension
+    HighSecurity,
+}
+
+impl ProofOptionsPreset {
+    /// Proof options of the preset
+    pub fn proof_options(self) -> ProofOptions {
+        match self {
+            Self::Fast => ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31),
+            Self::Balanced => ProofOptions::new(32, 8, 16, FieldExtension::Quadratic, 8, 31),
+            Self::HighSecurity => ProofOptions::new(27, 16, 20, FieldExtension::Cubic, 8, 127),
+        }
+    }
+}
+
+impl From<ProofOptionsPreset> for ProofOptions {
+    fn from(preset: ProofOptionsPreset) -> Self {
+        preset.proof_options()
+    }
+}
+
+/// Winterfell proof options, or the first parameter Winterfell would reject
+///
+/// The parameters are those of `ProofOptions::new`, which panics instead.
+pub fn checked_proof_options(
+    num_queries: usize,
+    blowup_factor: usize,
+    grinding_factor: u32,
+    field_extension: FieldExtension,
+    fri_folding_factor: usize,
+    fri_remainder_max_degree: usize,
+) -> Result<ProofOptions, ProofOptionsError> {
+    if num_queries == 0 || num_queries > MAX_NUM_QUERIES {
+        return Err(ProofOptionsError::NumQueries(num_queries));
+    }
+    if !blowup_factor.is_power_of_two() || !(2..=MAX_BLOWUP_FACTOR).contains(&blowup_factor) {
+        return Err(ProofOptionsError::BlowupFactor(blowup_factor));
+    }
+    if grinding_factor > MAX_GRINDING_FACTOR {
+        return Err(ProofOptionsError::GrindingFactor(grinding_factor));
+    }
+    if ![2, 4, 8, 16].contains(&fri_folding_factor) {
+        return Err(ProofOptionsError::FoldingFactor(fri_folding_factor));
+    }
+    if fri_remainder_max_degree > MAX_REMAINDER_DEGREE || !(fri_remainder_max_degree + 1).is_power_of_two() {
+        return Err(ProofOptionsError::RemainderDegree(fri_remainder_max_degree));
+    }
+    Ok(ProofOptions::new(
+        num_queries,
+        blowup_factor,
+        grinding_factor,
+        field_extension,
+        fri_folding_factor,
+        fri_remainder_max_degree,
+    ))
+}
+
+/// The same proof options over another field extension
+///
+/// Winterfell draws the composition and FRI challenges from the extension,
+/// and instantiates its prover and verifier over the matching extension
+/// field of `BaseElement`.
+pub fn with_field_extension(options: &ProofOptions, field_extension: FieldExtension) -> ProofOptions {
+    let fri = options.to_fri_options();
+    ProofOptions::new(
+        options.num_queries(),
+        options.blowup_factor(),
+        options.grinding_factor(),
+        field_extension,
+        fri.folding_factor(),
+        fri.remainder_max_degree(),
+    )
+}
+
+/// Check that Winterfell can prove or verify a statement with the given options
+///
+/// The trace must be a power of two of at least eight rows, the statement
+/// must assert a value, and the blowup factor must cover the degrees of the
+/// transition constraints.
+pub fn check_proof_options(
+    options: &ProofOptions,
+    inputs: &WinterfellAirInputs<BaseElement>,
+    trace_length: usize,
+) -> Result<(), ProofOptionsError> {
+    if trace_length < MIN_TRACE_LENGTH || !trace_length.is_power_of_two() {
+        return Err(ProofOptionsError::TraceLength(trace_length));
+    }
+    if inputs.assertions(trace_length).is_empty() {
+        return Err(ProofOptionsError::NoAssertions);
+    }
+    let required = inputs.degrees().iter().map(|degree| degree.min_blowup_factor()).max().unwrap_or(1);
+    if options.blowup_factor() < required {
+        return Err(ProofOptionsError::BlowupTooSmall {
+            required,
+            actual: options.blowup_factor(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::proof::public_inputs::PublicInputs;
+    use crate::types::field::PrimeField64;
+    use crate::types::FieldElement;
+
+    #[test]
+    fn test_proof_options_are_checked() {
+        for preset in [ProofOptionsPreset::Fast, ProofOptionsPreset::Balanced, ProofOptionsPreset::HighSecurity] {
+            let options = preset.proof_options();
+            let checked = checked_proof_options(
+                options.num_queries(),
+                options.blowup_factor(),
+                options.grinding_factor(),
+                options.field_extension(),
+                options.to_fri_options().folding_factor(),
+                options.to_fri_options().remainder_max_degree(),
+            );
+            assert_eq!(checked, Ok(options));
+        }
+
+        // The former defaults, which passed no queries or a security level as the folding factor
+        assert_eq!(
+            checked_proof_options(0, 16, 8, FieldExtension::None, 128, 0),
+            Err(ProofOptionsError::NumQueries(0))
+        );
+        assert_eq!(
+            checked_proof_options(16, 8, 4, FieldExtension::None, 128, 0),
+            Err(ProofOptionsError::FoldingFactor(128))
+        );
+        assert!(checked_proof_options(16, 6, 4, FieldExtension::None, 4, 31).is_err());
+        assert!(checked_proof_options(16, 8, 4, FieldExtension::None, 4, 30).is_err());
+
+        // A degree-4 constraint needs a blowup factor of four, and the
+        // statement needs an assertion
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_transition(col("x").next() - col("x") * col("x") * col("x") * col("x"))
+            .with_initial("x", PrimeField64::one())
+            .build()
+            .unwrap();
+        let inputs = WinterfellAirInputs::new(&air, &PublicInputs::new()).unwrap();
+        let narrow = checked_proof_options(28, 2, 0, FieldExtension::None, 4, 31).unwrap();
+        assert_eq!(
+            check_proof_options(&narrow, &inputs, 16),
+            Err(ProofOptionsError::BlowupTooSmall { required: 4, actual: 2 })
+        );
+        let options = ProofOptionsPreset::Fast.proof_options();
+        assert_eq!(check_proof_options(&options, &inputs, 16), Ok(()));
+        assert_eq!(check_proof_options(&options, &inputs, 12), Err(ProofOptionsError::TraceLength(12)));
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_column("x")
+            .with_transition(col("x").next() - col("x"))
+            .build()
+            .unwrap();
+        let inputs = WinterfellAirInputs::new(&air, &PublicInputs::new()).unwrap();
+        assert_eq!(check_proof_options(&options, &inputs, 16), Err(ProofOptionsError::NoAssertions));
+    }
+}
@@ -0,0 +1,520 @@
+//! Winterfell Prover Backend
+//!
+//! Proves an XFG statement with Winterfell's prover: the AIR is compiled by
+//! the [`adapter`](super::adapter), the trace is converted value by value
+//! into a Winterfell `TraceTable` over the 64-bit field
+//! `2^64 - 2^32 + 1`, and the proof is returned as a [`WinterfellProof`]
+//! together with the public inputs its verification needs. A proof can be
+//! wrapped in an XFG [`StarkProof`](crate::types::stark::StarkProof), which
+//! keeps Winterfell's bytes exactly (see [`ProofBody`]).
+//!
+//! Values keep their canonical integers, so the trace satisfies the
+//! compiled AIR only if the computation never wraps around either modulus.
+//! The prover checks this before proving rather than letting Winterfell
+//! produce a proof that does not verify.
+
+use super::adapter::{WinterfellAir, WinterfellAirInputs};
+use super::options::check_proof_options;
+use super::WinterfellTraceTable;
+use crate::air::Air;
+use crate::proof::composition::CompositionConstraints;
+use crate::proof::public_inputs::PublicInputs;
+use crate::proof::StarkProver;
+use crate::types::stark::{
+    ExecutionTrace, FieldId, FriProof, HashId, OodFrame, ProofBody, ProofMetadata, ProofParameters, StarkError,
+    StarkProof as XfgStarkProof,
+};
+use crate::types::{FieldElement, TraceStorage};
+use crate::Result;
+use std::marker::PhantomData;
+use winterfell::crypto::hashers::{Blake3_256, Rp64_256, Sha3_256};
+use winterfell::crypto::{DefaultRandomCoin, ElementHasher};
+use winterfell::math::fields::f64::BaseElement;
+use winterfell::math::{FieldElement as WinterfellElement, StarkField, ToElements};
+use winterfell::matrix::ColMatrix;
+use winterfell::{
+    Air as _, AuxTraceRandElements, ConstraintCompositionCoefficients, DefaultConstraintEvaluator, DefaultTraceLde,
+    AcceptableOptions, EvaluationFrame, ProofOptions, Prover, StarkDomain, StarkProof, Trace, TraceInfo,
+    TracePolyTable, TraceTable, VerifierError,
+};
+
+/// Default hash function of the Winterfell backend
+pub type WinterfellHash = Blake3_256<BaseElement>;
+
+/// Public coin of the Winterfell backend with its default hash function
+pub type WinterfellRandomCoin = DefaultRandomCoin<WinterfellHash>;
+
+/// Hash function of a Winterfell proof
+///
+/// Winterfell fixes the hash function in the types of its prover and
+/// verifier. The backend instantiates them for the hash function chosen at
+/// run time, and records it in the metadata of wrapped proofs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WinterfellHasher {
+    /// BLAKE3 with 256-bit digests, Winterfell's `Blake3_256`
+    #[default]
+    Blake3,
+    /// SHA3 with 256-bit digests, Winterfell's `Sha3_256`
+    Sha3,
+    /// Rescue Prime Optimized over the 64-bit field, Winterfell's `Rp64_256`,
+    /// which is cheap to verify inside another STARK
+    Rpo,
+}
+
+impl WinterfellHasher {
+    /// Identifier recorded in proof metadata
+    pub fn hash_id(self) -> HashId {
+        match self {
+            Self::Blake3 => HashId::Blake3,
+            Self::Sha3 => HashId::Sha3,
+            Self::Rpo => HashId::Rpo,
+        }
+    }
+
+    /// Hash function recorded as `hash_id`, unless Winterfell has none such
+    pub fn from_hash_id(hash_id: HashId) -> Option<Self> {
+        [Self::Blake3, Self::Sha3, Self::Rpo].into_iter().find(|hasher| hasher.hash_id() == hash_id)
+    }
+
+    /// Name of the hash function
+    pub fn name(self) -> &'static str {
+        self.hash_id().name()
+    }
+
+    /// Conjectured security of a proof generated with this hash function
+    fn security_level(self, proof: &StarkProof) -> u32 {
+        match self {
+            Self::Blake3 => proof.security_level::<Blake3_256<BaseElement>>(true),
+            Self::Sha3 => proof.security_level::<Sha3_256<BaseElement>>(true),
+            Self::Rpo => proof.security_level::<Rp64_256>(true),
+        }
+    }
+
+    /// Verify a proof with `winterfell::verify` instantiated with this hash function
+    pub(super) fn verify(
+        self,
+        proof: StarkProof,
+        inputs: WinterfellAirInputs<BaseElement>,
+        acceptable: &AcceptableOptions,
+    ) -> std::result::Result<(), VerifierError> {
+        fn verify<H: ElementHasher<BaseField = BaseElement>>(
+            proof: StarkProof,
+            inputs: WinterfellAirInputs<BaseElement>,
+            acceptable: &AcceptableOptions,
+        ) -> std::result::Result<(), VerifierError> {
+            winterfell::verify::<WinterfellAir<BaseElement>, H, DefaultRandomCoin<H>>(proof, inputs, acceptable)
+        }
+        match self {
+            Self::Blake3 => verify::<Blake3_256<BaseElement>>(proof, inputs, acceptable),
+            Self::Sha3 => verify::<Sha3_256<BaseElement>>(proof, inputs, acceptable),
+            Self::Rpo => verify::<Rp64_256>(proof, inputs, acceptable),
+        }
+    }
+}
+
+/// Proof generated by the Winterfell backend, with the public inputs it was generated for
+#[derive(Debug, Clone)]
+pub struct WinterfellProof {
+    /// Winterfell proof
+    proof: StarkProof,
+    /// Compiled AIR and asserted values, which the verifier needs
+    inputs: WinterfellAirInputs<BaseElement>,
+    /// Hash function the proof was generated with
+    hasher: WinterfellHasher,
+}
+
+impl WinterfellProof {
+    /// Winterfell proof
+    pub fn proof(&self) -> &StarkProof {
+        &self.proof
+    }
+
+    /// Compiled AIR and asserted values
+    pub fn inputs(&self) -> &WinterfellAirInputs<BaseElement> {
+        &self.inputs
+    }
+
+    /// Hash function the proof was generated with
+    pub fn hasher(&self) -> WinterfellHasher {
+        self.hasher
+    }
+
+    /// Serialized Winterfell proof
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.proof.to_bytes()
+    }
+
+    /// Split into the Winterfell proof and the public inputs
+    pub fn into_parts(self) -> (StarkProof, WinterfellAirInputs<BaseElement>) {
+        (self.proof, self.inputs)
+    }
+
+    /// Public input elements, as canonical integers
+    fn input_elements(inputs: &WinterfellAirInputs<BaseElement>) -> Vec<u64> {
+        inputs.to_elements().iter().map(StarkField::as_int).collect()
+    }
+
+    /// Wrap the proof in an XFG proof of `air`, which it was generated for
+    ///
+    /// The body holds the serialized Winterfell proof and the public input
+    /// elements; the native components are empty. The metadata records the
+    /// parameters of the Winterfell proof and the digest of `air`.
+    pub fn to_stark_proof<F: FieldElement>(&self, air: &Air<F>) -> Result<XfgStarkProof<F>> {
+        let length = self.proof.context.trace_info().length();
+        let constraints = CompositionConstraints::from_air(air, length)
+            .map_err(|error| StarkError::InvalidConstraints(error.to_string()))?;
+        let bytes = self.to_bytes();
+        let options = self.proof.options();
+        let metadata = ProofMetadata {
+            version: 1,
+            prover_version: crate::VERSION.to_string(),
+            field: FieldId::of::<F>(),
+            hash_id: self.hasher.hash_id(),
+            air_digest: air.digest(),
+            parameters: ProofParameters {
+                security_parameter: self.hasher.security_level(&self.proof),
+                zero_knowledge: false,
+                blowup_factor: options.blowup_factor(),
+                num_queries: options.num_queries(),
+                field_extension_degree: options.field_extension().degree(),
+                grinding_bits: options.grinding_factor(),
+            },
+            grinding_nonce: self.proof.pow_nonce,
+            // Set once the proof is wrapped
+            proof_size: 0,
+            // Winterfell proofs are deterministic, and so is their wrapping
+            timestamp: 0,
+        };
+        Ok(XfgStarkProof {
+            trace: ExecutionTrace {
+                columns: TraceStorage::new(),
+                length,
+                num_registers: air.num_registers(),
+                aux_segments: Vec::new(),
+            },
+            air: StarkProver::to_stark_air(air, &constraints),
+            commitments: Vec::new(),
+            ood_frame: OodFrame::default(),
+            fri_proof: FriProof {
+                extension_degree: options.field_extension().degree() as usize,
+                layers: Vec::new(),
+                final_polynomial: Vec::new(),
+                queries: Vec::new(),
+            },
+            openings: Vec::new(),
+            metadata,
+            body: ProofBody::Winterfell {
+                proof: bytes,
+                inputs: Self::input_elements(&self.inputs),
+            },
+        }
+        .with_encoded_size())
+    }
+
+    /// Unwrap a proof wrapped by [`to_stark_proof`](Self::to_stark_proof)
+    ///
+    /// The public inputs are compiled again from `air` and `public_inputs`,
+    /// and must be the ones recorded in the proof, as must the AIR digest.
+    pub fn from_stark_proof<F: FieldElement>(
+        proof: &XfgStarkProof<F>,
+        air: &Air<F>,
+        public_inputs: &PublicInputs<F>,
+    ) -> Result<Self> {
+        let (bytes, recorded) = match &proof.body {
+            ProofBody::Winterfell { proof, inputs } => (proof, inputs),
+            ProofBody::Native => return Err(StarkError::InvalidProof("not a Winterfell proof".to_string()).into()),
+        };
+        if proof.metadata.air_digest != air.digest() {
+            return Err(StarkError::VerificationFailed("proof AIR digest does not match the AIR".to_string()).into());
+        }
+        let recorded_degree = proof.metadata.parameters.field_extension_degree;
+        let hasher = WinterfellHasher::from_hash_id(proof.metadata.hash_id).ok_or_else(|| {
+            StarkError::InvalidProof(format!("no Winterfell hash function {}", proof.metadata.hash_id))
+        })?;
+        let inputs = WinterfellAirInputs::new(air, public_inputs).map_err(StarkError::from)?;
+        if Self::input_elements(&inputs) != *recorded {
+            let reason = "proof was generated for other public inputs".to_string();
+            return Err(StarkError::VerificationFailed(reason).into());
+        }
+        let proof = StarkProof::from_bytes(bytes)
+            .map_err(|error| StarkError::InvalidProof(format!("Winterfell proof: {}", error)))?;
+        if proof.options().field_extension().degree() != recorded_degree {
+            let reason = "proof metadata records another field extension than the proof".to_string();
+            return Err(StarkError::InvalidProof(reason).into());
+        }
+        Ok(Self { proof, inputs, hasher })
+    }
+}
+
+impl WinterfellTraceTable {
+    /// Winterfell trace table over its 64-bit field, with the same canonical integers
+    pub fn to_trace_table(&self) -> TraceTable<BaseElement> {
+        let columns = (0..self.num_cols)
+            .map(|column| {
+                self.data
+                    .iter()
+                    .map(|row| BaseElement::new(row[column].value().as_canonical_u64()))
+                    .collect()
+            })
+            .collect();
+        TraceTable::init(columns)
+    }
+}
+
+/// Winterfell prover for a compiled XFG AIR
+pub(super) struct AdaptedProver {
+    /// Proof options
+    options: ProofOptions,
+    /// Public inputs of the statement being proven
+    inputs: WinterfellAirInputs<BaseElement>,
+    /// Hash function to prove with
+    hasher: WinterfellHasher,
+}
+
+impl AdaptedProver {
+    /// Prover of the statement described by `inputs`
+    pub(super) fn new(
+        options: ProofOptions,
+        inputs: WinterfellAirInputs<BaseElement>,
+        hasher: WinterfellHasher,
+    ) -> Self {
+        Self { options, inputs, hasher }
+    }
+
+    /// Check the trace against the compiled AIR, then prove it
+    pub(super) fn prove_checked(&self, trace: TraceTable<BaseElement>) -> Result<WinterfellProof> {
+        self.inputs.check_assertions(trace.length()).map_err(StarkError::from)?;
+        check_proof_options(&self.options, &self.inputs, trace.length()).map_err(StarkError::from)?;
+        let air = WinterfellAir::new(trace.info().clone(), self.inputs.clone(), self.options.clone());
+        check_trace(&air, &trace)?;
+        let proof = match self.hasher {
+            WinterfellHasher::Blake3 => HashedProver::<Blake3_256<BaseElement>>::new(self).prove(trace),
+            WinterfellHasher::Sha3 => HashedProver::<Sha3_256<BaseElement>>::new(self).prove(trace),
+            WinterfellHasher::Rpo => HashedProver::<Rp64_256>::new(self).prove(trace),
+        }
+        .map_err(|error| StarkError::InvalidProof(format!("Winterfell prover failed: {}", error)))?;
+        Ok(WinterfellProof {
+            proof,
+            inputs: self.inputs.clone(),
+            hasher: self.hasher,
+        })
+    }
+}
+
+/// [`AdaptedProver`] instantiated with the hash function `H`
+struct HashedProver<'p, H> {
+    /// Statement and options
+    prover: &'p AdaptedProver,
+    /// Hash function
+    hasher: PhantomData<H>,
+}
+
+impl<'p, H> HashedProver<'p, H> {
+    /// Prove with `H`
+    fn new(prover: &'p AdaptedProver) -> Self {
+        Self {
+            prover,
+            hasher: PhantomData,
+        }
+    }
+}
+
+impl<H: ElementHasher<BaseField = BaseElement>> Prover for HashedProver<'_, H> {
+    type BaseField = BaseElement;
+    type Air = WinterfellAir<BaseElement>;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = H;
+    type RandomCoin = DefaultRandomCoin<H>;
+    type TraceLde<E: WinterfellElement<BaseField = BaseElement>> = DefaultTraceLde<E, H>;
+    type ConstraintEvaluator<'a, E: WinterfellElement<BaseField = BaseElement>> =
+        DefaultConstraintEvaluator<'a, WinterfellAir<BaseElement>, E>;
+
+    fn get_pub_inputs(&self, _trace: &Self::Trace) -> WinterfellAirInputs<BaseElement> {
+        self.prover.inputs.clone()
+    }
+
+    fn options(&self) -> &ProofOptions {
+        &self.prover.options
+    }
+
+    fn new_trace_lde<E: WinterfellElement<BaseField = BaseElement>>(
+        &self,
+        trace_info: &TraceInfo,
+        main_trace: &ColMatrix<BaseElement>,
+        domain: &StarkDomain<BaseElement>,
+    ) -> (Self::TraceLde<E>, TracePolyTable<E>) {
+        DefaultTraceLde::new(trace_info, main_trace, domain)
+    }
+
+    fn new_evaluator<'a, E: WinterfellElement<BaseField = BaseElement>>(
+        &self,
+        air: &'a WinterfellAir<BaseElement>,
+        aux_rand_elements: AuxTraceRandElements<E>,
+        composition_coefficients: ConstraintCompositionCoefficients<E>,
+    ) -> Self::ConstraintEvaluator<'a, E> {
+        DefaultConstraintEvaluator::new(air, aux_rand_elements, composition_coefficients)
+    }
+}
+
+/// Check a trace against a compiled AIR over Winterfell's field
+///
+/// Winterfell only checks the trace in debug builds, and panics there.
+fn check_trace(air: &WinterfellAir<BaseElement>, trace: &TraceTable<BaseElement>) -> Result<()> {
+    let invalid = |reason: String| StarkError::InvalidTrace(format!("{} over Winterfell's field", reason));
+    let periodic = air.get_periodic_column_values();
+    let num_constraints = air.context().num_main_transition_constraints();
+    let row_values = |row: usize| (0..trace.main_trace_width()).map(|column| trace.get(column, row)).collect();
+    let mut result = vec![BaseElement::ZERO; num_constraints];
+    for row in 0..trace.length().saturating_sub(1) {
+        let frame = EvaluationFrame::from_rows(row_values(row), row_values(row + 1));
+        let periodic_values: Vec<BaseElement> = periodic.iter().map(|values| values[row % values.len()]).collect();
+        air.evaluate_transition(&frame, &periodic_values, &mut result);
+        if let Some(index) = result.iter().position(|value| *value != BaseElement::ZERO) {
+            return Err(invalid(format!("transition constraint {} fails at row {}", index, row)).into());
+        }
+    }
+    let mut violation = None;
+    for assertion in air.get_assertions() {
+        let register = assertion.column();
+        assertion.apply(trace.length(), |row, value| {
+            if violation.is_none() && trace.get(register, row) != value {
+                violation = Some((register, row));
+            }
+        });
+    }
+    match violation {
+        Some((register, row)) => {
+            let reason = format!("register {} does not hold its asserted value at row {}", register, row);
+            Err(invalid(reason).into())
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::air::builder::{col, AirBuilder};
+    use crate::air::fixtures::fibonacci;
+    use crate::proof::StarkVerifier;
+    use crate::types::field::PrimeField64;
+    use crate::types::StarkComponent;
+    use crate::winterfell_integration::{XfgWinterfellProver, XfgWinterfellVerifier};
+    use winterfell::FieldExtension;
+
+    #[test]
+    fn test_winterfell_backend_round_trip() {
+        let (air, trace) = fibonacci::<PrimeField64>(16);
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let prover = XfgWinterfellProver::with_options(options.clone());
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 15));
+        let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+        assert!(!proof.to_bytes().is_empty());
+        assert_eq!(proof.inputs(), &WinterfellAirInputs::new(&air, &public_inputs).unwrap());
+
+        let verifier = XfgWinterfellVerifier::with_options(options);
+        verifier.verify(&air, &public_inputs, &proof).unwrap();
+
+        // The proof does not verify for another claim, and a wrong claim is
+        // caught before Winterfell runs
+        let wrong = PublicInputs::new().assert_final(1, PrimeField64::new(7));
+        assert!(verifier.verify(&air, &wrong, &proof).is_err());
+        assert!(prover.prove(&air, &wrong, &trace).is_err());
+        let other = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+        assert!(XfgWinterfellVerifier::with_options(other).verify(&air, &public_inputs, &proof).is_err());
+
+        // Wrapped in an XFG proof, the Winterfell bytes survive serialization exactly
+        let wrapped = proof.to_stark_proof(&air).unwrap();
+        let decoded = XfgStarkProof::<PrimeField64>::from_bytes(&wrapped.to_bytes()).unwrap();
+        assert!(matches!(&decoded.body, ProofBody::Winterfell { proof: bytes, .. } if *bytes == proof.to_bytes()));
+        let unwrapped = WinterfellProof::from_stark_proof(&decoded, &air, &public_inputs).unwrap();
+        assert_eq!(unwrapped.to_bytes(), proof.to_bytes());
+        verifier.verify(&air, &public_inputs, &unwrapped).unwrap();
+        assert!(WinterfellProof::from_stark_proof(&decoded, &air, &wrong).is_err());
+        let native = StarkVerifier::<PrimeField64>::new(48).verify_with_air(&air, &decoded, &public_inputs);
+        assert!(native.is_err());
+    }
+
+    #[test]
+    fn test_winterfell_backend_fixed_columns() {
+        let air = AirBuilder::<PrimeField64>::new(128)
+            .with_fixed_column("rom", &[PrimeField64::new(3), PrimeField64::new(1)])
+            .with_column("acc")
+            .with_transition(col("acc").next() - col("acc") - col("rom"))
+            .with_initial("acc", PrimeField64::zero())
+            .build()
+            .unwrap();
+        let trace = StarkProver::<PrimeField64>::with_params(128, 8, 16, 1)
+            .generate_trace(&air, &[PrimeField64::zero(); 2], 16)
+            .unwrap();
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 15));
+
+        // `rom - periodic` is declared of degree one; Winterfell checks the
+        // declared degrees against the evaluations when proving in debug builds
+        let inputs = WinterfellAirInputs::<BaseElement>::new(&air, &public_inputs).unwrap();
+        let degrees: Vec<usize> = inputs.degrees().iter().map(|degree| degree.get_evaluation_degree(16)).collect();
+        assert_eq!(degrees, vec![15, 15]);
+
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let proof = XfgWinterfellProver::with_options(options.clone()).prove(&air, &public_inputs, &trace).unwrap();
+        let verifier = XfgWinterfellVerifier::with_options(options);
+        verifier.verify(&air, &public_inputs, &proof).unwrap();
+        let wrong = PublicInputs::new().assert_final(1, trace.columns.value(1, 15) + PrimeField64::one());
+        assert!(verifier.verify(&air, &wrong, &proof).is_err());
+    }
+
+    #[test]
+    fn test_winterfell_backend_field_extensions() {
+        let (air, trace) = fibonacci::<PrimeField64>(16);
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 15));
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        for (extension, degree) in [(FieldExtension::Quadratic, 2), (FieldExtension::Cubic, 3)] {
+            let prover = XfgWinterfellProver::with_options(options.clone()).with_field_extension(extension);
+            assert_eq!(prover.proof_options().num_queries(), 28);
+            let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+            assert_eq!(proof.proof().options().field_extension(), extension);
+            let verifier = XfgWinterfellVerifier::with_options(options.clone()).with_field_extension(extension);
+            verifier.verify(&air, &public_inputs, &proof).unwrap();
+            assert!(XfgWinterfellVerifier::with_options(options.clone()).verify(&air, &public_inputs, &proof).is_err());
+
+            // The wrapped proof records the extension, and must agree with it
+            let mut wrapped = proof.to_stark_proof(&air).unwrap();
+            assert_eq!(wrapped.metadata.parameters.field_extension_degree, degree);
+            assert_eq!(wrapped.fri_proof.extension_degree, degree as usize);
+            let unwrapped = WinterfellProof::from_stark_proof(&wrapped, &air, &public_inputs).unwrap();
+            verifier.verify(&air, &public_inputs, &unwrapped).unwrap();
+            wrapped.metadata.parameters.field_extension_degree = 1;
+            assert!(WinterfellProof::from_stark_proof(&wrapped, &air, &public_inputs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_winterfell_backend_hashers() {
+        let (air, trace) = fibonacci::<PrimeField64>(16);
+        let public_inputs = PublicInputs::new().assert_final(1, trace.columns.value(1, 15));
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let verifier = XfgWinterfellVerifier::with_options(options.clone());
+        for hasher in [WinterfellHasher::Blake3, WinterfellHasher::Sha3, WinterfellHasher::Rpo] {
+            let prover = XfgWinterfellProver::with_options(options.clone()).with_hasher(hasher);
+            let proof = prover.prove(&air, &public_inputs, &trace).unwrap();
+            assert_eq!(proof.hasher(), hasher);
+            verifier.verify(&air, &public_inputs, &proof).unwrap();
+
+            // The verifier picks the hash function up from the wrapped proof's metadata
+            let wrapped = XfgStarkProof::<PrimeField64>::from_bytes(&proof.to_stark_proof(&air).unwrap().to_bytes())
+                .unwrap();
+            assert_eq!(WinterfellHasher::from_hash_id(wrapped.metadata.hash_id), Some(hasher));
+            let unwrapped = WinterfellProof::from_stark_proof(&wrapped, &air, &public_inputs).unwrap();
+            verifier.verify(&air, &public_inputs, &unwrapped).unwrap();
+        }
+
+        // A verifier pinned to one hash function rejects the others
+        let proof = XfgWinterfellProver::with_options(options.clone())
+            .with_hasher(WinterfellHasher::Sha3)
+            .prove(&air, &public_inputs, &trace)
+            .unwrap();
+        let pinned = XfgWinterfellVerifier::with_options(options).with_hasher(WinterfellHasher::Blake3);
+        assert!(pinned.verify(&air, &public_inputs, &proof).is_err());
+        assert_eq!(WinterfellHasher::from_hash_id(HashId::Sha256), None);
+    }
+}
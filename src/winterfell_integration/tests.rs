@@ -5,7 +5,10 @@
 
 use super::*;
 use crate::types::field::PrimeField64;
-use crate::types::stark::{ExecutionTrace, Air, TransitionFunction, BoundaryConditions};
+use crate::types::stark::{
+    Air, BoundaryConditions, ExecutionTrace, FieldId, HashId, ProofParameters, TransitionFunction,
+};
+use crate::types::Digest;
 
 #[test]
 fn test_winterfell_field_element_creation() {
@@ -124,9 +127,10 @@ fn test_winterfell_trace_table_creation() {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(2)],
             vec![PrimeField64::new(3), PrimeField64::new(4)],
-        ],
+        ].into(),
         length: 2,
         num_registers: 2,
+        aux_segments: Vec::new(),
     };
     
     let winterfell_trace = WinterfellTraceTable::from_xfg_trace(&trace).unwrap();
@@ -140,9 +144,10 @@ fn test_winterfell_trace_table_creation() {
 fn test_winterfell_trace_table_conversion_errors() {
     // Test with empty columns
     let empty_trace = ExecutionTrace {
-        columns: vec![],
+        columns: vec![].into(),
         length: 0,
         num_registers: 0,
+        aux_segments: Vec::new(),
     };
     
     let result = WinterfellTraceTable::from_xfg_trace(&empty_trace);
@@ -153,9 +158,10 @@ fn test_winterfell_trace_table_conversion_errors() {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(2)],
             vec![PrimeField64::new(3)], // Different length
-        ],
+        ].into(),
         length: 2,
         num_registers: 2,
+        aux_segments: Vec::new(),
     };
     
     let result = WinterfellTraceTable::from_xfg_trace(&mismatched_trace);
@@ -189,9 +195,10 @@ fn test_xfg_winterfell_prover_proof_generation() {
     let trace = ExecutionTrace {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(2)],
-        ],
+        ].into(),
         length: 2,
         num_registers: 1,
+        aux_segments: Vec::new(),
     };
     
     let air = Air {
@@ -223,9 +230,10 @@ fn test_xfg_winterfell_verifier_verification() {
     
     let proof = StarkProof {
         trace: ExecutionTrace {
-            columns: vec![vec![PrimeField64::new(1)]],
+            columns: vec![vec![PrimeField64::new(1)]].into(),
             length: 1,
             num_registers: 1,
+            aux_segments: Vec::new(),
         },
         air: Air {
             constraints: vec![],
@@ -238,14 +246,26 @@ fn test_xfg_winterfell_verifier_verification() {
         },
         commitments: vec![],
         fri_proof: FriProof {
+            extension_degree: 1,
             layers: vec![],
             final_polynomial: vec![PrimeField64::new(1)],
             queries: vec![],
         },
         metadata: ProofMetadata {
             version: 1,
-            security_parameter: 128,
-            field_modulus: "0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47".to_string(),
+            prover_version: crate::VERSION.to_string(),
+            field: FieldId::of::<PrimeField64>(),
+            hash_id: HashId::Blake3,
+            air_digest: Digest::default(),
+            parameters: ProofParameters {
+                security_parameter: 128,
+                zero_knowledge: false,
+                blowup_factor: 8,
+                num_queries: 16,
+                field_extension_degree: 1,
+                grinding_bits: 0,
+            },
+            grinding_nonce: 0,
             proof_size: 1024,
             timestamp: 1234567890,
         },
@@ -373,9 +393,10 @@ fn test_error_handling() {
     let trace = ExecutionTrace {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(2)],
-        ],
+        ].into(),
         length: 2,
         num_registers: 1,
+        aux_segments: Vec::new(),
     };
     
     // Test with invalid field element (should be handled gracefully)
@@ -12,8 +12,9 @@
 //! - Real-world use case scenarios
 
 use xfg_stark::{
-    types::{FieldElement, PrimeField64, StarkComponent},
+    types::{ColIdx, FieldElement, PrimeField64, StarkComponent, StepIdx},
     proof::{StarkProver, StarkVerifier},
+    proof::public_inputs::PublicInputs,
     proof::fri::{FriProver, FriVerifier},
     proof::merkle::{MerkleTree, generate_commitment},
     air::{Air, Constraint, TransitionFunction, BoundaryConditions},
@@ -44,10 +45,10 @@ mod test_utils {
         
         // Add initial conditions: F(0) = 0, F(1) = 1
         boundary.add_constraint(BoundaryConstraint::new(
-            0, 0, PrimeField64::zero(), BoundaryType::Initial
+            ColIdx(0), StepIdx(0), PrimeField64::zero(), BoundaryType::Initial
         ));
         boundary.add_constraint(BoundaryConstraint::new(
-            0, 1, PrimeField64::one(), BoundaryType::Initial
+            ColIdx(0), StepIdx(1), PrimeField64::one(), BoundaryType::Initial
         ));
 
         Air::new(constraints, transition, boundary, 128)
@@ -68,7 +69,7 @@ mod test_utils {
         
         // Start at 0
         boundary.add_constraint(BoundaryConstraint::new(
-            0, 0, PrimeField64::zero(), BoundaryType::Initial
+            ColIdx(0), StepIdx(0), PrimeField64::zero(), BoundaryType::Initial
         ));
 
         Air::new(constraints, transition, boundary, 128)
@@ -103,13 +104,14 @@ fn test_complete_stark_proof_workflow() {
     // Step 1: Generate proof
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero(), PrimeField64::one()];
-    let proof = prover.prove(&air, &initial_state, 100).expect("Proof generation should succeed");
+    let trace = prover.generate_trace(&air, &initial_state, 100).expect("Trace generation should succeed");
+    let proof = prover.prove(&air, &PublicInputs::new(), &trace).expect("Proof generation should succeed");
     
     // Step 2: Verify proof
     let verifier = StarkVerifier::new(128);
-    let is_valid = verifier.verify(&proof).expect("Proof verification should succeed");
-    
-    assert!(is_valid, "Generated proof should be valid");
+    verifier
+        .verify_with_air(&air, &proof, &PublicInputs::new())
+        .expect("Proof verification should succeed");
     
     // Step 3: Validate proof components
     let validation_result = proof.validate();
@@ -181,13 +183,14 @@ fn test_winterfell_integration() {
         columns: vec![
             vec![PrimeField64::new(1), PrimeField64::new(3), PrimeField64::new(5)],
             vec![PrimeField64::new(2), PrimeField64::new(4), PrimeField64::new(6)],
-        ],
+        ].into(),
         length: 3,
         num_registers: 2,
+        aux_segments: Vec::new(),
     };
     
     // Create trace table
-    let trace_table = WinterfellTraceTable::from_xfg_trace(&trace);
+    let trace_table = WinterfellTraceTable::from_xfg_trace(&trace).unwrap();
     
     // Test prover
     let prover = XfgWinterfellProver::new();
@@ -274,7 +277,9 @@ fn test_error_handling() {
     
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero()];
-    let result = prover.prove(&empty_air, &initial_state, 10);
+    let result = prover
+        .generate_trace(&empty_air, &initial_state, 10)
+        .and_then(|trace| prover.prove(&empty_air, &PublicInputs::new(), &trace));
     // Should handle empty constraints gracefully
     
     // Test with invalid polynomial for FRI
@@ -297,7 +302,8 @@ fn test_cross_component_integration() {
     let air = test_utils::create_counter_air();
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero()];
-    let proof = prover.prove(&air, &initial_state, 100).expect("STARK proof should succeed");
+    let trace = prover.generate_trace(&air, &initial_state, 100).expect("Trace generation should succeed");
+    let proof = prover.prove(&air, &PublicInputs::new(), &trace).expect("STARK proof should succeed");
     
     // 2. Extract polynomial from proof and create FRI proof
     let polynomial = test_utils::create_test_polynomial();
@@ -312,7 +318,9 @@ fn test_cross_component_integration() {
     
     // 4. Verify all components work together
     let verifier = StarkVerifier::new(128);
-    let stark_valid = verifier.verify(&proof).expect("STARK verification should succeed");
+    verifier
+        .verify_with_air(&air, &proof, &PublicInputs::new())
+        .expect("STARK verification should succeed");
     
     let fri_verifier = FriVerifier::new(128);
     let fri_valid = fri_verifier.verify(&fri_proof, &polynomial).expect("FRI verification should succeed");
@@ -320,7 +328,6 @@ fn test_cross_component_integration() {
     let merkle_proof = tree.generate_proof(0).expect("Merkle proof should succeed");
     let merkle_valid = tree.verify_proof(&leaves[0], &merkle_proof).expect("Merkle verification should succeed");
     
-    assert!(stark_valid, "STARK proof should be valid");
     assert!(fri_valid, "FRI proof should be valid");
     assert!(merkle_valid, "Merkle proof should be valid");
 }
@@ -355,23 +362,28 @@ fn test_real_world_scenario() {
     let mut boundary = BoundaryConditions::new(vec![]);
     
     // Initial conditions: sum=0, counter=1
-    boundary.add_constraint(BoundaryConstraint::new(0, 0, PrimeField64::zero(), BoundaryType::Initial));
-    boundary.add_constraint(BoundaryConstraint::new(1, 0, PrimeField64::one(), BoundaryType::Initial));
+    boundary.add_constraint(BoundaryConstraint::new(
+        ColIdx(0), StepIdx(0), PrimeField64::zero(), BoundaryType::Initial
+    ));
+    boundary.add_constraint(BoundaryConstraint::new(ColIdx(1), StepIdx(0), PrimeField64::one(), BoundaryType::Initial));
     
     // Final condition: sum should equal expected_sum
-    boundary.add_constraint(BoundaryConstraint::new(0, n-1, PrimeField64::new(expected_sum as u64), BoundaryType::Final));
+    boundary.add_constraint(BoundaryConstraint::new(
+        ColIdx(0), StepIdx(n - 1), PrimeField64::new(expected_sum as u64), BoundaryType::Final
+    ));
     
     let air = Air::new(constraints, transition, boundary, 128);
     
     // Generate and verify proof
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero(), PrimeField64::one()];
-    let proof = prover.prove(&air, &initial_state, n).expect("Real-world proof should succeed");
+    let trace = prover.generate_trace(&air, &initial_state, n).expect("Trace generation should succeed");
+    let proof = prover.prove(&air, &PublicInputs::new(), &trace).expect("Real-world proof should succeed");
     
     let verifier = StarkVerifier::new(128);
-    let is_valid = verifier.verify(&proof).expect("Real-world verification should succeed");
-    
-    assert!(is_valid, "Real-world proof should be valid");
+    verifier
+        .verify_with_air(&air, &proof, &PublicInputs::new())
+        .expect("Real-world verification should succeed");
 }
 
 #[test]
@@ -426,12 +438,15 @@ fn test_security_parameters() {
     for security_level in security_levels {
         let prover = StarkProver::new(security_level);
         let initial_state = vec![PrimeField64::zero(), PrimeField64::one()];
-        let proof = prover.prove(&air, &initial_state, 100).expect(&format!("Proof should succeed with security level {}", security_level));
+        let trace = prover.generate_trace(&air, &initial_state, 100).expect("Trace generation should succeed");
+        let proof = prover
+            .prove(&air, &PublicInputs::new(), &trace)
+            .expect(&format!("Proof should succeed with security level {}", security_level));
         
         let verifier = StarkVerifier::new(security_level);
-        let is_valid = verifier.verify(&proof).expect(&format!("Verification should succeed with security level {}", security_level));
-        
-        assert!(is_valid, "Proof should be valid with security level {}", security_level);
+        verifier
+            .verify_with_air(&air, &proof, &PublicInputs::new())
+            .expect(&format!("Verification should succeed with security level {}", security_level));
     }
 }
 
@@ -486,7 +501,9 @@ fn test_edge_cases() {
     // Zero security parameter (should use default)
     let air = test_utils::create_fibonacci_air();
     let prover = StarkProver::new(0);
-    let proof_result = prover.prove(&air);
+    let proof_result = prover
+        .generate_trace(&air, &[PrimeField64::zero(), PrimeField64::one()], 100)
+        .and_then(|trace| prover.prove(&air, &PublicInputs::new(), &trace));
     // Should handle gracefully or use default security parameter
 }
 
@@ -504,7 +521,9 @@ fn test_concurrent_operations() {
         thread::spawn(move || {
             let prover = StarkProver::new(128);
             let initial_state = vec![PrimeField64::zero(), PrimeField64::one()];
-            prover.prove(&air_clone, &initial_state, 100)
+            prover
+                .generate_trace(&air_clone, &initial_state, 100)
+                .and_then(|trace| prover.prove(&air_clone, &PublicInputs::new(), &trace))
         })
     }).collect();
     
@@ -559,16 +578,27 @@ fn test_error_recovery() {
     
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero()];
-    let result = prover.prove(&invalid_air, &initial_state, 10);
+    let result = prover
+        .generate_trace(&invalid_air, &initial_state, 10)
+        .and_then(|trace| prover.prove(&invalid_air, &PublicInputs::new(), &trace));
     // Should handle invalid AIR gracefully
     
     // Test with corrupted proof (should detect corruption)
     let valid_air = test_utils::create_fibonacci_air();
-    let valid_proof = prover.prove(&valid_air, &vec![PrimeField64::zero(), PrimeField64::one()], 100).expect("Valid proof should succeed");
+    let valid_trace = prover
+        .generate_trace(&valid_air, &[PrimeField64::zero(), PrimeField64::one()], 100)
+        .expect("Valid trace should succeed");
+    let valid_proof = prover.prove(&valid_air, &PublicInputs::new(), &valid_trace).expect("Valid proof should succeed");
     
     let verifier = StarkVerifier::new(128);
-    let is_valid = verifier.verify(&valid_proof).expect("Valid proof should verify");
-    assert!(is_valid, "Valid proof should be valid");
+    verifier
+        .verify_with_air(&valid_air, &valid_proof, &PublicInputs::new())
+        .expect("Valid proof should verify");
+
+    // A corrupted proof is rejected
+    let mut corrupted = valid_proof.clone();
+    corrupted.ood_frame.composition[0] = corrupted.ood_frame.composition[0] + PrimeField64::one();
+    assert!(verifier.verify_with_air(&valid_air, &corrupted, &PublicInputs::new()).is_err());
 }
 
 #[test]
@@ -578,7 +608,8 @@ fn test_comprehensive_validation() {
     let air = test_utils::create_fibonacci_air();
     let prover = StarkProver::new(128);
     let initial_state = vec![PrimeField64::zero(), PrimeField64::one()];
-    let proof = prover.prove(&air, &initial_state, 100).expect("Proof should succeed");
+    let trace = prover.generate_trace(&air, &initial_state, 100).expect("Trace generation should succeed");
+    let proof = prover.prove(&air, &PublicInputs::new(), &trace).expect("Proof should succeed");
     
     // Validate each component
     let trace_validation = proof.trace.validate();
@@ -591,7 +622,7 @@ fn test_comprehensive_validation() {
     assert!(fri_validation.is_ok() || fri_validation.is_err(), "FRI validation should be handled");
     
     // Validate metadata
-    assert_eq!(proof.metadata.version, 1, "Version should be 1");
-    assert!(!proof.metadata.field_modulus.is_empty(), "Field modulus should not be empty");
+    assert_eq!(proof.metadata.version, 2, "Version should be 2");
+    assert_eq!(proof.metadata.field.modulus(), PrimeField64::MODULUS, "Field should be that of the proof");
     assert!(proof.metadata.proof_size > 0, "Proof size should be positive");
 }
\ No newline at end of file